@@ -1,4 +1,4 @@
-//! Build script for rasterix.
+//! Build script for the fixture tests.
 //!
 //! Generates Rust code from XML fixtures at compile time for roundtrip testing.
 //! The generated code is placed in OUT_DIR and included in tests via `include!`.
@@ -15,17 +15,39 @@ fn main() {
     let generated_dir = Path::new(&out_dir).join("generated");
     fs::create_dir_all(&generated_dir).unwrap();
 
-    // List of fixtures to generate code for
+    // List of fixtures to generate code for, and whether repetitive items
+    // are stored inline
     let fixtures = [
-        ("simple_fixed", "simple_fixed.xml"),
-        ("multi_item_record", "multi_item_record.xml"),
-        ("extended_multi_part", "extended_multi_part.xml"),
-        ("enum_basic", "enum_basic.xml"),
-        ("compound_simple", "compound_simple.xml"),
-        ("repetitive_basic", "repetitive_basic.xml"),
-        ("epb_field", "epb_field.xml"),
-        ("explicit_item", "explicit_item.xml"),
-        ("spare_bits", "spare_bits.xml"),
+        ("simple_fixed", "simple_fixed.xml", false),
+        ("multi_item_record", "multi_item_record.xml", false),
+        ("cat048_subset", "cat048_subset.xml", false),
+        ("extended_multi_part", "extended_multi_part.xml", false),
+        ("enum_basic", "enum_basic.xml", false),
+        ("compound_simple", "compound_simple.xml", false),
+        ("repetitive_basic", "repetitive_basic.xml", false),
+        ("epb_field", "epb_field.xml", false),
+        ("explicit_item", "explicit_item.xml", false),
+        ("spare_bits", "spare_bits.xml", false),
+        ("high_item_ids", "high_item_ids.xml", false),
+        ("named_items", "named_items.xml", false),
+        ("field_revisions", "field_revisions.xml", false),
+        ("enum_policies", "enum_policies.xml", false),
+        ("field_descriptions", "field_descriptions.xml", false),
+        ("aligned_fields", "aligned_fields.xml", false),
+        ("derived_fields", "derived_fields.xml", false),
+        ("field_constraints", "field_constraints.xml", false),
+        ("geo_positions", "geo_positions.xml", false),
+        ("altitude_fields", "altitude_fields.xml", false),
+        ("velocity_fields", "velocity_fields.xml", false),
+        ("icao_fields", "icao_fields.xml", false),
+        ("item_examples", "item_examples.xml", false),
+        ("reserved_items", "reserved_items.xml", false),
+        ("little_endian_fields", "little_endian_fields.xml", false),
+        ("float_fields", "float_fields.xml", false),
+        ("byte_fields", "byte_fields.xml", false),
+        ("conformance_rules", "conformance_rules.xml", false),
+        // Repetitive elements stored in a `heapless::Vec`
+        ("repetitive_heapless", "repetitive_basic.xml", true),
     ];
 
     // Generate mod.rs that includes all generated modules
@@ -35,7 +57,7 @@ fn main() {
          // This module contains code generated from XML fixtures for roundtrip testing.\n\n"
     );
 
-    for (module_name, xml_file) in &fixtures {
+    for (module_name, xml_file, heapless) in &fixtures {
         let xml_path = Path::new("../testdata/valid").join(xml_file);
 
         if !xml_path.exists() {
@@ -53,7 +75,7 @@ fn main() {
         };
 
         // Generate Rust code using rasterix-codegen
-        match generate_code(&xml_content) {
+        match generate_code(&xml_content, *heapless) {
             Ok(code) => {
                 let output_path = generated_dir.join(format!("{}.rs", module_name));
 
@@ -86,7 +108,7 @@ fn main() {
 }
 
 /// Generate Rust code from XML content using rasterix-codegen.
-fn generate_code(xml_content: &str) -> Result<String, String> {
+fn generate_code(xml_content: &str, heapless: bool) -> Result<String, String> {
     // We need to use the codegen crate directly
     // Since build.rs runs before the crate is compiled, we use a subprocess approach
     // or inline the generation logic
@@ -95,15 +117,31 @@ fn generate_code(xml_content: &str) -> Result<String, String> {
     // This requires adding rasterix-codegen as a build dependency
 
     use rasterix_codegen::parse::parser::parse_category;
-    use rasterix_codegen::transform::transformer::to_ir;
-    use rasterix_codegen::generate::generate;
+    use rasterix_codegen::transform::{lower, transformer::to_ir, use_heapless};
+    use rasterix_codegen::generate::{
+        arrow_gen::ArrowBackend, backend::run_backend, generate_borrowed, generate_lowered, proto_gen::ProtoBackend,
+    };
 
     let category = parse_category(xml_content)
         .map_err(|e| format!("Parse error: {}", e))?;
 
-    let ir = std::panic::catch_unwind(|| to_ir(category))
-        .map_err(|_| "Transform/validation error".to_string())?;
+    let ir = to_ir(category)
+        .map_err(|e| format!("Transform/validation error: {}", e))?;
 
-    let tokens = generate(&ir);
-    Ok(tokens.to_string())
+    let mut lowered = lower(&ir)?;
+    if heapless {
+        use_heapless(&mut lowered);
+    }
+
+    // The borrowed types, protobuf messages and Arrow conversions go in the
+    // same file as the types they refer to.
+    let proto = run_backend(ProtoBackend::default(), &lowered);
+    let arrow = run_backend(ArrowBackend, &lowered);
+    Ok(format!(
+        "{}\n{}\n{}\n{}",
+        generate_lowered(&lowered),
+        generate_borrowed(&ir)?,
+        proto.conversions,
+        arrow
+    ))
 }
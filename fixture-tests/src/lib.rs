@@ -0,0 +1,5 @@
+//! Tests of the code generated from the XML fixtures in `testdata/valid`.
+//!
+//! `build.rs` generates a module per fixture into `OUT_DIR`, which the
+//! integration tests in `tests/` include.  The crate has no code of its
+//! own.
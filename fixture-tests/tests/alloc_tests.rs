@@ -0,0 +1,126 @@
+//! Heap allocation tests for generated code.
+//!
+//! Fixed and extended items, and repetitive items stored in a
+//! `heapless::Vec`, must decode and encode without allocating, and records
+//! must decode in place without allocating once their context is warm.  A
+//! counting global allocator checks this on the generated fixtures from
+//! build.rs.
+
+include!(concat!(env!("OUT_DIR"), "/generated/mod.rs"));
+
+use rasterix::rcore::{BitReader, BitWriter, Decode, DecodeContext, DecodeInto, Encode};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::io::Cursor;
+
+/// Counts the allocations made by each thread, so tests running in
+/// parallel do not see each other's.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Returns the number of allocations made on this thread by `f`.
+fn allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+/// Decodes `T` from `bytes` and encodes it back into a fixed buffer,
+/// asserting that neither step allocates and that the bytes roundtrip.
+fn assert_roundtrip_without_allocating<T: Decode + Encode, const N: usize>(bytes: [u8; N]) {
+    let mut output = [0u8; N];
+    let count = allocations(|| {
+        let value = T::decode(&mut BitReader::new(Cursor::new(bytes))).unwrap();
+        let mut writer = BitWriter::new(&mut output[..]);
+        value.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    });
+
+    assert_eq!(count, 0);
+    assert_eq!(output, bytes);
+}
+
+#[test]
+fn fixed_item_does_not_allocate() {
+    assert_roundtrip_without_allocating::<simple_fixed::cat001::Item010, 2>([0x12, 0x34]);
+}
+
+#[test]
+fn extended_item_does_not_allocate() {
+    // All three parts present
+    assert_roundtrip_without_allocating::<extended_multi_part::cat048::Item020, 3>([0x2B, 0x29, 0x28]);
+}
+
+#[test]
+fn heapless_repetitive_item_does_not_allocate() {
+    assert_roundtrip_without_allocating::<repetitive_heapless::cat001::Item070, 10>([
+        0x00, 0x64, 0x00, 0xC8, 0x01, 0x2C, 0x01, 0x90, 0x01, 0xF4,
+    ]);
+}
+
+#[test]
+fn heapless_repetitive_item_decodes_into_without_allocating() {
+    use repetitive_heapless::cat001::Item070;
+
+    let bytes = [0x00, 0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0x04, 0x00, 0x05];
+    let mut item = Item070::decode(&mut BitReader::new(Cursor::new(bytes))).unwrap();
+    let mut ctx = DecodeContext::new();
+
+    let updated = [0xFF; 10];
+    let count = allocations(|| {
+        item.decode_into(&mut BitReader::new(Cursor::new(updated)), &mut ctx).unwrap();
+    });
+
+    assert_eq!(count, 0);
+    assert!(item.items.iter().all(|element| element.azimuth == 0xFFFF));
+}
+
+#[test]
+fn vec_repetitive_item_allocates() {
+    let bytes = [0u8; 10];
+    let count = allocations(|| {
+        repetitive_basic::cat001::Item070::decode(&mut BitReader::new(Cursor::new(bytes))).unwrap();
+    });
+
+    assert_eq!(count, 1);
+}
+
+#[test]
+fn record_decodes_into_without_allocating() {
+    use compound_simple::cat001::Record;
+
+    // Record FSPEC, then the compound item's FSPEC and both sub-items
+    let bytes = [0x80, 0xC0, 0x12, 0x34, 0x56];
+    let mut record = Record::decode(&mut BitReader::new(Cursor::new(bytes))).unwrap();
+    let mut ctx = DecodeContext::new();
+    record.decode_into(&mut BitReader::new(Cursor::new(bytes)), &mut ctx).unwrap();
+
+    let count = allocations(|| {
+        record.decode_into(&mut BitReader::new(Cursor::new(bytes)), &mut ctx).unwrap();
+    });
+
+    assert_eq!(count, 0);
+    assert_eq!(record.item100.unwrap().sub1.unwrap().data, 0x3456);
+}
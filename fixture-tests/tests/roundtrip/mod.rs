@@ -0,0 +1,8 @@
+//! Round-trip test infrastructure.
+//!
+//! The helpers live in [`test_utils::roundtrip`], so tests of other crates
+//! can use them too.
+
+#![allow(unused_imports)]
+
+pub use test_utils::roundtrip::*;
@@ -0,0 +1,2588 @@
+//! Roundtrip tests for generated ASTERIX code.
+//!
+//! These tests verify that the code generated by rasterix-codegen correctly
+//! implements encode/decode roundtrips. The code is generated at build time
+//! from XML fixtures using build.rs.
+//!
+//! **IMPORTANT**: These tests use REAL GENERATED CODE, not manual implementations.
+//! If the code generator changes, these tests will reflect those changes.
+
+mod roundtrip;
+
+// Include the generated modules from build.rs
+// This code is generated from testdata/valid/*.xml at compile time
+include!(concat!(env!("OUT_DIR"), "/generated/mod.rs"));
+
+use rasterix::rcore::{
+    BitReader, BitWriter, DataItem, Decode, DecodeContext, DecodeError, DecodeInto, DecodePolicy,
+    Conformance, Encode, FromValue, Fspec, Header, RecordStream, Validate, Value, ValueMap, policy,
+};
+use std::io::Cursor;
+
+// ============================================================================
+// Simple Fixed Item Roundtrip Tests
+// ============================================================================
+
+#[test]
+fn roundtrip_simple_fixed_zeros() {
+    use simple_fixed::cat001::*;
+
+    let original = Item010 { sac: 0, sic: 0 };
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let decoded = Item010::decode(&mut reader).unwrap();
+
+    assert_eq!(original, decoded);
+}
+
+#[test]
+fn roundtrip_simple_fixed_max_values() {
+    use simple_fixed::cat001::*;
+
+    let original = Item010 { sac: 255, sic: 255 };
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let decoded = Item010::decode(&mut reader).unwrap();
+
+    assert_eq!(original, decoded);
+}
+
+#[test]
+fn roundtrip_simple_fixed_typical() {
+    use simple_fixed::cat001::*;
+
+    let original = Item010 { sac: 42, sic: 128 };
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let decoded = Item010::decode(&mut reader).unwrap();
+
+    assert_eq!(original, decoded);
+}
+
+#[test]
+fn roundtrip_simple_fixed_bytes() {
+    use simple_fixed::cat001::*;
+
+    // Known byte sequence: sac=42 (0x2A), sic=128 (0x80)
+    let bytes = [0x2A, 0x80];
+
+    let mut reader = BitReader::new(Cursor::new(&bytes));
+    let item = Item010::decode(&mut reader).unwrap();
+
+    assert_eq!(item.sac, 42);
+    assert_eq!(item.sic, 128);
+
+    // Re-encode
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        item.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    test_utils::assert_bytes_eq_bitdiff(&bytes, &buffer);
+}
+
+// ============================================================================
+// Multi-Item Record Roundtrip Tests
+// ============================================================================
+
+#[test]
+fn roundtrip_record_all_items() {
+    use multi_item_record::cat048::*;
+
+    let original = Record {
+        item010: Some(Item010 { sac: 42, sic: 128 }),
+        item020: Some(Item020 { typ: 99 }),
+        item240: Some(Item240 { aircraft_id: "TEST".to_string() }),
+    };
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let decoded = Record::decode(&mut reader).unwrap();
+
+    assert_eq!(original, decoded);
+}
+
+#[test]
+fn roundtrip_record_partial_items() {
+    use multi_item_record::cat048::*;
+
+    let original = Record {
+        item010: Some(Item010 { sac: 1, sic: 2 }),
+        item020: None,
+        item240: None,
+    };
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let decoded = Record::decode(&mut reader).unwrap();
+
+    assert_eq!(original, decoded);
+}
+
+#[test]
+fn roundtrip_record_empty() {
+    use multi_item_record::cat048::*;
+
+    let original = Record {
+        item010: None,
+        item020: None,
+        item240: None,
+    };
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let decoded = Record::decode(&mut reader).unwrap();
+
+    assert_eq!(original, decoded);
+}
+
+// ============================================================================
+// String Field Roundtrip Tests
+// ============================================================================
+
+#[test]
+fn roundtrip_string_field_exact_length() {
+    use multi_item_record::cat048::*;
+
+    // 6-byte field with exactly 6 characters
+    let original = Item240 { aircraft_id: "ABCDEF".to_string() };
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let decoded = Item240::decode(&mut reader).unwrap();
+
+    assert_eq!(original, decoded);
+}
+
+#[test]
+fn roundtrip_string_field_shorter_than_field() {
+    use multi_item_record::cat048::*;
+
+    // 6-byte field with only 3 characters — should be space-padded on wire
+    let original = Item240 { aircraft_id: "ABC".to_string() };
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    // Verify wire format: 'A' 'B' 'C' ' ' ' ' ' '
+    assert_eq!(buffer.len(), 6);
+    test_utils::assert_bytes_eq_bitdiff(&[0x41, 0x42, 0x43, 0x20, 0x20, 0x20], &buffer);
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let decoded = Item240::decode(&mut reader).unwrap();
+
+    assert_eq!(decoded, original);
+}
+
+#[test]
+fn roundtrip_string_field_empty() {
+    use multi_item_record::cat048::*;
+
+    // Empty string — all spaces on wire
+    let original = Item240 { aircraft_id: "".to_string() };
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    // Should be 6 space bytes
+    test_utils::assert_bytes_eq_bitdiff(&[0x20, 0x20, 0x20, 0x20, 0x20, 0x20], &buffer);
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let decoded = Item240::decode(&mut reader).unwrap();
+
+    assert_eq!(decoded, original);
+}
+
+#[test]
+fn roundtrip_string_field_known_bytes() {
+    use multi_item_record::cat048::*;
+
+    // Decode from known byte sequence: "BAW123" (British Airways callsign)
+    let bytes = [0x42, 0x41, 0x57, 0x31, 0x32, 0x33]; // "BAW123"
+
+    let mut reader = BitReader::new(Cursor::new(&bytes));
+    let item = Item240::decode(&mut reader).unwrap();
+
+    assert_eq!(item.aircraft_id, "BAW123");
+
+    // Re-encode and verify exact bytes
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        item.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    test_utils::assert_bytes_eq_bitdiff(&bytes, &buffer);
+}
+
+#[test]
+fn roundtrip_string_field_in_record() {
+    use multi_item_record::cat048::*;
+
+    // String field only in record (other items absent)
+    let original = Record {
+        item010: None,
+        item020: None,
+        item240: Some(Item240 { aircraft_id: "DLH42".to_string() }),
+    };
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let decoded = Record::decode(&mut reader).unwrap();
+
+    assert_eq!(original, decoded);
+}
+
+// ============================================================================
+// Enum Roundtrip Tests
+// ============================================================================
+
+#[test]
+fn roundtrip_enum_known_values() {
+    use enum_basic::cat001::*;
+
+    // Test with known enum values
+    let original = Item010 {
+        target_type: TargetType::Psr,
+    };
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let decoded = Item010::decode(&mut reader).unwrap();
+
+    assert_eq!(original, decoded);
+}
+
+#[test]
+fn roundtrip_enum_all_variants() {
+    use enum_basic::cat001::*;
+
+    for variant in [TargetType::Psr, TargetType::Ssr] {
+        let original = Item010 {
+            target_type: variant,
+        };
+
+        let mut buffer = Vec::new();
+        {
+            let mut writer = BitWriter::new(&mut buffer);
+            original.encode(&mut writer).unwrap();
+            writer.flush().unwrap();
+        }
+
+        let mut reader = BitReader::new(Cursor::new(&buffer));
+        let decoded = Item010::decode(&mut reader).unwrap();
+
+        assert_eq!(original, decoded, "Failed for variant {:?}", variant);
+    }
+}
+
+// ============================================================================
+// Extended Item Roundtrip Tests
+// ============================================================================
+
+#[test]
+fn roundtrip_extended_single_part() {
+    use extended_multi_part::cat048::*;
+
+    // Test with only the first part present
+    // Part0 has fields: a (3 bits), b (4 bits)
+    let original = Item020 {
+        part0: Item020Part0 { a: 5, b: 10 },
+        part1: None,
+        part2: None,
+        extensions: Vec::new(),
+    };
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let decoded = Item020::decode(&mut reader).unwrap();
+
+    assert_eq!(original, decoded);
+}
+
+#[test]
+fn roundtrip_extended_two_parts() {
+    use extended_multi_part::cat048::*;
+
+    // Test with both parts present
+    // Part0: a (3 bits), b (4 bits)
+    // Part1: c (5 bits)
+    let original = Item020 {
+        part0: Item020Part0 { a: 5, b: 10 },
+        part1: Some(Item020Part1 { c: 20 }),
+        part2: Some(Item020Part2 { d: 30 }),
+        extensions: Vec::new(),
+    };
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let decoded = Item020::decode(&mut reader).unwrap();
+
+    assert_eq!(original, decoded);
+}
+
+#[test]
+fn extended_keeps_unknown_extensions() {
+    use extended_multi_part::cat048::*;
+
+    // All three parts with FX set, then two octets the definition lacks
+    let bytes = [0x2B, 0x29, 0x29, 0x55, 0x02];
+    let decoded = Item020::decode(&mut BitReader::new(Cursor::new(&bytes))).unwrap();
+    assert_eq!(decoded.part2, Some(Item020Part2 { d: 5 }));
+    assert_eq!(decoded.extensions, [0x55, 0x02]);
+
+    let mut buffer = Vec::new();
+    decoded.encode(&mut BitWriter::new(&mut buffer)).unwrap();
+    assert_eq!(buffer, bytes);
+}
+
+#[test]
+fn extended_encode_rejects_broken_fx_chains() {
+    use extended_multi_part::cat048::*;
+
+    let item = Item020 {
+        part0: Item020Part0 { a: 5, b: 10 },
+        part1: Some(Item020Part1 { c: 20 }),
+        part2: Some(Item020Part2 { d: 30 }),
+        extensions: vec![0x55, 0x02],
+    };
+    let encode = |item: &Item020| {
+        let mut buffer = Vec::new();
+        item.encode(&mut BitWriter::new(&mut buffer)).map(|_| buffer)
+    };
+    assert!(encode(&item).is_ok());
+
+    let broken = [
+        (Item020 { part1: None, ..item.clone() }, "extended item has a part after an absent one"),
+        (Item020 { part2: None, ..item.clone() }, "extended item has extensions after an absent part"),
+        (Item020 { extensions: vec![0x55, 0x03], ..item.clone() }, "extended item extensions must have FX set on every octet but the last"),
+        (Item020 { extensions: vec![0x54, 0x02], ..item.clone() }, "extended item extensions must have FX set on every octet but the last"),
+    ];
+    for (item, message) in broken {
+        match encode(&item) {
+            Err(DecodeError::InvalidData(error)) => assert_eq!(error, message),
+            result => panic!("{:?} encoded to {:?}", item, result),
+        }
+    }
+}
+
+#[test]
+fn extended_unknown_extensions_keep_records_in_sync() {
+    use extended_multi_part::cat048::*;
+
+    // Two records: the first with an unknown extension octet, the second
+    // with the first part only
+    let bytes = [48, 0x00, 0x0A, 0x80, 0x2B, 0x29, 0x29, 0x02, 0x80, 0x2A];
+    let block = DataBlock::decode(&mut BitReader::new(Cursor::new(&bytes))).unwrap();
+
+    assert_eq!(block.records.len(), 2);
+    assert_eq!(block.records[0].item020.as_ref().unwrap().extensions, [0x02]);
+    let second = block.records[1].item020.as_ref().unwrap();
+    assert_eq!((second.part0.a, second.part0.b, second.part1.is_none()), (1, 5, true));
+
+    let lazy = RecordLazy::parse(&bytes[3..]).unwrap();
+    assert_eq!(lazy.encoded_len(), 5);
+}
+
+// ============================================================================
+// Compound Item Roundtrip Tests
+// ============================================================================
+
+#[test]
+fn roundtrip_compound_all_subitems() {
+    use compound_simple::cat001::*;
+
+    let original = Item100 {
+        sub0: Some(Item100Sub0 { flags: 10 }),
+        sub1: Some(Item100Sub1 { data: 20 }),
+    };
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let decoded = Item100::decode(&mut reader).unwrap();
+
+    assert_eq!(original, decoded);
+}
+
+#[test]
+fn roundtrip_compound_partial_subitems() {
+    use compound_simple::cat001::*;
+
+    let original = Item100 {
+        sub0: Some(Item100Sub0 { flags: 10 }),
+        sub1: None,
+    };
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let decoded = Item100::decode(&mut reader).unwrap();
+
+    assert_eq!(original, decoded);
+}
+
+#[test]
+fn compound_items_and_records_share_byte_level_traits() {
+    use compound_simple::cat001::*;
+
+    let item = Item100 {
+        sub0: None,
+        sub1: Some(Item100Sub1 { data: 0x1234 }),
+    };
+    let (bytes, decoded) = roundtrip::roundtrip_record(&item);
+    assert_eq!(bytes, [0x40, 0x12, 0x34]);
+    assert_eq!(decoded, item);
+
+    let record = Record { item100: Some(item) };
+    let (bytes, decoded) = roundtrip::roundtrip_record(&record);
+    assert_eq!(bytes, [0x80, 0x40, 0x12, 0x34]);
+    assert_eq!(decoded, record);
+}
+
+#[test]
+fn record_level_helpers_roundtrip_records_and_compound_items() {
+    use compound_simple::cat001::*;
+
+    roundtrip::assert_record_roundtrip(&Item100 {
+        sub0: Some(Item100Sub0 { flags: 0xff }),
+        sub1: Some(Item100Sub1 { data: 0 }),
+    });
+    roundtrip::assert_record_roundtrip(&Record { item100: None });
+    roundtrip::assert_record_bytes_roundtrip::<Item100>(&[0xc0, 0x01, 0x02, 0x03]);
+    roundtrip::assert_record_bytes_roundtrip::<Record>(&[0x80, 0x80, 0x07]);
+    // Both records of the fixture, after the block header.
+    let block = test_utils::load_message_fixture("cat048_two_records.hex");
+    roundtrip::assert_record_bytes_roundtrip::<multi_item_record::cat048::Record>(&block[3..7]);
+    roundtrip::assert_record_bytes_roundtrip::<multi_item_record::cat048::Record>(&block[7..]);
+}
+
+// ============================================================================
+// Repetitive Item Roundtrip Tests
+// ============================================================================
+
+#[test]
+fn roundtrip_repetitive_basic() {
+    use repetitive_basic::cat001::*;
+
+    // The repetitive_basic.xml fixture has counter="5", meaning exactly 5 elements
+    let original = Item070 {
+        items: vec![
+            Item070Element { azimuth: 100 },
+            Item070Element { azimuth: 200 },
+            Item070Element { azimuth: 300 },
+            Item070Element { azimuth: 400 },
+            Item070Element { azimuth: 500 },
+        ],
+    };
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let decoded = Item070::decode(&mut reader).unwrap();
+
+    assert_eq!(original, decoded);
+}
+
+#[test]
+fn roundtrip_repetitive_boundary_values() {
+    use repetitive_basic::cat001::*;
+
+    // Test with boundary values for 16-bit azimuth field
+    let original = Item070 {
+        items: vec![
+            Item070Element { azimuth: 0 },
+            Item070Element { azimuth: 1 },
+            Item070Element { azimuth: 32767 },
+            Item070Element { azimuth: 32768 },
+            Item070Element { azimuth: 65535 },
+        ],
+    };
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let decoded = Item070::decode(&mut reader).unwrap();
+
+    assert_eq!(original, decoded);
+}
+
+#[test]
+fn repetitive_items_reject_other_counts() {
+    use repetitive_basic::cat001::*;
+
+    for count in [0, 4, 6] {
+        let item = Item070 { items: vec![Item070Element { azimuth: 1 }; count] };
+        let mut buffer = Vec::new();
+        let mut writer = BitWriter::new(&mut buffer);
+        assert!(matches!(item.encode(&mut writer), Err(DecodeError::InvalidData(_))));
+        writer.flush().unwrap();
+        assert!(buffer.is_empty(), "nothing is written for {} elements", count);
+    }
+}
+
+// ============================================================================
+// EPB (Optional Field) Roundtrip Tests
+// ============================================================================
+
+#[test]
+fn roundtrip_epb_present() {
+    use epb_field::cat001::*;
+
+    let original = Item010 {
+        optional_value: Some(12345),
+    };
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let decoded = Item010::decode(&mut reader).unwrap();
+
+    assert_eq!(original, decoded);
+}
+
+#[test]
+fn roundtrip_epb_absent() {
+    use epb_field::cat001::*;
+
+    let original = Item010 {
+        optional_value: None,
+    };
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let decoded = Item010::decode(&mut reader).unwrap();
+
+    assert_eq!(original, decoded);
+}
+
+// ============================================================================
+// Spare Bits Roundtrip Tests
+// ============================================================================
+
+#[test]
+fn roundtrip_spare_bits() {
+    use spare_bits::cat001::*;
+
+    let original = Item010 { data: 42 };
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let decoded = Item010::decode(&mut reader).unwrap();
+
+    assert_eq!(original, decoded);
+}
+
+#[test]
+fn roundtrip_aligned_fields() {
+    use aligned_fields::cat001::*;
+
+    let original = Item010 { mode: 5, code: 0xabc };
+    let repeated = Item020 {
+        items: vec![
+            Item020Element { flag: Some(3), level: 9 },
+            Item020Element { flag: None, level: 1 },
+        ],
+    };
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        repeated.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    // Each field starts at the boundary after the one before.
+    assert_eq!(buffer, [0b1010_0000, 0xab, 0xc0, 0b1110_1001, 0b0000_0001]);
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    assert_eq!(Item010::decode(&mut reader).unwrap(), original);
+    assert_eq!(Item020::decode(&mut reader).unwrap(), repeated);
+}
+
+// ============================================================================
+// Derived Field Tests
+// ============================================================================
+
+#[test]
+fn derived_fields_are_computed_from_decoded_fields() {
+    use derived_fields::cat062::*;
+
+    let speed = Item200 { gsp: 1250 };
+    assert_eq!(speed.speed_kt(), 1250.0 * 0.22);
+    assert_eq!(speed.speed_hundreds(), 12);
+
+    // FL 50 with FX set, then a valid rate of 52 without.
+    let mut reader = BitReader::new(Cursor::new([0x65, 0xe8]));
+    let altitude = Item210::decode(&mut reader).unwrap();
+    assert_eq!(altitude.part0.altitude_ft(), 5000);
+    assert_eq!(altitude.part1.unwrap().rate_fpm(), Some(125.0));
+
+    let level = Item210Part1 { rate: None };
+    assert_eq!(level.rate_fpm(), None);
+}
+
+// ============================================================================
+// Field Constraint Tests
+// ============================================================================
+
+#[test]
+fn validate_accepts_values_within_constraints() {
+    use field_constraints::cat048::*;
+
+    let record = Record {
+        item010: Some(Item010 { sac: 1, sic: 200 }),
+        item040: Some(Item040 {
+            part0: Item040Part0 { gsp: 100 },
+            part1: Some(Item040Part1 { heading: Some(45) }),
+            extensions: vec![],
+        }),
+        item050: Some(Item050 { items: vec![Item050Element { level: 0 }, Item050Element { level: 100 }] }),
+        item060: None,
+    };
+
+    assert_eq!(record.validate(), Ok(()));
+}
+
+#[test]
+fn validate_reports_every_violated_constraint() {
+    use field_constraints::cat048::*;
+
+    // Decoding accepts the values; validation reports them.
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        let record = Record {
+            item010: Some(Item010 { sac: 0, sic: 201 }),
+            item040: Some(Item040 {
+                part0: Item040Part0 { gsp: 0 },
+                part1: Some(Item040Part1 { heading: Some(7) }),
+                extensions: vec![],
+            }),
+            item050: Some(Item050 { items: vec![Item050Element { level: 50 }, Item050Element { level: 101 }] }),
+            item060: Some(Item060 {
+                sub0: Some(Item060Sub0 { flags: 0 }),
+                sub1: Some(Item060Sub1 {
+                    part0: Item060Sub1Part0 { x: 0 },
+                    part1: Some(Item060Sub1Part1 { y: 9 }),
+                    extensions: vec![],
+                }),
+            }),
+        };
+        record.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+    let record = Record::decode(&mut BitReader::new(Cursor::new(&buffer))).unwrap();
+
+    let errors: Vec<String> = record.validate().unwrap_err().iter().map(ToString::to_string).collect();
+    assert_eq!(
+        errors,
+        [
+            "item010.sac: 0 is below the minimum 1",
+            "item010.sic: 201 is above the maximum 200",
+            "item040.part0.gsp: 0 is below the minimum 1",
+            "item040.part1.heading: 7 is not a multiple of 5",
+            "item050.items.1.level: 101 is above the maximum 100",
+            "item060.sub1.part1.y: 9 is below the minimum 10",
+        ]
+    );
+
+    // Items check their fields on their own too.
+    assert_eq!(record.item010.unwrap().validate().unwrap_err().len(), 2);
+}
+
+#[test]
+fn check_conformance_reports_every_broken_rule() {
+    use conformance_rules::cat048::*;
+
+    let record = Record {
+        item010: Some(Item010 { sac: 1, sic: 2 }),
+        item020: Some(Item020 {
+            part0: Item020Part0 { typ: Typ::Psr, sim: 15 },
+            part1: Some(Item020Part1 { rdp: 127 }),
+            part2: None,
+            extensions: vec![],
+        }),
+        item030: Some(Item030 { items: vec![Item030Element { warning: 1 }, Item030Element { warning: 2 }] }),
+        item240: Some(Item240 { callsign: "AFR123".to_string() }),
+    };
+    assert_eq!(record.check_conformance(), []);
+
+    // Encoding would cut or drop all of these without an error.
+    let record = Record {
+        item010: None,
+        item020: Some(Item020 {
+            part0: Item020Part0 { typ: Typ::Unknown(5), sim: 16 },
+            part1: None,
+            part2: Some(Item020Part2 { tst: 0 }),
+            extensions: vec![0x02],
+        }),
+        item030: Some(Item030 { items: vec![Item030Element { warning: 128 }] }),
+        item240: Some(Item240 { callsign: "SPEEDBIRD".to_string() }),
+    };
+    let issues: Vec<String> = record.check_conformance().iter().map(ToString::to_string).collect();
+    assert_eq!(
+        issues,
+        [
+            "item010: mandatory item is absent",
+            "item020.part2: present after an absent part",
+            "item020.part0.typ: 5 is not a variant of the enum",
+            "item020.part0.sim: 16 does not fit in 4 bits",
+            "item030.items: 1 repetitions instead of 2",
+            "item030.items.0.warning: 128 does not fit in 7 bits",
+            "item240.callsign: 9 bytes, not 6",
+        ]
+    );
+
+    // Constraints of the fields are issues too.
+    let record = Record { item010: Some(Item010 { sac: 1, sic: 0 }), item020: None, item030: None, item240: None };
+    let issues: Vec<String> = record.check_conformance().iter().map(ToString::to_string).collect();
+    assert_eq!(issues, ["item010.sic: 0 is below the minimum 1"]);
+}
+
+// ============================================================================
+// Generic Value Tests
+// ============================================================================
+
+#[test]
+fn records_convert_to_and_from_value_maps() {
+    use conformance_rules::cat048::*;
+
+    let record = Record {
+        item010: Some(Item010 { sac: 1, sic: 2 }),
+        item020: Some(Item020 {
+            part0: Item020Part0 { typ: Typ::Ssr, sim: 3 },
+            part1: None,
+            part2: None,
+            extensions: vec![],
+        }),
+        item030: Some(Item030 { items: vec![Item030Element { warning: 4 }, Item030Element { warning: 5 }] }),
+        item240: None,
+    };
+    let mut map = record.to_value_map();
+
+    let item020 = Value::Map(ValueMap::from([
+        ("part0".to_string(), Value::Map(ValueMap::from([
+            ("typ".to_string(), Value::Text("Ssr".to_string())),
+            ("sim".to_string(), Value::Number(3)),
+        ]))),
+        ("part1".to_string(), Value::Null),
+        ("part2".to_string(), Value::Null),
+    ]));
+    assert_eq!(map.len(), 3);
+    assert_eq!(map["item020"], item020);
+    assert_eq!(Record::from_value_map(&map).unwrap(), record);
+
+    // Edited generically, e.g. by a field mapping.
+    let Some(Value::List(elements)) = map.get_mut("item030") else { panic!("item030 is a list") };
+    elements.pop();
+    map.insert("item240".to_string(), Value::Map(ValueMap::from([
+        ("callsign".to_string(), Value::Text("AFR123".to_string())),
+    ])));
+    map.insert("item020".to_string(), Value::Map(ValueMap::from([
+        ("part0".to_string(), Value::Map(ValueMap::from([
+            ("typ".to_string(), Value::Number(5)),
+            ("sim".to_string(), Value::Number(3)),
+        ]))),
+    ])));
+    let edited = Record::from_value_map(&map).unwrap();
+    assert_eq!(edited.item020.unwrap().part0.typ, Typ::Unknown(5));
+    assert_eq!(edited.item030.unwrap().items, [Item030Element { warning: 4 }]);
+    assert_eq!(edited.item240.unwrap().callsign, "AFR123");
+
+    // Errors name the value.
+    map.insert("item010".to_string(), Value::Map(ValueMap::from([
+        ("sac".to_string(), Value::Number(1)),
+        ("sic".to_string(), Value::Number(256)),
+    ])));
+    assert_eq!(Record::from_value_map(&map).unwrap_err().to_string(), "item010.sic: 256 is out of range");
+    map.insert("item099".to_string(), Value::Null);
+    assert_eq!(Record::from_value_map(&map).unwrap_err().to_string(), "item099: unknown key");
+
+    // Elements can't outnumber a `heapless::Vec`.
+    let elements = Value::List(vec![Value::Map(ValueMap::from([("azimuth".to_string(), Value::Number(1))])); 6]);
+    let error = repetitive_heapless::cat001::Item070::from_value(&elements).unwrap_err();
+    assert_eq!(error.to_string(), "6 values instead of 5");
+}
+
+// ============================================================================
+// Protobuf Tests
+// ============================================================================
+
+#[test]
+fn records_convert_to_and_from_protobuf_messages() {
+    use conformance_rules::{cat048, cat048_proto};
+    use prost::Message;
+
+    let block = cat048::DataBlock {
+        records: vec![cat048::Record {
+            item010: Some(cat048::Item010 { sac: 1, sic: 2 }),
+            item020: Some(cat048::Item020 {
+                part0: cat048::Item020Part0 { typ: cat048::Typ::Ssr, sim: 3 },
+                part1: Some(cat048::Item020Part1 { rdp: 4 }),
+                part2: None,
+                extensions: vec![],
+            }),
+            item030: Some(cat048::Item030 { items: vec![cat048::Item030Element { warning: 5 }] }),
+            item240: Some(cat048::Item240 { callsign: "AFR123".to_string() }),
+        }],
+    };
+
+    let bytes = cat048_proto::DataBlock::from(&block).encode_to_vec();
+    let message = cat048_proto::DataBlock::decode(bytes.as_slice()).unwrap();
+    assert_eq!(message.records[0].item020.as_ref().unwrap().part0.as_ref().unwrap().typ, 2);
+    assert_eq!(cat048::DataBlock::try_from(message).unwrap(), block);
+
+    // Values without a place in the generated types are errors naming them.
+    let mut record = cat048_proto::Record::from(&block.records[0]);
+    record.item010.as_mut().unwrap().sic = 256;
+    let error = cat048::Record::try_from(record.clone()).unwrap_err();
+    assert_eq!(error.to_string(), "item010.sic: 256 is out of range");
+
+    record.item010 = None;
+    record.item020.as_mut().unwrap().part0 = None;
+    let error = cat048::Record::try_from(record).unwrap_err();
+    assert_eq!(error.to_string(), "item020.part0: missing value");
+
+    // Elements can't outnumber a `heapless::Vec`.
+    use repetitive_heapless::{cat001, cat001_proto};
+    let message = cat001_proto::Item070 { items: vec![cat001_proto::Item070Element { azimuth: 1 }; 6] };
+    let error = cat001::Item070::try_from(message).unwrap_err();
+    assert_eq!(error.to_string(), "items: 6 values instead of 5");
+}
+
+// ============================================================================
+// Arrow Tests
+// ============================================================================
+
+#[test]
+fn records_convert_to_arrow_record_batches() {
+    use arrow_array::{Array, ListArray, StringArray, StructArray, UInt8Array};
+    use conformance_rules::{cat048, cat048_arrow};
+
+    let records = [
+        cat048::Record {
+            item010: Some(cat048::Item010 { sac: 1, sic: 2 }),
+            item020: Some(cat048::Item020 {
+                part0: cat048::Item020Part0 { typ: cat048::Typ::Ssr, sim: 3 },
+                part1: None,
+                part2: None,
+                extensions: vec![],
+            }),
+            item030: Some(cat048::Item030 {
+                items: vec![cat048::Item030Element { warning: 4 }, cat048::Item030Element { warning: 5 }],
+            }),
+            item240: None,
+        },
+        cat048::Record {
+            item010: Some(cat048::Item010 { sac: 6, sic: 7 }),
+            item020: None,
+            item030: None,
+            item240: Some(cat048::Item240 { callsign: "AFR123".to_string() }),
+        },
+    ];
+
+    let batch = cat048_arrow::record_batch(&records).unwrap();
+    let names: Vec<_> = batch.schema().fields().iter().map(|field| field.name().clone()).collect();
+    assert_eq!(names, [
+        "item010_sac",
+        "item010_sic",
+        "item020_part0_typ",
+        "item020_part0_sim",
+        "item020_part1_rdp",
+        "item020_part2_tst",
+        "item030",
+        "item240_callsign",
+    ]);
+    assert_eq!(batch.num_rows(), 2);
+    assert_eq!(batch.schema(), cat048_arrow::schema());
+
+    let column = |name: &str| batch.column_by_name(name).unwrap().clone();
+    let sic = column("item010_sic");
+    assert_eq!(sic.as_any().downcast_ref::<UInt8Array>().unwrap().values(), &[2, 7]);
+    let typ = column("item020_part0_typ");
+    let typ = typ.as_any().downcast_ref::<UInt8Array>().unwrap();
+    assert_eq!((typ.value(0), typ.is_null(1)), (2, true));
+    assert_eq!(column("item020_part1_rdp").null_count(), 2);
+    let callsign = column("item240_callsign");
+    let callsign = callsign.as_any().downcast_ref::<StringArray>().unwrap();
+    assert_eq!((callsign.is_null(0), callsign.value(1)), (true, "AFR123"));
+
+    // Repetitive items are lists of structs of their elements.
+    let warnings = column("item030");
+    let warnings = warnings.as_any().downcast_ref::<ListArray>().unwrap();
+    assert!(warnings.is_null(1));
+    let elements = warnings.value(0);
+    let elements = elements.as_any().downcast_ref::<StructArray>().unwrap();
+    let warning = elements.column_by_name("warning").unwrap();
+    assert_eq!(warning.as_any().downcast_ref::<UInt8Array>().unwrap().values(), &[4, 5]);
+}
+
+// ============================================================================
+// Geo Position Tests
+// ============================================================================
+
+#[test]
+fn positions_with_geo_roles_convert_to_wgs84() {
+    use geo_positions::cat048::*;
+    use rasterix::rcore::geo::{CartesianPosition, PolarPosition, RadarSite};
+
+    let site = RadarSite::new(45.0, 9.0, 200.0);
+
+    // 60 NM at 90 degrees, FL 350.
+    let polar = Item040 { rho: 60 * 256, theta: 16384 };
+    assert_eq!((polar.range_nm(), polar.azimuth_deg()), (60.0, 90.0));
+    let level = Item090 { flight_level: 1400 };
+    let position = polar.to_wgs84(&site, Some(level.height_m()));
+    assert_eq!(position, site.polar_to_wgs84(60.0 * 1852.0, 90.0, Some(10_668.0)));
+    assert!(position.longitude > 10.0 && (position.height - 10_668.0).abs() < 0.01);
+
+    // 10 NM west and 20 NM north, in two's complement.
+    let cartesian = Item042 { x: (-10i16 * 128) as u16, y: 20 * 128 };
+    assert_eq!((cartesian.east_nm(), cartesian.north_nm()), (-10.0, 20.0));
+    let position = cartesian.to_wgs84(&site);
+    assert!(position.longitude < 9.0 && position.latitude > 45.0);
+}
+
+// ============================================================================
+// Altitude Tests
+// ============================================================================
+
+#[test]
+fn typed_altitude_fields_convert_units() {
+    use altitude_fields::cat062::*;
+    use rasterix::rcore::altitude::{FlightLevel, GeometricAltitude};
+
+    let mut level = Item136 { flight_level: 0 };
+    level.set_flight_level(FlightLevel::from_feet(-1_000.0));
+    assert_eq!(level.flight_level, 0xFFD8);
+    assert_eq!(level.flight_level().fl(), -10.0);
+
+    let mut altitude = Item130 { altitude: 0 };
+    altitude.set_altitude(GeometricAltitude::from_feet(30_000.0));
+    assert_eq!(altitude.altitude, 4800);
+    assert!((altitude.altitude().meters() - 9144.0).abs() < 1e-9);
+
+    // 13 bits of the optional level, in two's complement.
+    let mut mode_c = Item090 { v: 0, g: 0, measured_level: None };
+    assert_eq!(mode_c.measured_level(), None);
+    mode_c.set_measured_level(Some(FlightLevel::from_fl(-12.0)));
+    assert_eq!(mode_c.measured_level, Some(0x1FD0));
+
+    let original = Record { item130: Some(altitude), item136: Some(level), item090: Some(mode_c) };
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let decoded = Record::decode(&mut reader).unwrap();
+
+    assert_eq!(original, decoded);
+    assert_eq!(decoded.item136.unwrap().flight_level().to_string(), "FL-10");
+    assert_eq!(decoded.item090.unwrap().measured_level().unwrap().fl(), -12.0);
+}
+
+// ============================================================================
+// Velocity Tests
+// ============================================================================
+
+#[test]
+fn velocity_fields_convert_between_polar_and_cartesian() {
+    use velocity_fields::cat062::*;
+    use rasterix::rcore::velocity::Velocity;
+
+    // 0.1 NM/s heading west.
+    let polar = Item200 { ground_speed: 1638, heading: 0xC000 };
+    let velocity = polar.velocity();
+    assert!((velocity.knots() - 360.0).abs() < 0.1);
+    assert!((velocity.heading() - 270.0).abs() < 1e-9);
+
+    let mut cartesian = Item185 { vx: 0, vy: 0 };
+    cartesian.set_velocity(velocity);
+    assert_eq!((cartesian.vx, cartesian.vy), ((-741i16) as u16, 0));
+    assert_eq!(cartesian.velocity(), Velocity::from_cartesian(-185.25, 0.0));
+
+    let mut polar = Item200 { ground_speed: 0, heading: 0 };
+    polar.set_velocity(Velocity::from_knots(360.0, 90.0));
+    assert_eq!((polar.ground_speed, polar.heading), (1638, 0x4000));
+
+    let original = Record { item185: Some(cartesian), item200: Some(polar) };
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let decoded = Record::decode(&mut reader).unwrap();
+
+    assert_eq!(original, decoded);
+}
+
+// ============================================================================
+// ICAO Address Tests
+// ============================================================================
+
+#[test]
+fn icao_address_fields_format_as_hex() {
+    use icao_fields::cat048::*;
+    use rasterix::rcore::icao::IcaoAddress;
+
+    let mut original = Item220 { address: 0 };
+    original.set_address("4CA1D3".parse().unwrap());
+    assert_eq!(original.address, 0x4CA1D3);
+    assert_eq!(original.address().to_string(), "4CA1D3");
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+    assert_eq!(buffer, [0x4C, 0xA1, 0xD3]);
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let decoded = Item220::decode(&mut reader).unwrap();
+
+    assert_eq!(decoded.address(), IcaoAddress::new(0x4CA1D3).unwrap());
+}
+
+// ============================================================================
+// Explicit Item Roundtrip Tests
+// ============================================================================
+
+#[test]
+fn roundtrip_explicit_item() {
+    use explicit_item::cat001::*;
+
+    let original = Item060 {
+        altitude: 1000,
+        speed: 250,
+    };
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let decoded = Item060::decode(&mut reader).unwrap();
+
+    assert_eq!(original, decoded);
+}
+
+// ============================================================================
+// Edge Cases
+// ============================================================================
+
+#[test]
+fn roundtrip_boundary_values() {
+    use simple_fixed::cat001::*;
+
+    for &value in &[0u8, 1, 127, 128, 254, 255] {
+        let original = Item010 { sac: value, sic: value };
+
+        let mut buffer = Vec::new();
+        {
+            let mut writer = BitWriter::new(&mut buffer);
+            original.encode(&mut writer).unwrap();
+            writer.flush().unwrap();
+        }
+
+        let mut reader = BitReader::new(Cursor::new(&buffer));
+        let decoded = Item010::decode(&mut reader).unwrap();
+
+        assert_eq!(original, decoded, "Failed for value {}", value);
+    }
+}
+
+// ============================================================================
+// DataBlock Roundtrip Tests
+// ============================================================================
+
+#[test]
+fn roundtrip_datablock_empty() {
+    use multi_item_record::cat048::*;
+
+    let original = DataBlock::new();
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    // CAT=48, LEN=3 (0x00 0x03)
+    test_utils::assert_bytes_eq_bitdiff(&[48, 0x00, 0x03], &buffer);
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let decoded = DataBlock::decode(&mut reader).unwrap();
+
+    assert_eq!(original, decoded);
+}
+
+#[test]
+fn roundtrip_datablock_single_record() {
+    use multi_item_record::cat048::*;
+
+    let original = DataBlock::with_records(vec![
+        Record {
+            item010: Some(Item010 { sac: 42, sic: 128 }),
+            item020: None,
+            item240: None,
+        },
+    ]);
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    // Verify header: CAT=48, LEN > 3
+    assert_eq!(buffer[0], 48);
+    let len = u16::from_be_bytes([buffer[1], buffer[2]]);
+    assert_eq!(len as usize, buffer.len());
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let decoded = DataBlock::decode(&mut reader).unwrap();
+
+    assert_eq!(original, decoded);
+}
+
+#[test]
+fn roundtrip_datablock_multiple_records() {
+    use multi_item_record::cat048::*;
+
+    let original = DataBlock::with_records(vec![
+        Record {
+            item010: Some(Item010 { sac: 1, sic: 2 }),
+            item020: Some(Item020 { typ: 10 }),
+            item240: Some(Item240 { aircraft_id: "BAW123".to_string() }),
+        },
+        Record {
+            item010: Some(Item010 { sac: 3, sic: 4 }),
+            item020: None,
+            item240: None,
+        },
+        Record {
+            item010: None,
+            item020: None,
+            item240: Some(Item240 { aircraft_id: "DLH42".to_string() }),
+        },
+    ]);
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    // Verify header
+    assert_eq!(buffer[0], 48);
+    let len = u16::from_be_bytes([buffer[1], buffer[2]]);
+    assert_eq!(len as usize, buffer.len());
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let decoded = DataBlock::decode(&mut reader).unwrap();
+
+    assert_eq!(original, decoded);
+}
+
+#[test]
+fn encode_to_slice_matches_bit_writer() {
+    use multi_item_record::cat048::*;
+
+    let original = DataBlock::with_records(vec![
+        Record {
+            item010: Some(Item010 { sac: 1, sic: 2 }),
+            item020: None,
+            item240: Some(Item240 { aircraft_id: "BAW123".to_string() }),
+        },
+        Record {
+            item010: None,
+            item020: Some(Item020 { typ: 10 }),
+            item240: None,
+        },
+    ]);
+
+    let mut expected = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut expected);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut buf = [0u8; 64];
+    let written = original.encode_to_slice(&mut buf).unwrap();
+    assert_eq!(&buf[..written], &expected[..]);
+
+    // A buffer one byte short is rejected
+    let mut short = vec![0u8; expected.len() - 1];
+    assert!(matches!(original.encode_to_slice(&mut short), Err(DecodeError::Io(_))));
+}
+
+#[test]
+fn datablock_lengths_are_bounded() {
+    use multi_item_record::cat048::*;
+
+    // 30 000 records of 3 bytes do not fit in the 16-bit block length
+    let record = Record { item010: Some(Item010 { sac: 1, sic: 2 }), item020: None, item240: None };
+    let oversized = DataBlock::with_records(vec![record.clone(); 30_000]);
+    let result = oversized.encode(&mut BitWriter::new(Vec::new()));
+    assert!(matches!(result, Err(DecodeError::LimitExceeded { limit: "data block length", .. })));
+
+    struct SmallBlocks;
+    impl DecodePolicy for SmallBlocks {
+        fn max_block_len(&self) -> usize {
+            8
+        }
+    }
+
+    let mut buffer = Vec::new();
+    let block = DataBlock::with_records(vec![record.clone(), record]);
+    block.encode(&mut BitWriter::new(&mut buffer)).unwrap();
+    assert_eq!(buffer.len(), 9);
+
+    let result = policy::with_policy(SmallBlocks, || DataBlock::decode(&mut BitReader::new(Cursor::new(&buffer))));
+    assert!(matches!(result, Err(DecodeError::LimitExceeded { max: 8, .. })));
+    assert!(DataBlock::decode(&mut BitReader::new(Cursor::new(&buffer))).is_ok());
+}
+
+#[test]
+fn item_layout_consts_locate_items_in_the_fspec() {
+    use multi_item_record::cat048::*;
+
+    fn is_present<I: DataItem>(fspec: &Fspec) -> bool {
+        fspec.is_set(I::FSPEC_BYTE, I::FSPEC_BIT)
+    }
+
+    assert_eq!((Item240::ITEM_ID, Item240::FRN), (240, 4));
+    assert_eq!((Item240::FSPEC_BYTE, Item240::FSPEC_BIT), (0, 3));
+
+    let record = Record { item010: None, item020: None, item240: Some(Item240 { aircraft_id: "A".into() }) };
+    let mut buffer = Vec::new();
+    record.encode(&mut BitWriter::new(&mut buffer)).unwrap();
+    let fspec = Fspec::read(&mut Cursor::new(&buffer)).unwrap();
+
+    assert!(is_present::<Item240>(&fspec));
+    assert!(!is_present::<Item010>(&fspec));
+}
+
+#[test]
+fn three_digit_item_ids_roundtrip() {
+    use high_item_ids::cat062::*;
+
+    assert_eq!((Item500::ITEM_ID, Item500::FRN), (500, 9));
+    assert_eq!((Item999::ITEM_ID, Item999::FRN), (999, 10));
+
+    let record = Record {
+        item010: Some(Item010 { sac: 1, sic: 2 }),
+        item500: Some(Item500 { apc: 3 }),
+        item999: Some(Item999 { track_count: 4 }),
+    };
+    let mut buffer = Vec::new();
+    record.encode(&mut BitWriter::new(&mut buffer)).unwrap();
+    assert_eq!(buffer, [0x81, 0x60, 0x01, 0x02, 0x03, 0x04]);
+
+    let decoded = Record::decode(&mut BitReader::new(Cursor::new(&buffer))).unwrap();
+    assert_eq!(decoded, record);
+}
+
+#[test]
+fn items_are_reachable_by_title() {
+    use named_items::cat048::*;
+
+    let dsi: DataSourceIdentifier = Item010 { sac: 1, sic: 2 };
+    assert_eq!((Item010::TITLE, Item010::SHORT_NAME), ("Data Source Identifier", "DSI"));
+    assert_eq!(Mode3ACodeInOctalRepresentation::TITLE, "Mode-3/A Code in Octal Representation");
+    assert_eq!(Item140::SHORT_NAME, "TOD");
+
+    let mut buffer = Vec::new();
+    dsi.encode(&mut BitWriter::new(&mut buffer)).unwrap();
+    assert_eq!(DataSourceIdentifier::decode(&mut BitReader::new(Cursor::new(&buffer))).unwrap(), dsi);
+}
+
+#[test]
+fn reserved_items_skip_their_bytes() {
+    use reserved_items::cat048::*;
+
+    let bytes = [0xE0, 0x01, 0x02, 0xAB, 0xCD, 0x07];
+    let record = Record::decode(&mut BitReader::new(Cursor::new(&bytes))).unwrap();
+    assert_eq!(record.item015, Some(Item015));
+    assert_eq!(record.item020, Some(Item020 { typ: 7 }));
+
+    // Spare bits are written as zeros.
+    let mut buffer = Vec::new();
+    record.encode(&mut BitWriter::new(&mut buffer)).unwrap();
+    assert_eq!(buffer, [0xE0, 0x01, 0x02, 0x00, 0x00, 0x07]);
+}
+
+#[test]
+fn little_endian_fields_swap_their_bytes() {
+    use little_endian_fields::cat240::*;
+
+    let bytes = [0x34, 0x12, 0xA5, 0x63, 0x41, 0x20];
+    let item = Item020::decode(&mut BitReader::new(Cursor::new(&bytes))).unwrap();
+    assert_eq!(item, Item020 { sequence: 0x1234, flags: 0xA, offset: 0x123456 });
+
+    let mut buffer = Vec::new();
+    item.encode(&mut BitWriter::new(&mut buffer)).unwrap();
+    assert_eq!(buffer, bytes);
+
+    let item = Item030 { timestamp: Some(0x12345678) };
+    let mut buffer = Vec::new();
+    item.encode(&mut BitWriter::new(&mut buffer)).unwrap();
+    assert_eq!(buffer, [0xBC, 0x2B, 0x1A, 0x09, 0x00]);
+    assert_eq!(Item030::decode(&mut BitReader::new(Cursor::new(&buffer))).unwrap(), item);
+}
+
+#[test]
+fn float_fields_convert_their_bits() {
+    use float_fields::cat250::*;
+
+    let bytes = [0x41, 0xC8, 0x00, 0x00, 0x00, 0x40, 0x7D, 0x44];
+    let item = WeatherSample::decode(&mut BitReader::new(Cursor::new(&bytes))).unwrap();
+    assert_eq!(item, WeatherSample { temperature: 25.0, pressure: 1013.0 });
+
+    let item = Item030 { range: Some(1.5) };
+    let mut buffer = Vec::new();
+    item.encode(&mut BitWriter::new(&mut buffer)).unwrap();
+    assert_eq!(buffer, [0x9F, 0xFC, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    assert_eq!(Item030::decode(&mut BitReader::new(Cursor::new(&buffer))).unwrap(), item);
+}
+
+#[test]
+fn byte_fields_keep_their_bytes() {
+    use byte_fields::cat048::*;
+
+    let bytes = [
+        0x20, 0x00, 0x14, 0x00, 0x00, 0x00, 0x01, 0x30, // BDS 3,0
+        0x40, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x50, // BDS 5,0
+    ];
+    let item = ModeSMbData::decode(&mut BitReader::new(Cursor::new(&bytes))).unwrap();
+    assert_eq!(item.items[0].mb_data, [0x20, 0x00, 0x14, 0x00, 0x00, 0x00, 0x01]);
+    assert_eq!((item.items[1].bds1, item.items[1].bds2), (5, 0));
+
+    let mut buffer = Vec::new();
+    item.encode(&mut BitWriter::new(&mut buffer)).unwrap();
+    assert_eq!(buffer, bytes);
+
+    // Longer fields are vectors, padded with zeros to their length.
+    let item = Item270 { payload: vec![0xAB; 3] };
+    let mut buffer = Vec::new();
+    item.encode(&mut BitWriter::new(&mut buffer)).unwrap();
+    assert_eq!(buffer.len(), 40);
+    assert_eq!(buffer[..4], [0xAB, 0xAB, 0xAB, 0x00]);
+
+    // Bytes after the valid bit are not aligned.
+    let item = Item280 { signature: Some([0xDE, 0xAD, 0xBE, 0xEF]) };
+    let mut buffer = Vec::new();
+    item.encode(&mut BitWriter::new(&mut buffer)).unwrap();
+    assert_eq!(buffer, [0xEF, 0x56, 0xDF, 0x77, 0x80]);
+    assert_eq!(Item280::decode(&mut BitReader::new(Cursor::new(&buffer))).unwrap(), item);
+}
+
+#[test]
+#[allow(deprecated)]
+fn renamed_fields_keep_their_previous_accessors() {
+    use field_revisions::cat034::*;
+
+    let mut item = Item050 {
+        system_mode: SystemMode::Degraded,
+        legacy_flag: 1,
+        elevation: Some(0x55),
+    };
+    assert_eq!(*item.mode(), SystemMode::Degraded);
+    *item.height_mut() = Some(0x2A);
+    assert_eq!(item.elevation, Some(0x2A));
+
+    let mut buffer = Vec::new();
+    item.encode(&mut BitWriter::new(&mut buffer)).unwrap();
+    assert_eq!(buffer, [0x60, 0xAA]);
+    assert_eq!(Item050::decode(&mut BitReader::new(Cursor::new(&buffer))).unwrap(), item);
+
+    let item = Item041 { rotation_period: 0x1234 };
+    assert_eq!(*item.antenna_rotation_speed(), 0x1234);
+}
+
+#[test]
+fn datablock_category_constant() {
+    use multi_item_record::cat048::*;
+    assert_eq!(DataBlock::CATEGORY, 48);
+}
+
+#[test]
+fn datablock_decode_wrong_category() {
+    use multi_item_record::cat048::*;
+
+    // Manually craft a data block with wrong category (1 instead of 48)
+    let data = vec![1, 0x00, 0x03];
+    let mut reader = BitReader::new(Cursor::new(&data));
+    let result = DataBlock::decode(&mut reader);
+    assert!(result.is_err());
+}
+
+#[test]
+fn datablock_decode_length_too_small() {
+    use multi_item_record::cat048::*;
+
+    // LEN=2 is invalid (minimum is 3)
+    let data = vec![48, 0x00, 0x02];
+    let mut reader = BitReader::new(Cursor::new(&data));
+    let result = DataBlock::decode(&mut reader);
+    assert!(result.is_err());
+}
+
+#[test]
+fn datablock_decode_record_overrun() {
+    use multi_item_record::cat048::*;
+
+    // LEN=8 cuts the second I048/010 after its SAC
+    let data = vec![48, 0x00, 0x08, 0x80, 0x01, 0x02, 0x80, 0x01, 0x02];
+    let result = DataBlock::decode(&mut BitReader::new(Cursor::new(&data)));
+    assert!(matches!(result, Err(DecodeError::RecordOverrun { offset: 6, block_len: 8 })));
+
+    let mut block = DataBlock::new();
+    let result = block.decode_into(&mut BitReader::new(Cursor::new(&data)), &mut DecodeContext::new());
+    assert!(matches!(result, Err(DecodeError::RecordOverrun { offset: 6, block_len: 8 })));
+}
+
+#[test]
+fn datablock_decode_record_underrun() {
+    use multi_item_record::cat048::*;
+
+    // The only record ends at byte 6, the last two bytes hold none
+    let data = vec![48, 0x00, 0x08, 0x80, 0x01, 0x02, 0x00, 0x00];
+    let result = DataBlock::decode(&mut BitReader::new(Cursor::new(&data)));
+    assert!(matches!(result, Err(DecodeError::RecordUnderrun { offset: 6, block_len: 8 })));
+
+    let mut block = DataBlock::new();
+    let result = block.decode_into(&mut BitReader::new(Cursor::new(&data)), &mut DecodeContext::new());
+    assert!(matches!(result, Err(DecodeError::RecordUnderrun { offset: 6, block_len: 8 })));
+}
+
+#[test]
+fn datablock_decode_tail_padding_accepted_by_policy() {
+    use multi_item_record::cat048::*;
+    use rasterix::rcore::policy::with_policy;
+
+    struct Padded;
+    impl DecodePolicy for Padded {
+        fn on_tail_padding(&self, _: usize, _: usize) -> Result<(), DecodeError> {
+            Ok(())
+        }
+    }
+
+    let data = vec![48, 0x00, 0x08, 0x80, 0x01, 0x02, 0x00, 0x00];
+    let block = with_policy(Padded, || DataBlock::decode(&mut BitReader::new(Cursor::new(&data)))).unwrap();
+    assert_eq!(block.records.len(), 1);
+
+    let empty = Record { item010: None, item020: None, item240: None };
+    let mut block = DataBlock::with_records(vec![empty; 3]);
+    with_policy(Padded, || block.decode_into(&mut BitReader::new(Cursor::new(&data)), &mut DecodeContext::new()))
+        .unwrap();
+    assert_eq!(block.records.len(), 1);
+    assert_eq!(block.records[0].item010, Some(Item010 { sac: 1, sic: 2 }));
+}
+
+// ============================================================================
+// In-place Decode Tests
+// ============================================================================
+
+#[test]
+fn decode_into_overwrites_record() {
+    use multi_item_record::cat048::*;
+
+    let first = Record {
+        item010: Some(Item010 { sac: 1, sic: 2 }),
+        item020: Some(Item020 { typ: 10 }),
+        item240: Some(Item240 { aircraft_id: "BAW123".to_string() }),
+    };
+    let second = Record {
+        item010: None,
+        item020: Some(Item020 { typ: 20 }),
+        item240: Some(Item240 { aircraft_id: "DLH42".to_string() }),
+    };
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        first.encode(&mut writer).unwrap();
+        second.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut ctx = DecodeContext::new();
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let mut record = Record::decode(&mut reader).unwrap();
+    assert_eq!(record, first);
+
+    record.decode_into(&mut reader, &mut ctx).unwrap();
+    assert_eq!(record, second);
+}
+
+#[test]
+fn decode_into_reuses_repetitive_items() {
+    use repetitive_basic::cat001::*;
+
+    let original = Item070 {
+        items: (1..=5).map(|i| Item070Element { azimuth: i * 100 }).collect(),
+    };
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut decoded = Item070 {
+        items: Vec::with_capacity(8),
+    };
+    let ptr = decoded.items.as_ptr();
+
+    let mut ctx = DecodeContext::new();
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    decoded.decode_into(&mut reader, &mut ctx).unwrap();
+
+    assert_eq!(decoded, original);
+    assert_eq!(decoded.items.as_ptr(), ptr);
+}
+
+#[test]
+fn decode_into_extended_clears_absent_parts() {
+    use extended_multi_part::cat048::*;
+
+    let original = Item020 {
+        part0: Item020Part0 { a: 1, b: 2 },
+        part1: None,
+        part2: None,
+        extensions: Vec::new(),
+    };
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut decoded = Item020 {
+        part0: Item020Part0 { a: 7, b: 15 },
+        part1: Some(Item020Part1 { c: 20 }),
+        part2: Some(Item020Part2 { d: 30 }),
+        extensions: Vec::new(),
+    };
+    let mut ctx = DecodeContext::new();
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    decoded.decode_into(&mut reader, &mut ctx).unwrap();
+
+    assert_eq!(decoded, original);
+}
+
+#[test]
+fn decode_into_datablock_replaces_records() {
+    use multi_item_record::cat048::*;
+
+    let large = DataBlock::with_records(vec![
+        Record {
+            item010: Some(Item010 { sac: 1, sic: 2 }),
+            item020: None,
+            item240: None,
+        },
+        Record {
+            item010: Some(Item010 { sac: 3, sic: 4 }),
+            item020: None,
+            item240: None,
+        },
+    ]);
+    let small = DataBlock::with_records(vec![Record {
+        item010: None,
+        item020: Some(Item020 { typ: 5 }),
+        item240: Some(Item240 { aircraft_id: "AFR1".to_string() }),
+    }]);
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        large.encode(&mut writer).unwrap();
+        small.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut ctx = DecodeContext::new();
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let mut block = DataBlock::new();
+
+    block.decode_into(&mut reader, &mut ctx).unwrap();
+    assert_eq!(block, large);
+
+    block.decode_into(&mut reader, &mut ctx).unwrap();
+    assert_eq!(block, small);
+}
+
+// ============================================================================
+// Record Stream Tests
+// ============================================================================
+
+#[test]
+fn record_stream_yields_records_across_blocks() {
+    use multi_item_record::cat048::*;
+
+    let first = DataBlock::with_records(vec![
+        Record {
+            item010: Some(Item010 { sac: 1, sic: 2 }),
+            item020: None,
+            item240: None,
+        },
+        Record {
+            item010: None,
+            item020: Some(Item020 { typ: 3 }),
+            item240: Some(Item240 { aircraft_id: "BAW1".to_string() }),
+        },
+    ]);
+    let second = DataBlock::with_records(vec![Record {
+        item010: Some(Item010 { sac: 4, sic: 5 }),
+        item020: None,
+        item240: None,
+    }]);
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        first.encode(&mut writer).unwrap();
+        second.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let results: Vec<(Header, Record)> = RecordStream::<Record, _>::new(Cursor::new(&buffer))
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(results.len(), 3);
+    assert!(results.iter().all(|(header, _)| header.category == 48));
+    assert_eq!(results[0].1, first.records[0]);
+    assert_eq!(results[1].1, first.records[1]);
+    assert_eq!(results[2].1, second.records[0]);
+}
+
+#[test]
+fn record_stream_reports_record_overrun() {
+    use multi_item_record::cat048::*;
+
+    // LEN=8 cuts the second I048/010 after its SAC
+    let mut data = vec![48, 0x00, 0x08, 0x80, 0x01, 0x02, 0x80, 0x01];
+    data.extend([48, 0x00, 0x06, 0x80, 0x03, 0x04]);
+
+    let mut records = RecordStream::<Record, _>::new(Cursor::new(&data));
+    assert_eq!(records.next().unwrap().unwrap().1.item010, Some(Item010 { sac: 1, sic: 2 }));
+    assert!(matches!(records.next(), Some(Err(DecodeError::RecordOverrun { offset: 6, block_len: 8 }))));
+    assert_eq!(records.next().unwrap().unwrap().1.item010, Some(Item010 { sac: 3, sic: 4 }));
+    assert!(records.next().is_none());
+}
+
+// ============================================================================
+// Item Skipping Tests
+// ============================================================================
+
+#[test]
+fn item_lengths_match_encodings() {
+    use multi_item_record::cat048::*;
+
+    assert_eq!(Item010::min_encoded_len(), 2);
+    assert_eq!(Item010::encoded_len_hint(), Some(2));
+    assert_eq!(Record::ITEM_LENGTHS, [Some(2), Some(1), None, Some(6)]);
+    assert_eq!(extended_multi_part::cat048::Item020::encoded_len_hint(), None);
+    assert_eq!(extended_multi_part::cat048::Item020::min_encoded_len(), 1);
+    assert_eq!(repetitive_basic::cat001::Item070::encoded_len_hint(), Some(10));
+}
+
+#[test]
+fn skip_items_to_decode_one() {
+    use multi_item_record::cat048::*;
+
+    let record = Record {
+        item010: Some(Item010 { sac: 1, sic: 2 }),
+        item020: Some(Item020 { typ: 3 }),
+        item240: Some(Item240 { aircraft_id: "BAW123".to_string() }),
+    };
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        record.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut reader = BitReader::new(Cursor::new(&buffer));
+    let fspec = Fspec::read(&mut reader).unwrap();
+    let mut item020 = None;
+    for frn in 0..Record::ITEM_LENGTHS.len() {
+        if !fspec.is_set(frn / 7, (frn % 7) as u8) {
+            continue;
+        }
+        match frn {
+            1 => item020 = Some(Item020::decode(&mut reader).unwrap()),
+            _ => Record::skip_item(frn, &mut reader).unwrap(),
+        }
+    }
+
+    assert_eq!(item020, Some(Item020 { typ: 3 }));
+    assert_eq!(reader.read_bits(1).unwrap_err().kind(), std::io::ErrorKind::UnexpectedEof);
+}
+
+type SkipItem = fn(usize, &mut BitReader<Cursor<Vec<u8>>>) -> Result<(), DecodeError>;
+
+/// Encodes `record`, then skips every present item with `skip_item` and
+/// checks that the whole record was consumed.
+fn assert_skips_record<T: Encode>(record: &T, skip_item: SkipItem) {
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        record.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut reader = BitReader::new(Cursor::new(buffer));
+    let fspec = Fspec::read(&mut reader).unwrap();
+    for frn in 0..14 {
+        if fspec.is_set(frn / 7, (frn % 7) as u8) {
+            skip_item(frn, &mut reader).unwrap();
+        }
+    }
+
+    assert!(reader.read_bits(8).is_err(), "record not fully skipped");
+}
+
+#[test]
+fn skip_variable_length_items() {
+    {
+        use extended_multi_part::cat048::*;
+        let record = Record {
+            item020: Some(Item020 {
+                part0: Item020Part0 { a: 5, b: 3 },
+                part1: Some(Item020Part1 { c: 17 }),
+                part2: None,
+                extensions: Vec::new(),
+            }),
+        };
+        assert_skips_record(&record, Record::skip_item);
+    }
+    {
+        use compound_simple::cat001::*;
+        let record = Record {
+            item100: Some(Item100 {
+                sub0: Some(Item100Sub0 { flags: 0xA5 }),
+                sub1: Some(Item100Sub1 { data: 0x1234 }),
+            }),
+        };
+        assert_skips_record(&record, Record::skip_item);
+    }
+    {
+        use explicit_item::cat001::*;
+        let record = Record { item060: Some(Item060 { altitude: 1200, speed: 450 }) };
+        assert_skips_record(&record, Record::skip_item);
+    }
+    {
+        use repetitive_basic::cat001::*;
+        let items = (0..5).map(|i| Item070Element { azimuth: i }).collect();
+        assert_skips_record(&Record { item070: Some(Item070 { items }) }, Record::skip_item);
+    }
+}
+
+#[test]
+fn skip_item_rejects_unknown_position() {
+    use multi_item_record::cat048::*;
+
+    let mut reader = BitReader::new(Cursor::new(&[0u8; 4]));
+    assert!(matches!(Record::skip_item(2, &mut reader), Err(DecodeError::InvalidData(_))));
+}
+
+// ============================================================================
+// Lazy Record Tests
+// ============================================================================
+
+#[test]
+fn lazy_records_decode_items_on_access() {
+    use multi_item_record::cat048::*;
+
+    let bytes = test_utils::load_message_fixture("cat048_two_records.hex");
+
+    let first = RecordLazy::parse(&bytes[Header::SIZE..]).unwrap();
+    assert_eq!(first.item020().unwrap(), Some(Item020 { typ: 3 }));
+    assert_eq!(first.item240().unwrap(), None);
+
+    let second = RecordLazy::parse(&bytes[Header::SIZE + first.encoded_len()..]).unwrap();
+    assert_eq!(second.item010().unwrap(), None);
+    assert_eq!(second.item240().unwrap(), Some(Item240 { aircraft_id: "BAW123".to_string() }));
+    assert_eq!(Header::SIZE + first.encoded_len() + second.encoded_len(), bytes.len());
+
+    let mut reader = BitReader::new(Cursor::new(&bytes));
+    let block = DataBlock::decode(&mut reader).unwrap();
+    assert_eq!(first.decode().unwrap(), block.records[0]);
+    assert_eq!(second.decode().unwrap(), block.records[1]);
+}
+
+#[test]
+fn lazy_record_with_compound_item() {
+    use compound_simple::cat001::*;
+
+    let record = Record {
+        item100: Some(Item100 { sub0: None, sub1: Some(Item100Sub1 { data: 0x1234 }) }),
+    };
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        record.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let lazy = RecordLazy::parse(&buffer).unwrap();
+
+    assert_eq!(lazy.encoded_len(), buffer.len());
+    assert_eq!(lazy.item100().unwrap(), record.item100);
+}
+
+#[test]
+fn lazy_record_skips_explicit_items_like_decode() {
+    use explicit_item::cat001::*;
+
+    // The length byte says 3 bytes where I001/060 is defined with 5.
+    let bytes = [0x80, 0x03, 0x04, 0xB0, 0x01, 0xC2];
+
+    let lazy = RecordLazy::parse(&bytes).unwrap();
+    let eager = Record::decode(&mut BitReader::new(Cursor::new(&bytes))).unwrap();
+    assert_eq!(lazy.encoded_len(), bytes.len());
+    assert_eq!(lazy.item060().unwrap(), eager.item060);
+    assert_eq!(lazy.decode().unwrap(), eager);
+
+    struct Strict;
+    impl DecodePolicy for Strict {
+        fn on_length_mismatch(&self, _: usize, _: usize) -> Result<(), DecodeError> {
+            Err(DecodeError::InvalidData("length mismatch"))
+        }
+    }
+    policy::with_policy(Strict, || {
+        assert!(matches!(RecordLazy::parse(&bytes), Err(DecodeError::InvalidData("length mismatch"))));
+        assert!(matches!(
+            Record::decode(&mut BitReader::new(Cursor::new(&bytes))),
+            Err(DecodeError::InvalidData("length mismatch"))
+        ));
+    });
+}
+
+#[test]
+fn lazy_record_truncated() {
+    use multi_item_record::cat048::*;
+
+    // FSPEC announces I048/010 but only one of its two bytes follows.
+    assert!(RecordLazy::parse(&[0x80, 0x01]).is_err());
+}
+
+// ============================================================================
+// Borrowed Decode Tests
+// ============================================================================
+
+#[test]
+fn borrowed_block_matches_owned_decode() {
+    use multi_item_record::cat048::*;
+    use multi_item_record::cat048_ref::*;
+
+    let bytes = test_utils::load_message_fixture("cat048_two_records.hex");
+
+    let mut reader = BitReader::new(bytes.as_slice());
+    let borrowed = DataBlockRef::decode(&mut reader).unwrap();
+
+    let aircraft_id = borrowed.records[1].item240.as_ref().unwrap().aircraft_id;
+    assert_eq!(aircraft_id, "BAW123");
+    assert!(bytes.as_ptr_range().contains(&aircraft_id.as_ptr()));
+    assert_eq!(borrowed.records[0].item010, Some(Item010 { sac: 1, sic: 2 }));
+
+    let mut reader = BitReader::new(Cursor::new(&bytes));
+    assert_eq!(borrowed.into_owned(), DataBlock::decode(&mut reader).unwrap());
+}
+
+#[test]
+fn borrowed_decode_without_strings_uses_owned_items() {
+    use compound_simple::cat001::*;
+    use compound_simple::cat001_ref::*;
+
+    let original = DataBlock::with_records(vec![
+        Record { item100: Some(Item100 { sub0: Some(Item100Sub0 { flags: 7 }), sub1: None }) },
+        Record { item100: None },
+    ]);
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        original.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let mut reader = BitReader::new(buffer.as_slice());
+    let borrowed = DataBlockRef::decode(&mut reader).unwrap();
+
+    assert_eq!(borrowed.records[0].item100, original.records[0].item100);
+    assert_eq!(borrowed.into_owned(), original);
+}
+
+// ============================================================================
+// Hashable Record Tests
+// ============================================================================
+
+#[test]
+fn decoded_records_dedup_in_sets() {
+    use simple_fixed::cat001::*;
+    use std::collections::{BTreeSet, HashSet};
+
+    let record = |sac| Record { item010: Some(Item010 { sac, sic: 1 }) };
+    let block = DataBlock::with_records(vec![record(2), record(1), record(2)]);
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        block.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+    let decoded = DataBlock::decode(&mut BitReader::new(Cursor::new(&buffer))).unwrap();
+
+    let unique: HashSet<_> = decoded.records.iter().cloned().collect();
+    assert_eq!(unique.len(), 2);
+    let ordered: Vec<_> = decoded.records.into_iter().collect::<BTreeSet<_>>().into_iter().collect();
+    assert_eq!(ordered, vec![record(1), record(2)]);
+    assert!(HashSet::from([block]).len() == 1);
+}
+
+#[test]
+fn enums_are_ordered_by_declaration() {
+    use enum_basic::cat001::*;
+
+    assert!(TargetType::Psr < TargetType::Ssr);
+    assert!(TargetType::Ssr < TargetType::Unknown(0));
+}
+
+#[test]
+fn enum_aliases_name_their_variants() {
+    use enum_policies::cat048::*;
+
+    assert_eq!(Typ::None, Typ::NoDetection);
+    assert!(matches!(Typ::SinglePsr, Typ::Psr));
+}
+
+#[test]
+fn enums_apply_their_unknown_values_policy() {
+    use enum_policies::cat048::*;
+
+    let decode = |byte: u8| Item020::decode(&mut BitReader::new(Cursor::new([byte])));
+
+    // typ=5 preserved, sim=0, rdp=1
+    let item = decode(0b1010_0001).unwrap();
+    assert_eq!((item.typ, item.sim, item.rdp), (Typ::Unknown(5), Sim::Actual, Rdp::Chain2));
+
+    // sim=3 has no variant
+    assert!(matches!(decode(0b0001_1000), Err(DecodeError::InvalidData(_))));
+    assert_eq!(Sim::try_from(3), Err(()));
+
+    // rdp=4 decodes as Undefined, and encodes as its value
+    let item = decode(0b0000_1100).unwrap();
+    assert_eq!(item.rdp, Rdp::Undefined);
+    let mut buffer = Vec::new();
+    item.encode(&mut BitWriter::new(&mut buffer)).unwrap();
+    assert_eq!(buffer, [0b0000_1111]);
+}
+
+#[test]
+fn items_describe_their_fields() {
+    use field_descriptions::cat021::*;
+
+    assert_eq!(Item010::describe("sac"), Some("System Area Code"));
+    assert_eq!(Item040::describe("part0.atp"), Some("Address Type"));
+    assert_eq!(Item040::describe("part0.rc"), None);
+    assert_eq!(Item220::describe("sub0.wind_speed"), Some("Wind Speed in knots"));
+    assert_eq!(Item220::describe("wind_speed"), None);
+    assert_eq!(<enum_basic::cat001::Item010 as DataItem>::describe("target_type"), None);
+}
+
+// ============================================================================
+// Profile Decoding Tests
+// ============================================================================
+
+#[test]
+fn decode_exact_reads_records_of_profile() {
+    use multi_item_record::cat048::*;
+
+    let record = Record {
+        item010: Some(Item010 { sac: 1, sic: 2 }),
+        item020: None,
+        item240: Some(Item240 { aircraft_id: "ABC".to_string() }),
+    };
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        record.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    const PLOT: u64 = profile::ITEM010 | profile::ITEM240;
+    let decoded = Record::decode_exact::<PLOT, _>(&mut BitReader::new(Cursor::new(&buffer))).unwrap();
+    assert_eq!(decoded, record);
+
+    let result = Record::decode_exact::<{ profile::ITEM010 }, _>(&mut BitReader::new(Cursor::new(&buffer)));
+    assert!(matches!(result, Err(DecodeError::InvalidData(_))));
+}
+
+// ============================================================================
+// Decode Policy Tests
+// ============================================================================
+
+/// Repairs unknown enum values to 1 and rejects the other anomalies.
+struct RepairOrReject;
+
+impl DecodePolicy for RepairOrReject {
+    fn on_unknown_enum(&self, _: &'static str, _: u8) -> Result<u8, DecodeError> {
+        Ok(1)
+    }
+
+    fn on_nonzero_spare(&self, _: usize, _: u64) -> Result<(), DecodeError> {
+        Err(DecodeError::InvalidData("non-zero spare bits"))
+    }
+
+    fn on_length_mismatch(&self, _: usize, _: usize) -> Result<(), DecodeError> {
+        Err(DecodeError::InvalidData("length mismatch"))
+    }
+}
+
+#[test]
+fn policy_repairs_unknown_enum_values() {
+    use enum_basic::cat001::*;
+
+    let decode = || Item010::decode(&mut BitReader::new(Cursor::new([0b101_00000]))).unwrap();
+
+    assert_eq!(decode().target_type, TargetType::Unknown(5));
+    assert_eq!(policy::with_policy(RepairOrReject, decode).target_type, TargetType::Psr);
+}
+
+#[test]
+fn policy_rejects_nonzero_spare_bits() {
+    use spare_bits::cat001::*;
+
+    let decode = || Item010::decode(&mut BitReader::new(Cursor::new([7, 0x01])));
+
+    assert_eq!(decode().unwrap(), Item010 { data: 7 });
+    assert!(policy::with_policy(RepairOrReject, decode).is_err());
+}
+
+#[test]
+fn policy_rejects_length_mismatch() {
+    use explicit_item::cat001::*;
+
+    let decode = |len| Item060::decode(&mut BitReader::new(Cursor::new([len, 0, 1, 0, 2])));
+
+    assert!(decode(6).is_ok());
+    assert!(policy::with_policy(RepairOrReject, || decode(5)).is_ok());
+    assert!(policy::with_policy(RepairOrReject, || decode(6)).is_err());
+}
+
+// ============================================================================
+// Message Fixture Tests
+// ============================================================================
+
+#[test]
+fn decode_hex_message_fixture() {
+    use multi_item_record::cat048::*;
+
+    let bytes = test_utils::load_message_fixture("cat048_two_records.hex");
+
+    let mut reader = BitReader::new(Cursor::new(&bytes));
+    let block = DataBlock::decode(&mut reader).unwrap();
+
+    assert_eq!(
+        block.records,
+        vec![
+            Record {
+                item010: Some(Item010 { sac: 1, sic: 2 }),
+                item020: Some(Item020 { typ: 3 }),
+                item240: None,
+            },
+            Record {
+                item010: None,
+                item020: None,
+                item240: Some(Item240 { aircraft_id: "BAW123".to_string() }),
+            },
+        ]
+    );
+}
+
+/// Field values of a `cat048_subset` record, for comparison with reference
+/// decoders and recording expectations.
+///
+/// Only raw integer fields are listed: reference decoders report scaled
+/// values (ToD in seconds) and their own names for some fields.
+fn cat048_subset_values(record: &cat048_subset::cat048::Record) -> test_utils::reference::FieldValues {
+    use test_utils::reference::{field_key, FieldValues};
+
+    let mut values = FieldValues::new();
+    if let Some(item) = &record.item010 {
+        values.insert(field_key("010", "sac"), item.sac.to_string());
+        values.insert(field_key("010", "sic"), item.sic.to_string());
+    }
+    if let Some(item) = &record.item020 {
+        let part0 = &item.part0;
+        values.insert(field_key("020", "typ"), part0.typ.to_string());
+        values.insert(field_key("020", "sim"), part0.sim.to_string());
+        values.insert(field_key("020", "rdp"), part0.rdp.to_string());
+        values.insert(field_key("020", "spi"), part0.spi.to_string());
+        values.insert(field_key("020", "rab"), part0.rab.to_string());
+        if let Some(part1) = &item.part1 {
+            values.insert(field_key("020", "tst"), part1.tst.to_string());
+            values.insert(field_key("020", "err"), part1.err.to_string());
+            values.insert(field_key("020", "xpp"), part1.xpp.to_string());
+            values.insert(field_key("020", "me"), part1.me.to_string());
+            values.insert(field_key("020", "mi"), part1.mi.to_string());
+        }
+    }
+    if let Some(item) = &record.item161 {
+        values.insert(field_key("161", "trn"), item.trn.to_string());
+    }
+    values
+}
+
+#[test]
+fn differential_hex_message_fixture() {
+    use cat048_subset::cat048::*;
+    use test_utils::reference::assert_matches_reference;
+
+    let bytes = test_utils::load_message_fixture("cat048_subset.hex");
+
+    let mut reader = BitReader::new(Cursor::new(&bytes));
+    let block = DataBlock::decode(&mut reader).unwrap();
+
+    assert_eq!(block.records.len(), 2);
+    assert_eq!(block.records[0].item140.as_ref().unwrap().tod, 0x356a4d);
+    assert_eq!(block.records[0].item161.as_ref().unwrap().trn, 300);
+    let item020 = block.records[1].item020.as_ref().unwrap();
+    assert_eq!((item020.part0.typ, item020.part0.sim), (2, 1));
+    assert!(item020.part1.is_none());
+
+    let ours: Vec<_> = block.records.iter().map(cat048_subset_values).collect();
+
+    assert_matches_reference(&[&bytes], &ours);
+}
+
+// ============================================================================
+// Recording Replay Tests
+// ============================================================================
+
+/// Decodes every data block in `data`, re-encoding each one bit-exactly.
+fn replay_blocks<B: Decode + Encode>(name: &str, data: &[u8]) -> Vec<B> {
+    let mut blocks = Vec::new();
+    let mut offset = 0;
+
+    while offset < data.len() {
+        let header: [u8; Header::SIZE] = data
+            .get(offset..offset + Header::SIZE)
+            .and_then(|h| h.try_into().ok())
+            .unwrap_or_else(|| panic!("{}: truncated header at byte {}", name, offset));
+        let length = (Header::from_bytes(header).length as usize).max(Header::SIZE);
+        let bytes = data
+            .get(offset..offset + length)
+            .unwrap_or_else(|| panic!("{}: truncated block at byte {}", name, offset));
+
+        let mut reader = BitReader::new(Cursor::new(bytes));
+        let block = B::decode(&mut reader)
+            .unwrap_or_else(|e| panic!("{}: failed to decode block at byte {}: {:?}", name, offset, e));
+        roundtrip::assert_bytes_roundtrip::<B>(bytes);
+
+        blocks.push(block);
+        offset += length;
+    }
+
+    blocks
+}
+
+#[test]
+fn replay_recordings() {
+    for recording in test_utils::recordings::load_recordings() {
+        let ours: Vec<_> = match recording.definition.as_str() {
+            "cat048_subset" => replay_blocks::<cat048_subset::cat048::DataBlock>(&recording.name, &recording.data)
+                .iter()
+                .flat_map(|block| block.records.iter().map(cat048_subset_values))
+                .collect(),
+            other => panic!("{}: no generated code for definition '{}'", recording.name, other),
+        };
+
+        let mismatches = recording.mismatches(&ours);
+        assert!(
+            mismatches.is_empty(),
+            "{}: decoded values differ from recording:\n  {}",
+            recording.name,
+            mismatches.join("\n  ")
+        );
+    }
+}
+
+// ============================================================================
+// Dynamic Decoder Tests
+// ============================================================================
+
+/// Encodes `block` with generated code and checks that the dynamic decoder
+/// for the same definition re-encodes it bit-exactly.
+fn assert_dynamic_lossless<B: Encode>(fixture: &str, block: &B) {
+    use rasterix_codegen::dynamic::{verify_blocks, DynamicDecoder};
+    use rasterix_codegen::parse::parser::parse_category;
+    use rasterix_codegen::transform::transformer::to_ir;
+
+    let mut buffer = Vec::new();
+    {
+        let mut writer = BitWriter::new(&mut buffer);
+        block.encode(&mut writer).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let xml = test_utils::load_fixture("valid", &format!("{}.xml", fixture));
+    let decoder = DynamicDecoder::from_ir(&to_ir(parse_category(&xml).unwrap()).unwrap()).unwrap();
+    let report = verify_blocks(&decoder, &buffer);
+    assert!(report.is_lossless(), "{}:\n{}", fixture, report);
+}
+
+#[test]
+fn dynamic_decoder_matches_generated_code() {
+    {
+        use extended_multi_part::cat048::*;
+        assert_dynamic_lossless("extended_multi_part", &DataBlock::with_records(vec![
+            Record {
+                item020: Some(Item020 {
+                    part0: Item020Part0 { a: 5, b: 3 },
+                    part1: Some(Item020Part1 { c: 17 }),
+                    part2: Some(Item020Part2 { d: 9 }),
+                    extensions: Vec::new(),
+                }),
+            },
+            Record {
+                item020: Some(Item020 { part0: Item020Part0 { a: 1, b: 0 }, part1: None, part2: None, extensions: Vec::new() }),
+            },
+        ]));
+    }
+    {
+        use compound_simple::cat001::*;
+        assert_dynamic_lossless("compound_simple", &DataBlock::with_records(vec![
+            Record { item100: Some(Item100 { sub0: None, sub1: Some(Item100Sub1 { data: 0x1234 }) }) },
+            // An empty record can't end a block, its FSPEC is a zero byte
+            Record { item100: None },
+            Record { item100: Some(Item100 { sub0: Some(Item100Sub0 { flags: 0xA5 }), sub1: None }) },
+        ]));
+    }
+    {
+        use repetitive_basic::cat001::*;
+        let items = (0..5).map(|i| Item070Element { azimuth: i * 1000 }).collect();
+        assert_dynamic_lossless("repetitive_basic", &DataBlock::with_records(vec![
+            Record { item070: Some(Item070 { items }) },
+        ]));
+    }
+    {
+        use epb_field::cat001::*;
+        assert_dynamic_lossless("epb_field", &DataBlock::with_records(vec![
+            Record { item010: Some(Item010 { optional_value: Some(300) }) },
+            Record { item010: Some(Item010 { optional_value: None }) },
+        ]));
+    }
+}
+
+#[test]
+fn dynamic_decoder_verifies_hex_message_fixture() {
+    use rasterix_codegen::dynamic::{verify_blocks, DynamicDecoder};
+    use rasterix_codegen::parse::parser::parse_category;
+    use rasterix_codegen::transform::transformer::to_ir;
+
+    let bytes = test_utils::load_message_fixture("cat048_two_records.hex");
+    let xml = test_utils::load_fixture("valid", "multi_item_record.xml");
+    let decoder = DynamicDecoder::from_ir(&to_ir(parse_category(&xml).unwrap()).unwrap()).unwrap();
+
+    let report = verify_blocks(&decoder, &bytes);
+
+    assert_eq!(report.blocks.len(), 1);
+    assert!(report.is_lossless(), "{}", report);
+}
+
+// ============================================================================
+// Differential Generator Tests
+// ============================================================================
+
+/// Number of synthetic blocks fed to both decoders per fixture.
+const DIFFERENTIAL_BLOCKS: usize = 20;
+
+/// Outcome of decoding a block: the values of its records, or the failure.
+fn decode_outcome<B: Decode>(bytes: &[u8], records: fn(&B) -> Vec<ValueMap>) -> Result<Vec<serde_json::Value>, DecodeError> {
+    B::decode(&mut BitReader::new(Cursor::new(bytes)))
+        .map(|block| records(&block).iter().map(|record| value_to_json(&Value::Map(record.clone()))).collect())
+}
+
+/// Outcome of decoding a block with the dynamic decoder, in the same form.
+fn dynamic_outcome(
+    decoder: &rasterix_codegen::dynamic::DynamicDecoder,
+    bytes: &[u8],
+) -> Result<Vec<serde_json::Value>, DecodeError> {
+    use rasterix_codegen::dynamic::json::record_to_json;
+
+    let block = decoder.decode_block(&mut BitReader::new(Cursor::new(bytes)))?;
+    Ok(block.records.iter().map(|record| normalize_floats(record_to_json(decoder, record))).collect())
+}
+
+/// Converts the generic values of a generated record into the JSON the
+/// dynamic decoder writes for it.
+fn value_to_json(value: &Value) -> serde_json::Value {
+    use serde_json::Value as Json;
+
+    match value {
+        Value::Null => Json::Null,
+        Value::Number(n) => u64::try_from(*n).map_or_else(|_| Json::from(n.to_string()), Json::from),
+        Value::Float(f) => normalize_floats(serde_json::Number::from_f64(*f).map_or(Json::Null, Json::Number)),
+        Value::Text(s) => Json::from(s.as_str()),
+        Value::Bytes(bytes) => Json::from(bytes.clone()),
+        Value::List(values) => Json::Array(values.iter().map(value_to_json).collect()),
+        Value::Map(map) => Json::Object(map.iter().map(|(key, value)| (key.clone(), value_to_json(value))).collect()),
+    }
+}
+
+/// Rounds every float in `json` to `f32` precision: the generic values of
+/// an `f32` field are widened to `f64`, while the dynamic decoder writes
+/// its shortest decimal.
+fn normalize_floats(json: serde_json::Value) -> serde_json::Value {
+    use serde_json::Value as Json;
+
+    match json {
+        Json::Number(n) if n.is_f64() => {
+            let rounded = n.as_f64().unwrap() as f32;
+            rounded.to_string().parse::<f64>().ok().and_then(serde_json::Number::from_f64).map_or(Json::Null, Json::Number)
+        }
+        Json::Array(values) => Json::Array(values.into_iter().map(normalize_floats).collect()),
+        Json::Object(map) => Json::Object(map.into_iter().map(|(key, value)| (key, normalize_floats(value))).collect()),
+        other => other,
+    }
+}
+
+/// Feeds synthetic blocks of `fixture`, and every truncation of them, to
+/// the code generated from it and to the dynamic decoder, failing where
+/// they disagree on whether a block decodes, on the value of any field or
+/// on its re-encoding.
+fn assert_decoders_agree<B: Decode + Encode>(fixture: &str, records: fn(&B) -> Vec<ValueMap>) {
+    use rasterix_codegen::dynamic::generator::{TrafficConfig, TrafficGenerator};
+    use rasterix_codegen::dynamic::DynamicDecoder;
+    use rasterix_codegen::parse::parser::parse_category;
+    use rasterix_codegen::transform::transformer::to_ir;
+
+    let xml = test_utils::load_fixture("valid", &format!("{}.xml", fixture));
+    let decoder = DynamicDecoder::from_ir(&to_ir(parse_category(&xml).unwrap()).unwrap()).unwrap();
+    let config = TrafficConfig { records_per_block: 3, ..TrafficConfig::with_seed(7) };
+    let blocks = TrafficGenerator::new(decoder.clone(), config).take(DIFFERENTIAL_BLOCKS);
+
+    for (index, block) in blocks.enumerate() {
+        let bytes = block.data;
+        let dynamic = dynamic_outcome(&decoder, &bytes).unwrap();
+        let decoded = decode_outcome::<B>(&bytes, records)
+            .unwrap_or_else(|e| panic!("{} block {}: generated code failed: {}\n{}", fixture, index, e, hex(&bytes)));
+        assert_eq!(decoded, dynamic, "{} block {}: record values\n{}", fixture, index, hex(&bytes));
+        roundtrip::assert_bytes_roundtrip::<B>(&bytes);
+
+        // The same block cut short, with its LEN field patched to match.
+        for len in Header::SIZE..bytes.len() {
+            let mut cut = bytes[..len].to_vec();
+            cut[1..3].copy_from_slice(&(len as u16).to_be_bytes());
+            let generated = decode_outcome::<B>(&cut, records);
+            let dynamic = dynamic_outcome(&decoder, &cut);
+            assert_eq!(
+                generated.as_ref().ok(),
+                dynamic.as_ref().ok(),
+                "{} block {} cut to {} bytes: generated {:?}, dynamic {:?}\n{}",
+                fixture,
+                index,
+                len,
+                generated,
+                dynamic,
+                hex(&cut)
+            );
+            if generated.is_ok() {
+                roundtrip::assert_bytes_roundtrip::<B>(&cut);
+            }
+        }
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    rasterix::rcore::hex::encode_spaced(bytes)
+}
+
+/// Runs [`assert_decoders_agree`] on the data block of each generated
+/// module.
+macro_rules! assert_all_decoders_agree {
+    ($($fixture:ident::$cat:ident),* $(,)?) => {
+        $(assert_decoders_agree::<$fixture::$cat::DataBlock>(stringify!($fixture), |block| {
+            block.records.iter().map(|record| record.to_value_map()).collect()
+        });)*
+    };
+}
+
+#[test]
+fn generated_code_agrees_with_dynamic_decoder() {
+    // Every module built by build.rs, but `repetitive_heapless`, which is
+    // `repetitive_basic` with other storage.
+    assert_all_decoders_agree!(
+        simple_fixed::cat001,
+        multi_item_record::cat048,
+        cat048_subset::cat048,
+        extended_multi_part::cat048,
+        enum_basic::cat001,
+        compound_simple::cat001,
+        repetitive_basic::cat001,
+        epb_field::cat001,
+        explicit_item::cat001,
+        spare_bits::cat001,
+        high_item_ids::cat062,
+        named_items::cat048,
+        field_revisions::cat034,
+        enum_policies::cat048,
+        field_descriptions::cat021,
+        aligned_fields::cat001,
+        derived_fields::cat062,
+        field_constraints::cat048,
+        geo_positions::cat048,
+        altitude_fields::cat062,
+        velocity_fields::cat062,
+        icao_fields::cat048,
+        item_examples::cat048,
+        reserved_items::cat048,
+        little_endian_fields::cat240,
+        float_fields::cat250,
+        byte_fields::cat048,
+        conformance_rules::cat048,
+    );
+}
@@ -0,0 +1,67 @@
+use std::path::Path;
+use std::process::ExitCode;
+
+use rasterix_codegen::dynamic::anonymize::Anonymizer;
+use rasterix_core::Timestamped;
+
+use crate::definitions;
+use crate::formats;
+
+const USAGE: &str = "\
+usage: rasterix anonymize <input> <output> --key <secret> (--xml <definition.xml> | --xml-dir <dir>)...
+                          [--mode3a-field <name>,...]";
+
+/// `rasterix anonymize <input> <output> --key <secret> (--xml <definition.xml> | --xml-dir <dir>)... [--mode3a-field <name>,...]`
+///
+/// Replaces the callsigns, Mode S addresses and Mode 3/A codes in the
+/// records of `input` with pseudonyms derived from `--key`, and writes the
+/// re-encoded blocks to `output`.  Blocks of categories without a
+/// definition are dropped, since their identities can't be found.  Fails
+/// if a block doesn't decode.
+pub fn run(args: &[String]) -> Result<ExitCode, String> {
+    let mut paths = Vec::new();
+    let mut files = Vec::new();
+    let mut dirs = Vec::new();
+    let mut key = None;
+    let mut mode3a_fields = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value\n{}", arg, USAGE));
+        match arg.as_str() {
+            "--xml" => files.push(value()?.clone()),
+            "--xml-dir" => dirs.push(value()?.clone()),
+            "--key" => key = Some(value()?.clone()),
+            "--mode3a-field" => mode3a_fields.extend(value()?.split(',').map(str::to_string)),
+            _ if !arg.starts_with('-') => paths.push(arg),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    let ([input, output], Some(key)) = (&paths[..], key) else {
+        return Err(USAGE.to_string());
+    };
+    let (input, output) = (Path::new(input), Path::new(output));
+
+    let decoders = definitions::load(&files, &dirs)?;
+    let mut anonymizer = Anonymizer::new(key.as_bytes());
+    if !mode3a_fields.is_empty() {
+        anonymizer = anonymizer.with_mode3a_fields(mode3a_fields);
+    }
+
+    let blocks = formats::read(input).map_err(|e| format!("{}: {}", input.display(), e))?;
+    let total = blocks.len();
+    let mut anonymized = Vec::with_capacity(total);
+    for (index, block) in blocks.into_iter().enumerate() {
+        let Some(decoder) = block.data.first().and_then(|category| decoders.get(category)) else {
+            continue;
+        };
+        let data = anonymizer
+            .anonymize_block(decoder, &block.data)
+            .map_err(|e| format!("{}: block {}: {}", input.display(), index, e))?;
+        anonymized.push(Timestamped::new(block.recv_time, data));
+    }
+    formats::write(output, &anonymized).map_err(|e| format!("{}: {}", output.display(), e))?;
+
+    println!("{} block(s) anonymized, {} dropped without a definition", anonymized.len(), total - anonymized.len());
+    Ok(ExitCode::SUCCESS)
+}
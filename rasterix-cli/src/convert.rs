@@ -0,0 +1,22 @@
+use std::path::Path;
+use std::process::ExitCode;
+
+use crate::formats;
+
+/// `rasterix convert <input> <output>`
+///
+/// Converts data blocks between pcap captures, recordings and concatenated
+/// blocks, choosing the formats by extension.  Blocks read without a time,
+/// from concatenated blocks, are given the Unix epoch.
+pub fn run(args: &[String]) -> Result<ExitCode, String> {
+    let [input, output] = args else {
+        return Err("usage: rasterix convert <input> <output>".to_string());
+    };
+    let (input, output) = (Path::new(input), Path::new(output));
+
+    let blocks = formats::read(input).map_err(|e| format!("{}: {}", input.display(), e))?;
+    formats::write(output, &blocks).map_err(|e| format!("{}: {}", output.display(), e))?;
+
+    println!("{} block(s) converted", blocks.len());
+    Ok(ExitCode::SUCCESS)
+}
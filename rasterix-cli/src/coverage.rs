@@ -0,0 +1,71 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::ExitCode;
+
+use rasterix_codegen::dynamic::coverage::Coverage;
+use rasterix_core::BitReader;
+
+use crate::definitions;
+use crate::formats;
+
+const USAGE: &str = "\
+usage: rasterix coverage <input> (--xml <definition.xml> | --xml-dir <dir>)... [--deny-unobserved]";
+
+/// `rasterix coverage <input> (--xml <definition.xml> | --xml-dir <dir>)... [--deny-unobserved]`
+///
+/// Decodes the blocks of `input` and reports, for each category it holds,
+/// the items, optional parts, sub-items and enum variants of the definition
+/// that no record holds.  Blocks of categories without a definition, and
+/// blocks that don't decode, are skipped with a warning.  With
+/// `--deny-unobserved`, fails unless every category is fully covered.
+pub fn run(args: &[String]) -> Result<ExitCode, String> {
+    let mut inputs = Vec::new();
+    let mut files = Vec::new();
+    let mut dirs = Vec::new();
+    let mut deny_unobserved = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value\n{}", arg, USAGE));
+        match arg.as_str() {
+            "--xml" => files.push(value()?.clone()),
+            "--xml-dir" => dirs.push(value()?.clone()),
+            "--deny-unobserved" => deny_unobserved = true,
+            _ if !arg.starts_with('-') => inputs.push(arg),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    let [input] = inputs[..] else {
+        return Err(USAGE.to_string());
+    };
+    let input = Path::new(input);
+
+    let decoders = definitions::load(&files, &dirs)?;
+    let blocks = formats::read(input).map_err(|e| format!("{}: {}", input.display(), e))?;
+
+    let mut coverages = BTreeMap::new();
+    for (index, block) in blocks.iter().enumerate() {
+        let category = block.data[0];
+        let Some(decoder) = decoders.get(&category) else {
+            eprintln!("warning: {}: no definition for category {} of block {}", input.display(), category, index);
+            continue;
+        };
+        match decoder.decode_block(&mut BitReader::new(&block.data[..])) {
+            Ok(decoded) => coverages
+                .entry(category)
+                .or_insert_with(|| Coverage::new(decoder))
+                .add_block(decoder, &decoded),
+            Err(e) => eprintln!("warning: {}: block {}: {}", input.display(), index, e),
+        }
+    }
+
+    for coverage in coverages.values() {
+        println!("{}", coverage);
+    }
+    if coverages.is_empty() {
+        println!("no records of a defined category");
+    }
+
+    let complete = coverages.values().all(Coverage::is_complete);
+    Ok(if deny_unobserved && !complete { ExitCode::FAILURE } else { ExitCode::SUCCESS })
+}
@@ -0,0 +1,44 @@
+use std::collections::BTreeMap;
+use std::fs;
+
+use rasterix_codegen::builder::RustBuilder;
+use rasterix_codegen::dynamic::DynamicDecoder;
+
+/// Dynamic decoders for a set of definitions, by category.
+pub type Decoders = BTreeMap<u8, DynamicDecoder>;
+
+/// Loads the definitions in `files` and the `.xml` files in `dirs`.
+///
+/// Fails if two definitions are for the same category, or if there are
+/// none.
+pub fn load(files: &[String], dirs: &[String]) -> Result<Decoders, String> {
+    let mut paths: Vec<String> = files.to_vec();
+    for dir in dirs {
+        let entries = fs::read_dir(dir).map_err(|e| format!("{}: {}", dir, e))?;
+        let mut xml: Vec<String> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().and_then(|s| s.to_str()) == Some("xml"))
+            .filter_map(|path| path.to_str().map(str::to_string))
+            .collect();
+        xml.sort();
+        paths.extend(xml);
+    }
+
+    let builder = RustBuilder::new();
+    let mut decoders = Decoders::new();
+    let mut sources = BTreeMap::new();
+    for path in &paths {
+        let decoder = builder.dynamic_decoder(path).map_err(|e| e.to_string())?;
+        let category = decoder.lowered().category_id;
+        if let Some(previous) = sources.insert(category, path) {
+            return Err(format!("{} and {} both define category {}", previous, path, category));
+        }
+        decoders.insert(category, decoder);
+    }
+
+    if decoders.is_empty() {
+        return Err(format!("no definitions found in {}", dirs.join(", ")));
+    }
+    Ok(decoders)
+}
+
@@ -0,0 +1,200 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::{Duration, SystemTime};
+
+use rasterix_codegen::dynamic::json;
+use rasterix_core::BitReader;
+use serde_json::Value;
+
+use crate::definitions::{self, Decoders};
+use crate::formats;
+
+const USAGE: &str = "\
+usage: rasterix diff <a> <b> (--xml <definition.xml> | --xml-dir <dir>)...
+                     [--key <field>,...] [--max-skew <ms>]";
+
+/// `rasterix diff <a> <b> (--xml <definition.xml> | --xml-dir <dir>)... [--key <field>,...] [--max-skew <ms>]`
+///
+/// Decodes two files of data blocks and reports the fields that differ
+/// between matching records.  Records of a category are matched in order
+/// among those with the same key: the values of the `--key` fields, given
+/// as dotted paths (`item010.sac`, or `item010` for all its fields) or as
+/// `sacsic` (I010) and `track` (the track number item of CAT048 and
+/// CAT062).  With `--max-skew`, records received further apart don't
+/// match.  Fails if any record differs or has no match.
+pub fn run(args: &[String]) -> Result<ExitCode, String> {
+    let mut inputs = Vec::new();
+    let mut files = Vec::new();
+    let mut dirs = Vec::new();
+    let mut keys = Vec::new();
+    let mut max_skew = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value\n{}", arg, USAGE));
+        match arg.as_str() {
+            "--xml" => files.push(value()?.clone()),
+            "--xml-dir" => dirs.push(value()?.clone()),
+            "--key" => keys.extend(value()?.split(',').map(str::to_string)),
+            "--max-skew" => {
+                let ms = value()?;
+                max_skew = Some(Duration::from_millis(ms.parse().map_err(|_| format!("invalid skew `{}`", ms))?));
+            }
+            _ if !arg.starts_with('-') => inputs.push(arg),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    let [a, b] = inputs[..] else {
+        return Err(USAGE.to_string());
+    };
+
+    let decoders = definitions::load(&files, &dirs)?;
+    let a_records = decode(&decoders, a, &keys)?;
+    let b_records = decode(&decoders, b, &keys)?;
+
+    // Candidates of each key in B, in order.
+    let mut candidates: HashMap<(u8, &str), VecDeque<usize>> = HashMap::new();
+    for (i, record) in b_records.iter().enumerate() {
+        candidates.entry((record.category, &record.key)).or_default().push_back(i);
+    }
+
+    let mut matched = vec![false; b_records.len()];
+    let (mut compared, mut differing, mut only_a) = (0, 0, 0);
+    for record in &a_records {
+        let queue = candidates.get_mut(&(record.category, record.key.as_str()));
+        let position = queue.as_ref().and_then(|queue| {
+            queue.iter().position(|&i| max_skew.is_none_or(|skew| skew_between(record.time, b_records[i].time) <= skew))
+        });
+        let Some(other) = queue.zip(position).and_then(|(queue, position)| queue.remove(position)) else {
+            println!("only in {}: {}", a, record);
+            only_a += 1;
+            continue;
+        };
+        matched[other] = true;
+        compared += 1;
+
+        let differences = differences(&record.fields, &b_records[other].fields);
+        if !differences.is_empty() {
+            differing += 1;
+            println!("{} / {}:", record, b_records[other]);
+            for (path, left, right) in differences {
+                println!("  {}: {} != {}", path, left, right);
+            }
+        }
+    }
+
+    let mut only_b = 0;
+    for (record, _) in b_records.iter().zip(&matched).filter(|(_, matched)| !**matched) {
+        println!("only in {}: {}", b, record);
+        only_b += 1;
+    }
+
+    println!(
+        "{} record(s) compared, {} differ, {} only in {}, {} only in {}",
+        compared, differing, only_a, a, only_b, b
+    );
+    Ok(if differing + only_a + only_b == 0 { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
+
+/// A decoded record with its flattened field values.
+struct Record {
+    /// Position of the record in its file.
+    index: usize,
+    time: SystemTime,
+    category: u8,
+    /// Values of the key fields.
+    key: String,
+    /// Value of each field by dotted path.
+    fields: BTreeMap<String, String>,
+}
+
+impl std::fmt::Display for Record {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "record {} (cat {}", self.index, self.category)?;
+        if !self.key.is_empty() {
+            write!(f, ", {}", self.key)?;
+        }
+        write!(f, ")")
+    }
+}
+
+fn decode(decoders: &Decoders, path: &str, keys: &[String]) -> Result<Vec<Record>, String> {
+    let blocks = formats::read(Path::new(path)).map_err(|e| format!("{}: {}", path, e))?;
+
+    let mut records = Vec::new();
+    for (i, block) in blocks.iter().enumerate() {
+        let category = block.data[0];
+        let Some(decoder) = decoders.get(&category) else {
+            eprintln!("warning: {}: no definition for category {} of block {}", path, category, i);
+            continue;
+        };
+        let decoded = match decoder.decode_block(&mut BitReader::new(&block.data[..])) {
+            Ok(decoded) => decoded,
+            Err(e) => {
+                eprintln!("warning: {}: block {}: {}", path, i, e);
+                continue;
+            }
+        };
+
+        for record in &decoded.records {
+            let mut fields = BTreeMap::new();
+            flatten("", &json::record_to_json(decoder, record), &mut fields);
+            let key = keys
+                .iter()
+                .flat_map(|key| key_paths(key, category))
+                .flat_map(|key| {
+                    fields
+                        .iter()
+                        .filter(move |(path, _)| **path == key || path.starts_with(&format!("{}.", key)))
+                        .map(|(path, value)| format!("{}={}", path, value))
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            records.push(Record { index: records.len(), time: block.recv_time, category, key, fields });
+        }
+    }
+    Ok(records)
+}
+
+/// Resolves the key names that stand for items of common categories.
+fn key_paths(key: &str, category: u8) -> Vec<String> {
+    match (key, category) {
+        ("sacsic", _) => vec!["item010".to_string()],
+        ("track", 48) => vec!["item161".to_string()],
+        ("track", 62) => vec!["item040".to_string()],
+        _ => vec![key.to_string()],
+    }
+}
+
+/// Collects the scalar values of `json` by dotted path.  Array elements are
+/// named by index.
+fn flatten(path: &str, json: &Value, out: &mut BTreeMap<String, String>) {
+    let child = |name: &str| if path.is_empty() { name.to_string() } else { format!("{}.{}", path, name) };
+    match json {
+        Value::Object(object) => object.iter().for_each(|(name, value)| flatten(&child(name), value, out)),
+        Value::Array(values) => values.iter().enumerate().for_each(|(i, value)| flatten(&child(&i.to_string()), value, out)),
+        value => {
+            out.insert(path.to_string(), value.to_string());
+        }
+    }
+}
+
+/// Returns each path whose value differs, with both values; `absent` for a
+/// field only one side has.
+fn differences(a: &BTreeMap<String, String>, b: &BTreeMap<String, String>) -> Vec<(String, String, String)> {
+    let mut paths: Vec<&String> = a.keys().chain(b.keys()).collect();
+    paths.sort();
+    paths.dedup();
+
+    let value = |map: &BTreeMap<String, String>, path: &String| map.get(path).cloned().unwrap_or_else(|| "absent".to_string());
+    paths
+        .into_iter()
+        .filter(|path| a.get(*path) != b.get(*path))
+        .map(|path| (path.clone(), value(a, path), value(b, path)))
+        .collect()
+}
+
+fn skew_between(a: SystemTime, b: SystemTime) -> Duration {
+    a.duration_since(b).or_else(|_| b.duration_since(a)).unwrap_or_default()
+}
@@ -0,0 +1,60 @@
+use std::fs;
+use std::process::ExitCode;
+
+use rasterix_codegen::builder::RustBuilder;
+use rasterix_codegen::dynamic::json;
+use rasterix_core::{hex, BitWriter};
+
+const USAGE: &str = "usage: rasterix encode [--cat <id>] --xml <definition.xml> [--output <file>] <records.json>";
+
+/// `rasterix encode [--cat <id>] --xml <definition.xml> [--output <file>] <records.json>`
+///
+/// Encodes a data block from a JSON record, or an array of records, shaped
+/// like the JSON written by generated code.  Prints the block as hex, or
+/// writes it as binary to `--output`.
+pub fn run(args: &[String]) -> Result<ExitCode, String> {
+    let mut category = None;
+    let mut definition = None;
+    let mut output = None;
+    let mut input = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value\n{}", arg, USAGE));
+        match arg.as_str() {
+            "--cat" => {
+                let id = value()?;
+                category = Some(id.parse::<u8>().map_err(|_| format!("invalid category `{}`", id))?);
+            }
+            "--xml" => definition = Some(value()?),
+            "-o" | "--output" => output = Some(value()?),
+            _ if input.is_none() && !arg.starts_with('-') => input = Some(arg),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    let (Some(definition), Some(input)) = (definition, input) else {
+        return Err(USAGE.to_string());
+    };
+
+    let decoder = RustBuilder::new().dynamic_decoder(definition).map_err(|e| e.to_string())?;
+    let category_id = decoder.lowered().category_id;
+    if let Some(category) = category.filter(|&category| category != category_id) {
+        return Err(format!("{} defines category {}, not {}", definition, category_id, category));
+    }
+
+    let text = fs::read_to_string(input).map_err(|e| format!("{}: {}", input, e))?;
+    let block = json::parse_block(&decoder, &text).map_err(|e| format!("{}: {}", input, e))?;
+
+    let mut bytes = Vec::new();
+    let mut writer = BitWriter::new(&mut bytes);
+    decoder
+        .encode_block(&block, &mut writer)
+        .and_then(|()| Ok(writer.flush()?))
+        .map_err(|e| format!("encoding: {}", e))?;
+
+    match output {
+        Some(path) => fs::write(path, &bytes).map_err(|e| format!("{}: {}", path, e))?,
+        None => println!("{}", hex::encode_spaced(&bytes)),
+    }
+    Ok(ExitCode::SUCCESS)
+}
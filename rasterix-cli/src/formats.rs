@@ -0,0 +1,102 @@
+//! Files of data blocks, in the formats chosen by extension.
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::SystemTime;
+
+use rasterix_core::recording::{RecordingReader, RecordingWriter};
+use rasterix_core::{base64, hex, Timestamped};
+
+use crate::{pcap, samples};
+
+/// Format of a file, chosen by its extension.
+enum Format {
+    /// Classic pcap capture of UDP packets.
+    Pcap,
+    /// Timestamped recording (`.rxr`).
+    Recording,
+    /// Timestamped recording compressed with zstd (`.zst`, as in
+    /// `.rxr.zst`).
+    #[cfg(feature = "zstd")]
+    CompressedRecording,
+    /// Hex text, one block per line when written.
+    Hex,
+    /// Base64 text, on one line when written.
+    Base64,
+    /// Concatenated data blocks.
+    Raw,
+}
+
+impl Format {
+    fn of(path: &Path) -> Self {
+        match path.extension().and_then(|s| s.to_str()) {
+            Some("pcap") => Format::Pcap,
+            Some("rxr") => Format::Recording,
+            #[cfg(feature = "zstd")]
+            Some("zst") => Format::CompressedRecording,
+            Some("hex") => Format::Hex,
+            Some("b64") => Format::Base64,
+            _ => Format::Raw,
+        }
+    }
+}
+
+/// Reads the data blocks of a file with their receive times.  Blocks read
+/// without a time, from concatenated blocks, are given the Unix epoch.
+pub fn read(path: &Path) -> Result<Vec<Timestamped<Vec<u8>>>, String> {
+    match Format::of(path) {
+        Format::Pcap => {
+            let capture = pcap::read(&fs::read(path).map_err(|e| e.to_string())?)?;
+            if capture.skipped > 0 {
+                eprintln!("warning: skipped {} packet(s) without UDP payload", capture.skipped);
+            }
+            Ok(capture
+                .datagrams
+                .iter()
+                .flat_map(|datagram| {
+                    samples::blocks(&datagram.data).map(|block| Timestamped::new(datagram.recv_time, block.to_vec()))
+                })
+                .collect())
+        }
+        #[cfg(not(feature = "zstd"))]
+        Format::Recording => RecordingReader::new(File::open(path).map_err(|e| e.to_string())?)
+            .and_then(|reader| reader.collect())
+            .map_err(|e| e.to_string()),
+        // Compressed or not, whatever the extension.
+        #[cfg(feature = "zstd")]
+        Format::Recording | Format::CompressedRecording => {
+            RecordingReader::decompressing(File::open(path).map_err(|e| e.to_string())?)
+                .and_then(|reader| reader.collect())
+                .map_err(|e| e.to_string())
+        }
+        Format::Hex | Format::Base64 | Format::Raw => {
+            let bytes = samples::load(path)?;
+            Ok(samples::blocks(&bytes).map(|block| Timestamped::new(SystemTime::UNIX_EPOCH, block.to_vec())).collect())
+        }
+    }
+}
+
+/// Writes data blocks to a file, dropping their times unless the format
+/// keeps them.
+pub fn write(path: &Path, blocks: &[Timestamped<Vec<u8>>]) -> Result<(), String> {
+    let mut out = BufWriter::new(File::create(path).map_err(|e| e.to_string())?);
+    let result = match Format::of(path) {
+        Format::Pcap => pcap::write_header(&mut out)
+            .and_then(|()| blocks.iter().try_for_each(|b| pcap::write_packet(&mut out, b.recv_time, &b.data))),
+        Format::Recording => RecordingWriter::new(&mut out)
+            .and_then(|mut writer| blocks.iter().try_for_each(|b| writer.write_block(b.recv_time, &b.data))),
+        #[cfg(feature = "zstd")]
+        Format::CompressedRecording => RecordingWriter::compressed(&mut out, 0).and_then(|mut writer| {
+            blocks.iter().try_for_each(|b| writer.write_block(b.recv_time, &b.data))?;
+            writer.finish().map(drop)
+        }),
+        Format::Hex => blocks.iter().try_for_each(|b| writeln!(out, "{}", hex::encode_spaced(&b.data))),
+        Format::Base64 => {
+            let bytes: Vec<u8> = blocks.iter().flat_map(|b| b.data.iter().copied()).collect();
+            writeln!(out, "{}", base64::encode(&bytes))
+        }
+        Format::Raw => blocks.iter().try_for_each(|b| out.write_all(&b.data)),
+    };
+    result.and_then(|()| out.flush()).map_err(|e| e.to_string())
+}
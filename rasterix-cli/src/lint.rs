@@ -0,0 +1,98 @@
+use std::fs;
+use std::process::ExitCode;
+
+use rasterix_codegen::parse::parser::parse_category;
+use rasterix_codegen::transform::lint::{lint, Lint, Rule, Severity};
+use rasterix_codegen::transform::lower;
+use rasterix_codegen::transform::transformer::to_unvalidated_ir;
+use serde_json::json;
+
+const USAGE: &str = "usage: rasterix lint [--format text|json] [--deny-warnings] <definition.xml>...";
+
+/// `rasterix lint [--format text|json] [--deny-warnings] <definition.xml>...`
+///
+/// Checks definitions, printing each problem with its severity and rule
+/// code.  Fails if any definition has errors, or warnings with
+/// `--deny-warnings`.
+pub fn run(args: &[String]) -> Result<ExitCode, String> {
+    let mut json = false;
+    let mut deny_warnings = false;
+    let mut files = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value\n{}", arg, USAGE));
+        match arg.as_str() {
+            "--format" => match value()?.as_str() {
+                "text" => json = false,
+                "json" => json = true,
+                other => return Err(format!("unknown format `{}`", other)),
+            },
+            "--deny-warnings" => deny_warnings = true,
+            _ if !arg.starts_with('-') => files.push(arg),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    if files.is_empty() {
+        return Err(USAGE.to_string());
+    }
+
+    let mut lints = Vec::new();
+    for file in &files {
+        let xml = fs::read_to_string(file).map_err(|e| format!("{}: {}", file, e))?;
+        lints.extend(lint_definition(&xml).into_iter().map(|lint| (file, lint)));
+    }
+
+    let errors = lints.iter().filter(|(_, lint)| lint.severity() == Severity::Error).count();
+    let warnings = lints.len() - errors;
+    if json {
+        let lints: Vec<_> = lints
+            .iter()
+            .map(|(file, lint)| {
+                json!({
+                    "file": file,
+                    "code": lint.rule.code(),
+                    "rule": lint.rule.name(),
+                    "severity": lint.severity().to_string(),
+                    "item": lint.item,
+                    "message": lint.message,
+                })
+            })
+            .collect();
+        let report = json!({ "files": files.len(), "errors": errors, "warnings": warnings, "lints": lints });
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    } else {
+        for (file, lint) in &lints {
+            println!("{}: {}", file, lint);
+        }
+        println!("{} file(s) checked, {} error(s), {} warning(s)", files.len(), errors, warnings);
+    }
+
+    let clean = errors == 0 && (warnings == 0 || !deny_warnings);
+    Ok(if clean { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
+
+/// Lints a definition, reporting one that can't be parsed or converted
+/// under [`Rule::InvalidDefinition`].
+fn lint_definition(xml: &str) -> Vec<Lint> {
+    let invalid = |message: String| vec![Lint { rule: Rule::InvalidDefinition, item: None, message }];
+
+    let category = match parse_category(xml) {
+        Ok(category) => category,
+        Err(e) => return invalid(format!("failed to parse XML: {}", e)),
+    };
+    let ir = match to_unvalidated_ir(category) {
+        Ok(ir) => ir,
+        Err(e) => return invalid(e),
+    };
+
+    let lints = lint(&ir);
+    // Code generation may still fail, e.g. on an alias collision, once the
+    // layout is sound.
+    if lints.iter().all(|lint| lint.severity() == Severity::Warning)
+        && let Err(e) = lower(&ir)
+    {
+        return [lints, invalid(e)].concat();
+    }
+    lints
+}
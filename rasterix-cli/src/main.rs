@@ -0,0 +1,104 @@
+//! `rasterix` command line tool.
+//!
+//! ```text
+//! rasterix verify <definition.xml> <samples>
+//! rasterix encode [--cat <id>] --xml <definition.xml> [--output <file>] <records.json>
+//! rasterix stream --listen <address:port> --xml-dir <dir> [--format jsonl]
+//! rasterix convert <input> <output>
+//! rasterix slice <input> <output> [--from <s>] [--to <s>] [--cat <id>,...]
+//! rasterix anonymize <input> <output> --key <secret> --xml-dir <dir> [--mode3a-field <name>,...]
+//! rasterix verify-recording <recording.rxr> [--manifest <file>] [--write]
+//! rasterix diff <a> <b> --xml-dir <dir> [--key <field>,...] [--max-skew <ms>]
+//! rasterix lint [--format text|json] [--deny-warnings] <definition.xml>...
+//! rasterix coverage <input> --xml-dir <dir> [--deny-unobserved]
+//! ```
+
+mod anonymize;
+mod convert;
+mod coverage;
+mod definitions;
+mod diff;
+mod encode;
+mod formats;
+mod lint;
+mod pcap;
+mod samples;
+mod slice;
+mod stream;
+mod verify;
+mod verify_recording;
+
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: rasterix <command> [args]
+
+Commands:
+  verify <definition.xml> <samples>   Check that sample data blocks re-encode bit-exactly
+  encode [--cat <id>] --xml <definition.xml> [--output <file>] <records.json>
+                                      Encode a data block from JSON records, as hex
+                                      or to a binary file
+  stream --listen <address:port> (--xml <definition.xml> | --xml-dir <dir>)...
+         [--format jsonl] [--count <blocks>]
+                                      Decode data blocks received over UDP, printing
+                                      one JSON object per record
+  convert <input> <output>            Convert data blocks between pcap captures (.pcap),
+                                      recordings (.rxr), hex, base64 and raw blocks
+  slice <input> <output> [--from <s>] [--to <s>] [--cat <id>,...]
+                                      Keep the blocks of an interval, in seconds since
+                                      the first block, and of some categories
+  anonymize <input> <output> --key <secret> (--xml <definition.xml> | --xml-dir <dir>)...
+            [--mode3a-field <name>,...]
+                                      Replace callsigns, Mode S addresses and Mode 3/A
+                                      codes with keyed pseudonyms
+  verify-recording <recording.rxr> [--manifest <file>] [--write]
+                                      Check a recording against its manifest of block
+                                      lengths and SHA-256 digests, or write it
+  diff <a> <b> (--xml <definition.xml> | --xml-dir <dir>)...
+       [--key <field>,...] [--max-skew <ms>]
+                                      Report field differences between the records of
+                                      two files, matched by key and order
+  lint [--format text|json] [--deny-warnings] <definition.xml>...
+                                      Check definitions, reporting problems with
+                                      their severity and rule code
+  coverage <input> (--xml <definition.xml> | --xml-dir <dir>)... [--deny-unobserved]
+                                      Report the items, parts, sub-items and enum
+                                      variants no record of the input holds
+
+Samples are read as hex from .hex files, base64 from .b64 files, and raw
+bytes otherwise. Lines starting with '#' are ignored in text files.
+
+JSON records are objects keyed by item (\"item010\"), shaped like the JSON
+written by generated code; enums are given by variant name or value.";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("verify") => verify::run(&args[1..]),
+        Some("encode") => encode::run(&args[1..]),
+        Some("stream") => stream::run(&args[1..]),
+        Some("convert") => convert::run(&args[1..]),
+        Some("slice") => slice::run(&args[1..]),
+        Some("anonymize") => anonymize::run(&args[1..]),
+        Some("verify-recording") => verify_recording::run(&args[1..]),
+        Some("diff") => diff::run(&args[1..]),
+        Some("lint") => lint::run(&args[1..]),
+        Some("coverage") => coverage::run(&args[1..]),
+        Some("-h" | "--help" | "help") => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,188 @@
+//! Classic pcap captures of ASTERIX over UDP.
+//!
+//! Reading takes the UDP payloads of Ethernet, Linux cooked and raw IP
+//! captures, over IPv4 or IPv6.  Writing produces an Ethernet capture with
+//! one IPv4/UDP packet per data block, sent from and to `127.0.0.1:8600`.
+
+use std::io::{self, Write};
+use std::time::{Duration, SystemTime};
+
+use rasterix_core::Timestamped;
+
+const MAGIC_MICROS: u32 = 0xa1b2_c3d4;
+const MAGIC_NANOS: u32 = 0xa1b2_3c4d;
+
+const LINKTYPE_ETHERNET: u32 = 1;
+const LINKTYPE_RAW: u32 = 101;
+const LINKTYPE_LINUX_SLL: u32 = 113;
+const LINKTYPE_IPV4: u32 = 228;
+const LINKTYPE_IPV6: u32 = 229;
+const LINKTYPE_LINUX_SLL2: u32 = 276;
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const ETHERTYPE_IPV6: u16 = 0x86dd;
+const ETHERTYPE_VLAN: [u16; 2] = [0x8100, 0x88a8];
+
+const IPPROTO_UDP: u8 = 17;
+
+/// Port packets are written with.
+const PORT: u16 = 8600;
+
+/// UDP payloads of a capture.
+pub struct Capture {
+    /// Payload of each UDP packet, with its capture time.
+    pub datagrams: Vec<Timestamped<Vec<u8>>>,
+    /// Packets that aren't UDP, or are fragments past the first.
+    pub skipped: usize,
+}
+
+/// Reads the UDP payloads of a pcap capture.
+pub fn read(bytes: &[u8]) -> Result<Capture, String> {
+    if bytes.len() < 24 {
+        return Err("not a pcap capture".to_string());
+    }
+    let magic = [bytes[0], bytes[1], bytes[2], bytes[3]];
+    let (u32_at, nanos): (fn([u8; 4]) -> u32, bool) = match (u32::from_le_bytes(magic), u32::from_be_bytes(magic)) {
+        (MAGIC_MICROS, _) => (u32::from_le_bytes, false),
+        (MAGIC_NANOS, _) => (u32::from_le_bytes, true),
+        (_, MAGIC_MICROS) => (u32::from_be_bytes, false),
+        (_, MAGIC_NANOS) => (u32::from_be_bytes, true),
+        _ => return Err("not a pcap capture (pcapng is not supported)".to_string()),
+    };
+    let word = |offset: usize| u32_at([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]]);
+    let link_type = word(20) & 0x0fff_ffff;
+
+    let mut capture = Capture { datagrams: Vec::new(), skipped: 0 };
+    let mut offset = 24;
+    while offset < bytes.len() {
+        if offset + 16 > bytes.len() {
+            return Err(format!("packet header at byte {} cut short", offset));
+        }
+        let seconds = word(offset) as u64;
+        let fraction = word(offset + 4) as u64;
+        let len = word(offset + 8) as usize;
+        let start = offset + 16;
+        let Some(frame) = bytes.get(start..start + len) else {
+            return Err(format!("packet at byte {} cut short", offset));
+        };
+        offset = start + len;
+
+        let time = SystemTime::UNIX_EPOCH
+            + Duration::from_secs(seconds)
+            + if nanos { Duration::from_nanos(fraction) } else { Duration::from_micros(fraction) };
+        match udp_payload(link_type, frame) {
+            Some(payload) => capture.datagrams.push(Timestamped::new(time, payload.to_vec())),
+            None => capture.skipped += 1,
+        }
+    }
+    Ok(capture)
+}
+
+fn udp_payload(link_type: u32, frame: &[u8]) -> Option<&[u8]> {
+    let (ethertype, ip) = match link_type {
+        LINKTYPE_ETHERNET => {
+            let mut ethertype = u16_at(frame, 12)?;
+            let mut header = 14;
+            while ETHERTYPE_VLAN.contains(&ethertype) {
+                ethertype = u16_at(frame, header + 2)?;
+                header += 4;
+            }
+            (ethertype, frame.get(header..)?)
+        }
+        LINKTYPE_LINUX_SLL => (u16_at(frame, 14)?, frame.get(16..)?),
+        LINKTYPE_LINUX_SLL2 => (u16_at(frame, 0)?, frame.get(20..)?),
+        LINKTYPE_RAW | LINKTYPE_IPV4 | LINKTYPE_IPV6 => match frame.first()? >> 4 {
+            4 => (ETHERTYPE_IPV4, frame),
+            6 => (ETHERTYPE_IPV6, frame),
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    let udp = match ethertype {
+        ETHERTYPE_IPV4 => {
+            let header = (*ip.first()? as usize & 0x0f) * 4;
+            let fragment = u16_at(ip, 6)?;
+            // Later fragments have no UDP header; the first has a partial payload.
+            if *ip.get(9)? != IPPROTO_UDP || fragment & 0x3fff != 0 {
+                return None;
+            }
+            let total = (u16_at(ip, 2)? as usize).min(ip.len());
+            ip.get(header..total)?
+        }
+        ETHERTYPE_IPV6 => {
+            if *ip.get(6)? != IPPROTO_UDP {
+                return None;
+            }
+            let total = (40 + u16_at(ip, 4)? as usize).min(ip.len());
+            ip.get(40..total)?
+        }
+        _ => return None,
+    };
+
+    let len = (u16_at(udp, 4)? as usize).min(udp.len());
+    udp.get(8..len)
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes([*bytes.get(offset)?, *bytes.get(offset + 1)?]))
+}
+
+/// Writes the header of a capture with microsecond timestamps.
+pub fn write_header(out: &mut impl Write) -> io::Result<()> {
+    out.write_all(&MAGIC_MICROS.to_le_bytes())?;
+    out.write_all(&2u16.to_le_bytes())?;
+    out.write_all(&4u16.to_le_bytes())?;
+    out.write_all(&[0; 8])?;
+    out.write_all(&65_535u32.to_le_bytes())?;
+    out.write_all(&LINKTYPE_ETHERNET.to_le_bytes())
+}
+
+/// Writes `payload` as a UDP packet captured at `time`.
+pub fn write_packet(out: &mut impl Write, time: SystemTime, payload: &[u8]) -> io::Result<()> {
+    const HEADERS: usize = 14 + 20 + 8;
+    if payload.len() > u16::MAX as usize - 28 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "data block too long for a UDP packet"));
+    }
+    let since_epoch = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "time before the Unix epoch"))?;
+    let seconds = u32::try_from(since_epoch.as_secs())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "time past the range of pcap"))?;
+
+    let frame_len = (HEADERS + payload.len()) as u32;
+    out.write_all(&seconds.to_le_bytes())?;
+    out.write_all(&since_epoch.subsec_micros().to_le_bytes())?;
+    out.write_all(&frame_len.to_le_bytes())?;
+    out.write_all(&frame_len.to_le_bytes())?;
+
+    let mut frame = Vec::with_capacity(frame_len as usize);
+    frame.extend([0; 12]);
+    frame.extend(ETHERTYPE_IPV4.to_be_bytes());
+
+    let ip_len = (20 + 8 + payload.len()) as u16;
+    let mut ip = [
+        0x45, 0, 0, 0, 0, 0, 0x40, 0, 64, IPPROTO_UDP, 0, 0,
+        127, 0, 0, 1,
+        127, 0, 0, 1,
+    ];
+    ip[2..4].copy_from_slice(&ip_len.to_be_bytes());
+    let checksum = !ip
+        .chunks(2)
+        .map(|pair| u16::from_be_bytes([pair[0], pair[1]]) as u32)
+        .fold(0u32, |sum, word| {
+            let sum = sum + word;
+            (sum & 0xffff) + (sum >> 16)
+        }) as u16;
+    ip[10..12].copy_from_slice(&checksum.to_be_bytes());
+    frame.extend(ip);
+
+    frame.extend(PORT.to_be_bytes());
+    frame.extend(PORT.to_be_bytes());
+    frame.extend((8 + payload.len() as u16).to_be_bytes());
+    // A zero checksum means none for UDP over IPv4.
+    frame.extend([0, 0]);
+    frame.extend(payload);
+
+    out.write_all(&frame)
+}
@@ -0,0 +1,43 @@
+use std::fs;
+use std::path::Path;
+
+use rasterix_core::{base64, hex, Header};
+
+/// Reads encoded data blocks from a file, choosing the format by extension:
+/// `.hex` for hex text, `.b64` for base64 text, and raw bytes otherwise.
+///
+/// Lines starting with `#` are skipped in text formats.
+pub fn load(path: &Path) -> Result<Vec<u8>, String> {
+    let read_text = || -> Result<String, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(text
+            .lines()
+            .filter(|line| !line.trim_start().starts_with('#'))
+            .collect::<Vec<_>>()
+            .join("\n"))
+    };
+
+    let decoded = match path.extension().and_then(|s| s.to_str()) {
+        Some("hex") => hex::decode_loose(&read_text()?),
+        Some("b64") => base64::decode(&read_text()?),
+        _ => return fs::read(path).map_err(|e| format!("{}: {}", path.display(), e)),
+    };
+
+    decoded.map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Splits concatenated data blocks using their LEN field.  A truncated
+/// last block is returned as is, to be reported when decoded.
+pub fn blocks(mut data: &[u8]) -> impl Iterator<Item = &[u8]> {
+    std::iter::from_fn(move || {
+        let [cat, hi, lo, ..] = *data else {
+            let rest = data;
+            data = &[];
+            return (!rest.is_empty()).then_some(rest);
+        };
+        let len = (Header::from_bytes([cat, hi, lo]).length as usize).clamp(Header::SIZE, data.len());
+        let (block, rest) = data.split_at(len);
+        data = rest;
+        Some(block)
+    })
+}
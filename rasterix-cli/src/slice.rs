@@ -0,0 +1,70 @@
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::Duration;
+
+use rasterix_core::recording;
+
+use crate::formats;
+
+const USAGE: &str = "usage: rasterix slice <input> <output> [--from <s>] [--to <s>] [--cat <id>,...]";
+
+/// `rasterix slice <input> <output> [--from <s>] [--to <s>] [--cat <id>,...]`
+///
+/// Copies the blocks of `input` received from `--from` until before `--to`,
+/// both in seconds since the first block of `input`, and of the `--cat`
+/// categories.  Without them, the interval runs from the first to the last
+/// block and every category is kept.
+pub fn run(args: &[String]) -> Result<ExitCode, String> {
+    let mut paths = Vec::new();
+    let mut from = None;
+    let mut to = None;
+    let mut categories: Option<Vec<u8>> = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value\n{}", arg, USAGE));
+        match arg.as_str() {
+            "--from" => from = Some(seconds(value()?)?),
+            "--to" => to = Some(seconds(value()?)?),
+            "--cat" => {
+                let list = value()?;
+                let ids = list.split(',').map(|id| id.parse().map_err(|_| format!("invalid category `{}`", id)));
+                categories.get_or_insert_default().extend(ids.collect::<Result<Vec<u8>, _>>()?);
+            }
+            _ if !arg.starts_with('-') => paths.push(arg),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    let [input, output] = paths[..] else {
+        return Err(USAGE.to_string());
+    };
+    let (input, output) = (Path::new(input), Path::new(output));
+
+    let blocks = formats::read(input).map_err(|e| format!("{}: {}", input.display(), e))?;
+    let total = blocks.len();
+    let (Some(first), Some(last)) = (
+        blocks.iter().map(|block| block.recv_time).min(),
+        blocks.iter().map(|block| block.recv_time).max(),
+    ) else {
+        return Err(format!("{}: no data blocks", input.display()));
+    };
+    let from = first + from.unwrap_or(Duration::ZERO);
+    // The last block is kept unless `--to` says otherwise.
+    let to = to.map_or(last + Duration::from_nanos(1), |to| first + to);
+
+    let sliced = recording::slice(blocks.into_iter().map(Ok), from, to, categories.as_deref())
+        .collect::<std::io::Result<Vec<_>>>()
+        .map_err(|e| e.to_string())?;
+    formats::write(output, &sliced).map_err(|e| format!("{}: {}", output.display(), e))?;
+
+    println!("{} of {} block(s) kept", sliced.len(), total);
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Parses a non-negative number of seconds.
+fn seconds(text: &str) -> Result<Duration, String> {
+    text.parse()
+        .ok()
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        .ok_or_else(|| format!("invalid time `{}`", text))
+}
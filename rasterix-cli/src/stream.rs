@@ -0,0 +1,111 @@
+use std::io::{self, Write};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket};
+use std::process::ExitCode;
+
+use rasterix_codegen::dynamic::json;
+use rasterix_core::BitReader;
+use serde_json::{Map, Value};
+
+use crate::definitions::{self, Decoders};
+use crate::samples;
+
+const USAGE: &str = "\
+usage: rasterix stream --listen <address:port> (--xml <definition.xml> | --xml-dir <dir>)...
+                       [--format jsonl] [--count <blocks>]";
+
+/// Largest UDP payload.
+const MAX_DATAGRAM: usize = 65_535;
+
+/// `rasterix stream --listen <address:port> (--xml <definition.xml> | --xml-dir <dir>)... [--format jsonl] [--count <blocks>]`
+///
+/// Receives data blocks over UDP and prints each decoded record as a line
+/// of JSON, `{"category":48,"record":{...}}`.  A multicast address is
+/// joined on the default interface.  Blocks that can't be decoded are
+/// reported on stderr and skipped.  Runs until `--count` blocks have been
+/// received, or forever.
+pub fn run(args: &[String]) -> Result<ExitCode, String> {
+    let mut listen = None;
+    let mut files = Vec::new();
+    let mut dirs = Vec::new();
+    let mut count = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value\n{}", arg, USAGE));
+        match arg.as_str() {
+            "--listen" => {
+                let address = value()?;
+                listen = Some(address.parse::<SocketAddr>().map_err(|_| format!("invalid address `{}`", address))?);
+            }
+            "--xml" => files.push(value()?.clone()),
+            "--xml-dir" => dirs.push(value()?.clone()),
+            "--format" => match value()?.as_str() {
+                "jsonl" => {}
+                format => return Err(format!("unsupported format `{}`", format)),
+            },
+            "--count" => {
+                let blocks = value()?;
+                count = Some(blocks.parse::<u64>().map_err(|_| format!("invalid count `{}`", blocks))?);
+            }
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    let Some(listen) = listen else {
+        return Err(USAGE.to_string());
+    };
+
+    let decoders = definitions::load(&files, &dirs)?;
+    let socket = bind(listen).map_err(|e| format!("{}: {}", listen, e))?;
+
+    let mut buf = vec![0u8; MAX_DATAGRAM];
+    let mut received = 0;
+    while count.is_none_or(|count| received < count) {
+        let len = socket.recv(&mut buf).map_err(|e| format!("{}: {}", listen, e))?;
+        let mut out = io::stdout().lock();
+        for block in samples::blocks(&buf[..len]) {
+            received += 1;
+            print_block(&decoders, block, &mut out).map_err(|e| e.to_string())?;
+        }
+        out.flush().map_err(|e| e.to_string())?;
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Binds to `address`, joining it on the default interface if it is a
+/// multicast group.
+fn bind(address: SocketAddr) -> io::Result<UdpSocket> {
+    match address {
+        SocketAddr::V4(v4) if v4.ip().is_multicast() => {
+            let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, v4.port()))?;
+            socket.join_multicast_v4(v4.ip(), &Ipv4Addr::UNSPECIFIED)?;
+            Ok(socket)
+        }
+        SocketAddr::V6(v6) if v6.ip().is_multicast() => {
+            let socket = UdpSocket::bind((Ipv6Addr::UNSPECIFIED, v6.port()))?;
+            socket.join_multicast_v6(v6.ip(), 0)?;
+            Ok(socket)
+        }
+        _ => UdpSocket::bind(address),
+    }
+}
+
+fn print_block(decoders: &Decoders, block: &[u8], out: &mut impl Write) -> io::Result<()> {
+    let category = block[0];
+    let Some(decoder) = decoders.get(&category) else {
+        eprintln!("warning: no definition for category {}", category);
+        return Ok(());
+    };
+
+    match decoder.decode_block(&mut BitReader::new(block)) {
+        Ok(decoded) => {
+            for record in &decoded.records {
+                let mut line = Map::new();
+                line.insert("category".to_string(), Value::from(category));
+                line.insert("record".to_string(), json::record_to_json(decoder, record));
+                writeln!(out, "{}", Value::Object(line))?;
+            }
+        }
+        Err(e) => eprintln!("warning: category {} block of {} bytes: {}", category, block.len(), e),
+    }
+    Ok(())
+}
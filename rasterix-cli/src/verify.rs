@@ -0,0 +1,24 @@
+use std::path::Path;
+use std::process::ExitCode;
+
+use rasterix_codegen::builder::RustBuilder;
+
+use crate::samples;
+
+/// `rasterix verify <definition.xml> <samples>`
+///
+/// Prints the verify report and fails if any block does not re-encode
+/// bit-exactly.
+pub fn run(args: &[String]) -> Result<ExitCode, String> {
+    let [definition, samples_path] = args else {
+        return Err("usage: rasterix verify <definition.xml> <samples>".to_string());
+    };
+
+    let bytes = samples::load(Path::new(samples_path))?;
+    let report = RustBuilder::new()
+        .verify(definition, &bytes)
+        .map_err(|e| e.to_string())?;
+
+    println!("{}", report);
+    Ok(if report.is_lossless() { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
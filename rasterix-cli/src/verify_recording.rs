@@ -0,0 +1,65 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use rasterix_core::recording::{self, ManifestWriter, RecordingReader};
+
+const USAGE: &str = "usage: rasterix verify-recording <recording.rxr> [--manifest <file>] [--write]";
+
+/// `rasterix verify-recording <recording.rxr> [--manifest <file>] [--write]`
+///
+/// Checks a recording against its manifest, `<recording>.manifest` unless
+/// `--manifest` says otherwise, and fails if any entry is corrupt, missing
+/// or not in the manifest.  With `--write`, writes the manifest instead.
+pub fn run(args: &[String]) -> Result<ExitCode, String> {
+    let mut input = None;
+    let mut manifest = None;
+    let mut write = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--manifest" => {
+                let path = args.next().ok_or_else(|| format!("--manifest needs a value\n{}", USAGE))?;
+                manifest = Some(PathBuf::from(path));
+            }
+            "--write" => write = true,
+            _ if !arg.starts_with('-') && input.is_none() => input = Some(Path::new(arg)),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    let input = input.ok_or_else(|| USAGE.to_string())?;
+    let manifest = manifest.unwrap_or_else(|| {
+        let mut path = input.as_os_str().to_owned();
+        path.push(".manifest");
+        PathBuf::from(path)
+    });
+
+    let reader = File::open(input)
+        .and_then(|file| RecordingReader::new(BufReader::new(file)))
+        .map_err(|e| format!("{}: {}", input.display(), e))?;
+
+    if write {
+        let mut entries = 0;
+        let result = File::create(&manifest).and_then(|file| {
+            let mut writer = ManifestWriter::new(BufWriter::new(file))?;
+            for block in reader {
+                let block = block.map_err(|e| std::io::Error::new(e.kind(), format!("{}: {}", input.display(), e)))?;
+                writer.add_block(block.recv_time, &block.data)?;
+                entries += 1;
+            }
+            writer.flush()
+        });
+        result.map_err(|e| format!("{}: {}", manifest.display(), e))?;
+        println!("{} entry(ies) written to {}", entries, manifest.display());
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let file = File::open(&manifest).map_err(|e| format!("{}: {}", manifest.display(), e))?;
+    let report = recording::verify_manifest(reader, BufReader::new(file))
+        .map_err(|e| format!("{}: {}", manifest.display(), e))?;
+    let mut out = std::io::stdout().lock();
+    writeln!(out, "{}", report).map_err(|e| e.to_string())?;
+    Ok(if report.is_intact() { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
@@ -0,0 +1,526 @@
+//! Tests running the `rasterix` binary.
+
+use std::process::{Command, Output};
+
+use test_utils::{cleanup_temp_files, create_temp_file, fixture_path};
+
+fn rasterix(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_rasterix")).args(args).output().unwrap()
+}
+
+// ============================================================================
+// Verify Tests
+// ============================================================================
+
+#[test]
+fn verify_lossless_hex_fixture() {
+    let definition = fixture_path("valid", "multi_item_record.xml");
+    let samples = fixture_path("messages", "cat048_two_records.hex");
+
+    let output = rasterix(&["verify", definition.to_str().unwrap(), samples.to_str().unwrap()]);
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("1 block(s), 1 lossless"), "{}", stdout);
+}
+
+#[test]
+fn verify_reports_changed_bits() {
+    let definition = fixture_path("valid", "spare_bits.xml");
+    let samples = create_temp_file("# spare bits set\n01 00 06 80 2A 01\n", "hex");
+
+    let output = rasterix(&["verify", definition.to_str().unwrap(), samples.to_str().unwrap()]);
+
+    cleanup_temp_files();
+
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("byte 5 bit 0: expected 1, got 0"), "{}", stdout);
+}
+
+#[test]
+fn verify_fails_on_missing_definition() {
+    let samples = fixture_path("messages", "cat048_two_records.hex");
+
+    let output = rasterix(&["verify", "nonexistent_file.xml", samples.to_str().unwrap()]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr).unwrap().starts_with("error: "));
+}
+
+#[test]
+fn unknown_command_prints_usage() {
+    let output = rasterix(&["frobnicate"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr).unwrap().contains("Usage: rasterix"));
+}
+
+// ============================================================================
+// Encode Tests
+// ============================================================================
+
+#[test]
+fn encode_json_records_as_hex() {
+    let definition = fixture_path("valid", "multi_item_record.xml");
+    let records = create_temp_file(
+        r#"[{"item010": {"sac": 1, "sic": 2}, "item020": {"typ": 3}}, {"item240": {"aircraft_id": "BAW123"}}]"#,
+        "json",
+    );
+
+    let output = rasterix(&[
+        "encode", "--cat", "48", "--xml", definition.to_str().unwrap(), records.to_str().unwrap(),
+    ]);
+    std::fs::remove_file(&records).ok();
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let expected = test_utils::load_message_fixture("cat048_two_records.hex");
+    assert_eq!(String::from_utf8(output.stdout).unwrap().trim(), rasterix_core::hex::encode_spaced(&expected));
+}
+
+#[test]
+fn encode_writes_binary_output() {
+    let definition = fixture_path("valid", "enum_basic.xml");
+    let records = create_temp_file(r#"{"item010": {"target_type": "Ssr"}}"#, "json");
+    let binary = records.with_extension("bin");
+
+    let output = rasterix(&[
+        "encode", "--xml", definition.to_str().unwrap(), "--output", binary.to_str().unwrap(), records.to_str().unwrap(),
+    ]);
+    let written = std::fs::read(&binary);
+    std::fs::remove_file(&records).ok();
+    std::fs::remove_file(&binary).ok();
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.stdout.is_empty());
+    assert_eq!(written.unwrap(), [0x01, 0x00, 0x05, 0x80, 0x40]);
+}
+
+#[test]
+fn encode_reports_invalid_records() {
+    let definition = fixture_path("valid", "multi_item_record.xml");
+    let records = create_temp_file(r#"{"item010": {"sac": 256, "sic": 2}}"#, "json");
+
+    let mismatch = rasterix(&["encode", "--cat", "62", "--xml", definition.to_str().unwrap(), records.to_str().unwrap()]);
+    let invalid = rasterix(&["encode", "--xml", definition.to_str().unwrap(), records.to_str().unwrap()]);
+    std::fs::remove_file(&records).ok();
+
+    assert_eq!(mismatch.status.code(), Some(2));
+    assert!(String::from_utf8(mismatch.stderr).unwrap().contains("defines category 48, not 62"));
+    assert_eq!(invalid.status.code(), Some(2));
+    assert!(String::from_utf8(invalid.stderr).unwrap().contains("item010.sac: 256 does not fit in 8 bits"));
+}
+
+// ============================================================================
+// Stream Tests
+// ============================================================================
+
+#[test]
+fn stream_prints_records_received_over_udp() {
+    use std::net::UdpSocket;
+    use std::process::Stdio;
+    use std::time::{Duration, Instant};
+
+    let definition = fixture_path("valid", "multi_item_record.xml");
+    let port = UdpSocket::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let listen = format!("127.0.0.1:{}", port);
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rasterix"))
+        .args(["stream", "--listen", &listen, "--xml", definition.to_str().unwrap(), "--count", "2"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // A CAT048 block with two records, and a block of a category without a
+    // definition.  Resent until the listener is bound and has read them.
+    let mut datagram = test_utils::load_message_fixture("cat048_two_records.hex");
+    datagram.extend([0x3e, 0x00, 0x03]);
+    let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while child.try_wait().unwrap().is_none() {
+        if Instant::now() > deadline {
+            child.kill().unwrap();
+            panic!("stream did not exit");
+        }
+        sender.send_to(&datagram, &listen).unwrap();
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        stdout.lines().collect::<Vec<_>>(),
+        [
+            r#"{"category":48,"record":{"item010":{"sac":1,"sic":2},"item020":{"typ":3}}}"#,
+            r#"{"category":48,"record":{"item240":{"aircraft_id":"BAW123"}}}"#,
+        ]
+    );
+    assert!(String::from_utf8(output.stderr).unwrap().contains("no definition for category 62"));
+}
+
+// ============================================================================
+// Convert Tests
+// ============================================================================
+
+#[test]
+fn convert_between_formats_keeps_blocks() {
+    let samples = fixture_path("messages", "cat048_two_records.hex");
+    let base = create_temp_file("", "tmp");
+    let recording = base.with_extension("rxr");
+    let capture = base.with_extension("pcap");
+    let raw = base.with_extension("ast");
+    let path = |p: &std::path::Path| p.to_str().unwrap().to_string();
+
+    let outputs = [
+        rasterix(&["convert", &path(&samples), &path(&recording)]),
+        rasterix(&["convert", &path(&recording), &path(&capture)]),
+        rasterix(&["convert", &path(&capture), &path(&raw)]),
+    ];
+    let converted = std::fs::read(&raw);
+    let recorded = std::fs::read(&recording);
+    for file in [&base, &recording, &capture, &raw] {
+        std::fs::remove_file(file).ok();
+    }
+
+    for output in &outputs {
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "1 block(s) converted");
+    }
+    let expected = test_utils::load_message_fixture("cat048_two_records.hex");
+    assert_eq!(converted.unwrap(), expected);
+    // Blocks without a receive time are stamped with the Unix epoch.
+    assert_eq!(recorded.unwrap(), [&b"RXR1"[..], &[0; 8], &expected].concat());
+}
+
+#[test]
+fn convert_reads_udp_payloads_of_captures() {
+    // Big-endian capture with nanosecond timestamps of Linux cooked frames:
+    // one UDP packet with two data blocks and one TCP packet.
+    let mut pcap = vec![0xa1, 0xb2, 0x3c, 0x4d, 0, 2, 0, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff, 0, 0, 0, 113];
+    let blocks = [0x01, 0x00, 0x04, 0x00, 0x02, 0x00, 0x03];
+    for protocol in [17, 6] {
+        let mut frame = vec![0; 14];
+        frame.extend([0x08, 0x00]);
+        frame.extend([0x45, 0, 0, 35, 0, 0, 0x40, 0, 64, protocol, 0, 0, 10, 0, 0, 1, 10, 0, 0, 2]);
+        frame.extend([0x21, 0x98, 0x21, 0x98, 0, 15, 0, 0]);
+        frame.extend(blocks);
+        pcap.extend(1_700_000_000u32.to_be_bytes());
+        pcap.extend(5u32.to_be_bytes());
+        pcap.extend((frame.len() as u32).to_be_bytes());
+        pcap.extend((frame.len() as u32).to_be_bytes());
+        pcap.extend(frame);
+    }
+    let base = create_temp_file("", "tmp");
+    let capture = base.with_extension("pcap");
+    let recording = base.with_extension("rxr");
+    std::fs::write(&capture, &pcap).unwrap();
+
+    let output = rasterix(&["convert", capture.to_str().unwrap(), recording.to_str().unwrap()]);
+    let recorded = std::fs::read(&recording);
+    for file in [&base, &capture, &recording] {
+        std::fs::remove_file(file).ok();
+    }
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "2 block(s) converted");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("skipped 1 packet(s)"));
+    let time = (1_700_000_000u64 * 1_000_000_000 + 5).to_be_bytes();
+    assert_eq!(recorded.unwrap(), [&b"RXR1"[..], &time, &blocks[..4], &time, &blocks[4..]].concat());
+}
+
+// ============================================================================
+// Slice Tests
+// ============================================================================
+
+#[test]
+fn slice_keeps_interval_and_categories() {
+    let base = create_temp_file("", "tmp");
+    let input = base.with_extension("rxr");
+    let output = base.with_extension("hex");
+    let mut recording = b"RXR1".to_vec();
+    for (secs, cat) in [(0u64, 48u8), (1, 62), (2, 48), (3, 48)] {
+        recording.extend((secs * 1_000_000_000).to_be_bytes());
+        recording.extend([cat, 0x00, 0x04, secs as u8]);
+    }
+    std::fs::write(&input, &recording).unwrap();
+
+    let result = rasterix(&[
+        "slice",
+        input.to_str().unwrap(),
+        output.to_str().unwrap(),
+        "--from",
+        "0.5",
+        "--to",
+        "3",
+        "--cat",
+        "48",
+    ]);
+    let sliced = std::fs::read_to_string(&output);
+    for file in [&base, &input, &output] {
+        std::fs::remove_file(file).ok();
+    }
+
+    assert!(result.status.success(), "{}", String::from_utf8_lossy(&result.stderr));
+    assert_eq!(String::from_utf8_lossy(&result.stdout).trim(), "1 of 4 block(s) kept");
+    assert_eq!(sliced.unwrap().trim().to_lowercase(), "30 00 04 02");
+}
+
+// ============================================================================
+// Verify Recording Tests
+// ============================================================================
+
+#[test]
+fn verify_recording_detects_corruption() {
+    let recording = create_temp_file("", "rxr");
+    let manifest = recording.with_extension("rxr.manifest");
+    let mut bytes = b"RXR1".to_vec();
+    for secs in 0u64..3 {
+        bytes.extend((secs * 1_000_000_000).to_be_bytes());
+        bytes.extend([0x30, 0x00, 0x04, secs as u8]);
+    }
+    std::fs::write(&recording, &bytes).unwrap();
+    let verify = |extra: &[&str]| rasterix(&[&["verify-recording", recording.to_str().unwrap()], extra].concat());
+
+    let written = verify(&["--write"]);
+    let intact = verify(&[]);
+    *bytes.last_mut().unwrap() ^= 0x01;
+    std::fs::write(&recording, &bytes).unwrap();
+    let corrupt = verify(&[]);
+    for file in [&recording, &manifest] {
+        std::fs::remove_file(file).ok();
+    }
+
+    assert!(written.status.success(), "{}", String::from_utf8_lossy(&written.stderr));
+    assert!(String::from_utf8_lossy(&written.stdout).starts_with("3 entry(ies) written"));
+    assert!(intact.status.success(), "{}", String::from_utf8_lossy(&intact.stdout));
+    assert!(!corrupt.status.success());
+    let report = String::from_utf8_lossy(&corrupt.stdout);
+    assert!(report.contains("entry 2: does not match the manifest"), "{}", report);
+    assert!(report.contains("2 entry(ies) verified, 1 corrupt"), "{}", report);
+}
+
+// ============================================================================
+// Anonymize Tests
+// ============================================================================
+
+#[test]
+fn anonymize_replaces_callsigns() {
+    let definition = fixture_path("valid", "multi_item_record.xml");
+    let samples = fixture_path("messages", "cat048_two_records.hex");
+    let output = create_temp_file("", "hex");
+    let anonymize = |key: &str| {
+        let result = rasterix(&[
+            "anonymize",
+            samples.to_str().unwrap(),
+            output.to_str().unwrap(),
+            "--key",
+            key,
+            "--xml",
+            definition.to_str().unwrap(),
+        ]);
+        (result, std::fs::read_to_string(&output).unwrap())
+    };
+
+    let (result, first) = anonymize("secret");
+    let (_, again) = anonymize("secret");
+    let (_, other) = anonymize("other");
+    std::fs::remove_file(&output).ok();
+
+    assert!(result.status.success(), "{}", String::from_utf8_lossy(&result.stderr));
+    assert_eq!(String::from_utf8_lossy(&result.stdout).trim(), "1 block(s) anonymized, 0 dropped without a definition");
+    // The SAC/SIC are kept, the callsign `BAW123` is not.
+    let original = test_utils::load_message_fixture("cat048_two_records.hex");
+    let anonymized: Vec<u8> = first.split_whitespace().map(|byte| u8::from_str_radix(byte, 16).unwrap()).collect();
+    assert_eq!(anonymized.len(), original.len());
+    assert_eq!(anonymized[..8], original[..8]);
+    assert_ne!(anonymized, original);
+    assert_eq!(first, again);
+    assert_ne!(first, other);
+}
+
+// ============================================================================
+// Diff Tests
+// ============================================================================
+
+#[test]
+fn diff_reports_changed_fields() {
+    let definition = fixture_path("valid", "multi_item_record.xml");
+    let samples = fixture_path("messages", "cat048_two_records.hex");
+    let changed = create_temp_file("30 00 0e c0 01 03 03 10 42 41 57 31 32 33\n", "hex");
+
+    let same = rasterix(&["diff", samples.to_str().unwrap(), samples.to_str().unwrap(), "--xml", definition.to_str().unwrap()]);
+    let output = rasterix(&["diff", samples.to_str().unwrap(), changed.to_str().unwrap(), "--xml", definition.to_str().unwrap()]);
+    std::fs::remove_file(&changed).ok();
+
+    assert!(same.status.success(), "{}", String::from_utf8_lossy(&same.stdout));
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("record 0 (cat 48) / record 0 (cat 48):\n  item010.sic: 2 != 3\n"), "{}", stdout);
+    assert!(stdout.contains("2 record(s) compared, 1 differ, 0 only in"), "{}", stdout);
+}
+
+#[test]
+fn diff_matches_records_by_key() {
+    let definition = fixture_path("valid", "multi_item_record.xml");
+    let samples = fixture_path("messages", "cat048_two_records.hex");
+    let reordered = create_temp_file("30 00 0e 10 42 41 57 31 32 33 c0 01 02 03\n", "hex");
+    let missing = create_temp_file("30 00 0a 10 42 41 57 31 32 33\n", "hex");
+    let diff = |other: &std::path::Path| {
+        rasterix(&[
+            "diff",
+            samples.to_str().unwrap(),
+            other.to_str().unwrap(),
+            "--xml",
+            definition.to_str().unwrap(),
+            "--key",
+            "sacsic",
+        ])
+    };
+
+    let reordered_output = diff(&reordered);
+    let missing_output = diff(&missing);
+    std::fs::remove_file(&reordered).ok();
+    std::fs::remove_file(&missing).ok();
+
+    assert!(reordered_output.status.success(), "{}", String::from_utf8_lossy(&reordered_output.stdout));
+    assert_eq!(missing_output.status.code(), Some(1));
+    let stdout = String::from_utf8(missing_output.stdout).unwrap();
+    assert!(stdout.contains(": record 0 (cat 48, item010.sac=1 item010.sic=2)\n"), "{}", stdout);
+    assert!(stdout.contains("1 record(s) compared, 0 differ, 1 only in"), "{}", stdout);
+}
+
+// ============================================================================
+// Lint Tests
+// ============================================================================
+
+#[test]
+fn lint_passes_valid_definitions() {
+    let definitions = ["simple_fixed.xml", "compound_complex.xml", "enum_policies.xml"].map(|name| fixture_path("valid", name));
+    let args: Vec<&str> = definitions.iter().map(|path| path.to_str().unwrap()).collect();
+
+    let output = rasterix(&[&["lint"], &args[..]].concat());
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stdout));
+    assert_eq!(String::from_utf8(output.stdout).unwrap().trim(), "3 file(s) checked, 0 error(s), 0 warning(s)");
+}
+
+#[test]
+fn lint_reports_rule_codes_as_json() {
+    let bit_mismatch = fixture_path("invalid", "bit_mismatch.xml");
+    let item_id = fixture_path("invalid", "item_id_too_long.xml");
+
+    let output = rasterix(&["lint", "--format", "json", bit_mismatch.to_str().unwrap(), item_id.to_str().unwrap()]);
+
+    assert_eq!(output.status.code(), Some(1));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["errors"], 2);
+    assert_eq!(report["lints"][0]["file"], bit_mismatch.to_str().unwrap());
+    assert_eq!(report["lints"][0]["code"], "E001");
+    assert_eq!(report["lints"][0]["rule"], "bit-count");
+    assert_eq!(report["lints"][0]["severity"], "error");
+    assert_eq!(report["lints"][0]["item"], 10);
+    assert_eq!(report["lints"][1]["code"], "E000");
+    assert_eq!(report["lints"][1]["item"], serde_json::Value::Null);
+}
+
+#[test]
+fn lint_reports_definitions_that_cant_be_lowered_without_panicking() {
+    let bad_name = create_temp_file(
+        r#"<category id="1">
+            <item id="10" frn="1"><fixed bytes="1"><field name="3d range" bits="8"/></fixed></item>
+        </category>"#,
+        "xml",
+    );
+    let alias_collision = create_temp_file(
+        r#"<category id="1">
+            <item id="10" frn="1" name="Track Number"><fixed bytes="1"><field name="a" bits="8"/></fixed></item>
+            <item id="20" frn="2" name="Track number"><fixed bytes="1"><field name="b" bits="8"/></fixed></item>
+        </category>"#,
+        "xml",
+    );
+
+    let output = rasterix(&["lint", "--format", "json", bad_name.to_str().unwrap(), alias_collision.to_str().unwrap()]);
+    std::fs::remove_file(&bad_name).ok();
+    std::fs::remove_file(&alias_collision).ok();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stderr.is_empty(), "{}", String::from_utf8_lossy(&output.stderr));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["errors"], 2);
+    assert_eq!(report["lints"][0]["code"], "E000");
+    assert_eq!(
+        report["lints"][0]["message"],
+        "Invalid name: item 010: field `3d range` does not give an identifier"
+    );
+    assert_eq!(report["lints"][1]["code"], "E000");
+    assert_eq!(report["lints"][1]["message"], "Alias collision: Item010 and Item020 both give TrackNumber");
+}
+
+#[test]
+fn lint_rejects_repetition_bounds() {
+    let definition = create_temp_file(
+        r#"<category id="1">
+            <item id="10" frn="1">
+                <repetitive bytes="1" counter="2" min_rep="1"><field name="a" bits="8"/></repetitive>
+            </item>
+        </category>"#,
+        "xml",
+    );
+
+    let output = rasterix(&["lint", "--format", "json", definition.to_str().unwrap()]);
+    std::fs::remove_file(&definition).ok();
+
+    assert_eq!(output.status.code(), Some(1));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["errors"], 1);
+    assert_eq!(
+        report["lints"][0]["message"],
+        "Unsupported attribute `min_rep` on repetitive item: counts are exact, with no REP byte to bound"
+    );
+}
+
+#[test]
+fn lint_denies_warnings_on_request() {
+    let definition = create_temp_file(
+        r#"<category id="1">
+            <item id="20" frn="2"><fixed bytes="1"><field name="b" bits="8"/></fixed></item>
+            <item id="10" frn="1"><fixed bytes="1"><field name="a" bits="8"/></fixed></item>
+        </category>"#,
+        "xml",
+    );
+
+    let allowed = rasterix(&["lint", definition.to_str().unwrap()]);
+    let denied = rasterix(&["lint", "--deny-warnings", definition.to_str().unwrap()]);
+    std::fs::remove_file(&definition).ok();
+
+    assert!(allowed.status.success());
+    let stdout = String::from_utf8(allowed.stdout).unwrap();
+    assert!(stdout.contains("warning[W003]: item 010: FRN 1 is listed after FRN 2 of item 020 (unordered-items)"), "{}", stdout);
+    assert_eq!(denied.status.code(), Some(1));
+}
+
+// ============================================================================
+// Coverage Tests
+// ============================================================================
+
+#[test]
+fn coverage_lists_unobserved_items() {
+    let definition = fixture_path("valid", "multi_item_record.xml");
+    let samples = fixture_path("messages", "cat048_two_records.hex");
+    let partial = create_temp_file("30 00 0a 10 42 41 57 31 32 33\n", "hex");
+
+    let full = rasterix(&["coverage", samples.to_str().unwrap(), "--xml", definition.to_str().unwrap(), "--deny-unobserved"]);
+    let allowed = rasterix(&["coverage", partial.to_str().unwrap(), "--xml", definition.to_str().unwrap()]);
+    let denied = rasterix(&["coverage", partial.to_str().unwrap(), "--xml", definition.to_str().unwrap(), "--deny-unobserved"]);
+    std::fs::remove_file(&partial).ok();
+
+    assert!(full.status.success(), "{}", String::from_utf8_lossy(&full.stdout));
+    assert_eq!(String::from_utf8(full.stdout).unwrap(), "category 48: 3 of 3 element(s) observed in 2 record(s)\n");
+    assert!(allowed.status.success());
+    assert_eq!(
+        String::from_utf8(allowed.stdout).unwrap(),
+        "never observed: item010\nnever observed: item020\ncategory 48: 1 of 3 element(s) observed in 1 record(s)\n"
+    );
+    assert_eq!(denied.status.code(), Some(1));
+}
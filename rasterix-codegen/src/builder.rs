@@ -1,12 +1,143 @@
-use std::{fs, path::PathBuf};
+use std::{
+    error::Error,
+    fmt, fs,
+    path::{Path, PathBuf},
+    marker::PhantomData,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use proc_macro2::TokenStream;
+use quote::quote;
 use crate::{
-    generate::generate,
-    parse::parser::parse_category,
-    transform::transformer::to_ir,
+    dynamic::{verify_blocks, DynamicDecoder, VerifyReport},
+    generate::{
+        arrow_gen::ArrowBackend,
+        backend::{run_backend, CodegenBackend},
+        borrowed_gen::BorrowedBackend,
+        diagram_gen::{self, DiagramBackend, ItemDiagram},
+        docs_gen::DocsBackend,
+        ffi_gen::{FfiBackend, FfiOutput},
+        generate_lowered,
+        ksy_gen::KsyBackend,
+        proto_gen::{ProtoBackend, ProtoOutput},
+        wasm_gen::WasmBackend,
+    },
+    parse::{
+        parser::{parse_category_with_elements, parse_features, parse_renames},
+        rename_model::RenameConfig,
+        xml_model::{Category, FeatureConfig},
+    },
+    transform::{
+        apply_features, apply_plugins, apply_renames, ir::IR, lint::{lint, Lint, Severity}, lower,
+        lower_ir::LoweredIR,
+        plugins::{ElementPlugin, ElementPlugins},
+        transformer::to_unvalidated_ir, use_heapless,
+    },
 };
 
+/// Stage of a build that failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildStage {
+    /// Reading a definition or configuration file, or writing the output.
+    Io,
+    /// Parsing a definition or configuration file.
+    Parse,
+    /// Validating a definition and lowering it, with its feature and rename
+    /// configuration.
+    Transform,
+    /// Generating code from a valid definition.
+    Generate,
+}
+
+impl fmt::Display for BuildStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BuildStage::Io => "io",
+            BuildStage::Parse => "parse",
+            BuildStage::Transform => "transform",
+            BuildStage::Generate => "generate",
+        })
+    }
+}
+
+/// Error of a build, with the stage and file it failed at.
+///
+/// Definitions that fail validation are reported as errors of the
+/// [`Transform`](BuildStage::Transform) stage, listing every
+/// [lint](crate::transform::lint) error, instead of panicking.
+///
+/// ```
+/// use rasterix_codegen::builder::{BuildStage, Builder, RustBuilder};
+///
+/// let err = RustBuilder::new().build_from_str(r#"<category id="1">
+///     <item id="10" frn="1">
+///         <fixed bytes="2"><field name="a" bits="8"/></fixed>
+///     </item>
+/// </category>"#).unwrap_err();
+///
+/// assert_eq!(err.stage, BuildStage::Transform);
+/// assert_eq!(
+///     err.to_string(),
+///     "Invalid definition: error[E001]: item 010: elements use 8 bits but 2 bytes = 16 bits (bit-count)"
+/// );
+/// ```
+#[derive(Debug)]
+pub struct BuildError {
+    /// Stage the build failed at.
+    pub stage: BuildStage,
+    /// File being processed, if the input was read from one.
+    pub file: Option<PathBuf>,
+    /// What the builder was doing, e.g. "Failed to parse XML".
+    pub context: String,
+    /// The underlying error.
+    pub source: Box<dyn Error + Send + Sync>,
+}
+
+impl BuildError {
+    pub fn new(
+        stage: BuildStage,
+        file: Option<&Path>,
+        context: impl Into<String>,
+        source: impl Into<Box<dyn Error + Send + Sync>>,
+    ) -> Self {
+        Self { stage, file: file.map(Path::to_path_buf), context: context.into(), source: source.into() }
+    }
+
+    /// Attributes an error about a definition to the file it was read from.
+    fn in_file(mut self, file: &Path) -> Self {
+        self.file.get_or_insert_with(|| file.to_path_buf());
+        self
+    }
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}: ", file.display())?;
+        }
+        write!(f, "{}: {}", self.context, self.source)
+    }
+}
+
+impl Error for BuildError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
 /// Trait for building ASTERIX code from XML definitions.
 pub trait Builder {
+    /// Builds Rust code from the text of an XML definition.
+    ///
+    /// # Arguments
+    ///
+    /// * `xml` - The XML definition
+    ///
+    /// # Returns
+    ///
+    /// The generated Rust code as a string
+    fn build_from_str(&self, xml: &str) -> Result<String, BuildError>;
+
     /// Builds Rust code from an XML file.
     /// 
     /// # Arguments
@@ -16,42 +147,360 @@ pub trait Builder {
     /// # Returns
     /// 
     /// The generated Rust code as a string
-    fn build(&self, file_path: &str) -> Result<String, std::io::Error>;
+    fn build(&self, file_path: &str) -> Result<String, BuildError> {
+        let file = Path::new(file_path);
+        let xml = read_file(file)?;
+
+        self.build_from_str(&xml).map_err(|e| e.in_file(file))
+    }
+}
+
+/// Receives the progress of a [`RustBuilder`].
+///
+/// Every method does nothing by default.  Stages are reported for each
+/// definition, with the file it was read from, if any.
+pub trait BuildObserver {
+    /// Called when a stage starts.
+    fn on_stage_start(&self, stage: BuildStage, file: Option<&Path>) {
+        let _ = (stage, file);
+    }
+
+    /// Called when a stage completes successfully, after `elapsed`.  A
+    /// failed stage is reported by the error the build returns.
+    fn on_stage_finish(&self, stage: BuildStage, file: Option<&Path>, elapsed: Duration) {
+        let _ = (stage, file, elapsed);
+    }
+
+    /// Called for problems that don't fail the build, such as lint
+    /// warnings or definitions [`build_directory`](RustBuilder::build_directory)
+    /// skipped.
+    fn on_warning(&self, file: Option<&Path>, message: &str) {
+        let _ = (file, message);
+    }
+}
+
+/// Reports warnings, and optionally progress, as `cargo:warning=` lines
+/// from a build script.
+#[derive(Debug, Clone, Default)]
+pub struct CargoObserver {
+    progress: bool,
+}
+
+impl CargoObserver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also reports each completed stage with its duration.
+    pub fn progress(mut self, enabled: bool) -> Self {
+        self.progress = enabled;
+        self
+    }
+}
+
+impl BuildObserver for CargoObserver {
+    fn on_stage_finish(&self, stage: BuildStage, file: Option<&Path>, elapsed: Duration) {
+        if self.progress {
+            println!("cargo:warning={}{} done in {:.1?}", file_prefix(file), stage, elapsed);
+        }
+    }
+
+    fn on_warning(&self, file: Option<&Path>, message: &str) {
+        println!("cargo:warning={}{}", file_prefix(file), message);
+    }
+}
+
+fn file_prefix(file: Option<&Path>) -> String {
+    file.map(|file| format!("{}: ", file.display())).unwrap_or_default()
 }
 
 /// Rust code generator builder.
-pub struct RustBuilder;
+#[derive(Clone)]
+pub struct RustBuilder {
+    features_file: Option<PathBuf>,
+    rename_file: Option<PathBuf>,
+    heapless: bool,
+    transliterate_names: bool,
+    plugins: ElementPlugins,
+    observer: Option<Arc<dyn BuildObserver + Send + Sync>>,
+    post_processors: Vec<Arc<PostProcessor>>,
+}
 
-impl Builder for RustBuilder {
-    fn build(&self, file_path: &str) -> Result<String, std::io::Error> {
-        // Read XML file
-        let xml = fs::read_to_string(file_path)
-            .map_err(|e| std::io::Error::new(
-                std::io::ErrorKind::NotFound,
-                format!("Failed to read {}: {}", file_path, e)
-            ))?;
+/// A transformation of generated code, see
+/// [`RustBuilder::with_post_process`].
+type PostProcessor = dyn Fn(TokenStream) -> TokenStream + Send + Sync;
 
-        // Parse XML into model
-        let category = parse_category(&xml)
-            .map_err(|e| std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                format!("Failed to parse XML: {}", e)
-            ))?;
+impl fmt::Debug for RustBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RustBuilder")
+            .field("features_file", &self.features_file)
+            .field("rename_file", &self.rename_file)
+            .field("heapless", &self.heapless)
+            .field("transliterate_names", &self.transliterate_names)
+            .field("plugins", &self.plugins)
+            .field("observer", &self.observer.is_some())
+            .field("post_processors", &self.post_processors.len())
+            .finish()
+    }
+}
 
-        // Transform to IR (validates at this stage)
-        let ir = to_ir(category);
+impl Builder for RustBuilder {
+    fn build_from_str(&self, xml: &str) -> Result<String, BuildError> {
+        Ok(self.build_tokens(xml)?.to_string())
+    }
 
-        // Generate Rust code
-        let tokens = generate(&ir);
-        
-        Ok(tokens.to_string())
+    fn build(&self, file_path: &str) -> Result<String, BuildError> {
+        let file = Path::new(file_path);
+        let xml = self.stage(BuildStage::Io, Some(file), || read_file(file))?;
+
+        Ok(self.generate(&xml, Some(file))?.to_string())
     }
 }
 
 impl RustBuilder {
     /// Creates a new RustBuilder instance.
     pub fn new() -> Self {
-        Self
+        Self {
+            features_file: None,
+            rename_file: None,
+            heapless: false,
+            transliterate_names: true,
+            plugins: ElementPlugins::new(),
+            observer: None,
+            post_processors: Vec::new(),
+        }
+    }
+
+    /// Passes the generated code through `post_process` before it is
+    /// returned or written, e.g. to add attributes, wrap the module or strip
+    /// items.  Transformations run in the order they were added.
+    ///
+    /// ```
+    /// use quote::quote;
+    /// use rasterix_codegen::builder::{Builder, RustBuilder};
+    ///
+    /// let builder = RustBuilder::new().with_post_process(|code| quote! {
+    ///     #[allow(clippy::all)]
+    ///     pub mod generated { #code }
+    /// });
+    /// let code = builder.build_from_str(r#"<category id="1">
+    ///     <item id="10" frn="1">
+    ///         <fixed bytes="1"><field name="a" bits="8"/></fixed>
+    ///     </item>
+    /// </category>"#).unwrap();
+    ///
+    /// assert!(code.starts_with("# [allow (clippy :: all)] pub mod generated {"));
+    /// ```
+    pub fn with_post_process(
+        mut self,
+        post_process: impl Fn(TokenStream) -> TokenStream + Send + Sync + 'static,
+    ) -> Self {
+        self.post_processors.push(Arc::new(post_process));
+        self
+    }
+
+    /// Handles custom XML elements with the tag of `plugin`, e.g. `<crc/>`,
+    /// with its size and code.  See [`plugins`](crate::transform::plugins).
+    ///
+    /// A plugin replaces an earlier one of the same tag.
+    pub fn element_plugin(mut self, plugin: impl ElementPlugin + 'static) -> Self {
+        self.plugins.register(plugin);
+        self
+    }
+
+    /// Reports the stages and warnings of each build to `observer`, e.g. a
+    /// [`CargoObserver`] in build scripts.
+    pub fn observer(mut self, observer: impl BuildObserver + Send + Sync + 'static) -> Self {
+        self.observer = Some(Arc::new(observer));
+        self
+    }
+
+    /// Compiles the items listed in a feature configuration file only when
+    /// their cargo feature is enabled.
+    ///
+    /// The file assigns items to features:
+    ///
+    /// ```xml
+    /// <features>
+    ///     <feature name="cat048_bds">
+    ///         <item id="250"/>
+    ///     </feature>
+    /// </features>
+    /// ```
+    ///
+    /// The features must be declared in the `Cargo.toml` of the crate that
+    /// includes the generated code.  A record containing a compiled-out item
+    /// fails to decode.
+    pub fn features_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.features_file = Some(path.into());
+        self
+    }
+
+    /// Overrides generated type, field and enum variant names with those of
+    /// a `rusterix.toml` file, leaving the XML definitions untouched.
+    ///
+    /// Names are overridden per category and item, by their generated
+    /// name:
+    ///
+    /// ```toml
+    /// [cat048.item020.types]
+    /// Item020 = "TargetReport"
+    /// TargetType = "DetectionKind"
+    ///
+    /// [cat048.item020.fields]
+    /// typ = "detection_type"
+    ///
+    /// [cat048.item020.variants.TargetType]
+    /// Psr = "Primary"
+    /// ```
+    ///
+    /// Categories other than the one being built are ignored.  Naming an
+    /// item, type, field or variant the category doesn't have fails the
+    /// build.
+    pub fn rename_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.rename_file = Some(path.into());
+        self
+    }
+
+    /// Stores the elements of repetitive items in a `heapless::Vec` sized
+    /// by their repetition count instead of a `Vec`.
+    ///
+    /// Together with fixed and extended items, which never allocate unless
+    /// they contain string fields, records made of such items decode and
+    /// encode without touching the heap.  The generated code requires the
+    /// `heapless` feature of `rasterix`.
+    pub fn heapless(mut self, enabled: bool) -> Self {
+        self.heapless = enabled;
+        self
+    }
+
+    /// Whether names with non-ASCII characters are
+    /// [transliterated](crate::generate::utils::transliterate) into
+    /// identifiers, e.g. a field `vélocité` into `velocite`.  Enabled by
+    /// default.
+    ///
+    /// When disabled, definitions with such names fail to build, listing
+    /// every one of them, so that generated names always match the
+    /// definition.
+    ///
+    /// ```
+    /// use rasterix_codegen::builder::{Builder, RustBuilder};
+    ///
+    /// let err = RustBuilder::new().transliterate_names(false).build_from_str(r#"<category id="1">
+    ///     <item id="10" frn="1">
+    ///         <fixed bytes="1"><field name="qualité" bits="8"/></fixed>
+    ///     </item>
+    /// </category>"#).unwrap_err();
+    ///
+    /// assert_eq!(err.to_string(), "Non-ASCII names: item 010: field `qualité`");
+    /// ```
+    pub fn transliterate_names(mut self, enabled: bool) -> Self {
+        self.transliterate_names = enabled;
+        self
+    }
+
+    /// Builds the files of the backend `B` instead, e.g. the C API of
+    /// [`FfiBackend`], with the options of this builder.  See
+    /// [`BackendBuilder`].
+    pub fn backend<B: FileBackend>(self) -> BackendBuilder<B> {
+        BackendBuilder { rust: self, backend: PhantomData }
+    }
+
+    /// Generates the code of an XML definition as tokens, for build tools
+    /// that process it further before writing it out.
+    ///
+    /// # Arguments
+    ///
+    /// * `xml` - The XML definition
+    pub fn build_tokens(&self, xml: &str) -> Result<TokenStream, BuildError> {
+        self.generate(xml, None)
+    }
+
+    /// Generates the code of the definition `xml`, read from `file`.
+    fn generate(&self, xml: &str, file: Option<&Path>) -> Result<TokenStream, BuildError> {
+        let lowered = self.lower(xml, file)?;
+
+        // Generate Rust code
+        self.stage(BuildStage::Generate, file, || Ok(self.generate_code(&lowered)))
+    }
+
+    /// Parses, validates and lowers the definition `xml`, read from `file`,
+    /// with the feature and rename configuration applied.
+    fn lower(&self, xml: &str, file: Option<&Path>) -> Result<LoweredIR, BuildError> {
+        // Parse XML into model
+        let category = self.stage(BuildStage::Parse, file, || parse_definition(xml, file, &self.plugins))?;
+
+        // Transform to IR (validates at this stage), lower and tag
+        // feature-gated items
+        self.stage(BuildStage::Transform, file, || {
+            let (ir, warnings) = transform(category, file, &self.plugins)?;
+            for warning in &warnings {
+                self.warn(file, &warning.to_string());
+            }
+            if !self.transliterate_names {
+                let names = ir.category.non_ascii_names();
+                if !names.is_empty() {
+                    return Err(BuildError::new(BuildStage::Transform, file, "Non-ASCII names", names.join("; ")));
+                }
+            }
+
+            let mut lowered = lower_ir(&ir, file)?;
+            if let Some(features_file) = &self.features_file {
+                let config = Self::read_features(features_file)?;
+                apply_features(&mut lowered, &config)
+                    .map_err(|e| BuildError::new(BuildStage::Transform, Some(features_file), "Invalid feature configuration", e))?;
+            }
+            if let Some(rename_file) = &self.rename_file {
+                let config = Self::read_renames(rename_file)?;
+                apply_renames(&mut lowered, &config)
+                    .map_err(|e| BuildError::new(BuildStage::Transform, Some(rename_file), "Invalid rename configuration", e))?;
+            }
+            if self.heapless {
+                use_heapless(&mut lowered);
+            }
+            Ok(lowered)
+        })
+    }
+
+    /// Generates the code of a lowered definition, passed through the
+    /// post-processors.
+    fn generate_code(&self, lowered: &LoweredIR) -> TokenStream {
+        self.post_processors.iter().fold(generate_lowered(lowered), |code, post_process| post_process(code))
+    }
+
+    /// Runs a stage of a build, reporting it to the observer.
+    fn stage<T>(
+        &self,
+        stage: BuildStage,
+        file: Option<&Path>,
+        run: impl FnOnce() -> Result<T, BuildError>,
+    ) -> Result<T, BuildError> {
+        let Some(observer) = &self.observer else {
+            return run();
+        };
+        observer.on_stage_start(stage, file);
+        let start = Instant::now();
+        let result = run()?;
+        observer.on_stage_finish(stage, file, start.elapsed());
+        Ok(result)
+    }
+
+    /// Reports a warning to the observer, or to stderr without one.
+    fn warn(&self, file: Option<&Path>, message: &str) {
+        match &self.observer {
+            Some(observer) => observer.on_warning(file, message),
+            None => eprintln!("Warning: {}{}", file_prefix(file), message),
+        }
+    }
+
+    fn read_features(path: &Path) -> Result<FeatureConfig, BuildError> {
+        parse_features(&read_file(path)?)
+            .map_err(|e| BuildError::new(BuildStage::Parse, Some(path), "Failed to parse feature configuration", e))
+    }
+
+    fn read_renames(path: &Path) -> Result<RenameConfig, BuildError> {
+        parse_renames(&read_file(path)?)
+            .map_err(|e| BuildError::new(BuildStage::Parse, Some(path), "Failed to parse rename configuration", e))
     }
     
     /// Builds code from a single file and writes to output directory.
@@ -68,20 +517,13 @@ impl RustBuilder {
         &self,
         input_path: &str,
         output_dir: &str,
-    ) -> Result<PathBuf, std::io::Error> {
+    ) -> Result<PathBuf, BuildError> {
         let code = self.build(input_path)?;
         
-        // Extract category number from generated code or filename
-        let output_filename = Self::extract_output_filename(input_path);
-        let output_path = PathBuf::from(output_dir).join(output_filename);
-        
-        // Create output directory if needed
-        if let Some(parent) = output_path.parent() {
-            fs::create_dir_all(parent)?;
-        }
+        let output_path = output_path(input_path, output_dir, ".rs");
         
-        // Write generated code
-        fs::write(&output_path, code)?;
+        // Write generated code, creating the output directory if needed
+        self.stage(BuildStage::Io, Some(&output_path), || write_file(&output_path, &code))?;
         
         Ok(output_path)
     }
@@ -100,23 +542,22 @@ impl RustBuilder {
         &self,
         input_dir: &str,
         output_dir: &str,
-    ) -> Result<Vec<PathBuf>, std::io::Error> {
+    ) -> Result<Vec<PathBuf>, BuildError> {
         let mut generated_files = Vec::new();
+        let dir = Path::new(input_dir);
+        let io_error = |e| BuildError::new(BuildStage::Io, Some(dir), "Failed to read directory", e);
         
         // Read directory
-        let entries = fs::read_dir(input_dir)?;
+        let entries = fs::read_dir(dir).map_err(io_error)?;
         
         for entry in entries {
-            let entry = entry?;
+            let entry = entry.map_err(io_error)?;
             let path = entry.path();
             
             // Process only .xml files
             if path.extension().and_then(|s| s.to_str()) == Some("xml") {
                 let input_path = path.to_str()
-                    .ok_or_else(|| std::io::Error::new(
-                        std::io::ErrorKind::InvalidInput,
-                        "Invalid UTF-8 in path"
-                    ))?;
+                    .ok_or_else(|| BuildError::new(BuildStage::Io, Some(&path), "Invalid path", "invalid UTF-8"))?;
                 
                 match self.build_file(input_path, output_dir) {
                     Ok(output_path) => {
@@ -124,7 +565,8 @@ impl RustBuilder {
                         generated_files.push(output_path);
                     }
                     Err(e) => {
-                        eprintln!("Warning: Failed to process {}: {}", input_path, e);
+                        let file = e.file.clone().unwrap_or(path);
+                        self.warn(Some(&file), &format!("Skipped: {}: {}", e.context, e.source));
                     }
                 }
             }
@@ -133,15 +575,42 @@ impl RustBuilder {
         Ok(generated_files)
     }
     
-    /// Extracts the output filename from the input path.
-    /// 
-    /// For example: "cat048.xml" -> "cat048.rs"
-    fn extract_output_filename(input_path: &str) -> String {
-        PathBuf::from(input_path)
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .map(|s| format!("{}.rs", s))
-            .unwrap_or_else(|| "generated.rs".to_string())
+    /// Checks that a definition decodes sample traffic losslessly.
+    ///
+    /// Each data block in `samples` is decoded with a [`DynamicDecoder`]
+    /// and encoded again, so a definition can be validated against live
+    /// traffic before generating code.  The report lists every bit that
+    /// differs, such as non-zero spare bits or fields the definition is
+    /// missing.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - Path to the XML file
+    /// * `samples` - Consecutive data blocks of the category
+    pub fn verify(&self, file_path: &str, samples: &[u8]) -> Result<VerifyReport, BuildError> {
+        let decoder = self.dynamic_decoder(file_path)?;
+
+        Ok(verify_blocks(&decoder, samples))
+    }
+
+    /// Creates a [`DynamicDecoder`] for a definition, to decode or encode
+    /// its messages without generating code.
+    ///
+    /// Symbols are renamed as configured with [`rename_file`](Self::rename_file),
+    /// so the decoder uses the names of the generated code.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - Path to the XML file
+    pub fn dynamic_decoder(&self, file_path: &str) -> Result<DynamicDecoder, BuildError> {
+        let file = Path::new(file_path);
+        let mut lowered = lower_ir(&load_ir(file, &self.plugins)?, Some(file))?;
+        if let Some(path) = &self.rename_file {
+            apply_renames(&mut lowered, &Self::read_renames(path)?)
+                .map_err(|e| BuildError::new(BuildStage::Transform, Some(path), "Invalid rename configuration", e))?;
+        }
+
+        Ok(DynamicDecoder::new(lowered))
     }
 }
 
@@ -151,23 +620,244 @@ impl Default for RustBuilder {
     }
 }
 
+/// Files a [`FileBackend`] produces for a definition.
+#[derive(Debug, Default)]
+pub struct BackendFiles {
+    /// Code compiled together with the generated code, which
+    /// [`BackendBuilder`] appends to it in `<name>.rs`.
+    pub code: Option<TokenStream>,
+    /// Files of their own, by the suffix following the name of the
+    /// definition, e.g. `(".h", header)` for `<name>.h`.
+    pub files: Vec<(&'static str, String)>,
+}
+
+/// A [`CodegenBackend`] whose output a [`BackendBuilder`] writes to files.
+pub trait FileBackend: CodegenBackend + Default {
+    /// Splits the output of the backend for `lowered` into files.
+    fn files(output: Self::Output, lowered: &LoweredIR) -> BackendFiles;
+}
+
+impl FileBackend for FfiBackend {
+    fn files(output: FfiOutput, _lowered: &LoweredIR) -> BackendFiles {
+        BackendFiles { code: Some(output.shims), files: vec![(".h", output.header)] }
+    }
+}
+
+impl FileBackend for WasmBackend {
+    fn files(output: TokenStream, _lowered: &LoweredIR) -> BackendFiles {
+        BackendFiles { code: Some(output), files: Vec::new() }
+    }
+}
+
+impl FileBackend for BorrowedBackend {
+    fn files(output: TokenStream, _lowered: &LoweredIR) -> BackendFiles {
+        BackendFiles { code: Some(output), files: Vec::new() }
+    }
+}
+
+impl FileBackend for ProtoBackend {
+    fn files(output: ProtoOutput, _lowered: &LoweredIR) -> BackendFiles {
+        let conversions = output.conversions;
+        BackendFiles {
+            code: Some(quote! { #[cfg(feature = "prost")] #conversions }),
+            files: vec![(".proto", output.proto)],
+        }
+    }
+}
+
+impl FileBackend for ArrowBackend {
+    fn files(output: TokenStream, _lowered: &LoweredIR) -> BackendFiles {
+        BackendFiles { code: Some(quote! { #[cfg(feature = "arrow")] #output }), files: Vec::new() }
+    }
+}
+
+impl FileBackend for KsyBackend {
+    fn files(output: String, _lowered: &LoweredIR) -> BackendFiles {
+        BackendFiles { code: None, files: vec![(".ksy", output)] }
+    }
+}
+
+impl FileBackend for DocsBackend {
+    fn files(output: String, _lowered: &LoweredIR) -> BackendFiles {
+        BackendFiles { code: None, files: vec![(".md", output)] }
+    }
+}
+
+impl FileBackend for DiagramBackend {
+    fn files(output: Vec<ItemDiagram>, lowered: &LoweredIR) -> BackendFiles {
+        BackendFiles { code: None, files: vec![("_layout.md", diagram_gen::to_markdown(lowered.category_id, &output))] }
+    }
+}
+
+/// Builds the files of a [`FileBackend`] for definitions, with the options
+/// of the [`RustBuilder`] it was created from.
+///
+/// Backends extending the generated code, like the C API of
+/// [`FfiBackend`], write `<name>.rs` with the generated code followed by
+/// theirs; the others, like [`KsyBackend`], only write files of their own:
+///
+/// | Backend              | Files                                  |
+/// |----------------------|----------------------------------------|
+/// | [`FfiBackend`]       | `<name>.rs`, `<name>.h`                |
+/// | [`WasmBackend`]      | `<name>.rs`                            |
+/// | [`BorrowedBackend`]  | `<name>.rs`                            |
+/// | [`ProtoBackend`]     | `<name>.rs`, `<name>.proto`            |
+/// | [`ArrowBackend`]     | `<name>.rs`                            |
+/// | [`KsyBackend`]       | `<name>.ksy`                           |
+/// | [`DocsBackend`]      | `<name>.md`                            |
+/// | [`DiagramBackend`]   | `<name>_layout.md`                     |
+///
+/// The protobuf and Arrow code is compiled only with the `prost` and
+/// `arrow` features of the crate including it.
+///
+/// ```
+/// use rasterix_codegen::builder::{Builder, RustBuilder};
+/// use rasterix_codegen::generate::ksy_gen::KsyBackend;
+///
+/// let ksy = RustBuilder::new().backend::<KsyBackend>().build_from_str(r#"<category id="1">
+///     <item id="10" frn="1">
+///         <fixed bytes="1"><field name="a" bits="8"/></fixed>
+///     </item>
+/// </category>"#).unwrap();
+///
+/// assert!(ksy.contains("id: cat001"));
+/// ```
+pub struct BackendBuilder<B> {
+    rust: RustBuilder,
+    backend: PhantomData<fn() -> B>,
+}
+
+impl<B> fmt::Debug for BackendBuilder<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BackendBuilder")
+            .field("backend", &std::any::type_name::<B>())
+            .field("rust", &self.rust)
+            .finish()
+    }
+}
+
+impl<B> Clone for BackendBuilder<B> {
+    fn clone(&self) -> Self {
+        Self { rust: self.rust.clone(), backend: PhantomData }
+    }
+}
+
+/// Builds the main file of the backend: `<name>.rs` for backends extending
+/// the generated code, its first file of its own otherwise.
+impl<B: FileBackend> Builder for BackendBuilder<B> {
+    fn build_from_str(&self, xml: &str) -> Result<String, BuildError> {
+        Ok(main_file(self.generate(xml, None)?))
+    }
+
+    fn build(&self, file_path: &str) -> Result<String, BuildError> {
+        Ok(main_file(self.build_files(file_path)?))
+    }
+}
+
+impl<B: FileBackend> BackendBuilder<B> {
+    /// Generates every file of the backend for an XML file, as the suffix
+    /// of its name, e.g. `".rs"`, and its contents.
+    pub fn build_files(&self, file_path: &str) -> Result<Vec<(&'static str, String)>, BuildError> {
+        let file = Path::new(file_path);
+        let xml = self.rust.stage(BuildStage::Io, Some(file), || read_file(file))?;
+
+        self.generate(&xml, Some(file))
+    }
+
+    /// Writes the files of the backend to `output_dir`, named after the
+    /// input file, returning their paths.
+    pub fn build_file(&self, input_path: &str, output_dir: &str) -> Result<Vec<PathBuf>, BuildError> {
+        self.build_files(input_path)?
+            .into_iter()
+            .map(|(suffix, contents)| {
+                let path = output_path(input_path, output_dir, suffix);
+                self.rust.stage(BuildStage::Io, Some(&path), || write_file(&path, contents))?;
+                Ok(path)
+            })
+            .collect()
+    }
+
+    /// Generates the files of the definition `xml`, read from `file`.
+    fn generate(&self, xml: &str, file: Option<&Path>) -> Result<Vec<(&'static str, String)>, BuildError> {
+        let lowered = self.rust.lower(xml, file)?;
+
+        self.rust.stage(BuildStage::Generate, file, || {
+            let output = B::files(run_backend(B::default(), &lowered), &lowered);
+            let mut files = Vec::new();
+            if let Some(code) = output.code {
+                files.push((".rs", format!("{}\n{}", self.rust.generate_code(&lowered), code)));
+            }
+            files.extend(output.files);
+            Ok(files)
+        })
+    }
+}
+
+/// The first of the files of a backend.
+fn main_file(files: Vec<(&'static str, String)>) -> String {
+    files.into_iter().next().map(|(_, contents)| contents).unwrap_or_default()
+}
+
+/// Stem of the name of an input file, e.g. `cat048` for `defs/cat048.xml`.
+fn file_stem(input_path: &str) -> &str {
+    Path::new(input_path).file_stem().and_then(|s| s.to_str()).unwrap_or("generated")
+}
+
+/// Path of the output file in `output_dir` named after `input_path`,
+/// followed by `suffix`, e.g. `out/cat048.rs` for a suffix `.rs`.
+fn output_path(input_path: &str, output_dir: &str, suffix: &str) -> PathBuf {
+    Path::new(output_dir).join(format!("{}{}", file_stem(input_path), suffix))
+}
+
+fn read_file(path: &Path) -> Result<String, BuildError> {
+    fs::read_to_string(path).map_err(|e| BuildError::new(BuildStage::Io, Some(path), "Failed to read", e))
+}
+
+/// Writes `contents` to `path`, creating its directory if needed.
+fn write_file(path: &Path, contents: impl AsRef<[u8]>) -> Result<(), BuildError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| BuildError::new(BuildStage::Io, Some(parent), "Failed to create directory", e))?;
+    }
+    fs::write(path, contents).map_err(|e| BuildError::new(BuildStage::Io, Some(path), "Failed to write", e))
+}
+
+fn parse_definition(xml: &str, file: Option<&Path>, plugins: &ElementPlugins) -> Result<Category, BuildError> {
+    parse_category_with_elements(xml, &plugins.tags()).map_err(|e| BuildError::new(BuildStage::Parse, file, "Failed to parse XML", e))
+}
+
+/// Transforms a definition to IR with its custom elements resolved by
+/// `plugins`, failing with its lint errors.  Returns its lint warnings with
+/// the IR.
+fn transform(category: Category, file: Option<&Path>, plugins: &ElementPlugins) -> Result<(IR, Vec<Lint>), BuildError> {
+    let mut ir = to_unvalidated_ir(category).map_err(|e| BuildError::new(BuildStage::Transform, file, "Invalid definition", e))?;
+    apply_plugins(&mut ir, plugins).map_err(|e| BuildError::new(BuildStage::Transform, file, "Invalid custom element", e))?;
+    let (errors, warnings): (Vec<Lint>, Vec<Lint>) =
+        lint(&ir).into_iter().partition(|lint| lint.severity() == Severity::Error);
+    if !errors.is_empty() {
+        let errors: Vec<String> = errors.iter().map(ToString::to_string).collect();
+        return Err(BuildError::new(BuildStage::Transform, file, "Invalid definition", errors.join("; ")));
+    }
+    Ok((ir, warnings))
+}
+
+fn lower_ir(ir: &IR, file: Option<&Path>) -> Result<LoweredIR, BuildError> {
+    lower(ir).map_err(|e| BuildError::new(BuildStage::Transform, file, "Invalid definition", e))
+}
+
+/// Reads, parses and transforms the definition in `file`.
+fn load_ir(file: &Path, plugins: &ElementPlugins) -> Result<IR, BuildError> {
+    Ok(transform(parse_definition(&read_file(file)?, Some(file), plugins)?, Some(file), plugins)?.0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     
     #[test]
-    fn test_extract_output_filename() {
-        assert_eq!(
-            RustBuilder::extract_output_filename("cat048.xml"),
-            "cat048.rs"
-        );
-        assert_eq!(
-            RustBuilder::extract_output_filename("/path/to/cat001.xml"),
-            "cat001.rs"
-        );
-        assert_eq!(
-            RustBuilder::extract_output_filename("test.xml"),
-            "test.rs"
-        );
-    }
-}
\ No newline at end of file
+    fn test_output_path() {
+        assert_eq!(output_path("cat048.xml", "out", ".rs"), Path::new("out/cat048.rs"));
+        assert_eq!(output_path("/path/to/cat001.xml", "out", ".h"), Path::new("out/cat001.h"));
+        assert_eq!(output_path("test.xml", "docs", "_layout.md"), Path::new("docs/test_layout.md"));
+    }
+}
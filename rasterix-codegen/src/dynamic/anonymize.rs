@@ -0,0 +1,284 @@
+//! Anonymization of recorded traffic.
+//!
+//! Operational recordings identify aircraft by their callsign, Mode S
+//! address and Mode 3/A code.  [`Anonymizer`] replaces these with
+//! pseudonyms derived from a secret key, then re-encodes the data blocks,
+//! so recordings can be shared without the identities they hold.
+//!
+//! The mapping is deterministic: with the same key, an aircraft gets the
+//! same pseudonyms in every record and every recording, so tracks stay
+//! consistent.  Addresses and Mode 3/A codes are permuted, so two aircraft
+//! never share a pseudonym; the emergency codes 7500, 7600 and 7700 are
+//! kept.  The mapping keeps identities from casual readers, but it is not a
+//! cryptographic one: keep the key secret, and don't reuse it across
+//! recordings shared with different parties.
+//!
+//! Fields are recognized by their definition:
+//!
+//! - Mode S addresses are the fields of `type="icao"`;
+//! - callsigns are the string fields;
+//! - Mode 3/A codes are the 12-bit fields named as set with
+//!   [`with_mode3a_fields`](Anonymizer::with_mode3a_fields), by default
+//!   [`DEFAULT_MODE3A_FIELDS`].
+//!
+//! # Example
+//!
+//! ```
+//! use rasterix_codegen::dynamic::anonymize::Anonymizer;
+//!
+//! let anonymizer = Anonymizer::new(b"secret");
+//! let pseudonym = anonymizer.callsign("BAW123");
+//! assert_ne!(pseudonym, "BAW123");
+//! assert_eq!(pseudonym.len(), 6);
+//! assert_eq!(anonymizer.callsign("BAW123"), pseudonym);
+//! assert_eq!(anonymizer.mode3a(0o7700), 0o7700);
+//! ```
+
+use rasterix_core::icao::IcaoAddress;
+use rasterix_core::{BitReader, BitWriter, DecodeError};
+
+use crate::transform::lower_ir::{FieldDescriptor, LoweredItemKind, LoweredPart, LoweredSubItemKind, ValueType};
+use super::value::{DynamicItem, DynamicRecord, Field, FieldValue};
+use super::DynamicDecoder;
+
+/// Names of the fields holding Mode 3/A codes unless set otherwise.
+pub const DEFAULT_MODE3A_FIELDS: &[&str] = &["mode3a", "mode_3a", "mode3a_code"];
+
+/// Mode 3/A codes kept as they are: unlawful interference, radio failure
+/// and emergency.
+const KEPT_MODE3A_CODES: [u16; 3] = [0o7500, 0o7600, 0o7700];
+
+/// Domains separating the pseudonyms of each kind of identity.
+const ADDRESS: u64 = 1;
+const MODE3A: u64 = 2;
+const CALLSIGN: u64 = 3;
+
+/// Replaces the identities in records with keyed pseudonyms.
+#[derive(Debug, Clone)]
+pub struct Anonymizer {
+    key: u64,
+    mode3a_fields: Vec<String>,
+}
+
+impl Anonymizer {
+    /// Creates an anonymizer deriving its pseudonyms from `secret`.
+    pub fn new(secret: &[u8]) -> Self {
+        let key = secret.iter().fold(0x5241_5354_4552_4958, |key, &byte| mix(key ^ byte as u64));
+        Self {
+            key,
+            mode3a_fields: DEFAULT_MODE3A_FIELDS.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+
+    /// Treats the 12-bit fields named `names` as Mode 3/A codes, instead of
+    /// [`DEFAULT_MODE3A_FIELDS`].
+    pub fn with_mode3a_fields<S: Into<String>>(mut self, names: impl IntoIterator<Item = S>) -> Self {
+        self.mode3a_fields = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Returns the pseudonym of a Mode S address.
+    pub fn address(&self, address: IcaoAddress) -> IcaoAddress {
+        IcaoAddress::from_raw(self.permute(ADDRESS, address.to_raw(), 24))
+    }
+
+    /// Returns the pseudonym of a 12-bit Mode 3/A code.  Emergency codes are
+    /// kept, and no other code becomes one.
+    pub fn mode3a(&self, code: u16) -> u16 {
+        let code = code & 0o7777;
+        if KEPT_MODE3A_CODES.contains(&code) {
+            return code;
+        }
+        // Walking the cycle of the permutation past kept codes keeps it one
+        // to one on the others.
+        let mut pseudonym = code as u64;
+        loop {
+            pseudonym = self.permute(MODE3A, pseudonym, 12);
+            if !KEPT_MODE3A_CODES.contains(&(pseudonym as u16)) {
+                return pseudonym as u16;
+            }
+        }
+    }
+
+    /// Returns the pseudonym of a callsign: letters are replaced by letters
+    /// and digits by digits, other characters are kept.
+    pub fn callsign(&self, callsign: &str) -> String {
+        let seed = callsign.bytes().fold(self.hash(CALLSIGN, 0), |seed, byte| mix(seed ^ byte as u64));
+        callsign
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                let draw = mix(seed ^ i as u64);
+                match c {
+                    'A'..='Z' => (b'A' + (draw % 26) as u8) as char,
+                    '0'..='9' => (b'0' + (draw % 10) as u8) as char,
+                    c => c,
+                }
+            })
+            .collect()
+    }
+
+    /// Replaces the identities in `record`, decoded by `decoder`.
+    pub fn anonymize_record(&self, decoder: &DynamicDecoder, record: &mut DynamicRecord) {
+        for (item, value) in decoder.record_items().zip(&mut record.items) {
+            if let Some(value) = value {
+                self.anonymize_item(&item.kind, value);
+            }
+        }
+    }
+
+    /// Decodes a data block with `decoder`, replaces the identities in its
+    /// records and encodes it again.
+    pub fn anonymize_block(&self, decoder: &DynamicDecoder, block: &[u8]) -> Result<Vec<u8>, DecodeError> {
+        let mut decoded = decoder.decode_block(&mut BitReader::new(block))?;
+        for record in &mut decoded.records {
+            self.anonymize_record(decoder, record);
+        }
+
+        let mut bytes = Vec::with_capacity(block.len());
+        let mut writer = BitWriter::new(&mut bytes);
+        decoder.encode_block(&decoded, &mut writer)?;
+        writer.flush()?;
+        Ok(bytes)
+    }
+
+    fn anonymize_item(&self, kind: &LoweredItemKind, item: &mut DynamicItem) {
+        match (kind, item) {
+            (LoweredItemKind::Simple { fields, .. }, DynamicItem::Fields(values)) => {
+                self.anonymize_fields(fields, values);
+            }
+            (LoweredItemKind::Extended { parts }, DynamicItem::Extended(values, _)) => {
+                self.anonymize_parts(parts, values);
+            }
+            (LoweredItemKind::Repetitive { fields, .. }, DynamicItem::Repetitive(elements)) => {
+                elements.iter_mut().for_each(|values| self.anonymize_fields(fields, values));
+            }
+            (LoweredItemKind::Compound { sub_items }, DynamicItem::Compound(values)) => {
+                for (sub, value) in sub_items.iter().zip(values) {
+                    match (&sub.kind, value) {
+                        (LoweredSubItemKind::Simple { fields, .. }, Some(DynamicItem::Fields(values))) => {
+                            self.anonymize_fields(fields, values);
+                        }
+                        (LoweredSubItemKind::Extended { parts }, Some(DynamicItem::Extended(values, _))) => {
+                            self.anonymize_parts(parts, values);
+                        }
+                        (LoweredSubItemKind::Repetitive { fields, .. }, Some(DynamicItem::Repetitive(elements))) => {
+                            elements.iter_mut().for_each(|values| self.anonymize_fields(fields, values));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn anonymize_parts(&self, parts: &[LoweredPart], values: &mut [Option<Vec<Field>>]) {
+        for (part, values) in parts.iter().zip(values) {
+            if let Some(values) = values {
+                self.anonymize_fields(&part.fields, values);
+            }
+        }
+    }
+
+    fn anonymize_fields(&self, descriptors: &[FieldDescriptor], fields: &mut [Field]) {
+        for field in fields {
+            let Some(descriptor) = descriptors.iter().find(|d| d.name == field.name) else {
+                continue;
+            };
+            let is_address = descriptor.typed.is_some_and(|typed| typed.value_type == ValueType::Icao);
+            let is_mode3a = self.mode3a_fields.contains(&field.name);
+
+            match &mut field.value {
+                FieldValue::String(callsign) | FieldValue::EpbString(Some(callsign)) => {
+                    *callsign = self.callsign(callsign);
+                }
+                FieldValue::Unsigned(value) | FieldValue::Epb(Some(value)) => {
+                    if is_address {
+                        *value = self.address(IcaoAddress::from_raw(*value)).to_raw();
+                    } else if is_mode3a && *value <= 0o7777 {
+                        *value = self.mode3a(*value as u16) as u64;
+                    }
+                }
+                // Byte arrays are opaque and kept as they are.
+                FieldValue::Bytes(_) | FieldValue::EpbBytes(_) => {}
+                FieldValue::Epb(None) | FieldValue::EpbString(None) => {}
+            }
+        }
+    }
+
+    /// Permutes the values of `bits` bits, an even number, with a Feistel
+    /// network keyed by the domain.
+    fn permute(&self, domain: u64, value: u64, bits: u32) -> u64 {
+        let half = bits / 2;
+        let mask = (1 << half) - 1;
+        let (mut left, mut right) = (value >> half & mask, value & mask);
+        for round in 0..4 {
+            let f = self.hash(domain, round << 32 | right) & mask;
+            (left, right) = (right, left ^ f);
+        }
+        left << half | right
+    }
+
+    fn hash(&self, domain: u64, value: u64) -> u64 {
+        mix(mix(self.key ^ domain) ^ value)
+    }
+}
+
+/// The SplitMix64 finalizer.
+fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parser::parse_category;
+    use crate::transform::transformer::to_ir;
+
+    #[test]
+    fn pseudonyms_are_one_to_one() {
+        let anonymizer = Anonymizer::new(b"key");
+
+        let mut codes: Vec<u16> = (0..=0o7777).map(|code| anonymizer.mode3a(code)).collect();
+        assert_eq!(codes[0o7500], 0o7500);
+        assert_ne!(codes[0o1234], 0o1234);
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes.len(), 0o10000);
+
+        let address = IcaoAddress::new(0x4CA123).unwrap();
+        assert_ne!(anonymizer.address(address), address);
+        assert_eq!(anonymizer.address(address), anonymizer.address(address));
+        assert_ne!(Anonymizer::new(b"other").address(address), anonymizer.address(address));
+    }
+
+    #[test]
+    fn block_identities_are_replaced() {
+        let decoder = DynamicDecoder::from_ir(&to_ir(parse_category(r#"<category id="48">
+            <item id="10" frn="1"><fixed bytes="2"><field name="sac" bits="8"/><field name="sic" bits="8"/></fixed></item>
+            <item id="70" frn="2"><fixed bytes="2"><spare bits="4"/><field name="mode3a" bits="12"/></fixed></item>
+            <item id="220" frn="3"><fixed bytes="3"><field name="address" bits="24" type="icao"/></fixed></item>
+            <item id="240" frn="4"><fixed bytes="6"><field name="callsign" bits="48" type="string"/></fixed></item>
+        </category>"#).unwrap()).unwrap()).unwrap();
+        let anonymizer = Anonymizer::new(b"key");
+
+        let mut block = vec![0x30, 0x00, 0x11, 0xF0, 0x01, 0x02, 0x0A, 0x9C, 0x4C, 0xA1, 0x23];
+        block.extend(b"BAW1  ");
+        let anonymized = anonymizer.anonymize_block(&decoder, &block).unwrap();
+
+        assert_eq!(anonymized.len(), block.len());
+        assert_eq!(anonymized[..6], block[..6]);
+        let decoded = decoder.decode_block(&mut BitReader::new(&anonymized[..])).unwrap();
+        let field = |item: usize| match &decoded.records[0].items[item] {
+            Some(DynamicItem::Fields(fields)) => fields[0].value.clone(),
+            other => panic!("unexpected item {:?}", other),
+        };
+        assert_eq!(field(1), FieldValue::Unsigned(anonymizer.mode3a(0o5234) as u64));
+        assert_eq!(field(2), FieldValue::Unsigned(anonymizer.address(IcaoAddress::new(0x4CA123).unwrap()).to_raw()));
+        assert_eq!(field(3), FieldValue::String(anonymizer.callsign("BAW1")));
+    }
+}
@@ -0,0 +1,379 @@
+//! Coverage of a definition by recorded traffic.
+//!
+//! [`Coverage`] counts how often the records decoded by a
+//! [`DynamicDecoder`] hold each item, optional extended part, compound
+//! sub-item and enum variant of the category.  The ones never observed show
+//! what test data doesn't exercise: a decoder can only be trusted with the
+//! parts of a definition its test traffic covers.
+//!
+//! # Example
+//!
+//! ```
+//! use rasterix_codegen::dynamic::DynamicDecoder;
+//! use rasterix_codegen::dynamic::coverage::Coverage;
+//! use rasterix_codegen::parse::parser::parse_category;
+//! use rasterix_codegen::transform::transformer::to_ir;
+//! use rasterix_core::BitReader;
+//!
+//! let xml = r#"<category id="48">
+//!     <item id="10" frn="1">
+//!         <fixed bytes="2"><field name="sac" bits="8"/><field name="sic" bits="8"/></fixed>
+//!     </item>
+//!     <item id="20" frn="2">
+//!         <fixed bytes="1"><field name="typ" bits="8"/></fixed>
+//!     </item>
+//! </category>"#;
+//! let decoder = DynamicDecoder::from_ir(&to_ir(parse_category(xml).unwrap()).unwrap()).unwrap();
+//!
+//! let mut coverage = Coverage::new(&decoder);
+//! let block = decoder.decode_block(&mut BitReader::new(&[48, 0, 6, 0x80, 25, 7][..])).unwrap();
+//! coverage.add_block(&decoder, &block);
+//!
+//! let unobserved: Vec<_> = coverage.unobserved().map(|entry| entry.to_string()).collect();
+//! assert_eq!(unobserved, ["item020"]);
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::transform::lower_ir::{
+    FieldDescriptor, FieldType, LoweredEnum, LoweredItemKind, LoweredPart, LoweredSubItemKind,
+};
+use super::value::{DynamicBlock, DynamicItem, DynamicRecord, Field, FieldValue};
+use super::DynamicDecoder;
+
+/// Something a definition allows records to hold, and how often they did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverageEntry {
+    /// Dotted path of the item, part or sub-item (`item020.part1`), or of
+    /// the field of an enum variant.
+    pub path: String,
+    /// Name of the variant of the enum field at `path`, for enum variants.
+    pub variant: Option<String>,
+    /// Number of records holding it.
+    pub count: u64,
+}
+
+impl fmt::Display for CoverageEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.variant {
+            Some(variant) => write!(f, "{} = {}", self.path, variant),
+            None => write!(f, "{}", self.path),
+        }
+    }
+}
+
+/// Counts of the items, optional parts, sub-items and enum variants of a
+/// category observed in records.
+///
+/// Required parts are counted with their item.  Enum variants are counted
+/// wherever their field is, including in repetitive elements; raw values
+/// without a variant are not counted.
+#[derive(Debug, Clone)]
+pub struct Coverage {
+    category: u8,
+    records: u64,
+    /// Entries in definition order.
+    entries: Vec<CoverageEntry>,
+    /// Position of each entry in `entries`, by its display form.
+    index: HashMap<String, usize>,
+}
+
+impl Coverage {
+    /// Lists everything the category of `decoder` allows, none observed
+    /// yet.
+    pub fn new(decoder: &DynamicDecoder) -> Self {
+        let mut coverage = Self {
+            category: decoder.lowered().category_id,
+            records: 0,
+            entries: Vec::new(),
+            index: HashMap::new(),
+        };
+
+        for (entry, item) in decoder.lowered().record.entries.iter().zip(decoder.record_items()) {
+            let path = entry.field_name.to_string();
+            coverage.push(&path, None);
+            match &item.kind {
+                LoweredItemKind::Simple { fields, .. } | LoweredItemKind::Repetitive { fields, .. } => {
+                    coverage.push_variants(&path, fields, &item.enums);
+                }
+                LoweredItemKind::Extended { parts } => coverage.push_parts(&path, parts, &item.enums),
+                LoweredItemKind::Compound { sub_items } => {
+                    for sub in sub_items {
+                        let path = format!("{}.{}", path, sub.field_name);
+                        coverage.push(&path, None);
+                        match &sub.kind {
+                            LoweredSubItemKind::Simple { fields, .. } | LoweredSubItemKind::Repetitive { fields, .. } => {
+                                coverage.push_variants(&path, fields, &sub.enums);
+                            }
+                            LoweredSubItemKind::Extended { parts } => coverage.push_parts(&path, parts, &sub.enums),
+                        }
+                    }
+                }
+            }
+        }
+        coverage
+    }
+
+    fn push(&mut self, path: &str, variant: Option<&str>) {
+        let entry = CoverageEntry { path: path.to_string(), variant: variant.map(str::to_string), count: 0 };
+        self.index.insert(entry.to_string(), self.entries.len());
+        self.entries.push(entry);
+    }
+
+    fn push_parts(&mut self, path: &str, parts: &[LoweredPart], enums: &[LoweredEnum]) {
+        for part in parts {
+            let path = format!("{}.{}", path, part.field_name);
+            if !part.is_required {
+                self.push(&path, None);
+            }
+            self.push_variants(&path, &part.fields, enums);
+        }
+    }
+
+    fn push_variants(&mut self, path: &str, fields: &[FieldDescriptor], enums: &[LoweredEnum]) {
+        for field in fields {
+            let Some(lowered) = field_enum(field, enums) else { continue };
+            let path = format!("{}.{}", path, field.name);
+            for variant in &lowered.variants {
+                self.push(&path, Some(&variant.name.to_string()));
+            }
+        }
+    }
+
+    /// Category of the definition.
+    pub fn category(&self) -> u8 {
+        self.category
+    }
+
+    /// Number of records added.
+    pub fn records(&self) -> u64 {
+        self.records
+    }
+
+    /// Every entry of the definition, in definition order.
+    pub fn entries(&self) -> &[CoverageEntry] {
+        &self.entries
+    }
+
+    /// Entries no record held.
+    pub fn unobserved(&self) -> impl Iterator<Item = &CoverageEntry> {
+        self.entries.iter().filter(|entry| entry.count == 0)
+    }
+
+    /// Returns `true` if every entry was observed.
+    pub fn is_complete(&self) -> bool {
+        self.unobserved().next().is_none()
+    }
+
+    /// Counts what the records of `block`, decoded by `decoder`, hold.
+    pub fn add_block(&mut self, decoder: &DynamicDecoder, block: &DynamicBlock) {
+        for record in &block.records {
+            self.add_record(decoder, record);
+        }
+    }
+
+    /// Counts what `record`, decoded by `decoder`, holds.
+    ///
+    /// Each entry is counted once per record, however often it occurs in
+    /// the record.
+    pub fn add_record(&mut self, decoder: &DynamicDecoder, record: &DynamicRecord) {
+        self.records += 1;
+        let mut observed = Vec::new();
+        let items = decoder.lowered().record.entries.iter().zip(decoder.record_items()).zip(&record.items);
+        for ((entry, item), value) in items {
+            let Some(value) = value else { continue };
+            let path = entry.field_name.to_string();
+            observed.push(path.clone());
+            match (&item.kind, value) {
+                (LoweredItemKind::Simple { fields, .. }, DynamicItem::Fields(values)) => {
+                    observe_variants(&path, fields, &item.enums, values, &mut observed);
+                }
+                (LoweredItemKind::Repetitive { fields, .. }, DynamicItem::Repetitive(elements)) => {
+                    for values in elements {
+                        observe_variants(&path, fields, &item.enums, values, &mut observed);
+                    }
+                }
+                (LoweredItemKind::Extended { parts }, DynamicItem::Extended(values, _)) => {
+                    observe_parts(&path, parts, &item.enums, values, &mut observed);
+                }
+                (LoweredItemKind::Compound { sub_items }, DynamicItem::Compound(values)) => {
+                    for (sub, value) in sub_items.iter().zip(values) {
+                        let Some(value) = value else { continue };
+                        let path = format!("{}.{}", path, sub.field_name);
+                        observed.push(path.clone());
+                        match (&sub.kind, value) {
+                            (LoweredSubItemKind::Simple { fields, .. }, DynamicItem::Fields(values)) => {
+                                observe_variants(&path, fields, &sub.enums, values, &mut observed);
+                            }
+                            (LoweredSubItemKind::Repetitive { fields, .. }, DynamicItem::Repetitive(elements)) => {
+                                for values in elements {
+                                    observe_variants(&path, fields, &sub.enums, values, &mut observed);
+                                }
+                            }
+                            (LoweredSubItemKind::Extended { parts }, DynamicItem::Extended(values, _)) => {
+                                observe_parts(&path, parts, &sub.enums, values, &mut observed);
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        observed.sort_unstable();
+        observed.dedup();
+        for key in observed {
+            if let Some(&position) = self.index.get(&key) {
+                self.entries[position].count += 1;
+            }
+        }
+    }
+}
+
+impl fmt::Display for Coverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in self.unobserved() {
+            writeln!(f, "never observed: {}", entry)?;
+        }
+        let observed = self.entries.len() - self.unobserved().count();
+        write!(
+            f,
+            "category {}: {} of {} element(s) observed in {} record(s)",
+            self.category,
+            observed,
+            self.entries.len(),
+            self.records
+        )
+    }
+}
+
+/// Returns the enum of an enum field.
+fn field_enum<'a>(field: &FieldDescriptor, enums: &'a [LoweredEnum]) -> Option<&'a LoweredEnum> {
+    let (FieldType::Enum(name) | FieldType::OptionalEnum(name)) = &field.type_tokens else {
+        return None;
+    };
+    enums.iter().find(|lowered| lowered.name == *name)
+}
+
+fn observe_parts(
+    path: &str,
+    parts: &[LoweredPart],
+    enums: &[LoweredEnum],
+    values: &[Option<Vec<Field>>],
+    observed: &mut Vec<String>,
+) {
+    for (part, values) in parts.iter().zip(values) {
+        let Some(values) = values else { continue };
+        let path = format!("{}.{}", path, part.field_name);
+        observed.push(path.clone());
+        observe_variants(&path, &part.fields, enums, values, observed);
+    }
+}
+
+fn observe_variants(
+    path: &str,
+    fields: &[FieldDescriptor],
+    enums: &[LoweredEnum],
+    values: &[Field],
+    observed: &mut Vec<String>,
+) {
+    for value in values {
+        let (FieldValue::Unsigned(raw) | FieldValue::Epb(Some(raw))) = value.value else { continue };
+        let Some(field) = fields.iter().find(|field| field.name == value.name) else { continue };
+        let Some(lowered) = field_enum(field, enums) else { continue };
+        if let Some(variant) = lowered.variants.iter().find(|variant| variant.value as u64 == raw) {
+            observed.push(format!("{}.{} = {}", path, field.name, variant.name));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parser::parse_category;
+    use crate::transform::transformer::to_ir;
+    use rasterix_core::BitReader;
+
+    fn decoder() -> DynamicDecoder {
+        DynamicDecoder::from_ir(&to_ir(parse_category(r#"<category id="48">
+            <item id="20" frn="1">
+                <extended bytes="2">
+                    <part index="0">
+                        <enum name="typ" bits="3">
+                            <value name="PSR" value="1"/>
+                            <value name="SSR" value="2"/>
+                        </enum>
+                        <spare bits="4"/>
+                    </part>
+                    <part index="1">
+                        <field name="tst" bits="7"/>
+                    </part>
+                </extended>
+            </item>
+            <item id="40" frn="2">
+                <compound>
+                    <fixed bytes="1"><field name="a" bits="8"/></fixed>
+                    <repetitive bytes="1" counter="2">
+                        <epb>
+                            <enum name="mode" bits="7">
+                                <value name="ON" value="1"/>
+                                <value name="OFF" value="2"/>
+                            </enum>
+                        </epb>
+                    </repetitive>
+                </compound>
+            </item>
+        </category>"#).unwrap()).unwrap()).unwrap()
+    }
+
+    fn coverage(blocks: &[&[u8]]) -> Coverage {
+        let decoder = decoder();
+        let mut coverage = Coverage::new(&decoder);
+        for block in blocks {
+            coverage.add_block(&decoder, &decoder.decode_block(&mut BitReader::new(*block)).unwrap());
+        }
+        coverage
+    }
+
+    #[test]
+    fn lists_items_parts_sub_items_and_variants() {
+        let coverage = coverage(&[]);
+        let entries: Vec<_> = coverage.entries().iter().map(|entry| entry.to_string()).collect();
+        assert_eq!(entries, [
+            "item020",
+            "item020.part0.typ = Psr",
+            "item020.part0.typ = Ssr",
+            "item020.part1",
+            "item040",
+            "item040.sub0",
+            "item040.sub1",
+            "item040.sub1.mode = On",
+            "item040.sub1.mode = Off",
+        ]);
+        assert!(!coverage.is_complete());
+    }
+
+    #[test]
+    fn counts_observations_once_per_record() {
+        // A PSR plot, then an SSR plot with a test part and a compound item
+        // whose two elements are both ON.
+        let coverage = coverage(&[
+            &[48, 0, 5, 0x80, 0x20],
+            &[48, 0, 9, 0xC0, 0x41, 0x00, 0x40, 0x81, 0x81],
+        ]);
+
+        let unobserved: Vec<_> = coverage.unobserved().map(|entry| entry.to_string()).collect();
+        assert_eq!(unobserved, ["item040.sub0", "item040.sub1.mode = Off"]);
+        assert_eq!(coverage.records(), 2);
+        assert_eq!(coverage.entries()[0].count, 2);
+        assert_eq!(coverage.entries()[7].count, 1);
+        assert_eq!(
+            coverage.to_string(),
+            "never observed: item040.sub0\n\
+             never observed: item040.sub1.mode = Off\n\
+             category 48: 7 of 9 element(s) observed in 2 record(s)"
+        );
+    }
+}
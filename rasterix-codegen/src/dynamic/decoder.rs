@@ -0,0 +1,704 @@
+use std::io::{Cursor, Read, Write};
+
+use rasterix_core::{policy, BitReader, BitWrite, BitWriter, DecodeError, Fspec};
+
+use crate::transform::ir::IR;
+use crate::transform::lower;
+use crate::transform::lower_ir::{
+    DecodeOp, EncodeOp, LoweredIR, LoweredItem, LoweredItemKind, LoweredPart, LoweredSubItem,
+    LoweredSubItemKind,
+};
+use super::value::{DynamicBlock, DynamicItem, DynamicRecord, Field, FieldValue};
+
+const VALUE_MISMATCH: &str = "value does not match the definition";
+
+/// Decodes and encodes a category by interpreting its lowered IR.
+///
+/// Every read and write mirrors the generated code for the same definition,
+/// so decoding a message and encoding it again produces the same bytes the
+/// generated types would.
+///
+/// # Example
+///
+/// ```
+/// use rasterix_codegen::dynamic::{DynamicDecoder, DynamicItem, FieldValue};
+/// use rasterix_codegen::parse::parser::parse_category;
+/// use rasterix_codegen::transform::transformer::to_ir;
+/// use rasterix_core::BitReader;
+///
+/// let xml = r#"<category id="48">
+///     <item id="10" frn="1">
+///         <fixed bytes="2">
+///             <field name="sac" bits="8"/>
+///             <field name="sic" bits="8"/>
+///         </fixed>
+///     </item>
+/// </category>"#;
+///
+/// let decoder = DynamicDecoder::from_ir(&to_ir(parse_category(xml).unwrap()).unwrap()).unwrap();
+/// let data = [0x30, 0x00, 0x06, 0x80, 0x01, 0x02];
+/// let block = decoder.decode_block(&mut BitReader::new(&data[..])).unwrap();
+///
+/// let Some(DynamicItem::Fields(fields)) = &block.records[0].items[0] else { panic!() };
+/// assert_eq!(fields[1].name, "sic");
+/// assert_eq!(fields[1].value, FieldValue::Unsigned(2));
+/// ```
+#[derive(Debug, Clone)]
+pub struct DynamicDecoder {
+    lowered: LoweredIR,
+    /// Index into `lowered.items` for each record entry.
+    item_index: Vec<usize>,
+}
+
+impl DynamicDecoder {
+    /// Creates a decoder for a lowered category.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a record entry refers to an item type that isn't defined.
+    pub fn new(lowered: LoweredIR) -> Self {
+        let item_index = lowered
+            .record
+            .entries
+            .iter()
+            .map(|entry| {
+                lowered
+                    .items
+                    .iter()
+                    .position(|item| item.name == entry.type_name)
+                    .unwrap_or_else(|| panic!("record refers to undefined item {}", entry.type_name))
+            })
+            .collect();
+        Self { lowered, item_index }
+    }
+
+    /// Creates a decoder for a category IR, failing if it can't be
+    /// [lowered](lower).
+    pub fn from_ir(ir: &IR) -> Result<Self, String> {
+        Ok(Self::new(lower(ir)?))
+    }
+
+    /// Returns the lowered category being interpreted.
+    pub fn lowered(&self) -> &LoweredIR {
+        &self.lowered
+    }
+
+    /// Returns the item of each record entry, in FRN order.
+    pub(crate) fn record_items(&self) -> impl Iterator<Item = &LoweredItem> {
+        self.item_index.iter().map(|&index| &self.lowered.items[index])
+    }
+
+    /// Decodes a data block, including its CAT/LEN header.
+    pub fn decode_block<R: Read>(&self, reader: &mut BitReader<R>) -> Result<DynamicBlock, DecodeError> {
+        let cat = reader.read_bits(8)? as u8;
+        if cat != self.lowered.category_id {
+            return Err(DecodeError::InvalidData("category mismatch"));
+        }
+
+        let len = reader.read_bits(16)? as u16;
+        if len < 3 {
+            return Err(DecodeError::InvalidData("data block length too small"));
+        }
+
+        let payload_len = (len - 3) as usize;
+        let mut payload = vec![0u8; payload_len];
+        for byte in payload.iter_mut() {
+            *byte = reader.read_bits(8)? as u8;
+        }
+
+        let mut records = Vec::new();
+        let mut cursor = Cursor::new(payload);
+        while cursor.position() < payload_len as u64 {
+            let position = cursor.position() as usize;
+            let offset = 3 + position;
+            // Zero padding accepted by the policy ends the block
+            if !policy::block_rest(&cursor.get_ref()[position..], offset, len as usize)? {
+                break;
+            }
+            let record = self.decode_record(&mut BitReader::new(&mut cursor))
+                .map_err(|e| e.in_record(offset, len as usize))?;
+            records.push(record);
+        }
+
+        Ok(DynamicBlock { records })
+    }
+
+    /// Decodes one record: the FSPEC and the items it announces.
+    pub fn decode_record<R: Read>(&self, reader: &mut BitReader<R>) -> Result<DynamicRecord, DecodeError> {
+        let fspec = Fspec::read(reader)?;
+
+        let mut items = Vec::with_capacity(self.item_index.len());
+        for (entry, &index) in self.lowered.record.entries.iter().zip(&self.item_index) {
+            items.push(if fspec.is_frn_set(entry.frn) {
+                Some(decode_item(reader, &self.lowered.items[index].kind)?)
+            } else {
+                None
+            });
+        }
+
+        Ok(DynamicRecord { items })
+    }
+
+    /// Encodes a data block, including its CAT/LEN header.
+    pub fn encode_block<W: Write>(&self, block: &DynamicBlock, writer: &mut BitWriter<W>) -> Result<(), DecodeError> {
+        let mut record_buf = Vec::new();
+        {
+            let mut record_writer = BitWriter::new(&mut record_buf);
+            for record in &block.records {
+                self.encode_record(record, &mut record_writer)?;
+            }
+            record_writer.flush()?;
+        }
+
+        // LEN = 1 (CAT) + 2 (LEN) + record bytes
+        let total_len = u16::try_from(3 + record_buf.len())
+            .map_err(|_| DecodeError::LimitExceeded { limit: "data block length", max: u16::MAX as usize })?;
+        writer.write_bits(self.lowered.category_id as u64, 8)?;
+        writer.write_bits(total_len as u64, 16)?;
+        for &byte in &record_buf {
+            writer.write_bits(byte as u64, 8)?;
+        }
+
+        Ok(())
+    }
+
+    /// Encodes one record: the FSPEC and the items present.
+    pub fn encode_record<W: Write>(&self, record: &DynamicRecord, writer: &mut BitWriter<W>) -> Result<(), DecodeError> {
+        if record.items.len() != self.item_index.len() {
+            return Err(DecodeError::InvalidData(VALUE_MISMATCH));
+        }
+
+        let mut fspec = Fspec::new();
+        for (entry, item) in self.lowered.record.entries.iter().zip(&record.items) {
+            if item.is_some() {
+                fspec.set_frn(entry.frn);
+            }
+        }
+        fspec.write(writer)?;
+
+        for (item, &index) in record.items.iter().zip(&self.item_index) {
+            if let Some(item) = item {
+                encode_item(writer, &self.lowered.items[index].kind, item)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// ── Decoding ──────────────────────────────────────────────────────────────
+
+fn decode_fields<R: Read>(reader: &mut BitReader<R>, ops: &[DecodeOp]) -> Result<Vec<Field>, DecodeError> {
+    let mut fields = Vec::new();
+    for op in ops {
+        let (name, value) = match op {
+            DecodeOp::ReadField { name, bits, little_endian: true, .. } => {
+                (name, FieldValue::Unsigned(reader.read_bits_le(*bits)?))
+            }
+            DecodeOp::ReadField { name, bits, .. } | DecodeOp::ReadEnum { name, bits, .. } => {
+                (name, FieldValue::Unsigned(reader.read_bits(*bits)?))
+            }
+            DecodeOp::ReadEpbField { name, bits, little_endian: true, .. } => {
+                let valid = reader.read_bits(1)? != 0;
+                let value = reader.read_bits_le(*bits)?;
+                (name, FieldValue::Epb(valid.then_some(value)))
+            }
+            DecodeOp::ReadEpbField { name, bits, .. } | DecodeOp::ReadEpbEnum { name, bits, .. } => {
+                let valid = reader.read_bits(1)? != 0;
+                let value = reader.read_bits(*bits)?;
+                (name, FieldValue::Epb(valid.then_some(value)))
+            }
+            DecodeOp::ReadString { name, byte_len } => {
+                (name, FieldValue::String(reader.read_string(*byte_len)?))
+            }
+            DecodeOp::ReadEpbString { name, byte_len } => {
+                let valid = reader.read_bits(1)? != 0;
+                let value = reader.read_string(*byte_len)?;
+                (name, FieldValue::EpbString(valid.then_some(value)))
+            }
+            DecodeOp::ReadBytes { name, byte_len } => {
+                let mut value = vec![0u8; *byte_len];
+                reader.read_bytes(&mut value)?;
+                (name, FieldValue::Bytes(value))
+            }
+            DecodeOp::ReadEpbBytes { name, byte_len } => {
+                let valid = reader.read_bits(1)? != 0;
+                let mut value = vec![0u8; *byte_len];
+                reader.read_bytes(&mut value)?;
+                (name, FieldValue::EpbBytes(valid.then_some(value)))
+            }
+            // The code of custom elements is only compiled into generated
+            // decoders, so they are skipped like spare bits.
+            DecodeOp::SkipSpare { bits } | DecodeOp::Custom { bits, .. } => {
+                reader.read_bits(*bits)?;
+                continue;
+            }
+            DecodeOp::ReadLengthByte { .. } => {
+                reader.read_bits(8)?;
+                continue;
+            }
+        };
+        fields.push(Field { name: name.to_string(), value });
+    }
+    Ok(fields)
+}
+
+/// Reads the first part, then further parts while the FX bit after the
+/// previous one is set.  Extension octets after the last part are kept, so
+/// that the item encodes back unchanged.
+fn decode_extended<R: Read>(
+    reader: &mut BitReader<R>,
+    parts: &[LoweredPart],
+) -> Result<DynamicItem, DecodeError> {
+    let mut values = Vec::with_capacity(parts.len());
+    let mut fx = true;
+    for part in parts {
+        if fx {
+            values.push(Some(decode_fields(reader, &part.decode_ops)?));
+            fx = reader.read_bits(1)? != 0;
+        } else {
+            values.push(None);
+        }
+    }
+    let mut extensions = Vec::new();
+    while fx {
+        let octet = reader.read_bits(8)? as u8;
+        extensions.push(octet);
+        fx = octet & 1 != 0;
+    }
+    Ok(DynamicItem::Extended(values, extensions))
+}
+
+fn decode_repetitive<R: Read>(
+    reader: &mut BitReader<R>,
+    count: usize,
+    ops: &[DecodeOp],
+) -> Result<DynamicItem, DecodeError> {
+    let mut elements = Vec::with_capacity(count);
+    for _ in 0..count {
+        elements.push(decode_fields(reader, ops)?);
+    }
+    Ok(DynamicItem::Repetitive(elements))
+}
+
+fn decode_sub_item<R: Read>(reader: &mut BitReader<R>, sub: &LoweredSubItem) -> Result<DynamicItem, DecodeError> {
+    match &sub.kind {
+        LoweredSubItemKind::Simple { decode_ops, .. } => {
+            Ok(DynamicItem::Fields(decode_fields(reader, decode_ops)?))
+        }
+        LoweredSubItemKind::Extended { parts } => decode_extended(reader, parts),
+        LoweredSubItemKind::Repetitive { count, decode_ops, .. } => {
+            decode_repetitive(reader, *count, decode_ops)
+        }
+    }
+}
+
+/// Compound items read their FSPEC byte-wise and their sub-items through a
+/// fresh bit reader, like the generated code.
+fn decode_compound<R: Read>(reader: &mut R, sub_items: &[LoweredSubItem]) -> Result<DynamicItem, DecodeError> {
+    let fspec = Fspec::read(reader)?;
+    let mut reader = BitReader::new(reader);
+
+    let mut values = Vec::with_capacity(sub_items.len());
+    for sub in sub_items {
+        values.push(if fspec.is_frn_set(sub.frn) {
+            Some(decode_sub_item(&mut reader, sub)?)
+        } else {
+            None
+        });
+    }
+    Ok(DynamicItem::Compound(values))
+}
+
+fn decode_item<R: Read>(reader: &mut BitReader<R>, kind: &LoweredItemKind) -> Result<DynamicItem, DecodeError> {
+    match kind {
+        LoweredItemKind::Simple { decode_ops, .. } => {
+            Ok(DynamicItem::Fields(decode_fields(reader, decode_ops)?))
+        }
+        LoweredItemKind::Extended { parts } => decode_extended(reader, parts),
+        LoweredItemKind::Repetitive { count, decode_ops, .. } => {
+            decode_repetitive(reader, *count, decode_ops)
+        }
+        LoweredItemKind::Compound { sub_items } => decode_compound(reader, sub_items),
+    }
+}
+
+// ── Encoding ──────────────────────────────────────────────────────────────
+
+fn encode_fields<W: Write>(writer: &mut BitWriter<W>, ops: &[EncodeOp], fields: &[Field]) -> Result<(), DecodeError> {
+    let mut values = fields.iter().map(|f| &f.value);
+    let mut next = || values.next().ok_or(DecodeError::InvalidData(VALUE_MISMATCH));
+
+    for op in ops {
+        match op {
+            EncodeOp::WriteField { bits, little_endian: true, .. } => {
+                let FieldValue::Unsigned(value) = next()? else {
+                    return Err(DecodeError::InvalidData(VALUE_MISMATCH));
+                };
+                writer.write_bits_le(*value, *bits)?;
+            }
+            EncodeOp::WriteField { bits, .. } | EncodeOp::WriteEnum { bits, .. } => {
+                let FieldValue::Unsigned(value) = next()? else {
+                    return Err(DecodeError::InvalidData(VALUE_MISMATCH));
+                };
+                writer.write_bits(*value, *bits)?;
+            }
+            EncodeOp::WriteEpbField { bits, little_endian: true, .. } => {
+                let FieldValue::Epb(value) = next()? else {
+                    return Err(DecodeError::InvalidData(VALUE_MISMATCH));
+                };
+                writer.write_bits(value.is_some() as u64, 1)?;
+                writer.write_bits_le(value.unwrap_or(0), *bits)?;
+            }
+            EncodeOp::WriteEpbField { bits, .. } | EncodeOp::WriteEpbEnum { bits, .. } => {
+                let FieldValue::Epb(value) = next()? else {
+                    return Err(DecodeError::InvalidData(VALUE_MISMATCH));
+                };
+                writer.write_bits(value.is_some() as u64, 1)?;
+                writer.write_bits(value.unwrap_or(0), *bits)?;
+            }
+            EncodeOp::WriteString { byte_len, .. } => {
+                let FieldValue::String(value) = next()? else {
+                    return Err(DecodeError::InvalidData(VALUE_MISMATCH));
+                };
+                writer.write_string(value, *byte_len)?;
+            }
+            EncodeOp::WriteEpbString { byte_len, .. } => {
+                let FieldValue::EpbString(value) = next()? else {
+                    return Err(DecodeError::InvalidData(VALUE_MISMATCH));
+                };
+                writer.write_bits(value.is_some() as u64, 1)?;
+                writer.write_string(value.as_deref().unwrap_or(""), *byte_len)?;
+            }
+            EncodeOp::WriteBytes { byte_len, .. } => {
+                let FieldValue::Bytes(value) = next()? else {
+                    return Err(DecodeError::InvalidData(VALUE_MISMATCH));
+                };
+                writer.write_fixed_bytes(value, *byte_len)?;
+            }
+            EncodeOp::WriteEpbBytes { byte_len, .. } => {
+                let FieldValue::EpbBytes(value) = next()? else {
+                    return Err(DecodeError::InvalidData(VALUE_MISMATCH));
+                };
+                writer.write_bits(value.is_some() as u64, 1)?;
+                writer.write_fixed_bytes(value.as_deref().unwrap_or(&[]), *byte_len)?;
+            }
+            EncodeOp::WriteSpare { bits } | EncodeOp::Custom { bits, .. } => writer.write_bits(0, *bits)?,
+            EncodeOp::WriteLengthByte { total_bytes } => writer.write_bits(*total_bytes as u64, 8)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the parts present, each followed by an FX bit announcing the next,
+/// then the extension octets after the last part.
+fn encode_extended<W: Write>(
+    writer: &mut BitWriter<W>,
+    parts: &[LoweredPart],
+    values: &[Option<Vec<Field>>],
+    extensions: &[u8],
+) -> Result<(), DecodeError> {
+    if values.len() != parts.len() || values.first().is_none_or(Option::is_none) {
+        return Err(DecodeError::InvalidData(VALUE_MISMATCH));
+    }
+    if !extensions.is_empty() && values.last().is_some_and(Option::is_none) {
+        return Err(DecodeError::InvalidData("extended item has extensions after an absent part"));
+    }
+    let fx_broken = extensions.split_last().is_some_and(|(last, octets)| {
+        last & 1 != 0 || octets.iter().any(|octet| octet & 1 == 0)
+    });
+    if fx_broken {
+        return Err(DecodeError::InvalidData("extended item extensions must have FX set on every octet but the last"));
+    }
+
+    for (i, (part, value)) in parts.iter().zip(values).enumerate() {
+        if let Some(fields) = value {
+            encode_fields(writer, &part.encode_ops, fields)?;
+            let next_present = match values.get(i + 1) {
+                Some(next) => next.is_some(),
+                None => !extensions.is_empty(),
+            };
+            writer.write_bits(next_present as u64, 1)?;
+        }
+    }
+    writer.write_bytes(extensions)?;
+    Ok(())
+}
+
+fn encode_sub_item<W: Write>(
+    writer: &mut BitWriter<W>,
+    sub: &LoweredSubItem,
+    value: &DynamicItem,
+) -> Result<(), DecodeError> {
+    match (&sub.kind, value) {
+        (LoweredSubItemKind::Simple { encode_ops, .. }, DynamicItem::Fields(fields)) => {
+            encode_fields(writer, encode_ops, fields)
+        }
+        (LoweredSubItemKind::Extended { parts }, DynamicItem::Extended(values, extensions)) => {
+            encode_extended(writer, parts, values, extensions)
+        }
+        (LoweredSubItemKind::Repetitive { count, encode_ops, .. }, DynamicItem::Repetitive(elements)) => {
+            encode_repetitive(writer, *count, encode_ops, elements)
+        }
+        _ => Err(DecodeError::InvalidData(VALUE_MISMATCH)),
+    }
+}
+
+/// Writes the elements of a repetitive item, which must be exactly
+/// `count`.
+fn encode_repetitive<W: Write>(
+    writer: &mut BitWriter<W>,
+    count: usize,
+    ops: &[EncodeOp],
+    elements: &[Vec<Field>],
+) -> Result<(), DecodeError> {
+    if elements.len() != count {
+        return Err(DecodeError::InvalidData(VALUE_MISMATCH));
+    }
+    elements.iter().try_for_each(|e| encode_fields(writer, ops, e))
+}
+
+fn encode_compound<W: Write>(
+    writer: &mut W,
+    sub_items: &[LoweredSubItem],
+    values: &[Option<DynamicItem>],
+) -> Result<(), DecodeError> {
+    if values.len() != sub_items.len() {
+        return Err(DecodeError::InvalidData(VALUE_MISMATCH));
+    }
+
+    let mut fspec = Fspec::new();
+    for (sub, value) in sub_items.iter().zip(values) {
+        if value.is_some() {
+            fspec.set_frn(sub.frn);
+        }
+    }
+    fspec.write(writer)?;
+
+    let mut writer = BitWriter::new(writer);
+    for (sub, value) in sub_items.iter().zip(values) {
+        if let Some(value) = value {
+            encode_sub_item(&mut writer, sub, value)?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn encode_item<W: Write>(
+    writer: &mut BitWriter<W>,
+    kind: &LoweredItemKind,
+    value: &DynamicItem,
+) -> Result<(), DecodeError> {
+    match (kind, value) {
+        (LoweredItemKind::Simple { encode_ops, .. }, DynamicItem::Fields(fields)) => {
+            encode_fields(writer, encode_ops, fields)
+        }
+        (LoweredItemKind::Extended { parts }, DynamicItem::Extended(values, extensions)) => {
+            encode_extended(writer, parts, values, extensions)
+        }
+        (LoweredItemKind::Repetitive { count, encode_ops, .. }, DynamicItem::Repetitive(elements)) => {
+            encode_repetitive(writer, *count, encode_ops, elements)
+        }
+        (LoweredItemKind::Compound { sub_items }, DynamicItem::Compound(values)) => {
+            encode_compound(writer, sub_items, values)
+        }
+        _ => Err(DecodeError::InvalidData(VALUE_MISMATCH)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parser::parse_category;
+    use crate::transform::transformer::to_ir;
+
+    fn decoder(xml: &str) -> DynamicDecoder {
+        DynamicDecoder::from_ir(&to_ir(parse_category(xml).unwrap()).unwrap()).unwrap()
+    }
+
+    fn reencode(decoder: &DynamicDecoder, data: &[u8]) -> Vec<u8> {
+        let block = decoder.decode_block(&mut BitReader::new(data)).unwrap();
+        let mut out = Vec::new();
+        let mut writer = BitWriter::new(&mut out);
+        decoder.encode_block(&block, &mut writer).unwrap();
+        writer.flush().unwrap();
+        out
+    }
+
+    #[test]
+    fn test_fixed_and_string_roundtrip() {
+        let decoder = decoder(r#"<category id="48">
+            <item id="10" frn="1">
+                <fixed bytes="2">
+                    <field name="sac" bits="8"/>
+                    <field name="sic" bits="8"/>
+                </fixed>
+            </item>
+            <item id="240" frn="2">
+                <fixed bytes="6"><field name="callsign" bits="48" type="string"/></fixed>
+            </item>
+        </category>"#);
+        let data = [0x30, 0x00, 0x0c, 0xc0, 0x01, 0x02, b'B', b'A', b'W', b'1', b'2', b'3'];
+
+        let block = decoder.decode_block(&mut BitReader::new(&data[..])).unwrap();
+        let Some(DynamicItem::Fields(fields)) = &block.records[0].items[1] else { panic!() };
+        assert_eq!(fields[0].value, FieldValue::String("BAW123".to_string()));
+        assert_eq!(reencode(&decoder, &data), data);
+    }
+
+    #[test]
+    fn test_encode_rejects_blocks_too_long_for_len() {
+        let decoder = decoder(r#"<category id="48">
+            <item id="10" frn="1">
+                <fixed bytes="2"><field name="a" bits="16"/></fixed>
+            </item>
+        </category>"#);
+        let record = DynamicRecord {
+            items: vec![Some(DynamicItem::Fields(vec![Field { name: "a".to_string(), value: FieldValue::Unsigned(1) }]))],
+        };
+        // 22000 records of 3 bytes don't fit in the 16-bit LEN.
+        let block = DynamicBlock { records: vec![record; 22000] };
+
+        let mut out = Vec::new();
+        let result = decoder.encode_block(&block, &mut BitWriter::new(&mut out));
+        assert!(matches!(result, Err(DecodeError::LimitExceeded { limit: "data block length", max: 65535 })));
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_extended_stops_at_clear_fx() {
+        let decoder = decoder(r#"<category id="48">
+            <item id="20" frn="1">
+                <extended bytes="2">
+                    <part index="0"><field name="a" bits="7"/></part>
+                    <part index="1"><field name="b" bits="7"/></part>
+                </extended>
+            </item>
+        </category>"#);
+
+        let block = decoder.decode_block(&mut BitReader::new(&[0x30, 0x00, 0x05, 0x80, 0x06][..])).unwrap();
+        assert_eq!(
+            block.records[0].items[0],
+            Some(DynamicItem::Extended(vec![
+                Some(vec![Field { name: "a".to_string(), value: FieldValue::Unsigned(3) }]),
+                None,
+            ], Vec::new()))
+        );
+    }
+
+    #[test]
+    fn test_extended_keeps_extension_octets() {
+        let decoder = decoder(r#"<category id="48">
+            <item id="20" frn="1">
+                <extended bytes="1">
+                    <part index="0">
+                        <field name="a" bits="3"/>
+                        <field name="b" bits="4"/>
+                    </part>
+                </extended>
+            </item>
+        </category>"#);
+        let data = [0x30, 0x00, 0x06, 0x80, 0x03, 0x02];
+
+        let block = decoder.decode_block(&mut BitReader::new(&data[..])).unwrap();
+        let Some(DynamicItem::Extended(parts, extensions)) = &block.records[0].items[0] else { panic!() };
+        assert_eq!(parts.len(), 1);
+        assert_eq!(extensions, &[0x02]);
+        assert_eq!(reencode(&decoder, &data), data);
+    }
+
+    #[test]
+    fn test_compound_sub_items() {
+        let decoder = decoder(r#"<category id="1">
+            <item id="100" frn="1">
+                <compound>
+                    <fixed bytes="1"><field name="flags" bits="8"/></fixed>
+                    <fixed bytes="2"><field name="data" bits="16"/></fixed>
+                </compound>
+            </item>
+        </category>"#);
+        let data = [0x01, 0x00, 0x07, 0x80, 0x40, 0x12, 0x34];
+
+        let block = decoder.decode_block(&mut BitReader::new(&data[..])).unwrap();
+        let Some(DynamicItem::Compound(subs)) = &block.records[0].items[0] else { panic!() };
+        assert!(subs[0].is_none());
+        assert_eq!(
+            subs[1],
+            Some(DynamicItem::Fields(vec![Field { name: "data".to_string(), value: FieldValue::Unsigned(0x1234) }]))
+        );
+        assert_eq!(reencode(&decoder, &data), data);
+    }
+
+    #[test]
+    fn test_spare_bits_are_not_preserved() {
+        let decoder = decoder(r#"<category id="1">
+            <item id="10" frn="1">
+                <fixed bytes="1">
+                    <field name="a" bits="4"/>
+                    <spare bits="4"/>
+                </fixed>
+            </item>
+        </category>"#);
+
+        assert_eq!(reencode(&decoder, &[0x01, 0x00, 0x05, 0x80, 0x5f]), [0x01, 0x00, 0x05, 0x80, 0x50]);
+    }
+
+    #[test]
+    fn test_repetitive_needs_its_count() {
+        let decoder = decoder(r#"<category id="1">
+            <item id="70" frn="1">
+                <repetitive bytes="1" counter="2"><field name="a" bits="8"/></repetitive>
+            </item>
+        </category>"#);
+        let mut block = decoder.decode_block(&mut BitReader::new(&[0x01, 0x00, 0x06, 0x80, 0x01, 0x02][..])).unwrap();
+        let Some(DynamicItem::Repetitive(elements)) = &mut block.records[0].items[0] else { panic!() };
+        elements.pop();
+
+        let mut out = Vec::new();
+        let err = decoder.encode_block(&block, &mut BitWriter::new(&mut out)).unwrap_err();
+        assert!(matches!(err, DecodeError::InvalidData(VALUE_MISMATCH)));
+    }
+
+    #[test]
+    fn test_category_mismatch() {
+        let decoder = decoder(r#"<category id="1">
+            <item id="10" frn="1"><fixed bytes="1"><field name="a" bits="8"/></fixed></item>
+        </category>"#);
+
+        let err = decoder.decode_block(&mut BitReader::new(&[0x02, 0x00, 0x03][..])).unwrap_err();
+        assert!(matches!(err, DecodeError::InvalidData("category mismatch")));
+    }
+
+    #[test]
+    fn test_record_overrun_and_underrun() {
+        let decoder = decoder(r#"<category id="1">
+            <item id="10" frn="1"><fixed bytes="2"><field name="a" bits="16"/></fixed></item>
+        </category>"#);
+
+        let err = decoder.decode_block(&mut BitReader::new(&[0x01, 0x00, 0x05, 0x80, 0x06, 0x07][..])).unwrap_err();
+        assert!(matches!(err, DecodeError::RecordOverrun { offset: 3, block_len: 5 }));
+
+        let err = decoder.decode_block(&mut BitReader::new(&[0x01, 0x00, 0x07, 0x80, 0x06, 0x07, 0x00][..])).unwrap_err();
+        assert!(matches!(err, DecodeError::RecordUnderrun { offset: 6, block_len: 7 }));
+    }
+
+    #[test]
+    fn test_tail_padding_accepted_by_policy() {
+        struct Padded;
+        impl policy::DecodePolicy for Padded {
+            fn on_tail_padding(&self, _: usize, _: usize) -> Result<(), DecodeError> {
+                Ok(())
+            }
+        }
+
+        let decoder = decoder(r#"<category id="1">
+            <item id="10" frn="1"><fixed bytes="2"><field name="a" bits="16"/></fixed></item>
+        </category>"#);
+
+        let data = [0x01, 0x00, 0x08, 0x80, 0x06, 0x07, 0x00, 0x00];
+        let block = policy::with_policy(Padded, || decoder.decode_block(&mut BitReader::new(&data[..]))).unwrap();
+        assert_eq!(block.records.len(), 1);
+    }
+}
@@ -0,0 +1,460 @@
+//! Synthetic traffic for load testing.
+//!
+//! [`TrafficGenerator`] produces pseudo-random but plausible data blocks of
+//! any category, from its definition alone: SAC/SIC pairs from a list of
+//! sources, azimuths following a rotating antenna, times of day advancing
+//! with the configured record rate, track numbers cycling through a set of
+//! targets, enums set to defined variants and fields within their
+//! constraints.  Other fields get random values.
+//!
+//! Generation is driven by a seeded PRNG, so a run can be reproduced.  Each
+//! block is stamped with the simulated time of its last record; write them
+//! to a [`recording`](rasterix_core::recording) or pace them to the record
+//! rate when sending.
+//!
+//! # Example
+//!
+//! ```
+//! use rasterix_codegen::dynamic::DynamicDecoder;
+//! use rasterix_codegen::dynamic::generator::{TrafficConfig, TrafficGenerator};
+//! use rasterix_codegen::parse::parser::parse_category;
+//! use rasterix_codegen::transform::transformer::to_ir;
+//!
+//! let xml = r#"<category id="48">
+//!     <item id="10" frn="1">
+//!         <fixed bytes="2"><field name="sac" bits="8"/><field name="sic" bits="8"/></fixed>
+//!     </item>
+//! </category>"#;
+//! let decoder = DynamicDecoder::from_ir(&to_ir(parse_category(xml).unwrap()).unwrap()).unwrap();
+//!
+//! let config = TrafficConfig { sources: vec![(25, 7)], ..TrafficConfig::with_seed(1) };
+//! let mut traffic = TrafficGenerator::new(decoder, config);
+//! let block = traffic.next().unwrap();
+//! assert_eq!(block.data[0], 48);
+//! ```
+
+use std::time::{Duration, SystemTime};
+
+use rasterix_core::{BitWriter, Timestamped};
+
+use crate::transform::lower_ir::{
+    DecodeOp, FieldConstraints, FieldDescriptor, LoweredEnum, LoweredItem, LoweredItemKind, LoweredPart,
+    LoweredSubItem, LoweredSubItemKind,
+};
+use super::value::{DynamicBlock, DynamicItem, DynamicRecord, Field, FieldValue};
+use super::DynamicDecoder;
+
+/// Parameters of a [`TrafficGenerator`].
+#[derive(Debug, Clone)]
+pub struct TrafficConfig {
+    /// Seed for the PRNG.
+    pub seed: u64,
+    /// Time of the first record.
+    pub start: SystemTime,
+    /// Simulated rate of records, which sets the times of day.
+    pub records_per_second: f64,
+    /// Number of records in each data block.
+    pub records_per_block: usize,
+    /// SAC/SIC pairs of the simulated sources, one picked per record.
+    pub sources: Vec<(u8, u8)>,
+    /// Number of simulated targets, numbered from 1 in track numbers.
+    pub targets: u16,
+    /// Time the antenna takes for a full turn, which sets the azimuths.
+    pub rotation_period: Duration,
+    /// Percentage of records in which each item, sub-item and optional
+    /// part is present.
+    pub item_percent: u32,
+}
+
+impl Default for TrafficConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            start: SystemTime::UNIX_EPOCH,
+            records_per_second: 100.0,
+            records_per_block: 10,
+            sources: vec![(0, 1)],
+            targets: 50,
+            rotation_period: Duration::from_secs(4),
+            item_percent: 80,
+        }
+    }
+}
+
+impl TrafficConfig {
+    /// Default configuration with the given seed.
+    pub fn with_seed(seed: u64) -> Self {
+        Self { seed, ..Self::default() }
+    }
+}
+
+/// Infinite iterator over synthetic data blocks of one category.
+#[derive(Debug, Clone)]
+pub struct TrafficGenerator {
+    decoder: DynamicDecoder,
+    config: TrafficConfig,
+    rng: Rng,
+    /// Number of records generated so far.
+    records: u64,
+}
+
+impl TrafficGenerator {
+    /// Creates a generator of the category of `decoder`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the configuration has no sources, no records per block,
+    /// or a rate that isn't positive.
+    pub fn new(decoder: DynamicDecoder, config: TrafficConfig) -> Self {
+        assert!(!config.sources.is_empty(), "TrafficConfig::sources must not be empty");
+        assert!(config.records_per_block > 0, "TrafficConfig::records_per_block must not be 0");
+        assert!(config.records_per_second > 0.0, "TrafficConfig::records_per_second must be positive");
+        let rng = Rng::new(config.seed);
+        Self { decoder, config, rng, records: 0 }
+    }
+
+    /// Returns the number of records generated so far.
+    pub fn records_generated(&self) -> u64 {
+        self.records
+    }
+
+    /// Generates the next record.
+    pub fn next_record(&mut self) -> DynamicRecord {
+        let elapsed = self.records as f64 / self.config.records_per_second;
+        let source = *self.rng.choose(&self.config.sources);
+        let mut draw = Draw {
+            rng: &mut self.rng,
+            percent: self.config.item_percent,
+            source,
+            time: self.config.start + Duration::from_secs_f64(elapsed),
+            turn: elapsed / self.config.rotation_period.as_secs_f64().max(f64::MIN_POSITIVE),
+            target: (self.records % self.config.targets.max(1) as u64) as u16 + 1,
+        };
+        self.records += 1;
+
+        let mut items: Vec<_> = self
+            .decoder
+            .record_items()
+            .map(|item| draw.rng.percent(draw.percent).then(|| draw.item(item)))
+            .collect();
+        // An empty record can't end a data block: its FSPEC reads as padding.
+        if items.iter().all(Option::is_none)
+            && let Some(first) = self.decoder.record_items().next()
+        {
+            items[0] = Some(draw.item(first));
+        }
+        DynamicRecord { items }
+    }
+
+    /// Generates the next data block, encoded, stamped with the time of its
+    /// last record.
+    pub fn next_block(&mut self) -> Timestamped<Vec<u8>> {
+        let records = (0..self.config.records_per_block).map(|_| self.next_record()).collect();
+        let last = (self.records - 1) as f64 / self.config.records_per_second;
+
+        let mut data = Vec::new();
+        let mut writer = BitWriter::new(&mut data);
+        self.decoder
+            .encode_block(&DynamicBlock { records }, &mut writer)
+            .expect("generated records match their definition");
+        writer.flush().expect("writing to a Vec does not fail");
+
+        Timestamped::new(self.config.start + Duration::from_secs_f64(last), data)
+    }
+}
+
+impl Iterator for TrafficGenerator {
+    type Item = Timestamped<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.next_block())
+    }
+}
+
+/// Generator of the values of one record, from the state of the simulation
+/// at its time.
+struct Draw<'a> {
+    rng: &'a mut Rng,
+    /// Percentage of optional items, sub-items and parts present.
+    percent: u32,
+    source: (u8, u8),
+    time: SystemTime,
+    /// Number of antenna turns since the start.
+    turn: f64,
+    target: u16,
+}
+
+impl Draw<'_> {
+    fn item(&mut self, item: &LoweredItem) -> DynamicItem {
+        let enums = &item.enums;
+        match &item.kind {
+            LoweredItemKind::Simple { fields, decode_ops, .. } => DynamicItem::Fields(self.fields(decode_ops, fields, enums)),
+            LoweredItemKind::Extended { parts } => self.extended(parts, enums),
+            LoweredItemKind::Repetitive { count, fields, decode_ops, .. } => {
+                DynamicItem::Repetitive((0..*count).map(|_| self.fields(decode_ops, fields, enums)).collect())
+            }
+            LoweredItemKind::Compound { sub_items } => {
+                let mut subs: Vec<_> = sub_items
+                    .iter()
+                    .map(|sub| self.rng.percent(self.percent).then(|| self.sub_item(sub)))
+                    .collect();
+                // An empty compound is valid but useless traffic.
+                if subs.iter().all(Option::is_none) {
+                    subs[0] = Some(self.sub_item(&sub_items[0]));
+                }
+                DynamicItem::Compound(subs)
+            }
+        }
+    }
+
+    fn sub_item(&mut self, sub: &LoweredSubItem) -> DynamicItem {
+        let enums = &sub.enums;
+        match &sub.kind {
+            LoweredSubItemKind::Simple { fields, decode_ops, .. } => DynamicItem::Fields(self.fields(decode_ops, fields, enums)),
+            LoweredSubItemKind::Extended { parts } => self.extended(parts, enums),
+            LoweredSubItemKind::Repetitive { count, fields, decode_ops, .. } => {
+                DynamicItem::Repetitive((0..*count).map(|_| self.fields(decode_ops, fields, enums)).collect())
+            }
+        }
+    }
+
+    /// Generates the first part and a random number of the following ones,
+    /// since a part is only present after all those before it.
+    fn extended(&mut self, parts: &[LoweredPart], enums: &[LoweredEnum]) -> DynamicItem {
+        let mut present = true;
+        let values = parts
+            .iter()
+            .enumerate()
+            .map(|(i, part)| {
+                present &= i == 0 || part.is_required || self.rng.percent(self.percent);
+                present.then(|| self.fields(&part.decode_ops, &part.fields, enums))
+            })
+            .collect();
+        DynamicItem::Extended(values, Vec::new())
+    }
+
+    fn fields(&mut self, ops: &[DecodeOp], descriptors: &[FieldDescriptor], enums: &[LoweredEnum]) -> Vec<Field> {
+        let no_constraints = FieldConstraints::default();
+        let mut fields = Vec::new();
+        for op in ops {
+            let (name, value) = match op {
+                DecodeOp::ReadField { name, bits, .. } | DecodeOp::ReadEpbField { name, bits, .. } => {
+                    let constraints = descriptors
+                        .iter()
+                        .find(|field| field.name == *name)
+                        .map_or(&no_constraints, |field| &field.constraints);
+                    (name, self.number(&name.to_string(), *bits, constraints))
+                }
+                DecodeOp::ReadEnum { name, bits, enum_type } | DecodeOp::ReadEpbEnum { name, bits, enum_type } => {
+                    let values: Vec<u64> = enums
+                        .iter()
+                        .find(|e| e.name == *enum_type)
+                        .map(|e| e.variants.iter().map(|variant| variant.value as u64).collect())
+                        .unwrap_or_default();
+                    (name, if values.is_empty() { self.rng.bits(*bits) } else { *self.rng.choose(&values) })
+                }
+                DecodeOp::ReadString { name, byte_len } => {
+                    fields.push(Field { name: name.to_string(), value: FieldValue::String(self.text(*byte_len)) });
+                    continue;
+                }
+                DecodeOp::ReadEpbString { name, byte_len } => {
+                    let value = self.rng.percent(90).then(|| self.text(*byte_len));
+                    fields.push(Field { name: name.to_string(), value: FieldValue::EpbString(value) });
+                    continue;
+                }
+                DecodeOp::ReadBytes { name, byte_len } => {
+                    fields.push(Field { name: name.to_string(), value: FieldValue::Bytes(self.bytes(*byte_len)) });
+                    continue;
+                }
+                DecodeOp::ReadEpbBytes { name, byte_len } => {
+                    let value = self.rng.percent(90).then(|| self.bytes(*byte_len));
+                    fields.push(Field { name: name.to_string(), value: FieldValue::EpbBytes(value) });
+                    continue;
+                }
+                DecodeOp::SkipSpare { .. } | DecodeOp::Custom { .. } | DecodeOp::ReadLengthByte { .. } => continue,
+            };
+            let value = match op {
+                // Invalid values are part of realistic traffic too.
+                DecodeOp::ReadEpbField { .. } | DecodeOp::ReadEpbEnum { .. } => {
+                    FieldValue::Epb(self.rng.percent(90).then_some(value))
+                }
+                _ => FieldValue::Unsigned(value),
+            };
+            fields.push(Field { name: name.to_string(), value });
+        }
+        fields
+    }
+
+    /// Returns a plausible value of a numeric field, recognized by name.
+    fn number(&mut self, name: &str, bits: usize, constraints: &FieldConstraints) -> u64 {
+        let max = if bits >= 64 { u64::MAX } else { (1 << bits) - 1 };
+        let value = match name {
+            "sac" => self.source.0 as u64,
+            "sic" => self.source.1 as u64,
+            _ if name.contains("azimuth") || name == "theta" => ((self.turn.fract() * (max as f64 + 1.0)) as u64).min(max),
+            _ if name.contains("time") || name == "tod" => {
+                // Times of day count 1/128 s since midnight.
+                let since_epoch = self.time.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default();
+                (since_epoch.as_secs_f64() % 86_400.0 * 128.0) as u64
+            }
+            _ if name.contains("track") || name == "trn" => self.target as u64,
+            _ => self.rng.bits(bits),
+        };
+        constrain(value & max, max, constraints)
+    }
+
+    /// Returns a random string of capital letters and digits.
+    fn text(&mut self, len: usize) -> String {
+        const CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+        (0..len).map(|_| *self.rng.choose(CHARS) as char).collect()
+    }
+
+    fn bytes(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.rng.bits(8) as u8).collect()
+    }
+}
+
+/// Brings `value` within `constraints` and the field's `max`, if possible.
+fn constrain(value: u64, max: u64, constraints: &FieldConstraints) -> u64 {
+    let low = constraints.min.unwrap_or(0);
+    let high = constraints.max.unwrap_or(max).min(max);
+    if low > high {
+        return value;
+    }
+    let mut value = value.clamp(low, high);
+    if let Some(step) = constraints.multiple_of.filter(|step| *step > 0) {
+        value -= value % step;
+        if value < low {
+            value = value.saturating_add(step);
+        }
+    }
+    value
+}
+
+/// SplitMix64, good enough for traffic and cheap to seed.
+#[derive(Debug, Clone)]
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a random value of `bits` bits.
+    fn bits(&mut self, bits: usize) -> u64 {
+        if bits >= 64 { self.next_u64() } else { self.next_u64() & ((1 << bits) - 1) }
+    }
+
+    fn percent(&mut self, percent: u32) -> bool {
+        self.next_u64() % 100 < percent as u64
+    }
+
+    fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[(self.next_u64() % items.len() as u64) as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parser::parse_category;
+    use crate::transform::transformer::to_ir;
+    use rasterix_core::BitReader;
+
+    const XML: &str = r#"<category id="48">
+        <item id="10" frn="1">
+            <fixed bytes="2"><field name="sac" bits="8"/><field name="sic" bits="8"/></fixed>
+        </item>
+        <item id="140" frn="2">
+            <fixed bytes="3"><field name="time_of_day" bits="24"/></fixed>
+        </item>
+        <item id="40" frn="3">
+            <extended bytes="2">
+                <part index="0"><field name="azimuth" bits="7"/></part>
+                <part index="1">
+                    <enum name="kind" bits="3"><value name="PSR" value="1"/><value name="SSR" value="2"/></enum>
+                    <field name="level" bits="4" min="2" max="9" multiple_of="3"/>
+                </part>
+            </extended>
+        </item>
+        <item id="50" frn="4">
+            <compound>
+                <fixed bytes="1"><field name="a" bits="8"/></fixed>
+                <repetitive bytes="1" counter="2"><field name="b" bits="8"/></repetitive>
+            </compound>
+        </item>
+    </category>"#;
+
+    fn decoder() -> DynamicDecoder {
+        DynamicDecoder::from_ir(&to_ir(parse_category(XML).unwrap()).unwrap()).unwrap()
+    }
+
+    fn unsigned(fields: &[Field], name: &str) -> u64 {
+        match fields.iter().find(|field| field.name == name).map(|field| &field.value) {
+            Some(FieldValue::Unsigned(value)) => *value,
+            other => panic!("{}: {:?}", name, other),
+        }
+    }
+
+    #[test]
+    fn blocks_decode_with_plausible_values() {
+        let config = TrafficConfig {
+            sources: vec![(25, 7)],
+            records_per_second: 128.0,
+            item_percent: 100,
+            ..TrafficConfig::with_seed(3)
+        };
+        let mut traffic = TrafficGenerator::new(decoder(), config);
+
+        let blocks: Vec<_> = traffic.by_ref().take(3).collect();
+        assert_eq!(traffic.records_generated(), 30);
+        assert_eq!(blocks[1].recv_time, SystemTime::UNIX_EPOCH + Duration::from_secs_f64(19.0 / 128.0));
+
+        let decoder = decoder();
+        let block = decoder.decode_block(&mut BitReader::new(&blocks[1].data[..])).unwrap();
+        assert_eq!(block.records.len(), 10);
+        for (i, record) in block.records.iter().enumerate() {
+            let Some(DynamicItem::Fields(source)) = &record.items[0] else { panic!() };
+            assert_eq!((unsigned(source, "sac"), unsigned(source, "sic")), (25, 7));
+
+            // One record per 1/128 s.
+            let Some(DynamicItem::Fields(time)) = &record.items[1] else { panic!() };
+            assert_eq!(unsigned(time, "time_of_day"), 10 + i as u64);
+
+            let Some(DynamicItem::Extended(parts, _)) = &record.items[2] else { panic!() };
+            if let Some(part) = &parts[1] {
+                assert!([1, 2].contains(&unsigned(part, "kind")));
+                assert!([3, 6, 9].contains(&unsigned(part, "level")));
+            }
+
+            let Some(DynamicItem::Compound(subs)) = &record.items[3] else { panic!() };
+            if let Some(DynamicItem::Repetitive(elements)) = &subs[1] {
+                assert_eq!(elements.len(), 2);
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_same_traffic() {
+        let blocks = |seed| TrafficGenerator::new(decoder(), TrafficConfig::with_seed(seed)).take(5).collect::<Vec<_>>();
+        assert_eq!(blocks(7), blocks(7));
+        assert_ne!(blocks(7), blocks(8));
+    }
+
+    #[test]
+    fn constrain_fits_values_to_constraints() {
+        let constraints = FieldConstraints { min: Some(2), max: Some(9), multiple_of: Some(3) };
+        assert_eq!(constrain(0, 15, &constraints), 3);
+        assert_eq!(constrain(8, 15, &constraints), 6);
+        assert_eq!(constrain(15, 15, &constraints), 9);
+        assert_eq!(constrain(12, 15, &FieldConstraints::default()), 12);
+    }
+}
@@ -0,0 +1,631 @@
+//! Dynamic records as JSON.
+//!
+//! The JSON mirrors what the generated `ToJson` implementations write: a
+//! record is an object keyed by item field name (`item010`), extended items
+//! are objects keyed by part (`part0`), with their unknown extension octets
+//! under `extensions` if there are any, compound items by sub-item (`sub0`),
+//! and repetitive items are arrays of their elements.  Enums are written by
+//! variant name, or raw value if they have none, and read from either.
+//! Float fields are written as numbers, non-finite ones as `null`.
+//! Absent items are left out; absent parts, sub-items and EPB values are
+//! `null`.
+
+use std::fmt;
+
+use proc_macro2::Ident;
+use serde_json::{Map, Value};
+
+use crate::generate::utils::is_float_type;
+use crate::transform::lower_ir::{
+    EncodeOp, FieldDescriptor, FieldType, LoweredEnum, LoweredItemKind, LoweredPart,
+    LoweredSubItem, LoweredSubItemKind,
+};
+use super::value::{DynamicBlock, DynamicItem, DynamicRecord, Field, FieldValue};
+use super::DynamicDecoder;
+
+/// Parses a data block from JSON text: a single record object, or an array
+/// of them.
+///
+/// Errors name the path of the offending value, e.g.
+/// ``item010.sac: 300 does not fit in 8 bits``.
+///
+/// # Example
+///
+/// ```
+/// use rasterix_codegen::dynamic::{json, DynamicDecoder};
+/// use rasterix_codegen::parse::parser::parse_category;
+/// use rasterix_codegen::transform::transformer::to_ir;
+/// use rasterix_core::BitWriter;
+///
+/// let xml = r#"<category id="48">
+///     <item id="10" frn="1">
+///         <fixed bytes="2">
+///             <field name="sac" bits="8"/>
+///             <field name="sic" bits="8"/>
+///         </fixed>
+///     </item>
+/// </category>"#;
+///
+/// let decoder = DynamicDecoder::from_ir(&to_ir(parse_category(xml).unwrap()).unwrap()).unwrap();
+/// let block = json::parse_block(&decoder, r#"{"item010": {"sac": 1, "sic": 2}}"#).unwrap();
+///
+/// let mut bytes = Vec::new();
+/// decoder.encode_block(&block, &mut BitWriter::new(&mut bytes)).unwrap();
+/// assert_eq!(bytes, [0x30, 0x00, 0x06, 0x80, 0x01, 0x02]);
+/// ```
+pub fn parse_block(decoder: &DynamicDecoder, text: &str) -> Result<DynamicBlock, String> {
+    let value: Value = serde_json::from_str(text).map_err(|e| format!("invalid JSON: {}", e))?;
+    let records = match &value {
+        Value::Array(records) => records
+            .iter()
+            .enumerate()
+            .map(|(i, record)| record_from_json(decoder, record).map_err(|e| format!("record {}: {}", i, e)))
+            .collect::<Result<_, _>>()?,
+        record => vec![record_from_json(decoder, record)?],
+    };
+    Ok(DynamicBlock { records })
+}
+
+/// Converts a JSON record object into a [`DynamicRecord`].
+pub fn record_from_json(decoder: &DynamicDecoder, json: &Value) -> Result<DynamicRecord, String> {
+    let lowered = decoder.lowered();
+    let object = as_object(json).map_err(|e| e.to_string())?;
+    check_keys(object, lowered.record.entries.iter().map(|entry| &entry.field_name), "item")
+        .map_err(|e| e.to_string())?;
+
+    let mut items = Vec::with_capacity(lowered.record.entries.len());
+    for (entry, item) in lowered.record.entries.iter().zip(decoder.record_items()) {
+        let name = entry.field_name.to_string();
+        items.push(match present(object, &name) {
+            Some(value) => Some(
+                item_from_json(&item.kind, &item.enums, value).map_err(|e| Error::at(&name, e).to_string())?,
+            ),
+            None => None,
+        });
+    }
+    Ok(DynamicRecord { items })
+}
+
+/// Converts a [`DynamicRecord`] into JSON, as the generated `ToJson` of the
+/// record would write it.
+pub fn record_to_json(decoder: &DynamicDecoder, record: &DynamicRecord) -> Value {
+    let mut object = Map::new();
+    for ((entry, item), value) in decoder.lowered().record.entries.iter().zip(decoder.record_items()).zip(&record.items) {
+        if let Some(value) = value {
+            object.insert(entry.field_name.to_string(), item_to_json(&item.kind, &item.enums, value));
+        }
+    }
+    Value::Object(object)
+}
+
+fn item_to_json(kind: &LoweredItemKind, enums: &[LoweredEnum], value: &DynamicItem) -> Value {
+    match (kind, value) {
+        (LoweredItemKind::Simple { fields, .. }, DynamicItem::Fields(values)) => {
+            fields_to_json(fields, enums, values)
+        }
+        (LoweredItemKind::Extended { parts }, DynamicItem::Extended(values, extensions)) => {
+            extended_to_json(parts, enums, values, extensions)
+        }
+        (LoweredItemKind::Repetitive { fields, .. }, DynamicItem::Repetitive(elements)) => {
+            Value::Array(elements.iter().map(|e| fields_to_json(fields, enums, e)).collect())
+        }
+        (LoweredItemKind::Compound { sub_items }, DynamicItem::Compound(values)) => {
+            let mut object = Map::new();
+            for (sub, value) in sub_items.iter().zip(values) {
+                let json = match (&sub.kind, value) {
+                    (_, None) => Value::Null,
+                    (LoweredSubItemKind::Simple { fields, .. }, Some(DynamicItem::Fields(values))) => {
+                        fields_to_json(fields, &sub.enums, values)
+                    }
+                    (LoweredSubItemKind::Extended { parts }, Some(DynamicItem::Extended(values, extensions))) => {
+                        extended_to_json(parts, &sub.enums, values, extensions)
+                    }
+                    (LoweredSubItemKind::Repetitive { fields, .. }, Some(DynamicItem::Repetitive(elements))) => {
+                        Value::Array(elements.iter().map(|e| fields_to_json(fields, &sub.enums, e)).collect())
+                    }
+                    _ => Value::Null,
+                };
+                object.insert(sub.field_name.to_string(), json);
+            }
+            Value::Object(object)
+        }
+        _ => Value::Null,
+    }
+}
+
+fn extended_to_json(
+    parts: &[LoweredPart],
+    enums: &[LoweredEnum],
+    values: &[Option<Vec<Field>>],
+    extensions: &[u8],
+) -> Value {
+    let mut object = Map::new();
+    for (part, value) in parts.iter().zip(values) {
+        let json = value.as_ref().map_or(Value::Null, |fields| fields_to_json(&part.fields, enums, fields));
+        object.insert(part.field_name.to_string(), json);
+    }
+    if !extensions.is_empty() {
+        object.insert(EXTENSIONS.to_string(), Value::from(extensions.to_vec()));
+    }
+    Value::Object(object)
+}
+
+fn fields_to_json(fields: &[FieldDescriptor], enums: &[LoweredEnum], values: &[Field]) -> Value {
+    let mut object = Map::new();
+    for value in values {
+        let lowered = enum_of(fields, enums, &value.name).ok();
+        let float = float_of(fields, &value.name);
+        let json = match &value.value {
+            FieldValue::Unsigned(raw) | FieldValue::Epb(Some(raw)) => match (lowered, float) {
+                (Some(lowered), _) => variant_to_json(lowered, *raw),
+                (None, Some(ty)) => float_to_json(ty, *raw),
+                (None, None) => Value::from(*raw),
+            },
+            FieldValue::String(s) | FieldValue::EpbString(Some(s)) => Value::from(s.as_str()),
+            FieldValue::Bytes(bytes) | FieldValue::EpbBytes(Some(bytes)) => Value::from(bytes.clone()),
+            FieldValue::Epb(None) | FieldValue::EpbString(None) | FieldValue::EpbBytes(None) => Value::Null,
+        };
+        object.insert(value.name.clone(), json);
+    }
+    Value::Object(object)
+}
+
+/// Returns the type of the field `name` if it is a float.
+fn float_of<'a>(fields: &'a [FieldDescriptor], name: &str) -> Option<&'a Ident> {
+    fields.iter().find(|field| field.name == name).and_then(|field| match &field.type_tokens {
+        FieldType::Primitive(ty) | FieldType::OptionalPrimitive(ty) if is_float_type(ty) => Some(ty),
+        _ => None,
+    })
+}
+
+fn float_to_json(ty: &Ident, raw: u64) -> Value {
+    if ty == "f32" {
+        // Through its shortest decimal, as `ToJson` writes it, rather than
+        // widened to an `f64`.
+        let value = f32::from_bits(raw as u32).to_string();
+        value.parse::<f64>().map_or(Value::Null, Value::from)
+    } else {
+        Value::from(f64::from_bits(raw))
+    }
+}
+
+fn variant_to_json(lowered: &LoweredEnum, raw: u64) -> Value {
+    match lowered.variants.iter().find(|v| v.value as u64 == raw) {
+        Some(variant) => Value::from(variant.name.to_string()),
+        None => Value::from(raw),
+    }
+}
+
+/// A conversion error and the path of the value that caused it.
+struct Error {
+    path: Vec<String>,
+    message: String,
+}
+
+impl Error {
+    fn new(message: impl Into<String>) -> Self {
+        Self { path: Vec::new(), message: message.into() }
+    }
+
+    /// Adds the name of the enclosing value to the path.
+    fn at(name: &str, mut error: Error) -> Self {
+        error.path.insert(0, name.to_string());
+        error
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.path.is_empty() {
+            write!(f, "{}: ", self.path.join("."))?;
+        }
+        f.write_str(&self.message)
+    }
+}
+
+fn item_from_json(kind: &LoweredItemKind, enums: &[LoweredEnum], json: &Value) -> Result<DynamicItem, Error> {
+    match kind {
+        LoweredItemKind::Simple { fields, encode_ops, .. } => {
+            fields_from_json(fields, encode_ops, enums, json).map(DynamicItem::Fields)
+        }
+        LoweredItemKind::Extended { parts } => extended_from_json(parts, enums, json),
+        LoweredItemKind::Repetitive { count, fields, encode_ops, .. } => {
+            repetitive_from_json(*count, fields, encode_ops, enums, json)
+        }
+        LoweredItemKind::Compound { sub_items } => compound_from_json(sub_items, json),
+    }
+}
+
+fn extended_from_json(parts: &[LoweredPart], enums: &[LoweredEnum], json: &Value) -> Result<DynamicItem, Error> {
+    let object = as_object(json)?;
+    let extensions = match object.get(EXTENSIONS) {
+        Some(json) => extension_octets(json).map_err(|e| Error::at(EXTENSIONS, e))?,
+        None => Vec::new(),
+    };
+    let unknown = object.keys().find(|key| *key != EXTENSIONS && !parts.iter().any(|part| part.field_name == key));
+    if let Some(key) = unknown {
+        return Err(Error::new(format!("no part `{}`", key)));
+    }
+
+    let mut values = Vec::with_capacity(parts.len());
+    for part in parts {
+        let name = part.field_name.to_string();
+        values.push(match present(object, &name) {
+            Some(value) => Some(
+                fields_from_json(&part.fields, &part.encode_ops, enums, value).map_err(|e| Error::at(&name, e))?,
+            ),
+            None => None,
+        });
+    }
+
+    // Each part is announced by the FX bit of the previous one, so the
+    // parts present must be leading.
+    if let Some(gap) = values.iter().position(Option::is_none) {
+        if gap == 0 {
+            return Err(Error::at(&parts[0].field_name.to_string(), Error::new("missing")));
+        }
+        if let Some(after) = values[gap..].iter().position(Option::is_some) {
+            let message = format!("present after absent {}", parts[gap].field_name);
+            return Err(Error::at(&parts[gap + after].field_name.to_string(), Error::new(message)));
+        }
+    }
+    if !extensions.is_empty() && values.last().is_some_and(Option::is_none) {
+        let message = format!("present after absent {}", parts[parts.len() - 1].field_name);
+        return Err(Error::at(EXTENSIONS, Error::new(message)));
+    }
+    Ok(DynamicItem::Extended(values, extensions))
+}
+
+fn repetitive_from_json(
+    count: usize,
+    fields: &[FieldDescriptor],
+    ops: &[EncodeOp],
+    enums: &[LoweredEnum],
+    json: &Value,
+) -> Result<DynamicItem, Error> {
+    let Value::Array(elements) = json else {
+        return Err(Error::new(format!("expected an array, got {}", json)));
+    };
+    if elements.len() != count {
+        return Err(Error::new(format!("expected {} elements, got {}", count, elements.len())));
+    }
+
+    elements
+        .iter()
+        .enumerate()
+        .map(|(i, element)| fields_from_json(fields, ops, enums, element).map_err(|e| Error::at(&i.to_string(), e)))
+        .collect::<Result<_, _>>()
+        .map(DynamicItem::Repetitive)
+}
+
+fn compound_from_json(sub_items: &[LoweredSubItem], json: &Value) -> Result<DynamicItem, Error> {
+    let object = as_object(json)?;
+    check_keys(object, sub_items.iter().map(|sub| &sub.field_name), "sub-item")?;
+
+    let mut values = Vec::with_capacity(sub_items.len());
+    for sub in sub_items {
+        let name = sub.field_name.to_string();
+        let Some(value) = present(object, &name) else {
+            values.push(None);
+            continue;
+        };
+        let item = match &sub.kind {
+            LoweredSubItemKind::Simple { fields, encode_ops, .. } => {
+                fields_from_json(fields, encode_ops, &sub.enums, value).map(DynamicItem::Fields)
+            }
+            LoweredSubItemKind::Extended { parts } => extended_from_json(parts, &sub.enums, value),
+            LoweredSubItemKind::Repetitive { count, fields, encode_ops, .. } => {
+                repetitive_from_json(*count, fields, encode_ops, &sub.enums, value)
+            }
+        };
+        values.push(Some(item.map_err(|e| Error::at(&name, e))?));
+    }
+    Ok(DynamicItem::Compound(values))
+}
+
+/// Reads the fields written by `ops`, in the order the encoder expects them.
+fn fields_from_json(
+    fields: &[FieldDescriptor],
+    ops: &[EncodeOp],
+    enums: &[LoweredEnum],
+    json: &Value,
+) -> Result<Vec<Field>, Error> {
+    let object = as_object(json)?;
+    check_keys(object, fields.iter().map(|field| &field.name), "field")?;
+
+    let mut values = Vec::new();
+    for op in ops {
+        let (name, value) = match op {
+            EncodeOp::WriteField { name, bits, float: true, .. } => {
+                (name, required(object, name).and_then(|v| float(v, *bits)).map(FieldValue::Unsigned))
+            }
+            EncodeOp::WriteField { name, bits, .. } => {
+                (name, required(object, name).and_then(|v| number(v, *bits)).map(FieldValue::Unsigned))
+            }
+            EncodeOp::WriteEpbField { name, bits, float: true, .. } => {
+                (name, optional(object, name, |v| float(v, *bits)).map(FieldValue::Epb))
+            }
+            EncodeOp::WriteEpbField { name, bits, .. } => {
+                (name, optional(object, name, |v| number(v, *bits)).map(FieldValue::Epb))
+            }
+            EncodeOp::WriteEnum { name, bits } => {
+                let value = enum_of(fields, enums, &name.to_string())
+                    .and_then(|lowered| variant(lowered, required(object, name)?, *bits));
+                (name, value.map(FieldValue::Unsigned))
+            }
+            EncodeOp::WriteEpbEnum { name, bits } => {
+                let value = enum_of(fields, enums, &name.to_string())
+                    .and_then(|lowered| optional(object, name, |v| variant(lowered, v, *bits)));
+                (name, value.map(FieldValue::Epb))
+            }
+            EncodeOp::WriteString { name, .. } => {
+                (name, required(object, name).and_then(string).map(FieldValue::String))
+            }
+            EncodeOp::WriteEpbString { name, .. } => {
+                (name, optional(object, name, string).map(FieldValue::EpbString))
+            }
+            EncodeOp::WriteBytes { name, byte_len } => {
+                (name, required(object, name).and_then(|v| bytes(v, *byte_len)).map(FieldValue::Bytes))
+            }
+            EncodeOp::WriteEpbBytes { name, byte_len } => {
+                (name, optional(object, name, |v| bytes(v, *byte_len)).map(FieldValue::EpbBytes))
+            }
+            EncodeOp::WriteSpare { .. } | EncodeOp::WriteLengthByte { .. } | EncodeOp::Custom { .. } => continue,
+        };
+        let name = name.to_string();
+        let value = value.map_err(|e| Error::at(&name, e))?;
+        values.push(Field { name, value });
+    }
+    Ok(values)
+}
+
+fn enum_of<'a>(fields: &[FieldDescriptor], enums: &'a [LoweredEnum], name: &str) -> Result<&'a LoweredEnum, Error> {
+    fields
+        .iter()
+        .find(|field| field.name == name)
+        .and_then(|field| match &field.type_tokens {
+            FieldType::Enum(ty) | FieldType::OptionalEnum(ty) => enums.iter().find(|e| e.name == *ty),
+            _ => None,
+        })
+        .ok_or_else(|| Error::new("enum type not found"))
+}
+
+/// Returns the value of `key`, treating `null` as absent.
+/// Key of the unknown extension octets of an extended item.
+const EXTENSIONS: &str = "extensions";
+
+fn present<'a>(object: &'a Map<String, Value>, key: &str) -> Option<&'a Value> {
+    object.get(key).filter(|value| !value.is_null())
+}
+
+fn required<'a>(object: &'a Map<String, Value>, name: &Ident) -> Result<&'a Value, Error> {
+    present(object, &name.to_string()).ok_or_else(|| Error::new("missing"))
+}
+
+fn optional<T>(
+    object: &Map<String, Value>,
+    name: &Ident,
+    convert: impl FnOnce(&Value) -> Result<T, Error>,
+) -> Result<Option<T>, Error> {
+    present(object, &name.to_string()).map(convert).transpose()
+}
+
+fn as_object(json: &Value) -> Result<&Map<String, Value>, Error> {
+    json.as_object().ok_or_else(|| Error::new(format!("expected an object, got {}", json)))
+}
+
+/// Rejects keys that are not among `names`, which would otherwise be
+/// silently dropped.
+fn check_keys<'a>(
+    object: &Map<String, Value>,
+    names: impl Iterator<Item = &'a Ident> + Clone,
+    what: &str,
+) -> Result<(), Error> {
+    match object.keys().find(|key| !names.clone().any(|name| name == key)) {
+        Some(key) => Err(Error::new(format!("no {} `{}`", what, key))),
+        None => Ok(()),
+    }
+}
+
+fn number(json: &Value, bits: usize) -> Result<u64, Error> {
+    let value = json.as_u64().ok_or_else(|| Error::new(format!("expected an unsigned integer, got {}", json)))?;
+    if bits < 64 && value >> bits != 0 {
+        return Err(Error::new(format!("{} does not fit in {} bits", value, bits)));
+    }
+    Ok(value)
+}
+
+/// Returns the bits of a float of `bits` bits.
+fn float(json: &Value, bits: usize) -> Result<u64, Error> {
+    let value = json.as_f64().ok_or_else(|| Error::new(format!("expected a number, got {}", json)))?;
+    Ok(if bits == 32 { (value as f32).to_bits() as u64 } else { value.to_bits() })
+}
+
+/// Accepts a variant name as written by `ToJson`, or a raw value.
+fn variant(lowered: &LoweredEnum, json: &Value, bits: usize) -> Result<u64, Error> {
+    match json {
+        Value::String(name) => lowered
+            .variants
+            .iter()
+            .find(|v| v.name == name || v.alias.as_ref().is_some_and(|alias| alias == name))
+            .map(|v| v.value as u64)
+            .ok_or_else(|| Error::new(format!("`{}` is not a variant of {}", name, lowered.name))),
+        _ => number(json, bits),
+    }
+}
+
+fn string(json: &Value) -> Result<String, Error> {
+    json.as_str().map(str::to_string).ok_or_else(|| Error::new(format!("expected a string, got {}", json)))
+}
+
+/// Accepts the extension octets of an extended item, which must have FX set
+/// on every octet but the last.
+fn extension_octets(json: &Value) -> Result<Vec<u8>, Error> {
+    let array = json.as_array().ok_or_else(|| Error::new(format!("expected an array of bytes, got {}", json)))?;
+    let octets: Vec<u8> = array.iter().map(|byte| number(byte, 8).map(|byte| byte as u8)).collect::<Result<_, _>>()?;
+    let fx_broken = octets.split_last().is_some_and(|(last, octets)| {
+        last & 1 != 0 || octets.iter().any(|octet| octet & 1 == 0)
+    });
+    if fx_broken {
+        return Err(Error::new("FX must be set on every octet but the last"));
+    }
+    Ok(octets)
+}
+
+/// Accepts an array of `byte_len` bytes, as written by `ToJson`.
+fn bytes(json: &Value, byte_len: usize) -> Result<Vec<u8>, Error> {
+    let array = json.as_array().ok_or_else(|| Error::new(format!("expected an array of bytes, got {}", json)))?;
+    if array.len() != byte_len {
+        return Err(Error::new(format!("expected {} bytes, got {}", byte_len, array.len())));
+    }
+    array.iter().map(|byte| number(byte, 8).map(|byte| byte as u8)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rasterix_core::{BitReader, BitWriter};
+    use crate::parse::parser::parse_category;
+    use crate::transform::transformer::to_ir;
+
+    fn decoder() -> DynamicDecoder {
+        DynamicDecoder::from_ir(&to_ir(parse_category(r#"<category id="1">
+            <item id="10" frn="1">
+                <fixed bytes="1">
+                    <enum name="kind" bits="4">
+                        <value name="PSR" value="1"/>
+                        <value name="SSR" value="2"/>
+                    </enum>
+                    <field name="a" bits="4"/>
+                </fixed>
+            </item>
+            <item id="20" frn="2">
+                <extended bytes="2">
+                    <part index="0"><field name="b" bits="7"/></part>
+                    <part index="1"><field name="c" bits="7"/></part>
+                </extended>
+            </item>
+            <item id="30" frn="3">
+                <compound>
+                    <fixed bytes="1"><field name="d" bits="8"/></fixed>
+                    <repetitive bytes="1" counter="2"><field name="e" bits="8"/></repetitive>
+                </compound>
+            </item>
+        </category>"#).unwrap()).unwrap()).unwrap()
+    }
+
+    fn encode(decoder: &DynamicDecoder, text: &str) -> Result<Vec<u8>, String> {
+        let block = parse_block(decoder, text)?;
+        let mut bytes = Vec::new();
+        let mut writer = BitWriter::new(&mut bytes);
+        decoder.encode_block(&block, &mut writer).unwrap();
+        writer.flush().unwrap();
+        Ok(bytes)
+    }
+
+    #[test]
+    fn test_json_matches_decoded_record() {
+        let decoder = decoder();
+        let bytes = encode(&decoder, r#"[{
+            "item010": {"kind": "Ssr", "a": 5},
+            "item020": {"part0": {"b": 3}, "part1": null},
+            "item030": {"sub1": [{"e": 7}, {"e": 8}]}
+        }]"#).unwrap();
+
+        assert_eq!(bytes, [0x01, 0x00, 0x09, 0xe0, 0x25, 0x06, 0x40, 0x07, 0x08]);
+        let block = decoder.decode_block(&mut BitReader::new(&bytes[..])).unwrap();
+        assert_eq!(block, parse_block(&decoder, r#"{
+            "item010": {"kind": 2, "a": 5},
+            "item020": {"part0": {"b": 3}},
+            "item030": {"sub1": [{"e": 7}, {"e": 8}]}
+        }"#).unwrap());
+    }
+
+    #[test]
+    fn test_records_write_like_generated_json() {
+        let decoder = decoder();
+        let block = parse_block(&decoder, r#"{
+            "item010": {"kind": 7, "a": 5},
+            "item020": {"part0": {"b": 3}},
+            "item030": {"sub0": {"d": 1}}
+        }"#).unwrap();
+
+        assert_eq!(
+            record_to_json(&decoder, &block.records[0]).to_string(),
+            r#"{"item010":{"kind":7,"a":5},"item020":{"part0":{"b":3},"part1":null},"item030":{"sub0":{"d":1},"sub1":null}}"#
+        );
+        let block = parse_block(&decoder, r#"{"item010": {"kind": "Psr", "a": 0}}"#).unwrap();
+        assert_eq!(record_to_json(&decoder, &block.records[0]).to_string(), r#"{"item010":{"kind":"Psr","a":0}}"#);
+    }
+
+    #[test]
+    fn test_errors_name_the_value() {
+        let decoder = decoder();
+        for (text, error) in [
+            (r#"{"item011": {}}"#, "no item `item011`"),
+            (r#"{"item010": {"kind": "Psr"}}"#, "item010.a: missing"),
+            (r#"{"item010": {"kind": "Radar", "a": 1}}"#, "item010.kind: `Radar` is not a variant of Kind"),
+            (r#"{"item010": {"kind": 1, "a": 16}}"#, "item010.a: 16 does not fit in 4 bits"),
+            (r#"{"item020": {"part1": {"c": 1}}}"#, "item020.part0: missing"),
+            (r#"{"item030": {"sub1": [{"e": 1}]}}"#, "item030.sub1: expected 2 elements, got 1"),
+            (r#"[{}, {"item030": {"sub1": [{"e": 1}, {"f": 2}]}}]"#, "record 1: item030.sub1.1: no field `f`"),
+        ] {
+            assert_eq!(encode(&decoder, text).unwrap_err(), error);
+        }
+    }
+
+    #[test]
+    fn test_extensions_are_kept() {
+        let decoder = decoder();
+        let bytes = [0x01, 0x00, 0x07, 0x40, 0x07, 0x03, 0x02];
+        let block = decoder.decode_block(&mut BitReader::new(&bytes[..])).unwrap();
+        let json = r#"{"item020":{"part0":{"b":3},"part1":{"c":1},"extensions":[2]}}"#;
+
+        assert_eq!(record_to_json(&decoder, &block.records[0]).to_string(), json);
+        assert_eq!(encode(&decoder, json).unwrap(), bytes);
+        for (text, error) in [
+            (r#"{"item020": {"part0": {"b": 3}, "extensions": [2]}}"#, "item020.extensions: present after absent part1"),
+            (r#"{"item020": {"part0": {"b": 3}, "part1": {"c": 1}, "extensions": [3]}}"#, "item020.extensions: FX must be set on every octet but the last"),
+        ] {
+            assert_eq!(encode(&decoder, text).unwrap_err(), error);
+        }
+    }
+
+    #[test]
+    fn test_floats_are_numbers() {
+        let decoder = DynamicDecoder::from_ir(&to_ir(parse_category(r#"<category id="250">
+            <item id="10" frn="1">
+                <fixed bytes="13">
+                    <field name="t" bits="32" type="f32"/>
+                    <epb><field name="r" bits="64" type="f64"/></epb>
+                    <spare bits="7"/>
+                </fixed>
+            </item>
+        </category>"#).unwrap()).unwrap()).unwrap();
+        let block = parse_block(&decoder, r#"{"item010": {"t": 0.1, "r": 1.5}}"#).unwrap();
+        let bytes = encode(&decoder, r#"{"item010": {"t": 0.1, "r": 1.5}}"#).unwrap();
+
+        assert_eq!(&bytes[4..8], 0.1f32.to_bits().to_be_bytes());
+        assert_eq!(record_to_json(&decoder, &block.records[0]).to_string(), r#"{"item010":{"t":0.1,"r":1.5}}"#);
+        assert_eq!(encode(&decoder, r#"{"item010": {"t": "warm", "r": null}}"#).unwrap_err(), "item010.t: expected a number, got \"warm\"");
+    }
+
+    #[test]
+    fn test_bytes_are_arrays() {
+        let decoder = DynamicDecoder::from_ir(&to_ir(parse_category(r#"<category id="48">
+            <item id="250" frn="1">
+                <fixed bytes="4">
+                    <field name="mb" bits="16" type="bytes"/>
+                    <epb><field name="sig" bits="8" type="bytes"/></epb>
+                    <spare bits="7"/>
+                </fixed>
+            </item>
+        </category>"#).unwrap()).unwrap()).unwrap();
+        let json = r#"{"item250":{"mb":[171,205],"sig":null}}"#;
+        let block = parse_block(&decoder, json).unwrap();
+
+        assert_eq!(&encode(&decoder, json).unwrap()[4..], [0xAB, 0xCD, 0x00, 0x00]);
+        assert_eq!(record_to_json(&decoder, &block.records[0]).to_string(), json);
+        assert_eq!(encode(&decoder, r#"{"item250": {"mb": [1], "sig": null}}"#).unwrap_err(), "item250.mb: expected 2 bytes, got 1");
+    }
+}
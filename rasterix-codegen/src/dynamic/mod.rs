@@ -0,0 +1,28 @@
+//! Decoding and encoding without generated code.
+//!
+//! [`DynamicDecoder`] interprets a lowered category at runtime, reading and
+//! writing exactly what the generated `Decode`/`Encode` implementations
+//! would, into generic [`value`] types.  It is slower than generated code
+//! but needs no build step, which makes it suitable for tooling: checking a
+//! new definition against recorded traffic ([`verify`]), inspecting
+//! messages, or converting them.  Records can also be described as JSON
+//! ([`json`]) and encoded from that, and synthetic traffic generated for
+//! load testing ([`generator`]) or scripted for integration tests
+//! (`scenario`, with the `scenario` feature).  Recordings can be stripped of
+//! the identities they hold before being shared ([`anonymize`]), or checked
+//! for the parts of a definition they never exercise ([`coverage`]).
+
+pub mod value;
+pub mod decoder;
+pub mod json;
+pub mod verify;
+pub mod generator;
+pub mod anonymize;
+pub mod coverage;
+#[cfg(feature = "scenario")]
+pub mod scenario;
+
+pub use decoder::DynamicDecoder;
+pub use value::{DynamicBlock, DynamicItem, DynamicRecord, Field, FieldValue};
+pub use verify::{verify_blocks, VerifyReport};
+pub use generator::{TrafficConfig, TrafficGenerator};
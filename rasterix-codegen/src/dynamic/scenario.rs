@@ -0,0 +1,608 @@
+//! Scripted air situations for integration tests.
+//!
+//! A scenario describes a sensor and the trajectories of a few targets in
+//! YAML.  [`Scenario::blocks`] turns it into the timed data blocks the
+//! sensor would send, in any category whose fields it recognizes by name,
+//! typically CAT048 plots or CAT062 tracks:
+//!
+//! ```yaml
+//! start: 43200          # Unix time of t = 0, in seconds (12:00:00 UTC)
+//! sensor: { sac: 25, sic: 7 }
+//! targets:
+//!   - track_number: 101
+//!     callsign: AZA123
+//!     mode3a: "1234"     # octal
+//!     address: 0x3C6586
+//!     update_period: 4   # seconds between reports
+//!     waypoints:         # t in seconds; x east and y north of the sensor, in NM
+//!       - { t: 0, x: -20, y: 5, fl: 350 }
+//!       - { t: 120, x: 20, y: 5 }
+//! ```
+//!
+//! Targets report every `update_period` seconds from their first waypoint to
+//! their last, moving in straight lines between waypoints.  A waypoint's
+//! flight level holds until the next one that has one, and is interpolated
+//! in between.
+//!
+//! Records contain the items with at least one recognized field:
+//!
+//! | Field | Value |
+//! |-------|-------|
+//! | `sac`, `sic` | the sensor |
+//! | `*time*` | time of day, in 1/128 s |
+//! | `track_number`, `trn` | track number |
+//! | `rho`, `theta` | range in 1/256 NM, azimuth in 360/2^16 degrees |
+//! | `x`, `y` | position, in 0.5 m in CAT062 and 1/128 NM otherwise |
+//! | `vx`, `vy` | velocity, in 0.25 m/s |
+//! | `ground_speed`, `heading` | speed in 2^-14 NM/s, heading in 360/2^16 degrees |
+//! | `*flight_level*` | flight level, in 1/4 FL |
+//! | `mode3a` | Mode 3/A code |
+//! | `*address*` | aircraft address |
+//! | `aircraft_id`, `callsign`, `target_identification` | callsign |
+//!
+//! Their other fields are zero.  Enabled by the `scenario` feature.
+
+use std::time::{Duration, SystemTime};
+
+use rasterix_core::{BitWriter, Timestamped};
+use serde::Deserialize;
+
+use crate::transform::lower_ir::{DecodeOp, LoweredItemKind, LoweredPart, LoweredSubItemKind};
+use super::value::{DynamicBlock, DynamicItem, DynamicRecord, Field, FieldValue};
+use super::DynamicDecoder;
+
+/// Meters in a nautical mile.
+const NM: f64 = 1852.0;
+
+/// A sensor and the targets it reports.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    /// Unix time of scenario time 0, in seconds.
+    #[serde(default)]
+    pub start: f64,
+    pub sensor: Sensor,
+    pub targets: Vec<Target>,
+}
+
+/// Identity of the reporting sensor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Sensor {
+    pub sac: u8,
+    pub sic: u8,
+}
+
+/// A simulated target.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Target {
+    pub track_number: u16,
+    #[serde(default)]
+    pub callsign: Option<String>,
+    /// Mode 3/A code, in octal digits.
+    #[serde(default)]
+    pub mode3a: Option<String>,
+    /// 24-bit ICAO aircraft address.
+    #[serde(default)]
+    pub address: Option<u32>,
+    /// Seconds between reports.
+    #[serde(default = "default_update_period")]
+    pub update_period: f64,
+    pub waypoints: Vec<Waypoint>,
+}
+
+fn default_update_period() -> f64 {
+    4.0
+}
+
+/// A point of a trajectory.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Waypoint {
+    /// Scenario time, in seconds.
+    pub t: f64,
+    /// Distance east of the sensor, in NM.
+    pub x: f64,
+    /// Distance north of the sensor, in NM.
+    pub y: f64,
+    /// Flight level.
+    #[serde(default)]
+    pub fl: Option<f64>,
+}
+
+/// The state of a target at one of its reports.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    /// Scenario time, in seconds.
+    pub t: f64,
+    pub track_number: u16,
+    /// Distance east of the sensor, in NM.
+    pub x: f64,
+    /// Distance north of the sensor, in NM.
+    pub y: f64,
+    /// Velocity east, in NM/s.
+    pub vx: f64,
+    /// Velocity north, in NM/s.
+    pub vy: f64,
+    pub flight_level: Option<f64>,
+    pub callsign: Option<String>,
+    pub mode3a: Option<u16>,
+    pub address: Option<u32>,
+}
+
+impl Report {
+    /// Slant range from the sensor, in NM.
+    pub fn range(&self) -> f64 {
+        self.x.hypot(self.y)
+    }
+
+    /// Azimuth from the sensor, in degrees clockwise from north.
+    pub fn azimuth(&self) -> f64 {
+        degrees(self.x, self.y)
+    }
+
+    /// Ground speed, in NM/s.
+    pub fn ground_speed(&self) -> f64 {
+        self.vx.hypot(self.vy)
+    }
+
+    /// Heading, in degrees clockwise from north.
+    pub fn heading(&self) -> f64 {
+        degrees(self.vx, self.vy)
+    }
+}
+
+/// Returns the direction of (`east`, `north`), in degrees clockwise from
+/// north.
+fn degrees(east: f64, north: f64) -> f64 {
+    east.atan2(north).to_degrees().rem_euclid(360.0)
+}
+
+impl Scenario {
+    /// Parses and checks a scenario.
+    ///
+    /// Errors name the offending target, e.g. `target 101: waypoint times
+    /// must increase`.
+    pub fn from_yaml(text: &str) -> Result<Self, String> {
+        let scenario: Scenario = serde_yaml::from_str(text).map_err(|e| format!("invalid scenario: {}", e))?;
+        for target in &scenario.targets {
+            target.check().map_err(|e| format!("target {}: {}", target.track_number, e))?;
+        }
+        Ok(scenario)
+    }
+
+    /// Returns the reports of every target, in time order.
+    pub fn reports(&self) -> Vec<Report> {
+        let mut reports: Vec<Report> = self.targets.iter().flat_map(Target::reports).collect();
+        reports.sort_by(|a, b| a.t.total_cmp(&b.t).then(a.track_number.cmp(&b.track_number)));
+        reports
+    }
+
+    /// Returns the records of every report in the category of `decoder`,
+    /// stamped with their time.
+    pub fn records(&self, decoder: &DynamicDecoder) -> Vec<Timestamped<DynamicRecord>> {
+        self.reports()
+            .iter()
+            .map(|report| Timestamped::new(self.time(report.t), self.record(decoder, report)))
+            .collect()
+    }
+
+    /// Returns encoded data blocks, one per instant with reports, stamped
+    /// with its time.
+    pub fn blocks(&self, decoder: &DynamicDecoder) -> Vec<Timestamped<Vec<u8>>> {
+        let mut blocks: Vec<Timestamped<DynamicBlock>> = Vec::new();
+        for record in self.records(decoder) {
+            match blocks.last_mut() {
+                Some(block) if block.recv_time == record.recv_time => block.data.records.push(record.data),
+                _ => blocks.push(Timestamped::new(record.recv_time, DynamicBlock { records: vec![record.data] })),
+            }
+        }
+
+        blocks
+            .into_iter()
+            .map(|block| {
+                let mut data = Vec::new();
+                let mut writer = BitWriter::new(&mut data);
+                decoder.encode_block(&block.data, &mut writer).expect("scenario records match their definition");
+                writer.flush().expect("writing to a Vec does not fail");
+                Timestamped::new(block.recv_time, data)
+            })
+            .collect()
+    }
+
+    fn time(&self, t: f64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs_f64((self.start + t).max(0.0))
+    }
+
+    fn record(&self, decoder: &DynamicDecoder, report: &Report) -> DynamicRecord {
+        let values = Values {
+            category: decoder.lowered().category_id,
+            sensor: self.sensor,
+            time_of_day: (self.start + report.t).rem_euclid(86_400.0),
+            report,
+        };
+        let items = decoder.record_items().map(|item| values.item(&item.kind)).collect();
+        DynamicRecord { items }
+    }
+}
+
+impl Target {
+    fn check(&self) -> Result<(), String> {
+        if self.waypoints.is_empty() {
+            return Err("no waypoints".to_string());
+        }
+        if self.waypoints.windows(2).any(|pair| pair[0].t >= pair[1].t) {
+            return Err("waypoint times must increase".to_string());
+        }
+        if self.update_period.is_nan() || self.update_period <= 0.0 {
+            return Err(format!("update_period must be positive, got {}", self.update_period));
+        }
+        if let Some(code) = &self.mode3a {
+            parse_mode3a(code).ok_or_else(|| format!("invalid mode3a {:?}: expected 4 octal digits", code))?;
+        }
+        if self.address.is_some_and(|address| address > 0xFF_FFFF) {
+            return Err("address must fit in 24 bits".to_string());
+        }
+        Ok(())
+    }
+
+    fn reports(&self) -> Vec<Report> {
+        let first = self.waypoints[0].t;
+        let last = self.waypoints[self.waypoints.len() - 1].t;
+        let mode3a = self.mode3a.as_deref().and_then(parse_mode3a);
+
+        let mut reports = Vec::new();
+        let mut t = first;
+        let mut count = 0;
+        while t <= last {
+            let (x, y, vx, vy) = self.position(t);
+            reports.push(Report {
+                t,
+                track_number: self.track_number,
+                x,
+                y,
+                vx,
+                vy,
+                flight_level: self.flight_level(t),
+                callsign: self.callsign.clone(),
+                mode3a,
+                address: self.address,
+            });
+            count += 1;
+            t = first + count as f64 * self.update_period;
+        }
+        reports
+    }
+
+    /// Returns the position and velocity at `t`, within the trajectory.
+    fn position(&self, t: f64) -> (f64, f64, f64, f64) {
+        let Some(i) = self.waypoints.windows(2).position(|pair| t <= pair[1].t) else {
+            let only = self.waypoints[0];
+            return (only.x, only.y, 0.0, 0.0);
+        };
+        let (a, b) = (self.waypoints[i], self.waypoints[i + 1]);
+        let dt = b.t - a.t;
+        let (vx, vy) = ((b.x - a.x) / dt, (b.y - a.y) / dt);
+        (a.x + vx * (t - a.t), a.y + vy * (t - a.t), vx, vy)
+    }
+
+    /// Returns the flight level at `t`: the latest one given, interpolated
+    /// towards the next one.
+    fn flight_level(&self, t: f64) -> Option<f64> {
+        let before = self.waypoints.iter().rev().find(|w| w.t <= t && w.fl.is_some())?;
+        let level = before.fl?;
+        match self.waypoints.iter().find(|w| w.t > t && w.fl.is_some()) {
+            Some(after) => Some(level + (after.fl? - level) * (t - before.t) / (after.t - before.t)),
+            None => Some(level),
+        }
+    }
+}
+
+/// Parses a Mode 3/A code given in octal digits.
+fn parse_mode3a(code: &str) -> Option<u16> {
+    u16::from_str_radix(code, 8).ok().filter(|code| *code <= 0o7777)
+}
+
+/// Field values of one report in one category.
+struct Values<'a> {
+    category: u8,
+    sensor: Sensor,
+    /// Seconds since midnight.
+    time_of_day: f64,
+    report: &'a Report,
+}
+
+impl Values<'_> {
+    /// Returns the item if it has a recognized field.
+    fn item(&self, kind: &LoweredItemKind) -> Option<DynamicItem> {
+        match kind {
+            LoweredItemKind::Simple { decode_ops, .. } => self.fields(decode_ops).map(DynamicItem::Fields),
+            LoweredItemKind::Extended { parts } => self.extended(parts),
+            LoweredItemKind::Repetitive { .. } => None,
+            LoweredItemKind::Compound { sub_items } => {
+                let subs: Vec<_> = sub_items
+                    .iter()
+                    .map(|sub| match &sub.kind {
+                        LoweredSubItemKind::Simple { decode_ops, .. } => self.fields(decode_ops).map(DynamicItem::Fields),
+                        LoweredSubItemKind::Extended { parts } => self.extended(parts),
+                        LoweredSubItemKind::Repetitive { .. } => None,
+                    })
+                    .collect();
+                subs.iter().any(Option::is_some).then_some(DynamicItem::Compound(subs))
+            }
+        }
+    }
+
+    /// Returns the parts up to the last one with a recognized field, which
+    /// must be the first one or required.
+    fn extended(&self, parts: &[LoweredPart]) -> Option<DynamicItem> {
+        let known: Vec<_> = parts.iter().map(|part| self.fields(&part.decode_ops)).collect();
+        let count = known.iter().rposition(Option::is_some)? + 1;
+        let count = count.max(parts.iter().rposition(|part| part.is_required).map_or(1, |i| i + 1));
+        let values = parts
+            .iter()
+            .zip(known)
+            .enumerate()
+            .map(|(i, (part, fields))| {
+                (i < count).then(|| fields.unwrap_or_else(|| self.fields_or_zero(&part.decode_ops)))
+            })
+            .collect();
+        Some(DynamicItem::Extended(values, Vec::new()))
+    }
+
+    /// Returns the fields of `ops` if at least one of them is recognized.
+    fn fields(&self, ops: &[DecodeOp]) -> Option<Vec<Field>> {
+        let mut known = false;
+        let fields = self.fields_with(ops, &mut known);
+        known.then_some(fields)
+    }
+
+    fn fields_or_zero(&self, ops: &[DecodeOp]) -> Vec<Field> {
+        self.fields_with(ops, &mut false)
+    }
+
+    fn fields_with(&self, ops: &[DecodeOp], known: &mut bool) -> Vec<Field> {
+        let mut fields = Vec::new();
+        for op in ops {
+            let (name, value) = match op {
+                DecodeOp::ReadField { name, bits, .. } | DecodeOp::ReadEnum { name, bits, .. } => {
+                    let value = self.number(&name.to_string(), *bits);
+                    *known |= value.is_some();
+                    (name, FieldValue::Unsigned(value.unwrap_or(0)))
+                }
+                DecodeOp::ReadEpbField { name, bits, .. } | DecodeOp::ReadEpbEnum { name, bits, .. } => {
+                    let value = self.number(&name.to_string(), *bits);
+                    *known |= value.is_some();
+                    (name, FieldValue::Epb(value))
+                }
+                DecodeOp::ReadString { name, byte_len } => {
+                    let value = self.text(&name.to_string(), *byte_len);
+                    *known |= value.is_some();
+                    (name, FieldValue::String(value.unwrap_or_else(|| " ".repeat(*byte_len))))
+                }
+                DecodeOp::ReadEpbString { name, byte_len } => {
+                    let value = self.text(&name.to_string(), *byte_len);
+                    *known |= value.is_some();
+                    (name, FieldValue::EpbString(value))
+                }
+                // No report value is carried in a byte array.
+                DecodeOp::ReadBytes { name, byte_len } => (name, FieldValue::Bytes(vec![0; *byte_len])),
+                DecodeOp::ReadEpbBytes { name, .. } => (name, FieldValue::EpbBytes(None)),
+                DecodeOp::SkipSpare { .. } | DecodeOp::Custom { .. } | DecodeOp::ReadLengthByte { .. } => continue,
+            };
+            fields.push(Field { name: name.to_string(), value });
+        }
+        fields
+    }
+
+    /// Returns the raw value of a recognized numeric field.
+    fn number(&self, name: &str, bits: usize) -> Option<u64> {
+        let report = self.report;
+        let value = match name {
+            "sac" => self.sensor.sac as f64,
+            "sic" => self.sensor.sic as f64,
+            "track_number" | "trn" => report.track_number as f64,
+            "rho" => report.range() * 256.0,
+            "theta" => report.azimuth() * 65_536.0 / 360.0,
+            "x" | "y" => {
+                let nm = if name == "x" { report.x } else { report.y };
+                if self.category == 62 { nm * NM * 2.0 } else { nm * 128.0 }
+            }
+            "vx" => report.vx * NM * 4.0,
+            "vy" => report.vy * NM * 4.0,
+            "ground_speed" => report.ground_speed() * 16_384.0,
+            "heading" => report.heading() * 65_536.0 / 360.0,
+            "mode3a" => report.mode3a? as f64,
+            _ if name.contains("time") => self.time_of_day * 128.0,
+            _ if name.contains("flight_level") => report.flight_level? * 4.0,
+            _ if name.contains("address") => report.address? as f64,
+            _ => return None,
+        };
+        // Negative values wrap to two's complement.
+        let mask = if bits >= 64 { u64::MAX } else { (1 << bits) - 1 };
+        Some(value.round() as i64 as u64 & mask)
+    }
+
+    /// Returns the callsign, padded with spaces, for a callsign field.
+    fn text(&self, name: &str, byte_len: usize) -> Option<String> {
+        match name {
+            "aircraft_id" | "callsign" | "target_identification" => {
+                let callsign = self.report.callsign.as_deref()?;
+                Some(format!("{:<width$.width$}", callsign, width = byte_len))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dynamic::FieldValue;
+    use crate::parse::parser::parse_category;
+    use crate::transform::transformer::to_ir;
+    use rasterix_core::BitReader;
+
+    const SCENARIO: &str = r#"
+start: 43200
+sensor: { sac: 25, sic: 7 }
+targets:
+  - track_number: 101
+    callsign: AZA123
+    mode3a: "1234"
+    address: 0x3C6586
+    waypoints:
+      - { t: 0, x: 0, y: 10, fl: 300 }
+      - { t: 8, x: 0, y: 12, fl: 340 }
+  - track_number: 102
+    update_period: 2
+    waypoints:
+      - { t: 2, x: -5, y: 0 }
+      - { t: 4, x: -5, y: 0 }
+"#;
+
+    const CAT048: &str = r#"<category id="48">
+        <item id="10" frn="1">
+            <fixed bytes="2"><field name="sac" bits="8"/><field name="sic" bits="8"/></fixed>
+        </item>
+        <item id="140" frn="2">
+            <fixed bytes="3"><field name="time_of_day" bits="24"/></fixed>
+        </item>
+        <item id="40" frn="3">
+            <fixed bytes="4"><field name="rho" bits="16"/><field name="theta" bits="16"/></fixed>
+        </item>
+        <item id="70" frn="4">
+            <fixed bytes="2">
+                <field name="v" bits="1"/><field name="g" bits="1"/><field name="l" bits="1"/>
+                <spare bits="1"/><field name="mode3a" bits="12"/>
+            </fixed>
+        </item>
+        <item id="90" frn="5">
+            <fixed bytes="2">
+                <field name="v" bits="1"/><field name="g" bits="1"/><spare bits="2"/>
+                <field name="flight_level" bits="12"/>
+            </fixed>
+        </item>
+        <item id="240" frn="6">
+            <fixed bytes="6"><field name="aircraft_id" bits="48" type="string"/></fixed>
+        </item>
+        <item id="161" frn="7">
+            <fixed bytes="2"><spare bits="4"/><field name="track_number" bits="12"/></fixed>
+        </item>
+        <item id="30" frn="8">
+            <fixed bytes="1"><field name="code" bits="8"/></fixed>
+        </item>
+    </category>"#;
+
+    fn decoder(xml: &str) -> DynamicDecoder {
+        DynamicDecoder::from_ir(&to_ir(parse_category(xml).unwrap()).unwrap()).unwrap()
+    }
+
+    fn unsigned(item: &Option<DynamicItem>, name: &str) -> u64 {
+        let Some(DynamicItem::Fields(fields)) = item else { panic!("{:?}", item) };
+        match &fields.iter().find(|field| field.name == name).unwrap().value {
+            FieldValue::Unsigned(value) => *value,
+            value => panic!("{:?}", value),
+        }
+    }
+
+    #[test]
+    fn targets_report_along_their_trajectories() {
+        let scenario = Scenario::from_yaml(SCENARIO).unwrap();
+        let reports = scenario.reports();
+
+        let times: Vec<_> = reports.iter().map(|report| (report.t, report.track_number)).collect();
+        assert_eq!(times, [(0.0, 101), (2.0, 102), (4.0, 101), (4.0, 102), (8.0, 101)]);
+
+        let middle = &reports[2];
+        assert_eq!((middle.x, middle.y), (0.0, 11.0));
+        assert_eq!(middle.flight_level, Some(320.0));
+        assert_eq!(middle.ground_speed(), 0.25);
+        assert_eq!(middle.heading(), 0.0);
+        assert_eq!(middle.mode3a, Some(0o1234));
+
+        assert_eq!(reports[1].azimuth(), 270.0);
+        assert_eq!(reports[1].flight_level, None);
+    }
+
+    #[test]
+    fn cat048_blocks_carry_report_values() {
+        let scenario = Scenario::from_yaml(SCENARIO).unwrap();
+        let decoder = decoder(CAT048);
+        let blocks = scenario.blocks(&decoder);
+
+        // Reports at the same time share a block.
+        assert_eq!(blocks.len(), 4);
+        assert_eq!(blocks[2].recv_time, SystemTime::UNIX_EPOCH + Duration::from_secs(43_204));
+
+        let block = decoder.decode_block(&mut BitReader::new(&blocks[2].data[..])).unwrap();
+        assert_eq!(block.records.len(), 2);
+        let [first, second] = &block.records[..] else { panic!() };
+
+        assert_eq!((unsigned(&first.items[0], "sac"), unsigned(&first.items[0], "sic")), (25, 7));
+        assert_eq!(unsigned(&first.items[1], "time_of_day"), 43_204 * 128);
+        assert_eq!(unsigned(&first.items[2], "rho"), 11 * 256);
+        assert_eq!(unsigned(&first.items[2], "theta"), 0);
+        assert_eq!(unsigned(&first.items[3], "mode3a"), 0o1234);
+        assert_eq!(unsigned(&first.items[4], "flight_level"), 320 * 4);
+        assert_eq!(
+            first.items[5],
+            Some(DynamicItem::Fields(vec![Field {
+                name: "aircraft_id".to_string(),
+                value: FieldValue::String("AZA123".to_string()),
+            }]))
+        );
+        assert_eq!(unsigned(&first.items[6], "track_number"), 101);
+        // No field of I048/030 is recognized.
+        assert_eq!(first.items[7], None);
+
+        // Without a callsign, code or flight level, their items are left out.
+        assert_eq!(unsigned(&second.items[2], "theta"), 3 * 16_384);
+        assert_eq!((&second.items[3], &second.items[4], &second.items[5]), (&None, &None, &None));
+    }
+
+    #[test]
+    fn cat062_positions_are_cartesian_in_meters() {
+        let xml = r#"<category id="62">
+            <item id="40" frn="1">
+                <fixed bytes="2"><field name="track_number" bits="16"/></fixed>
+            </item>
+            <item id="100" frn="2">
+                <fixed bytes="6"><field name="x" bits="24"/><field name="y" bits="24"/></fixed>
+            </item>
+            <item id="185" frn="3">
+                <fixed bytes="4"><field name="vx" bits="16"/><field name="vy" bits="16"/></fixed>
+            </item>
+        </category>"#;
+        let scenario = Scenario::from_yaml(SCENARIO).unwrap();
+        let decoder = decoder(xml);
+
+        let records = scenario.records(&decoder);
+        let target = &records[1].data;
+        assert_eq!(unsigned(&target.items[0], "track_number"), 102);
+        // -5 NM in two's complement, in 0.5 m.
+        assert_eq!(unsigned(&target.items[1], "x"), (1 << 24) - 5 * 1852 * 2);
+        assert_eq!(unsigned(&target.items[1], "y"), 0);
+        assert_eq!(unsigned(&records[0].data.items[2], "vy"), (0.25 * 1852.0 * 4.0) as u64);
+    }
+
+    #[test]
+    fn invalid_scenarios_are_rejected() {
+        let error = |text: &str| Scenario::from_yaml(text).unwrap_err();
+
+        assert!(error("sensor: { sac: 1, sic: 2 }\ntargets: []\nspeed: 3").starts_with("invalid scenario: unknown field"));
+        assert_eq!(
+            error("sensor: { sac: 1, sic: 2 }\ntargets:\n  - { track_number: 5, waypoints: [] }"),
+            "target 5: no waypoints"
+        );
+        assert_eq!(
+            error("sensor: { sac: 1, sic: 2 }\ntargets:\n  - track_number: 5\n    waypoints: [{ t: 3, x: 0, y: 0 }, { t: 1, x: 0, y: 0 }]"),
+            "target 5: waypoint times must increase"
+        );
+        assert_eq!(
+            error("sensor: { sac: 1, sic: 2 }\ntargets:\n  - track_number: 5\n    mode3a: \"8000\"\n    waypoints: [{ t: 0, x: 0, y: 0 }]"),
+            "target 5: invalid mode3a \"8000\": expected 4 octal digits"
+        );
+    }
+}
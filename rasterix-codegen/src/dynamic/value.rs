@@ -0,0 +1,58 @@
+//! Generic values produced by the [`DynamicDecoder`](super::DynamicDecoder).
+//!
+//! The shapes mirror the generated types: fields are listed in definition
+//! order under their generated names, and optional parts, sub-items and
+//! items are `None` when absent.
+
+/// A decoded field value.
+///
+/// Enums are kept as their raw value, so unknown values round-trip.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldValue {
+    /// Plain numeric field or enum.
+    Unsigned(u64),
+    /// EPB-guarded numeric field or enum; `None` when the valid bit is clear.
+    Epb(Option<u64>),
+    /// Fixed-length string, with trailing padding removed.
+    String(String),
+    /// EPB-guarded string; `None` when the valid bit is clear.
+    EpbString(Option<String>),
+    /// Fixed-length byte array.
+    Bytes(Vec<u8>),
+    /// EPB-guarded byte array; `None` when the valid bit is clear.
+    EpbBytes(Option<Vec<u8>>),
+}
+
+/// A named field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: String,
+    pub value: FieldValue,
+}
+
+/// A decoded item or compound sub-item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DynamicItem {
+    /// Fixed or explicit item.
+    Fields(Vec<Field>),
+    /// Extended item: one entry per part, `None` for absent parts, then
+    /// the extension octets received after the last part, FX bits
+    /// included.
+    Extended(Vec<Option<Vec<Field>>>, Vec<u8>),
+    /// Repetitive item: one entry per element.
+    Repetitive(Vec<Vec<Field>>),
+    /// Compound item: one entry per sub-item, `None` for absent sub-items.
+    Compound(Vec<Option<DynamicItem>>),
+}
+
+/// A decoded record: one entry per record item, in FRN order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DynamicRecord {
+    pub items: Vec<Option<DynamicItem>>,
+}
+
+/// A decoded data block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DynamicBlock {
+    pub records: Vec<DynamicRecord>,
+}
@@ -0,0 +1,200 @@
+//! Lossless round-trip checks against sample traffic.
+
+use std::fmt;
+
+use rasterix_core::{BitReader, BitWriter};
+
+use super::DynamicDecoder;
+
+/// A bit that differs between a sample and its re-encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitDifference {
+    /// Byte offset into the samples.
+    pub byte: usize,
+    /// Bit within the byte, 7 being the most significant.
+    pub bit: u8,
+    /// Value of the bit in the sample.
+    pub expected: bool,
+}
+
+/// Result of checking one data block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockOutcome {
+    /// Re-encoding reproduced the block exactly.
+    Lossless,
+    /// Re-encoding changed the block.
+    Differs {
+        /// Length of the re-encoded block.
+        encoded_len: usize,
+        /// Differing bits within the shorter of the two blocks.
+        differences: Vec<BitDifference>,
+    },
+    /// The block could not be decoded or encoded.
+    Failed(String),
+}
+
+/// Check of one data block in the samples.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockReport {
+    /// Byte offset of the block in the samples.
+    pub offset: usize,
+    /// Length of the block in the samples.
+    pub len: usize,
+    pub outcome: BlockOutcome,
+}
+
+/// Result of [`verify_blocks`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerifyReport {
+    pub blocks: Vec<BlockReport>,
+}
+
+impl VerifyReport {
+    /// Returns `true` if every block was re-encoded exactly.
+    pub fn is_lossless(&self) -> bool {
+        self.blocks.iter().all(|b| b.outcome == BlockOutcome::Lossless)
+    }
+}
+
+impl fmt::Display for VerifyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for block in &self.blocks {
+            write!(f, "block at byte {} ({} bytes): ", block.offset, block.len)?;
+            match &block.outcome {
+                BlockOutcome::Lossless => writeln!(f, "lossless")?,
+                BlockOutcome::Differs { encoded_len, differences } => {
+                    if *encoded_len != block.len {
+                        write!(f, "re-encoded to {} bytes, ", encoded_len)?;
+                    }
+                    writeln!(f, "{} bit(s) differ", differences.len())?;
+                    for d in differences {
+                        writeln!(
+                            f,
+                            "  byte {} bit {}: expected {}, got {}",
+                            d.byte, d.bit, d.expected as u8, !d.expected as u8
+                        )?;
+                    }
+                }
+                BlockOutcome::Failed(error) => writeln!(f, "failed: {}", error)?,
+            }
+        }
+        let lossless = self.blocks.iter().filter(|b| b.outcome == BlockOutcome::Lossless).count();
+        write!(f, "{} block(s), {} lossless", self.blocks.len(), lossless)
+    }
+}
+
+/// Decodes each data block in `samples` and encodes it again, reporting
+/// every bit that changed.
+///
+/// `samples` holds consecutive data blocks, split using their LEN field.
+/// Checking stops at a block whose header is truncated or inconsistent,
+/// which is reported as failed.
+pub fn verify_blocks(decoder: &DynamicDecoder, samples: &[u8]) -> VerifyReport {
+    let mut report = VerifyReport::default();
+    let mut offset = 0;
+
+    while offset < samples.len() {
+        let rest = &samples[offset..];
+        let len = match rest {
+            [_, hi, lo, ..] => u16::from_be_bytes([*hi, *lo]) as usize,
+            _ => 0,
+        };
+        if len < 3 || len > rest.len() {
+            report.blocks.push(BlockReport {
+                offset,
+                len: rest.len(),
+                outcome: BlockOutcome::Failed("truncated or invalid data block header".to_string()),
+            });
+            break;
+        }
+
+        let block = &rest[..len];
+        report.blocks.push(BlockReport { offset, len, outcome: check_block(decoder, block, offset) });
+        offset += len;
+    }
+
+    report
+}
+
+fn check_block(decoder: &DynamicDecoder, block: &[u8], offset: usize) -> BlockOutcome {
+    let decoded = match decoder.decode_block(&mut BitReader::new(block)) {
+        Ok(decoded) => decoded,
+        Err(e) => return BlockOutcome::Failed(format!("decoding: {}", e)),
+    };
+
+    let mut encoded = Vec::new();
+    let mut writer = BitWriter::new(&mut encoded);
+    if let Err(e) = decoder.encode_block(&decoded, &mut writer).and_then(|()| Ok(writer.flush()?)) {
+        return BlockOutcome::Failed(format!("encoding: {}", e));
+    }
+
+    if encoded == block {
+        return BlockOutcome::Lossless;
+    }
+
+    let mut differences = Vec::new();
+    for (i, (expected, actual)) in block.iter().zip(&encoded).enumerate() {
+        let diff = expected ^ actual;
+        for bit in (0..8).rev() {
+            if diff & (1 << bit) != 0 {
+                differences.push(BitDifference {
+                    byte: offset + i,
+                    bit,
+                    expected: expected & (1 << bit) != 0,
+                });
+            }
+        }
+    }
+
+    BlockOutcome::Differs { encoded_len: encoded.len(), differences }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parser::parse_category;
+    use crate::transform::transformer::to_ir;
+
+    fn decoder() -> DynamicDecoder {
+        DynamicDecoder::from_ir(&to_ir(parse_category(r#"<category id="1">
+            <item id="10" frn="1">
+                <fixed bytes="1">
+                    <field name="a" bits="4"/>
+                    <spare bits="4"/>
+                </fixed>
+            </item>
+        </category>"#).unwrap()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_lossless_blocks() {
+        let report = verify_blocks(&decoder(), &[0x01, 0x00, 0x05, 0x80, 0x50, 0x01, 0x00, 0x05, 0x80, 0x70]);
+
+        assert_eq!(report.blocks.len(), 2);
+        assert_eq!(report.blocks[1].offset, 5);
+        assert!(report.is_lossless());
+    }
+
+    #[test]
+    fn test_spare_bits_reported_with_offsets() {
+        let report = verify_blocks(&decoder(), &[0x01, 0x00, 0x05, 0x80, 0x50, 0x01, 0x00, 0x05, 0x80, 0x52]);
+
+        assert!(!report.is_lossless());
+        assert_eq!(
+            report.blocks[1].outcome,
+            BlockOutcome::Differs {
+                encoded_len: 5,
+                differences: vec![BitDifference { byte: 9, bit: 1, expected: true }],
+            }
+        );
+        assert!(report.to_string().contains("  byte 9 bit 1: expected 1, got 0"));
+    }
+
+    #[test]
+    fn test_truncated_block() {
+        let report = verify_blocks(&decoder(), &[0x01, 0x00, 0x09, 0x80]);
+
+        assert!(matches!(report.blocks[0].outcome, BlockOutcome::Failed(_)));
+        assert_eq!(report.to_string(), "block at byte 0 (4 bytes): failed: truncated or invalid data block header\n1 block(s), 0 lossless");
+    }
+}
@@ -0,0 +1,384 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+
+use crate::transform::lower_ir::{
+    FieldDescriptor, FieldType, LoweredIR, LoweredItemKind, LoweredPart, LoweredSubItemKind, MAX_BYTE_ARRAY_LEN,
+};
+use super::backend::CodegenBackend;
+use super::utils::feature_cfg;
+
+/// Backend emitting conversions of generated records to Arrow record
+/// batches.
+///
+/// The output is a `<module>_arrow` module with the `schema()` of the
+/// batches and `record_batch(&[Record])`, building one nullable column per
+/// field with the layout of a [`RecordTable`](crate::sink::RecordTable):
+/// columns are named after the field path, e.g. `item020_part1_x`, and
+/// absent items, parts and sub-items are null.
+///
+/// Numbers are unsigned integers of the width of their field, or
+/// 16-byte big-endian fixed size binaries beyond 64 bits, floats are
+/// floats, enums their raw `UInt8` values, strings `Utf8` and byte fields
+/// `Binary`.  Unlike the table sinks, repetitive items are lists of
+/// structs of their element fields.
+///
+/// The module refers to the generated types as `super::<module>` and to
+/// the `arrow-array` and `arrow-schema` crates, so it belongs in the same
+/// file as the generated code, in a crate depending on both.
+#[derive(Debug, Default)]
+pub struct ArrowBackend;
+
+impl CodegenBackend for ArrowBackend {
+    type Output = TokenStream;
+
+    fn finalize(self, lowered: &LoweredIR) -> TokenStream {
+        let module_name = &lowered.module_name;
+        let record_name = &lowered.record.name;
+
+        let mut fields = Vec::new();
+        let mut arrays = Vec::new();
+        for entry in &lowered.record.entries {
+            let item = lowered
+                .items
+                .iter()
+                .find(|item| item.name == entry.type_name)
+                .unwrap_or_else(|| panic!("record refers to undefined item {}", entry.type_name));
+            let field_name = &entry.field_name;
+            let path = Path {
+                name: column_name(field_name),
+                base: quote! { record.#field_name.as_ref() },
+            };
+            let mut columns = Vec::new();
+            item_columns(&path, &item.kind, &mut columns);
+
+            let cfg = feature_cfg(&entry.feature);
+            for column in columns {
+                let (field, array) = (column.field, column.array);
+                fields.push(quote! { #cfg #field });
+                arrays.push(quote! { #cfg #array });
+            }
+        }
+
+        let arrow_module = format_ident!("{}_arrow", module_name);
+        let doc = format!(" Arrow columns of the records of [`{}`](super::{}).", module_name, module_name);
+        quote! {
+            #[doc = #doc]
+            pub mod #arrow_module {
+                use std::sync::Arc;
+                use super::#module_name as generated;
+
+                /// Schema of the batches built by [`record_batch`], with a
+                /// nullable column per field.
+                pub fn schema() -> ::arrow_schema::SchemaRef {
+                    Arc::new(::arrow_schema::Schema::new(vec![#(#fields),*]))
+                }
+
+                /// Converts records to a batch with a row per record and the
+                /// columns of [`schema`].
+                pub fn record_batch(
+                    records: &[generated::#record_name],
+                ) -> Result<::arrow_array::RecordBatch, ::arrow_schema::ArrowError> {
+                    let columns: Vec<::arrow_array::ArrayRef> = vec![#(#arrays),*];
+                    let options = ::arrow_array::RecordBatchOptions::new().with_row_count(Some(records.len()));
+                    ::arrow_array::RecordBatch::try_new_with_options(schema(), columns, &options)
+                }
+            }
+        }
+    }
+}
+
+/// A column: its schema field and the expression building its array from
+/// `records`.
+struct Column {
+    field: TokenStream,
+    array: TokenStream,
+}
+
+/// Where the values of a struct are, within a record.
+struct Path {
+    /// Column name prefix, e.g. `item020_part1`.
+    name: String,
+    /// Expression of type `Option<&T>` reaching the struct from `record`.
+    base: TokenStream,
+}
+
+impl Path {
+    /// The path of the struct in field `field_name` of this one; `required`
+    /// fields aren't optional.
+    fn child(&self, field_name: &Ident, required: bool) -> Path {
+        let base = &self.base;
+        Path {
+            name: format!("{}_{}", self.name, column_name(field_name)),
+            base: if required {
+                quote! { #base.map(|value| &value.#field_name) }
+            } else {
+                quote! { #base.and_then(|value| value.#field_name.as_ref()) }
+            },
+        }
+    }
+}
+
+fn item_columns(path: &Path, kind: &LoweredItemKind, columns: &mut Vec<Column>) {
+    match kind {
+        LoweredItemKind::Simple { fields, .. } => field_columns(path, fields, columns),
+        LoweredItemKind::Extended { parts } => part_columns(path, parts, columns),
+        LoweredItemKind::Repetitive { fields, .. } => columns.push(list_column(path, fields)),
+        LoweredItemKind::Compound { sub_items } => {
+            for sub in sub_items {
+                let path = path.child(&sub.field_name, false);
+                match &sub.kind {
+                    LoweredSubItemKind::Simple { fields, .. } => field_columns(&path, fields, columns),
+                    LoweredSubItemKind::Extended { parts } => part_columns(&path, parts, columns),
+                    LoweredSubItemKind::Repetitive { fields, .. } => columns.push(list_column(&path, fields)),
+                }
+            }
+        }
+    }
+}
+
+fn part_columns(path: &Path, parts: &[LoweredPart], columns: &mut Vec<Column>) {
+    for part in parts {
+        field_columns(&path.child(&part.field_name, part.is_required), &part.fields, columns);
+    }
+}
+
+fn field_columns(path: &Path, fields: &[FieldDescriptor], columns: &mut Vec<Column>) {
+    let base = &path.base;
+    columns.extend(fields.iter().map(|field| {
+        let leaf = Leaf::new(field);
+        let expr = &leaf.expr;
+        let values = if leaf.optional {
+            quote! { records.iter().map(|record| #base.and_then(|value| #expr)) }
+        } else {
+            quote! { records.iter().map(|record| #base.map(|value| #expr)) }
+        };
+        let name = format!("{}_{}", path.name, column_name(&field.name));
+        let data_type = &leaf.data_type;
+        Column {
+            field: quote! { ::arrow_schema::Field::new(#name, #data_type, true) },
+            array: leaf.array(values),
+        }
+    }));
+}
+
+/// The column of a repetitive item: a list of structs of its element
+/// fields, null when the item is absent.
+fn list_column(path: &Path, fields: &[FieldDescriptor]) -> Column {
+    let base = &path.base;
+    let name = &path.name;
+
+    let leaves: Vec<_> = fields.iter().map(Leaf::new).collect();
+    let struct_fields = fields.iter().zip(&leaves).map(|(field, leaf)| {
+        let name = column_name(&field.name);
+        let data_type = &leaf.data_type;
+        quote! { ::arrow_schema::Field::new(#name, #data_type, true) }
+    });
+    let element_fields = quote! { ::arrow_schema::Fields::from(vec![#(#struct_fields),*]) };
+    let data_type = quote! {
+        ::arrow_schema::DataType::List(Arc::new(::arrow_schema::Field::new_list_field(
+            ::arrow_schema::DataType::Struct(#element_fields),
+            true,
+        )))
+    };
+
+    let values = if leaves.is_empty() {
+        quote! { ::arrow_array::StructArray::new_empty_fields(elements.len(), None) }
+    } else {
+        let element_arrays = leaves.iter().map(|leaf| {
+            let expr = &leaf.expr;
+            let values = if leaf.optional {
+                quote! { elements.iter().map(|value| #expr) }
+            } else {
+                quote! { elements.iter().map(|value| Some(#expr)) }
+            };
+            leaf.array(values)
+        });
+        quote! { ::arrow_array::StructArray::try_new(#element_fields, vec![#(#element_arrays),*], None)? }
+    };
+
+    Column {
+        field: quote! { ::arrow_schema::Field::new(#name, #data_type, true) },
+        array: quote! {
+            {
+                let lists: Vec<_> = records.iter().map(|record| #base.map(|value| value.items.as_slice())).collect();
+                let elements: Vec<_> = lists.iter().flatten().flat_map(|items| items.iter()).collect();
+                let mut offsets = ::arrow_array::builder::OffsetBufferBuilder::new(lists.len());
+                let mut nulls = ::arrow_array::builder::NullBufferBuilder::new(lists.len());
+                for items in &lists {
+                    offsets.push_length(items.map_or(0, |items| items.len()));
+                    nulls.append(items.is_some());
+                }
+                let values = #values;
+                let data_type = ::arrow_schema::DataType::Struct(values.fields().clone());
+                let field = ::arrow_schema::Field::new_list_field(data_type, true);
+                let list = ::arrow_array::ListArray::try_new(
+                    Arc::new(field),
+                    offsets.finish(),
+                    Arc::new(values),
+                    nulls.finish(),
+                )?;
+                Arc::new(list)
+            }
+        },
+    }
+}
+
+/// The Arrow representation of a field.
+struct Leaf {
+    data_type: TokenStream,
+    /// Array type built from an iterator of options.
+    array_type: TokenStream,
+    /// Expression of the value of the field of `value`, the struct holding
+    /// it, as an `Option` if `optional`.
+    expr: TokenStream,
+    optional: bool,
+    /// Whether the value is the 16 big-endian bytes of a `u128`.
+    wide: bool,
+}
+
+impl Leaf {
+    fn new(field: &FieldDescriptor) -> Self {
+        let name = &field.name;
+        match &field.type_tokens {
+            FieldType::Primitive(rust_type) | FieldType::OptionalPrimitive(rust_type) => {
+                let optional = matches!(field.type_tokens, FieldType::OptionalPrimitive(_));
+                if rust_type == "u128" {
+                    return Self {
+                        data_type: quote! { ::arrow_schema::DataType::FixedSizeBinary(16) },
+                        array_type: quote! { ::arrow_array::FixedSizeBinaryArray },
+                        expr: if optional {
+                            quote! { value.#name.map(u128::to_be_bytes) }
+                        } else {
+                            quote! { value.#name.to_be_bytes() }
+                        },
+                        optional,
+                        wide: true,
+                    };
+                }
+                let (data_type, array_type) = match rust_type.to_string().as_str() {
+                    "u8" => (quote! { UInt8 }, quote! { UInt8Array }),
+                    "u16" => (quote! { UInt16 }, quote! { UInt16Array }),
+                    "u32" => (quote! { UInt32 }, quote! { UInt32Array }),
+                    "f32" => (quote! { Float32 }, quote! { Float32Array }),
+                    "f64" => (quote! { Float64 }, quote! { Float64Array }),
+                    _ => (quote! { UInt64 }, quote! { UInt64Array }),
+                };
+                Self {
+                    data_type: quote! { ::arrow_schema::DataType::#data_type },
+                    array_type: quote! { ::arrow_array::#array_type },
+                    expr: quote! { value.#name },
+                    optional,
+                    wide: false,
+                }
+            }
+            FieldType::Enum(_) => Self::raw_enum(quote! { u8::from(value.#name) }, false),
+            FieldType::OptionalEnum(_) => Self::raw_enum(quote! { value.#name.map(u8::from) }, true),
+            FieldType::FixedString(_) => Self::string(quote! { value.#name.as_str() }, false),
+            FieldType::OptionalFixedString(_) => Self::string(quote! { value.#name.as_deref() }, true),
+            FieldType::Bytes(_) => Self::bytes(quote! { value.#name.as_slice() }, false),
+            FieldType::OptionalBytes(byte_len) if *byte_len > MAX_BYTE_ARRAY_LEN => {
+                Self::bytes(quote! { value.#name.as_deref() }, true)
+            }
+            FieldType::OptionalBytes(_) => Self::bytes(quote! { value.#name.as_ref().map(|bytes| bytes.as_slice()) }, true),
+        }
+    }
+
+    fn raw_enum(expr: TokenStream, optional: bool) -> Self {
+        Self {
+            data_type: quote! { ::arrow_schema::DataType::UInt8 },
+            array_type: quote! { ::arrow_array::UInt8Array },
+            expr,
+            optional,
+            wide: false,
+        }
+    }
+
+    fn string(expr: TokenStream, optional: bool) -> Self {
+        Self {
+            data_type: quote! { ::arrow_schema::DataType::Utf8 },
+            array_type: quote! { ::arrow_array::StringArray },
+            expr,
+            optional,
+            wide: false,
+        }
+    }
+
+    fn bytes(expr: TokenStream, optional: bool) -> Self {
+        Self {
+            data_type: quote! { ::arrow_schema::DataType::Binary },
+            array_type: quote! { ::arrow_array::BinaryArray },
+            expr,
+            optional,
+            wide: false,
+        }
+    }
+
+    /// The expression building the array of `values`, an iterator of
+    /// options.
+    fn array(&self, values: TokenStream) -> TokenStream {
+        let array_type = &self.array_type;
+        if self.wide {
+            quote! { Arc::new(#array_type::try_from_sparse_iter_with_size(#values, 16)?) }
+        } else {
+            quote! { Arc::new(#array_type::from_iter(#values)) }
+        }
+    }
+}
+
+/// Name of a field in a column name, without the `r#` of a raw identifier.
+fn column_name(name: &Ident) -> String {
+    name.to_string().trim_start_matches("r#").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::backend::run_backend;
+    use crate::parse::parser::parse_category;
+    use crate::transform::{lower, transformer::to_ir};
+
+    fn arrow(xml: &str) -> String {
+        run_backend(ArrowBackend, &lower(&to_ir(parse_category(xml).unwrap()).unwrap()).unwrap()).to_string()
+    }
+
+    #[test]
+    fn test_arrow_columns_follow_the_field_paths() {
+        let code = arrow(r#"<category id="1">
+            <item id="10" frn="1">
+                <fixed bytes="2">
+                    <field name="a" bits="8"/>
+                    <epb><field name="b" bits="7"/></epb>
+                </fixed>
+            </item>
+            <item id="20" frn="2">
+                <extended bytes="2">
+                    <part index="0"><field name="c" bits="7"/></part>
+                    <part index="1"><field name="d" bits="7"/></part>
+                </extended>
+            </item>
+        </category>"#);
+
+        assert!(code.contains("pub mod cat001_arrow"));
+        assert!(code.contains(":: arrow_schema :: Field :: new (\"item010_a\" , :: arrow_schema :: DataType :: UInt8 , true)"));
+        assert!(code.contains(
+            "records . iter () . map (| record | record . item010 . as_ref () . and_then (| value | value . b))"
+        ));
+        assert!(code.contains(
+            "record . item020 . as_ref () . and_then (| value | value . part1 . as_ref ()) . map (| value | value . d)"
+        ));
+        assert!(code.contains("\"item020_part0_c\""));
+    }
+
+    #[test]
+    fn test_arrow_repetitive_items_are_lists_of_structs() {
+        let code = arrow(r#"<category id="1">
+            <item id="30" frn="1">
+                <repetitive bytes="1" counter="2"><field name="e" bits="8"/></repetitive>
+            </item>
+        </category>"#);
+
+        assert!(code.contains(":: arrow_schema :: DataType :: List"));
+        assert!(code.contains("elements . iter () . map (| value | Some (value . e))"));
+        assert!(code.contains(":: arrow_array :: ListArray :: try_new"));
+    }
+}
@@ -0,0 +1,184 @@
+use crate::transform::lower_ir::{LoweredIR, LoweredItem, LoweredRecord};
+
+/// A code generation backend over the lowered IR.
+///
+/// [`run_backend`] drives a backend through a category in a fixed order:
+///
+/// 1. [`begin_module`](Self::begin_module) once, with the whole category
+/// 2. [`emit_record`](Self::emit_record) once
+/// 3. for each item, in definition order: [`emit_struct`](Self::emit_struct),
+///    [`emit_decode`](Self::emit_decode), [`emit_encode`](Self::emit_encode)
+/// 4. [`finalize`](Self::finalize), which produces the output
+///
+/// Backends only implement the steps they need; the default methods do
+/// nothing.  [`RustBackend`](super::generator::RustBackend) is the emitter
+/// behind [`generate`](super::generate).
+///
+/// # Example
+///
+/// A backend listing item names:
+///
+/// ```
+/// use rasterix_codegen::generate::backend::{run_backend, CodegenBackend};
+/// use rasterix_codegen::parse::parser::parse_category;
+/// use rasterix_codegen::transform::lower_ir::{LoweredIR, LoweredItem};
+/// use rasterix_codegen::transform::{lower, transformer::to_ir};
+///
+/// #[derive(Default)]
+/// struct ItemNames(Vec<String>);
+///
+/// impl CodegenBackend for ItemNames {
+///     type Output = Vec<String>;
+///
+///     fn emit_struct(&mut self, item: &LoweredItem) {
+///         self.0.push(item.name.to_string());
+///     }
+///
+///     fn finalize(self, _lowered: &LoweredIR) -> Vec<String> {
+///         self.0
+///     }
+/// }
+///
+/// let xml = r#"<category id="1">
+///     <item id="10" frn="1"><fixed bytes="1"><field name="a" bits="8"/></fixed></item>
+///     <item id="20" frn="2"><fixed bytes="1"><field name="b" bits="8"/></fixed></item>
+/// </category>"#;
+///
+/// let lowered = lower(&to_ir(parse_category(xml).unwrap()).unwrap()).unwrap();
+/// assert_eq!(run_backend(ItemNames::default(), &lowered), ["Item010", "Item020"]);
+/// ```
+pub trait CodegenBackend {
+    /// What the backend produces for a category.
+    type Output;
+
+    /// Called once before anything else is emitted.
+    fn begin_module(&mut self, lowered: &LoweredIR) {
+        let _ = lowered;
+    }
+
+    /// Emits the category record.
+    fn emit_record(&mut self, record: &LoweredRecord) {
+        let _ = record;
+    }
+
+    /// Emits the type definitions of an item, including its enums.
+    fn emit_struct(&mut self, item: &LoweredItem) {
+        let _ = item;
+    }
+
+    /// Emits the decoding side of an item.
+    fn emit_decode(&mut self, item: &LoweredItem) {
+        let _ = item;
+    }
+
+    /// Emits the encoding side of an item.
+    fn emit_encode(&mut self, item: &LoweredItem) {
+        let _ = item;
+    }
+
+    /// Assembles everything emitted into the final output.
+    fn finalize(self, lowered: &LoweredIR) -> Self::Output;
+}
+
+/// Runs `backend` over a lowered category and returns its output.
+pub fn run_backend<B: CodegenBackend>(mut backend: B, lowered: &LoweredIR) -> B::Output {
+    backend.begin_module(lowered);
+    backend.emit_record(&lowered.record);
+    for item in &lowered.items {
+        backend.emit_struct(item);
+        backend.emit_decode(item);
+        backend.emit_encode(item);
+    }
+    backend.finalize(lowered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::format_ident;
+
+    use crate::transform::lower_ir::{LoweredItemKind, RecordEntry};
+
+    /// Records the order of calls.
+    #[derive(Default)]
+    struct Trace(Vec<String>);
+
+    impl CodegenBackend for Trace {
+        type Output = Vec<String>;
+
+        fn begin_module(&mut self, lowered: &LoweredIR) {
+            self.0.push(format!("begin {}", lowered.module_name));
+        }
+        fn emit_record(&mut self, record: &LoweredRecord) {
+            self.0.push(format!("record {}", record.entries.len()));
+        }
+        fn emit_struct(&mut self, item: &LoweredItem) {
+            self.0.push(format!("struct {}", item.name));
+        }
+        fn emit_decode(&mut self, item: &LoweredItem) {
+            self.0.push(format!("decode {}", item.name));
+        }
+        fn emit_encode(&mut self, item: &LoweredItem) {
+            self.0.push(format!("encode {}", item.name));
+        }
+        fn finalize(mut self, _lowered: &LoweredIR) -> Vec<String> {
+            self.0.push("finalize".to_string());
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_run_backend_call_order() {
+        let item = |name: &str| LoweredItem {
+            id: 0,
+            name: format_ident!("{}", name),
+            title: None,
+            short_name: None,
+            is_reserved: false,
+            alias: None,
+            enums: vec![],
+            feature: None,
+            is_hashable: true,
+            derived: vec![],
+            examples: vec![],
+            kind: LoweredItemKind::Simple {
+                is_explicit: false,
+                byte_size: 0,
+                fields: vec![],
+                decode_ops: vec![],
+                encode_ops: vec![],
+            },
+        };
+        let lowered = LoweredIR {
+            category_id: 1,
+            module_name: format_ident!("cat001"),
+            record: LoweredRecord {
+                name: format_ident!("Record"),
+                entries: vec![RecordEntry {
+                    field_name: format_ident!("item010"),
+                    type_name: format_ident!("Item010"),
+                    frn: 1,
+                    feature: None,
+                    is_mandatory: false,
+                }],
+                is_hashable: true,
+            },
+            items: vec![item("Item010"), item("Item020")],
+        };
+
+        assert_eq!(
+            run_backend(Trace::default(), &lowered),
+            [
+                "begin cat001",
+                "record 1",
+                "struct Item010",
+                "decode Item010",
+                "encode Item010",
+                "struct Item020",
+                "decode Item020",
+                "encode Item020",
+                "finalize",
+            ]
+        );
+    }
+}
@@ -0,0 +1,293 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+
+use crate::transform::lower_ir::{DecodeOp, LoweredIR, LoweredItem, LoweredItemKind, LoweredRecord};
+use super::backend::CodegenBackend;
+use super::decode_gen::emit_decode_op;
+use super::struct_gen::generate_field;
+use super::utils::{compiled_out_check, feature_cfg};
+
+/// Backend emitting borrowed variants of the generated types.
+///
+/// The output is a `<module>_ref` module with `RecordRef<'a>` and
+/// `DataBlockRef<'a>`, decoded from a `BitReader<&'a [u8]>`.  String
+/// fields starting on a byte boundary are decoded as `&'a str` pointing
+/// into the input instead of a `String`, so decoding them allocates
+/// nothing.  Items with such fields get an `Item{N}Ref<'a>` type; all
+/// other items use their owned type.
+///
+/// Like the wasm wrappers, the module refers to the generated types as
+/// `super::<module>` and belongs in the same file as the generated code.
+///
+/// Only fixed and explicit items are borrowed: strings inside extended,
+/// repetitive and compound items, and EPB strings, which follow their
+/// valid bit, stay owned.
+#[derive(Debug, Default)]
+pub struct BorrowedBackend {
+    /// Borrowed type of each item that has one.
+    borrowed: Vec<(Ident, Ident)>,
+    items: Vec<TokenStream>,
+}
+
+impl CodegenBackend for BorrowedBackend {
+    type Output = TokenStream;
+
+    fn emit_struct(&mut self, item: &LoweredItem) {
+        let LoweredItemKind::Simple { fields, decode_ops, .. } = &item.kind else {
+            return;
+        };
+        let borrowed = borrowed_strings(decode_ops);
+        if borrowed.is_empty() {
+            return;
+        }
+
+        let name = &item.name;
+        let ref_name = format_ident!("{}Ref", name);
+        let field_decls: Vec<_> = fields.iter().map(|field| {
+            if borrowed.contains(&&field.name) {
+                let field_name = &field.name;
+                quote! { pub #field_name: &'a str }
+            } else {
+                generate_field(field)
+            }
+        }).collect();
+        let op_tokens: Vec<_> = decode_ops.iter().map(|op| match op {
+            DecodeOp::ReadString { name, byte_len } if borrowed.contains(&name) => quote! {
+                let #name = reader.read_str(#byte_len)?;
+            },
+            _ => emit_decode_op(op),
+        }).collect();
+        let field_names: Vec<_> = fields.iter().map(|f| &f.name).collect();
+        let owned_fields: Vec<_> = fields.iter().map(|field| {
+            let field_name = &field.name;
+            if borrowed.contains(&field_name) {
+                quote! { #field_name: self.#field_name.to_string() }
+            } else {
+                quote! { #field_name: self.#field_name }
+            }
+        }).collect();
+
+        let doc = format!(" Borrowed variant of [`{}`].", name);
+        let cfg = feature_cfg(&item.feature);
+        self.items.push(quote! {
+            #[doc = #doc]
+            #cfg
+            #[derive(Debug, Clone, PartialEq)]
+            pub struct #ref_name<'a> {
+                #(#field_decls),*
+            }
+
+            #cfg
+            impl<'a> #ref_name<'a> {
+                pub fn decode(reader: &mut BitReader<&'a [u8]>) -> Result<Self, DecodeError> {
+                    #(#op_tokens)*
+
+                    Ok(Self {
+                        #(#field_names),*
+                    })
+                }
+
+                /// Converts to the owned item, allocating its strings.
+                pub fn into_owned(self) -> #name {
+                    #name {
+                        #(#owned_fields),*
+                    }
+                }
+            }
+        });
+        self.borrowed.push((name.clone(), ref_name));
+    }
+
+    fn finalize(self, lowered: &LoweredIR) -> TokenStream {
+        let module_name = &lowered.module_name;
+        let ref_module = format_ident!("{}_ref", module_name);
+        let category_id = lowered.category_id;
+        let record = generate_record_ref(&lowered.record, &self.borrowed);
+        let record_name = &lowered.record.name;
+        let record_ref = format_ident!("{}Ref", record_name);
+        let items = self.items;
+
+        quote! {
+            /// Borrowed variants of the generated types, decoded without
+            /// allocating strings.
+            pub mod #ref_module {
+                use super::#module_name::*;
+                use rasterix::rcore::{BitReader, Decode, DecodeError, Fspec};
+
+                #record
+
+                /// Borrowed variant of [`DataBlock`].
+                #[derive(Debug, Clone, PartialEq)]
+                pub struct DataBlockRef<'a> {
+                    pub records: Vec<#record_ref<'a>>,
+                }
+
+                impl<'a> DataBlockRef<'a> {
+                    pub fn decode(reader: &mut BitReader<&'a [u8]>) -> Result<Self, DecodeError> {
+                        let cat = reader.read_bits(8)? as u8;
+                        if cat != #category_id {
+                            return Err(DecodeError::InvalidData("category mismatch"));
+                        }
+
+                        let len = reader.read_bits(16)? as u16;
+                        if len < 3 {
+                            return Err(DecodeError::InvalidData("data block length too small"));
+                        }
+
+                        // Every record starts on a byte boundary, as in `DataBlock::decode`.
+                        let mut payload = reader.read_slice((len - 3) as usize)?;
+                        let mut records = Vec::new();
+                        while !payload.is_empty() {
+                            let mut record_reader = BitReader::new(payload);
+                            records.push(#record_ref::decode(&mut record_reader)?);
+                            payload = record_reader.into_inner();
+                        }
+
+                        Ok(Self { records })
+                    }
+
+                    /// Converts to the owned data block, allocating its strings.
+                    pub fn into_owned(self) -> DataBlock {
+                        DataBlock::with_records(
+                            self.records.into_iter().map(#record_ref::into_owned).collect(),
+                        )
+                    }
+                }
+
+                #(#items)*
+            }
+        }
+    }
+}
+
+/// Returns the plain string fields of a unit that start on a byte boundary.
+fn borrowed_strings(decode_ops: &[DecodeOp]) -> Vec<&Ident> {
+    let mut offset = 0;
+    let mut names = Vec::new();
+    for op in decode_ops {
+        if let DecodeOp::ReadString { name, .. } = op
+            && offset % 8 == 0
+        {
+            names.push(name);
+        }
+        offset += op.bits();
+    }
+    names
+}
+
+fn generate_record_ref(record: &LoweredRecord, borrowed: &[(Ident, Ident)]) -> TokenStream {
+    let record_name = &record.name;
+    let record_ref = format_ident!("{}Ref", record_name);
+    let ref_type = |type_name: &Ident| {
+        borrowed.iter().find(|(owned, _)| owned == type_name).map(|(_, ref_name)| ref_name)
+    };
+
+    let fields: Vec<_> = record.entries.iter().map(|entry| {
+        let field_name = &entry.field_name;
+        let cfg = feature_cfg(&entry.feature);
+        match ref_type(&entry.type_name) {
+            Some(ref_name) => quote! { #cfg pub #field_name: Option<#ref_name<'a>> },
+            None => {
+                let item_type = &entry.type_name;
+                quote! { #cfg pub #field_name: Option<#item_type> }
+            }
+        }
+    }).collect();
+
+    let decode_fields: Vec<_> = record.entries.iter().map(|entry| {
+        let field_name = &entry.field_name;
+        let decode_type = ref_type(&entry.type_name).unwrap_or(&entry.type_name);
+        let frn = entry.frn;
+        let cfg = feature_cfg(&entry.feature);
+        quote! {
+            #cfg
+            #field_name: if fspec.is_frn_set(#frn) {
+                Some(#decode_type::decode(reader)?)
+            } else {
+                None
+            }
+        }
+    }).collect();
+
+    let owned_fields: Vec<_> = record.entries.iter().map(|entry| {
+        let field_name = &entry.field_name;
+        let cfg = feature_cfg(&entry.feature);
+        match ref_type(&entry.type_name) {
+            Some(ref_name) => quote! { #cfg #field_name: self.#field_name.map(#ref_name::into_owned) },
+            None => quote! { #cfg #field_name: self.#field_name },
+        }
+    }).collect();
+    let compiled_out: Vec<_> = record.entries.iter().map(|entry| {
+        compiled_out_check(&entry.feature, entry.frn)
+    }).collect();
+
+    let doc = format!(" Borrowed variant of [`{}`].", record_name);
+    quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct #record_ref<'a> {
+            #(#fields,)*
+            /// Ties the record to the input when no item borrows from it.
+            input: std::marker::PhantomData<&'a [u8]>,
+        }
+
+        impl<'a> #record_ref<'a> {
+            pub fn decode(reader: &mut BitReader<&'a [u8]>) -> Result<Self, DecodeError> {
+                let fspec = Fspec::read(reader)?;
+                #(#compiled_out)*
+
+                Ok(Self {
+                    #(#decode_fields,)*
+                    input: std::marker::PhantomData,
+                })
+            }
+
+            /// Converts to the owned record, allocating its strings.
+            pub fn into_owned(self) -> #record_name {
+                #record_name {
+                    #(#owned_fields),*
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::backend::run_backend;
+    use crate::parse::parser::parse_category;
+    use crate::transform::{lower, transformer::to_ir};
+
+    #[test]
+    fn test_only_aligned_strings_are_borrowed() {
+        let lowered = lower(&to_ir(parse_category(r#"<category id="48">
+            <item id="10" frn="1">
+                <fixed bytes="2"><field name="sac" bits="8"/><field name="sic" bits="8"/></fixed>
+            </item>
+            <item id="240" frn="2">
+                <fixed bytes="7">
+                    <field name="callsign" bits="48" type="string"/>
+                    <field name="flag" bits="4"/>
+                    <spare bits="4"/>
+                </fixed>
+            </item>
+            <item id="250" frn="3">
+                <fixed bytes="3">
+                    <field name="kind" bits="4"/>
+                    <field name="code" bits="16" type="string"/>
+                    <spare bits="4"/>
+                </fixed>
+            </item>
+        </category>"#).unwrap()).unwrap()).unwrap();
+
+        let code = run_backend(BorrowedBackend::default(), &lowered).to_string();
+
+        assert!(code.contains("pub mod cat048_ref"));
+        assert!(code.contains("pub struct Item240Ref < 'a > { pub callsign : & 'a str , pub flag : u8 }"));
+        assert!(!code.contains("Item010Ref"));
+        assert!(!code.contains("Item250Ref"));
+        assert!(code.contains("pub item010 : Option < Item010 >"));
+        assert!(code.contains("pub item240 : Option < Item240Ref < 'a >>"));
+    }
+}
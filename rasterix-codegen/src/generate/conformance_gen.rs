@@ -0,0 +1,244 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+
+use crate::transform::lower_ir::{
+    EncodeOp, FieldDescriptor, FieldType, LoweredEnum, LoweredItem, LoweredItemKind, LoweredPart, LoweredSubItemKind,
+    UnknownValues, MAX_BYTE_ARRAY_LEN,
+};
+
+/// Generates the [`Conformance`](rasterix_core::Conformance) impls of an
+/// item and of the structs it is made of.
+///
+/// Each struct checks its own values: numbers wider than their field,
+/// enum values without a variant, strings longer than their field and
+/// byte vectors of another length.  Extended items check that no part
+/// follows an absent one, repetitive items their number of repetitions,
+/// and the values of parts, elements and sub-items are checked under
+/// their paths, e.g. `part1.` or `items.0.`.
+pub fn generate_item_conformance(item: &LoweredItem) -> TokenStream {
+    let item_name = &item.name;
+    let enums = &item.enums;
+
+    match &item.kind {
+        LoweredItemKind::Simple { .. } if item.is_reserved => quote! { impl Conformance for #item_name {} },
+        LoweredItemKind::Simple { fields, encode_ops, .. } => struct_conformance(item_name, fields, encode_ops, enums),
+        LoweredItemKind::Extended { parts } => extended_conformance(item_name, parts, enums),
+        LoweredItemKind::Repetitive { element_type_name, count, fields, encode_ops, .. } => {
+            repetitive_conformance(item_name, element_type_name, *count, fields, encode_ops, enums)
+        }
+        LoweredItemKind::Compound { sub_items } => {
+            let sub_impls = sub_items.iter().map(|sub| {
+                let name = &sub.struct_name;
+                match &sub.kind {
+                    LoweredSubItemKind::Simple { fields, encode_ops, .. } => {
+                        struct_conformance(name, fields, encode_ops, &sub.enums)
+                    }
+                    LoweredSubItemKind::Extended { parts } => extended_conformance(name, parts, &sub.enums),
+                    LoweredSubItemKind::Repetitive { element_type_name, count, fields, encode_ops, .. } => {
+                        repetitive_conformance(name, element_type_name, *count, fields, encode_ops, &sub.enums)
+                    }
+                }
+            });
+            let audits = sub_items.iter().map(|sub| {
+                let field_name = &sub.field_name;
+                let prefix = format!("{{}}{}.", field_name);
+                quote! {
+                    if let Some(sub) = &self.#field_name {
+                        sub.audit(&format!(#prefix, path), issues);
+                    };
+                }
+            });
+            quote! {
+                #(#sub_impls)*
+
+                impl Conformance for #item_name {
+                    fn audit(&self, path: &str, issues: &mut Vec<ConformanceIssue>) {
+                        #(#audits)*
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The `Conformance` impl of a struct of `fields`, written by
+/// `encode_ops`.  Structs without checked values get the default, empty
+/// audit.
+fn struct_conformance(
+    name: &Ident,
+    fields: &[FieldDescriptor],
+    encode_ops: &[EncodeOp],
+    enums: &[LoweredEnum],
+) -> TokenStream {
+    let audits: Vec<_> = encode_ops.iter().filter_map(|op| op_audit(op, fields, enums)).collect();
+    if audits.is_empty() {
+        return quote! { impl Conformance for #name {} };
+    }
+    quote! {
+        impl Conformance for #name {
+            fn audit(&self, path: &str, issues: &mut Vec<ConformanceIssue>) {
+                #(#audits)*
+            }
+        }
+    }
+}
+
+/// The check of the value an encode op writes, or `None` if every value of
+/// its type can be written.
+fn op_audit(op: &EncodeOp, fields: &[FieldDescriptor], enums: &[LoweredEnum]) -> Option<TokenStream> {
+    let field_of = |name: &Ident| fields.iter().find(|field| field.name == *name).expect("ops write fields");
+    let (name, check) = match op {
+        EncodeOp::WriteField { name, bits, float: false, .. }
+        | EncodeOp::WriteEpbField { name, bits, float: false, .. } => {
+            let (FieldType::Primitive(rust_type) | FieldType::OptionalPrimitive(rust_type)) =
+                &field_of(name).type_tokens
+            else {
+                return None;
+            };
+            let width = match rust_type.to_string().as_str() {
+                "u8" => 8,
+                "u16" => 16,
+                "u32" => 32,
+                "u64" => 64,
+                _ => return None,
+            };
+            if *bits >= width {
+                return None;
+            }
+            let field_name = name.to_string();
+            (name, quote! { conformance::check_bits(issues, path, #field_name, field as u64, #bits); })
+        }
+        EncodeOp::WriteEnum { name, .. } | EncodeOp::WriteEpbEnum { name, .. } => {
+            let (FieldType::Enum(enum_name) | FieldType::OptionalEnum(enum_name)) = &field_of(name).type_tokens
+            else {
+                return None;
+            };
+            let lowered = enums.iter().find(|lowered| lowered.name == *enum_name)?;
+            if lowered.unknown != UnknownValues::Preserve {
+                return None;
+            }
+            let field_name = name.to_string();
+            (name, quote! {
+                if let #enum_name::Unknown(value) = field {
+                    conformance::report(issues, path, #field_name, conformance::Rule::UnknownEnumValue { value });
+                }
+            })
+        }
+        EncodeOp::WriteString { name, byte_len } | EncodeOp::WriteEpbString { name, byte_len } => {
+            let field_name = name.to_string();
+            (name, quote! {
+                if field.len() > #byte_len {
+                    let rule = conformance::Rule::FieldLength { len: field.len(), byte_len: #byte_len };
+                    conformance::report(issues, path, #field_name, rule);
+                }
+            })
+        }
+        EncodeOp::WriteBytes { name, byte_len } | EncodeOp::WriteEpbBytes { name, byte_len }
+            if *byte_len > MAX_BYTE_ARRAY_LEN =>
+        {
+            let field_name = name.to_string();
+            (name, quote! {
+                if field.len() != #byte_len {
+                    let rule = conformance::Rule::FieldLength { len: field.len(), byte_len: #byte_len };
+                    conformance::report(issues, path, #field_name, rule);
+                }
+            })
+        }
+        _ => return None,
+    };
+
+    Some(match field_of(name).type_tokens {
+        FieldType::OptionalPrimitive(_) | FieldType::OptionalEnum(_) => quote! {
+            if let Some(field) = self.#name { #check };
+        },
+        FieldType::OptionalFixedString(_) | FieldType::OptionalBytes(_) => quote! {
+            if let Some(field) = &self.#name { #check };
+        },
+        FieldType::Primitive(_) | FieldType::Enum(_) => quote! {
+            { let field = self.#name; #check };
+        },
+        FieldType::FixedString(_) | FieldType::Bytes(_) => quote! {
+            { let field = &self.#name; #check }
+        },
+    })
+}
+
+/// The `Conformance` impls of an extended item and its parts.
+///
+/// A part present after an absent one, or extensions after an absent last
+/// part, have no FX bit announcing them.
+fn extended_conformance(name: &Ident, parts: &[LoweredPart], enums: &[LoweredEnum]) -> TokenStream {
+    let part_impls = parts
+        .iter()
+        .map(|part| struct_conformance(&part.struct_name, &part.fields, &part.encode_ops, enums));
+
+    let audits = parts.iter().map(|part| {
+        let field_name = &part.field_name;
+        let prefix = format!("{{}}{}.", field_name);
+        if part.is_required {
+            quote! { self.#field_name.audit(&format!(#prefix, path), issues); }
+        } else {
+            quote! {
+                if let Some(part) = &self.#field_name {
+                    part.audit(&format!(#prefix, path), issues);
+                };
+            }
+        }
+    });
+
+    let optional: Vec<_> = parts.iter().filter(|part| !part.is_required).map(|part| &part.field_name).collect();
+    let gaps = optional.windows(2).map(|pair| {
+        let (previous, next) = (pair[0], pair[1]);
+        let next_name = next.to_string();
+        quote! {
+            if self.#next.is_some() && self.#previous.is_none() {
+                conformance::report(issues, path, #next_name, conformance::Rule::FxGap);
+            };
+        }
+    });
+    let extensions_gap = optional.last().map(|last| quote! {
+        if !self.extensions.is_empty() && self.#last.is_none() {
+            conformance::report(issues, path, "extensions", conformance::Rule::FxGap);
+        }
+    });
+
+    quote! {
+        #(#part_impls)*
+
+        impl Conformance for #name {
+            fn audit(&self, path: &str, issues: &mut Vec<ConformanceIssue>) {
+                #(#gaps)*
+                #extensions_gap
+                #(#audits)*
+            }
+        }
+    }
+}
+
+/// The `Conformance` impls of a repetitive item and its elements.
+fn repetitive_conformance(
+    name: &Ident,
+    element_type_name: &Ident,
+    count: usize,
+    fields: &[FieldDescriptor],
+    encode_ops: &[EncodeOp],
+    enums: &[LoweredEnum],
+) -> TokenStream {
+    let element_impl = struct_conformance(element_type_name, fields, encode_ops, enums);
+
+    quote! {
+        #element_impl
+
+        impl Conformance for #name {
+            fn audit(&self, path: &str, issues: &mut Vec<ConformanceIssue>) {
+                if self.items.len() != #count {
+                    let rule = conformance::Rule::RepetitionCount { count: self.items.len(), expected: #count };
+                    conformance::report(issues, path, "items", rule);
+                }
+                for (i, element) in self.items.iter().enumerate() {
+                    element.audit(&format!("{}items.{}.", path, i), issues);
+                }
+            }
+        }
+    }
+}
@@ -2,8 +2,9 @@ use proc_macro2::TokenStream;
 use quote::quote;
 
 use crate::transform::lower_ir::LoweredIR;
+use super::struct_gen::struct_derives;
 
-/// Generates the DataBlock struct and its Encode/Decode implementations.
+/// Generates the DataBlock struct and its Encode/Decode/DecodeInto implementations.
 ///
 /// The DataBlock is a container of records for a single ASTERIX category.
 /// Wire format: `[CAT: 1 byte][LEN: 2 bytes big-endian][records...]`
@@ -11,6 +12,7 @@ use crate::transform::lower_ir::LoweredIR;
 pub fn generate_datablock(lowered: &LoweredIR) -> TokenStream {
     let record_name = &lowered.record.name;
     let category_id = lowered.category_id;
+    let derives = struct_derives(lowered.record.is_hashable);
 
     quote! {
         /// ASTERIX Data Block — a container of records for this category.
@@ -22,7 +24,7 @@ pub fn generate_datablock(lowered: &LoweredIR) -> TokenStream {
         ///
         /// `LEN` is the total byte length of the entire data block, including
         /// the CAT and LEN fields themselves (minimum value is 3).
-        #[derive(Debug, Clone, PartialEq)]
+        #derives
         pub struct DataBlock {
             pub records: Vec<#record_name>,
         }
@@ -42,6 +44,10 @@ pub fn generate_datablock(lowered: &LoweredIR) -> TokenStream {
             }
         }
 
+        impl CategoryRecord for #record_name {
+            const CATEGORY: u8 = #category_id;
+        }
+
         impl Default for DataBlock {
             fn default() -> Self {
                 Self::new()
@@ -49,9 +55,9 @@ pub fn generate_datablock(lowered: &LoweredIR) -> TokenStream {
         }
 
         impl Encode for DataBlock {
-            fn encode<W: std::io::Write>(
+            fn encode<W: BitWrite>(
                 &self,
-                writer: &mut BitWriter<W>,
+                writer: &mut W,
             ) -> Result<(), DecodeError> {
                 // Encode all records into a temporary buffer to compute total length.
                 let mut record_buf = Vec::new();
@@ -64,7 +70,8 @@ pub fn generate_datablock(lowered: &LoweredIR) -> TokenStream {
                 }
 
                 // LEN = 1 (CAT) + 2 (LEN) + record bytes
-                let total_len: u16 = 3 + record_buf.len() as u16;
+                let total_len = u16::try_from(3 + record_buf.len())
+                    .map_err(|_| DecodeError::LimitExceeded { limit: "data block length", max: u16::MAX as usize })?;
 
                 // Write CAT (1 byte)
                 writer.write_bits(#category_id as u64, 8)?;
@@ -96,6 +103,8 @@ pub fn generate_datablock(lowered: &LoweredIR) -> TokenStream {
                 if len < 3 {
                     return Err(DecodeError::InvalidData("data block length too small"));
                 }
+                policy::block_len(len as usize)?;
+                let _span = trace::block_span(#category_id, len);
 
                 // Read remaining bytes into a buffer, then decode records from it.
                 let payload_len = (len - 3) as usize;
@@ -108,17 +117,92 @@ pub fn generate_datablock(lowered: &LoweredIR) -> TokenStream {
                 let mut cursor = std::io::Cursor::new(payload);
                 let total = payload_len as u64;
 
+                // Each record must start on a byte the previous one did not
+                // consume and end within LEN, or the records are misaligned.
                 while cursor.position() < total {
-                    let record = {
-                        let mut record_reader = BitReader::new(&mut cursor);
-                        #record_name::decode(&mut record_reader)?
-                    };
+                    let position = cursor.position() as usize;
+                    let offset = 3 + position;
+                    let record = policy::block_rest(&cursor.get_ref()[position..], offset, len as usize)
+                        .and_then(|record_follows| {
+                            let mut record_reader = BitReader::new(&mut cursor);
+                            record_follows
+                                .then(|| #record_name::decode(&mut record_reader))
+                                .transpose()
+                                .map_err(|e| e.in_record(offset, len as usize))
+                        })
+                        .inspect_err(|e| trace::record_error(#category_id, offset as u64, e))?;
+                    // Zero padding accepted by the policy ends the block
+                    let Some(record) = record else { break };
                     records.push(record);
                 }
 
                 Ok(Self { records })
             }
         }
+
+        impl DecodeInto for DataBlock {
+            fn decode_into<R: std::io::Read>(
+                &mut self,
+                reader: &mut BitReader<R>,
+                ctx: &mut DecodeContext,
+            ) -> Result<(), DecodeError> {
+                // Read CAT (1 byte)
+                let cat = reader.read_bits(8)? as u8;
+                if cat != #category_id {
+                    return Err(DecodeError::InvalidData("category mismatch"));
+                }
+
+                // Read LEN (2 bytes, big-endian)
+                let len = reader.read_bits(16)? as u16;
+                if len < 3 {
+                    return Err(DecodeError::InvalidData("data block length too small"));
+                }
+                policy::block_len(len as usize)?;
+                let _span = trace::block_span(#category_id, len);
+
+                // Borrow the context's block buffer for the payload so it is
+                // not reallocated for every data block.
+                let payload_len = (len - 3) as usize;
+                ctx.with_buffer(|ctx, payload| {
+                    for _ in 0..payload_len {
+                        payload.push(reader.read_bits(8)? as u8);
+                    }
+
+                    // Overwrite existing records in place, appending new ones
+                    // and dropping any left over from the previous block.
+                    let mut cursor = std::io::Cursor::new(payload.as_slice());
+                    let total = payload_len as u64;
+                    let mut count = 0;
+
+                    while cursor.position() < total {
+                        let position = cursor.position() as usize;
+                        let offset = 3 + position;
+                        let result = policy::block_rest(&cursor.get_ref()[position..], offset, len as usize)
+                            .and_then(|record_follows| {
+                                if !record_follows {
+                                    return Ok(false);
+                                }
+                                let mut record_reader = BitReader::new(&mut cursor);
+                                match self.records.get_mut(count) {
+                                    Some(record) => record.decode_into(&mut record_reader, ctx),
+                                    None => #record_name::decode(&mut record_reader)
+                                        .map(|record| self.records.push(record)),
+                                }
+                                .map(|()| true)
+                                .map_err(|e| e.in_record(offset, len as usize))
+                            });
+                        // Zero padding accepted by the policy ends the block
+                        if !result.inspect_err(|e| trace::record_error(#category_id, offset as u64, e))? {
+                            break;
+                        }
+                        count += 1;
+                    }
+                    self.records.truncate(count);
+
+                    Ok(())
+                })
+            }
+        }
     }
 }
 
@@ -139,10 +223,12 @@ mod tests {
                     RecordEntry {
                         field_name: format_ident!("item010"),
                         type_name: format_ident!("Item010"),
-                        fspec_byte: 0,
-                        fspec_bit: 0,
+                        frn: 1,
+                        feature: None,
+                        is_mandatory: false,
                     },
                 ],
+                is_hashable: true,
             },
             items: vec![],
         };
@@ -155,6 +241,10 @@ mod tests {
         assert!(code.contains("pub const CATEGORY : u8 = 48u8"));
         assert!(code.contains("impl Encode for DataBlock"));
         assert!(code.contains("impl Decode for DataBlock"));
+        assert!(code.contains("impl DecodeInto for DataBlock"));
         assert!(code.contains("impl Default for DataBlock"));
+        assert!(code.contains("impl CategoryRecord for Record"));
+        assert!(code.contains("policy :: block_rest"));
+        assert!(code.contains("in_record (offset , len as usize)"));
     }
 }
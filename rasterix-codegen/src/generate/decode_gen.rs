@@ -1,59 +1,90 @@
 use proc_macro2::{Ident, TokenStream};
-use quote::{quote};
+use quote::{format_ident, quote};
 
-use crate::transform::lower_ir::{DecodeOp, FieldDescriptor, LoweredPart, LoweredSubItem, LoweredSubItemKind};
+use crate::transform::lower_ir::{
+    DecodeOp, FieldDescriptor, LoweredPart, LoweredSubItem, LoweredSubItemKind, RepetitiveStorage, MAX_BYTE_ARRAY_LEN,
+};
 
-/// Emits a single decode operation as a TokenStream.
-fn emit_decode_op(op: &DecodeOp) -> TokenStream {
+/// Returns the reader method reading a number in its byte order.
+fn read_bits(little_endian: bool) -> Ident {
+    if little_endian { format_ident!("read_bits_le") } else { format_ident!("read_bits") }
+}
+
+/// Emits the expression reading a number of type `rust_type`, converting
+/// the bits of a float.
+fn read_number(bits: usize, rust_type: &Ident, little_endian: bool) -> TokenStream {
+    let read = read_bits(little_endian);
+    if rust_type == "f32" {
+        quote! { f32::from_bits(reader.#read(#bits)? as u32) }
+    } else if rust_type == "f64" {
+        quote! { f64::from_bits(reader.#read(#bits)?) }
+    } else {
+        quote! { reader.#read(#bits)? as #rust_type }
+    }
+}
+
+/// Emits the expression reading a byte array field of `byte_len` bytes into
+/// its array or vector.
+fn read_bytes(byte_len: usize) -> TokenStream {
+    let buf = if byte_len <= MAX_BYTE_ARRAY_LEN {
+        quote! { [0u8; #byte_len] }
+    } else {
+        quote! { vec![0u8; #byte_len] }
+    };
+    quote! {
+        {
+            let mut bytes = #buf;
+            reader.read_bytes(&mut bytes)?;
+            bytes
+        }
+    }
+}
+
+/// Emits the expression producing the value of a field-bearing decode op.
+///
+/// Returns `None` for ops that don't produce a struct field (spare bits,
+/// length byte), which are emitted as plain statements instead.
+fn decode_op_value(op: &DecodeOp) -> Option<TokenStream> {
     match op {
-        DecodeOp::ReadField { name, bits, rust_type } => {
-            quote! {
-                let #name = reader.read_bits(#bits)? as #rust_type;
-            }
+        DecodeOp::ReadField { bits, rust_type, little_endian, .. } => {
+            Some(read_number(*bits, rust_type, *little_endian))
         }
-        DecodeOp::ReadEnum { name, bits, enum_type } => {
-            quote! {
-                let #name = {
-                    let value = reader.read_bits(#bits)? as u8;
-                    #enum_type::try_from(value).unwrap()
-                };
-            }
+        DecodeOp::ReadEnum { bits, enum_type, .. } => {
+            Some(quote! { #enum_type::from_decoded(reader.read_bits(#bits)? as u8)? })
         }
-        DecodeOp::ReadEpbField { name, bits, rust_type } => {
-            quote! {
-                let #name = {
+        DecodeOp::ReadEpbField { bits, rust_type, little_endian, .. } => {
+            let read = read_number(*bits, rust_type, *little_endian);
+            Some(quote! {
+                {
                     let valid = reader.read_bits(1)? != 0;
                     if valid {
-                        Some(reader.read_bits(#bits)? as #rust_type)
+                        Some(#read)
                     } else {
                         reader.read_bits(#bits)?; // Skip the value
                         None
                     }
-                };
-            }
+                }
+            })
         }
-        DecodeOp::ReadEpbEnum { name, bits, enum_type } => {
-            quote! {
-                let #name = {
+        DecodeOp::ReadEpbEnum { bits, enum_type, .. } => {
+            Some(quote! {
+                {
                     let valid = reader.read_bits(1)? != 0;
                     if valid {
-                        let value = reader.read_bits(#bits)? as u8;
-                        Some(#enum_type::try_from(value).unwrap())
+                        Some(#enum_type::from_decoded(reader.read_bits(#bits)? as u8)?)
                     } else {
                         reader.read_bits(#bits)?; // Skip the value
                         None
                     }
-                };
-            }
+                }
+            })
         }
-        DecodeOp::ReadString { name, byte_len } => {
-            quote! {
-                let #name = reader.read_string(#byte_len)?;
-            }
+        DecodeOp::ReadString { byte_len, .. } => {
+            Some(quote! { reader.read_string(#byte_len)? })
         }
-        DecodeOp::ReadEpbString { name, byte_len } => {
-            quote! {
-                let #name = {
+        DecodeOp::ReadEpbString { byte_len, .. } => {
+            Some(quote! {
+                {
                     let valid = reader.read_bits(1)? != 0;
                     if valid {
                         Some(reader.read_string(#byte_len)?)
@@ -61,20 +92,135 @@ fn emit_decode_op(op: &DecodeOp) -> TokenStream {
                         reader.read_string(#byte_len)?; // Skip the value
                         None
                     }
-                };
-            }
+                }
+            })
+        }
+        DecodeOp::ReadBytes { byte_len, .. } => Some(read_bytes(*byte_len)),
+        DecodeOp::ReadEpbBytes { byte_len, .. } => {
+            let read = read_bytes(*byte_len);
+            let bits = byte_len * 8;
+            Some(quote! {
+                {
+                    let valid = reader.read_bits(1)? != 0;
+                    if valid {
+                        Some(#read)
+                    } else {
+                        reader.skip_bits(#bits)?; // Skip the value
+                        None
+                    }
+                }
+            })
         }
+        DecodeOp::SkipSpare { .. } | DecodeOp::ReadLengthByte { .. } | DecodeOp::Custom { .. } => None,
+    }
+}
+
+/// Emits the statement for a decode op that doesn't produce a field.
+fn emit_decode_statement(op: &DecodeOp) -> TokenStream {
+    match op {
         DecodeOp::SkipSpare { bits } => {
             quote! {
-                reader.read_bits(#bits)?; // Skip spare bits
+                policy::reserved_bits(#bits, reader.read_bits(#bits)?)?;
+            }
+        }
+        DecodeOp::ReadLengthByte { total_bytes } => {
+            // The length includes the length byte itself.
+            quote! {
+                policy::length(reader.read_bits(8)? as usize, #total_bytes)?;
+            }
+        }
+        DecodeOp::Custom { code, .. } => code.clone(),
+        _ => unreachable!("field-bearing decode op has no statement form"),
+    }
+}
+
+/// Returns the struct field name written by a decode op, if any.
+fn decode_op_name(op: &DecodeOp) -> Option<&Ident> {
+    match op {
+        DecodeOp::ReadField { name, .. }
+        | DecodeOp::ReadEnum { name, .. }
+        | DecodeOp::ReadEpbField { name, .. }
+        | DecodeOp::ReadEpbEnum { name, .. }
+        | DecodeOp::ReadString { name, .. }
+        | DecodeOp::ReadEpbString { name, .. }
+        | DecodeOp::ReadBytes { name, .. }
+        | DecodeOp::ReadEpbBytes { name, .. } => Some(name),
+        DecodeOp::SkipSpare { .. } | DecodeOp::ReadLengthByte { .. } | DecodeOp::Custom { .. } => None,
+    }
+}
+
+/// Emits a single decode operation as a TokenStream.
+pub(crate) fn emit_decode_op(op: &DecodeOp) -> TokenStream {
+    match (decode_op_name(op), decode_op_value(op)) {
+        (Some(name), Some(value)) => quote! {
+            let #name = #value;
+        },
+        _ => emit_decode_statement(op),
+    }
+}
+
+/// Emits a single decode operation that overwrites `self` in place.
+///
+/// String fields are read through the [`DecodeContext`] so that the existing
+/// `String` allocation is reused.
+fn emit_decode_into_op(op: &DecodeOp) -> TokenStream {
+    match op {
+        DecodeOp::ReadString { name, byte_len } => {
+            quote! {
+                ctx.read_string_into(reader, #byte_len, &mut self.#name)?;
             }
         }
-        DecodeOp::ReadLengthByte => {
+        DecodeOp::ReadEpbString { name, byte_len } => {
             quote! {
-                let _len = reader.read_bits(8)? as usize;
-                // Length includes itself, so actual data is len - 1 bytes
+                if reader.read_bits(1)? != 0 {
+                    ctx.read_string_into(reader, #byte_len, self.#name.get_or_insert_with(String::new))?;
+                } else {
+                    ctx.read_bytes(reader, #byte_len)?; // Skip the value
+                    self.#name = None;
+                }
             }
         }
+        _ => match (decode_op_name(op), decode_op_value(op)) {
+            (Some(name), Some(value)) => quote! {
+                self.#name = #value;
+            },
+            _ => emit_decode_statement(op),
+        },
+    }
+}
+
+/// Emits code that decodes into an `Option` field in place.
+///
+/// An existing value is overwritten with `decode_into`; otherwise a new one
+/// is decoded.  `reader` is the expression passed to the decode call.
+pub(crate) fn emit_option_decode_into(
+    field: &TokenStream,
+    type_name: &Ident,
+    reader: &TokenStream,
+) -> TokenStream {
+    quote! {
+        match #field.as_mut() {
+            Some(value) => value.decode_into(#reader, ctx)?,
+            None => #field = Some(#type_name::decode(#reader)?),
+        }
+    }
+}
+
+/// Returns the name of the `DecodeContext` parameter of an in-place decode
+/// of `decode_ops`, underscored if only string fields would use it.
+fn context_param(decode_ops: &[DecodeOp]) -> Ident {
+    let uses_context = decode_ops
+        .iter()
+        .any(|op| matches!(op, DecodeOp::ReadString { .. } | DecodeOp::ReadEpbString { .. }));
+    if uses_context { format_ident!("ctx") } else { format_ident!("_ctx") }
+}
+
+/// Generates the body of an in-place decode for flat decode ops.
+fn generate_decode_into_body(decode_ops: &[DecodeOp]) -> TokenStream {
+    let op_tokens: Vec<_> = decode_ops.iter().map(emit_decode_into_op).collect();
+    quote! {
+        #(#op_tokens)*
+        Ok(())
     }
 }
 
@@ -86,6 +232,8 @@ pub fn generate_simple_decode(
 ) -> TokenStream {
     let op_tokens: Vec<_> = decode_ops.iter().map(emit_decode_op).collect();
     let field_names: Vec<_> = fields.iter().map(|f| &f.name).collect();
+    let decode_into_body = generate_decode_into_body(decode_ops);
+    let ctx = context_param(decode_ops);
 
     quote! {
         impl Decode for #name {
@@ -99,6 +247,16 @@ pub fn generate_simple_decode(
                 })
             }
         }
+
+        impl DecodeInto for #name {
+            fn decode_into<R: std::io::Read>(
+                &mut self,
+                reader: &mut BitReader<R>,
+                #ctx: &mut DecodeContext,
+            ) -> Result<(), DecodeError> {
+                #decode_into_body
+            }
+        }
     }
 }
 
@@ -109,9 +267,9 @@ pub fn generate_extended_decode(
 ) -> TokenStream {
     let mut part_impl_tokens = Vec::new();
     let mut main_decode_body = Vec::new();
+    let mut main_decode_into_body = Vec::new();
     let mut field_names = Vec::new();
 
-    let number_of_parts = parts.len();
     for (i, part) in parts.iter().enumerate() {
         let part_name = &part.struct_name;
         let field_name = &part.field_name;
@@ -119,6 +277,8 @@ pub fn generate_extended_decode(
 
         let element_decodes: Vec<_> = part.decode_ops.iter().map(emit_decode_op).collect();
         let element_names: Vec<_> = part.fields.iter().map(|f| &f.name).collect();
+        let element_decode_into_body = generate_decode_into_body(&part.decode_ops);
+        let ctx = context_param(&part.decode_ops);
 
         part_impl_tokens.push(quote! {
             impl #part_name {
@@ -128,51 +288,73 @@ pub fn generate_extended_decode(
                     #(#element_decodes)*
                     Ok(Self { #(#element_names),* })
                 }
+
+                pub fn decode_into<R: std::io::Read>(
+                    &mut self,
+                    reader: &mut BitReader<R>,
+                    #ctx: &mut DecodeContext,
+                ) -> Result<(), DecodeError> {
+                    #element_decode_into_body
+                }
             }
         });
 
         if i == 0 {
             main_decode_body.push(quote! {
                 let #field_name = #part_name::decode(reader)?;
+                let mut fx = reader.read_bits(1)? != 0;
+            });
+            main_decode_into_body.push(quote! {
+                self.#field_name.decode_into(reader, ctx)?;
+                let mut fx = reader.read_bits(1)? != 0;
             });
+        } else {
+            let in_place = emit_option_decode_into(
+                &quote! { self.#field_name },
+                part_name,
+                &quote! { reader },
+            );
 
-            if i != number_of_parts - 1 {
-                if number_of_parts <= 2 {
-                    // no need for mutable fx if there are only 2 parts
-                    main_decode_body.push(quote! {
-                        let fx = reader.read_bits(1)? != 0;
-                    });
+            main_decode_body.push(quote! {
+                let #field_name = if fx {
+                    let part = #part_name::decode(reader)?;
+                    fx = reader.read_bits(1)? != 0;
+                    Some(part)
                 } else {
-                    // For more than 2 parts, we need to keep track of fx for 
-                    // subsequent parts
-                    main_decode_body.push(quote! {
-                        let mut fx = reader.read_bits(1)? != 0;
-                    });
-                }
-            }
-        } else {
-            if i != number_of_parts - 1 {
-                main_decode_body.push(quote! {
-                    let #field_name = if fx {
-                        let part = #part_name::decode(reader)?;
-                        fx = reader.read_bits(1)? != 0;
-                        Some(part)
-                    } else {
-                        None
-                    };
-                });
-            } else {
-                main_decode_body.push(quote! {
-                    let #field_name = if fx {
-                        Some(#part_name::decode(reader)?)
-                    } else {
-                        None
-                    };
-                });
-            }
+                    None
+                };
+            });
+            main_decode_into_body.push(quote! {
+                if fx {
+                    #in_place
+                    fx = reader.read_bits(1)? != 0;
+                } else {
+                    self.#field_name = None;
+                };
+            });
         }
     }
 
+    // Octets the definition doesn't know are kept while FX stays set, so
+    // the stream stays in sync and the item encodes back unchanged.
+    let read_extensions = |extensions: TokenStream| quote! {
+        while fx {
+            let octet = reader.read_bits(8)? as u8;
+            #extensions.push(octet);
+            fx = octet & 1 != 0;
+        }
+    };
+    let decode_extensions = read_extensions(quote! { extensions });
+    let decode_into_extensions = read_extensions(quote! { self.extensions });
+    main_decode_body.push(quote! {
+        let mut extensions = Vec::new();
+        #decode_extensions
+    });
+    main_decode_into_body.push(quote! {
+        self.extensions.clear();
+        #decode_into_extensions
+    });
+
     quote! {
         #(#part_impl_tokens)*
 
@@ -183,10 +365,23 @@ pub fn generate_extended_decode(
                 #(#main_decode_body)*
 
                 Ok(Self {
-                    #(#field_names),*
+                    #(#field_names,)*
+                    extensions,
                 })
             }
         }
+
+        impl DecodeInto for #name {
+            fn decode_into<R: std::io::Read>(
+                &mut self,
+                reader: &mut BitReader<R>,
+                ctx: &mut DecodeContext,
+            ) -> Result<(), DecodeError> {
+                #(#main_decode_into_body)*
+
+                Ok(())
+            }
+        }
     }
 }
 
@@ -194,12 +389,30 @@ pub fn generate_extended_decode(
 pub fn generate_repetitive_decode(
     name: &Ident,
     count: usize,
+    storage: RepetitiveStorage,
     element_type_name: &Ident,
     decode_ops: &[DecodeOp],
     fields: &[FieldDescriptor],
 ) -> TokenStream {
     let element_decodes: Vec<_> = decode_ops.iter().map(emit_decode_op).collect();
     let field_names: Vec<_> = fields.iter().map(|f| &f.name).collect();
+    let element_decode_into_body = generate_decode_into_body(decode_ops);
+    let ctx = context_param(decode_ops);
+
+    let element = quote! { #element_type_name::decode(reader)? };
+    let (new_items, push, push_into) = match storage {
+        RepetitiveStorage::Vec => (
+            quote! { Vec::with_capacity(#count) },
+            quote! { items.push(#element); },
+            quote! { self.items.push(#element); },
+        ),
+        // The capacity is the repetition count, so pushing never fails
+        RepetitiveStorage::Heapless => (
+            quote! { heapless::Vec::new() },
+            quote! { let _ = items.push(#element); },
+            quote! { let _ = self.items.push(#element); },
+        ),
+    };
 
     quote! {
         impl #element_type_name {
@@ -212,20 +425,48 @@ pub fn generate_repetitive_decode(
                     #(#field_names),*
                 })
             }
+
+            fn decode_into<R: std::io::Read>(
+                &mut self,
+                reader: &mut BitReader<R>,
+                #ctx: &mut DecodeContext,
+            ) -> Result<(), DecodeError> {
+                #element_decode_into_body
+            }
         }
 
         impl Decode for #name {
             fn decode<R: std::io::Read>(
                 reader: &mut BitReader<R>,
             ) -> Result<Self, DecodeError> {
-                let mut items = Vec::with_capacity(#count);
+                let mut items = #new_items;
                 for _ in 0..#count {
-                    items.push(#element_type_name::decode(reader)?);
+                    #push
                 }
 
                 Ok(Self { items })
             }
         }
+
+        impl DecodeInto for #name {
+            fn decode_into<R: std::io::Read>(
+                &mut self,
+                reader: &mut BitReader<R>,
+                ctx: &mut DecodeContext,
+            ) -> Result<(), DecodeError> {
+                // Overwrite the elements we already have, then grow if needed
+                let reused = self.items.len().min(#count);
+                self.items.truncate(reused);
+                for item in self.items.iter_mut() {
+                    item.decode_into(reader, ctx)?;
+                }
+                for _ in reused..#count {
+                    #push_into
+                }
+
+                Ok(())
+            }
+        }
     }
 }
 
@@ -235,6 +476,7 @@ pub fn generate_compound_decode(
     sub_items: &[LoweredSubItem],
 ) -> TokenStream {
     let mut sub_decodes = Vec::new();
+    let mut sub_decode_intos = Vec::new();
     let mut field_names = Vec::new();
 
     for sub in sub_items {
@@ -242,15 +484,27 @@ pub fn generate_compound_decode(
         let field_name = &sub.field_name;
         field_names.push(field_name);
 
-        let byte = sub.fspec_byte;
-        let bit = sub.fspec_bit;
+        let frn = sub.frn;
         sub_decodes.push(quote! {
-            let #field_name = if fspec.is_set(#byte, #bit) {
+            let #field_name = if fspec.is_frn_set(#frn) {
                 Some(#sub_name::decode(&mut reader)?)
             } else {
                 None
             };
         });
+
+        let in_place = emit_option_decode_into(
+            &quote! { self.#field_name },
+            sub_name,
+            &quote! { &mut reader },
+        );
+        sub_decode_intos.push(quote! {
+            if fspec.is_frn_set(#frn) {
+                #in_place
+            } else {
+                self.#field_name = None;
+            };
+        });
     }
 
     quote! {
@@ -267,6 +521,28 @@ pub fn generate_compound_decode(
                     #(#field_names),*
                 })
             }
+
+            pub fn decode_into<R: std::io::Read>(
+                &mut self,
+                reader: &mut R,
+                ctx: &mut DecodeContext,
+            ) -> Result<(), DecodeError> {
+                ctx.with_fspec(reader, |ctx, reader, fspec| {
+                    let mut reader = BitReader::new(reader);
+
+                    #(#sub_decode_intos)*
+
+                    Ok(())
+                })
+            }
+        }
+
+        impl DecodeFromRead for #name {
+            fn decode_from<R: std::io::Read>(
+                reader: &mut R,
+            ) -> Result<Self, DecodeError> {
+                Self::decode(reader)
+            }
         }
     }
 }
@@ -283,8 +559,8 @@ pub fn generate_compound_sub_decodes(
             LoweredSubItemKind::Extended { parts } => {
                 generate_extended_decode(&sub.struct_name, parts)
             }
-            LoweredSubItemKind::Repetitive { element_type_name, count, decode_ops, fields, .. } => {
-                generate_repetitive_decode(&sub.struct_name, *count, element_type_name, decode_ops, fields)
+            LoweredSubItemKind::Repetitive { element_type_name, count, storage, decode_ops, fields, .. } => {
+                generate_repetitive_decode(&sub.struct_name, *count, *storage, element_type_name, decode_ops, fields)
             }
         }
     }).collect();
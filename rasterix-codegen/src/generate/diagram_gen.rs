@@ -0,0 +1,195 @@
+use std::fmt::Write;
+
+use crate::transform::lower_ir::{
+    DecodeOp, LoweredIR, LoweredItem, LoweredItemKind, LoweredPart, LoweredSubItem,
+    LoweredSubItemKind,
+};
+use super::backend::CodegenBackend;
+use super::docs_gen::{item_label, op_rows};
+
+/// Bit layout diagram of a single item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemDiagram {
+    /// ASTERIX label of the item, e.g. `I048/010`.
+    pub item: String,
+    /// Mermaid `packet-beta` diagram source.
+    pub mermaid: String,
+}
+
+/// Backend emitting a Mermaid bit layout diagram for each item.
+///
+/// Bits are numbered from 0 at the most significant bit of the item.
+/// Extended items show every part with the FX bits between them, compound
+/// items show their FSPEC followed by every sub-item, and repetitive items
+/// show a single element.
+///
+/// Diagrams can be embedded in Markdown as ` ```mermaid ` blocks;
+/// [`to_markdown`] does this for a whole category.
+#[derive(Debug, Default)]
+pub struct DiagramBackend {
+    category_id: u8,
+    diagrams: Vec<ItemDiagram>,
+}
+
+impl CodegenBackend for DiagramBackend {
+    type Output = Vec<ItemDiagram>;
+
+    fn begin_module(&mut self, lowered: &LoweredIR) {
+        self.category_id = lowered.category_id;
+    }
+
+    fn emit_struct(&mut self, item: &LoweredItem) {
+        let label = item_label(self.category_id, &item.name);
+        let mut packet = Packet::default();
+        let title = match &item.kind {
+            LoweredItemKind::Simple { decode_ops, .. } => {
+                packet.ops("", decode_ops);
+                label.clone()
+            }
+            LoweredItemKind::Extended { parts } => {
+                packet.parts("", parts);
+                label.clone()
+            }
+            LoweredItemKind::Repetitive { count, decode_ops, .. } => {
+                packet.ops("", decode_ops);
+                format!("{} (element, repeated {} times)", label, count)
+            }
+            LoweredItemKind::Compound { sub_items } => {
+                packet.compound(sub_items);
+                label.clone()
+            }
+        };
+
+        let mut mermaid = String::new();
+        writeln!(mermaid, "packet-beta").unwrap();
+        writeln!(mermaid, "title {}", title).unwrap();
+        mermaid.push_str(&packet.rows);
+        self.diagrams.push(ItemDiagram { item: label, mermaid });
+    }
+
+    fn finalize(self, _lowered: &LoweredIR) -> Vec<ItemDiagram> {
+        self.diagrams
+    }
+}
+
+/// Renders diagrams as a Markdown document with one section per item.
+pub fn to_markdown(category_id: u8, diagrams: &[ItemDiagram]) -> String {
+    let mut out = String::new();
+    writeln!(out, "<!-- AUTO-GENERATED - DO NOT EDIT -->\n").unwrap();
+    writeln!(out, "# ASTERIX Category {:03} Layouts", category_id).unwrap();
+    for diagram in diagrams {
+        writeln!(out, "\n## {}\n\n```mermaid\n{}```", diagram.item, diagram.mermaid).unwrap();
+    }
+    out
+}
+
+/// Accumulates packet rows at increasing bit offsets.
+#[derive(Default)]
+struct Packet {
+    offset: usize,
+    rows: String,
+}
+
+impl Packet {
+    fn row(&mut self, bits: usize, name: &str) {
+        let start = self.offset;
+        self.offset += bits;
+        if bits == 1 {
+            writeln!(self.rows, "{}: \"{}\"", start, name).unwrap();
+        } else {
+            writeln!(self.rows, "{}-{}: \"{}\"", start, self.offset - 1, name).unwrap();
+        }
+    }
+
+    fn ops(&mut self, prefix: &str, decode_ops: &[DecodeOp]) {
+        for row in decode_ops.iter().flat_map(op_rows) {
+            self.row(row.bits, &format!("{}{}", prefix, row.name));
+        }
+    }
+
+    /// Every part but the last is followed by an FX bit.
+    fn parts(&mut self, prefix: &str, parts: &[LoweredPart]) {
+        for (i, part) in parts.iter().enumerate() {
+            self.ops(prefix, &part.decode_ops);
+            if i != parts.len() - 1 {
+                self.row(1, &format!("{}FX", prefix));
+            }
+        }
+    }
+
+    fn compound(&mut self, sub_items: &[LoweredSubItem]) {
+        let fspec_bytes = sub_items.iter().map(|s| s.frn.div_ceil(7)).max().unwrap_or(1);
+        for byte in 0..fspec_bytes {
+            for bit in 0..7 {
+                match sub_items.iter().find(|s| s.frn == byte * 7 + bit + 1) {
+                    Some(sub) => self.row(1, &sub.field_name.to_string()),
+                    None => self.row(1, "-"),
+                }
+            }
+            self.row(1, "FX");
+        }
+
+        for sub in sub_items {
+            let prefix = format!("{}.", sub.field_name);
+            match &sub.kind {
+                LoweredSubItemKind::Simple { decode_ops, .. } => self.ops(&prefix, decode_ops),
+                LoweredSubItemKind::Extended { parts } => self.parts(&prefix, parts),
+                LoweredSubItemKind::Repetitive { decode_ops, .. } => self.ops(&prefix, decode_ops),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::backend::run_backend;
+    use crate::parse::parser::parse_category;
+    use crate::transform::{lower, transformer::to_ir};
+
+    fn diagrams(xml: &str) -> Vec<ItemDiagram> {
+        run_backend(DiagramBackend::default(), &lower(&to_ir(parse_category(xml).unwrap()).unwrap()).unwrap())
+    }
+
+    #[test]
+    fn test_fixed_item_diagram() {
+        let diagrams = diagrams(r#"<category id="48">
+            <item id="10" frn="1">
+                <fixed bytes="2">
+                    <field name="sac" bits="8"/>
+                    <field name="sic" bits="8"/>
+                </fixed>
+            </item>
+        </category>"#);
+
+        assert_eq!(diagrams[0].item, "I048/010");
+        assert_eq!(
+            diagrams[0].mermaid,
+            "packet-beta\ntitle I048/010\n0-7: \"sac\"\n8-15: \"sic\"\n"
+        );
+    }
+
+    #[test]
+    fn test_extended_and_compound_diagrams() {
+        let diagrams = diagrams(r#"<category id="1">
+            <item id="20" frn="1">
+                <extended bytes="2">
+                    <part index="0"><field name="a" bits="7"/></part>
+                    <part index="1"><field name="b" bits="7"/></part>
+                </extended>
+            </item>
+            <item id="100" frn="2">
+                <compound>
+                    <fixed bytes="1"><field name="flags" bits="8"/></fixed>
+                </compound>
+            </item>
+        </category>"#);
+
+        assert!(diagrams[0].mermaid.ends_with("0-6: \"a\"\n7: \"FX\"\n8-14: \"b\"\n"));
+        assert!(diagrams[1].mermaid.contains("0: \"sub0\"\n1: \"-\""));
+        assert!(diagrams[1].mermaid.ends_with("7: \"FX\"\n8-15: \"sub0.flags\"\n"));
+
+        let markdown = to_markdown(1, &diagrams);
+        assert!(markdown.contains("## I001/020\n\n```mermaid\npacket-beta\n"));
+    }
+}
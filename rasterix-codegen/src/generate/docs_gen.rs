@@ -0,0 +1,317 @@
+use std::fmt::Write;
+
+use proc_macro2::Ident;
+
+use crate::transform::lower_ir::{
+    DecodeOp, LoweredEnum, LoweredIR, LoweredItem, LoweredItemKind, LoweredPart, LoweredSubItem,
+    LoweredSubItemKind, UnknownValues,
+};
+use super::backend::CodegenBackend;
+
+/// Backend emitting a Markdown reference for a category.
+///
+/// The reference has an item table (FRN, item, structure, length) followed
+/// by a section per item with a bit map of each fixed-size unit and a value
+/// table for each enum.  Bits are numbered ASTERIX-style, from the most
+/// significant bit of the unit down to 1.
+///
+/// Since it is generated from the same definition as the code, it can be
+/// regenerated in CI to keep the documentation in sync.
+#[derive(Debug, Default)]
+pub struct DocsBackend {
+    category_id: u8,
+    /// FRN for each item type, from the record entries.
+    frns: Vec<(Ident, usize)>,
+    summary: String,
+    sections: String,
+}
+
+impl CodegenBackend for DocsBackend {
+    type Output = String;
+
+    fn begin_module(&mut self, lowered: &LoweredIR) {
+        self.category_id = lowered.category_id;
+        self.frns = lowered
+            .record
+            .entries
+            .iter()
+            .map(|e| (e.type_name.clone(), e.frn - 1))
+            .collect();
+    }
+
+    fn emit_struct(&mut self, item: &LoweredItem) {
+        let label = item_label(self.category_id, &item.name);
+        let frn = self
+            .frns
+            .iter()
+            .find(|(type_name, _)| *type_name == item.name)
+            .map_or_else(|| "-".to_string(), |(_, frn)| frn.to_string());
+        let (structure, length) = describe_kind(&item.kind);
+        writeln!(self.summary, "| {} | {} | {} | {} |", frn, label, structure, length).unwrap();
+
+        let out = &mut self.sections;
+        writeln!(out, "\n## {}\n", label).unwrap();
+        writeln!(out, "{}, {}. Generated type: `{}`.", structure, length, item.name).unwrap();
+        match &item.kind {
+            LoweredItemKind::Simple { decode_ops, .. } => write_bit_map(out, None, decode_ops, false),
+            LoweredItemKind::Extended { parts } => write_parts(out, parts),
+            LoweredItemKind::Repetitive { count, decode_ops, .. } => {
+                writeln!(out, "\nThe element below is repeated {} times.", count).unwrap();
+                write_bit_map(out, None, decode_ops, false);
+            }
+            LoweredItemKind::Compound { sub_items } => {
+                for sub in sub_items {
+                    write_sub_item(out, sub);
+                }
+            }
+        }
+        write_enums(out, &item.enums);
+    }
+
+    fn finalize(self, lowered: &LoweredIR) -> String {
+        let mut out = String::new();
+        writeln!(out, "<!-- AUTO-GENERATED - DO NOT EDIT -->\n").unwrap();
+        writeln!(out, "# ASTERIX Category {:03}\n", lowered.category_id).unwrap();
+        writeln!(out, "| FRN | Item | Structure | Length |").unwrap();
+        writeln!(out, "|-----|------|-----------|--------|").unwrap();
+        out.push_str(&self.summary);
+        out.push_str(&self.sections);
+        out
+    }
+}
+
+/// Returns the ASTERIX label of an item, e.g. `I048/010`.
+pub(crate) fn item_label(category_id: u8, item_name: &Ident) -> String {
+    format!("I{:03}/{}", category_id, item_name.to_string().trim_start_matches("Item"))
+}
+
+/// One row of a bit map.
+pub(crate) struct BitRow {
+    pub(crate) name: String,
+    pub(crate) bits: usize,
+    pub(crate) notes: String,
+}
+
+/// Notes a number sent least significant byte first.
+fn byte_order_note(little_endian: bool) -> &'static str {
+    if little_endian { ", little-endian" } else { "" }
+}
+
+/// Returns the bit map rows a decode op reads, in wire order.
+pub(crate) fn op_rows(op: &DecodeOp) -> Vec<BitRow> {
+    let row = |name: &Ident, bits: usize, notes: String| BitRow { name: name.to_string(), bits, notes };
+    let valid = |name: &Ident| BitRow {
+        name: format!("{} valid", name),
+        bits: 1,
+        notes: format!("`{}` is present when set", name),
+    };
+
+    match op {
+        DecodeOp::ReadField { name, bits, rust_type, little_endian } => {
+            vec![row(name, *bits, format!("`{}`{}", rust_type, byte_order_note(*little_endian)))]
+        }
+        DecodeOp::ReadEnum { name, bits, enum_type } => vec![row(name, *bits, format!("`{}`", enum_type))],
+        DecodeOp::ReadEpbField { name, bits, rust_type, little_endian } => {
+            let notes = format!("`Option<{}>`{}", rust_type, byte_order_note(*little_endian));
+            vec![valid(name), row(name, *bits, notes)]
+        }
+        DecodeOp::ReadEpbEnum { name, bits, enum_type } => {
+            vec![valid(name), row(name, *bits, format!("`Option<{}>`", enum_type))]
+        }
+        DecodeOp::ReadString { name, byte_len } => {
+            vec![row(name, byte_len * 8, format!("{}-character string", byte_len))]
+        }
+        DecodeOp::ReadEpbString { name, byte_len } => vec![
+            valid(name),
+            row(name, byte_len * 8, format!("optional {}-character string", byte_len)),
+        ],
+        DecodeOp::ReadBytes { name, byte_len } => vec![row(name, byte_len * 8, format!("{}-byte array", byte_len))],
+        DecodeOp::ReadEpbBytes { name, byte_len } => {
+            vec![valid(name), row(name, byte_len * 8, format!("optional {}-byte array", byte_len))]
+        }
+        DecodeOp::SkipSpare { bits } => vec![BitRow {
+            name: "spare".to_string(),
+            bits: *bits,
+            notes: "ignored".to_string(),
+        }],
+        DecodeOp::Custom { tag, bits, .. } => vec![BitRow {
+            name: tag.clone(),
+            bits: *bits,
+            notes: format!("custom `<{}>` element", tag),
+        }],
+        DecodeOp::ReadLengthByte { .. } => vec![BitRow {
+            name: "LEN".to_string(),
+            bits: 8,
+            notes: "length of the item in bytes, including LEN".to_string(),
+        }],
+    }
+}
+
+/// Writes the bit map of a unit, optionally headed by a title and followed
+/// by an FX bit.
+fn write_bit_map(out: &mut String, title: Option<&str>, decode_ops: &[DecodeOp], fx: bool) {
+    let mut rows: Vec<BitRow> = decode_ops.iter().flat_map(op_rows).collect();
+    if fx {
+        rows.push(BitRow {
+            name: "FX".to_string(),
+            bits: 1,
+            notes: "next part follows when set".to_string(),
+        });
+    }
+
+    if let Some(title) = title {
+        writeln!(out, "\n{}", title).unwrap();
+    }
+    writeln!(out, "\n| Bits | Field | Notes |").unwrap();
+    writeln!(out, "|------|-------|-------|").unwrap();
+    let mut high: usize = rows.iter().map(|r| r.bits).sum();
+    for row in rows {
+        let low = high + 1 - row.bits;
+        let bits = if row.bits == 1 { high.to_string() } else { format!("{}-{}", high, low) };
+        writeln!(out, "| {} | {} | {} |", bits, row.name, row.notes).unwrap();
+        high = low - 1;
+    }
+}
+
+fn write_parts(out: &mut String, parts: &[LoweredPart]) {
+    for (i, part) in parts.iter().enumerate() {
+        let presence = if part.is_required { "required" } else { "optional" };
+        let title = format!("Part {} (`{}`, {}):", part.index, part.struct_name, presence);
+        write_bit_map(out, Some(&title), &part.decode_ops, i != parts.len() - 1);
+    }
+}
+
+fn write_sub_item(out: &mut String, sub: &LoweredSubItem) {
+    let title = format!("### Subfield {} (`{}`)", sub.index, sub.struct_name);
+    match &sub.kind {
+        LoweredSubItemKind::Simple { decode_ops, .. } => {
+            write_bit_map(out, Some(&title), decode_ops, false);
+        }
+        LoweredSubItemKind::Extended { parts } => {
+            writeln!(out, "\n{}", title).unwrap();
+            write_parts(out, parts);
+        }
+        LoweredSubItemKind::Repetitive { count, decode_ops, .. } => {
+            writeln!(out, "\n{}\n\nThe element below is repeated {} times.", title, count).unwrap();
+            write_bit_map(out, None, decode_ops, false);
+        }
+    }
+}
+
+fn write_enums(out: &mut String, enums: &[LoweredEnum]) {
+    for lowered in enums {
+        writeln!(out, "\n`{}`:\n", lowered.name).unwrap();
+        writeln!(out, "| Value | Name |").unwrap();
+        writeln!(out, "|-------|------|").unwrap();
+        for variant in &lowered.variants {
+            match &variant.alias {
+                Some(alias) => writeln!(out, "| {} | {} (alias `{}`) |", variant.value, variant.name, alias),
+                None => writeln!(out, "| {} | {} |", variant.value, variant.name),
+            }
+            .unwrap();
+        }
+        match &lowered.unknown {
+            UnknownValues::Preserve => {}
+            UnknownValues::Error => writeln!(out, "\nOther values are rejected.").unwrap(),
+            UnknownValues::MapTo(target) => writeln!(out, "\nOther values decode as {}.", target).unwrap(),
+        }
+    }
+}
+
+fn unit_bytes(decode_ops: &[DecodeOp], fx: bool) -> usize {
+    let bits: usize = decode_ops.iter().flat_map(op_rows).map(|r| r.bits).sum();
+    (bits + fx as usize).div_ceil(8)
+}
+
+fn parts_length(parts: &[LoweredPart]) -> String {
+    let sizes: Vec<usize> = parts
+        .iter()
+        .enumerate()
+        .map(|(i, p)| unit_bytes(&p.decode_ops, i != parts.len() - 1))
+        .collect();
+    let min: usize = sizes.iter().zip(parts).filter(|(_, p)| p.is_required).map(|(s, _)| s).sum();
+    let max: usize = sizes.iter().sum();
+    if min == max {
+        byte_count(max)
+    } else {
+        format!("{}-{} bytes", min, max)
+    }
+}
+
+fn byte_count(bytes: usize) -> String {
+    if bytes == 1 { "1 byte".to_string() } else { format!("{} bytes", bytes) }
+}
+
+/// Returns the structure name and length description of an item.
+fn describe_kind(kind: &LoweredItemKind) -> (&'static str, String) {
+    match kind {
+        LoweredItemKind::Simple { is_explicit: false, byte_size, .. } => {
+            ("Fixed", byte_count(*byte_size))
+        }
+        LoweredItemKind::Simple { is_explicit: true, byte_size, .. } => {
+            ("Explicit", byte_count(byte_size + 1))
+        }
+        LoweredItemKind::Extended { parts } => ("Extended", parts_length(parts)),
+        LoweredItemKind::Repetitive { count, decode_ops, .. } => {
+            ("Repetitive", format!("{} x {}", count, byte_count(unit_bytes(decode_ops, false))))
+        }
+        LoweredItemKind::Compound { .. } => ("Compound", "variable length".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::backend::run_backend;
+    use crate::parse::parser::parse_category;
+    use crate::transform::{lower, transformer::to_ir};
+
+    fn docs(xml: &str) -> String {
+        run_backend(DocsBackend::default(), &lower(&to_ir(parse_category(xml).unwrap()).unwrap()).unwrap())
+    }
+
+    #[test]
+    fn test_docs_item_table_and_bit_map() {
+        let docs = docs(r#"<category id="48">
+            <item id="10" frn="1">
+                <fixed bytes="2">
+                    <field name="sac" bits="8"/>
+                    <field name="sic" bits="8"/>
+                </fixed>
+            </item>
+            <item id="40" frn="4">
+                <fixed bytes="1">
+                    <enum name="target_type" bits="3">
+                        <value name="PSR" value="1"/>
+                    </enum>
+                    <spare bits="5"/>
+                </fixed>
+            </item>
+        </category>"#);
+
+        assert!(docs.contains("# ASTERIX Category 048"));
+        assert!(docs.contains("| 0 | I048/010 | Fixed | 2 bytes |"));
+        assert!(docs.contains("| 3 | I048/040 | Fixed | 1 byte |"));
+        assert!(docs.contains("| 16-9 | sac | `u8` |"));
+        assert!(docs.contains("| 8-1 | sic | `u8` |"));
+        assert!(docs.contains("| 5-1 | spare | ignored |"));
+        assert!(docs.contains("| 1 | Psr |"));
+    }
+
+    #[test]
+    fn test_docs_extended_parts() {
+        let docs = docs(r#"<category id="48">
+            <item id="20" frn="1">
+                <extended bytes="2">
+                    <part index="0"><field name="a" bits="7"/></part>
+                    <part index="1"><epb><field name="b" bits="6"/></epb></part>
+                </extended>
+            </item>
+        </category>"#);
+
+        assert!(docs.contains("| 0 | I048/020 | Extended | 1-2 bytes |"));
+        assert!(docs.contains("| 8-2 | a | `u8` |\n| 1 | FX | next part follows when set |"));
+        assert!(docs.contains("| 7 | b valid | `b` is present when set |"));
+        assert!(docs.contains("| 6-1 | b | `Option<u8>` |"));
+    }
+}
@@ -1,14 +1,41 @@
 use proc_macro2::{Ident, TokenStream};
-use quote::quote;
+use quote::{format_ident, quote};
 
-use crate::transform::lower_ir::{EncodeOp, LoweredPart, LoweredSubItem, LoweredSubItemKind};
+use crate::transform::lower_ir::{EncodeOp, LoweredPart, LoweredSubItem, LoweredSubItemKind, MAX_BYTE_ARRAY_LEN};
+
+/// Returns the writer method writing a number in its byte order.
+fn write_bits(little_endian: bool) -> Ident {
+    if little_endian { format_ident!("write_bits_le") } else { format_ident!("write_bits") }
+}
+
+/// Emits the bits of a number as a `u64`, those of its IEEE 754
+/// representation for a float.
+fn number_bits(value: TokenStream, bits: usize, float: bool) -> TokenStream {
+    match (float, bits) {
+        (true, 64) => quote! { #value.to_bits() },
+        (true, _) => quote! { #value.to_bits() as u64 },
+        (false, _) => quote! { #value as u64 },
+    }
+}
+
+/// Emits the statement writing a reference to a byte array field of
+/// `byte_len` bytes; a vector is truncated or padded to its length.
+fn write_bytes(value: TokenStream, byte_len: usize) -> TokenStream {
+    if byte_len <= MAX_BYTE_ARRAY_LEN {
+        quote! { writer.write_bytes(#value)?; }
+    } else {
+        quote! { writer.write_fixed_bytes(#value, #byte_len)?; }
+    }
+}
 
 /// Emits a single encode operation as a TokenStream.
 fn emit_encode_op(op: &EncodeOp) -> TokenStream {
     match op {
-        EncodeOp::WriteField { name, bits } => {
+        EncodeOp::WriteField { name, bits, little_endian, float } => {
+            let write = write_bits(*little_endian);
+            let value = number_bits(quote! { self.#name }, *bits, *float);
             quote! {
-                writer.write_bits(self.#name as u64, #bits)?;
+                writer.#write(#value, #bits)?;
             }
         }
         EncodeOp::WriteEnum { name, bits } => {
@@ -16,11 +43,13 @@ fn emit_encode_op(op: &EncodeOp) -> TokenStream {
                 writer.write_bits(u8::from(self.#name) as u64, #bits)?;
             }
         }
-        EncodeOp::WriteEpbField { name, bits } => {
+        EncodeOp::WriteEpbField { name, bits, little_endian, float } => {
+            let write = write_bits(*little_endian);
+            let value = number_bits(quote! { value }, *bits, *float);
             quote! {
                 if let Some(value) = self.#name {
                     writer.write_bits(1, 1)?; // Valid bit
-                    writer.write_bits(value as u64, #bits)?;
+                    writer.#write(#value, #bits)?;
                 } else {
                     writer.write_bits(0, 1)?; // Invalid bit
                     writer.write_bits(0, #bits)?; // Zero value
@@ -54,6 +83,19 @@ fn emit_encode_op(op: &EncodeOp) -> TokenStream {
                 }
             }
         }
+        EncodeOp::WriteBytes { name, byte_len } => write_bytes(quote! { &self.#name }, *byte_len),
+        EncodeOp::WriteEpbBytes { name, byte_len } => {
+            let write = write_bytes(quote! { value }, *byte_len);
+            quote! {
+                if let Some(ref value) = self.#name {
+                    writer.write_bits(1, 1)?; // Valid bit
+                    #write
+                } else {
+                    writer.write_bits(0, 1)?; // Invalid bit
+                    writer.write_fixed_bytes(&[], #byte_len)?; // Zero value
+                }
+            }
+        }
         EncodeOp::WriteSpare { bits } => {
             quote! {
                 writer.write_bits(0, #bits)?; // Write spare bits as zero
@@ -64,6 +106,7 @@ fn emit_encode_op(op: &EncodeOp) -> TokenStream {
                 writer.write_bits(#total_bytes as u64, 8)?;
             }
         }
+        EncodeOp::Custom { code, .. } => code.clone(),
     }
 }
 
@@ -76,9 +119,9 @@ pub fn generate_simple_encode(
 
     quote! {
         impl Encode for #name {
-            fn encode<W: std::io::Write>(
+            fn encode<W: BitWrite>(
                 &self,
-                writer: &mut BitWriter<W>,
+                writer: &mut W,
             ) -> Result<(), DecodeError> {
                 #(#op_tokens)*
                 Ok(())
@@ -88,6 +131,11 @@ pub fn generate_simple_encode(
 }
 
 /// Generates encode implementations for an Extended item.
+///
+/// Items whose FX chain can't be written are rejected before anything is
+/// written: a part present after an absent one, unknown extensions after
+/// an absent last part, or extension octets not ending with the only one
+/// whose FX bit is clear.
 pub fn generate_extended_encode(
     name: &Ident,
     parts: &[LoweredPart],
@@ -104,9 +152,9 @@ pub fn generate_extended_encode(
 
         part_impl_tokens.push(quote! {
             impl #part_name {
-                pub fn encode<W: std::io::Write>(
+                pub fn encode<W: BitWrite>(
                     &self,
-                    writer: &mut BitWriter<W>,
+                    writer: &mut W,
                 ) -> Result<(), DecodeError> {
                     #(#element_encodes)*
                     Ok(())
@@ -114,45 +162,69 @@ pub fn generate_extended_encode(
             }
         });
 
-        if i == 0 {
-            if total_parts > 1 {
-                let next_field = &parts[i + 1].field_name;
-                main_encode_body.push(quote! {
-                    self.#field_name.encode(writer)?;
-                    writer.write_bits(self.#next_field.is_some() as u64, 1)?; // FX bit
-                });
-            } else {
-                main_encode_body.push(quote! {
-                    self.#field_name.encode(writer)?;
-                    writer.write_bits(0, 1)?; // FX bit = 0, no extension
-                });
+        // The FX bit after the last part announces the unknown extensions.
+        let next_present = match parts.get(i + 1) {
+            Some(next) => {
+                let next_field = &next.field_name;
+                quote! { self.#next_field.is_some() }
             }
-        } else if i < total_parts - 1 {
-            let next_field = &parts[i + 1].field_name;
+            None => quote! { !self.extensions.is_empty() },
+        };
+        let extensions = (i == total_parts - 1).then(|| quote! {
+            writer.write_bytes(&self.extensions)?;
+        });
+
+        if i == 0 {
             main_encode_body.push(quote! {
-                if let Some(ref part_data) = self.#field_name {
-                    part_data.encode(writer)?;
-                    writer.write_bits(self.#next_field.is_some() as u64, 1)?; // FX bit
-                }
+                self.#field_name.encode(writer)?;
+                writer.write_bits(#next_present as u64, 1)?; // FX bit
+                #extensions
             });
         } else {
             main_encode_body.push(quote! {
                 if let Some(ref part_data) = self.#field_name {
                     part_data.encode(writer)?;
-                    writer.write_bits(0, 1)?; // FX bit = 0, no more extension
-                }
+                    writer.write_bits(#next_present as u64, 1)?; // FX bit
+                    #extensions
+                };
             });
         }
     }
 
+    // The first part is always present.
+    let gap_checks = parts.windows(2).skip(1).map(|pair| {
+        let (part, next) = (&pair[0].field_name, &pair[1].field_name);
+        quote! {
+            if self.#next.is_some() && self.#part.is_none() {
+                return Err(DecodeError::InvalidData("extended item has a part after an absent one"));
+            };
+        }
+    });
+    let last_absent = parts[1..].last().map(|last| {
+        let last = &last.field_name;
+        quote! {
+            if !self.extensions.is_empty() && self.#last.is_none() {
+                return Err(DecodeError::InvalidData("extended item has extensions after an absent part"));
+            };
+        }
+    });
+
     quote! {
         #(#part_impl_tokens)*
 
         impl Encode for #name {
-            fn encode<W: std::io::Write>(
+            fn encode<W: BitWrite>(
                 &self,
-                writer: &mut BitWriter<W>,
+                writer: &mut W,
             ) -> Result<(), DecodeError> {
+                #(#gap_checks)*
+                #last_absent
+                let fx_broken = self.extensions.split_last().is_some_and(|(last, octets)| {
+                    last & 1 != 0 || octets.iter().any(|octet| octet & 1 == 0)
+                });
+                if fx_broken {
+                    return Err(DecodeError::InvalidData("extended item extensions must have FX set on every octet but the last"));
+                };
                 #(#main_encode_body)*
                 Ok(())
             }
@@ -161,18 +233,22 @@ pub fn generate_extended_encode(
 }
 
 /// Generates encode implementation for a Repetitive item.
+///
+/// Items without exactly `count` elements are rejected before anything is
+/// written, so an illegal item is never produced.
 pub fn generate_repetitive_encode(
     name: &Ident,
     element_type_name: &Ident,
+    count: usize,
     encode_ops: &[EncodeOp],
 ) -> TokenStream {
     let element_encodes: Vec<_> = encode_ops.iter().map(emit_encode_op).collect();
 
     quote! {
         impl #element_type_name {
-            fn encode<W: std::io::Write>(
+            fn encode<W: BitWrite>(
                 &self,
-                writer: &mut BitWriter<W>,
+                writer: &mut W,
             ) -> Result<(), DecodeError> {
                 #(#element_encodes)*
                 Ok(())
@@ -180,10 +256,13 @@ pub fn generate_repetitive_encode(
         }
 
         impl Encode for #name {
-            fn encode<W: std::io::Write>(
+            fn encode<W: BitWrite>(
                 &self,
-                writer: &mut BitWriter<W>,
+                writer: &mut W,
             ) -> Result<(), DecodeError> {
+                if self.items.len() != #count {
+                    return Err(DecodeError::InvalidData("repetitive item does not have its number of repetitions"));
+                }
                 for item in &self.items {
                     item.encode(writer)?;
                 }
@@ -203,19 +282,18 @@ pub fn generate_compound_encode(
 
     for sub in sub_items {
         let field_name = &sub.field_name;
-        let byte = sub.fspec_byte;
-        let bit = sub.fspec_bit;
+        let frn = sub.frn;
 
         fspec_setup.push(quote! {
             if self.#field_name.is_some() {
-                fspec.set(#byte, #bit);
-            }
+                fspec.set_frn(#frn);
+            };
         });
 
         sub_encodes.push(quote! {
             if let Some(ref sub_data) = self.#field_name {
                 sub_data.encode(&mut writer)?;
-            }
+            };
         });
     }
 
@@ -236,6 +314,15 @@ pub fn generate_compound_encode(
                 Ok(())
             }
         }
+
+        impl EncodeToWrite for #name {
+            fn encode_to<W: std::io::Write>(
+                &self,
+                writer: &mut W,
+            ) -> Result<(), DecodeError> {
+                self.encode(writer)
+            }
+        }
     }
 }
 
@@ -251,8 +338,8 @@ pub fn generate_compound_sub_encodes(
             LoweredSubItemKind::Extended { parts } => {
                 generate_extended_encode(&sub.struct_name, parts)
             }
-            LoweredSubItemKind::Repetitive { element_type_name, encode_ops, .. } => {
-                generate_repetitive_encode(&sub.struct_name, element_type_name, encode_ops)
+            LoweredSubItemKind::Repetitive { element_type_name, count, encode_ops, .. } => {
+                generate_repetitive_encode(&sub.struct_name, element_type_name, *count, encode_ops)
             }
         }
     }).collect();
@@ -1,15 +1,18 @@
 use proc_macro2::TokenStream;
 use quote::quote;
 
-use crate::transform::lower_ir::LoweredEnum;
+use crate::transform::lower_ir::{LoweredEnum, UnknownValues};
 
 /// Generates a Rust enum from a pre-lowered enum definition.
 ///
 /// Creates an enum with:
 /// - Named variants for all defined values
-/// - An Unknown(u8) variant for undefined values
+/// - An Unknown(u8) variant for undefined values, unless the definition
+///   rejects them or maps them to a variant
+/// - Associated constants for variant aliases
 /// - TryFrom<u8> implementation for decoding
 /// - Into<u8> implementation for encoding
+/// - `from_decoded`, which consults the decode policy about unknown values
 pub fn generate_enum(lowered: &LoweredEnum) -> TokenStream {
     let enum_name = &lowered.name;
 
@@ -25,18 +28,90 @@ pub fn generate_enum(lowered: &LoweredEnum) -> TokenStream {
         quote! { #vval => Ok(Self::#vname) }
     }).collect();
 
+    let name = enum_name.to_string();
+
     let from_arms: Vec<_> = lowered.variants.iter().map(|v| {
         let vname = &v.name;
         let vval = v.value;
         quote! { #enum_name::#vname => #vval }
     }).collect();
 
+    let aliases: Vec<_> = lowered.variants.iter().filter_map(|v| {
+        let vname = &v.name;
+        let alias = v.alias.as_ref()?;
+        let doc = format!("Alias of [`Self::{}`].", vname);
+        Some(quote! {
+            #[doc = #doc]
+            #[allow(non_upper_case_globals)]
+            pub const #alias: Self = Self::#vname;
+        })
+    }).collect();
+
+    // Only preserved unknown values need a variant to hold them
+    let (unknown_variant, unknown_try_from, unknown_from, from_decoded) = match &lowered.unknown {
+        UnknownValues::Preserve => (
+            quote! { Unknown(u8), },
+            quote! { _ => Ok(Self::Unknown(value)), },
+            quote! { #enum_name::Unknown(v) => v, },
+            quote! {
+                /// Converts a decoded value, consulting the current
+                /// `DecodePolicy` if it has no variant.
+                pub fn from_decoded(value: u8) -> Result<Self, DecodeError> {
+                    match Self::try_from(value).unwrap() {
+                        Self::Unknown(value) => Ok(Self::try_from(policy::unknown_enum(#name, value)?).unwrap()),
+                        known => Ok(known),
+                    }
+                }
+            },
+        ),
+        UnknownValues::Error => (
+            quote! {},
+            quote! { _ => Err(()), },
+            quote! {},
+            quote! {
+                /// Converts a decoded value, consulting the current
+                /// `DecodePolicy` if it has no variant.  Fails if the value
+                /// the policy returns has no variant either.
+                pub fn from_decoded(value: u8) -> Result<Self, DecodeError> {
+                    match Self::try_from(value) {
+                        Ok(known) => Ok(known),
+                        Err(()) => Self::try_from(policy::unknown_enum(#name, value)?)
+                            .map_err(|()| DecodeError::InvalidData("enum value has no variant")),
+                    }
+                }
+            },
+        ),
+        UnknownValues::MapTo(target) => {
+            let doc = format!(
+                " Converts a decoded value, consulting the current\n \
+                 `DecodePolicy` if it has no variant.  Values the policy\n \
+                 leaves without a variant decode as [`Self::{}`].",
+                target
+            );
+            (
+                quote! {},
+                quote! { _ => Err(()), },
+                quote! {},
+                quote! {
+                    #[doc = #doc]
+                    pub fn from_decoded(value: u8) -> Result<Self, DecodeError> {
+                        match Self::try_from(value) {
+                            Ok(known) => Ok(known),
+                            Err(()) => Ok(Self::try_from(policy::unknown_enum(#name, value)?)
+                                .unwrap_or(Self::#target)),
+                        }
+                    }
+                },
+            )
+        }
+    };
+
     quote! {
-        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
         #[repr(u8)]
         pub enum #enum_name {
             #(#variants,)*
-            Unknown(u8),
+            #unknown_variant
         }
 
         impl TryFrom<u8> for #enum_name {
@@ -45,16 +120,22 @@ pub fn generate_enum(lowered: &LoweredEnum) -> TokenStream {
             fn try_from(value: u8) -> Result<Self, ()> {
                 match value {
                     #(#try_from_arms,)*
-                    _ => Ok(Self::Unknown(value)),
+                    #unknown_try_from
                 }
             }
         }
 
+        impl #enum_name {
+            #(#aliases)*
+
+            #from_decoded
+        }
+
         impl From<#enum_name> for u8 {
             fn from(val: #enum_name) -> u8 {
                 match val {
                     #(#from_arms,)*
-                    #enum_name::Unknown(v) => v,
+                    #unknown_from
                 }
             }
         }
@@ -72,10 +153,11 @@ mod tests {
         let lowered = LoweredEnum {
             name: format_ident!("TargetType"),
             variants: vec![
-                LoweredEnumVariant { name: format_ident!("Psr"), value: 1 },
-                LoweredEnumVariant { name: format_ident!("Ssr"), value: 2 },
-                LoweredEnumVariant { name: format_ident!("Combined"), value: 3 },
+                LoweredEnumVariant { name: format_ident!("Psr"), value: 1, alias: None },
+                LoweredEnumVariant { name: format_ident!("Ssr"), value: 2, alias: None },
+                LoweredEnumVariant { name: format_ident!("Combined"), value: 3, alias: None },
             ],
+            unknown: UnknownValues::Preserve,
         };
 
         let result = generate_enum(&lowered);
@@ -88,5 +170,6 @@ mod tests {
         assert!(code.contains("Unknown (u8)"));
         assert!(code.contains("impl TryFrom < u8 > for TargetType"));
         assert!(code.contains("impl From < TargetType > for u8"));
+        assert!(code.contains("policy :: unknown_enum (\"TargetType\" , value)"));
     }
 }
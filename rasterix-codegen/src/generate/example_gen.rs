@@ -0,0 +1,168 @@
+use proc_macro2::{Literal, TokenStream};
+use quote::{format_ident, quote};
+
+use crate::transform::lower_ir::{
+    ExampleCheck, ExampleStep, ExpectedValue, FieldDescriptor, FieldType, LoweredEnum, LoweredItem, LoweredItemKind,
+    LoweredPart, LoweredSubItem, LoweredSubItemKind, MAX_BYTE_ARRAY_LEN,
+};
+
+/// Generates a `#[cfg(test)]` module testing the worked examples of an
+/// item.
+///
+/// Each example gets a test decoding its bytes, asserting the documented
+/// field values and encoding the item back to the same bytes.  Items
+/// without examples generate nothing.
+pub fn generate_item_examples(item: &LoweredItem) -> TokenStream {
+    if item.examples.is_empty() {
+        return quote! {};
+    }
+
+    let item_name = &item.name;
+    let module = format_ident!("{}_examples", item_name.to_string().to_lowercase());
+    let tests = item.examples.iter().enumerate().map(|(i, example)| {
+        let test_name = format_ident!("example_{}", i + 1);
+        let doc = example.description.iter();
+        let bytes = &example.bytes;
+        let checks = example.checks.iter().map(|check| generate_check(item, check));
+        quote! {
+            #(#[doc = #doc])*
+            #[test]
+            fn #test_name() {
+                const BYTES: &[u8] = &[#(#bytes),*];
+                let item = #item_name::decode(&mut BitReader::new(BYTES)).expect("example decodes");
+                #(#checks)*
+
+                let mut bytes = Vec::new();
+                let mut writer = BitWriter::new(&mut bytes);
+                item.encode(&mut writer).expect("example encodes");
+                writer.flush().expect("example encodes");
+                assert_eq!(bytes, BYTES, "example encodes back to its bytes");
+            }
+        }
+    });
+
+    quote! {
+        #[cfg(test)]
+        mod #module {
+            use super::*;
+
+            #(#tests)*
+        }
+    }
+}
+
+/// The structs a step of an example path leads into.
+enum Container<'a> {
+    Fields(&'a [FieldDescriptor]),
+    Parts(&'a [LoweredPart]),
+    Elements(&'a [FieldDescriptor]),
+    SubItems(&'a [LoweredSubItem]),
+}
+
+impl<'a> Container<'a> {
+    fn of_item(kind: &'a LoweredItemKind) -> Self {
+        match kind {
+            LoweredItemKind::Simple { fields, .. } => Container::Fields(fields),
+            LoweredItemKind::Extended { parts } => Container::Parts(parts),
+            LoweredItemKind::Repetitive { fields, .. } => Container::Elements(fields),
+            LoweredItemKind::Compound { sub_items } => Container::SubItems(sub_items),
+        }
+    }
+
+    fn of_sub_item(kind: &'a LoweredSubItemKind) -> Self {
+        match kind {
+            LoweredSubItemKind::Simple { fields, .. } => Container::Fields(fields),
+            LoweredSubItemKind::Extended { parts } => Container::Parts(parts),
+            LoweredSubItemKind::Repetitive { fields, .. } => Container::Elements(fields),
+        }
+    }
+}
+
+/// Generates the assertion of one field value of an example, following
+/// its steps from `item`.
+fn generate_check(item: &LoweredItem, check: &ExampleCheck) -> TokenStream {
+    let mut access = quote! { item };
+    let mut container = Container::of_item(&item.kind);
+    let mut enums: &[LoweredEnum] = &item.enums;
+
+    for step in &check.steps {
+        container = match (*step, container) {
+            (ExampleStep::Part(position), Container::Parts(parts)) => {
+                let part = &parts[position];
+                let name = &part.field_name;
+                access = if part.is_required {
+                    quote! { #access.#name }
+                } else {
+                    let message = format!("{} is present", name);
+                    quote! { #access.#name.as_ref().expect(#message) }
+                };
+                Container::Fields(&part.fields)
+            }
+            (ExampleStep::SubItem(position), Container::SubItems(sub_items)) => {
+                let sub = &sub_items[position];
+                let name = &sub.field_name;
+                let message = format!("{} is present", name);
+                access = quote! { #access.#name.as_ref().expect(#message) };
+                enums = &sub.enums;
+                Container::of_sub_item(&sub.kind)
+            }
+            (ExampleStep::Element(repetition), Container::Elements(fields)) => {
+                access = quote! { #access.items[#repetition] };
+                Container::Fields(fields)
+            }
+            (ExampleStep::Field(position), Container::Fields(fields)) => {
+                let field = &fields[position];
+                let name = &field.name;
+                let expected = expected_tokens(field, enums, &check.value);
+                let actual = match field.type_tokens {
+                    FieldType::OptionalFixedString(_) => quote! { #access.#name.as_deref() },
+                    _ => quote! { #access.#name },
+                };
+                let path = &check.path;
+                return quote! { assert_eq!(#actual, #expected, #path); };
+            }
+            _ => unreachable!("example steps follow the item's structure"),
+        };
+    }
+    unreachable!("example paths end in a field")
+}
+
+/// Generates the expected value of a field in its Rust type.
+fn expected_tokens(field: &FieldDescriptor, enums: &[LoweredEnum], value: &ExpectedValue) -> TokenStream {
+    let value = match value {
+        ExpectedValue::Absent => return quote! { None },
+        ExpectedValue::Number(number) => {
+            let number = Literal::u128_unsuffixed(*number);
+            quote! { #number }
+        }
+        ExpectedValue::Float(number) => {
+            let number = Literal::f64_unsuffixed(*number);
+            quote! { #number }
+        }
+        ExpectedValue::Text(text) => quote! { #text },
+        ExpectedValue::Bytes(bytes) => match field.type_tokens {
+            FieldType::Bytes(byte_len) | FieldType::OptionalBytes(byte_len) if byte_len > MAX_BYTE_ARRAY_LEN => {
+                quote! { vec![#(#bytes),*] }
+            }
+            _ => quote! { [#(#bytes),*] },
+        },
+        ExpectedValue::Variant(position) => {
+            let (FieldType::Enum(enum_name) | FieldType::OptionalEnum(enum_name)) = &field.type_tokens else {
+                unreachable!("variants are expected of enum fields")
+            };
+            let lowered = enums
+                .iter()
+                .find(|lowered| lowered.name == *enum_name)
+                .expect("enum fields have their enum");
+            let variant = &lowered.variants[*position].name;
+            quote! { #enum_name::#variant }
+        }
+    };
+    match field.type_tokens {
+        FieldType::OptionalPrimitive(_)
+        | FieldType::OptionalEnum(_)
+        | FieldType::OptionalFixedString(_)
+        | FieldType::OptionalBytes(_) => quote! { Some(#value) },
+        FieldType::Primitive(_) | FieldType::Enum(_) | FieldType::FixedString(_) | FieldType::Bytes(_) => value,
+    }
+}
@@ -0,0 +1,565 @@
+use std::fmt::Write;
+
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+
+use crate::transform::lower_ir::{
+    FieldDescriptor, FieldType, LoweredIR, LoweredItem, LoweredItemKind, LoweredPart,
+    LoweredSubItemKind,
+};
+use super::backend::CodegenBackend;
+use super::utils::feature_cfg;
+
+/// Output of the [`FfiBackend`].
+#[derive(Debug, Clone)]
+pub struct FfiOutput {
+    /// C header declaring the API.
+    pub header: String,
+    /// Rust `extern "C"` shims implementing the API.
+    ///
+    /// The shims form a `<module>_ffi` module that refers to the generated
+    /// types as `super::<module>`, so they belong in the same file as the
+    /// generated code.
+    pub shims: TokenStream,
+}
+
+/// Backend emitting a C API over the generated Rust types.
+///
+/// Records and data blocks are opaque handles.  Every field gets a getter
+/// that returns `false` when the item, part, sub-item or EPB-guarded value
+/// is absent, so callers never see uninitialized values:
+///
+/// ```c
+/// cat048_record *record;
+/// if (cat048_decode(data, len, &record) == CAT048_OK) {
+///     uint8_t sac;
+///     if (cat048_item010_sac(record, &sac)) { /* ... */ }
+///     cat048_record_free(record);
+/// }
+/// ```
+///
+/// Enums are exposed as their raw `uint8_t` value, strings are copied into
+/// a caller-provided buffer, and repetitive items get a `_count` getter plus
+/// getters taking an element index.  Fields wider than 64 bits have no C
+/// type and are left out.  The shims of feature-gated items are compiled
+/// under the same feature as the item.
+#[derive(Debug, Default)]
+pub struct FfiBackend {
+    prefix: String,
+    /// Record field name for each item type, from the record entries.
+    item_fields: Vec<(Ident, Ident)>,
+    declarations: String,
+    shims: Vec<TokenStream>,
+}
+
+impl CodegenBackend for FfiBackend {
+    type Output = FfiOutput;
+
+    fn begin_module(&mut self, lowered: &LoweredIR) {
+        self.prefix = lowered.module_name.to_string();
+        self.item_fields = lowered
+            .record
+            .entries
+            .iter()
+            .map(|e| (e.type_name.clone(), e.field_name.clone()))
+            .collect();
+    }
+
+    fn emit_struct(&mut self, item: &LoweredItem) {
+        let Some(item_field) = self
+            .item_fields
+            .iter()
+            .find(|(type_name, _)| *type_name == item.name)
+            .map(|(_, field)| field.clone())
+        else {
+            return;
+        };
+
+        let prefix = &self.prefix;
+        let record = format_ident!("Record");
+        let has_fn = format_ident!("{}_has_{}", prefix, item_field);
+        match &item.feature {
+            Some(feature) => writeln!(self.declarations, "\n/* {} (feature \"{}\") */", item.name, feature).unwrap(),
+            None => writeln!(self.declarations, "\n/* {} */", item.name).unwrap(),
+        }
+        let first_shim = self.shims.len();
+        writeln!(self.declarations, "bool {}(const {}_record *record);", has_fn, prefix).unwrap();
+        self.shims.push(quote! {
+            #[unsafe(no_mangle)]
+            pub unsafe extern "C" fn #has_fn(record: *const #record) -> bool {
+                unsafe { record.as_ref() }.is_some_and(|r| r.#item_field.is_some())
+            }
+        });
+
+        let root = Access::root(&item_field);
+        let name = item_field.to_string();
+        match &item.kind {
+            LoweredItemKind::Simple { fields, .. } => self.fields(&name, &root, fields),
+            LoweredItemKind::Extended { parts } => self.parts(&name, &root, parts),
+            LoweredItemKind::Repetitive { fields, .. } => self.repetitive(&name, &root, fields),
+            LoweredItemKind::Compound { sub_items } => {
+                for sub in sub_items {
+                    let access = root.optional(&sub.field_name);
+                    let name = format!("{}_{}", name, sub.field_name);
+                    match &sub.kind {
+                        LoweredSubItemKind::Simple { fields, .. } => self.fields(&name, &access, fields),
+                        LoweredSubItemKind::Extended { parts } => self.parts(&name, &access, parts),
+                        LoweredSubItemKind::Repetitive { fields, .. } => {
+                            self.repetitive(&name, &access, fields)
+                        }
+                    }
+                }
+            }
+        }
+
+        let cfg = feature_cfg(&item.feature);
+        for shim in &mut self.shims[first_shim..] {
+            *shim = quote! { #cfg #shim };
+        }
+    }
+
+    fn finalize(self, lowered: &LoweredIR) -> FfiOutput {
+        let prefix = &self.prefix;
+        let upper = prefix.to_uppercase();
+        let guard = format!("RASTERIX_{}_H", upper);
+
+        let mut header = String::new();
+        writeln!(header, "/* AUTO-GENERATED CODE - DO NOT EDIT */").unwrap();
+        writeln!(header, "/* C API for ASTERIX category {:03}. */\n", lowered.category_id).unwrap();
+        writeln!(header, "#ifndef {}\n#define {}\n", guard, guard).unwrap();
+        writeln!(header, "#include <stdbool.h>\n#include <stddef.h>\n#include <stdint.h>\n").unwrap();
+        writeln!(header, "#ifdef __cplusplus\nextern \"C\" {{\n#endif\n").unwrap();
+        writeln!(header, "#define {}_OK 0", upper).unwrap();
+        writeln!(header, "#define {}_ERR_NULL -1", upper).unwrap();
+        writeln!(header, "#define {}_ERR_DECODE -2\n", upper).unwrap();
+        writeln!(header, "typedef struct {}_record {}_record;", prefix, prefix).unwrap();
+        writeln!(header, "typedef struct {}_block {}_block;\n", prefix, prefix).unwrap();
+        writeln!(header, "/* Decodes one record (FSPEC and items, no block header). */").unwrap();
+        writeln!(
+            header,
+            "int32_t {p}_decode(const uint8_t *data, size_t len, {p}_record **out);",
+            p = prefix
+        )
+        .unwrap();
+        writeln!(header, "void {p}_record_free({p}_record *record);\n", p = prefix).unwrap();
+        writeln!(header, "/* Decodes a data block, including its header. */").unwrap();
+        writeln!(
+            header,
+            "int32_t {p}_decode_block(const uint8_t *data, size_t len, {p}_block **out);",
+            p = prefix
+        )
+        .unwrap();
+        writeln!(header, "size_t {p}_block_len(const {p}_block *block);", p = prefix).unwrap();
+        writeln!(
+            header,
+            "/* The returned record is owned by the block. */\n\
+             const {p}_record *{p}_block_record(const {p}_block *block, size_t index);",
+            p = prefix
+        )
+        .unwrap();
+        writeln!(header, "void {p}_block_free({p}_block *block);", p = prefix).unwrap();
+        header.push_str(&self.declarations);
+        writeln!(header, "\n#ifdef __cplusplus\n}}\n#endif\n\n#endif /* {} */", guard).unwrap();
+
+        let module_name = &lowered.module_name;
+        let ffi_module = format_ident!("{}_ffi", prefix);
+        let ok = quote! { 0i32 };
+        let err_null = quote! { -1i32 };
+        let err_decode = quote! { -2i32 };
+        let decode = format_ident!("{}_decode", prefix);
+        let record_free = format_ident!("{}_record_free", prefix);
+        let decode_block = format_ident!("{}_decode_block", prefix);
+        let block_len = format_ident!("{}_block_len", prefix);
+        let block_record = format_ident!("{}_block_record", prefix);
+        let block_free = format_ident!("{}_block_free", prefix);
+        let shims = self.shims;
+
+        let shims = quote! {
+            /// C API over the generated types; see the generated header.
+            pub mod #ffi_module {
+                use super::#module_name::*;
+                use rasterix::rcore::{BitReader, Decode};
+                use std::ffi::c_char;
+
+                /// Decodes a value from a C buffer into a heap allocation.
+                unsafe fn decode_raw<T: Decode>(data: *const u8, len: usize, out: *mut *mut T) -> i32 {
+                    if data.is_null() || out.is_null() {
+                        return #err_null;
+                    }
+                    let bytes = unsafe { std::slice::from_raw_parts(data, len) };
+                    let mut reader = BitReader::new(std::io::Cursor::new(bytes));
+                    match T::decode(&mut reader) {
+                        Ok(value) => {
+                            unsafe { *out = Box::into_raw(Box::new(value)) };
+                            #ok
+                        }
+                        Err(_) => #err_decode,
+                    }
+                }
+
+                /// Writes a getter result, returning whether it was present.
+                unsafe fn write_value<T>(value: Option<T>, out: *mut T) -> bool {
+                    match value {
+                        Some(value) => {
+                            if !out.is_null() {
+                                unsafe { *out = value };
+                            }
+                            true
+                        }
+                        None => false,
+                    }
+                }
+
+                /// Copies a string into a NUL-terminated C buffer.
+                unsafe fn write_str(value: Option<&str>, buf: *mut c_char, cap: usize) -> bool {
+                    let Some(value) = value else {
+                        return false;
+                    };
+                    if !buf.is_null() && cap > 0 {
+                        let len = value.len().min(cap - 1);
+                        unsafe {
+                            std::ptr::copy_nonoverlapping(value.as_ptr(), buf as *mut u8, len);
+                            *buf.add(len) = 0;
+                        }
+                    }
+                    true
+                }
+
+                #[unsafe(no_mangle)]
+                pub unsafe extern "C" fn #decode(data: *const u8, len: usize, out: *mut *mut Record) -> i32 {
+                    unsafe { decode_raw(data, len, out) }
+                }
+
+                #[unsafe(no_mangle)]
+                pub unsafe extern "C" fn #record_free(record: *mut Record) {
+                    if !record.is_null() {
+                        drop(unsafe { Box::from_raw(record) });
+                    }
+                }
+
+                #[unsafe(no_mangle)]
+                pub unsafe extern "C" fn #decode_block(data: *const u8, len: usize, out: *mut *mut DataBlock) -> i32 {
+                    unsafe { decode_raw(data, len, out) }
+                }
+
+                #[unsafe(no_mangle)]
+                pub unsafe extern "C" fn #block_len(block: *const DataBlock) -> usize {
+                    unsafe { block.as_ref() }.map_or(0, |b| b.records.len())
+                }
+
+                #[unsafe(no_mangle)]
+                pub unsafe extern "C" fn #block_record(block: *const DataBlock, index: usize) -> *const Record {
+                    unsafe { block.as_ref() }
+                        .and_then(|b| b.records.get(index))
+                        .map_or(std::ptr::null(), |r| r as *const Record)
+                }
+
+                #[unsafe(no_mangle)]
+                pub unsafe extern "C" fn #block_free(block: *mut DataBlock) {
+                    if !block.is_null() {
+                        drop(unsafe { Box::from_raw(block) });
+                    }
+                }
+
+                #(#shims)*
+            }
+        };
+
+        FfiOutput { header, shims }
+    }
+}
+
+impl FfiBackend {
+    /// Emits getters for the fields of a part of an extended layout.
+    fn parts(&mut self, name: &str, access: &Access, parts: &[LoweredPart]) {
+        for part in parts {
+            let access = if part.is_required {
+                access.required(&part.field_name)
+            } else {
+                access.optional(&part.field_name)
+            };
+            self.fields(&format!("{}_{}", name, part.field_name), &access, &part.fields);
+        }
+    }
+
+    /// Emits a count getter and indexed field getters for a repetitive layout.
+    fn repetitive(&mut self, name: &str, access: &Access, fields: &[FieldDescriptor]) {
+        let prefix = &self.prefix;
+        let count_fn = format_ident!("{}_{}_count", prefix, name);
+        let items = access.expr();
+        writeln!(self.declarations, "size_t {}(const {}_record *record);", count_fn, prefix).unwrap();
+        self.shims.push(quote! {
+            #[unsafe(no_mangle)]
+            pub unsafe extern "C" fn #count_fn(record: *const Record) -> usize {
+                let Some(record) = (unsafe { record.as_ref() }) else {
+                    return 0;
+                };
+                #items.map_or(0, |v| v.items.len())
+            }
+        });
+
+        self.fields(name, &access.indexed(), fields);
+    }
+
+    /// Emits one getter per field.
+    fn fields(&mut self, name: &str, access: &Access, fields: &[FieldDescriptor]) {
+        let prefix = self.prefix.clone();
+        let parent = access.expr();
+        let (index_param, index_decl) = if access.indexed {
+            (quote! { index: usize, }, "size_t index, ")
+        } else {
+            (quote! {}, "")
+        };
+
+        for field in fields {
+            let fname = &field.name;
+            let getter = format_ident!("{}_{}_{}", prefix, name, fname);
+
+            let (c_type, rust_type, value) = match &field.type_tokens {
+                FieldType::Primitive(ty) => (c_type(ty), quote! { #ty }, quote! { .map(|v| v.#fname) }),
+                FieldType::OptionalPrimitive(ty) => (c_type(ty), quote! { #ty }, quote! { .and_then(|v| v.#fname) }),
+                FieldType::Enum(_) => (Some("uint8_t"), quote! { u8 }, quote! { .map(|v| u8::from(v.#fname)) }),
+                FieldType::OptionalEnum(_) => {
+                    (Some("uint8_t"), quote! { u8 }, quote! { .and_then(|v| v.#fname).map(u8::from) })
+                }
+                FieldType::FixedString(_) | FieldType::OptionalFixedString(_) => {
+                    let value = if matches!(field.type_tokens, FieldType::FixedString(_)) {
+                        quote! { .map(|v| v.#fname.as_str()) }
+                    } else {
+                        quote! { .and_then(|v| v.#fname.as_deref()) }
+                    };
+                    writeln!(
+                        self.declarations,
+                        "bool {}(const {}_record *record, {}char *buf, size_t cap);",
+                        getter, prefix, index_decl
+                    )
+                    .unwrap();
+                    self.shims.push(quote! {
+                        #[unsafe(no_mangle)]
+                        pub unsafe extern "C" fn #getter(record: *const Record, #index_param buf: *mut c_char, cap: usize) -> bool {
+                            let Some(record) = (unsafe { record.as_ref() }) else {
+                                return false;
+                            };
+                            unsafe { write_str(#parent #value, buf, cap) }
+                        }
+                    });
+                    continue;
+                }
+                FieldType::Bytes(_) | FieldType::OptionalBytes(_) => {
+                    writeln!(self.declarations, "/* {}: no C type for a byte array */", getter).unwrap();
+                    continue;
+                }
+            };
+
+            let Some(c_type) = c_type else {
+                writeln!(self.declarations, "/* {}: no C type for {} */", getter, rust_type).unwrap();
+                continue;
+            };
+
+            writeln!(
+                self.declarations,
+                "bool {}(const {}_record *record, {}{} *out);",
+                getter, prefix, index_decl, c_type
+            )
+            .unwrap();
+            self.shims.push(quote! {
+                #[unsafe(no_mangle)]
+                pub unsafe extern "C" fn #getter(record: *const Record, #index_param out: *mut #rust_type) -> bool {
+                    let Some(record) = (unsafe { record.as_ref() }) else {
+                        return false;
+                    };
+                    unsafe { write_value(#parent #value, out) }
+                }
+            });
+        }
+    }
+}
+
+/// Maps a Rust integer type to its C equivalent.
+fn c_type(ty: &Ident) -> Option<&'static str> {
+    match ty.to_string().as_str() {
+        "u8" => Some("uint8_t"),
+        "u16" => Some("uint16_t"),
+        "u32" => Some("uint32_t"),
+        "u64" => Some("uint64_t"),
+        "f32" => Some("float"),
+        "f64" => Some("double"),
+        _ => None,
+    }
+}
+
+/// Expression reaching a struct from `record`, as an `Option<&T>`.
+#[derive(Clone)]
+struct Access {
+    expr: TokenStream,
+    indexed: bool,
+}
+
+impl Access {
+    fn root(item_field: &Ident) -> Self {
+        Self {
+            expr: quote! { record.#item_field.as_ref() },
+            indexed: false,
+        }
+    }
+
+    fn required(&self, field: &Ident) -> Self {
+        let expr = &self.expr;
+        Self {
+            expr: quote! { #expr.map(|v| &v.#field) },
+            indexed: self.indexed,
+        }
+    }
+
+    fn optional(&self, field: &Ident) -> Self {
+        let expr = &self.expr;
+        Self {
+            expr: quote! { #expr.and_then(|v| v.#field.as_ref()) },
+            indexed: self.indexed,
+        }
+    }
+
+    /// Selects element `index` of a repetitive item.
+    fn indexed(&self) -> Self {
+        let expr = &self.expr;
+        Self {
+            expr: quote! { #expr.and_then(|v| v.items.get(index)) },
+            indexed: true,
+        }
+    }
+
+    fn expr(&self) -> TokenStream {
+        self.expr.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::backend::run_backend;
+    use crate::transform::lower_ir::{FieldConstraints, FieldRevision, LoweredRecord, RecordEntry, RepetitiveStorage};
+
+    fn lowered(kind: LoweredItemKind) -> LoweredIR {
+        LoweredIR {
+            category_id: 48,
+            module_name: format_ident!("cat048"),
+            record: LoweredRecord {
+                name: format_ident!("Record"),
+                entries: vec![RecordEntry {
+                    field_name: format_ident!("item010"),
+                    type_name: format_ident!("Item010"),
+                    frn: 1,
+                    feature: None,
+                    is_mandatory: false,
+                }],
+                is_hashable: true,
+            },
+            items: vec![LoweredItem {
+                id: 10,
+                name: format_ident!("Item010"),
+                title: None,
+                short_name: None,
+                is_reserved: false,
+                alias: None,
+                enums: vec![],
+                feature: None,
+                is_hashable: true,
+                derived: vec![],
+                examples: vec![],
+                kind,
+            }],
+        }
+    }
+
+    fn field(name: &str, type_tokens: FieldType) -> FieldDescriptor {
+        FieldDescriptor { name: format_ident!("{}", name), type_tokens, revision: FieldRevision::default(), description: None, constraints: FieldConstraints::default(), typed: None }
+    }
+
+    #[test]
+    fn test_simple_item_getters() {
+        let output = run_backend(
+            FfiBackend::default(),
+            &lowered(LoweredItemKind::Simple {
+                is_explicit: false,
+                byte_size: 2,
+                fields: vec![
+                    field("sac", FieldType::Primitive(format_ident!("u8"))),
+                    field("typ", FieldType::OptionalEnum(format_ident!("Typ"))),
+                    field("id", FieldType::FixedString(6)),
+                ],
+                decode_ops: vec![],
+                encode_ops: vec![],
+            }),
+        );
+
+        assert!(output.header.contains("#ifndef RASTERIX_CAT048_H"));
+        assert!(output.header.contains(
+            "int32_t cat048_decode(const uint8_t *data, size_t len, cat048_record **out);"
+        ));
+        assert!(output.header.contains("bool cat048_has_item010(const cat048_record *record);"));
+        assert!(output.header.contains("bool cat048_item010_sac(const cat048_record *record, uint8_t *out);"));
+        assert!(output.header.contains("bool cat048_item010_typ(const cat048_record *record, uint8_t *out);"));
+        assert!(output.header.contains(
+            "bool cat048_item010_id(const cat048_record *record, char *buf, size_t cap);"
+        ));
+
+        let shims = output.shims.to_string();
+        assert!(shims.contains("pub mod cat048_ffi"));
+        assert!(shims.contains("pub unsafe extern \"C\" fn cat048_item010_sac"));
+    }
+
+    #[test]
+    fn test_repetitive_item_getters_take_index() {
+        let output = run_backend(
+            FfiBackend::default(),
+            &lowered(LoweredItemKind::Repetitive {
+                element_type_name: format_ident!("Item010Element"),
+                count: 2,
+                storage: RepetitiveStorage::Vec,
+                fields: vec![field("azimuth", FieldType::Primitive(format_ident!("u16")))],
+                decode_ops: vec![],
+                encode_ops: vec![],
+            }),
+        );
+
+        assert!(output.header.contains("size_t cat048_item010_count(const cat048_record *record);"));
+        assert!(output.header.contains(
+            "bool cat048_item010_azimuth(const cat048_record *record, size_t index, uint16_t *out);"
+        ));
+    }
+
+    #[test]
+    fn test_wide_fields_are_skipped() {
+        let output = run_backend(
+            FfiBackend::default(),
+            &lowered(LoweredItemKind::Simple {
+                is_explicit: false,
+                byte_size: 16,
+                fields: vec![field("wide", FieldType::Primitive(format_ident!("u128")))],
+                decode_ops: vec![],
+                encode_ops: vec![],
+            }),
+        );
+
+        assert!(output.header.contains("/* cat048_item010_wide: no C type"));
+        assert!(!output.shims.to_string().contains("fn cat048_item010_wide"));
+    }
+
+    #[test]
+    fn test_feature_gated_item_shims() {
+        let mut lowered = lowered(LoweredItemKind::Simple {
+            is_explicit: false,
+            byte_size: 1,
+            fields: vec![field("sac", FieldType::Primitive(format_ident!("u8")))],
+            decode_ops: vec![],
+            encode_ops: vec![],
+        });
+        lowered.items[0].feature = Some("cat048_bds".to_string());
+        let output = run_backend(FfiBackend::default(), &lowered);
+
+        assert!(output.header.contains("/* Item010 (feature \"cat048_bds\") */"));
+        let shims = output.shims.to_string();
+        assert!(shims.contains("# [cfg (feature = \"cat048_bds\")] # [unsafe (no_mangle)] pub unsafe extern \"C\" fn cat048_has_item010"));
+        assert!(shims.contains("# [cfg (feature = \"cat048_bds\")] # [unsafe (no_mangle)] pub unsafe extern \"C\" fn cat048_item010_sac"));
+    }
+}
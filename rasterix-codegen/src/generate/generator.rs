@@ -1,8 +1,23 @@
 use proc_macro2::TokenStream;
-use quote::quote;
-
-use crate::transform::{lowerer, ir::IR, lower_ir::LoweredIR};
-use super::{item_gen::generate_item, record_gen::generate_record, datablock_gen::generate_datablock};
+use quote::{format_ident, quote};
+
+use crate::transform::{
+    lowerer, ir::IR, lower_ir::{LoweredIR, LoweredItem, LoweredRecord, RecordEntry}, storage::uses_heapless,
+};
+use super::{
+    backend::{run_backend, CodegenBackend},
+    conformance_gen::generate_item_conformance,
+    datablock_gen::generate_datablock,
+    example_gen::generate_item_examples,
+    item_gen::{
+        generate_item_decode, generate_item_encode, generate_item_layout, generate_item_names, generate_item_structs,
+        generate_item_validate,
+    },
+    lazy_gen::generate_record_lazy,
+    record_gen::generate_record,
+    size_gen::{generate_item_size, generate_record_size},
+    value_gen::{generate_item_values, generate_record_values},
+};
 
 /// Main code generation orchestrator.
 ///
@@ -20,44 +35,130 @@ use super::{item_gen::generate_item, record_gen::generate_record, datablock_gen:
 ///
 /// # Returns
 ///
-/// A TokenStream containing the complete generated module.
-pub fn generate(ir: &IR) -> TokenStream {
-    let lowered = lowerer::lower(ir);
-    generate_from_lowered(&lowered)
+/// A TokenStream containing the complete generated module, or the error of
+/// lowering the IR.
+pub fn generate(ir: &IR) -> Result<TokenStream, String> {
+    let lowered = lowerer::lower(ir)?;
+    Ok(generate_from_lowered(&lowered))
 }
 
-fn generate_from_lowered(lowered: &LoweredIR) -> TokenStream {
-    let module_name = &lowered.module_name;
+/// Produces the complete generated module from an already lowered IR.
+pub fn generate_from_lowered(lowered: &LoweredIR) -> TokenStream {
+    run_backend(RustBackend::default(), lowered)
+}
 
-    let record = generate_record(&lowered.record);
-    let datablock = generate_datablock(lowered);
+/// The default backend: Rust types with `rasterix-core` decode/encode
+/// implementations.
+///
+/// Items tagged with a cargo feature are generated in a private module
+/// under `#[cfg(feature = "...")]`, re-exported into the category module.
+/// Repetitive items stored in a `heapless::Vec` use the `heapless` crate
+/// re-exported by `rasterix-core`.
+#[derive(Debug, Default)]
+pub struct RustBackend {
+    record: TokenStream,
+    /// Record entries, locating each item in the FSPEC.
+    entries: Vec<RecordEntry>,
+    items: Vec<TokenStream>,
+    /// Code of the item being emitted.
+    current: Vec<TokenStream>,
+}
 
-    let items: Vec<_> = lowered.items.iter()
-        .map(generate_item)
-        .collect();
+impl CodegenBackend for RustBackend {
+    type Output = TokenStream;
 
-    quote! {
-        // AUTO-GENERATED CODE — DO NOT EDIT
-        //
-        // This file was automatically generated from ASTERIX XML definitions.
-        // Manual modifications will be lost on regeneration.
+    fn emit_record(&mut self, record: &LoweredRecord) {
+        let record_size = generate_record_size(record);
+        let record_lazy = generate_record_lazy(record);
+        let record_values = generate_record_values(record);
+        self.entries = record.entries.clone();
+        let record = generate_record(record);
+        self.record = quote! {
+            #record
 
-        #![allow(unused_imports)]
-        #![allow(dead_code)]
+            #record_size
 
-        use rasterix::rcore::{BitReader, BitWriter, DecodeError, Fspec, Decode, Encode};
-        use std::io::{Read, Write};
+            #record_lazy
 
-        pub mod #module_name {
-            use super::*;
-            // Category record
-            #record
+            #record_values
+        };
+    }
 
-            // Data block
-            #datablock
+    fn emit_struct(&mut self, item: &LoweredItem) {
+        self.current.push(generate_item_structs(item));
+        if let Some(entry) = self.entries.iter().find(|entry| entry.type_name == item.name) {
+            self.current.push(generate_item_layout(item, entry));
+        }
+        self.current.push(generate_item_validate(item));
+        self.current.push(generate_item_conformance(item));
+        self.current.push(generate_item_values(item));
+        self.current.push(generate_item_names(item));
+    }
+
+    fn emit_decode(&mut self, item: &LoweredItem) {
+        self.current.push(generate_item_decode(item));
+        self.current.push(generate_item_size(item));
+    }
+
+    fn emit_encode(&mut self, item: &LoweredItem) {
+        self.current.push(generate_item_encode(item));
+        self.current.push(generate_item_examples(item));
+
+        let code = std::mem::take(&mut self.current);
+        match &item.feature {
+            Some(feature) => {
+                let module = format_ident!("{}", item.name.to_string().to_lowercase());
+                self.items.push(quote! {
+                    #[cfg(feature = #feature)]
+                    mod #module {
+                        use super::*;
+
+                        #(#code)*
+                    }
+
+                    #[cfg(feature = #feature)]
+                    pub use #module::*;
+                });
+            }
+            None => self.items.extend(code),
+        }
+    }
 
-            // Data items
-            #(#items)*
+    fn finalize(self, lowered: &LoweredIR) -> TokenStream {
+        let module_name = &lowered.module_name;
+        let record = self.record;
+        let datablock = generate_datablock(lowered);
+        let items = self.items;
+        let heapless = uses_heapless(lowered).then(|| quote! { use rasterix::rcore::heapless; });
+
+        quote! {
+            // AUTO-GENERATED CODE — DO NOT EDIT
+            //
+            // This file was automatically generated from ASTERIX XML definitions.
+            // Manual modifications will be lost on regeneration.
+
+            #![allow(unused_imports)]
+            #![allow(dead_code)]
+            #![allow(deprecated)]
+
+            use rasterix::rcore::{
+                BitReader, BitWrite, BitWriter, CategoryRecord, DataItem, DecodeContext, DecodeError,
+                Fspec, Decode, DecodeFromRead, DecodeInto, Encode, EncodeToWrite, Validate, ValidationError, Conformance, ConformanceIssue, FromValue, ToValue, altitude, conformance, geo, icao, policy, trace, validate, value, velocity,
+            };
+            use std::io::{Read, Write};
+            #heapless
+
+            pub mod #module_name {
+                use super::*;
+                // Category record
+                #record
+
+                // Data block
+                #datablock
+
+                // Data items
+                #(#items)*
+            }
         }
     }
 }
@@ -75,7 +176,12 @@ mod tests {
                 items: vec![
                     IRItem {
                         id: 10,
-                        frn: 0,
+                        frn: 1,
+                        title: None,
+                        short: None,
+                        reserved: false,
+                        mandatory: false,
+                        examples: vec![],
                         layout: IRLayout::Fixed {
                             bytes: 2,
                             elements: vec![
@@ -83,11 +189,25 @@ mod tests {
                                     name: "sac".to_string(),
                                     bits: 8,
                                     is_string: false,
+                                    revision: IRRevision::default(),
+                                    description: None,
+                                    constraints: IRConstraints::default(),
+                                    value_type: None,
+                                    little_endian: false,
+                                    is_float: false,
+                                    is_bytes: false,
                                 },
                                 IRElement::Field {
                                     name: "sic".to_string(),
                                     bits: 8,
                                     is_string: false,
+                                    revision: IRRevision::default(),
+                                    description: None,
+                                    constraints: IRConstraints::default(),
+                                    value_type: None,
+                                    little_endian: false,
+                                    is_float: false,
+                                    is_bytes: false,
                                 },
                             ],
                         },
@@ -96,7 +216,7 @@ mod tests {
             },
         };
 
-        let result = generate(&ir);
+        let result = generate(&ir).unwrap();
         let code = result.to_string();
 
         // Check for imports (quote! adds spaces around :: and braces)
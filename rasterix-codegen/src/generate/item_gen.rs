@@ -1,12 +1,15 @@
 use proc_macro2::TokenStream;
 use quote::quote;
 
-use crate::transform::lower_ir::{LoweredItem, LoweredItemKind};
+use crate::transform::lower_ir::{
+    FieldDescriptor, FieldType, LoweredItem, LoweredItemKind, LoweredPart, LoweredSubItemKind, RecordEntry,
+};
 use super::{
     struct_gen::*,
     decode_gen::*,
     encode_gen::*,
     enum_gen::*,
+    utils::frn_to_fspec_position,
 };
 
 /// Generates all code for a single ASTERIX item from its lowered representation.
@@ -17,68 +20,311 @@ use super::{
 /// - Decode implementation
 /// - Encode implementation
 pub fn generate_item(item: &LoweredItem) -> TokenStream {
-    let item_name = &item.name;
+    let struct_defs = generate_item_structs(item);
+    let decode_impl = generate_item_decode(item);
+    let encode_impl = generate_item_encode(item);
 
-    let enum_defs: Vec<_> = item.enums.iter().map(generate_enum).collect();
+    quote! {
+        #struct_defs
+
+        #decode_impl
+
+        #encode_impl
+    }
+}
+
+/// Generates the [`DataItem`](rasterix_core::DataItem) impl of an item,
+/// locating it in the record through its `entry`.
+pub fn generate_item_layout(item: &LoweredItem, entry: &RecordEntry) -> TokenStream {
+    let item_name = &item.name;
+    let item_id = item.id;
+    let frn = entry.frn;
+    let (fspec_byte, fspec_bit) = frn_to_fspec_position(frn);
 
-    let (struct_def, decode_impl, encode_impl) = match &item.kind {
-        LoweredItemKind::Simple { fields, decode_ops, encode_ops, .. } => {
-            let struct_def = generate_struct(item_name, fields);
-            let decode_impl = generate_simple_decode(item_name, decode_ops, fields);
-            let encode_impl = generate_simple_encode(item_name, encode_ops);
-            (struct_def, decode_impl, encode_impl)
+    let descriptions = field_descriptions(item);
+    let describe = (!descriptions.is_empty()).then(|| {
+        let arms = descriptions.iter().map(|(path, description)| quote! { #path => Some(#description) });
+        quote! {
+            fn describe(field: &str) -> Option<&'static str> {
+                match field {
+                    #(#arms,)*
+                    _ => None,
+                }
+            }
         }
+    });
 
-        LoweredItemKind::Extended { parts } => {
-            let struct_def = generate_extended_structs(item_name, parts);
-            let decode_impl = generate_extended_decode(item_name, parts);
-            let encode_impl = generate_extended_encode(item_name, parts);
-            (struct_def, decode_impl, encode_impl)
+    quote! {
+        impl DataItem for #item_name {
+            const ITEM_ID: u16 = #item_id;
+            const FRN: usize = #frn;
+            const FSPEC_BYTE: usize = #fspec_byte;
+            const FSPEC_BIT: u8 = #fspec_bit;
+            #describe
         }
+    }
+}
 
-        LoweredItemKind::Repetitive { element_type_name, count, fields, decode_ops, encode_ops } => {
-            let struct_def = generate_repetitive_struct(item_name, element_type_name, fields);
-            let decode_impl = generate_repetitive_decode(item_name, *count, element_type_name, decode_ops, fields);
-            let encode_impl = generate_repetitive_encode(item_name, element_type_name, encode_ops);
-            (struct_def, decode_impl, encode_impl)
+/// Returns the path and description of every described field of an item.
+///
+/// Fields of parts and sub-items are prefixed with the name of their part
+/// or sub-item (`part0.sac`), while the fields of repetitive elements are
+/// named alone.
+fn field_descriptions(item: &LoweredItem) -> Vec<(String, String)> {
+    fn described(prefix: &str, fields: &[FieldDescriptor], out: &mut Vec<(String, String)>) {
+        for field in fields {
+            if let Some(description) = &field.description {
+                out.push((format!("{}{}", prefix, field.name), description.clone()));
+            }
         }
+    }
+    fn parts_described(prefix: &str, parts: &[LoweredPart], out: &mut Vec<(String, String)>) {
+        for part in parts {
+            described(&format!("{}{}.", prefix, part.field_name), &part.fields, out);
+        }
+    }
 
+    let mut out = Vec::new();
+    match &item.kind {
+        LoweredItemKind::Simple { fields, .. } | LoweredItemKind::Repetitive { fields, .. } => {
+            described("", fields, &mut out);
+        }
+        LoweredItemKind::Extended { parts } => parts_described("", parts, &mut out),
         LoweredItemKind::Compound { sub_items } => {
-            // Collect enums from sub-items
-            let sub_enum_defs: Vec<_> = sub_items.iter()
-                .flat_map(|sub| sub.enums.iter().map(generate_enum))
-                .collect();
+            for sub in sub_items {
+                let prefix = format!("{}.", sub.field_name);
+                match &sub.kind {
+                    LoweredSubItemKind::Simple { fields, .. }
+                    | LoweredSubItemKind::Repetitive { fields, .. } => described(&prefix, fields, &mut out),
+                    LoweredSubItemKind::Extended { parts } => parts_described(&prefix, parts, &mut out),
+                }
+            }
+        }
+    }
+    out
+}
 
-            let struct_def = generate_compound_structs(item_name, sub_items);
-            let sub_decode_impls = generate_compound_sub_decodes(sub_items);
-            let sub_encode_impls = generate_compound_sub_encodes(sub_items);
-            let decode_impl = generate_compound_decode(item_name, sub_items);
-            let encode_impl = generate_compound_encode(item_name, sub_items);
+/// Generates the [`Validate`](rasterix_core::Validate) impl of an item,
+/// checking the constraints of its fields.
+///
+/// Fields are named like in [`DataItem::describe`](rasterix_core::DataItem::describe),
+/// except the fields of repetitive elements, which follow `items.<index>.`.
+/// Items without constrained fields get the default, empty check.
+pub fn generate_item_validate(item: &LoweredItem) -> TokenStream {
+    let item_name = &item.name;
+    let this = quote! { self };
+    let checks = match &item.kind {
+        LoweredItemKind::Simple { fields, .. } => field_checks(&this, fields),
+        LoweredItemKind::Repetitive { fields, .. } => element_checks(&this, fields),
+        LoweredItemKind::Extended { parts } => part_checks(&this, parts),
+        LoweredItemKind::Compound { sub_items } => sub_items
+            .iter()
+            .filter_map(|sub| {
+                let sub_value = quote! { sub };
+                let checks = match &sub.kind {
+                    LoweredSubItemKind::Simple { fields, .. } => field_checks(&sub_value, fields),
+                    LoweredSubItemKind::Repetitive { fields, .. } => element_checks(&sub_value, fields),
+                    LoweredSubItemKind::Extended { parts } => part_checks(&sub_value, parts),
+                }?;
+                let field_name = &sub.field_name;
+                let prefix = format!("{{}}{}.", field_name);
+                Some(quote! {
+                    if let Some(sub) = &self.#field_name {
+                        let path: &str = &format!(#prefix, path);
+                        #checks
+                    };
+                })
+            })
+            .reduce(|a, b| quote! { #a #b }),
+    };
 
-            let combined_struct = quote! {
-                #(#sub_enum_defs)*
-                #struct_def
-            };
-            let combined_decode = quote! {
-                #sub_decode_impls
-                #decode_impl
+    match checks {
+        Some(checks) => quote! {
+            impl Validate for #item_name {
+                fn check(&self, path: &str, errors: &mut Vec<ValidationError>) {
+                    #checks
+                }
+            }
+        },
+        None => quote! { impl Validate for #item_name {} },
+    }
+}
+
+/// Checks of the constrained `fields` of `value`, or `None` if there are
+/// none.
+fn field_checks(value: &TokenStream, fields: &[FieldDescriptor]) -> Option<TokenStream> {
+    let checks: Vec<_> = fields
+        .iter()
+        .filter(|field| !field.constraints.is_empty())
+        .map(|field| {
+            let name = &field.name;
+            let field_name = name.to_string();
+            let constraints = &field.constraints;
+            let constraints = [
+                constraints.min.map(|min| quote! { validate::Constraint::Min(#min) }),
+                constraints.max.map(|max| quote! { validate::Constraint::Max(#max) }),
+                constraints.multiple_of.map(|n| quote! { validate::Constraint::MultipleOf(#n) }),
+            ]
+            .into_iter()
+            .flatten();
+            let check = quote! {
+                validate::check_field(errors, path, #field_name, field as u64, &[#(#constraints),*]);
             };
-            let combined_encode = quote! {
-                #sub_encode_impls
-                #encode_impl
+            match field.type_tokens {
+                FieldType::OptionalPrimitive(_) => quote! {
+                    if let Some(field) = #value.#name { #check };
+                },
+                _ => quote! {
+                    let field = #value.#name;
+                    #check
+                },
+            }
+        })
+        .collect();
+    (!checks.is_empty()).then(|| quote! { #(#checks)* })
+}
+
+/// Checks of the constrained fields of each element of the repetitive
+/// `value`.
+fn element_checks(value: &TokenStream, fields: &[FieldDescriptor]) -> Option<TokenStream> {
+    let checks = field_checks(&quote! { element }, fields)?;
+    Some(quote! {
+        for (i, element) in #value.items.iter().enumerate() {
+            let path: &str = &format!("{}items.{}.", path, i);
+            #checks
+        }
+    })
+}
+
+/// Checks of the constrained fields of the parts of the extended `value`
+/// present.
+fn part_checks(value: &TokenStream, parts: &[LoweredPart]) -> Option<TokenStream> {
+    let checks: Vec<_> = parts
+        .iter()
+        .filter_map(|part| {
+            let checks = field_checks(&quote! { part }, &part.fields)?;
+            let field_name = &part.field_name;
+            let prefix = format!("{{}}{}.", field_name);
+            let checks = quote! {
+                let path: &str = &format!(#prefix, path);
+                #checks
             };
-            (combined_struct, combined_decode, combined_encode)
+            Some(if part.is_required {
+                quote! { { let part = &#value.#field_name; #checks } }
+            } else {
+                quote! { if let Some(part) = &#value.#field_name { #checks } }
+            })
+        })
+        .collect();
+    (!checks.is_empty()).then(|| quote! { #(#checks)* })
+}
+
+/// Generates the type alias and name constants of an item defined with a
+/// `name` or `short` attribute, or nothing.
+pub fn generate_item_names(item: &LoweredItem) -> TokenStream {
+    let item_name = &item.name;
+    let alias = item.alias.as_ref().map(|alias| {
+        let doc = format!("`{}`: {}.", item_name, item.title.as_deref().unwrap_or_default());
+        quote! {
+            #[doc = #doc]
+            pub type #alias = #item_name;
+        }
+    });
+    let title = item.title.as_ref().map(|title| quote! {
+        /// Title of the item in its category.
+        pub const TITLE: &'static str = #title;
+    });
+    let short_name = item.short_name.as_ref().map(|short_name| quote! {
+        /// Short symbolic name of the item.
+        pub const SHORT_NAME: &'static str = #short_name;
+    });
+    if title.is_none() && short_name.is_none() {
+        return quote! {};
+    }
+
+    quote! {
+        #alias
+
+        impl #item_name {
+            #title
+            #short_name
+        }
+    }
+}
+
+/// Generates the enum and struct definitions of an item.
+pub fn generate_item_structs(item: &LoweredItem) -> TokenStream {
+    let item_name = &item.name;
+
+    // For compounds, sub-item enums are already part of `item.enums`.
+    let enum_defs: Vec<_> = item.enums.iter().map(generate_enum).collect();
+
+    let hashable = item.is_hashable;
+    let struct_def = match &item.kind {
+        LoweredItemKind::Simple { byte_size, .. } if item.is_reserved => {
+            generate_reserved_struct(item_name, *byte_size)
+        }
+        LoweredItemKind::Simple { fields, .. } => generate_struct(item_name, fields, hashable),
+        LoweredItemKind::Extended { parts } => generate_extended_structs(item_name, parts, hashable),
+        LoweredItemKind::Repetitive { element_type_name, count, storage, fields, .. } => {
+            generate_repetitive_struct(item_name, element_type_name, *count, *storage, fields, hashable)
+        }
+        LoweredItemKind::Compound { sub_items } => {
+            generate_compound_structs(item_name, sub_items, hashable)
         }
     };
 
+    let derived_methods = generate_derived_methods(&item.derived);
+
     quote! {
         #(#enum_defs)*
 
         #struct_def
+        #derived_methods
+    }
+}
 
-        #decode_impl
+/// Generates the decode implementations of an item.
+pub fn generate_item_decode(item: &LoweredItem) -> TokenStream {
+    let item_name = &item.name;
 
-        #encode_impl
+    match &item.kind {
+        LoweredItemKind::Simple { fields, decode_ops, .. } => {
+            generate_simple_decode(item_name, decode_ops, fields)
+        }
+        LoweredItemKind::Extended { parts } => generate_extended_decode(item_name, parts),
+        LoweredItemKind::Repetitive { element_type_name, count, storage, fields, decode_ops, .. } => {
+            generate_repetitive_decode(item_name, *count, *storage, element_type_name, decode_ops, fields)
+        }
+        LoweredItemKind::Compound { sub_items } => {
+            let sub_decode_impls = generate_compound_sub_decodes(sub_items);
+            let decode_impl = generate_compound_decode(item_name, sub_items);
+            quote! {
+                #sub_decode_impls
+                #decode_impl
+            }
+        }
+    }
+}
+
+/// Generates the encode implementations of an item.
+pub fn generate_item_encode(item: &LoweredItem) -> TokenStream {
+    let item_name = &item.name;
+
+    match &item.kind {
+        LoweredItemKind::Simple { encode_ops, .. } => generate_simple_encode(item_name, encode_ops),
+        LoweredItemKind::Extended { parts } => generate_extended_encode(item_name, parts),
+        LoweredItemKind::Repetitive { element_type_name, count, encode_ops, .. } => {
+            generate_repetitive_encode(item_name, element_type_name, *count, encode_ops)
+        }
+        LoweredItemKind::Compound { sub_items } => {
+            let sub_encode_impls = generate_compound_sub_encodes(sub_items);
+            let encode_impl = generate_compound_encode(item_name, sub_items);
+            quote! {
+                #sub_encode_impls
+                #encode_impl
+            }
+        }
     }
 }
 
@@ -91,8 +337,17 @@ mod tests {
     #[test]
     fn test_generate_simple_item() {
         let item = LoweredItem {
+            id: 10,
             name: format_ident!("Item010"),
+            title: None,
+            short_name: None,
+            is_reserved: false,
+            alias: None,
             enums: vec![],
+            feature: None,
+            is_hashable: true,
+            derived: vec![],
+            examples: vec![],
             kind: LoweredItemKind::Simple {
                 is_explicit: false,
                 byte_size: 2,
@@ -100,19 +355,27 @@ mod tests {
                     FieldDescriptor {
                         name: format_ident!("sac"),
                         type_tokens: FieldType::Primitive(format_ident!("u8")),
+                        revision: FieldRevision::default(),
+                        description: None,
+                        constraints: FieldConstraints::default(),
+                        typed: None,
                     },
                     FieldDescriptor {
                         name: format_ident!("sic"),
                         type_tokens: FieldType::Primitive(format_ident!("u8")),
+                        revision: FieldRevision::default(),
+                        description: None,
+                        constraints: FieldConstraints::default(),
+                        typed: None,
                     },
                 ],
                 decode_ops: vec![
-                    DecodeOp::ReadField { name: format_ident!("sac"), bits: 8, rust_type: format_ident!("u8") },
-                    DecodeOp::ReadField { name: format_ident!("sic"), bits: 8, rust_type: format_ident!("u8") },
+                    DecodeOp::ReadField { name: format_ident!("sac"), bits: 8, rust_type: format_ident!("u8"), little_endian: false },
+                    DecodeOp::ReadField { name: format_ident!("sic"), bits: 8, rust_type: format_ident!("u8"), little_endian: false },
                 ],
                 encode_ops: vec![
-                    EncodeOp::WriteField { name: format_ident!("sac"), bits: 8 },
-                    EncodeOp::WriteField { name: format_ident!("sic"), bits: 8 },
+                    EncodeOp::WriteField { name: format_ident!("sac"), bits: 8, little_endian: false, float: false },
+                    EncodeOp::WriteField { name: format_ident!("sic"), bits: 8, little_endian: false, float: false },
                 ],
             },
         };
@@ -0,0 +1,255 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+
+use crate::transform::lower_ir::{
+    FieldDescriptor, LoweredEnum, LoweredItem, LoweredItemKind, LoweredPart, LoweredRecord,
+    LoweredSubItem, LoweredSubItemKind, UnknownValues, ValueType,
+};
+
+/// Generates `ToJson` for a struct with the given fields.
+///
+/// Fields keep their Rust names; absent EPB values, parts and sub-items are
+/// written as `null`.
+fn generate_struct_json<'a>(name: &Ident, fields: impl IntoIterator<Item = &'a Ident>) -> TokenStream {
+    let field_writes = fields.into_iter().map(|fname| {
+        let key = fname.to_string();
+        quote! { obj.field(#key, &self.#fname); }
+    });
+    json_object_impl(name, field_writes)
+}
+
+/// Generates `ToJson` for a struct of fields, writing ICAO addresses as hex
+/// strings through their typed accessor rather than as numbers.
+fn generate_fields_json(name: &Ident, fields: &[FieldDescriptor]) -> TokenStream {
+    let field_writes = fields.iter().map(|field| {
+        let fname = &field.name;
+        let key = fname.to_string();
+        match field.typed {
+            Some(typed) if typed.value_type == ValueType::Icao => quote! { obj.field(#key, &self.#fname()); },
+            _ => quote! { obj.field(#key, &self.#fname); },
+        }
+    });
+    json_object_impl(name, field_writes)
+}
+
+fn json_object_impl(name: &Ident, field_writes: impl Iterator<Item = TokenStream>) -> TokenStream {
+    quote! {
+        impl ToJson for #name {
+            fn write_json(&self, out: &mut String) {
+                let mut obj = JsonObject::new(out);
+                #(#field_writes)*
+                obj.finish();
+            }
+        }
+    }
+}
+
+/// Generates `ToJson` for an enum: the variant name, or the raw value for
+/// `Unknown`.
+fn generate_enum_json(lowered: &LoweredEnum) -> TokenStream {
+    let enum_name = &lowered.name;
+    let arms: Vec<_> = lowered.variants.iter().map(|v| {
+        let vname = &v.name;
+        let key = vname.to_string();
+        quote! { #enum_name::#vname => rasterix::rcore::json::write_string(out, #key) }
+    }).collect();
+
+    let unknown_arm = (lowered.unknown == UnknownValues::Preserve)
+        .then(|| quote! { #enum_name::Unknown(value) => value.write_json(out), });
+
+    quote! {
+        impl ToJson for #enum_name {
+            fn write_json(&self, out: &mut String) {
+                match self {
+                    #(#arms,)*
+                    #unknown_arm
+                }
+            }
+        }
+    }
+}
+
+/// Extended items are objects of their parts, with their unknown extension
+/// octets under `extensions` if there are any.
+fn generate_extended_json(name: &Ident, parts: &[LoweredPart]) -> TokenStream {
+    let part_impls: Vec<_> = parts.iter()
+        .map(|p| generate_fields_json(&p.struct_name, &p.fields))
+        .collect();
+    let field_writes = parts.iter().map(|p| {
+        let fname = &p.field_name;
+        let key = fname.to_string();
+        quote! { obj.field(#key, &self.#fname); }
+    });
+    let extensions_write = quote! {
+        if !self.extensions.is_empty() {
+            obj.field("extensions", &self.extensions);
+        }
+    };
+    let main_impl = json_object_impl(name, field_writes.chain(std::iter::once(extensions_write)));
+
+    quote! {
+        #(#part_impls)*
+        #main_impl
+    }
+}
+
+/// Repetitive items are written as a plain array of their elements.
+fn generate_repetitive_json(name: &Ident, element_type_name: &Ident, fields: &[FieldDescriptor]) -> TokenStream {
+    let element_impl = generate_fields_json(element_type_name, fields);
+
+    quote! {
+        #element_impl
+
+        impl ToJson for #name {
+            fn write_json(&self, out: &mut String) {
+                self.items.as_slice().write_json(out);
+            }
+        }
+    }
+}
+
+fn generate_sub_item_json(sub: &LoweredSubItem) -> TokenStream {
+    match &sub.kind {
+        LoweredSubItemKind::Simple { fields, .. } => {
+            generate_fields_json(&sub.struct_name, fields)
+        }
+        LoweredSubItemKind::Extended { parts } => generate_extended_json(&sub.struct_name, parts),
+        LoweredSubItemKind::Repetitive { element_type_name, fields, .. } => {
+            generate_repetitive_json(&sub.struct_name, element_type_name, fields)
+        }
+    }
+}
+
+/// Generates `ToJson` for an item, its enums and all nested structs.
+pub fn generate_item_json(item: &LoweredItem) -> TokenStream {
+    let item_name = &item.name;
+    let enum_impls: Vec<_> = item.enums.iter().map(generate_enum_json).collect();
+
+    let struct_impls = match &item.kind {
+        LoweredItemKind::Simple { fields, .. } => generate_fields_json(item_name, fields),
+        LoweredItemKind::Extended { parts } => generate_extended_json(item_name, parts),
+        LoweredItemKind::Repetitive { element_type_name, fields, .. } => {
+            generate_repetitive_json(item_name, element_type_name, fields)
+        }
+        LoweredItemKind::Compound { sub_items } => {
+            let sub_impls: Vec<_> = sub_items.iter().map(generate_sub_item_json).collect();
+            let main_impl = generate_struct_json(item_name, sub_items.iter().map(|s| &s.field_name));
+            quote! {
+                #(#sub_impls)*
+                #main_impl
+            }
+        }
+    };
+
+    quote! {
+        #(#enum_impls)*
+        #struct_impls
+    }
+}
+
+/// Generates `ToJson` for the record, leaving out absent items, and for
+/// the data block.
+pub fn generate_record_json(record: &LoweredRecord) -> TokenStream {
+    let record_name = &record.name;
+    let item_writes: Vec<_> = record.entries.iter().map(|e| {
+        let fname = &e.field_name;
+        let key = fname.to_string();
+        quote! {
+            if let Some(item) = &self.#fname {
+                obj.field(#key, item);
+            };
+        }
+    }).collect();
+
+    quote! {
+        impl ToJson for #record_name {
+            fn write_json(&self, out: &mut String) {
+                let mut obj = JsonObject::new(out);
+                #(#item_writes)*
+                obj.finish();
+            }
+        }
+
+        impl ToJson for DataBlock {
+            fn write_json(&self, out: &mut String) {
+                let mut obj = JsonObject::new(out);
+                obj.field("category", &DataBlock::CATEGORY);
+                obj.field("records", &self.records);
+                obj.finish();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::format_ident;
+
+    use crate::transform::lower_ir::{
+        FieldConstraints, FieldRevision, FieldType, LoweredEnumVariant, RecordEntry, TypedValue,
+    };
+
+    #[test]
+    fn test_struct_json_writes_every_field() {
+        let fields = [format_ident!("sac"), format_ident!("sic")];
+
+        let code = generate_struct_json(&format_ident!("Item010"), &fields).to_string();
+        assert!(code.contains("impl ToJson for Item010"));
+        assert!(code.contains("obj . field (\"sac\" , & self . sac)"));
+        assert!(code.contains("obj . field (\"sic\" , & self . sic)"));
+    }
+
+    #[test]
+    fn test_fields_json_writes_icao_addresses_through_accessor() {
+        let field = |name: &str, typed: Option<TypedValue>| FieldDescriptor {
+            name: format_ident!("{}", name),
+            type_tokens: FieldType::Primitive(format_ident!("u32")),
+            revision: FieldRevision::default(),
+            description: None,
+            constraints: FieldConstraints::default(),
+            typed,
+        };
+        let fields = [
+            field("address", Some(TypedValue { value_type: ValueType::Icao, bits: 24 })),
+            field("level", Some(TypedValue { value_type: ValueType::FlightLevel, bits: 16 })),
+        ];
+
+        let code = generate_fields_json(&format_ident!("Item220"), &fields).to_string();
+        assert!(code.contains("obj . field (\"address\" , & self . address ())"));
+        assert!(code.contains("obj . field (\"level\" , & self . level)"));
+    }
+
+    #[test]
+    fn test_enum_json_uses_variant_names() {
+        let lowered = LoweredEnum {
+            name: format_ident!("TargetType"),
+            variants: vec![LoweredEnumVariant { name: format_ident!("Psr"), value: 1, alias: None }],
+            unknown: UnknownValues::Preserve,
+        };
+
+        let code = generate_enum_json(&lowered).to_string();
+        assert!(code.contains("TargetType :: Psr => rasterix :: rcore :: json :: write_string (out , \"Psr\")"));
+        assert!(code.contains("TargetType :: Unknown (value) => value . write_json (out)"));
+    }
+
+    #[test]
+    fn test_record_json_skips_absent_items() {
+        let record = LoweredRecord {
+            name: format_ident!("Record"),
+            entries: vec![RecordEntry {
+                field_name: format_ident!("item010"),
+                type_name: format_ident!("Item010"),
+                frn: 1,
+                feature: None,
+                is_mandatory: false,
+            }],
+            is_hashable: true,
+        };
+
+        let code = generate_record_json(&record).to_string();
+        assert!(code.contains("if let Some (item) = & self . item010"));
+        assert!(code.contains("impl ToJson for DataBlock"));
+        assert!(code.contains("obj . field (\"category\" , & DataBlock :: CATEGORY)"));
+    }
+}
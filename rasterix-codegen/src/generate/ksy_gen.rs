@@ -0,0 +1,364 @@
+use std::fmt::Write;
+
+use proc_macro2::Ident;
+
+use crate::transform::lower_ir::{
+    DecodeOp, LoweredEnum, LoweredIR, LoweredItem, LoweredItemKind, LoweredPart, LoweredRecord,
+    LoweredSubItem, LoweredSubItemKind,
+};
+use super::backend::CodegenBackend;
+use super::utils::{frn_to_fspec_position, is_float_type, to_snake_case};
+
+/// Backend emitting a Kaitai Struct (`.ksy`) description of a category.
+///
+/// The description follows the wire layout the generated decoders read: a
+/// data block with its header, records selected by the FSPEC, and one type
+/// per item, part, compound sub-item and repetitive element.  It can be
+/// loaded into the Kaitai IDE to inspect captures, or compiled with
+/// `kaitai-struct-compiler` to cross-check definitions against decoders in
+/// other languages.
+///
+/// EPB-guarded values are always present on the wire, so they are described
+/// as a `<field>_valid` flag followed by the value.
+#[derive(Debug, Default)]
+pub struct KsyBackend {
+    types: String,
+    enums: Vec<LoweredEnum>,
+}
+
+impl CodegenBackend for KsyBackend {
+    type Output = String;
+
+    fn emit_record(&mut self, record: &LoweredRecord) {
+        let out = &mut self.types;
+        writeln!(out, "  block_body:").unwrap();
+        writeln!(out, "    seq:").unwrap();
+        writeln!(out, "      - id: records").unwrap();
+        writeln!(out, "        type: record").unwrap();
+        writeln!(out, "        repeat: eos").unwrap();
+
+        writeln!(out, "  record:").unwrap();
+        writeln!(out, "    seq:").unwrap();
+        write_fspec(out);
+        for entry in &record.entries {
+            writeln!(out, "      - id: {}", ksy_id(&entry.field_name)).unwrap();
+            writeln!(out, "        type: {}", ksy_id(&entry.type_name)).unwrap();
+            writeln!(out, "        if: {}", fspec_condition(entry.frn)).unwrap();
+        }
+    }
+
+    fn emit_struct(&mut self, item: &LoweredItem) {
+        for lowered in &item.enums {
+            if !self.enums.iter().any(|e| e.name == lowered.name) {
+                self.enums.push(lowered.clone());
+            }
+        }
+
+        let name = &item.name;
+        match &item.kind {
+            LoweredItemKind::Simple { decode_ops, .. } => {
+                write_type(&mut self.types, name, decode_ops);
+            }
+            LoweredItemKind::Extended { parts } => write_extended(&mut self.types, name, parts),
+            LoweredItemKind::Repetitive { element_type_name, count, decode_ops, .. } => {
+                write_repetitive(&mut self.types, name, element_type_name, *count, decode_ops);
+            }
+            LoweredItemKind::Compound { sub_items } => {
+                write_compound(&mut self.types, name, sub_items);
+            }
+        }
+    }
+
+    fn finalize(self, lowered: &LoweredIR) -> String {
+        let mut out = String::new();
+        writeln!(out, "# AUTO-GENERATED CODE - DO NOT EDIT").unwrap();
+        writeln!(out, "meta:").unwrap();
+        writeln!(out, "  id: {}", lowered.module_name).unwrap();
+        writeln!(out, "  title: ASTERIX category {:03}", lowered.category_id).unwrap();
+        writeln!(out, "  endian: be").unwrap();
+        writeln!(out, "  bit-endian: be").unwrap();
+        writeln!(out, "seq:").unwrap();
+        writeln!(out, "  - id: category").unwrap();
+        writeln!(out, "    type: u1").unwrap();
+        writeln!(out, "    valid: {}", lowered.category_id).unwrap();
+        writeln!(out, "  - id: len").unwrap();
+        writeln!(out, "    type: u2").unwrap();
+        writeln!(out, "  - id: body").unwrap();
+        writeln!(out, "    type: block_body").unwrap();
+        writeln!(out, "    size: len - 3").unwrap();
+        writeln!(out, "types:").unwrap();
+        out.push_str(&self.types);
+
+        if !self.enums.is_empty() {
+            writeln!(out, "enums:").unwrap();
+            for lowered in &self.enums {
+                writeln!(out, "  {}:", ksy_id(&lowered.name)).unwrap();
+                for variant in &lowered.variants {
+                    writeln!(out, "    {}: {}", variant.value, ksy_id(&variant.name)).unwrap();
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Converts a generated Rust name into a Kaitai identifier.
+fn ksy_id(name: &Ident) -> String {
+    let name = name.to_string();
+    to_snake_case(name.trim_start_matches("r#")).to_string()
+}
+
+/// Writes an FSPEC: bytes up to the first one with the FX bit clear.
+fn write_fspec(out: &mut String) {
+    writeln!(out, "      - id: fspec").unwrap();
+    writeln!(out, "        type: u1").unwrap();
+    writeln!(out, "        repeat: until").unwrap();
+    writeln!(out, "        repeat-until: (_ & 1) == 0").unwrap();
+}
+
+fn fspec_condition(frn: usize) -> String {
+    let (byte, bit) = frn_to_fspec_position(frn);
+    format!("fspec.size > {} and (fspec[{}] & {:#04x}) != 0", byte, byte, 0x80u8 >> bit)
+}
+
+/// Writes the seq entry of a number of type `rust_type`.
+///
+/// Floats and little-endian numbers of 2, 4 or 8 bytes are read as
+/// Kaitai's byte types, from the next byte boundary.  Kaitai has no other
+/// little-endian widths, so those are read as bits and only documented.
+fn write_number(out: &mut String, name: &Ident, bits: usize, rust_type: &Ident, little_endian: bool) {
+    writeln!(out, "      - id: {}", ksy_id(name)).unwrap();
+    if is_float_type(rust_type) {
+        let endian = if little_endian { "le" } else { "be" };
+        writeln!(out, "        type: f{}{}", bits / 8, endian).unwrap();
+        return;
+    }
+    match (little_endian, bits) {
+        (true, 16 | 32 | 64) => writeln!(out, "        type: u{}le", bits / 8).unwrap(),
+        (true, _) => {
+            writeln!(out, "        type: b{}", bits).unwrap();
+            writeln!(out, "        doc: little-endian, bytes not swapped").unwrap();
+        }
+        (false, _) => writeln!(out, "        type: b{}", bits).unwrap(),
+    }
+}
+
+/// Writes the seq entries for a flat list of decode ops.
+fn write_ops(out: &mut String, decode_ops: &[DecodeOp]) {
+    for op in decode_ops {
+        match op {
+            DecodeOp::ReadField { name, bits, rust_type, little_endian } => {
+                write_number(out, name, *bits, rust_type, *little_endian);
+            }
+            DecodeOp::ReadEnum { name, bits, enum_type } => {
+                writeln!(out, "      - id: {}", ksy_id(name)).unwrap();
+                writeln!(out, "        type: b{}", bits).unwrap();
+                writeln!(out, "        enum: {}", ksy_id(enum_type)).unwrap();
+            }
+            DecodeOp::ReadEpbField { name, bits, rust_type, little_endian } => {
+                write_epb_flag(out, name);
+                write_number(out, name, *bits, rust_type, *little_endian);
+            }
+            DecodeOp::ReadEpbEnum { name, bits, enum_type } => {
+                write_epb_flag(out, name);
+                writeln!(out, "      - id: {}", ksy_id(name)).unwrap();
+                writeln!(out, "        type: b{}", bits).unwrap();
+                writeln!(out, "        enum: {}", ksy_id(enum_type)).unwrap();
+            }
+            DecodeOp::ReadString { name, byte_len } => {
+                write_string(out, name, *byte_len);
+            }
+            DecodeOp::ReadEpbString { name, byte_len } => {
+                write_epb_flag(out, name);
+                write_string(out, name, *byte_len);
+            }
+            DecodeOp::ReadBytes { name, byte_len } => {
+                write_bytes(out, name, *byte_len);
+            }
+            DecodeOp::ReadEpbBytes { name, byte_len } => {
+                write_epb_flag(out, name);
+                write_bytes(out, name, *byte_len);
+            }
+            DecodeOp::SkipSpare { bits } => {
+                writeln!(out, "      - type: b{}", bits).unwrap();
+                writeln!(out, "        doc: spare").unwrap();
+            }
+            DecodeOp::Custom { tag, bits, .. } => {
+                writeln!(out, "      - type: b{}", bits).unwrap();
+                writeln!(out, "        doc: {}", tag).unwrap();
+            }
+            DecodeOp::ReadLengthByte { .. } => {
+                writeln!(out, "      - id: len").unwrap();
+                writeln!(out, "        type: u1").unwrap();
+            }
+        }
+    }
+}
+
+fn write_epb_flag(out: &mut String, name: &Ident) {
+    writeln!(out, "      - id: {}_valid", ksy_id(name)).unwrap();
+    writeln!(out, "        type: b1").unwrap();
+}
+
+fn write_string(out: &mut String, name: &Ident, byte_len: usize) {
+    writeln!(out, "      - id: {}", ksy_id(name)).unwrap();
+    writeln!(out, "        type: str").unwrap();
+    writeln!(out, "        size: {}", byte_len).unwrap();
+    writeln!(out, "        encoding: ASCII").unwrap();
+}
+
+fn write_bytes(out: &mut String, name: &Ident, byte_len: usize) {
+    writeln!(out, "      - id: {}", ksy_id(name)).unwrap();
+    writeln!(out, "        size: {}", byte_len).unwrap();
+}
+
+fn write_type(out: &mut String, name: &Ident, decode_ops: &[DecodeOp]) {
+    writeln!(out, "  {}:", ksy_id(name)).unwrap();
+    writeln!(out, "    seq:").unwrap();
+    write_ops(out, decode_ops);
+}
+
+/// Every part but the last is followed by an FX bit; a part is present
+/// when all preceding FX bits are set.
+fn write_extended(out: &mut String, name: &Ident, parts: &[LoweredPart]) {
+    writeln!(out, "  {}:", ksy_id(name)).unwrap();
+    writeln!(out, "    seq:").unwrap();
+    let mut condition: Vec<String> = Vec::new();
+    for part in parts {
+        let part_id = ksy_id(&part.field_name);
+        writeln!(out, "      - id: {}", part_id).unwrap();
+        writeln!(out, "        type: {}", ksy_id(&part.struct_name)).unwrap();
+        if !condition.is_empty() {
+            writeln!(out, "        if: {}", condition.join(" and ")).unwrap();
+        }
+        writeln!(out, "      - id: {}_fx", part_id).unwrap();
+        writeln!(out, "        type: b1").unwrap();
+        if !condition.is_empty() {
+            writeln!(out, "        if: {}", condition.join(" and ")).unwrap();
+        }
+        condition.push(format!("{}_fx", part_id));
+    }
+    // Octets beyond the definition, up to the first one with FX clear.
+    writeln!(out, "      - id: extensions").unwrap();
+    writeln!(out, "        type: u1").unwrap();
+    writeln!(out, "        repeat: until").unwrap();
+    writeln!(out, "        repeat-until: (_ & 1) == 0").unwrap();
+    writeln!(out, "        if: {}", condition.join(" and ")).unwrap();
+
+    for part in parts {
+        write_type(out, &part.struct_name, &part.decode_ops);
+    }
+}
+
+fn write_repetitive(
+    out: &mut String,
+    name: &Ident,
+    element_type_name: &Ident,
+    count: usize,
+    decode_ops: &[DecodeOp],
+) {
+    writeln!(out, "  {}:", ksy_id(name)).unwrap();
+    writeln!(out, "    seq:").unwrap();
+    writeln!(out, "      - id: items").unwrap();
+    writeln!(out, "        type: {}", ksy_id(element_type_name)).unwrap();
+    writeln!(out, "        repeat: expr").unwrap();
+    writeln!(out, "        repeat-expr: {}", count).unwrap();
+    write_type(out, element_type_name, decode_ops);
+}
+
+fn write_compound(out: &mut String, name: &Ident, sub_items: &[LoweredSubItem]) {
+    writeln!(out, "  {}:", ksy_id(name)).unwrap();
+    writeln!(out, "    seq:").unwrap();
+    write_fspec(out);
+    for sub in sub_items {
+        writeln!(out, "      - id: {}", ksy_id(&sub.field_name)).unwrap();
+        writeln!(out, "        type: {}", ksy_id(&sub.struct_name)).unwrap();
+        writeln!(out, "        if: {}", fspec_condition(sub.frn)).unwrap();
+    }
+
+    for sub in sub_items {
+        match &sub.kind {
+            LoweredSubItemKind::Simple { decode_ops, .. } => {
+                write_type(out, &sub.struct_name, decode_ops);
+            }
+            LoweredSubItemKind::Extended { parts } => write_extended(out, &sub.struct_name, parts),
+            LoweredSubItemKind::Repetitive { element_type_name, count, decode_ops, .. } => {
+                write_repetitive(out, &sub.struct_name, element_type_name, *count, decode_ops);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::backend::run_backend;
+    use crate::parse::parser::parse_category;
+    use crate::transform::{lower, transformer::to_ir};
+
+    fn ksy(xml: &str) -> String {
+        run_backend(KsyBackend::default(), &lower(&to_ir(parse_category(xml).unwrap()).unwrap()).unwrap())
+    }
+
+    #[test]
+    fn test_ksy_header_and_record() {
+        let ksy = ksy(r#"<category id="48">
+            <item id="10" frn="1">
+                <fixed bytes="2">
+                    <field name="sac" bits="8"/>
+                    <field name="sic" bits="8"/>
+                </fixed>
+            </item>
+            <item id="20" frn="8">
+                <fixed bytes="1"><field name="a" bits="8"/></fixed>
+            </item>
+        </category>"#);
+
+        assert!(ksy.contains("  id: cat048\n"));
+        assert!(ksy.contains("    valid: 48\n"));
+        assert!(ksy.contains("        if: fspec.size > 0 and (fspec[0] & 0x80) != 0\n"));
+        assert!(ksy.contains("        if: fspec.size > 1 and (fspec[1] & 0x80) != 0\n"));
+        assert!(ksy.contains("  item010:\n    seq:\n      - id: sac\n        type: b8\n"));
+    }
+
+    #[test]
+    fn test_ksy_enums_and_epb() {
+        let ksy = ksy(r#"<category id="1">
+            <item id="10" frn="1">
+                <fixed bytes="1">
+                    <epb>
+                        <enum name="target_type" bits="3">
+                            <value name="PSR" value="1"/>
+                            <value name="SSR" value="2"/>
+                        </enum>
+                    </epb>
+                    <spare bits="4"/>
+                </fixed>
+            </item>
+        </category>"#);
+
+        assert!(ksy.contains("      - id: target_type_valid\n        type: b1\n"));
+        assert!(ksy.contains("        enum: target_type\n"));
+        assert!(ksy.contains("enums:\n  target_type:\n    1: psr\n    2: ssr\n"));
+        assert!(ksy.contains("      - type: b4\n        doc: spare\n"));
+    }
+
+    #[test]
+    fn test_ksy_extended_parts_chain_fx() {
+        let ksy = ksy(r#"<category id="48">
+            <item id="20" frn="1">
+                <extended bytes="3">
+                    <part index="0"><field name="a" bits="7"/></part>
+                    <part index="1"><field name="b" bits="7"/></part>
+                    <part index="2"><field name="c" bits="7"/></part>
+                </extended>
+            </item>
+        </category>"#);
+
+        assert!(ksy.contains("      - id: part0_fx\n        type: b1\n"));
+        assert!(ksy.contains("      - id: part1\n        type: item020_part1\n        if: part0_fx\n"));
+        assert!(ksy.contains("      - id: part2\n        type: item020_part2\n        if: part0_fx and part1_fx\n"));
+        assert!(ksy.contains("      - id: part2_fx\n        type: b1\n        if: part0_fx and part1_fx\n"));
+        assert!(ksy.contains("        repeat-until: (_ & 1) == 0\n        if: part0_fx and part1_fx and part2_fx\n"));
+    }
+}
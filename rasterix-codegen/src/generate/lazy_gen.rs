@@ -0,0 +1,145 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use crate::transform::lower_ir::LoweredRecord;
+use super::utils::{compiled_out_check, feature_cfg};
+
+/// Generates the lazily decoded view of the record, `{Record}Lazy`.
+///
+/// Parsing the view reads the FSPEC and skips over every present item,
+/// recording the bit offset where it starts.  Items are only decoded when
+/// their accessor is called, so consumers interested in a few items of a
+/// high-rate stream pay for the skips only.
+pub fn generate_record_lazy(record: &LoweredRecord) -> TokenStream {
+    let record_name = &record.name;
+    let lazy_name = format_ident!("{}Lazy", record_name);
+    let item_count = record.entries.len();
+
+    let offset_reads: Vec<_> = record.entries.iter().enumerate().map(|(i, entry)| {
+        let item_type = &entry.type_name;
+        let frn = entry.frn;
+        let cfg = feature_cfg(&entry.feature);
+        let compiled_out = compiled_out_check(&entry.feature, frn);
+        quote! {
+            #compiled_out
+            #cfg
+            if fspec.is_frn_set(#frn) {
+                offsets[#i] = Some(reader.bit_position() as usize);
+                #item_type::skip(&mut reader)?;
+            };
+        }
+    }).collect();
+
+    let accessors: Vec<_> = record.entries.iter().enumerate().map(|(i, entry)| {
+        let field_name = &entry.field_name;
+        let item_type = &entry.type_name;
+        let doc = format!(" Decodes `{}`, or returns `None` if it is absent.", item_type);
+        let cfg = feature_cfg(&entry.feature);
+        quote! {
+            #[doc = #doc]
+            #cfg
+            pub fn #field_name(&self) -> Result<Option<#item_type>, DecodeError> {
+                match self.offsets[#i] {
+                    Some(offset) => Ok(Some(#item_type::decode(&mut self.reader_at(offset)?)?)),
+                    None => Ok(None),
+                }
+            }
+        }
+    }).collect();
+
+    let field_decodes: Vec<_> = record.entries.iter().map(|entry| {
+        let field_name = &entry.field_name;
+        let cfg = feature_cfg(&entry.feature);
+        quote! { #cfg #field_name: self.#field_name()? }
+    }).collect();
+
+    quote! {
+        /// Lazily decoded view of a record.
+        ///
+        /// [`parse`](Self::parse) only locates the present items; each
+        /// accessor decodes its item on demand.
+        #[derive(Debug, Clone, Copy)]
+        pub struct #lazy_name<'a> {
+            bytes: &'a [u8],
+            /// Bit offset in `bytes` of each present item, in record order.
+            offsets: [Option<usize>; #item_count],
+            encoded_len: usize,
+        }
+
+        impl<'a> #lazy_name<'a> {
+            /// Parses the FSPEC and item offsets of the record at the start
+            /// of `bytes`.
+            pub fn parse(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+                let mut reader = BitReader::new(std::io::Cursor::new(bytes));
+                let fspec = Fspec::read(&mut reader)?;
+                let mut offsets = [None; #item_count];
+
+                #(#offset_reads)*
+
+                Ok(Self {
+                    bytes,
+                    offsets,
+                    encoded_len: (reader.bit_position() as usize).div_ceil(8),
+                })
+            }
+
+            /// Length of the record in bytes, so that the next record of a
+            /// data block starts at `bytes[encoded_len()..]`.
+            pub fn encoded_len(&self) -> usize {
+                self.encoded_len
+            }
+
+            /// Decodes every present item.
+            pub fn decode(&self) -> Result<#record_name, DecodeError> {
+                Ok(#record_name {
+                    #(#field_decodes),*
+                })
+            }
+
+            #(#accessors)*
+
+            fn reader_at(&self, offset: usize) -> Result<BitReader<&'a [u8]>, DecodeError> {
+                let mut reader = BitReader::new(&self.bytes[offset / 8..]);
+                reader.skip_bits(offset % 8)?;
+                Ok(reader)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transform::lower_ir::RecordEntry;
+
+    #[test]
+    fn test_generate_record_lazy() {
+        let record = LoweredRecord {
+            name: format_ident!("Record"),
+            entries: vec![
+                RecordEntry {
+                    field_name: format_ident!("item010"),
+                    type_name: format_ident!("Item010"),
+                    frn: 1,
+                    feature: None,
+                    is_mandatory: false,
+                },
+                RecordEntry {
+                    field_name: format_ident!("item040"),
+                    type_name: format_ident!("Item040"),
+                    frn: 10,
+                    feature: None,
+                    is_mandatory: false,
+                },
+            ],
+            is_hashable: true,
+        };
+
+        let code = generate_record_lazy(&record).to_string();
+
+        assert!(code.contains("pub struct RecordLazy < 'a >"));
+        assert!(code.contains("offsets : [Option < usize > ; 2usize]"));
+        assert!(code.contains("if fspec . is_frn_set (10usize) { offsets [1usize] = Some"));
+        assert!(code.contains("pub fn item040 (& self) -> Result < Option < Item040 > , DecodeError >"));
+    }
+}
@@ -1,29 +1,61 @@
 /// Code generation module.
 /// 
 /// This module is responsible for transforming the IR into Rust code.
+/// Every generator works on the lowered IR (`transform::lower_ir`), never on
+/// the semantic IR directly.
 /// The generation is split into several sub-modules:
 /// 
-/// - `generator`: Main orchestration, produces the complete output
+/// - `backend`: The `CodegenBackend` trait and the driver running a backend
+/// - `generator`: Main orchestration and the default Rust backend
+/// - `ffi_gen`: Backend emitting a C header and `extern "C"` shims
+/// - `wasm_gen`: Backend emitting `wasm-bindgen` wrappers returning JSON
+/// - `borrowed_gen`: Backend emitting types borrowing strings from the input
+/// - `json_gen`: Generates `ToJson` implementations
+/// - `ksy_gen`: Backend emitting a Kaitai Struct description
+/// - `proto_gen`: Backend emitting protobuf messages and their conversions
+/// - `arrow_gen`: Backend emitting conversions of records to Arrow batches
+/// - `docs_gen`: Backend emitting a Markdown reference
+/// - `diagram_gen`: Backend emitting Mermaid bit layout diagrams
 /// - `record_gen`: Generates the Cat{N}Record struct
+/// - `lazy_gen`: Generates the lazily decoded record view
 /// - `item_gen`: Generates Item{N} structs  
+/// - `conformance_gen`: Generates the conformance checks of items
+/// - `value_gen`: Generates conversions to and from generic values
+/// - `size_gen`: Generates encoded lengths and item skipping
 /// - `struct_gen`: Low-level struct generation utilities
 /// - `decode_gen`: Generates decode implementations
 /// - `encode_gen`: Generates encode implementations
 /// - `enum_gen`: Generates enum types
+/// - `example_gen`: Generates tests from the worked examples of items
 /// - `utils`: Helper functions and type mappings
 /// 
+pub mod backend;
 pub mod generator;
+pub mod ffi_gen;
+pub mod wasm_gen;
+pub mod borrowed_gen;
+pub mod json_gen;
+pub mod ksy_gen;
+pub mod proto_gen;
+pub mod arrow_gen;
+pub mod docs_gen;
+pub mod diagram_gen;
 pub mod record_gen;
+pub mod lazy_gen;
 pub mod datablock_gen;
 pub mod item_gen;
+pub mod conformance_gen;
+pub mod value_gen;
+pub mod size_gen;
 pub mod struct_gen;
 pub mod decode_gen;
 pub mod encode_gen;
 pub mod enum_gen;
+pub mod example_gen;
 pub mod utils;
 
 use proc_macro2::TokenStream;
-use crate::transform::ir::IR;
+use crate::transform::{ir::IR, lower_ir::LoweredIR};
 
 /// Main entry point for code generation.
 /// 
@@ -37,6 +69,96 @@ use crate::transform::ir::IR;
 /// 
 /// A TokenStream containing the complete generated Rust code, ready to be
 /// written to a file or included in a build script.
-pub fn generate(ir: &IR) -> TokenStream {
+///
+/// # Errors
+///
+/// Fails if the IR can't be [lowered](crate::transform::lower).
+pub fn generate(ir: &IR) -> Result<TokenStream, String> {
     generator::generate(ir)
-}
\ No newline at end of file
+}
+
+/// Generates code from an already lowered IR.
+///
+/// Use this together with [`lower`](crate::transform::lower) to inspect or
+/// adjust the lowered representation before generating code from it.
+///
+/// # Example
+///
+/// ```
+/// use rasterix_codegen::generate::generate_lowered;
+/// use rasterix_codegen::parse::parser::parse_category;
+/// use rasterix_codegen::transform::{lower, transformer::to_ir};
+///
+/// let xml = r#"<category id="1">
+///     <item id="10" frn="1">
+///         <fixed bytes="1"><field name="a" bits="8"/></fixed>
+///     </item>
+/// </category>"#;
+///
+/// let lowered = lower(&to_ir(parse_category(xml).unwrap()).unwrap()).unwrap();
+/// assert_eq!(lowered.items[0].name, "Item010");
+///
+/// let code = generate_lowered(&lowered).to_string();
+/// assert!(code.contains("pub struct Item010"));
+/// ```
+pub fn generate_lowered(lowered: &LoweredIR) -> TokenStream {
+    generator::generate_from_lowered(lowered)
+}
+
+/// Generates a C API for a category: a header and the Rust shims behind it.
+///
+/// The shims must be compiled in the same file as the output of
+/// [`generate`], which they refer to.
+pub fn generate_ffi(ir: &IR) -> Result<ffi_gen::FfiOutput, String> {
+    Ok(backend::run_backend(ffi_gen::FfiBackend::default(), &crate::transform::lower(ir)?))
+}
+
+/// Generates `wasm-bindgen` wrappers for a category, returning JSON.
+///
+/// Like the FFI shims, the output must be compiled in the same file as the
+/// output of [`generate`], in a crate depending on `wasm-bindgen`.
+pub fn generate_wasm(ir: &IR) -> Result<TokenStream, String> {
+    Ok(backend::run_backend(wasm_gen::WasmBackend::default(), &crate::transform::lower(ir)?))
+}
+
+/// Generates borrowed variants of the types of a category, decoding
+/// string fields as slices of the input.
+///
+/// The output must be compiled in the same file as the output of
+/// [`generate`], which it refers to.
+pub fn generate_borrowed(ir: &IR) -> Result<TokenStream, String> {
+    Ok(backend::run_backend(borrowed_gen::BorrowedBackend::default(), &crate::transform::lower(ir)?))
+}
+
+/// Generates a Kaitai Struct (`.ksy`) description of a category.
+pub fn generate_ksy(ir: &IR) -> Result<String, String> {
+    Ok(backend::run_backend(ksy_gen::KsyBackend::default(), &crate::transform::lower(ir)?))
+}
+
+/// Generates protobuf definitions mirroring the types of a category, and
+/// `prost` messages converting from and to them.
+///
+/// Like the FFI shims, the conversions must be compiled in the same file as
+/// the output of [`generate`], in a crate depending on `prost`.
+pub fn generate_proto(ir: &IR) -> Result<proto_gen::ProtoOutput, String> {
+    Ok(backend::run_backend(proto_gen::ProtoBackend::default(), &crate::transform::lower(ir)?))
+}
+
+/// Generates conversions of the records of a category to Arrow record
+/// batches.
+///
+/// The output must be compiled in the same file as the output of
+/// [`generate`], in a crate depending on `arrow-array` and `arrow-schema`.
+pub fn generate_arrow(ir: &IR) -> Result<TokenStream, String> {
+    Ok(backend::run_backend(arrow_gen::ArrowBackend, &crate::transform::lower(ir)?))
+}
+
+/// Generates a Markdown reference for a category.
+pub fn generate_docs(ir: &IR) -> Result<String, String> {
+    Ok(backend::run_backend(docs_gen::DocsBackend::default(), &crate::transform::lower(ir)?))
+}
+
+/// Generates a Mermaid bit layout diagram for each item of a category.
+pub fn generate_diagrams(ir: &IR) -> Result<Vec<diagram_gen::ItemDiagram>, String> {
+    Ok(backend::run_backend(diagram_gen::DiagramBackend::default(), &crate::transform::lower(ir)?))
+}
@@ -0,0 +1,684 @@
+use std::fmt::Write;
+
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+
+use crate::transform::lower_ir::{
+    FieldDescriptor, FieldType, LoweredEnum, LoweredIR, LoweredItem, LoweredItemKind, LoweredPart, LoweredRecord,
+    LoweredSubItemKind, RepetitiveStorage, MAX_BYTE_ARRAY_LEN,
+};
+use super::backend::CodegenBackend;
+use super::utils::{feature_cfg, to_snake_case};
+
+/// Output of the [`ProtoBackend`].
+#[derive(Debug, Clone)]
+pub struct ProtoOutput {
+    /// `.proto` definitions of the messages, in proto3 syntax.
+    pub proto: String,
+    /// Rust messages deriving `prost::Message`, with conversions from and
+    /// to the generated types.
+    ///
+    /// The messages form a `<module>_proto` module that refers to the
+    /// generated types as `super::<module>`, so they belong in the same
+    /// file as the generated code, in a crate depending on `prost`.
+    pub conversions: TokenStream,
+}
+
+/// Backend emitting protobuf messages mirroring the generated types.
+///
+/// Each record, item, part, compound sub-item and repetitive element
+/// becomes a message of the same name, so decoded records can be
+/// published on protobuf buses and read in any language from the `.proto`
+/// file.  Items are numbered by their FRN, sub-items by their position in
+/// the compound FSPEC and fields by their position in their struct.
+///
+/// Numbers up to 32 bits are `uint32`, wider ones `uint64`, and fields
+/// wider than 64 bits their big-endian bytes.  Enums are proto enums of
+/// their raw values, which keeps unknown values; the Rust messages hold
+/// them as `int32`, as prost does.  EPB-guarded values are `optional`.
+///
+/// Converting a message back fails with a [`ValueError`](rasterix_core::ValueError)
+/// naming the value that has no place in the generated type, e.g. a
+/// number too wide for its field or a missing first part.
+#[derive(Debug, Default)]
+pub struct ProtoBackend {
+    module: Option<Ident>,
+    enums: Vec<LoweredEnum>,
+    messages: String,
+    conversions: Vec<TokenStream>,
+}
+
+impl CodegenBackend for ProtoBackend {
+    type Output = ProtoOutput;
+
+    fn begin_module(&mut self, lowered: &LoweredIR) {
+        self.module = Some(lowered.module_name.clone());
+    }
+
+    fn emit_record(&mut self, record: &LoweredRecord) {
+        let fields: Vec<_> = record
+            .entries
+            .iter()
+            .map(|entry| {
+                let mut field = optional_message(&entry.field_name, entry.frn, &entry.type_name);
+                field.feature = entry.feature.clone();
+                field
+            })
+            .collect();
+        let record_name = &record.name;
+        self.message(record_name, None, &fields, &None);
+
+        let records = format_ident!("records");
+        let from = repeated_from(&records, record_name, None);
+        let data_block = ProtoField {
+            proto_type: format!("repeated {}", record_name),
+            attr: quote! { message, repeated },
+            rust_type: quote! { Vec<#record_name> },
+            to: quote! { value.records.iter().map(#record_name::from).collect() },
+            from,
+            fallible: true,
+            ..ProtoField::new(&records, 1)
+        };
+        self.message(&format_ident!("DataBlock"), None, &[data_block], &None);
+    }
+
+    fn emit_struct(&mut self, item: &LoweredItem) {
+        for lowered in &item.enums {
+            if !self.enums.iter().any(|e| e.name == lowered.name) {
+                self.enums.push(lowered.clone());
+            }
+        }
+
+        let name = &item.name;
+        let title = item.title.as_deref();
+        let feature = &item.feature;
+        match &item.kind {
+            LoweredItemKind::Simple { .. } if item.is_reserved => self.reserved_message(name, title, feature),
+            LoweredItemKind::Simple { fields, .. } => {
+                self.message(name, title, &scalar_fields(fields), feature);
+            }
+            LoweredItemKind::Extended { parts } => self.extended_messages(name, title, parts, feature),
+            LoweredItemKind::Repetitive { element_type_name, count, storage, fields, .. } => {
+                self.repetitive_messages(name, title, element_type_name, *count, *storage, fields, feature);
+            }
+            LoweredItemKind::Compound { sub_items } => {
+                let fields: Vec<_> = sub_items
+                    .iter()
+                    .map(|sub| optional_message(&sub.field_name, sub.frn, &sub.struct_name))
+                    .collect();
+                self.message(name, title, &fields, feature);
+                for sub in sub_items {
+                    let sub_name = &sub.struct_name;
+                    match &sub.kind {
+                        LoweredSubItemKind::Simple { fields, .. } => {
+                            self.message(sub_name, None, &scalar_fields(fields), feature);
+                        }
+                        LoweredSubItemKind::Extended { parts } => {
+                            self.extended_messages(sub_name, None, parts, feature);
+                        }
+                        LoweredSubItemKind::Repetitive { element_type_name, count, storage, fields, .. } => {
+                            self.repetitive_messages(
+                                sub_name, None, element_type_name, *count, *storage, fields, feature,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn finalize(self, lowered: &LoweredIR) -> ProtoOutput {
+        let module_name = &lowered.module_name;
+
+        let mut proto = String::new();
+        writeln!(proto, "// AUTO-GENERATED CODE - DO NOT EDIT").unwrap();
+        writeln!(proto, "// ASTERIX category {:03}", lowered.category_id).unwrap();
+        writeln!(proto).unwrap();
+        writeln!(proto, "syntax = \"proto3\";").unwrap();
+        writeln!(proto).unwrap();
+        writeln!(proto, "package asterix.{};", module_name).unwrap();
+        for lowered in &self.enums {
+            write_enum(&mut proto, lowered);
+        }
+        proto.push_str(&self.messages);
+
+        let proto_module = format_ident!("{}_proto", module_name);
+        let doc = format!(" Protobuf messages of the types of [`{}`](super::{}).", module_name, module_name);
+        let conversions = self.conversions;
+        let conversions = quote! {
+            #[doc = #doc]
+            pub mod #proto_module {
+                use super::#module_name as generated;
+                use rasterix::rcore::value;
+
+                #(#conversions)*
+            }
+        };
+
+        ProtoOutput { proto, conversions }
+    }
+}
+
+impl ProtoBackend {
+    /// Emits a message of `fields`, and its conversions from and to the
+    /// generated struct `name`.
+    fn message(&mut self, name: &Ident, title: Option<&str>, fields: &[ProtoField], feature: &Option<String>) {
+        let out = &mut self.messages;
+        writeln!(out).unwrap();
+        if let Some(title) = title {
+            writeln!(out, "// {}", title).unwrap();
+        }
+        writeln!(out, "message {} {{", name).unwrap();
+        for field in fields {
+            writeln!(out, "  {} {} = {};", field.proto_type, proto_name(&field.name), field.tag).unwrap();
+        }
+        writeln!(out, "}}").unwrap();
+
+        let struct_fields = fields.iter().map(|field| {
+            let field_name = &field.name;
+            let tag = field.tag.to_string();
+            let attr = &field.attr;
+            let rust_type = &field.rust_type;
+            quote! {
+                #[prost(#attr, tag = #tag)]
+                pub #field_name: #rust_type
+            }
+        });
+        let to_inits = fields.iter().map(|field| {
+            let field_name = &field.name;
+            let to = &field.to;
+            let cfg = feature_cfg(&field.feature);
+            quote! { #cfg #field_name: #to }
+        });
+        let from_inits = fields.iter().map(|field| {
+            let field_name = &field.name;
+            let from = &field.from;
+            let cfg = feature_cfg(&field.feature);
+            if field.fallible {
+                let key = proto_name(field_name);
+                quote! { #cfg #field_name: #from.map_err(|error| error.within(#key))? }
+            } else {
+                quote! { #cfg #field_name: #from }
+            }
+        });
+        // Fields of items compiled out keep their default.
+        let rest = fields.iter().any(|field| field.feature.is_some()).then(|| quote! { ..Default::default() });
+        let cfg = feature_cfg(feature);
+
+        self.conversions.push(quote! {
+            #[derive(Clone, PartialEq, ::prost::Message)]
+            pub struct #name {
+                #(#struct_fields,)*
+            }
+
+            #cfg
+            impl From<&generated::#name> for #name {
+                fn from(value: &generated::#name) -> Self {
+                    Self {
+                        #(#to_inits,)*
+                        #rest
+                    }
+                }
+            }
+
+            #cfg
+            impl TryFrom<#name> for generated::#name {
+                type Error = value::ValueError;
+
+                fn try_from(message: #name) -> Result<Self, value::ValueError> {
+                    Ok(Self {
+                        #(#from_inits,)*
+                    })
+                }
+            }
+        });
+    }
+
+    /// Emits the empty message of a reserved item, a unit struct.
+    fn reserved_message(&mut self, name: &Ident, title: Option<&str>, feature: &Option<String>) {
+        let out = &mut self.messages;
+        writeln!(out).unwrap();
+        if let Some(title) = title {
+            writeln!(out, "// {}", title).unwrap();
+        }
+        writeln!(out, "message {} {{}}", name).unwrap();
+
+        let cfg = feature_cfg(feature);
+        self.conversions.push(quote! {
+            #[derive(Clone, PartialEq, ::prost::Message)]
+            pub struct #name {}
+
+            #cfg
+            impl From<&generated::#name> for #name {
+                fn from(_: &generated::#name) -> Self {
+                    Self {}
+                }
+            }
+
+            #cfg
+            impl TryFrom<#name> for generated::#name {
+                type Error = value::ValueError;
+
+                fn try_from(_: #name) -> Result<Self, value::ValueError> {
+                    Ok(Self)
+                }
+            }
+        });
+    }
+
+    /// Emits the messages of an extended item and its parts; the first
+    /// part is required, and unknown extensions are kept as bytes.
+    fn extended_messages(&mut self, name: &Ident, title: Option<&str>, parts: &[LoweredPart], feature: &Option<String>) {
+        let mut fields: Vec<_> = parts
+            .iter()
+            .enumerate()
+            .map(|(i, part)| {
+                if part.is_required {
+                    required_message(&part.field_name, i + 1, &part.struct_name)
+                } else {
+                    optional_message(&part.field_name, i + 1, &part.struct_name)
+                }
+            })
+            .collect();
+        let extensions = format_ident!("extensions");
+        fields.push(ProtoField {
+            proto_type: "bytes".to_string(),
+            attr: quote! { bytes = "vec" },
+            rust_type: quote! { Vec<u8> },
+            to: quote! { value.extensions.clone() },
+            from: quote! { message.extensions },
+            ..ProtoField::new(&extensions, parts.len() + 1)
+        });
+        self.message(name, title, &fields, feature);
+
+        for part in parts {
+            self.message(&part.struct_name, None, &scalar_fields(&part.fields), feature);
+        }
+    }
+
+    /// Emits the messages of a repetitive item, a list of its elements, and
+    /// of its elements.
+    #[allow(clippy::too_many_arguments)]
+    fn repetitive_messages(
+        &mut self,
+        name: &Ident,
+        title: Option<&str>,
+        element_type_name: &Ident,
+        count: usize,
+        storage: RepetitiveStorage,
+        fields: &[FieldDescriptor],
+        feature: &Option<String>,
+    ) {
+        let items = format_ident!("items");
+        let capacity = (storage == RepetitiveStorage::Heapless).then_some(count);
+        let items_field = ProtoField {
+            proto_type: format!("repeated {}", element_type_name),
+            attr: quote! { message, repeated },
+            rust_type: quote! { Vec<#element_type_name> },
+            to: quote! { value.items.iter().map(#element_type_name::from).collect() },
+            from: repeated_from(&items, element_type_name, capacity),
+            fallible: true,
+            ..ProtoField::new(&items, 1)
+        };
+        self.message(name, title, &[items_field], feature);
+        self.message(element_type_name, None, &scalar_fields(fields), feature);
+    }
+}
+
+/// A field of a message, with its conversions from and to the field of
+/// the generated struct.
+struct ProtoField {
+    name: Ident,
+    tag: usize,
+    /// Type as written in the `.proto` file, with its label.
+    proto_type: String,
+    /// Type and label in the `#[prost]` attribute.
+    attr: TokenStream,
+    rust_type: TokenStream,
+    /// Expression converting the field of `value`, the generated struct.
+    to: TokenStream,
+    /// Expression converting the field of `message`, returning a `Result`
+    /// if `fallible`.
+    from: TokenStream,
+    fallible: bool,
+    /// Cargo feature the item of a record field is compiled under.
+    feature: Option<String>,
+}
+
+impl ProtoField {
+    fn new(name: &Ident, tag: usize) -> Self {
+        Self {
+            name: name.clone(),
+            tag,
+            proto_type: String::new(),
+            attr: quote! {},
+            rust_type: quote! {},
+            to: quote! {},
+            from: quote! {},
+            fallible: false,
+            feature: None,
+        }
+    }
+}
+
+/// A message field holding the generated struct `type_name`, `None` when
+/// absent.
+fn optional_message(name: &Ident, tag: usize, type_name: &Ident) -> ProtoField {
+    ProtoField {
+        proto_type: type_name.to_string(),
+        attr: quote! { message, optional },
+        rust_type: quote! { Option<#type_name> },
+        to: quote! { value.#name.as_ref().map(#type_name::from) },
+        from: quote! { message.#name.map(generated::#type_name::try_from).transpose() },
+        fallible: true,
+        ..ProtoField::new(name, tag)
+    }
+}
+
+/// A message field holding the generated struct `type_name`, which
+/// protobuf can't require.
+fn required_message(name: &Ident, tag: usize, type_name: &Ident) -> ProtoField {
+    ProtoField {
+        to: quote! { Some(#type_name::from(&value.#name)) },
+        from: quote! { value::required(message.#name).and_then(generated::#type_name::try_from) },
+        ..optional_message(name, tag, type_name)
+    }
+}
+
+/// Converts the repeated messages `name` to the generated `type_name`,
+/// failing beyond the `capacity` of a `heapless::Vec`.
+fn repeated_from(name: &Ident, type_name: &Ident, capacity: Option<usize>) -> TokenStream {
+    let convert = quote! {
+        message.#name
+            .into_iter()
+            .enumerate()
+            .map(|(i, element)| generated::#type_name::try_from(element).map_err(|error| error.within(&i.to_string())))
+            .collect::<Result<_, _>>()
+    };
+    match capacity {
+        Some(capacity) => quote! {
+            if message.#name.len() > #capacity {
+                let kind = value::ValueErrorKind::WrongLength { len: message.#name.len(), expected: #capacity };
+                Err(value::ValueError::new(kind))
+            } else {
+                #convert
+            }
+        },
+        None => convert,
+    }
+}
+
+/// The message fields of the value `fields` of a struct.
+fn scalar_fields(fields: &[FieldDescriptor]) -> Vec<ProtoField> {
+    fields.iter().enumerate().map(|(i, field)| scalar_field(field, i + 1)).collect()
+}
+
+fn scalar_field(field: &FieldDescriptor, tag: usize) -> ProtoField {
+    let name = &field.name;
+    let proto = ProtoField::new(name, tag);
+    match &field.type_tokens {
+        FieldType::Primitive(rust_type) => match rust_type.to_string().as_str() {
+            "f32" | "f64" => ProtoField {
+                proto_type: float_type(rust_type).to_string(),
+                attr: format_ident!("{}", float_type(rust_type)).into_token_stream(),
+                rust_type: quote! { #rust_type },
+                to: quote! { value.#name },
+                from: quote! { message.#name },
+                ..proto
+            },
+            "u128" => ProtoField {
+                proto_type: "bytes".to_string(),
+                attr: quote! { bytes = "vec" },
+                rust_type: quote! { Vec<u8> },
+                to: quote! { value.#name.to_be_bytes().to_vec() },
+                from: quote! { value::byte_array(&message.#name).map(u128::from_be_bytes) },
+                fallible: true,
+                ..proto
+            },
+            _ => {
+                let (proto_type, wire) = wire_type(rust_type);
+                let attr = format_ident!("{}", proto_type);
+                let proto = ProtoField {
+                    proto_type: proto_type.to_string(),
+                    attr: quote! { #attr },
+                    rust_type: quote! { #wire },
+                    ..proto
+                };
+                if wire == *rust_type {
+                    ProtoField { to: quote! { value.#name }, from: quote! { message.#name }, ..proto }
+                } else {
+                    ProtoField {
+                        to: quote! { #wire::from(value.#name) },
+                        from: quote! { value::narrow(message.#name) },
+                        fallible: true,
+                        ..proto
+                    }
+                }
+            }
+        },
+        FieldType::OptionalPrimitive(rust_type) => match rust_type.to_string().as_str() {
+            "f32" | "f64" => ProtoField {
+                proto_type: format!("optional {}", float_type(rust_type)),
+                attr: {
+                    let float = format_ident!("{}", float_type(rust_type));
+                    quote! { #float, optional }
+                },
+                rust_type: quote! { Option<#rust_type> },
+                to: quote! { value.#name },
+                from: quote! { message.#name },
+                ..proto
+            },
+            "u128" => ProtoField {
+                proto_type: "optional bytes".to_string(),
+                attr: quote! { bytes = "vec", optional },
+                rust_type: quote! { Option<Vec<u8>> },
+                to: quote! { value.#name.map(|number| number.to_be_bytes().to_vec()) },
+                from: quote! {
+                    message.#name.map(|bytes| value::byte_array(&bytes).map(u128::from_be_bytes)).transpose()
+                },
+                fallible: true,
+                ..proto
+            },
+            _ => {
+                let (proto_type, wire) = wire_type(rust_type);
+                let attr = format_ident!("{}", proto_type);
+                let proto = ProtoField {
+                    proto_type: format!("optional {}", proto_type),
+                    attr: quote! { #attr, optional },
+                    rust_type: quote! { Option<#wire> },
+                    ..proto
+                };
+                if wire == *rust_type {
+                    ProtoField { to: quote! { value.#name }, from: quote! { message.#name }, ..proto }
+                } else {
+                    ProtoField {
+                        to: quote! { value.#name.map(#wire::from) },
+                        from: quote! { message.#name.map(value::narrow).transpose() },
+                        fallible: true,
+                        ..proto
+                    }
+                }
+            }
+        },
+        FieldType::Enum(enum_name) => ProtoField {
+            proto_type: enum_name.to_string(),
+            attr: quote! { int32 },
+            rust_type: quote! { i32 },
+            to: quote! { i32::from(u8::from(value.#name)) },
+            from: quote! { value::raw_variant(message.#name) },
+            fallible: true,
+            ..proto
+        },
+        FieldType::OptionalEnum(enum_name) => ProtoField {
+            proto_type: format!("optional {}", enum_name),
+            attr: quote! { int32, optional },
+            rust_type: quote! { Option<i32> },
+            to: quote! { value.#name.map(|variant| i32::from(u8::from(variant))) },
+            from: quote! { message.#name.map(value::raw_variant).transpose() },
+            fallible: true,
+            ..proto
+        },
+        FieldType::FixedString(_) => ProtoField {
+            proto_type: "string".to_string(),
+            attr: quote! { string },
+            rust_type: quote! { String },
+            to: quote! { value.#name.clone() },
+            from: quote! { message.#name },
+            ..proto
+        },
+        FieldType::OptionalFixedString(_) => ProtoField {
+            proto_type: "optional string".to_string(),
+            attr: quote! { string, optional },
+            rust_type: quote! { Option<String> },
+            to: quote! { value.#name.clone() },
+            from: quote! { message.#name },
+            ..proto
+        },
+        FieldType::Bytes(byte_len) => ProtoField {
+            proto_type: "bytes".to_string(),
+            attr: quote! { bytes = "vec" },
+            rust_type: quote! { Vec<u8> },
+            to: quote! { value.#name.to_vec() },
+            ..if *byte_len > MAX_BYTE_ARRAY_LEN {
+                ProtoField { from: quote! { message.#name }, ..proto }
+            } else {
+                ProtoField { from: quote! { value::byte_array(&message.#name) }, fallible: true, ..proto }
+            }
+        },
+        FieldType::OptionalBytes(byte_len) => ProtoField {
+            proto_type: "optional bytes".to_string(),
+            attr: quote! { bytes = "vec", optional },
+            rust_type: quote! { Option<Vec<u8>> },
+            to: quote! { value.#name.as_ref().map(|bytes| bytes.to_vec()) },
+            ..if *byte_len > MAX_BYTE_ARRAY_LEN {
+                ProtoField { from: quote! { message.#name }, ..proto }
+            } else {
+                ProtoField {
+                    from: quote! { message.#name.map(|bytes| value::byte_array(&bytes)).transpose() },
+                    fallible: true,
+                    ..proto
+                }
+            }
+        },
+    }
+}
+
+/// The protobuf integer holding numbers of `rust_type`, and its Rust type.
+fn wire_type(rust_type: &Ident) -> (&'static str, Ident) {
+    match rust_type.to_string().as_str() {
+        "u64" => ("uint64", format_ident!("u64")),
+        _ => ("uint32", format_ident!("u32")),
+    }
+}
+
+fn float_type(rust_type: &Ident) -> &'static str {
+    if rust_type == "f32" { "float" } else { "double" }
+}
+
+/// Name of a field in the `.proto` file, without the `r#` of a raw
+/// identifier.
+fn proto_name(name: &Ident) -> String {
+    name.to_string().trim_start_matches("r#").to_string()
+}
+
+/// Writes a proto enum of the raw values of `lowered`.  Proto3 enums start
+/// at zero, so enums without a zero variant get an `_UNSPECIFIED` one.
+fn write_enum(out: &mut String, lowered: &LoweredEnum) {
+    let prefix = proto_name(&to_snake_case(&lowered.name.to_string())).to_uppercase();
+    let mut variants: Vec<_> = lowered.variants.iter().collect();
+    variants.sort_by_key(|variant| variant.value);
+
+    writeln!(out).unwrap();
+    writeln!(out, "enum {} {{", lowered.name).unwrap();
+    if variants.first().is_none_or(|variant| variant.value != 0) {
+        writeln!(out, "  {}_UNSPECIFIED = 0;", prefix).unwrap();
+    }
+    for variant in variants {
+        let name = proto_name(&to_snake_case(&proto_name(&variant.name))).to_uppercase();
+        writeln!(out, "  {}_{} = {};", prefix, name, variant.value).unwrap();
+    }
+    writeln!(out, "}}").unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::backend::run_backend;
+    use crate::parse::parser::parse_category;
+    use crate::transform::{lower, transformer::to_ir};
+
+    fn proto(xml: &str) -> ProtoOutput {
+        run_backend(ProtoBackend::default(), &lower(&to_ir(parse_category(xml).unwrap()).unwrap()).unwrap())
+    }
+
+    #[test]
+    fn test_proto_numbers_items_by_frn_and_fields_by_position() {
+        let output = proto(r#"<category id="48">
+            <item id="10" frn="1" name="Data Source Identifier">
+                <fixed bytes="2">
+                    <field name="sac" bits="8"/>
+                    <field name="sic" bits="8"/>
+                </fixed>
+            </item>
+            <item id="20" frn="3">
+                <fixed bytes="5">
+                    <epb><field name="time" bits="39"/></epb>
+                </fixed>
+            </item>
+        </category>"#);
+
+        assert!(output.proto.contains("syntax = \"proto3\";\n\npackage asterix.cat048;\n"));
+        assert!(output.proto.contains("message Record {\n  Item010 item010 = 1;\n  Item020 item020 = 3;\n}\n"));
+        assert!(output.proto.contains("message DataBlock {\n  repeated Record records = 1;\n}\n"));
+        assert!(output.proto.contains("// Data Source Identifier\nmessage Item010 {\n  uint32 sac = 1;\n  uint32 sic = 2;\n}\n"));
+        assert!(output.proto.contains("message Item020 {\n  optional uint64 time = 1;\n}\n"));
+
+        let code = output.conversions.to_string();
+        assert!(code.contains("pub mod cat048_proto"));
+        assert!(code.contains("# [prost (uint32 , tag = \"1\")] pub sac : u32"));
+        assert!(code.contains("sac : value :: narrow (message . sac) . map_err (| error | error . within (\"sac\")) ?"));
+    }
+
+    #[test]
+    fn test_proto_enums_start_at_zero() {
+        let output = proto(r#"<category id="1">
+            <item id="10" frn="1">
+                <fixed bytes="1">
+                    <enum name="target_type" bits="3">
+                        <value name="SSR" value="2"/>
+                        <value name="PSR" value="1"/>
+                    </enum>
+                    <spare bits="5"/>
+                </fixed>
+            </item>
+        </category>"#);
+
+        assert!(output.proto.contains(
+            "enum TargetType {\n  TARGET_TYPE_UNSPECIFIED = 0;\n  TARGET_TYPE_PSR = 1;\n  TARGET_TYPE_SSR = 2;\n}\n"
+        ));
+        assert!(output.proto.contains("  TargetType target_type = 1;\n"));
+    }
+
+    #[test]
+    fn test_proto_extended_parts_and_repetitive_elements() {
+        let output = proto(r#"<category id="48">
+            <item id="20" frn="1">
+                <extended bytes="2">
+                    <part index="0"><field name="a" bits="7"/></part>
+                    <part index="1"><field name="b" bits="7"/></part>
+                </extended>
+            </item>
+            <item id="30" frn="2">
+                <repetitive bytes="1" counter="2"><field name="c" bits="8"/></repetitive>
+            </item>
+        </category>"#);
+
+        assert!(output.proto.contains(
+            "message Item020 {\n  Item020Part0 part0 = 1;\n  Item020Part1 part1 = 2;\n  bytes extensions = 3;\n}\n"
+        ));
+        assert!(output.proto.contains("message Item030 {\n  repeated Item030Element items = 1;\n}\n"));
+
+        let code = output.conversions.to_string();
+        assert!(code.contains("value :: required (message . part0) . and_then (generated :: Item020Part0 :: try_from)"));
+    }
+}
@@ -1,7 +1,9 @@
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 
-use crate::transform::lower_ir::LoweredRecord;
+use crate::transform::lower_ir::{LoweredRecord, RecordEntry};
+use super::struct_gen::struct_derives;
+use super::utils::{compiled_out_check, feature_cfg};
 
 /// Generates the data Record struct and its implementations.
 ///
@@ -13,26 +15,50 @@ pub fn generate_record(record: &LoweredRecord) -> TokenStream {
     let fields: Vec<_> = record.entries.iter().map(|entry| {
         let field_name = &entry.field_name;
         let item_type = &entry.type_name;
+        let cfg = feature_cfg(&entry.feature);
         quote! {
+            #cfg
             pub #field_name: Option<#item_type>
         }
     }).collect();
 
     let decode_impl = generate_record_decode(record);
+    let decode_into_impl = generate_record_decode_into(record);
+    let decode_exact_impl = generate_record_decode_exact(record);
     let encode_impl = generate_record_encode(record);
+    let validate_impl = generate_record_validate(record);
+    let conformance_impl = generate_record_conformance(record);
+    let derives = struct_derives(record.is_hashable);
 
     quote! {
         /// ASTERIX Category record.
         ///
         /// Contains optional data items, each controlled by a bit in the FSPEC.
-        #[derive(Debug, Clone, PartialEq)]
+        #derives
         pub struct #record_name {
             #(#fields),*
         }
 
         #decode_impl
 
+        #decode_into_impl
+
+        #decode_exact_impl
+
         #encode_impl
+
+        #validate_impl
+
+        #conformance_impl
+    }
+}
+
+/// Emits a call reporting a failure to decode `entry`'s item, chained onto
+/// the decode result.
+fn trace_item_error(entry: &RecordEntry) -> TokenStream {
+    let item = entry.type_name.to_string();
+    quote! {
+        .inspect_err(|e| trace::item_error(<Self as CategoryRecord>::CATEGORY, #item, e))
     }
 }
 
@@ -42,17 +68,22 @@ fn generate_record_decode(record: &LoweredRecord) -> TokenStream {
     let decode_fields: Vec<_> = record.entries.iter().map(|entry| {
         let field_name = &entry.field_name;
         let item_type = &entry.type_name;
-        let byte = entry.fspec_byte;
-        let bit = entry.fspec_bit;
+        let frn = entry.frn;
+        let cfg = feature_cfg(&entry.feature);
+        let trace = trace_item_error(entry);
 
         quote! {
-            #field_name: if fspec.is_set(#byte, #bit) {
-                Some(#item_type::decode(reader)?)
+            #cfg
+            #field_name: if fspec.is_frn_set(#frn) {
+                Some(#item_type::decode(reader)#trace?)
             } else {
                 None
             }
         }
     }).collect();
+    let compiled_out: Vec<_> = record.entries.iter().map(|entry| {
+        compiled_out_check(&entry.feature, entry.frn)
+    }).collect();
 
     quote! {
         impl Decode for #record_name {
@@ -60,12 +91,162 @@ fn generate_record_decode(record: &LoweredRecord) -> TokenStream {
                 reader: &mut BitReader<R>,
             ) -> Result<Self, DecodeError> {
                 let fspec = Fspec::read(reader)?;
+                #(#compiled_out)*
 
                 Ok(Self {
                     #(#decode_fields),*
                 })
             }
         }
+
+        impl DecodeFromRead for #record_name {
+            fn decode_from<R: std::io::Read>(
+                reader: &mut R,
+            ) -> Result<Self, DecodeError> {
+                Self::decode(&mut BitReader::new(reader))
+            }
+        }
+    }
+}
+
+/// Generates the `profile` constants and `decode_exact`, which decodes
+/// records of a known item set without FSPEC branching.
+///
+/// Only items at the first 64 FSPEC positions can be part of a profile.
+fn generate_record_decode_exact(record: &LoweredRecord) -> TokenStream {
+    let record_name = &record.name;
+
+    let mut consts = Vec::new();
+    let mut all = Vec::new();
+    let mut const_names = Vec::new();
+    let mut decode_fields = Vec::new();
+    for entry in &record.entries {
+        let field_name = &entry.field_name;
+        let item_type = &entry.type_name;
+        let cfg = feature_cfg(&entry.feature);
+        let position = entry.frn - 1;
+
+        if position >= 64 {
+            decode_fields.push(quote! {
+                #cfg
+                #field_name: None
+            });
+            continue;
+        }
+
+        let const_name = format_ident!("{}", item_type.to_string().to_uppercase());
+        let mask = 1u64 << position;
+        let trace = trace_item_error(entry);
+        consts.push(quote! {
+            #cfg
+            pub const #const_name: u64 = #mask;
+        });
+        all.push(quote! {
+            #cfg
+            { all |= #const_name; }
+        });
+        const_names.push(const_name.clone());
+        decode_fields.push(quote! {
+            #cfg
+            #field_name: if PROFILE & profile::#const_name != 0 {
+                Some(#item_type::decode(reader)#trace?)
+            } else {
+                None
+            }
+        });
+    }
+
+    let profile_doc = format!(
+        " Item sets for [`super::{}::decode_exact`]: one bit per item, combined with `|`.",
+        record_name
+    );
+    let all = if all.is_empty() {
+        quote! { 0 }
+    } else if record.entries.iter().all(|entry| entry.feature.is_none()) {
+        quote! { #(#const_names)|* }
+    } else {
+        quote! {
+            {
+                let mut all = 0;
+                #(#all)*
+                all
+            }
+        }
+    };
+
+    quote! {
+        #[doc = #profile_doc]
+        pub mod profile {
+            #(#consts)*
+
+            /// Every item a profile can contain.
+            pub const ALL: u64 = #all;
+        }
+
+        impl #record_name {
+            /// Decodes a record that contains exactly the items of `PROFILE`,
+            /// a combination of the [`profile`] constants.
+            ///
+            /// The item set is fixed at compile time, so no FSPEC is kept and
+            /// nothing is allocated beyond the items themselves.  Fails if the
+            /// record's FSPEC differs from the profile's.
+            pub fn decode_exact<const PROFILE: u64, R: std::io::Read>(
+                reader: &mut BitReader<R>,
+            ) -> Result<Self, DecodeError> {
+                const {
+                    assert!(PROFILE & !profile::ALL == 0, "profile contains an item the record doesn't have")
+                };
+                Fspec::read_profile(reader, PROFILE)?;
+
+                Ok(Self {
+                    #(#decode_fields),*
+                })
+            }
+        }
+    }
+}
+
+fn generate_record_decode_into(record: &LoweredRecord) -> TokenStream {
+    let record_name = &record.name;
+
+    let decode_fields: Vec<_> = record.entries.iter().map(|entry| {
+        let field_name = &entry.field_name;
+        let item_type = &entry.type_name;
+        let frn = entry.frn;
+        let trace = trace_item_error(entry);
+        let cfg = feature_cfg(&entry.feature);
+        let compiled_out = compiled_out_check(&entry.feature, frn);
+
+        quote! {
+            #compiled_out
+            #cfg
+            if fspec.is_frn_set(#frn) {
+                let result = match self.#field_name.as_mut() {
+                    Some(value) => value.decode_into(reader, ctx),
+                    None => #item_type::decode(reader).map(|value| self.#field_name = Some(value)),
+                };
+                result #trace?;
+            } else {
+                self.#field_name = None;
+            };
+        }
+    }).collect();
+
+    quote! {
+        impl DecodeInto for #record_name {
+            fn decode_into<R: std::io::Read>(
+                &mut self,
+                reader: &mut BitReader<R>,
+                ctx: &mut DecodeContext,
+            ) -> Result<(), DecodeError> {
+                // The context's FSPEC buffer keeps its allocation across records
+                ctx.with_fspec(reader, |ctx, reader, fspec| {
+                    #(#decode_fields)*
+
+                    Ok(())
+                })
+            }
+        }
     }
 }
 
@@ -74,31 +255,34 @@ fn generate_record_encode(record: &LoweredRecord) -> TokenStream {
 
     let fspec_setup: Vec<_> = record.entries.iter().map(|entry| {
         let field_name = &entry.field_name;
-        let byte = entry.fspec_byte;
-        let bit = entry.fspec_bit;
+        let frn = entry.frn;
+        let cfg = feature_cfg(&entry.feature);
 
         quote! {
+            #cfg
             if self.#field_name.is_some() {
-                fspec.set(#byte, #bit);
-            }
+                fspec.set_frn(#frn);
+            };
         }
     }).collect();
 
     let encode_items: Vec<_> = record.entries.iter().map(|entry| {
         let field_name = &entry.field_name;
+        let cfg = feature_cfg(&entry.feature);
 
         quote! {
+            #cfg
             if let Some(ref item) = self.#field_name {
                 item.encode(writer)?;
-            }
+            };
         }
     }).collect();
 
     quote! {
         impl Encode for #record_name {
-            fn encode<W: std::io::Write>(
+            fn encode<W: BitWrite>(
                 &self,
-                writer: &mut BitWriter<W>,
+                writer: &mut W,
             ) -> Result<(), DecodeError> {
                 let mut fspec = Fspec::new();
                 #(#fspec_setup)*
@@ -107,6 +291,80 @@ fn generate_record_encode(record: &LoweredRecord) -> TokenStream {
                 Ok(())
             }
         }
+
+        impl EncodeToWrite for #record_name {
+            fn encode_to<W: std::io::Write>(
+                &self,
+                writer: &mut W,
+            ) -> Result<(), DecodeError> {
+                let mut writer = BitWriter::new(writer);
+                self.encode(&mut writer)?;
+                writer.flush()?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Generates the `Validate` impl of the record, checking the items
+/// present under their field names, e.g. `item040.`.
+fn generate_record_validate(record: &LoweredRecord) -> TokenStream {
+    let record_name = &record.name;
+
+    let check_items: Vec<_> = record.entries.iter().map(|entry| {
+        let field_name = &entry.field_name;
+        let prefix = format!("{{}}{}.", field_name);
+        let cfg = feature_cfg(&entry.feature);
+
+        quote! {
+            #cfg
+            if let Some(ref item) = self.#field_name {
+                item.check(&format!(#prefix, path), errors);
+            };
+        }
+    }).collect();
+
+    quote! {
+        impl Validate for #record_name {
+            fn check(&self, path: &str, errors: &mut Vec<ValidationError>) {
+                #(#check_items)*
+            }
+        }
+    }
+}
+
+/// Generates the `Conformance` impl of the record, auditing the items
+/// present under their field names and reporting absent mandatory items.
+fn generate_record_conformance(record: &LoweredRecord) -> TokenStream {
+    let record_name = &record.name;
+
+    let audit_items: Vec<_> = record.entries.iter().map(|entry| {
+        let field_name = &entry.field_name;
+        let prefix = format!("{{}}{}.", field_name);
+        let cfg = feature_cfg(&entry.feature);
+        let missing = entry.is_mandatory.then(|| {
+            let name = field_name.to_string();
+            quote! {
+                else {
+                    conformance::report(issues, path, #name, conformance::Rule::MissingItem);
+                }
+            }
+        });
+
+        quote! {
+            #cfg
+            if let Some(ref item) = self.#field_name {
+                conformance::audit_item(item, &format!(#prefix, path), issues);
+            } #missing;
+        }
+    }).collect();
+
+    quote! {
+        impl Conformance for #record_name {
+            fn audit(&self, path: &str, issues: &mut Vec<ConformanceIssue>) {
+                #(#audit_items)*
+            }
+        }
     }
 }
 
@@ -124,16 +382,19 @@ mod tests {
                 RecordEntry {
                     field_name: format_ident!("item010"),
                     type_name: format_ident!("Item010"),
-                    fspec_byte: 0,
-                    fspec_bit: 0,
+                    frn: 1,
+                    feature: None,
+                    is_mandatory: false,
                 },
                 RecordEntry {
                     field_name: format_ident!("item020"),
                     type_name: format_ident!("Item020"),
-                    fspec_byte: 0,
-                    fspec_bit: 1,
+                    frn: 2,
+                    feature: None,
+                    is_mandatory: false,
                 },
             ],
+            is_hashable: true,
         };
 
         let result = generate_record(&record);
@@ -143,6 +404,7 @@ mod tests {
         assert!(code.contains("pub item010 : Option < Item010 >"));
         assert!(code.contains("pub item020 : Option < Item020 >"));
         assert!(code.contains("impl Decode for Record"));
+        assert!(code.contains("impl DecodeInto for Record"));
         assert!(code.contains("impl Encode for Record"));
     }
 }
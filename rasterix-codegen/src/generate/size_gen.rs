@@ -0,0 +1,273 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::transform::lower_ir::{
+    DecodeOp, LoweredItem, LoweredItemKind, LoweredPart, LoweredRecord, LoweredSubItemKind,
+};
+use super::utils::feature_cfg;
+
+/// Generates the encoded length functions and `skip` of an item.
+///
+/// `skip` reads past the item without decoding its fields, by the same
+/// rules as decode: explicit items span their defined size, with the
+/// length byte checked against it by the decode policy, and extended and
+/// compound items follow their FX bits and FSPEC.
+pub fn generate_item_size(item: &LoweredItem) -> TokenStream {
+    let name = &item.name;
+    let (min_len, len_hint) = item_lengths(&item.kind);
+    let len_hint = match len_hint {
+        Some(len) => quote! { Some(#len) },
+        None => quote! { None },
+    };
+
+    let skip_fn = match &item.kind {
+        LoweredItemKind::Simple { is_explicit, byte_size, .. } => {
+            let body = emit_simple_skip(*is_explicit, *byte_size);
+            quote! {
+                pub fn skip<R: std::io::Read>(
+                    reader: &mut BitReader<R>,
+                ) -> Result<(), DecodeError> {
+                    #body
+                    Ok(())
+                }
+            }
+        }
+        LoweredItemKind::Extended { parts } => {
+            let body = emit_extended_skip(parts);
+            quote! {
+                pub fn skip<R: std::io::Read>(
+                    reader: &mut BitReader<R>,
+                ) -> Result<(), DecodeError> {
+                    #body
+                    Ok(())
+                }
+            }
+        }
+        LoweredItemKind::Repetitive { count, decode_ops, .. } => {
+            let bits = count * ops_bits(decode_ops);
+            quote! {
+                pub fn skip<R: std::io::Read>(
+                    reader: &mut BitReader<R>,
+                ) -> Result<(), DecodeError> {
+                    reader.skip_bits(#bits)?;
+                    Ok(())
+                }
+            }
+        }
+        LoweredItemKind::Compound { sub_items } => {
+            let sub_skips: Vec<_> = sub_items.iter().map(|sub| {
+                let frn = sub.frn;
+                let body = match &sub.kind {
+                    LoweredSubItemKind::Simple { is_explicit, byte_size, .. } => {
+                        emit_simple_skip(*is_explicit, *byte_size)
+                    }
+                    LoweredSubItemKind::Extended { parts } => emit_extended_skip(parts),
+                    LoweredSubItemKind::Repetitive { count, decode_ops, .. } => {
+                        let bits = count * ops_bits(decode_ops);
+                        quote! { reader.skip_bits(#bits)?; }
+                    }
+                };
+                quote! {
+                    if fspec.is_frn_set(#frn) {
+                        #body
+                    };
+                }
+            }).collect();
+
+            quote! {
+                pub fn skip<R: std::io::Read>(
+                    reader: &mut R,
+                ) -> Result<(), DecodeError> {
+                    let fspec = Fspec::read(reader)?;
+                    let mut reader = BitReader::new(reader);
+
+                    #(#sub_skips)*
+
+                    Ok(())
+                }
+            }
+        }
+    };
+
+    quote! {
+        impl #name {
+            /// Length in bytes of the shortest encoding the decoder accepts.
+            pub const fn min_encoded_len() -> usize {
+                #min_len
+            }
+
+            /// Length in bytes of every encoding of the item, or `None` if
+            /// it depends on the value.
+            pub const fn encoded_len_hint() -> Option<usize> {
+                #len_hint
+            }
+
+            /// Reads past the item without decoding its fields.
+            #skip_fn
+        }
+    }
+}
+
+/// Generates the record-level length table and item skipping.
+///
+/// Together with the per-item `skip`, this lets a consumer that only needs
+/// a few items read the FSPEC itself and skip every other present item.
+pub fn generate_record_size(record: &LoweredRecord) -> TokenStream {
+    let record_name = &record.name;
+    let positions: Vec<usize> = record
+        .entries
+        .iter()
+        .map(|e| e.frn - 1)
+        .collect();
+    let len = positions.iter().max().map_or(0, |max| max + 1);
+
+    let table: Vec<_> = (0..len).map(|position| {
+        match positions.iter().position(|p| *p == position) {
+            Some(i) => {
+                let entry = &record.entries[i];
+                let item_type = &entry.type_name;
+                match &entry.feature {
+                    Some(feature) => quote! {
+                        {
+                            #[cfg(feature = #feature)]
+                            let len = #item_type::encoded_len_hint();
+                            #[cfg(not(feature = #feature))]
+                            let len = None;
+                            len
+                        }
+                    },
+                    None => quote! { #item_type::encoded_len_hint() },
+                }
+            }
+            None => quote! { None },
+        }
+    }).collect();
+
+    let skip_arms: Vec<_> = record.entries.iter().zip(&positions).map(|(entry, position)| {
+        let item_type = &entry.type_name;
+        let cfg = feature_cfg(&entry.feature);
+        quote! { #cfg #position => #item_type::skip(reader) }
+    }).collect();
+
+    quote! {
+        impl #record_name {
+            /// Encoded length of the item at each FSPEC position, from
+            /// `encoded_len_hint`.  `None` for variable-length items,
+            /// compiled-out items and unused positions.
+            pub const ITEM_LENGTHS: [Option<usize>; #len] = [#(#table),*];
+
+            /// Reads past the item at FSPEC position `position`
+            /// (`byte * 7 + bit`, one less than its FRN) without decoding it.
+            pub fn skip_item<R: std::io::Read>(
+                position: usize,
+                reader: &mut BitReader<R>,
+            ) -> Result<(), DecodeError> {
+                match position {
+                    #(#skip_arms,)*
+                    _ => Err(DecodeError::InvalidData("no item at FSPEC position")),
+                }
+            }
+        }
+    }
+}
+
+/// Returns the minimum and, when fixed, the exact encoded length of an item.
+fn item_lengths(kind: &LoweredItemKind) -> (usize, Option<usize>) {
+    match kind {
+        LoweredItemKind::Simple { is_explicit, byte_size, .. } => {
+            let len = byte_size + *is_explicit as usize;
+            (len, Some(len))
+        }
+        LoweredItemKind::Extended { parts } => ((ops_bits(&parts[0].decode_ops) + 1).div_ceil(8), None),
+        LoweredItemKind::Repetitive { count, decode_ops, .. } => {
+            let len = (count * ops_bits(decode_ops)).div_ceil(8);
+            (len, Some(len))
+        }
+        // An FSPEC with no sub-item present.
+        LoweredItemKind::Compound { .. } => (1, None),
+    }
+}
+
+fn ops_bits(decode_ops: &[DecodeOp]) -> usize {
+    decode_ops.iter().map(DecodeOp::bits).sum()
+}
+
+/// Mirrors simple decode: the length byte of an explicit item is checked
+/// like decode checks it, and the defined size is skipped whatever it says.
+fn emit_simple_skip(is_explicit: bool, byte_size: usize) -> TokenStream {
+    let bits = byte_size * 8;
+    if is_explicit {
+        let total_bytes = byte_size + 1;
+        quote! {
+            policy::length(reader.read_bits(8)? as usize, #total_bytes)?;
+            reader.skip_bits(#bits)?;
+        }
+    } else {
+        quote! { reader.skip_bits(#bits)?; }
+    }
+}
+
+/// Mirrors extended decode: an FX bit is read after every part but the last.
+fn emit_extended_skip(parts: &[LoweredPart]) -> TokenStream {
+    let first_bits = ops_bits(&parts[0].decode_ops);
+
+    let mut body = vec![quote! {
+        reader.skip_bits(#first_bits)?;
+        let mut fx = reader.read_bits(1)? != 0;
+    }];
+    for part in &parts[1..] {
+        let bits = ops_bits(&part.decode_ops);
+        body.push(quote! {
+            if fx {
+                reader.skip_bits(#bits)?;
+                fx = reader.read_bits(1)? != 0;
+            };
+        });
+    }
+    body.push(quote! {
+        while fx {
+            fx = reader.read_bits(8)? & 1 != 0;
+        }
+    });
+
+    quote! { #(#body)* }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parser::parse_category;
+    use crate::transform::{lower, transformer::to_ir};
+
+    fn lowered(xml: &str) -> crate::transform::lower_ir::LoweredIR {
+        lower(&to_ir(parse_category(xml).unwrap()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_item_lengths() {
+        let lowered = lowered(r#"<category id="1">
+            <item id="10" frn="1">
+                <fixed bytes="2"><field name="a" bits="16"/></fixed>
+            </item>
+            <item id="20" frn="2">
+                <extended bytes="2">
+                    <part index="0"><field name="b" bits="7"/></part>
+                    <part index="1"><field name="c" bits="7"/></part>
+                </extended>
+            </item>
+            <item id="30" frn="4">
+                <repetitive bytes="2" counter="3"><field name="d" bits="16"/></repetitive>
+            </item>
+        </category>"#);
+
+        assert_eq!(item_lengths(&lowered.items[0].kind), (2, Some(2)));
+        assert_eq!(item_lengths(&lowered.items[1].kind), (1, None));
+        assert_eq!(item_lengths(&lowered.items[2].kind), (6, Some(6)));
+
+        let code = generate_record_size(&lowered.record).to_string();
+        assert!(code.contains(
+            "ITEM_LENGTHS : [Option < usize > ; 4usize] = [Item010 :: encoded_len_hint () , Item020 :: encoded_len_hint () , None , Item030 :: encoded_len_hint ()]"
+        ));
+        assert!(code.contains("3usize => Item030 :: skip (reader)"));
+    }
+}
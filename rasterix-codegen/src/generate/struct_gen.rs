@@ -1,30 +1,325 @@
 use proc_macro2::{Ident, TokenStream};
-use quote::quote;
+use quote::{format_ident, quote};
 
-use crate::transform::lower_ir::{FieldDescriptor, FieldType, LoweredPart, LoweredSubItem, LoweredSubItemKind};
+use crate::transform::lower_ir::{
+    DerivedField, FieldDescriptor, FieldType, GeoRole, LoweredPart, LoweredSubItem, LoweredSubItemKind, RepetitiveStorage,
+    ValueType, MAX_BYTE_ARRAY_LEN,
+};
+
+/// Returns the Rust type of a struct field.
+fn field_type(field: &FieldDescriptor) -> TokenStream {
+    match &field.type_tokens {
+        FieldType::Primitive(ty) => quote! { #ty },
+        FieldType::OptionalPrimitive(ty) => quote! { Option<#ty> },
+        FieldType::Enum(ty) => quote! { #ty },
+        FieldType::OptionalEnum(ty) => quote! { Option<#ty> },
+        FieldType::FixedString(_) => quote! { String },
+        FieldType::OptionalFixedString(_) => quote! { Option<String> },
+        FieldType::Bytes(byte_len) => bytes_type(*byte_len),
+        FieldType::OptionalBytes(byte_len) => {
+            let ty = bytes_type(*byte_len);
+            quote! { Option<#ty> }
+        }
+    }
+}
+
+/// Returns the Rust type of a byte array field of `byte_len` bytes: an
+/// array up to [`MAX_BYTE_ARRAY_LEN`] bytes, a vector beyond.
+fn bytes_type(byte_len: usize) -> TokenStream {
+    if byte_len <= MAX_BYTE_ARRAY_LEN {
+        quote! { [u8; #byte_len] }
+    } else {
+        quote! { Vec<u8> }
+    }
+}
 
 /// Generates a struct field declaration from a pre-resolved field descriptor.
-fn generate_field(field: &FieldDescriptor) -> TokenStream {
+///
+/// Described fields are documented with their description, and fields
+/// deprecated by a revision of the definition are marked `#[deprecated]`.
+/// The revision goes in the note rather than `since`, which is meant for
+/// crate versions.
+pub(crate) fn generate_field(field: &FieldDescriptor) -> TokenStream {
     let name = &field.name;
-    match &field.type_tokens {
-        FieldType::Primitive(ty) => quote! { pub #name: #ty },
-        FieldType::OptionalPrimitive(ty) => quote! { pub #name: Option<#ty> },
-        FieldType::Enum(ty) => quote! { pub #name: #ty },
-        FieldType::OptionalEnum(ty) => quote! { pub #name: Option<#ty> },
-        FieldType::FixedString(_) => quote! { pub #name: String },
-        FieldType::OptionalFixedString(_) => quote! { pub #name: Option<String> },
+    let ty = field_type(field);
+    let doc = field.description.as_ref().map(|description| quote! { #[doc = #description] });
+    match &field.revision.deprecated_since {
+        Some(since) => {
+            let note = format!("deprecated since revision {} of the definition", since);
+            quote! { #doc #[deprecated(note = #note)] pub #name: #ty }
+        }
+        None => quote! { #doc pub #name: #ty },
+    }
+}
+
+/// Generates deprecated accessors named after the previous names of the
+/// renamed fields, so code written against an earlier revision of the
+/// definition still compiles with a warning pointing to the new name.
+fn generate_renamed_accessors(name: &Ident, fields: &[FieldDescriptor]) -> TokenStream {
+    let accessors: Vec<_> = fields
+        .iter()
+        .filter_map(|field| {
+            let old = field.revision.renamed_from.as_ref()?;
+            let old_mut = format_ident!("{}_mut", old);
+            let new = &field.name;
+            let ty = field_type(field);
+            let note = format!("renamed to `{}`", new);
+            Some(quote! {
+                #[deprecated(note = #note)]
+                pub fn #old(&self) -> &#ty {
+                    &self.#new
+                }
+                #[deprecated(note = #note)]
+                pub fn #old_mut(&mut self) -> &mut #ty {
+                    &mut self.#new
+                }
+            })
+        })
+        .collect();
+
+    if accessors.is_empty() {
+        return quote! {};
+    }
+    quote! {
+        impl #name {
+            #(#accessors)*
+        }
+    }
+}
+
+/// Generates the typed accessors of the fields with a value type: a getter
+/// named after the field converting its raw value, and a `set_` setter
+/// encoding a value back.  Optional fields get and set an `Option`.
+fn generate_typed_accessors(name: &Ident, fields: &[FieldDescriptor]) -> TokenStream {
+    let accessors: Vec<_> = fields
+        .iter()
+        .filter_map(|field| {
+            let typed = field.typed?;
+            let field_name = &field.name;
+            let setter = format_ident!("set_{}", field_name);
+            let bits = typed.bits;
+            // Altitudes are two's complement of the width of the field.
+            let (value_type, width) = match typed.value_type {
+                ValueType::FlightLevel => (quote! { altitude::FlightLevel }, Some(bits)),
+                ValueType::Altitude => (quote! { altitude::GeometricAltitude }, Some(bits)),
+                ValueType::Icao => (quote! { icao::IcaoAddress }, None),
+                // Components of a velocity, see `generate_velocity_accessors`
+                ValueType::GroundSpeed | ValueType::Heading | ValueType::Vx | ValueType::Vy => return None,
+            };
+            let (from_raw, to_raw) = match width {
+                Some(bits) => (quote! { #value_type::from_raw(raw as u64, #bits) }, quote! { value.to_raw(#bits) }),
+                None => (quote! { #value_type::from_raw(raw as u64) }, quote! { value.to_raw() }),
+            };
+            Some(match &field.type_tokens {
+                FieldType::OptionalPrimitive(ty) => quote! {
+                    pub fn #field_name(&self) -> Option<#value_type> {
+                        self.#field_name.map(|raw| #from_raw)
+                    }
+                    pub fn #setter(&mut self, value: Option<#value_type>) {
+                        self.#field_name = value.map(|value| #to_raw as #ty);
+                    }
+                },
+                FieldType::Primitive(ty) => quote! {
+                    pub fn #field_name(&self) -> #value_type {
+                        let raw = self.#field_name;
+                        #from_raw
+                    }
+                    pub fn #setter(&mut self, value: #value_type) {
+                        self.#field_name = #to_raw as #ty;
+                    }
+                },
+                _ => return None,
+            })
+        })
+        .collect();
+
+    if accessors.is_empty() {
+        return quote! {};
+    }
+    quote! {
+        impl #name {
+            #(#accessors)*
+        }
+    }
+}
+
+/// Generates the `velocity()` accessor and `set_velocity()` setter of a
+/// struct with the fields of a velocity, polar or cartesian.
+fn generate_velocity_accessors(name: &Ident, fields: &[FieldDescriptor]) -> TokenStream {
+    let component = |value_type: ValueType| {
+        fields.iter().find_map(|field| {
+            let typed = field.typed.filter(|typed| typed.value_type == value_type)?;
+            let FieldType::Primitive(ty) = &field.type_tokens else { return None };
+            Some((&field.name, ty, typed.bits))
+        })
+    };
+
+    let polar = (component(ValueType::GroundSpeed), component(ValueType::Heading));
+    let cartesian = (component(ValueType::Vx), component(ValueType::Vy));
+    let accessors = if let (Some((speed, speed_ty, speed_bits)), Some((heading, heading_ty, heading_bits))) = polar {
+        quote! {
+            pub fn velocity(&self) -> velocity::Velocity {
+                velocity::Velocity::from_polar_raw(self.#speed as u64, self.#heading as u64, #heading_bits)
+            }
+            pub fn set_velocity(&mut self, value: velocity::Velocity) {
+                let (speed, heading) = value.to_polar_raw(#speed_bits, #heading_bits);
+                self.#speed = speed as #speed_ty;
+                self.#heading = heading as #heading_ty;
+            }
+        }
+    } else if let (Some((vx, vx_ty, bits)), Some((vy, vy_ty, _))) = cartesian {
+        quote! {
+            pub fn velocity(&self) -> velocity::Velocity {
+                velocity::Velocity::from_cartesian_raw(self.#vx as u64, self.#vy as u64, #bits)
+            }
+            pub fn set_velocity(&mut self, value: velocity::Velocity) {
+                let (vx, vy) = value.to_cartesian_raw(#bits);
+                self.#vx = vx as #vx_ty;
+                self.#vy = vy as #vy_ty;
+            }
+        }
+    } else {
+        return quote! {};
+    };
+    quote! {
+        impl #name {
+            #accessors
+        }
+    }
+}
+
+/// Returns the derive attribute of a generated struct.
+///
+/// Hashable structs also derive `Eq`, `Hash` and `Ord`, so decoded values
+/// can be used as map keys and in sets.
+pub(crate) fn struct_derives(hashable: bool) -> TokenStream {
+    if hashable {
+        quote! { #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)] }
+    } else {
+        quote! { #[derive(Debug, Clone, PartialEq)] }
     }
 }
 
 /// Generates a complete struct definition from flat field descriptors.
-pub fn generate_struct(name: &Ident, fields: &[FieldDescriptor]) -> TokenStream {
+pub fn generate_struct(name: &Ident, fields: &[FieldDescriptor], hashable: bool) -> TokenStream {
     let field_tokens: Vec<_> = fields.iter().map(generate_field).collect();
+    let derives = struct_derives(hashable);
+    let accessors = generate_renamed_accessors(name, fields);
+    let typed_accessors = generate_typed_accessors(name, fields);
+    let velocity_accessors = generate_velocity_accessors(name, fields);
 
     quote! {
-        #[derive(Debug, Clone, PartialEq)]
+        #derives
         pub struct #name {
             #(#field_tokens),*
         }
+        #accessors
+        #typed_accessors
+        #velocity_accessors
+    }
+}
+
+/// Generates the unit struct of a reserved item, which holds no values.
+pub fn generate_reserved_struct(name: &Ident, byte_size: usize) -> TokenStream {
+    let derives = struct_derives(true);
+    let doc = format!(
+        "Reserved item: its {} byte(s) of spare bits are skipped when decoding and written as zeros when encoding.",
+        byte_size
+    );
+    quote! {
+        #[doc = #doc]
+        #derives
+        pub struct #name;
+    }
+}
+
+/// Generates the methods computing derived values, on the struct of each.
+///
+/// Fields are converted to `f64` before evaluating the expression, and the
+/// result is cast to the type of the value.  A value computed from an
+/// optional field is `None` when the field is.  Values with geo roles also
+/// implement the position trait of their struct.
+pub(crate) fn generate_derived_methods(derived: &[DerivedField]) -> TokenStream {
+    let methods = derived.iter().map(|derived| {
+        let struct_name = &derived.struct_name;
+        let name = &derived.name;
+        let ty = &derived.rust_type;
+        let expr = &derived.expr;
+
+        let bindings = derived.inputs.iter().map(|input| {
+            let (name, field) = (&input.name, &input.field);
+            if input.is_optional {
+                quote! { let #name = self.#field? as f64; }
+            } else {
+                quote! { let #name = self.#field as f64; }
+            }
+        });
+        let value = if ty == "f64" { quote! { #expr } } else { quote! { (#expr) as #ty } };
+        let (ty, value) = if derived.inputs.iter().any(|input| input.is_optional) {
+            (quote! { Option<#ty> }, quote! { Some(#value) })
+        } else {
+            (quote! { #ty }, value)
+        };
+
+        let description = derived.description.as_ref().map(|description| quote! { #[doc = #description] #[doc = ""] });
+        let computed = format!("Computed as `{}`.", expr);
+        quote! {
+            impl #struct_name {
+                #description
+                #[doc = #computed]
+                pub fn #name(&self) -> #ty {
+                    #(#bindings)*
+                    #value
+                }
+            }
+        }
+    });
+    let positions = derived.iter().filter_map(|first| {
+        let (trait_name, first_method, second_role, second_method) = match first.geo? {
+            GeoRole::Range => (quote! { PolarPosition }, quote! { range_nm }, GeoRole::Azimuth, quote! { azimuth_deg }),
+            GeoRole::East => (quote! { CartesianPosition }, quote! { east_nm }, GeoRole::North, quote! { north_nm }),
+            GeoRole::Azimuth | GeoRole::North => return None,
+        };
+        let second = derived
+            .iter()
+            .find(|other| other.struct_name == first.struct_name && other.geo == Some(second_role))?;
+        let struct_name = &first.struct_name;
+        let value = |derived: &DerivedField| {
+            let name = &derived.name;
+            if derived.rust_type == "f64" {
+                quote! { #struct_name::#name(self) }
+            } else {
+                quote! { #struct_name::#name(self) as f64 }
+            }
+        };
+        let (first_value, second_value) = (value(first), value(second));
+        Some(quote! {
+            impl geo::#trait_name for #struct_name {
+                fn #first_method(&self) -> f64 {
+                    #first_value
+                }
+
+                fn #second_method(&self) -> f64 {
+                    #second_value
+                }
+            }
+        })
+    });
+    quote! {
+        #(#methods)*
+        #(#positions)*
+    }
+}
+
+/// Returns the type holding the `count` elements of a repetitive item.
+pub(crate) fn repetitive_container(
+    element_type_name: &Ident,
+    count: usize,
+    storage: RepetitiveStorage,
+) -> TokenStream {
+    match storage {
+        RepetitiveStorage::Vec => quote! { Vec<#element_type_name> },
+        RepetitiveStorage::Heapless => quote! { heapless::Vec<#element_type_name, #count> },
     }
 }
 
@@ -32,16 +327,21 @@ pub fn generate_struct(name: &Ident, fields: &[FieldDescriptor]) -> TokenStream
 pub fn generate_repetitive_struct(
     name: &Ident,
     element_type_name: &Ident,
+    count: usize,
+    storage: RepetitiveStorage,
     fields: &[FieldDescriptor],
+    hashable: bool,
 ) -> TokenStream {
-    let element_struct = generate_struct(element_type_name, fields);
+    let element_struct = generate_struct(element_type_name, fields, hashable);
+    let derives = struct_derives(hashable);
+    let container = repetitive_container(element_type_name, count, storage);
 
     quote! {
         #element_struct
 
-        #[derive(Debug, Clone, PartialEq)]
+        #derives
         pub struct #name {
-            pub items: Vec<#element_type_name>,
+            pub items: #container,
         }
     }
 }
@@ -50,12 +350,13 @@ pub fn generate_repetitive_struct(
 pub fn generate_extended_structs(
     name: &Ident,
     parts: &[LoweredPart],
+    hashable: bool,
 ) -> TokenStream {
     let mut all_structs = Vec::new();
     let mut main_fields = Vec::new();
 
     for part in parts {
-        let part_struct = generate_struct(&part.struct_name, &part.fields);
+        let part_struct = generate_struct(&part.struct_name, &part.fields, hashable);
         all_structs.push(part_struct);
 
         let field_name = &part.field_name;
@@ -67,13 +368,17 @@ pub fn generate_extended_structs(
             main_fields.push(quote! { pub #field_name: Option<#part_name> });
         }
     }
+    let derives = struct_derives(hashable);
 
     quote! {
         #(#all_structs)*
 
-        #[derive(Debug, Clone, PartialEq)]
+        #derives
         pub struct #name {
-            #(#main_fields),*
+            #(#main_fields,)*
+            /// Extension octets received after the last defined part, FX
+            /// bits included, so that they are encoded back unchanged.
+            pub extensions: Vec<u8>,
         }
     }
 }
@@ -82,6 +387,7 @@ pub fn generate_extended_structs(
 pub fn generate_compound_structs(
     name: &Ident,
     sub_items: &[LoweredSubItem],
+    hashable: bool,
 ) -> TokenStream {
     let mut all_structs = Vec::new();
     let mut main_fields = Vec::new();
@@ -89,13 +395,13 @@ pub fn generate_compound_structs(
     for sub in sub_items {
         let sub_struct = match &sub.kind {
             LoweredSubItemKind::Simple { fields, .. } => {
-                generate_struct(&sub.struct_name, fields)
+                generate_struct(&sub.struct_name, fields, hashable)
             }
             LoweredSubItemKind::Extended { parts } => {
-                generate_extended_structs(&sub.struct_name, parts)
+                generate_extended_structs(&sub.struct_name, parts, hashable)
             }
-            LoweredSubItemKind::Repetitive { element_type_name, fields, .. } => {
-                generate_repetitive_struct(&sub.struct_name, element_type_name, fields)
+            LoweredSubItemKind::Repetitive { element_type_name, count, storage, fields, .. } => {
+                generate_repetitive_struct(&sub.struct_name, element_type_name, *count, *storage, fields, hashable)
             }
         };
 
@@ -105,11 +411,12 @@ pub fn generate_compound_structs(
         let sub_name = &sub.struct_name;
         main_fields.push(quote! { pub #field_name: Option<#sub_name> });
     }
+    let derives = struct_derives(hashable);
 
     quote! {
         #(#all_structs)*
 
-        #[derive(Debug, Clone, PartialEq)]
+        #derives
         pub struct #name {
             #(#main_fields),*
         }
@@ -119,13 +426,17 @@ pub fn generate_compound_structs(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use quote::format_ident;
+    use crate::transform::lower_ir::{FieldConstraints, FieldRevision};
 
     #[test]
     fn test_generate_field_primitive() {
         let field = FieldDescriptor {
             name: format_ident!("test_field"),
             type_tokens: FieldType::Primitive(format_ident!("u8")),
+            revision: FieldRevision::default(),
+            description: None,
+            constraints: FieldConstraints::default(),
+            typed: None,
         };
 
         let result = generate_field(&field);
@@ -138,6 +449,10 @@ mod tests {
         let field = FieldDescriptor {
             name: format_ident!("optional_field"),
             type_tokens: FieldType::OptionalPrimitive(format_ident!("u16")),
+            revision: FieldRevision::default(),
+            description: None,
+            constraints: FieldConstraints::default(),
+            typed: None,
         };
 
         let result = generate_field(&field);
@@ -150,6 +465,10 @@ mod tests {
         let field = FieldDescriptor {
             name: format_ident!("aircraft_id"),
             type_tokens: FieldType::FixedString(6),
+            revision: FieldRevision::default(),
+            description: None,
+            constraints: FieldConstraints::default(),
+            typed: None,
         };
 
         let result = generate_field(&field);
@@ -162,6 +481,10 @@ mod tests {
         let field = FieldDescriptor {
             name: format_ident!("callsign"),
             type_tokens: FieldType::OptionalFixedString(8),
+            revision: FieldRevision::default(),
+            description: None,
+            constraints: FieldConstraints::default(),
+            typed: None,
         };
 
         let result = generate_field(&field);
@@ -175,17 +498,68 @@ mod tests {
             FieldDescriptor {
                 name: format_ident!("sac"),
                 type_tokens: FieldType::Primitive(format_ident!("u8")),
+                revision: FieldRevision::default(),
+                description: None,
+                constraints: FieldConstraints::default(),
+                typed: None,
             },
             FieldDescriptor {
                 name: format_ident!("sic"),
                 type_tokens: FieldType::Primitive(format_ident!("u8")),
+                revision: FieldRevision::default(),
+                description: None,
+                constraints: FieldConstraints::default(),
+                typed: None,
             },
         ];
 
-        let result = generate_struct(&format_ident!("Item010"), &fields);
+        let result = generate_struct(&format_ident!("Item010"), &fields, false);
         let code = result.to_string();
-        assert!(code.contains("pub struct Item010"));
+        assert!(code.contains("# [derive (Debug , Clone , PartialEq)] pub struct Item010"));
         assert!(code.contains("pub sac : u8"));
         assert!(code.contains("pub sic : u8"));
     }
+
+    #[test]
+    fn test_generate_hashable_struct() {
+        let fields = vec![FieldDescriptor {
+            name: format_ident!("sac"),
+            type_tokens: FieldType::Primitive(format_ident!("u8")),
+            revision: FieldRevision::default(),
+            description: None,
+            constraints: FieldConstraints::default(),
+            typed: None,
+        }];
+
+        let code = generate_struct(&format_ident!("Item010"), &fields, true).to_string();
+        assert!(code.contains("# [derive (Debug , Clone , PartialEq , Eq , Hash , PartialOrd , Ord)] pub struct Item010"));
+    }
+
+    #[test]
+    fn test_generate_deprecated_and_renamed_fields() {
+        let fields = vec![
+            FieldDescriptor {
+                name: format_ident!("legacy"),
+                type_tokens: FieldType::Primitive(format_ident!("u8")),
+                revision: FieldRevision { deprecated_since: Some("1.27".into()), renamed_from: None },
+                description: None,
+                constraints: FieldConstraints::default(),
+                typed: None,
+            },
+            FieldDescriptor {
+                name: format_ident!("elevation"),
+                type_tokens: FieldType::OptionalPrimitive(format_ident!("u8")),
+                revision: FieldRevision { deprecated_since: None, renamed_from: Some(format_ident!("height")) },
+                description: None,
+                constraints: FieldConstraints::default(),
+                typed: None,
+            },
+        ];
+
+        let code = generate_struct(&format_ident!("Item050"), &fields, false).to_string();
+        assert!(code.contains("# [deprecated (note = \"deprecated since revision 1.27 of the definition\")] pub legacy : u8"));
+        assert!(code.contains("pub elevation : Option < u8 >"));
+        assert!(code.contains("# [deprecated (note = \"renamed to `elevation`\")] pub fn height (& self) -> & Option < u8 > { & self . elevation }"));
+        assert!(code.contains("pub fn height_mut (& mut self) -> & mut Option < u8 > { & mut self . elevation }"));
+    }
 }
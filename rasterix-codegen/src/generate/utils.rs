@@ -1,5 +1,7 @@
-use proc_macro2::Ident;
-use quote::format_ident;
+use std::borrow::Cow;
+
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
 
 /// Maps a bit count to the appropriate Rust unsigned integer type.
 ///
@@ -32,6 +34,91 @@ pub fn rust_type_for_bits(bits: usize) -> String {
     }
 }
 
+/// Returns `true` for the Rust types of float fields, read and written
+/// through their bits.
+pub fn is_float_type(ty: &Ident) -> bool {
+    ty == "f32" || ty == "f64"
+}
+
+/// Replaces the non-ASCII characters of a name so it can become an
+/// identifier.
+///
+/// Accented Latin letters lose their accents and ligatures are spelled
+/// out (`é` gives `e`, `ß` gives `ss`).  Any other character is escaped
+/// by its code point as a separate word, so `"range_km²"` gives
+/// `"range_km_u00b2"`.  ASCII names are returned as they are.
+///
+/// # Examples
+///
+/// ```
+/// use rasterix_codegen::generate::utils::transliterate;
+/// assert_eq!(transliterate("vélocité"), "velocite");
+/// assert_eq!(transliterate("Straße"), "Strasse");
+/// assert_eq!(transliterate("range_km²"), "range_km_u00b2");
+/// assert_eq!(transliterate("Mode3A"), "Mode3A");
+/// ```
+pub fn transliterate(name: &str) -> Cow<'_, str> {
+    if name.is_ascii() {
+        return Cow::Borrowed(name);
+    }
+    let mut ascii = String::with_capacity(name.len());
+    for c in name.chars() {
+        match transliterate_char(c) {
+            Some(replacement) => ascii.push_str(replacement),
+            None if c.is_ascii() => ascii.push(c),
+            None => ascii.push_str(&format!("_u{:04x}_", c as u32)),
+        }
+    }
+    // Escapes next to separators or the ends of the name leave extra
+    // separators.
+    let words: Vec<&str> = ascii.split(['_', '-']).filter(|word| !word.is_empty()).collect();
+    Cow::Owned(words.join("_"))
+}
+
+/// Returns the ASCII spelling of an accented Latin letter or ligature.
+fn transliterate_char(c: char) -> Option<&'static str> {
+    Some(match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' | 'Ā' | 'Ă' | 'Ą' => "A",
+        'æ' => "ae",
+        'Æ' => "Ae",
+        'ç' | 'ć' | 'č' => "c",
+        'Ç' | 'Ć' | 'Č' => "C",
+        'ď' | 'đ' | 'ð' => "d",
+        'Ď' | 'Đ' | 'Ð' => "D",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ė' | 'ę' | 'ě' => "e",
+        'È' | 'É' | 'Ê' | 'Ë' | 'Ē' | 'Ė' | 'Ę' | 'Ě' => "E",
+        'ğ' => "g",
+        'Ğ' => "G",
+        'ì' | 'í' | 'î' | 'ï' | 'ī' | 'į' | 'ı' => "i",
+        'Ì' | 'Í' | 'Î' | 'Ï' | 'Ī' | 'Į' | 'İ' => "I",
+        'ł' => "l",
+        'Ł' => "L",
+        'ñ' | 'ń' | 'ň' => "n",
+        'Ñ' | 'Ń' | 'Ň' => "N",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => "o",
+        'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' | 'Ō' | 'Ő' => "O",
+        'œ' => "oe",
+        'Œ' => "Oe",
+        'ř' => "r",
+        'Ř' => "R",
+        'ś' | 'š' | 'ş' => "s",
+        'Ś' | 'Š' | 'Ş' => "S",
+        'ß' => "ss",
+        'ť' | 'ţ' => "t",
+        'Ť' | 'Ţ' => "T",
+        'þ' => "th",
+        'Þ' => "Th",
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' | 'ų' => "u",
+        'Ù' | 'Ú' | 'Û' | 'Ü' | 'Ū' | 'Ů' | 'Ű' | 'Ų' => "U",
+        'ý' | 'ÿ' => "y",
+        'Ý' | 'Ÿ' => "Y",
+        'ź' | 'ż' | 'ž' => "z",
+        'Ź' | 'Ż' | 'Ž' => "Z",
+        _ => return None,
+    })
+}
+
 /// Converts a name to PascalCase for type names.
 /// 
 /// # Arguments
@@ -40,7 +127,8 @@ pub fn rust_type_for_bits(bits: usize) -> String {
 /// 
 /// # Returns
 /// 
-/// An Ident in PascalCase suitable for a Rust type name.
+/// An Ident in PascalCase suitable for a Rust type name.  Non-ASCII
+/// characters are [transliterated](transliterate) first.
 /// 
 /// # Examples
 /// 
@@ -50,9 +138,20 @@ pub fn rust_type_for_bits(bits: usize) -> String {
 /// assert_eq!(to_pascal_case("field_name"), format_ident!("FieldName"));
 /// assert_eq!(to_pascal_case("SSR"), format_ident!("Ssr"));
 /// ```
+///
+/// # Panics
+///
+/// Panics if the name does not give an identifier, see
+/// [`try_to_pascal_case`].
 pub fn to_pascal_case(name: &str) -> Ident {
-    let pascal = name
-        .split(|c: char| c == '_' || c == '-')
+    try_to_pascal_case(name).unwrap_or_else(|| panic!("Name \"{}\" does not give an identifier", name))
+}
+
+/// Converts a name to PascalCase as [`to_pascal_case`] does, or returns
+/// `None` if it does not give an identifier, e.g. `"3d"`.
+pub fn try_to_pascal_case(name: &str) -> Option<Ident> {
+    let pascal = transliterate(name)
+        .split(['_', '-'])
         .filter(|s| !s.is_empty())
         .map(|word| {
             let mut chars = word.chars();
@@ -66,7 +165,37 @@ pub fn to_pascal_case(name: &str) -> Ident {
         })
         .collect::<String>();
     
-    format_ident!("{}", pascal)
+    is_identifier(&pascal).then(|| format_ident!("{}", pascal))
+}
+
+/// Converts an item title to a PascalCase type name.
+///
+/// Every run of characters other than letters and digits separates words,
+/// and words are capitalized as in [`to_pascal_case`], so
+/// `"Mode-3/A Code"` gives `Mode3ACode` and `"SSR Reply"` gives `SsrReply`.
+/// Non-ASCII characters are [transliterated](transliterate) first.
+///
+/// # Panics
+///
+/// Panics if the title has no letters or digits, or starts with a digit.
+pub fn title_to_type_name(title: &str) -> Ident {
+    try_title_to_type_name(title).unwrap_or_else(|| panic!("Item title \"{}\" does not give a type name", title))
+}
+
+/// Converts an item title to a type name as [`title_to_type_name`] does,
+/// or returns `None` if the title has no letters or digits, or starts
+/// with a digit.
+pub fn try_title_to_type_name(title: &str) -> Option<Ident> {
+    let words: String = transliterate(title)
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().unwrap_or_default();
+            format!("{}{}", first.to_ascii_uppercase(), chars.as_str().to_ascii_lowercase())
+        })
+        .collect();
+    words.starts_with(|c: char| c.is_ascii_alphabetic()).then(|| format_ident!("{}", words))
 }
 
 /// Converts a name to snake_case for field names.
@@ -77,7 +206,8 @@ pub fn to_pascal_case(name: &str) -> Ident {
 /// 
 /// # Returns
 /// 
-/// An Ident in snake_case suitable for a Rust field name.
+/// An Ident in snake_case suitable for a Rust field name.  Non-ASCII
+/// characters are [transliterated](transliterate) first.
 /// 
 /// # Examples
 /// 
@@ -87,7 +217,19 @@ pub fn to_pascal_case(name: &str) -> Ident {
 /// assert_eq!(to_snake_case("FieldName"), format_ident!("field_name"));
 /// assert_eq!(to_snake_case("SSR"), format_ident!("ssr"));
 /// ```
+///
+/// # Panics
+///
+/// Panics if the name does not give an identifier, see
+/// [`try_to_snake_case`].
 pub fn to_snake_case(name: &str) -> Ident {
+    try_to_snake_case(name).unwrap_or_else(|| panic!("Name \"{}\" does not give an identifier", name))
+}
+
+/// Converts a name to snake_case as [`to_snake_case`] does, or returns
+/// `None` if it does not give an identifier, e.g. `"3d"` or `"a b"`.
+pub fn try_to_snake_case(name: &str) -> Option<Ident> {
+    let name = transliterate(name);
     let snake = name
         .chars()
         .enumerate()
@@ -111,7 +253,14 @@ pub fn to_snake_case(name: &str) -> Ident {
         .collect::<String>()
         .replace('-', "_");
     
-    format_ident!("{}", snake)
+    is_identifier(&snake).then(|| format_ident!("{}", snake))
+}
+
+/// Returns `true` if `name` is made of ASCII letters, digits and
+/// underscores, and doesn't start with a digit.
+fn is_identifier(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
 }
 
 /// Generates a unique type name for a nested structure.
@@ -142,28 +291,61 @@ pub fn nested_type_name(parent_name: &str, suffix: &str) -> Ident {
 
 /// Calculates the FSPEC byte and bit position from an FRN.
 ///
-/// ASTERIX FSPEC layout (each byte has 7 data bits + 1 FX bit):
-/// - FRN 0 → byte 0, bit 7 (0x80)
-/// - FRN 1 → byte 0, bit 6 (0x40)
-/// - FRN 6 → byte 0, bit 1 (0x02)
+/// FRNs are 1-based, as in the ASTERIX specifications.  Each FSPEC byte
+/// has 7 data bits + 1 FX bit, so FX bits are never addressed:
+/// - FRN 1 → byte 0, bit 7 (0x80)
+/// - FRN 2 → byte 0, bit 6 (0x40)
+/// - FRN 7 → byte 0, bit 1 (0x02)
 /// - (bit 0 is FX bit, not used for items)
-/// - FRN 7 → byte 1, bit 7 (0x80)
-/// - FRN 8 → byte 1, bit 6 (0x40)
+/// - FRN 8 → byte 1, bit 7 (0x80)
+/// - FRN 9 → byte 1, bit 6 (0x40)
+///
+/// Earlier versions took 0-based FRNs; callers passing those must add one.
 ///
 /// # Arguments
 ///
-/// * `frn` - The Field Reference Number (0-indexed)
+/// * `frn` - The Field Reference Number (1-indexed)
 ///
 /// # Returns
 ///
-/// A tuple of (byte_index, bit_position) for use with Fspec::set().
-/// The bit_position is passed directly to Fspec which does `1 << (7 - bit)`.
+/// A tuple of (byte_index, bit_position) as in `Fspec::set()`, for the
+/// `DataItem` constants and exports that address FSPEC bytes.  Generated
+/// code uses `Fspec::set_frn()` and `Fspec::is_frn_set()` directly.
+///
+/// # Panics
+///
+/// Panics if `frn` is 0.
 pub fn frn_to_fspec_position(frn: usize) -> (usize, u8) {
-    let byte = frn / 7;  // 7 items per byte (bit 0 is FX)
-    let bit = frn % 7;   // Position 0-6, Fspec will compute 1 << (7 - bit)
+    assert!(frn >= 1, "FRNs start at 1");
+    let position = frn - 1;
+    let byte = position / 7;  // 7 items per byte (bit 0 is FX)
+    let bit = position % 7;   // Position 0-6, Fspec will compute 1 << (7 - bit)
     (byte, bit as u8)
 }
 
+/// Returns the `#[cfg(feature = "...")]` attribute of a feature-gated item,
+/// or nothing for an item that is always compiled.
+pub fn feature_cfg(feature: &Option<String>) -> TokenStream {
+    match feature {
+        Some(feature) => quote! { #[cfg(feature = #feature)] },
+        None => quote! {},
+    }
+}
+
+/// Emits a check failing decode when a compiled-out item is present in the
+/// FSPEC, since the rest of the record can't be located without it.
+pub fn compiled_out_check(feature: &Option<String>, frn: usize) -> TokenStream {
+    match feature {
+        Some(feature) => quote! {
+            #[cfg(not(feature = #feature))]
+            if fspec.is_frn_set(#frn) {
+                return Err(DecodeError::InvalidData("item compiled out by a cargo feature"));
+            }
+        },
+        None => quote! {},
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -183,17 +365,36 @@ mod tests {
 
     #[test]
     fn test_frn_to_fspec_position() {
-        // FRN 0-6 map to byte 0, bits 0-6 (Fspec computes 1 << (7-bit))
-        assert_eq!(frn_to_fspec_position(0), (0, 0)); // → 0x80
-        assert_eq!(frn_to_fspec_position(1), (0, 1)); // → 0x40
-        assert_eq!(frn_to_fspec_position(6), (0, 6)); // → 0x02
-        // FRN 7-13 map to byte 1
-        assert_eq!(frn_to_fspec_position(7), (1, 0)); // → 0x80 in byte 1
-        assert_eq!(frn_to_fspec_position(13), (1, 6)); // → 0x02 in byte 1
-        // FRN 14+ map to byte 2
-        assert_eq!(frn_to_fspec_position(14), (2, 0)); // → 0x80 in byte 2
+        // FRN 1-7 map to byte 0, bits 0-6 (Fspec computes 1 << (7-bit))
+        assert_eq!(frn_to_fspec_position(1), (0, 0)); // → 0x80
+        assert_eq!(frn_to_fspec_position(2), (0, 1)); // → 0x40
+        assert_eq!(frn_to_fspec_position(7), (0, 6)); // → 0x02
+        // FRN 8-14 map to byte 1, skipping the FX bit of byte 0
+        assert_eq!(frn_to_fspec_position(8), (1, 0)); // → 0x80 in byte 1
+        assert_eq!(frn_to_fspec_position(14), (1, 6)); // → 0x02 in byte 1
+        // FRN 15+ map to byte 2
+        assert_eq!(frn_to_fspec_position(15), (2, 0)); // → 0x80 in byte 2
+    }
+
+    #[test]
+    #[should_panic(expected = "FRNs start at 1")]
+    fn test_frn_zero_is_rejected() {
+        frn_to_fspec_position(0);
     }
     
+    #[test]
+    fn test_title_to_type_name() {
+        assert_eq!(title_to_type_name("Data Source Identifier"), format_ident!("DataSourceIdentifier"));
+        assert_eq!(title_to_type_name("Mode-3/A Code in Octal"), format_ident!("Mode3ACodeInOctal"));
+        assert_eq!(title_to_type_name("SSR Reply"), format_ident!("SsrReply"));
+    }
+
+    #[test]
+    #[should_panic(expected = "does not give a type name")]
+    fn test_title_starting_with_digit_is_rejected() {
+        title_to_type_name("3D Position");
+    }
+
     #[test]
     fn test_to_pascal_case() {
         assert_eq!(to_pascal_case("test"), format_ident!("Test"));
@@ -208,4 +409,31 @@ mod tests {
         assert_eq!(to_snake_case("FieldName"), format_ident!("field_name"));
         assert_eq!(to_snake_case("SSR"), format_ident!("ssr"));
     }
+
+    #[test]
+    fn test_names_not_giving_identifiers() {
+        assert_eq!(try_to_snake_case("3d_range"), None);
+        assert_eq!(try_to_snake_case("ground speed"), None);
+        assert_eq!(try_to_pascal_case("__"), None);
+        assert_eq!(try_title_to_type_name("3D Position"), None);
+    }
+
+    #[test]
+    fn test_transliterate() {
+        assert_eq!(transliterate("Ångström"), "Angstrom");
+        assert_eq!(transliterate("Œuvre"), "Oeuvre");
+        assert_eq!(transliterate("°C"), "u00b0_C");
+        assert_eq!(transliterate("height_°"), "height_u00b0");
+        assert_eq!(transliterate("α-β"), "u03b1_u03b2");
+    }
+
+    #[test]
+    fn test_non_ascii_names_become_identifiers() {
+        assert_eq!(to_snake_case("VitesseSol"), format_ident!("vitesse_sol"));
+        assert_eq!(to_snake_case("VélocitéSol"), format_ident!("velocite_sol"));
+        assert_eq!(to_pascal_case("état_cible"), format_ident!("EtatCible"));
+        assert_eq!(to_pascal_case("range_km²"), format_ident!("RangeKmU00b2"));
+        assert_eq!(to_snake_case("range_km²"), format_ident!("range_km_u00b2"));
+        assert_eq!(title_to_type_name("Qualité du Signal"), format_ident!("QualiteDuSignal"));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,255 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+
+use crate::transform::lower_ir::{
+    FieldDescriptor, LoweredEnum, LoweredItem, LoweredItemKind, LoweredPart, LoweredRecord, LoweredSubItemKind,
+    RepetitiveStorage, UnknownValues,
+};
+use super::utils::feature_cfg;
+
+/// Generates the `ToValue` and `FromValue` impls of an item, its enums and
+/// the structs it is made of.
+///
+/// Structs are maps keyed by field name, extended items maps of their
+/// parts, with their unknown extensions under `extensions` if there are
+/// any, compound items maps of their sub-items and repetitive items lists
+/// of their elements.
+pub fn generate_item_values(item: &LoweredItem) -> TokenStream {
+    let item_name = &item.name;
+    let enum_impls = item.enums.iter().map(enum_values);
+
+    let struct_impls = match &item.kind {
+        LoweredItemKind::Simple { .. } if item.is_reserved => quote! {
+            impl ToValue for #item_name {
+                fn to_value(&self) -> value::Value {
+                    value::Value::Map(value::ValueMap::new())
+                }
+            }
+
+            impl FromValue for #item_name {
+                fn from_value(input: &value::Value) -> Result<Self, value::ValueError> {
+                    value::check_keys(value::map(input)?, &[])?;
+                    Ok(Self)
+                }
+            }
+        },
+        LoweredItemKind::Simple { fields, .. } => fields_values(item_name, fields),
+        LoweredItemKind::Extended { parts } => extended_values(item_name, parts),
+        LoweredItemKind::Repetitive { element_type_name, count, storage, fields, .. } => {
+            repetitive_values(item_name, element_type_name, *count, *storage, fields)
+        }
+        LoweredItemKind::Compound { sub_items } => {
+            let sub_impls = sub_items.iter().map(|sub| match &sub.kind {
+                LoweredSubItemKind::Simple { fields, .. } => fields_values(&sub.struct_name, fields),
+                LoweredSubItemKind::Extended { parts } => extended_values(&sub.struct_name, parts),
+                LoweredSubItemKind::Repetitive { element_type_name, count, storage, fields, .. } => {
+                    repetitive_values(&sub.struct_name, element_type_name, *count, *storage, fields)
+                }
+            });
+            let main_impl = map_values(item_name, sub_items.iter().map(|sub| &sub.field_name), None);
+            quote! {
+                #(#sub_impls)*
+                #main_impl
+            }
+        }
+    };
+
+    quote! {
+        #(#enum_impls)*
+        #struct_impls
+    }
+}
+
+/// The impls of a struct of `fields`.
+fn fields_values(name: &Ident, fields: &[FieldDescriptor]) -> TokenStream {
+    map_values(name, fields.iter().map(|field| &field.name), None)
+}
+
+/// The impls of a struct converted to a map of its `fields`, plus the
+/// `extensions` bytes of an extended item, kept only if there are any.
+fn map_values<'a>(
+    name: &Ident,
+    fields: impl Iterator<Item = &'a Ident> + Clone,
+    extensions: Option<&Ident>,
+) -> TokenStream {
+    let inserts = fields.clone().map(|field| {
+        let key = field.to_string();
+        quote! { map.insert(#key.to_string(), self.#field.to_value()); }
+    });
+    let field_inits = fields.clone().map(|field| {
+        let key = field.to_string();
+        quote! { #field: value::field(map, #key)? }
+    });
+    let mut keys: Vec<String> = fields.map(ToString::to_string).collect();
+
+    let (insert_extensions, extensions_init) = match extensions {
+        Some(extensions) => {
+            let key = extensions.to_string();
+            keys.push(key.clone());
+            (
+                Some(quote! {
+                    if !self.#extensions.is_empty() {
+                        map.insert(#key.to_string(), self.#extensions.to_value());
+                    }
+                }),
+                Some(quote! { #extensions: value::field::<Option<Vec<u8>>>(map, #key)?.unwrap_or_default(), }),
+            )
+        }
+        None => (None, None),
+    };
+
+    quote! {
+        impl ToValue for #name {
+            fn to_value(&self) -> value::Value {
+                let mut map = value::ValueMap::new();
+                #(#inserts)*
+                #insert_extensions
+                value::Value::Map(map)
+            }
+        }
+
+        impl FromValue for #name {
+            fn from_value(input: &value::Value) -> Result<Self, value::ValueError> {
+                let map = value::map(input)?;
+                value::check_keys(map, &[#(#keys),*])?;
+                Ok(Self {
+                    #(#field_inits,)*
+                    #extensions_init
+                })
+            }
+        }
+    }
+}
+
+/// The impls of an extended item and its parts.
+fn extended_values(name: &Ident, parts: &[LoweredPart]) -> TokenStream {
+    let part_impls = parts.iter().map(|part| fields_values(&part.struct_name, &part.fields));
+    let extensions = quote::format_ident!("extensions");
+    let main_impl = map_values(name, parts.iter().map(|part| &part.field_name), Some(&extensions));
+
+    quote! {
+        #(#part_impls)*
+        #main_impl
+    }
+}
+
+/// The impls of a repetitive item, a list of its elements, and of its
+/// elements.  Elements stored in a `heapless::Vec` can't outnumber its
+/// capacity.
+fn repetitive_values(
+    name: &Ident,
+    element_type_name: &Ident,
+    count: usize,
+    storage: RepetitiveStorage,
+    fields: &[FieldDescriptor],
+) -> TokenStream {
+    let element_impl = fields_values(element_type_name, fields);
+    let capacity_check = (storage == RepetitiveStorage::Heapless).then(|| quote! {
+        if list.len() > #count {
+            let kind = value::ValueErrorKind::WrongLength { len: list.len(), expected: #count };
+            return Err(value::ValueError::new(kind));
+        }
+    });
+
+    quote! {
+        #element_impl
+
+        impl ToValue for #name {
+            fn to_value(&self) -> value::Value {
+                value::Value::List(self.items.iter().map(ToValue::to_value).collect())
+            }
+        }
+
+        impl FromValue for #name {
+            fn from_value(input: &value::Value) -> Result<Self, value::ValueError> {
+                let list = value::list(input)?;
+                #capacity_check
+                let items = list
+                    .iter()
+                    .enumerate()
+                    .map(|(i, element)| {
+                        #element_type_name::from_value(element).map_err(|error| error.within(&i.to_string()))
+                    })
+                    .collect::<Result<_, _>>()?;
+                Ok(Self { items })
+            }
+        }
+    }
+}
+
+/// The impls of an enum: the name of its variant, or the raw value of an
+/// unknown one.
+fn enum_values(lowered: &LoweredEnum) -> TokenStream {
+    let enum_name = &lowered.name;
+    let names: Vec<_> = lowered.variants.iter().map(|variant| variant.name.to_string()).collect();
+    let variants: Vec<_> = lowered.variants.iter().map(|variant| &variant.name).collect();
+    let unknown_arm = (lowered.unknown == UnknownValues::Preserve)
+        .then(|| quote! { #enum_name::Unknown(raw) => value::Value::Number(*raw as u128), });
+
+    quote! {
+        impl ToValue for #enum_name {
+            fn to_value(&self) -> value::Value {
+                match self {
+                    #(#enum_name::#variants => value::Value::Text(#names.to_string()),)*
+                    #unknown_arm
+                }
+            }
+        }
+
+        impl FromValue for #enum_name {
+            fn from_value(input: &value::Value) -> Result<Self, value::ValueError> {
+                value::variant(input, &[#((#names, #enum_name::#variants)),*])
+            }
+        }
+    }
+}
+
+/// Generates `to_value_map` and `from_value_map` on the record, keying the
+/// items present by their field names.
+pub fn generate_record_values(record: &LoweredRecord) -> TokenStream {
+    let record_name = &record.name;
+
+    let inserts = record.entries.iter().map(|entry| {
+        let field_name = &entry.field_name;
+        let key = field_name.to_string();
+        let cfg = feature_cfg(&entry.feature);
+        quote! {
+            #cfg
+            if let Some(item) = &self.#field_name {
+                map.insert(#key.to_string(), item.to_value());
+            };
+        }
+    });
+    let field_inits = record.entries.iter().map(|entry| {
+        let field_name = &entry.field_name;
+        let key = field_name.to_string();
+        let cfg = feature_cfg(&entry.feature);
+        quote! {
+            #cfg
+            #field_name: value::field(map, #key)?,
+        }
+    });
+    let keys = record.entries.iter().map(|entry| entry.field_name.to_string());
+
+    quote! {
+        impl #record_name {
+            /// Returns the items present as generic values, keyed by their
+            /// field names, e.g. `item010`.
+            pub fn to_value_map(&self) -> value::ValueMap {
+                let mut map = value::ValueMap::new();
+                #(#inserts)*
+                map
+            }
+
+            /// Builds a record from generic values keyed by item, as
+            /// returned by [`Self::to_value_map`].  Missing items are
+            /// absent.
+            pub fn from_value_map(map: &value::ValueMap) -> Result<Self, value::ValueError> {
+                value::check_keys(map, &[#(#keys),*])?;
+                Ok(Self {
+                    #(#field_inits)*
+                })
+            }
+        }
+    }
+}
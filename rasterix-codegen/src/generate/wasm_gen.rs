@@ -0,0 +1,111 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use crate::transform::lower_ir::{LoweredIR, LoweredItem, LoweredRecord};
+use super::backend::CodegenBackend;
+use super::json_gen::{generate_item_json, generate_record_json};
+
+/// Backend emitting `wasm-bindgen` wrappers over the generated Rust types.
+///
+/// The output is a `<module>_wasm` module that refers to the generated
+/// types as `super::<module>`, so it belongs in the same file as the
+/// generated code.  It implements [`ToJson`](rasterix_core::ToJson) for
+/// every generated type and exports two functions to JavaScript, both
+/// taking a `Uint8Array` and returning a JSON string:
+///
+/// ```js
+/// import { cat048_decode_block } from "./pkg/cat048.js";
+///
+/// const block = JSON.parse(cat048_decode_block(bytes));
+/// console.log(block.records[0].item010.sac);
+/// ```
+///
+/// `<module>_decode_record` decodes a single record without block header.
+/// Decoding errors are thrown as JavaScript strings.  Enums are written as
+/// their variant name, or as the raw value when unknown; items missing from
+/// a record are left out.
+#[derive(Debug, Default)]
+pub struct WasmBackend {
+    record: TokenStream,
+    items: Vec<TokenStream>,
+}
+
+impl CodegenBackend for WasmBackend {
+    type Output = TokenStream;
+
+    fn emit_record(&mut self, record: &LoweredRecord) {
+        self.record = generate_record_json(record);
+    }
+
+    fn emit_struct(&mut self, item: &LoweredItem) {
+        self.items.push(generate_item_json(item));
+    }
+
+    fn finalize(self, lowered: &LoweredIR) -> TokenStream {
+        let module_name = &lowered.module_name;
+        let wasm_module = format_ident!("{}_wasm", module_name);
+        let decode_block = format_ident!("{}_decode_block", module_name);
+        let decode_record = format_ident!("{}_decode_record", module_name);
+        let record = self.record;
+        let items = self.items;
+
+        quote! {
+            /// JSON output and `wasm-bindgen` exports for the generated types.
+            pub mod #wasm_module {
+                use super::#module_name::*;
+                use rasterix::rcore::{BitReader, Decode, DecodeError};
+                use rasterix::rcore::json::{JsonObject, ToJson};
+                use wasm_bindgen::prelude::*;
+
+                fn decode_json<T: Decode + ToJson>(data: &[u8]) -> Result<String, DecodeError> {
+                    let mut reader = BitReader::new(std::io::Cursor::new(data));
+                    Ok(T::decode(&mut reader)?.to_json())
+                }
+
+                /// Decodes a data block, including its header, to JSON.
+                #[wasm_bindgen]
+                pub fn #decode_block(data: &[u8]) -> Result<String, JsValue> {
+                    decode_json::<DataBlock>(data).map_err(|e| JsValue::from_str(&format!("{:?}", e)))
+                }
+
+                /// Decodes one record (FSPEC and items, no block header) to JSON.
+                #[wasm_bindgen]
+                pub fn #decode_record(data: &[u8]) -> Result<String, JsValue> {
+                    decode_json::<Record>(data).map_err(|e| JsValue::from_str(&format!("{:?}", e)))
+                }
+
+                #record
+                #(#items)*
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::backend::run_backend;
+    use crate::parse::parser::parse_category;
+    use crate::transform::{lower, transformer::to_ir};
+
+    #[test]
+    fn test_wasm_exports() {
+        let xml = r#"<category id="48">
+            <item id="10" frn="1">
+                <fixed bytes="2">
+                    <field name="sac" bits="8"/>
+                    <field name="sic" bits="8"/>
+                </fixed>
+            </item>
+        </category>"#;
+
+        let lowered = lower(&to_ir(parse_category(xml).unwrap()).unwrap()).unwrap();
+        let code = run_backend(WasmBackend::default(), &lowered).to_string();
+
+        assert!(code.contains("pub mod cat048_wasm"));
+        assert!(code.contains("pub fn cat048_decode_block (data : & [u8]) -> Result < String , JsValue >"));
+        assert!(code.contains("pub fn cat048_decode_record (data : & [u8]) -> Result < String , JsValue >"));
+        assert!(code.contains("impl ToJson for Item010"));
+        assert!(code.contains("impl ToJson for Record"));
+    }
+}
@@ -2,6 +2,8 @@ pub mod generate;
 pub mod transform;
 pub mod parse;
 pub mod builder;
+pub mod dynamic;
+pub mod sink;
 
 #[cfg(test)]
 mod tests {
@@ -1,2 +1,3 @@
 pub mod xml_model;
+pub mod rename_model;
 pub mod parser;
\ No newline at end of file
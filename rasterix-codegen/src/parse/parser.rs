@@ -1,4 +1,8 @@
-use crate::parse::xml_model::Category;
+use quick_xml::events::{BytesEnd, BytesStart, Event};
+use quick_xml::{Reader, Writer};
+
+use crate::parse::rename_model::RenameConfig;
+use crate::parse::xml_model::{Category, FeatureConfig};
 
 /// Parses the given XML string into a Category struct.
 /// 
@@ -9,4 +13,57 @@ use crate::parse::xml_model::Category;
 /// * `Result<Category, quick_xml::DeError>` - The parsed Category or an error if parsing fails.
 pub fn parse_category(xml: &str) -> Result<Category, quick_xml::DeError> {
     quick_xml::de::from_str(xml)
-}
\ No newline at end of file
+}
+
+/// Parses the given XML string into a Category struct, reading elements
+/// with one of the given `tags` as [`Custom`](crate::parse::xml_model::Custom)
+/// elements, e.g. `<crc poly="0x1021"/>` as
+/// `<custom tag="crc" poly="0x1021"/>`.
+pub fn parse_category_with_elements(xml: &str, tags: &[&str]) -> Result<Category, quick_xml::DeError> {
+    if tags.is_empty() {
+        return parse_category(xml);
+    }
+    let xml = rename_custom_elements(xml, tags).map_err(quick_xml::DeError::InvalidXml)?;
+    quick_xml::de::from_str(&xml)
+}
+
+/// Rewrites the elements with one of `tags` as `custom` elements.
+fn rename_custom_elements(xml: &str, tags: &[&str]) -> Result<String, quick_xml::Error> {
+    let is_custom = |name: &[u8]| tags.iter().any(|tag| tag.as_bytes() == name);
+    let custom = |start: &BytesStart| -> Result<BytesStart<'static>, quick_xml::Error> {
+        let tag = String::from_utf8_lossy(start.name().as_ref()).into_owned();
+        let mut custom = BytesStart::new("custom");
+        custom.push_attribute(("tag", tag.as_str()));
+        for attribute in start.attributes() {
+            custom.push_attribute(attribute?);
+        }
+        Ok(custom)
+    };
+
+    let mut reader = Reader::from_str(xml);
+    let mut writer = Writer::new(Vec::new());
+    loop {
+        match reader.read_event()? {
+            Event::Eof => break,
+            Event::Empty(start) if is_custom(start.name().as_ref()) => {
+                writer.write_event(Event::Empty(custom(&start)?))?
+            }
+            Event::Start(start) if is_custom(start.name().as_ref()) => {
+                writer.write_event(Event::Start(custom(&start)?))?
+            }
+            Event::End(end) if is_custom(end.name().as_ref()) => writer.write_event(Event::End(BytesEnd::new("custom")))?,
+            event => writer.write_event(event)?,
+        }
+    }
+    Ok(String::from_utf8(writer.into_inner()).expect("rewritten XML is UTF-8"))
+}
+
+/// Parses a feature configuration, assigning items to cargo features.
+pub fn parse_features(xml: &str) -> Result<FeatureConfig, quick_xml::DeError> {
+    quick_xml::de::from_str(xml)
+}
+
+/// Parses a symbol rename configuration (`rusterix.toml`).
+pub fn parse_renames(toml: &str) -> Result<RenameConfig, toml::de::Error> {
+    toml::from_str(toml)
+}
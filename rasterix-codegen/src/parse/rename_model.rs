@@ -0,0 +1,47 @@
+//! Data model of the symbol rename configuration (`rusterix.toml`).
+//!
+//! The file overrides generated names without editing the XML definitions.
+//! Tables are keyed by the generated module (`cat048`) and the record field
+//! of the item (`item020`), and map generated names to new ones:
+//!
+//! ```toml
+//! [cat048.item020.types]
+//! Item020 = "TargetReport"
+//! TargetType = "DetectionKind"
+//!
+//! [cat048.item020.fields]
+//! typ = "detection_type"
+//!
+//! [cat048.item020.variants.TargetType]
+//! Psr = "Primary"
+//! ```
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+/// Symbol overrides for any number of categories.
+#[derive(Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct RenameConfig {
+    /// Items to rename in each category, by module name and then by the
+    /// record field of the item.
+    pub categories: BTreeMap<String, BTreeMap<String, ItemRenames>>,
+}
+
+/// Overrides for the symbols of a single item.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ItemRenames {
+    /// New names of the item type and its nested struct and enum types.
+    #[serde(default)]
+    pub types: BTreeMap<String, String>,
+
+    /// New names of struct fields, including parts and sub-items.
+    #[serde(default)]
+    pub fields: BTreeMap<String, String>,
+
+    /// New names of enum variants, by enum type (before renaming).
+    #[serde(default)]
+    pub variants: BTreeMap<String, BTreeMap<String, String>>,
+}
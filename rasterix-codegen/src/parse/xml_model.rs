@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use serde::Deserialize;
 
 //
@@ -19,15 +21,76 @@ pub struct Category {
 /// Represents a single data item within a category.
 #[derive(Debug, Deserialize)]
 pub struct Item {
+    /// Item number, up to three digits (e.g. 010, 500)
     #[serde(rename = "@id")]
-    pub id: u8,
+    pub id: u16,
 
+    /// Field Reference Number, counted from 1 as in the specifications
     #[serde(rename = "@frn")]
     pub frn: u8,
 
+    /// Title of the item (e.g. "Data Source Identifier")
+    #[serde(rename = "@name", default)]
+    pub name: Option<String>,
+
+    /// Short symbolic name of the item (e.g. "DSI")
+    #[serde(rename = "@short", default)]
+    pub short: Option<String>,
+
+    /// Whether the item is a placeholder made only of spare bits, e.g. an
+    /// FRN reserved for future use
+    #[serde(rename = "@reserved", default)]
+    pub reserved: bool,
+
+    /// Whether every record must hold the item, checked by the generated
+    /// conformance checks
+    #[serde(rename = "@mandatory", default)]
+    pub mandatory: bool,
+
     /// The structural definition of this item
     #[serde(rename = "$value")]
     pub data: ItemStructure,
+
+    /// Worked examples from the specification, generated as tests
+    #[serde(rename = "example", default)]
+    pub examples: Vec<Example>,
+}
+
+/// A worked example of an item: its encoding and some of the field values
+/// it decodes to.
+///
+/// ```xml
+/// <example bytes="0x01 0x02" description="SAC 1, SIC 2">
+///     <expect field="sac" value="1"/>
+///     <expect field="sic" value="2"/>
+/// </example>
+/// ```
+#[derive(Debug, Deserialize)]
+pub struct Example {
+    /// Bytes of the item, in hex, optionally prefixed with `0x` and
+    /// separated by whitespace
+    #[serde(rename = "@bytes")]
+    pub bytes: String,
+
+    #[serde(rename = "@description", default)]
+    pub description: Option<String>,
+
+    #[serde(rename = "expect", default)]
+    pub expects: Vec<Expect>,
+}
+
+/// Value a field of an example decodes to.
+#[derive(Debug, Deserialize)]
+pub struct Expect {
+    /// Path of the field within the item, as in its JSON: `sac`, `part1.c`,
+    /// `sub0.flags` or `2.azimuth` for the third repetition
+    #[serde(rename = "@field")]
+    pub field: String,
+
+    /// Raw value in decimal or `0x` hex, text of a string field, or
+    /// variant name of an enum field
+    #[serde(rename = "@value")]
+    pub value: String,
 }
 
 //
@@ -92,6 +155,14 @@ pub struct RepetitiveItem {
     #[serde(rename = "@counter")]
     pub counter: String,
 
+    /// Bounds on a REP byte, which exact counts don't have: rejected when
+    /// transformed rather than silently ignored.
+    #[serde(rename = "@min_rep")]
+    pub min_rep: Option<String>,
+
+    #[serde(rename = "@max_rep")]
+    pub max_rep: Option<String>,
+
     #[serde(rename = "$value", default)]
     pub elements: Vec<Element>,
 }
@@ -137,6 +208,15 @@ pub enum Element {
     
     #[serde(rename = "spare")]
     Spare(Spare),
+
+    #[serde(rename = "align")]
+    Align(Align),
+
+    #[serde(rename = "derived")]
+    Derived(Derived),
+
+    #[serde(rename = "custom")]
+    Custom(Custom),
 }
 
 /// A basic data field.
@@ -148,9 +228,39 @@ pub struct Field {
     #[serde(rename = "@bits")]
     pub bits: usize,
 
-    // defines the type of the field, e.g., "string" or "numeric"
+    // defines the type of the field: "numeric" (default), "string", or a
+    // numeric value with a typed accessor: "flight_level" or "altitude"
     #[serde(rename = "@type", default = "default_type")]
     pub field_type: String,
+
+    /// Revision of the definition that deprecated the field (e.g. "1.31")
+    #[serde(rename = "@deprecated_since", default)]
+    pub deprecated_since: Option<String>,
+
+    /// Name of the field in earlier revisions of the definition
+    #[serde(rename = "@renamed_from", default)]
+    pub renamed_from: Option<String>,
+
+    /// Description of the field from the specification
+    #[serde(rename = "@description", default)]
+    pub description: Option<String>,
+
+    /// Smallest valid raw value, checked by the generated `validate`
+    #[serde(rename = "@min", default)]
+    pub min: Option<u64>,
+
+    /// Largest valid raw value, checked by the generated `validate`
+    #[serde(rename = "@max", default)]
+    pub max: Option<u64>,
+
+    /// Step of the valid raw values, checked by the generated `validate`
+    #[serde(rename = "@multiple_of", default)]
+    pub multiple_of: Option<u64>,
+
+    /// Order of the bytes of the value: "big" (default), or "little" for
+    /// the fields of non-standard feeds sent least significant byte first
+    #[serde(rename = "@byte_order", default)]
+    pub byte_order: Option<String>,
 }
 /// Default value for the type field.
 fn default_type() -> String {
@@ -182,6 +292,57 @@ pub struct Spare {
     pub bits: usize,
 }
 
+/// Spare bits up to the next multiple of `bits`, counted from the start of
+/// the enclosing element list.
+#[derive(Debug, Deserialize)]
+pub struct Align {
+    #[serde(rename = "@bits")]
+    pub bits: usize,
+}
+
+/// A value computed from the fields of the same structure, generated as a
+/// method rather than a struct field.
+#[derive(Debug, Deserialize)]
+pub struct Derived {
+    #[serde(rename = "@name")]
+    pub name: String,
+
+    /// Rust expression over the numeric fields of the structure, each an
+    /// `f64` (e.g. "gsp * 0.22")
+    #[serde(rename = "@expr")]
+    pub expr: String,
+
+    /// Numeric type of the value: "f64" (default), "f32" or an integer type
+    #[serde(rename = "@type", default = "default_derived_type")]
+    pub rust_type: String,
+
+    /// Description of the value
+    #[serde(rename = "@description", default)]
+    pub description: Option<String>,
+
+    /// Role of the value in a position reported relative to the sensor:
+    /// "range", "azimuth", "east" or "north"
+    #[serde(rename = "@geo", default)]
+    pub geo: Option<String>,
+}
+/// Default value for the type of a derived value.
+fn default_derived_type() -> String {
+    "f64".into()
+}
+
+/// An element handled by a plugin, see
+/// [`parse_category_with_elements`](super::parser::parse_category_with_elements).
+#[derive(Debug, Deserialize)]
+pub struct Custom {
+    /// Tag of the element as written in the definition (e.g. "crc")
+    #[serde(rename = "@tag")]
+    pub tag: String,
+
+    /// Other attributes, by name prefixed with `@`
+    #[serde(flatten)]
+    pub attributes: BTreeMap<String, String>,
+}
+
 /// An enumeration field with named values.
 #[derive(Debug, Deserialize)]
 pub struct Enum {
@@ -193,6 +354,23 @@ pub struct Enum {
 
     #[serde(rename = "value", default)]
     pub values: Vec<Value>,
+
+    /// Representation of values without a variant: "preserve" (default),
+    /// "error" or "map_to=NAME"
+    #[serde(rename = "@unknown", default)]
+    pub unknown: Option<String>,
+
+    /// Revision of the definition that deprecated the enum field
+    #[serde(rename = "@deprecated_since", default)]
+    pub deprecated_since: Option<String>,
+
+    /// Name of the enum field in earlier revisions of the definition
+    #[serde(rename = "@renamed_from", default)]
+    pub renamed_from: Option<String>,
+
+    /// Description of the enum field from the specification
+    #[serde(rename = "@description", default)]
+    pub description: Option<String>,
 }
 
 /// A single value within an enumeration.
@@ -203,4 +381,44 @@ pub struct Value {
 
     #[serde(rename = "@value")]
     pub value: String,
-}
\ No newline at end of file
+
+    /// Alternative name of the variant (e.g. "None")
+    #[serde(rename = "@alias", default)]
+    pub alias: Option<String>,
+}
+//
+// Feature configuration
+//
+
+/// Assigns items of a category to cargo features, so that the generated
+/// code for them is only compiled when the feature is enabled.
+///
+/// ```xml
+/// <features>
+///     <feature name="cat048_bds">
+///         <item id="250"/>
+///     </feature>
+/// </features>
+/// ```
+#[derive(Debug, Default, Deserialize)]
+pub struct FeatureConfig {
+    #[serde(rename = "feature", default)]
+    pub features: Vec<FeatureGroup>,
+}
+
+/// The items compiled under one cargo feature.
+#[derive(Debug, Deserialize)]
+pub struct FeatureGroup {
+    #[serde(rename = "@name")]
+    pub name: String,
+
+    #[serde(rename = "item", default)]
+    pub items: Vec<FeatureItem>,
+}
+
+/// Reference to an item by id.
+#[derive(Debug, Deserialize)]
+pub struct FeatureItem {
+    #[serde(rename = "@id")]
+    pub id: u16,
+}
@@ -0,0 +1,346 @@
+//! Writing decoded records into tables for offline analysis.
+//!
+//! [`RecordTable`] flattens the records of a category, as decoded by the
+//! [`DynamicDecoder`](crate::dynamic::DynamicDecoder), into rows with one
+//! column per field.  The column layout comes from the lowered definition,
+//! so every record of a category has the same columns, and absent items,
+//! parts and sub-items are `NULL`.
+//!
+//! Columns are named after the generated field path, e.g. `item010_sac`,
+//! `item020_part1_x` or `item100_sub2_y`.  Repetitive items are stored in a
+//! single text column holding a JSON array of their elements, and byte
+//! array fields in a text column holding their bytes in hex.
+//!
+//! The sinks write one table per category:
+//! - [`sqlite::SqliteSink`] (feature `sqlite`) into a SQLite database
+//! - [`parquet::ParquetSink`] (feature `parquet`) into a Parquet file
+
+#[cfg(feature = "parquet")]
+pub mod parquet;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+use rasterix_core::hex;
+use rasterix_core::json::{JsonObject, ToJson};
+
+use crate::dynamic::{DynamicItem, DynamicRecord, Field, FieldValue};
+use crate::transform::lower_ir::{
+    FieldDescriptor, FieldType, LoweredIR, LoweredItemKind, LoweredPart, LoweredSubItemKind,
+};
+
+/// Type of a table column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    /// Unsigned integer: numeric fields, raw enum values and the bits of
+    /// float fields.
+    Integer,
+    /// String fields, byte array fields as hex and repetitive items as
+    /// JSON.
+    Text,
+}
+
+/// A table column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Column {
+    pub name: String,
+    pub column_type: ColumnType,
+}
+
+/// A value in a row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cell {
+    Null,
+    Integer(u64),
+    Text(String),
+}
+
+/// Column layout of the records of one category.
+///
+/// # Example
+///
+/// ```
+/// use rasterix_codegen::dynamic::DynamicDecoder;
+/// use rasterix_codegen::parse::parser::parse_category;
+/// use rasterix_codegen::sink::{Cell, RecordTable};
+/// use rasterix_codegen::transform::{lower, transformer::to_ir};
+/// use rasterix_core::BitReader;
+///
+/// let xml = r#"<category id="48">
+///     <item id="10" frn="1">
+///         <fixed bytes="2">
+///             <field name="sac" bits="8"/>
+///             <field name="sic" bits="8"/>
+///         </fixed>
+///     </item>
+/// </category>"#;
+/// let lowered = lower(&to_ir(parse_category(xml).unwrap()).unwrap()).unwrap();
+/// let table = RecordTable::new(&lowered);
+/// let decoder = DynamicDecoder::new(lowered);
+///
+/// let data = [0x30, 0x00, 0x06, 0x80, 0x01, 0x02];
+/// let block = decoder.decode_block(&mut BitReader::new(&data[..])).unwrap();
+///
+/// assert_eq!(table.name, "cat048");
+/// assert_eq!(table.columns[1].name, "item010_sic");
+/// assert_eq!(table.row(&block.records[0]), vec![Cell::Integer(1), Cell::Integer(2)]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RecordTable {
+    /// Table name, the category module name (`cat048`).
+    pub name: String,
+    pub columns: Vec<Column>,
+    /// Structure of each record item, in record order.
+    items: Vec<LoweredItemKind>,
+}
+
+impl RecordTable {
+    /// Derives the columns of a lowered category.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a record entry refers to an item type that isn't defined.
+    pub fn new(lowered: &LoweredIR) -> Self {
+        let items: Vec<LoweredItemKind> = lowered
+            .record
+            .entries
+            .iter()
+            .map(|entry| {
+                lowered
+                    .items
+                    .iter()
+                    .find(|item| item.name == entry.type_name)
+                    .unwrap_or_else(|| panic!("record refers to undefined item {}", entry.type_name))
+                    .kind
+                    .clone()
+            })
+            .collect();
+
+        let mut columns = Vec::new();
+        for (entry, kind) in lowered.record.entries.iter().zip(&items) {
+            item_columns(&entry.field_name.to_string(), kind, &mut columns);
+        }
+
+        Self {
+            name: lowered.module_name.to_string(),
+            columns,
+            items,
+        }
+    }
+
+    /// Flattens a record decoded with the same definition into a row, with
+    /// one cell per column.
+    pub fn row(&self, record: &DynamicRecord) -> Vec<Cell> {
+        let mut row = Vec::with_capacity(self.columns.len());
+        for (i, kind) in self.items.iter().enumerate() {
+            let item = record.items.get(i).and_then(Option::as_ref);
+            item_cells(kind, item, &mut row);
+        }
+        row
+    }
+}
+
+fn column_type(field: &FieldDescriptor) -> ColumnType {
+    match field.type_tokens {
+        FieldType::Primitive(_)
+        | FieldType::OptionalPrimitive(_)
+        | FieldType::Enum(_)
+        | FieldType::OptionalEnum(_) => ColumnType::Integer,
+        FieldType::FixedString(_)
+        | FieldType::OptionalFixedString(_)
+        | FieldType::Bytes(_)
+        | FieldType::OptionalBytes(_) => ColumnType::Text,
+    }
+}
+
+fn field_columns(prefix: &str, fields: &[FieldDescriptor], columns: &mut Vec<Column>) {
+    columns.extend(fields.iter().map(|field| Column {
+        name: format!("{}_{}", prefix, field.name),
+        column_type: column_type(field),
+    }));
+}
+
+fn part_columns(prefix: &str, parts: &[LoweredPart], columns: &mut Vec<Column>) {
+    for part in parts {
+        field_columns(&format!("{}_{}", prefix, part.field_name), &part.fields, columns);
+    }
+}
+
+fn item_columns(prefix: &str, kind: &LoweredItemKind, columns: &mut Vec<Column>) {
+    match kind {
+        LoweredItemKind::Simple { fields, .. } => field_columns(prefix, fields, columns),
+        LoweredItemKind::Extended { parts } => part_columns(prefix, parts, columns),
+        LoweredItemKind::Repetitive { .. } => columns.push(Column {
+            name: prefix.to_string(),
+            column_type: ColumnType::Text,
+        }),
+        LoweredItemKind::Compound { sub_items } => {
+            for sub in sub_items {
+                let prefix = format!("{}_{}", prefix, sub.field_name);
+                match &sub.kind {
+                    LoweredSubItemKind::Simple { fields, .. } => field_columns(&prefix, fields, columns),
+                    LoweredSubItemKind::Extended { parts } => part_columns(&prefix, parts, columns),
+                    LoweredSubItemKind::Repetitive { .. } => columns.push(Column {
+                        name: prefix,
+                        column_type: ColumnType::Text,
+                    }),
+                }
+            }
+        }
+    }
+}
+
+fn field_cell(value: &FieldValue) -> Cell {
+    match value {
+        FieldValue::Unsigned(v) | FieldValue::Epb(Some(v)) => Cell::Integer(*v),
+        FieldValue::String(s) | FieldValue::EpbString(Some(s)) => Cell::Text(s.clone()),
+        FieldValue::Bytes(bytes) | FieldValue::EpbBytes(Some(bytes)) => Cell::Text(hex::encode_spaced(bytes)),
+        FieldValue::Epb(None) | FieldValue::EpbString(None) | FieldValue::EpbBytes(None) => Cell::Null,
+    }
+}
+
+/// Appends the cells of `field_count` fields, all `NULL` if absent.
+fn field_cells(field_count: usize, fields: Option<&[Field]>, row: &mut Vec<Cell>) {
+    match fields {
+        Some(fields) => row.extend(fields.iter().map(|f| field_cell(&f.value))),
+        None => row.extend((0..field_count).map(|_| Cell::Null)),
+    }
+}
+
+fn part_cells(parts: &[LoweredPart], values: Option<&[Option<Vec<Field>>]>, row: &mut Vec<Cell>) {
+    for (i, part) in parts.iter().enumerate() {
+        let fields = values.and_then(|v| v.get(i)).and_then(Option::as_deref);
+        field_cells(part.fields.len(), fields, row);
+    }
+}
+
+fn repetitive_cell(elements: Option<&[Vec<Field>]>) -> Cell {
+    match elements {
+        Some(elements) => Cell::Text(JsonElements(elements).to_json()),
+        None => Cell::Null,
+    }
+}
+
+fn item_cells(kind: &LoweredItemKind, item: Option<&DynamicItem>, row: &mut Vec<Cell>) {
+    match kind {
+        LoweredItemKind::Simple { fields, .. } => {
+            let values = match item {
+                Some(DynamicItem::Fields(values)) => Some(values.as_slice()),
+                _ => None,
+            };
+            field_cells(fields.len(), values, row);
+        }
+        LoweredItemKind::Extended { parts } => {
+            let values = match item {
+                Some(DynamicItem::Extended(values, _)) => Some(values.as_slice()),
+                _ => None,
+            };
+            part_cells(parts, values, row);
+        }
+        LoweredItemKind::Repetitive { .. } => {
+            let elements = match item {
+                Some(DynamicItem::Repetitive(elements)) => Some(elements.as_slice()),
+                _ => None,
+            };
+            row.push(repetitive_cell(elements));
+        }
+        LoweredItemKind::Compound { sub_items } => {
+            for (i, sub) in sub_items.iter().enumerate() {
+                let value = match item {
+                    Some(DynamicItem::Compound(subs)) => subs.get(i).and_then(Option::as_ref),
+                    _ => None,
+                };
+                match (&sub.kind, value) {
+                    (LoweredSubItemKind::Simple { fields, .. }, Some(DynamicItem::Fields(values))) => {
+                        field_cells(fields.len(), Some(values), row)
+                    }
+                    (LoweredSubItemKind::Simple { fields, .. }, _) => field_cells(fields.len(), None, row),
+                    (LoweredSubItemKind::Extended { parts }, Some(DynamicItem::Extended(values, _))) => {
+                        part_cells(parts, Some(values), row)
+                    }
+                    (LoweredSubItemKind::Extended { parts }, _) => part_cells(parts, None, row),
+                    (LoweredSubItemKind::Repetitive { .. }, Some(DynamicItem::Repetitive(elements))) => {
+                        row.push(repetitive_cell(Some(elements)))
+                    }
+                    (LoweredSubItemKind::Repetitive { .. }, _) => row.push(Cell::Null),
+                }
+            }
+        }
+    }
+}
+
+/// Repetitive item elements as a JSON array of objects.
+struct JsonElements<'a>(&'a [Vec<Field>]);
+
+struct JsonFields<'a>(&'a [Field]);
+
+impl ToJson for JsonElements<'_> {
+    fn write_json(&self, out: &mut String) {
+        let elements: Vec<_> = self.0.iter().map(|fields| JsonFields(fields)).collect();
+        elements.write_json(out);
+    }
+}
+
+impl ToJson for JsonFields<'_> {
+    fn write_json(&self, out: &mut String) {
+        let mut obj = JsonObject::new(out);
+        for field in self.0 {
+            match &field.value {
+                FieldValue::Unsigned(v) => obj.field(&field.name, v),
+                FieldValue::Epb(v) => obj.field(&field.name, v),
+                FieldValue::String(s) => obj.field(&field.name, s),
+                FieldValue::EpbString(s) => obj.field(&field.name, s),
+                FieldValue::Bytes(bytes) => obj.field(&field.name, bytes),
+                FieldValue::EpbBytes(bytes) => obj.field(&field.name, bytes),
+            };
+        }
+        obj.finish();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dynamic::DynamicDecoder;
+    use crate::parse::parser::parse_category;
+    use crate::transform::{lower, transformer::to_ir};
+    use rasterix_core::BitReader;
+
+    #[test]
+    fn test_columns_and_rows() {
+        let lowered = lower(&to_ir(parse_category(r#"<category id="1">
+            <item id="10" frn="1">
+                <fixed bytes="1"><field name="a" bits="8"/></fixed>
+            </item>
+            <item id="20" frn="2">
+                <extended bytes="2">
+                    <part index="0"><field name="b" bits="7"/></part>
+                    <part index="1"><field name="c" bits="7"/></part>
+                </extended>
+            </item>
+            <item id="30" frn="3">
+                <repetitive bytes="1" counter="2"><field name="d" bits="8"/></repetitive>
+            </item>
+        </category>"#).unwrap()).unwrap()).unwrap();
+        let table = RecordTable::new(&lowered);
+
+        let names: Vec<_> = table.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["item010_a", "item020_part0_b", "item020_part1_c", "item030"]);
+        assert_eq!(table.columns[3].column_type, ColumnType::Text);
+
+        // FSPEC: items 020 and 030; item 020 without its second part.
+        let data = [0x01, 0x00, 0x07, 0x60, 0x0A, 0x01, 0x02];
+        let block = DynamicDecoder::new(lowered)
+            .decode_block(&mut BitReader::new(&data[..]))
+            .unwrap();
+
+        assert_eq!(
+            table.row(&block.records[0]),
+            vec![
+                Cell::Null,
+                Cell::Integer(5),
+                Cell::Null,
+                Cell::Text(r#"[{"d":1},{"d":2}]"#.to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! Parquet output of decoded records.
+
+use std::io::Write;
+use std::sync::Arc;
+
+use parquet::basic::{LogicalType, Repetition, Type as PhysicalType};
+use parquet::data_type::{ByteArray, ByteArrayType, Int64Type};
+use parquet::errors::Result;
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::types::Type;
+
+use crate::dynamic::DynamicBlock;
+use super::{Cell, ColumnType, RecordTable};
+
+/// Writes the decoded records of one category into a Parquet file.
+///
+/// The file holds a single dataset with the columns of the category's
+/// [`RecordTable`], all optional: integers as unsigned 64-bit `INT64`
+/// and text as `UTF8` byte arrays.  Each data block becomes a row group,
+/// so write blocks of a reasonable size (or merge them) for efficient
+/// files.  The file is complete once [`close`](Self::close) returns.
+///
+/// # Example
+///
+/// ```
+/// use rasterix_codegen::dynamic::DynamicDecoder;
+/// use rasterix_codegen::parse::parser::parse_category;
+/// use rasterix_codegen::sink::{RecordTable, parquet::ParquetSink};
+/// use rasterix_codegen::transform::{lower, transformer::to_ir};
+/// use rasterix_core::BitReader;
+///
+/// let xml = r#"<category id="48">
+///     <item id="10" frn="1">
+///         <fixed bytes="2">
+///             <field name="sac" bits="8"/>
+///             <field name="sic" bits="8"/>
+///         </fixed>
+///     </item>
+/// </category>"#;
+/// let lowered = lower(&to_ir(parse_category(xml).unwrap()).unwrap()).unwrap();
+/// let table = RecordTable::new(&lowered);
+/// let decoder = DynamicDecoder::new(lowered);
+///
+/// let data = [0x30, 0x00, 0x06, 0x80, 0x01, 0x02];
+/// let block = decoder.decode_block(&mut BitReader::new(&data[..])).unwrap();
+///
+/// let mut sink = ParquetSink::new(Vec::new(), &table).unwrap();
+/// sink.write_block(&block).unwrap();
+/// let file = sink.close().unwrap();
+/// assert_eq!(&file[..4], b"PAR1");
+/// ```
+pub struct ParquetSink<W: Write + Send> {
+    writer: SerializedFileWriter<W>,
+    table: RecordTable,
+}
+
+impl<W: Write + Send> ParquetSink<W> {
+    /// Starts a Parquet file for the records of `table`'s category.
+    pub fn new(output: W, table: &RecordTable) -> Result<Self> {
+        let fields = table
+            .columns
+            .iter()
+            .map(|column| {
+                let builder = match column.column_type {
+                    ColumnType::Integer => Type::primitive_type_builder(&column.name, PhysicalType::INT64)
+                        .with_logical_type(Some(LogicalType::Integer { bit_width: 64, is_signed: false })),
+                    ColumnType::Text => Type::primitive_type_builder(&column.name, PhysicalType::BYTE_ARRAY)
+                        .with_logical_type(Some(LogicalType::String)),
+                };
+                builder.with_repetition(Repetition::OPTIONAL).build().map(Arc::new)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let schema = Type::group_type_builder(&table.name).with_fields(fields).build()?;
+
+        let writer = SerializedFileWriter::new(
+            output,
+            Arc::new(schema),
+            Arc::new(WriterProperties::builder().build()),
+        )?;
+        Ok(Self { writer, table: table.clone() })
+    }
+
+    /// Writes the records of a data block as a row group.
+    pub fn write_block(&mut self, block: &DynamicBlock) -> Result<()> {
+        let rows: Vec<Vec<Cell>> = block.records.iter().map(|r| self.table.row(r)).collect();
+
+        let mut row_group = self.writer.next_row_group()?;
+        let mut index = 0;
+        while let Some(mut column) = row_group.next_column()? {
+            let cells = rows.iter().map(|row| &row[index]);
+            let levels: Vec<i16> = cells.clone().map(|cell| (*cell != Cell::Null) as i16).collect();
+            match self.table.columns[index].column_type {
+                ColumnType::Integer => {
+                    let values: Vec<i64> = cells
+                        .filter_map(|cell| match cell {
+                            Cell::Integer(v) => Some(*v as i64),
+                            _ => None,
+                        })
+                        .collect();
+                    column.typed::<Int64Type>().write_batch(&values, Some(&levels), None)?;
+                }
+                ColumnType::Text => {
+                    let values: Vec<ByteArray> = cells
+                        .filter_map(|cell| match cell {
+                            Cell::Text(s) => Some(ByteArray::from(s.as_str())),
+                            _ => None,
+                        })
+                        .collect();
+                    column.typed::<ByteArrayType>().write_batch(&values, Some(&levels), None)?;
+                }
+            }
+            column.close()?;
+            index += 1;
+        }
+        row_group.close()?;
+        Ok(())
+    }
+
+    /// Writes the file footer and returns the output.
+    pub fn close(self) -> Result<W> {
+        self.writer.into_inner()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dynamic::DynamicDecoder;
+    use crate::parse::parser::parse_category;
+    use crate::transform::{lower, transformer::to_ir};
+    use bytes::Bytes;
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use rasterix_core::BitReader;
+
+    #[test]
+    fn test_rows_read_back() {
+        let lowered = lower(&to_ir(parse_category(r#"<category id="1">
+            <item id="10" frn="1">
+                <fixed bytes="1"><field name="a" bits="8"/></fixed>
+            </item>
+            <item id="20" frn="2">
+                <fixed bytes="2"><field name="callsign" bits="16" type="string"/></fixed>
+            </item>
+        </category>"#).unwrap()).unwrap()).unwrap();
+        let table = RecordTable::new(&lowered);
+        let decoder = DynamicDecoder::new(lowered);
+
+        let data = [0x01, 0x00, 0x09, 0x80, 0x07, 0xC0, 0x08, b'A', b'B'];
+        let block = decoder.decode_block(&mut BitReader::new(&data[..])).unwrap();
+        let mut sink = ParquetSink::new(Vec::new(), &table).unwrap();
+        sink.write_block(&block).unwrap();
+        let file = sink.close().unwrap();
+
+        let reader = SerializedFileReader::new(Bytes::from(file)).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 2);
+        let rows: Vec<String> = reader
+            .get_row_iter(None)
+            .unwrap()
+            .map(|row| row.unwrap().to_string())
+            .collect();
+        assert_eq!(rows, ["{item010_a: 7, item020_callsign: null}", "{item010_a: 8, item020_callsign: \"AB\"}"]);
+    }
+}
@@ -0,0 +1,180 @@
+//! SQLite output of decoded records.
+
+use std::path::Path;
+
+use rusqlite::types::{ToSqlOutput, Value};
+use rusqlite::{Connection, ToSql, params_from_iter};
+
+use crate::dynamic::DynamicBlock;
+use super::{Cell, ColumnType, RecordTable};
+
+/// Writes decoded records into a SQLite database, one table per category.
+///
+/// Tables are created on first use, named after the category module
+/// (`cat048`) with the columns of its [`RecordTable`].  Integers are stored
+/// as SQLite's signed 64-bit integers, so 64-bit fields above `i64::MAX`
+/// read back negative.
+///
+/// # Example
+///
+/// ```
+/// use rasterix_codegen::dynamic::DynamicDecoder;
+/// use rasterix_codegen::parse::parser::parse_category;
+/// use rasterix_codegen::sink::{RecordTable, sqlite::SqliteSink};
+/// use rasterix_codegen::transform::{lower, transformer::to_ir};
+/// use rasterix_core::BitReader;
+///
+/// let xml = r#"<category id="48">
+///     <item id="10" frn="1">
+///         <fixed bytes="2">
+///             <field name="sac" bits="8"/>
+///             <field name="sic" bits="8"/>
+///         </fixed>
+///     </item>
+/// </category>"#;
+/// let lowered = lower(&to_ir(parse_category(xml).unwrap()).unwrap()).unwrap();
+/// let table = RecordTable::new(&lowered);
+/// let decoder = DynamicDecoder::new(lowered);
+///
+/// let data = [0x30, 0x00, 0x06, 0x80, 0x01, 0x02];
+/// let block = decoder.decode_block(&mut BitReader::new(&data[..])).unwrap();
+///
+/// let mut sink = SqliteSink::open_in_memory().unwrap();
+/// sink.write_block(&table, &block).unwrap();
+///
+/// let sic: i64 = sink
+///     .connection()
+///     .query_row("SELECT item010_sic FROM cat048", [], |row| row.get(0))
+///     .unwrap();
+/// assert_eq!(sic, 2);
+/// ```
+pub struct SqliteSink {
+    connection: Connection,
+    /// Tables created so far.
+    created: Vec<String>,
+}
+
+impl SqliteSink {
+    /// Opens or creates a database file.
+    pub fn open(path: impl AsRef<Path>) -> rusqlite::Result<Self> {
+        Ok(Self::with_connection(Connection::open(path)?))
+    }
+
+    /// Opens a database that lives in memory.
+    pub fn open_in_memory() -> rusqlite::Result<Self> {
+        Ok(Self::with_connection(Connection::open_in_memory()?))
+    }
+
+    /// Writes into an existing connection.
+    pub fn with_connection(connection: Connection) -> Self {
+        Self { connection, created: Vec::new() }
+    }
+
+    /// Returns the connection, e.g. to query the tables written so far.
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    /// Inserts the records of a data block into the table of its category,
+    /// in one transaction.
+    pub fn write_block(&mut self, table: &RecordTable, block: &DynamicBlock) -> rusqlite::Result<()> {
+        self.create_table(table)?;
+
+        let columns: Vec<_> = table.columns.iter().map(|c| quote_name(&c.name)).collect();
+        let placeholders = vec!["?"; columns.len()].join(", ");
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            quote_name(&table.name),
+            columns.join(", "),
+            placeholders
+        );
+
+        let transaction = self.connection.transaction()?;
+        {
+            let mut statement = transaction.prepare_cached(&sql)?;
+            for record in &block.records {
+                statement.execute(params_from_iter(table.row(record)))?;
+            }
+        }
+        transaction.commit()
+    }
+
+    fn create_table(&mut self, table: &RecordTable) -> rusqlite::Result<()> {
+        if self.created.contains(&table.name) {
+            return Ok(());
+        }
+
+        let columns: Vec<_> = table
+            .columns
+            .iter()
+            .map(|column| {
+                let column_type = match column.column_type {
+                    ColumnType::Integer => "INTEGER",
+                    ColumnType::Text => "TEXT",
+                };
+                format!("{} {}", quote_name(&column.name), column_type)
+            })
+            .collect();
+        self.connection.execute(
+            &format!("CREATE TABLE IF NOT EXISTS {} ({})", quote_name(&table.name), columns.join(", ")),
+            [],
+        )?;
+        self.created.push(table.name.clone());
+        Ok(())
+    }
+}
+
+fn quote_name(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+impl ToSql for Cell {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(match self {
+            Cell::Null => ToSqlOutput::Owned(Value::Null),
+            Cell::Integer(v) => ToSqlOutput::Owned(Value::Integer(*v as i64)),
+            Cell::Text(s) => ToSqlOutput::from(s.as_str()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dynamic::DynamicDecoder;
+    use crate::parse::parser::parse_category;
+    use crate::transform::{lower, transformer::to_ir};
+    use rasterix_core::BitReader;
+
+    #[test]
+    fn test_blocks_append_to_category_table() {
+        let lowered = lower(&to_ir(parse_category(r#"<category id="1">
+            <item id="10" frn="1">
+                <fixed bytes="1"><field name="a" bits="8"/></fixed>
+            </item>
+            <item id="20" frn="2">
+                <fixed bytes="2"><field name="callsign" bits="16" type="string"/></fixed>
+            </item>
+        </category>"#).unwrap()).unwrap()).unwrap();
+        let table = RecordTable::new(&lowered);
+        let decoder = DynamicDecoder::new(lowered);
+        let mut sink = SqliteSink::open_in_memory().unwrap();
+
+        for data in [&[0x01, 0x00, 0x05, 0x80, 0x07][..], &[0x01, 0x00, 0x06, 0x40, b'A', b'B'][..]] {
+            let block = decoder.decode_block(&mut BitReader::new(data)).unwrap();
+            sink.write_block(&table, &block).unwrap();
+        }
+
+        let mut statement = sink
+            .connection()
+            .prepare("SELECT item010_a, item020_callsign FROM cat001 ORDER BY rowid")
+            .unwrap();
+        let rows: Vec<(Option<i64>, Option<String>)> = statement
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(rows, vec![(Some(7), None), (None, Some("AB".to_string()))]);
+    }
+}
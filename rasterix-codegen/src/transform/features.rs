@@ -0,0 +1,87 @@
+use quote::format_ident;
+
+use crate::parse::xml_model::FeatureConfig;
+use super::lower_ir::LoweredIR;
+
+/// Tags the items listed in `config` with their cargo feature.
+///
+/// Returns an error naming the first item that is not part of the
+/// category or that is listed under more than one feature.
+pub fn apply_features(lowered: &mut LoweredIR, config: &FeatureConfig) -> Result<(), String> {
+    for group in &config.features {
+        for feature_item in &group.items {
+            let name = format_ident!("Item{:03}", feature_item.id);
+            let Some(item) = lowered.items.iter_mut().find(|item| item.name == name) else {
+                return Err(format!(
+                    "feature `{}` lists item {} which is not defined in category {}",
+                    group.name, feature_item.id, lowered.category_id
+                ));
+            };
+            if let Some(other) = &item.feature {
+                return Err(format!(
+                    "item {} is listed under both `{}` and `{}`",
+                    feature_item.id, other, group.name
+                ));
+            }
+            item.feature = Some(group.name.clone());
+
+            for entry in lowered.record.entries.iter_mut().filter(|e| e.type_name == name) {
+                entry.feature = Some(group.name.clone());
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parser::{parse_category, parse_features};
+    use crate::transform::{lower, transformer::to_ir};
+
+    fn lowered() -> LoweredIR {
+        lower(&to_ir(parse_category(r#"<category id="48">
+            <item id="10" frn="1">
+                <fixed bytes="1"><field name="a" bits="8"/></fixed>
+            </item>
+            <item id="250" frn="2">
+                <fixed bytes="1"><field name="b" bits="8"/></fixed>
+            </item>
+        </category>"#).unwrap()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_apply_features() {
+        let mut lowered = lowered();
+        let config = parse_features(r#"<features>
+            <feature name="cat048_bds"><item id="250"/></feature>
+        </features>"#).unwrap();
+
+        apply_features(&mut lowered, &config).unwrap();
+
+        assert_eq!(lowered.items[0].feature, None);
+        assert_eq!(lowered.items[1].feature.as_deref(), Some("cat048_bds"));
+        assert_eq!(lowered.record.entries[0].feature, None);
+        assert_eq!(lowered.record.entries[1].feature.as_deref(), Some("cat048_bds"));
+    }
+
+    #[test]
+    fn test_apply_features_rejects_unknown_and_duplicate_items() {
+        let unknown = parse_features(r#"<features>
+            <feature name="extra"><item id="20"/></feature>
+        </features>"#).unwrap();
+        assert_eq!(
+            apply_features(&mut lowered(), &unknown),
+            Err("feature `extra` lists item 20 which is not defined in category 48".to_string())
+        );
+
+        let duplicate = parse_features(r#"<features>
+            <feature name="a"><item id="10"/></feature>
+            <feature name="b"><item id="10"/></feature>
+        </features>"#).unwrap();
+        assert_eq!(
+            apply_features(&mut lowered(), &duplicate),
+            Err("item 10 is listed under both `a` and `b`".to_string())
+        );
+    }
+}
@@ -1,16 +1,27 @@
-/// Intermediate Representation (IR) for ASTERIX code generation.
-/// 
-/// The IR is a normalized, validated representation of the XML input that is
-/// easier to work with during code generation. It has been validated for
-/// correctness (e.g., bit counts match byte sizes).
+//! Intermediate Representation (IR) for ASTERIX code generation.
+//! 
+//! The IR is a normalized, validated representation of the XML input that is
+//! easier to work with during code generation. It has been validated for
+//! correctness (e.g., bit counts match byte sizes).
+//!
+//! With the `ir-json` feature, the IR can be saved to and loaded from JSON
+//! with [`IR::to_json`] and [`IR::from_json`], so the result of parsing and
+//! transforming can be cached between builds or edited by other tools.
+
+#[cfg(feature = "ir-json")]
+use serde::{Deserialize, Serialize};
+
+use crate::generate::utils::{to_pascal_case, to_snake_case, try_title_to_type_name, try_to_pascal_case, try_to_snake_case};
 
 /// Top-level IR structure representing a complete ASTERIX category.
+#[cfg_attr(feature = "ir-json", derive(Serialize, Deserialize))]
 #[derive(Debug)]
 pub struct IR {
     pub category: IRCategory,
 }
 
 /// A category containing multiple data items.
+#[cfg_attr(feature = "ir-json", derive(Serialize, Deserialize))]
 #[derive(Debug)]
 pub struct IRCategory {
     /// Category ID (e.g., 48 for CAT048)
@@ -20,23 +31,291 @@ pub struct IRCategory {
     pub items: Vec<IRItem>,
 }
 
+/// Version of the JSON form of the IR written by [`IR::to_json`].
+///
+/// The minor version grows with additions older readers can't represent,
+/// the major version with incompatible changes.  [`IR::from_json`] reads
+/// its own major version up to its own minor version.
+#[cfg(feature = "ir-json")]
+pub const IR_SCHEMA_VERSION: (u32, u32) = (1, 0);
+
+#[cfg(feature = "ir-json")]
+impl IR {
+    /// Serializes the IR to JSON, tagged with [`IR_SCHEMA_VERSION`].
+    pub fn to_json(&self) -> String {
+        let (major, minor) = IR_SCHEMA_VERSION;
+        let document = serde_json::json!({
+            "schema_version": format!("{}.{}", major, minor),
+            "ir": self,
+        });
+        serde_json::to_string_pretty(&document).expect("IR serializes to JSON")
+    }
+
+    /// Loads IR saved by [`to_json`](Self::to_json), possibly edited since.
+    ///
+    /// Fails if the schema version is not compatible with
+    /// [`IR_SCHEMA_VERSION`], or if the IR has
+    /// [lint](crate::transform::lint) errors, since code generation
+    /// expects valid IR.
+    pub fn from_json(json: &str) -> Result<IR, String> {
+        #[derive(Deserialize)]
+        struct Document {
+            schema_version: String,
+            ir: serde_json::Value,
+        }
+
+        let document: Document = serde_json::from_str(json).map_err(|e| format!("invalid IR JSON: {}", e))?;
+        let (major, minor) = IR_SCHEMA_VERSION;
+        let version = document
+            .schema_version
+            .split_once('.')
+            .and_then(|(major, minor)| Some((major.parse::<u32>().ok()?, minor.parse::<u32>().ok()?)));
+        match version {
+            Some((m, n)) if m == major && n <= minor => {}
+            _ => {
+                return Err(format!(
+                    "IR schema version {} is not supported (expected {}.0 to {}.{})",
+                    document.schema_version, major, major, minor
+                ));
+            }
+        }
+
+        let ir: IR = serde_json::from_value(document.ir).map_err(|e| format!("invalid IR JSON: {}", e))?;
+        let errors: Vec<String> = crate::transform::lint::lint(&ir)
+            .iter()
+            .filter(|lint| lint.severity() == crate::transform::lint::Severity::Error)
+            .map(ToString::to_string)
+            .collect();
+        if !errors.is_empty() {
+            return Err(format!("invalid IR: {}", errors.join("; ")));
+        }
+        Ok(ir)
+    }
+}
+
+impl IRCategory {
+    /// Validates that every item has its own FRN, counted from 1.
+    ///
+    /// FRN 0 is rejected so that definitions still numbered from 0 fail
+    /// instead of shifting every item by one FSPEC position.
+    ///
+    /// Fails if validation fails (build-time error).
+    pub fn validate_frns(&self) -> Result<(), String> {
+        for (i, item) in self.items.iter().enumerate() {
+            if item.frn == 0 {
+                return Err(format!("Invalid FRN: item {:03} has FRN 0, but FRNs start at 1", item.id));
+            }
+            if let Some(other) = self.items[..i].iter().find(|other| other.frn == item.frn) {
+                return Err(format!(
+                    "FRN collision: items {:03} and {:03} both use FRN {}",
+                    other.id, item.id, item.frn
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the names containing non-ASCII characters, which become
+    /// identifiers only [transliterated](crate::generate::utils::transliterate),
+    /// each described with its item.
+    pub fn non_ascii_names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        for item in &self.items {
+            if let Some(title) = item.title.as_deref().filter(|title| !title.is_ascii()) {
+                names.push(format!("item {:03}: title `{}`", item.id, title));
+            }
+            let mut structs = Vec::new();
+            item.layout.collect_structs(String::new(), &mut structs);
+            for (context, elements) in structs {
+                for element in elements {
+                    let element = match element {
+                        IRElement::EPB { content } => content.as_ref(),
+                        element => element,
+                    };
+                    let (name, values) = match element {
+                        IRElement::Field { name, .. } | IRElement::Derived { name, .. } => (name, &[][..]),
+                        IRElement::Enum { name, values, .. } => (name, &values[..]),
+                        _ => continue,
+                    };
+                    if !name.is_ascii() {
+                        names.push(format!("item {:03}{}: field `{}`", item.id, context, name));
+                    }
+                    for (variant, _) in values.iter().filter(|(variant, _)| !variant.is_ascii()) {
+                        names.push(format!("item {:03}{}: variant `{}` of enum `{}`", item.id, context, variant, name));
+                    }
+                }
+            }
+        }
+        names
+    }
+
+    /// Checks that every name gives an identifier once converted: item
+    /// titles, the names of fields, enums and derived values, and the
+    /// variants of enums and their aliases.
+    ///
+    /// Fails naming the first one that doesn't and its item (build-time
+    /// error).
+    pub fn check_names(&self) -> Result<(), String> {
+        for item in &self.items {
+            if let Some(title) = &item.title
+                && try_title_to_type_name(title).is_none()
+            {
+                return Err(format!("Invalid name: item {:03}: title `{}` does not give a type name", item.id, title));
+            }
+            let mut structs = Vec::new();
+            item.layout.collect_structs(String::new(), &mut structs);
+            for (context, elements) in structs {
+                let invalid = |kind: &str, name: &str| {
+                    format!("Invalid name: item {:03}{}: {} `{}` does not give an identifier", item.id, context, kind, name)
+                };
+                for element in elements {
+                    let element = match element {
+                        IRElement::EPB { content } => content.as_ref(),
+                        element => element,
+                    };
+                    let (name, revision) = match element {
+                        IRElement::Field { name, revision, .. } => (name, Some(revision)),
+                        IRElement::Enum { name, revision, .. } => (name, Some(revision)),
+                        IRElement::Derived { name, .. } => (name, None),
+                        _ => continue,
+                    };
+                    if try_to_snake_case(name).is_none() {
+                        return Err(invalid("field", name));
+                    }
+                    if let Some(old) = revision.and_then(|revision| revision.renamed_from.as_ref())
+                        && try_to_snake_case(old).is_none()
+                    {
+                        return Err(invalid("previous name", old));
+                    }
+
+                    let IRElement::Enum { values, aliases, .. } = element else { continue };
+                    if try_to_pascal_case(name).is_none() {
+                        return Err(invalid("enum", name));
+                    }
+                    let variants = values.iter().map(|(variant, _)| variant).chain(aliases.iter().map(|(_, alias)| alias));
+                    for variant in variants {
+                        if try_to_pascal_case(variant).is_none() {
+                            return Err(invalid("variant", variant));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Validates that names stay distinct once converted to identifiers:
+    /// the fields of each struct, the variants of each enum, and the enums
+    /// of the category, whose types share its module.
+    ///
+    /// Fails naming both original names and their item if two give the
+    /// same identifier (build-time error).
+    pub fn validate_identifiers(&self) -> Result<(), String> {
+        // Type name, original name and item of every enum so far.
+        let mut enums: Vec<(String, &str, u16)> = Vec::new();
+        for item in &self.items {
+            let mut structs = Vec::new();
+            item.layout.collect_structs(String::new(), &mut structs);
+            for (context, elements) in structs {
+                let mut fields: Vec<(String, &str)> = Vec::new();
+                for element in elements {
+                    let element = match element {
+                        IRElement::EPB { content } => content.as_ref(),
+                        element => element,
+                    };
+                    let name = match element {
+                        IRElement::Field { name, .. } | IRElement::Enum { name, .. } | IRElement::Derived { name, .. } => name,
+                        _ => continue,
+                    };
+                    let field = to_snake_case(name).to_string();
+                    if let Some((_, other)) = fields.iter().find(|(existing, _)| *existing == field) {
+                        return Err(format!(
+                            "Identifier collision: item {:03}{}: fields `{}` and `{}` both become `{}`",
+                            item.id, context, other, name, field
+                        ));
+                    }
+                    fields.push((field, name));
+
+                    let IRElement::Enum { values, .. } = element else { continue };
+                    let mut variants: Vec<(String, &str)> = Vec::new();
+                    for (variant, _) in values {
+                        let converted = to_pascal_case(variant).to_string();
+                        if let Some((_, other)) = variants.iter().find(|(existing, _)| *existing == converted) {
+                            return Err(format!(
+                                "Identifier collision: item {:03}{}: variants `{}` and `{}` of enum `{}` both become `{}`",
+                                item.id, context, other, variant, name, converted
+                            ));
+                        }
+                        variants.push((converted, variant));
+                    }
+
+                    let type_name = to_pascal_case(name).to_string();
+                    if let Some((_, other, other_item)) = enums.iter().find(|(existing, ..)| *existing == type_name) {
+                        return Err(format!(
+                            "Identifier collision: enums `{}` of item {:03} and `{}` of item {:03}{} both become `{}`",
+                            other, other_item, name, item.id, context, type_name
+                        ));
+                    }
+                    enums.push((type_name, name, item.id));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 /// A single data item within a category.
+#[cfg_attr(feature = "ir-json", derive(Serialize, Deserialize))]
 #[derive(Debug)]
 pub struct IRItem {
-    /// Item ID (e.g., 010, 020, 140)
-    pub id: u8,
+    /// Item ID (e.g., 010, 020, 140, 500)
+    pub id: u16,
     
     /// Field Reference Number - determines position in record FSPEC
-    /// FRN 0 → bit 0.7, FRN 1 → bit 0.6, etc.
+    /// FRN 1 → bit 0.7, FRN 2 → bit 0.6, ..., FRN 8 → bit 1.7, etc.
     pub frn: u8,
+
+    /// Title of the item, e.g. "Data Source Identifier"
+    pub title: Option<String>,
+
+    /// Short symbolic name of the item, e.g. "DSI"
+    pub short: Option<String>,
+
+    /// Whether the item is a placeholder made only of spare bits
+    #[cfg_attr(feature = "ir-json", serde(default))]
+    pub reserved: bool,
+
+    /// Whether every record must hold the item
+    #[cfg_attr(feature = "ir-json", serde(default))]
+    pub mandatory: bool,
     
     /// The structural layout of this item
     pub layout: IRLayout,
+
+    /// Worked examples from the specification
+    #[cfg_attr(feature = "ir-json", serde(default))]
+    pub examples: Vec<IRExample>,
+}
+
+/// A worked example of an item: its encoding and the values some of its
+/// fields decode to.
+#[cfg_attr(feature = "ir-json", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IRExample {
+    /// Encoding of the item
+    pub bytes: Vec<u8>,
+
+    pub description: Option<String>,
+
+    /// Path of a field (e.g. `part1.c`) and the value it decodes to, as
+    /// written in the definition
+    pub values: Vec<(String, String)>,
 }
 
 /// The structural layout of an item or sub-item.
 /// 
 /// This enum captures all possible ASTERIX item structures in a normalized form.
+#[cfg_attr(feature = "ir-json", derive(Serialize, Deserialize), serde(rename_all = "snake_case"))]
 #[derive(Debug)]
 pub enum IRLayout {
     /// Fixed-length item.
@@ -102,6 +381,7 @@ pub enum IRLayout {
 /// 
 /// Each part group contains elements that fit within one byte 
 /// (7 bits of data + 1 FX bit).
+#[cfg_attr(feature = "ir-json", derive(Serialize, Deserialize))]
 #[derive(Debug)]
 pub struct IRPartGroup {
     /// Zero-based index (0 = first byte, 1 = second byte, etc.)
@@ -112,6 +392,7 @@ pub struct IRPartGroup {
 }
 
 /// A sub-item within a compound item.
+#[cfg_attr(feature = "ir-json", derive(Serialize, Deserialize))]
 #[derive(Debug)]
 pub struct IRSubItem {
     /// Zero-based index for this sub-item
@@ -127,6 +408,7 @@ pub struct IRSubItem {
 /// 
 /// These represent the actual data fields, enumerations, and structural 
 /// markers.
+#[cfg_attr(feature = "ir-json", derive(Serialize, Deserialize), serde(rename_all = "snake_case"))]
 #[derive(Debug)]
 pub enum IRElement {
     /// A simple data field.
@@ -139,6 +421,32 @@ pub enum IRElement {
 
         /// Whether this field should be treated as a string
         is_string: bool,
+
+        /// Changes of the field across definition revisions
+        revision: IRRevision,
+
+        /// Description of the field from the specification
+        description: Option<String>,
+
+        /// Constraints on the value, checked by the generated `validate`
+        #[cfg_attr(feature = "ir-json", serde(default))]
+        constraints: IRConstraints,
+
+        /// Typed interpretation of a numeric value, from the field's `type`
+        #[cfg_attr(feature = "ir-json", serde(default))]
+        value_type: Option<IRValueType>,
+
+        /// Whether the bytes of the value are sent least significant first
+        #[cfg_attr(feature = "ir-json", serde(default))]
+        little_endian: bool,
+
+        /// Whether the value is an IEEE 754 float, `f32` or `f64` by its bits
+        #[cfg_attr(feature = "ir-json", serde(default))]
+        is_float: bool,
+
+        /// Whether the value is an opaque array of its bytes
+        #[cfg_attr(feature = "ir-json", serde(default))]
+        is_bytes: bool,
     },
     
     /// An Extended Primary Bit field - field/enum with automatic validity bit.
@@ -163,6 +471,18 @@ pub enum IRElement {
         
         /// List of (variant_name, numeric_value) pairs
         values: Vec<(String, u8)>,
+
+        /// List of (variant_name, alias) pairs
+        aliases: Vec<(String, String)>,
+
+        /// Representation of values without a variant
+        unknown: IRUnknownValues,
+
+        /// Changes of the field across definition revisions
+        revision: IRRevision,
+
+        /// Description of the field from the specification
+        description: Option<String>,
     },
     
     /// Spare bits - ignored on read, written as 0 on write.
@@ -172,6 +492,145 @@ pub enum IRElement {
         /// Number of spare bits
         bits: usize,
     },
+
+    /// A value computed from the numeric fields of the same structure.
+    ///
+    /// Takes up no bits; generated as a method of the struct.
+    Derived {
+        /// Name of the method
+        name: String,
+
+        /// Numeric Rust type of the value, e.g. "f64"
+        rust_type: String,
+
+        /// Rust expression over the fields, each an `f64`
+        expr: String,
+
+        /// Description of the value
+        description: Option<String>,
+
+        /// Role of the value in a sensor-relative position, e.g. "range"
+        #[cfg_attr(feature = "ir-json", serde(default))]
+        geo: Option<String>,
+    },
+
+    /// A custom element, handled by an
+    /// [`ElementPlugin`](super::plugins::ElementPlugin).
+    ///
+    /// These do not appear in the generated struct.
+    Custom {
+        /// Tag of the element, e.g. "crc"
+        tag: String,
+
+        /// Attributes of the element by name
+        attributes: std::collections::BTreeMap<String, String>,
+
+        /// Size and code given by the plugin, once resolved with
+        /// [`apply_plugins`](super::plugins::apply_plugins)
+        code: Option<IRCustomCode>,
+    },
+}
+
+/// Size and code of a custom element, given by its plugin.
+#[cfg_attr(feature = "ir-json", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct IRCustomCode {
+    /// Number of bits
+    pub bits: usize,
+
+    /// Statements decoding the element
+    pub decode: String,
+
+    /// Statements encoding the element
+    pub encode: String,
+}
+
+/// How a generated enum represents values that have no variant.
+#[cfg_attr(feature = "ir-json", derive(Serialize, Deserialize), serde(rename_all = "snake_case"))]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum IRUnknownValues {
+    /// Kept in an `Unknown(u8)` variant, so they encode back unchanged.
+    #[default]
+    Preserve,
+
+    /// Rejected when decoding; the enum has no `Unknown` variant.
+    Error,
+
+    /// Decoded as the named variant; the enum has no `Unknown` variant.
+    MapTo(String),
+}
+
+/// Constraints on the raw value of a numeric field.
+///
+/// Decoding accepts any value; the generated `validate` methods report the
+/// values outside the constraints.
+#[cfg_attr(feature = "ir-json", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IRConstraints {
+    /// Smallest valid value
+    pub min: Option<u64>,
+
+    /// Largest valid value
+    pub max: Option<u64>,
+
+    /// Step of the valid values
+    pub multiple_of: Option<u64>,
+}
+
+/// Typed interpretation of the raw value of a numeric field, given by a
+/// type of `rasterix_core` the field gets an accessor for.
+#[cfg_attr(feature = "ir-json", derive(Serialize, Deserialize), serde(rename_all = "snake_case"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IRValueType {
+    /// Pressure altitude in 1/4 FL, as `altitude::FlightLevel`
+    FlightLevel,
+    /// Geometric altitude in 6.25 ft, as `altitude::GeometricAltitude`
+    Altitude,
+    /// Ground speed in 2^-14 NM/s of a polar `velocity::Velocity`
+    GroundSpeed,
+    /// Heading of a polar `velocity::Velocity`
+    Heading,
+    /// East component in 0.25 m/s of a cartesian `velocity::Velocity`
+    Vx,
+    /// North component in 0.25 m/s of a cartesian `velocity::Velocity`
+    Vy,
+    /// 24-bit aircraft address, as `icao::IcaoAddress`
+    Icao,
+}
+
+/// Changes of a field across revisions of the category definition.
+///
+/// Generated code marks deprecated fields `#[deprecated]` and keeps a
+/// deprecated accessor under the previous name of renamed fields.
+#[cfg_attr(feature = "ir-json", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IRRevision {
+    /// Revision that deprecated the field
+    pub deprecated_since: Option<String>,
+
+    /// Name of the field in earlier revisions
+    pub renamed_from: Option<String>,
+}
+
+impl IRItem {
+    /// Validates that the item has fields unless it is reserved, so that
+    /// an item of spare bits alone is intentional, and that reserved items
+    /// are fixed items of spare bits alone.
+    ///
+    /// Fails if validation fails (build-time error).
+    pub fn validate_reserved(&self) -> Result<(), String> {
+        if self.reserved {
+            if !(matches!(self.layout, IRLayout::Fixed { .. }) && self.layout.is_spare_only()) {
+                return Err(format!("Reserved item {:03} is not a fixed item of spare bits alone", self.id));
+            }
+        } else if self.layout.is_spare_only() {
+            return Err(format!(
+                "Item {:03} has no fields: mark it reserved=\"true\" if it only holds spare bits",
+                self.id
+            ));
+        }
+        Ok(())
+    }
 }
 
 impl IRElement {
@@ -184,6 +643,8 @@ impl IRElement {
             IRElement::Field { bits, .. } => *bits,
             IRElement::Enum { bits, .. } => *bits,
             IRElement::Spare { bits } => *bits,
+            IRElement::Derived { .. } => 0,
+            IRElement::Custom { code, .. } => code.as_ref().map_or(0, |code| code.bits),
             IRElement::EPB { content, .. } => {
                 1 + content.bit_size()
             }
@@ -192,17 +653,51 @@ impl IRElement {
     
     /// Returns true if this element appears in the generated struct.
     /// 
-    /// Spare bits do not appear in the struct.
+    /// Spare bits, derived values and custom elements do not appear in the
+    /// struct.
     pub fn is_visible(&self) -> bool {
-        !matches!(self, IRElement::Spare { .. })
+        !matches!(self, IRElement::Spare { .. } | IRElement::Derived { .. } | IRElement::Custom { .. })
     }
 }
 
 impl IRLayout {
+    /// Returns true if the layout is a single struct with no fields, only
+    /// spare bits.
+    pub fn is_spare_only(&self) -> bool {
+        match self {
+            IRLayout::Fixed { elements, .. }
+            | IRLayout::Explicit { elements, .. }
+            | IRLayout::Repetitive { elements, .. } => {
+                elements.iter().all(|element| matches!(element, IRElement::Spare { .. }))
+            }
+            IRLayout::Extended { .. } | IRLayout::Compound { .. } => false,
+        }
+    }
+
+    /// Collects the elements of every struct of the layout, with where the
+    /// struct is (`, part 1`, `, sub-item 0`).
+    fn collect_structs<'a>(&'a self, context: String, structs: &mut Vec<(String, &'a [IRElement])>) {
+        match self {
+            IRLayout::Fixed { elements, .. }
+            | IRLayout::Explicit { elements, .. }
+            | IRLayout::Repetitive { elements, .. } => structs.push((context, elements)),
+            IRLayout::Extended { part_groups, .. } => {
+                for group in part_groups {
+                    structs.push((format!("{}, part {}", context, group.index), &group.elements));
+                }
+            }
+            IRLayout::Compound { sub_items } => {
+                for sub in sub_items {
+                    sub.layout.collect_structs(format!("{}, sub-item {}", context, sub.index), structs);
+                }
+            }
+        }
+    }
+
     /// Validates that the total bit count matches the declared byte size.
     /// 
-    /// Panics if validation fails (build-time error).
-    pub fn validate(&self) {
+    /// Fails if validation fails (build-time error).
+    pub fn validate(&self) -> Result<(), String> {
         match self {
             IRLayout::Fixed { bytes, elements } 
             | IRLayout::Explicit { bytes, elements } => {
@@ -210,29 +705,34 @@ impl IRLayout {
                     .map(|e| e.bit_size()).sum();
                 let expected_bits = bytes * 8;
                 
-                assert_eq!(
-                    total_bits, expected_bits,
-                    "Bit count mismatch: Fixed element use {} bits but {} bytes = {} bits",
-                    total_bits, bytes, expected_bits
-                );
+                if total_bits != expected_bits {
+                    return Err(format!(
+                        "Bit count mismatch: Fixed element use {} bits but {} bytes = {} bits",
+                        total_bits, bytes, expected_bits
+                    ));
+                }
             }
             
             IRLayout::Extended { bytes, part_groups } => {
                 let layout_bytes =  part_groups.len();
-                let declared_bytes = bytes.clone();
-                assert_eq!(declared_bytes, layout_bytes, 
-                    "Byte count mismatch: Extended element declared {} bytes but defines {} parts = {} bytes", 
-                    declared_bytes, layout_bytes, layout_bytes);
+                let declared_bytes = *bytes;
+                if declared_bytes != layout_bytes {
+                    return Err(format!(
+                        "Byte count mismatch: Extended element declared {} bytes but defines {} parts = {} bytes", 
+                        declared_bytes, layout_bytes, layout_bytes
+                    ));
+                }
                 for group in part_groups {
                     let total_bits: usize = group.elements.iter()
                         .map(|e| e.bit_size()).sum();
                     let expected_bits = 7;
                     
-                    assert_eq!(
-                        total_bits, expected_bits,
-                        "Part group {} has {} bits but should have {} bits (7 data + 1 FX)",
-                        group.index, total_bits, expected_bits
-                    );
+                    if total_bits != expected_bits {
+                        return Err(format!(
+                            "Part group {} has {} bits but should have {} bits (7 data + 1 FX)",
+                            group.index, total_bits, expected_bits
+                        ));
+                    }
                 }
             }
             
@@ -241,19 +741,21 @@ impl IRLayout {
                     .map(|e| e.bit_size()).sum();
                 let expected_bits = bytes * 8;
                 
-                assert_eq!(
-                    total_bits, expected_bits,
-                    "Repetitive item: elements use {} bits but {} bytes = {} bits",
-                    total_bits, bytes, expected_bits
-                );
+                if total_bits != expected_bits {
+                    return Err(format!(
+                        "Repetitive item: elements use {} bits but {} bytes = {} bits",
+                        total_bits, bytes, expected_bits
+                    ));
+                }
             }
             
             IRLayout::Compound { sub_items } => {
                 // Validate each sub-item recursively
                 for sub_item in sub_items {
-                    sub_item.layout.validate();
+                    sub_item.layout.validate()?;
                 }
             }
         }
+        Ok(())
     }
 }
\ No newline at end of file
@@ -0,0 +1,474 @@
+//! Checks of category definitions.
+//!
+//! [`lint`] reports every problem of a definition at once, where
+//! [`to_ir`](super::transformer::to_ir) fails on the first one that would
+//! break code generation.  Each problem is reported under a [`Rule`] with a
+//! stable code, so tools can filter and gate on them.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::generate::utils::{to_pascal_case, to_snake_case};
+use super::ir::{IRElement, IRItem, IRLayout, IR};
+
+/// How serious a lint is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The definition works but is likely wrong.
+    Warning,
+    /// Code cannot be generated from the definition.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// A check of a definition.
+///
+/// Codes and names are stable: new rules get new codes, and removed rules
+/// leave theirs unused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    /// The definition could not be read or converted at all.  Reported by
+    /// tools for parse errors, never by [`lint`].
+    InvalidDefinition,
+    /// The elements of an item don't fill its declared size.
+    BitCount,
+    /// An item has FRN 0.
+    FrnZero,
+    /// Two items have the same FRN.
+    FrnCollision,
+    /// Two fields of the same structure get the same name.
+    DuplicateField,
+    /// A numeric field is wider than 64 bits.
+    FieldTooWide,
+    /// A string field is not a whole number of bytes.
+    StringWidth,
+    /// An enum value doesn't fit in the bits of the enum.
+    EnumValueTooWide,
+    /// Two variants of an enum get the same name.
+    DuplicateVariant,
+    /// A custom element has no plugin.
+    UnknownElement,
+    /// Two enums of the category get the same type name.
+    DuplicateEnum,
+    /// An item has no fields without being reserved, or a reserved item
+    /// has fields.
+    ReservedItem,
+    /// Two variants of an enum have the same value, so only the first is
+    /// ever decoded.
+    DuplicateEnumValue,
+    /// A field has no bits.
+    ZeroWidthField,
+    /// Items are not listed in FRN order.
+    UnorderedItems,
+    /// An enum has no values.
+    EmptyEnum,
+}
+
+impl Rule {
+    /// Every rule, in code order.
+    pub const ALL: [Rule; 16] = [
+        Rule::InvalidDefinition,
+        Rule::BitCount,
+        Rule::FrnZero,
+        Rule::FrnCollision,
+        Rule::DuplicateField,
+        Rule::FieldTooWide,
+        Rule::StringWidth,
+        Rule::EnumValueTooWide,
+        Rule::DuplicateVariant,
+        Rule::UnknownElement,
+        Rule::DuplicateEnum,
+        Rule::ReservedItem,
+        Rule::DuplicateEnumValue,
+        Rule::ZeroWidthField,
+        Rule::UnorderedItems,
+        Rule::EmptyEnum,
+    ];
+
+    /// Stable code of the rule, e.g. `E001`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Rule::InvalidDefinition => "E000",
+            Rule::BitCount => "E001",
+            Rule::FrnZero => "E002",
+            Rule::FrnCollision => "E003",
+            Rule::DuplicateField => "E004",
+            Rule::FieldTooWide => "E005",
+            Rule::StringWidth => "E006",
+            Rule::EnumValueTooWide => "E007",
+            Rule::DuplicateVariant => "E008",
+            Rule::UnknownElement => "E009",
+            Rule::DuplicateEnum => "E010",
+            Rule::ReservedItem => "E011",
+            Rule::DuplicateEnumValue => "W001",
+            Rule::ZeroWidthField => "W002",
+            Rule::UnorderedItems => "W003",
+            Rule::EmptyEnum => "W004",
+        }
+    }
+
+    /// Stable name of the rule, e.g. `bit-count`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rule::InvalidDefinition => "invalid-definition",
+            Rule::BitCount => "bit-count",
+            Rule::FrnZero => "frn-zero",
+            Rule::FrnCollision => "frn-collision",
+            Rule::DuplicateField => "duplicate-field",
+            Rule::FieldTooWide => "field-too-wide",
+            Rule::StringWidth => "string-width",
+            Rule::EnumValueTooWide => "enum-value-too-wide",
+            Rule::DuplicateVariant => "duplicate-variant",
+            Rule::UnknownElement => "unknown-element",
+            Rule::DuplicateEnum => "duplicate-enum",
+            Rule::ReservedItem => "reserved-item",
+            Rule::DuplicateEnumValue => "duplicate-enum-value",
+            Rule::ZeroWidthField => "zero-width-field",
+            Rule::UnorderedItems => "unordered-items",
+            Rule::EmptyEnum => "empty-enum",
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self.code().as_bytes()[0] {
+            b'E' => Severity::Error,
+            _ => Severity::Warning,
+        }
+    }
+}
+
+/// A problem found in a definition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lint {
+    pub rule: Rule,
+    /// Item the problem is in, if any.
+    pub item: Option<u16>,
+    pub message: String,
+}
+
+impl Lint {
+    pub fn severity(&self) -> Severity {
+        self.rule.severity()
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}]: ", self.severity(), self.rule.code())?;
+        if let Some(item) = self.item {
+            write!(f, "item {:03}: ", item)?;
+        }
+        write!(f, "{} ({})", self.message, self.rule.name())
+    }
+}
+
+/// Checks a definition, returning its problems in item order.
+///
+/// Takes IR built by
+/// [`to_unvalidated_ir`](super::transformer::to_unvalidated_ir), so that
+/// definitions [`to_ir`](super::transformer::to_ir) would reject can be
+/// checked.
+pub fn lint(ir: &IR) -> Vec<Lint> {
+    let mut lints = Vec::new();
+    let items = &ir.category.items;
+    // Item of every enum type so far, by type name.
+    let mut enums: HashMap<String, (&str, u16)> = HashMap::new();
+
+    for (i, item) in items.iter().enumerate() {
+        let mut lint = |rule, message| lints.push(Lint { rule, item: Some(item.id), message });
+        if item.frn == 0 {
+            lint(Rule::FrnZero, "FRN 0, but FRNs start at 1".to_string());
+        } else if let Some(other) = items[..i].iter().find(|other| other.frn == item.frn) {
+            lint(Rule::FrnCollision, format!("FRN {} is also used by item {:03}", item.frn, other.id));
+        }
+        if i > 0 && items[i - 1].frn > item.frn {
+            lint(
+                Rule::UnorderedItems,
+                format!("FRN {} is listed after FRN {} of item {:03}", item.frn, items[i - 1].frn, items[i - 1].id),
+            );
+        }
+        lint_reserved(item, &mut lint);
+        lint_layout(&item.layout, "", &mut lint);
+        for name in enum_names(&item.layout) {
+            if let Some((other, other_item)) = enums.insert(to_pascal_case(name).to_string(), (name, item.id)) {
+                lint(
+                    Rule::DuplicateEnum,
+                    format!("enum `{}` has the same type name as enum `{}` of item {:03}", name, other, other_item),
+                );
+            }
+        }
+    }
+    lints
+}
+
+fn lint_reserved(item: &IRItem, lint: &mut impl FnMut(Rule, String)) {
+    let spare_only = item.layout.is_spare_only();
+    if item.reserved && !(spare_only && matches!(item.layout, IRLayout::Fixed { .. })) {
+        lint(Rule::ReservedItem, "reserved, but not a fixed item of spare bits alone".to_string());
+    } else if !item.reserved && spare_only {
+        lint(Rule::ReservedItem, "no fields, but not reserved".to_string());
+    }
+}
+
+fn lint_layout(layout: &IRLayout, context: &str, lint: &mut impl FnMut(Rule, String)) {
+    match layout {
+        IRLayout::Fixed { bytes, elements } | IRLayout::Explicit { bytes, elements } => {
+            let bits = bit_size(elements);
+            if bits != bytes * 8 {
+                lint(Rule::BitCount, format!("{}elements use {} bits but {} bytes = {} bits", context, bits, bytes, bytes * 8));
+            }
+            lint_elements(elements, context, lint);
+        }
+        IRLayout::Repetitive { bytes, elements, .. } => {
+            let bits = bit_size(elements);
+            if bits != bytes * 8 {
+                lint(
+                    Rule::BitCount,
+                    format!("{}repetition elements use {} bits but {} bytes = {} bits", context, bits, bytes, bytes * 8),
+                );
+            }
+            lint_elements(elements, context, lint);
+        }
+        IRLayout::Extended { bytes, part_groups } => {
+            if *bytes != part_groups.len() {
+                lint(Rule::BitCount, format!("{}declares {} bytes but defines {} parts", context, bytes, part_groups.len()));
+            }
+            for group in part_groups {
+                let context = format!("{}part {}: ", context, group.index);
+                let bits = bit_size(&group.elements);
+                if bits != 7 {
+                    lint(Rule::BitCount, format!("{}elements use {} bits but parts have 7 (and FX)", context, bits));
+                }
+                lint_elements(&group.elements, &context, lint);
+            }
+        }
+        IRLayout::Compound { sub_items } => {
+            for sub in sub_items {
+                lint_layout(&sub.layout, &format!("{}sub-item {}: ", context, sub.index), lint);
+            }
+        }
+    }
+}
+
+fn lint_elements(elements: &[IRElement], context: &str, lint: &mut impl FnMut(Rule, String)) {
+    let mut names: HashMap<String, &str> = HashMap::new();
+    for element in elements {
+        let element = match element {
+            IRElement::EPB { content } => content.as_ref(),
+            element => element,
+        };
+        let (name, bits) = match element {
+            IRElement::Field { name, bits, .. } | IRElement::Enum { name, bits, .. } => (name, *bits),
+            IRElement::Derived { name, .. } => {
+                if let Some(other) = names.insert(to_snake_case(name).to_string(), name) {
+                    lint(Rule::DuplicateField, format!("{}fields `{}` and `{}` have the same name", context, other, name));
+                }
+                continue;
+            }
+            IRElement::Custom { tag, code: None, .. } => {
+                lint(Rule::UnknownElement, format!("{}element <{}> has no plugin", context, tag));
+                continue;
+            }
+            _ => continue,
+        };
+
+        if let Some(other) = names.insert(to_snake_case(name).to_string(), name) {
+            lint(Rule::DuplicateField, format!("{}fields `{}` and `{}` have the same name", context, other, name));
+        }
+        if bits == 0 {
+            lint(Rule::ZeroWidthField, format!("{}field `{}` has no bits", context, name));
+        }
+
+        match element {
+            IRElement::Field { is_string: false, is_bytes: false, .. } if bits > 64 => {
+                lint(Rule::FieldTooWide, format!("{}field `{}` has {} bits, more than a u64", context, name, bits));
+            }
+            IRElement::Field { is_string: true, .. } if bits % 8 != 0 => {
+                lint(Rule::StringWidth, format!("{}string `{}` has {} bits, not a whole number of bytes", context, name, bits));
+            }
+            IRElement::Enum { values, .. } => lint_enum(name, bits, values, context, lint),
+            _ => {}
+        }
+    }
+}
+
+fn lint_enum(name: &str, bits: usize, values: &[(String, u8)], context: &str, lint: &mut impl FnMut(Rule, String)) {
+    if values.is_empty() {
+        lint(Rule::EmptyEnum, format!("{}enum `{}` has no values", context, name));
+    }
+    for (i, (variant, value)) in values.iter().enumerate() {
+        if bits < 8 && value >> bits != 0 {
+            lint(
+                Rule::EnumValueTooWide,
+                format!("{}value {} of enum `{}` does not fit in {} bits", context, value, name, bits),
+            );
+        }
+        let converted = to_pascal_case(variant);
+        if let Some((other, _)) = values[..i].iter().find(|(other, _)| to_pascal_case(other) == converted) {
+            if other == variant {
+                lint(Rule::DuplicateVariant, format!("{}enum `{}` has two variants `{}`", context, name, other));
+            } else {
+                lint(
+                    Rule::DuplicateVariant,
+                    format!("{}variants `{}` and `{}` of enum `{}` have the same name", context, other, variant, name),
+                );
+            }
+        } else if let Some((other, _)) = values[..i].iter().find(|(_, other)| other == value) {
+            lint(
+                Rule::DuplicateEnumValue,
+                format!("{}variants `{}` and `{}` of enum `{}` are both {}", context, other, variant, name, value),
+            );
+        }
+    }
+}
+
+/// Returns the names of the enums of a layout, in order.
+fn enum_names(layout: &IRLayout) -> Vec<&str> {
+    let elements: Vec<&IRElement> = match layout {
+        IRLayout::Fixed { elements, .. } | IRLayout::Explicit { elements, .. } | IRLayout::Repetitive { elements, .. } => {
+            elements.iter().collect()
+        }
+        IRLayout::Extended { part_groups, .. } => part_groups.iter().flat_map(|group| &group.elements).collect(),
+        IRLayout::Compound { sub_items } => return sub_items.iter().flat_map(|sub| enum_names(&sub.layout)).collect(),
+    };
+    elements
+        .into_iter()
+        .filter_map(|element| match element {
+            IRElement::EPB { content } => match content.as_ref() {
+                IRElement::Enum { name, .. } => Some(name.as_str()),
+                _ => None,
+            },
+            IRElement::Enum { name, .. } => Some(name.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn bit_size(elements: &[IRElement]) -> usize {
+    elements.iter().map(IRElement::bit_size).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parser::parse_category;
+    use crate::transform::transformer::to_unvalidated_ir;
+
+    fn lint_xml(xml: &str) -> Vec<String> {
+        lint(&to_unvalidated_ir(parse_category(xml).unwrap()).unwrap()).iter().map(Lint::to_string).collect()
+    }
+
+    #[test]
+    fn test_valid_definition_has_no_lints() {
+        let lints = lint_xml(r#"<category id="48">
+            <item id="10" frn="1">
+                <fixed bytes="2">
+                    <field name="sac" bits="8"/>
+                    <field name="sic" bits="8"/>
+                </fixed>
+            </item>
+        </category>"#);
+
+        assert!(lints.is_empty(), "{:?}", lints);
+    }
+
+    #[test]
+    fn test_lints_report_every_problem() {
+        let lints = lint_xml(r#"<category id="48">
+            <item id="20" frn="2">
+                <fixed bytes="2">
+                    <field name="a" bits="8"/>
+                    <field name="A" bits="4"/>
+                </fixed>
+            </item>
+            <item id="10" frn="1">
+                <extended bytes="1">
+                    <part index="0">
+                        <enum name="kind" bits="3">
+                            <value name="One" value="1"/>
+                            <value name="Uno" value="1"/>
+                            <value name="Nine" value="9"/>
+                        </enum>
+                        <field name="s" bits="4" type="string"/>
+                    </part>
+                </extended>
+            </item>
+            <item id="30" frn="2">
+                <fixed bytes="1"><field name="b" bits="8"/></fixed>
+            </item>
+        </category>"#);
+
+        assert_eq!(lints, [
+            "error[E001]: item 020: elements use 12 bits but 2 bytes = 16 bits (bit-count)",
+            "error[E004]: item 020: fields `a` and `A` have the same name (duplicate-field)",
+            "warning[W003]: item 010: FRN 1 is listed after FRN 2 of item 020 (unordered-items)",
+            "warning[W001]: item 010: part 0: variants `One` and `Uno` of enum `kind` are both 1 (duplicate-enum-value)",
+            "error[E007]: item 010: part 0: value 9 of enum `kind` does not fit in 3 bits (enum-value-too-wide)",
+            "error[E006]: item 010: part 0: string `s` has 4 bits, not a whole number of bytes (string-width)",
+            "error[E003]: item 030: FRN 2 is also used by item 020 (frn-collision)",
+        ]);
+    }
+
+    #[test]
+    fn test_names_colliding_as_identifiers() {
+        let lints = lint_xml(r#"<category id="48">
+            <item id="20" frn="1">
+                <fixed bytes="1">
+                    <enum name="typ" bits="4">
+                        <value name="SSR" value="1"/>
+                        <value name="ssr" value="2"/>
+                    </enum>
+                    <field name="Mode3A" bits="2"/>
+                    <field name="mode3a" bits="2"/>
+                </fixed>
+            </item>
+            <item id="30" frn="2">
+                <fixed bytes="1">
+                    <enum name="TYP" bits="8"><value name="PSR" value="1"/></enum>
+                </fixed>
+            </item>
+        </category>"#);
+
+        assert_eq!(lints, [
+            "error[E008]: item 020: variants `SSR` and `ssr` of enum `typ` have the same name (duplicate-variant)",
+            "error[E004]: item 020: fields `Mode3A` and `mode3a` have the same name (duplicate-field)",
+            "error[E010]: item 030: enum `TYP` has the same type name as enum `typ` of item 020 (duplicate-enum)",
+        ]);
+    }
+
+    #[test]
+    fn test_reserved_items() {
+        let lints = lint_xml(r#"<category id="48">
+            <item id="10" frn="1">
+                <fixed bytes="1"><spare bits="8"/></fixed>
+            </item>
+            <item id="20" frn="2" reserved="true">
+                <fixed bytes="1"><field name="a" bits="8"/></fixed>
+            </item>
+            <item id="30" frn="3" reserved="true">
+                <fixed bytes="1"><spare bits="8"/></fixed>
+            </item>
+        </category>"#);
+
+        assert_eq!(lints, [
+            "error[E011]: item 010: no fields, but not reserved (reserved-item)",
+            "error[E011]: item 020: reserved, but not a fixed item of spare bits alone (reserved-item)",
+        ]);
+    }
+
+    #[test]
+    fn test_rule_codes_are_unique() {
+        let mut codes: Vec<_> = Rule::ALL.iter().map(Rule::code).collect();
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), Rule::ALL.len());
+    }
+}
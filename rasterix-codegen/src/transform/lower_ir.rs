@@ -1,9 +1,18 @@
-use proc_macro2::Ident;
+//! Lowered IR: the representation every generator consumes.
+//!
+//! [`lower`](super::lowerer::lower) resolves names, Rust types, FSPEC
+//! positions and flat decode/encode instruction lists from the semantic IR,
+//! so generators only translate these types into output.  The types are
+//! public so external tools can build alternative backends on top of them,
+//! or adjust a lowered category before passing it to
+//! [`generate_lowered`](crate::generate::generate_lowered).
+
+use proc_macro2::{Ident, TokenStream};
 
 // ── Lowered IR Types ──────────────────────────────────────────────────────
 
 /// Top-level lowered representation of a complete ASTERIX category.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct LoweredIR {
     pub category_id: u8,
     pub module_name: Ident,
@@ -12,31 +21,175 @@ pub struct LoweredIR {
 }
 
 /// Lowered record: flat list of pre-computed entries.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct LoweredRecord {
     pub name: Ident,
     pub entries: Vec<RecordEntry>,
+    /// Whether every item is hashable, so the record and data block
+    /// derive `Eq`, `Hash` and `Ord` too.
+    pub is_hashable: bool,
 }
 
 /// Pre-computed record entry for a single item in the category record.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct RecordEntry {
     pub field_name: Ident,
     pub type_name: Ident,
-    pub fspec_byte: usize,
-    pub fspec_bit: u8,
+    /// Field reference number of the item, counted from 1.
+    pub frn: usize,
+    /// Cargo feature the item is compiled under, if any.
+    pub feature: Option<String>,
+    /// Whether every record must hold the item.
+    pub is_mandatory: bool,
 }
 
 /// A single lowered item with all code-gen info pre-resolved.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct LoweredItem {
+    /// Item number, e.g. `10` for I048/010.
+    pub id: u16,
     pub name: Ident,
+    /// Title of the item, e.g. "Data Source Identifier".
+    pub title: Option<String>,
+    /// Short symbolic name of the item, e.g. "DSI".
+    pub short_name: Option<String>,
+    /// Type alias derived from the title, e.g. `DataSourceIdentifier`.
+    pub alias: Option<Ident>,
+    /// Whether the item is a placeholder of spare bits, generated as a unit
+    /// struct.
+    pub is_reserved: bool,
     pub enums: Vec<LoweredEnum>,
     pub kind: LoweredItemKind,
+    /// Cargo feature the item is compiled under, if any.
+    pub feature: Option<String>,
+    /// Whether the item's types derive `Eq`, `Hash` and `Ord`.
+    ///
+    /// Items with string fields don't: decoding trims trailing padding,
+    /// so equal decoded values can come from different encodings.
+    pub is_hashable: bool,
+    /// Values computed from the fields of the item's structs.
+    pub derived: Vec<DerivedField>,
+    /// Worked examples from the specification, generated as tests.
+    pub examples: Vec<LoweredExample>,
+}
+
+/// A worked example of an item: its encoding and the values some of its
+/// fields decode to.
+#[derive(Debug, Clone)]
+pub struct LoweredExample {
+    pub bytes: Vec<u8>,
+    pub description: Option<String>,
+    pub checks: Vec<ExampleCheck>,
+}
+
+/// The value a field of an example decodes to.
+#[derive(Debug, Clone)]
+pub struct ExampleCheck {
+    /// Path of the field as written in the definition, e.g. `part1.c`.
+    pub path: String,
+    /// Steps from the item to the field.  They are positions rather than
+    /// names, so renames don't invalidate them.
+    pub steps: Vec<ExampleStep>,
+    pub value: ExpectedValue,
+}
+
+/// A step from an item or one of its structs to a nested value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExampleStep {
+    /// Position in the `parts` of an extended item.
+    Part(usize),
+    /// Position in the `sub_items` of a compound item.
+    SubItem(usize),
+    /// Repetition of a repetitive item.
+    Element(usize),
+    /// Position in the `fields` of a struct.
+    Field(usize),
+}
+
+/// Expected value of a field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExpectedValue {
+    /// Raw value of a numeric field.
+    Number(u128),
+    /// Value of a float field.
+    Float(f64),
+    /// Text of a string field.
+    Text(String),
+    /// Bytes of a byte array field.
+    Bytes(Vec<u8>),
+    /// Position of the variant in the enum of the field.
+    Variant(usize),
+    /// No value, for an EPB field.
+    Absent,
+}
+
+/// A value computed from the fields of a struct, generated as a method.
+#[derive(Debug, Clone)]
+pub struct DerivedField {
+    /// Struct the method is generated on, e.g. `Item010` or `Item020Part1`.
+    pub struct_name: Ident,
+    pub name: Ident,
+    /// Numeric type of the value, e.g. `f64`.
+    pub rust_type: Ident,
+    /// Expression over the inputs, as written in the definition.
+    pub expr: TokenStream,
+    /// Fields the expression refers to, in order of first reference.
+    pub inputs: Vec<DerivedInput>,
+    pub description: Option<String>,
+    /// Role of the value in a position, implementing a trait of
+    /// `rasterix_core::geo` on the struct.
+    pub geo: Option<GeoRole>,
+}
+
+/// Role of a derived value in a position reported relative to the sensor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeoRole {
+    /// Slant range in NM, with `Azimuth` a `PolarPosition`.
+    Range,
+    /// Azimuth in degrees.
+    Azimuth,
+    /// Distance east in NM, with `North` a `CartesianPosition`.
+    East,
+    /// Distance north in NM.
+    North,
+}
+
+impl GeoRole {
+    /// Parses a role as written in the definition.
+    pub fn parse(role: &str) -> Option<Self> {
+        match role {
+            "range" => Some(GeoRole::Range),
+            "azimuth" => Some(GeoRole::Azimuth),
+            "east" => Some(GeoRole::East),
+            "north" => Some(GeoRole::North),
+            _ => None,
+        }
+    }
+
+    /// Returns the role completing the position.
+    pub fn counterpart(self) -> Self {
+        match self {
+            GeoRole::Range => GeoRole::Azimuth,
+            GeoRole::Azimuth => GeoRole::Range,
+            GeoRole::East => GeoRole::North,
+            GeoRole::North => GeoRole::East,
+        }
+    }
+}
+
+/// A field a derived value is computed from.
+#[derive(Debug, Clone)]
+pub struct DerivedInput {
+    /// Name of the field in the expression.
+    pub name: Ident,
+    /// The struct field, which renames may give another name.
+    pub field: Ident,
+    /// Whether the field is an `Option`, so the value is one too.
+    pub is_optional: bool,
 }
 
 /// The structural kind of a lowered item.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum LoweredItemKind {
     Simple {
         is_explicit: bool,
@@ -51,6 +204,7 @@ pub enum LoweredItemKind {
     Repetitive {
         element_type_name: Ident,
         count: usize,
+        storage: RepetitiveStorage,
         fields: Vec<FieldDescriptor>,
         decode_ops: Vec<DecodeOp>,
         encode_ops: Vec<EncodeOp>,
@@ -60,8 +214,19 @@ pub enum LoweredItemKind {
     },
 }
 
+/// Container holding the elements of a Repetitive item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RepetitiveStorage {
+    /// A `Vec`, allocated on the heap.
+    #[default]
+    Vec,
+    /// A `heapless::Vec` with the repetition count as capacity, stored
+    /// inline.
+    Heapless,
+}
+
 /// A single part within an Extended item.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct LoweredPart {
     pub index: usize,
     pub struct_name: Ident,
@@ -73,19 +238,19 @@ pub struct LoweredPart {
 }
 
 /// A sub-item within a Compound item.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct LoweredSubItem {
     pub index: usize,
     pub struct_name: Ident,
     pub field_name: Ident,
-    pub fspec_byte: usize,
-    pub fspec_bit: u8,
+    /// Position of the sub-item in the compound FSPEC, counted from 1.
+    pub frn: usize,
     pub enums: Vec<LoweredEnum>,
     pub kind: LoweredSubItemKind,
 }
 
 /// Structural kind of a compound sub-item (no nested Compound).
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum LoweredSubItemKind {
     Simple {
         is_explicit: bool,
@@ -100,6 +265,7 @@ pub enum LoweredSubItemKind {
     Repetitive {
         element_type_name: Ident,
         count: usize,
+        storage: RepetitiveStorage,
         fields: Vec<FieldDescriptor>,
         decode_ops: Vec<DecodeOp>,
         encode_ops: Vec<EncodeOp>,
@@ -111,12 +277,81 @@ pub enum LoweredSubItemKind {
 pub struct FieldDescriptor {
     pub name: Ident,
     pub type_tokens: FieldType,
+    pub revision: FieldRevision,
+    /// Description of the field from the specification.
+    pub description: Option<String>,
+    /// Constraints checked by the generated `Validate` impl.
+    pub constraints: FieldConstraints,
+    /// Typed interpretation of a numeric value, given a typed accessor.
+    pub typed: Option<TypedValue>,
+}
+
+/// Typed interpretation of the raw value of a numeric field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypedValue {
+    pub value_type: ValueType,
+    /// Width of the raw value, for its two's complement.
+    pub bits: usize,
+}
+
+/// Types of `rasterix_core` a raw value converts to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueType {
+    /// `altitude::FlightLevel`
+    FlightLevel,
+    /// `altitude::GeometricAltitude`
+    Altitude,
+    /// Ground speed of a polar `velocity::Velocity`, with `Heading`
+    GroundSpeed,
+    Heading,
+    /// East component of a cartesian `velocity::Velocity`, with `Vy`
+    Vx,
+    Vy,
+    /// `icao::IcaoAddress`
+    Icao,
+}
+
+impl ValueType {
+    /// Returns the type completing a velocity, for the components of one.
+    pub fn velocity_counterpart(self) -> Option<Self> {
+        match self {
+            ValueType::GroundSpeed => Some(ValueType::Heading),
+            ValueType::Heading => Some(ValueType::GroundSpeed),
+            ValueType::Vx => Some(ValueType::Vy),
+            ValueType::Vy => Some(ValueType::Vx),
+            ValueType::FlightLevel | ValueType::Altitude | ValueType::Icao => None,
+        }
+    }
+}
+
+/// Constraints on the raw value of a numeric field.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FieldConstraints {
+    pub min: Option<u64>,
+    pub max: Option<u64>,
+    pub multiple_of: Option<u64>,
+}
+
+impl FieldConstraints {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Changes of a field across revisions of the category definition.
+#[derive(Debug, Clone, Default)]
+pub struct FieldRevision {
+    /// Revision that deprecated the field, for its `#[deprecated]`
+    /// attribute.
+    pub deprecated_since: Option<String>,
+    /// Previous name of the field, kept as a deprecated accessor.
+    pub renamed_from: Option<Ident>,
 }
 
 /// Resolved field types for code generation.
 #[derive(Debug, Clone)]
 pub enum FieldType {
-    /// Primitive type: u8, u16, u32, u64, u128
+    /// Primitive type: u8, u16, u32, u64, u128, or f32, f64 for floats
     Primitive(Ident),
     /// Option<Primitive>
     OptionalPrimitive(Ident),
@@ -128,13 +363,23 @@ pub enum FieldType {
     FixedString(usize),
     /// Option<String> for EPB-wrapped string fields
     OptionalFixedString(usize),
+    /// Opaque bytes (byte_len on the wire): `[u8; N]`, or `Vec<u8>` beyond
+    /// [`MAX_BYTE_ARRAY_LEN`]
+    Bytes(usize),
+    /// Option of the bytes for EPB-wrapped byte fields
+    OptionalBytes(usize),
 }
 
+/// Longest byte field stored in an array; longer ones are stored in a
+/// `Vec<u8>`, so structs stay cheap to move.
+pub const MAX_BYTE_ARRAY_LEN: usize = 32;
+
 /// A pre-collected enum definition.
 #[derive(Debug, Clone)]
 pub struct LoweredEnum {
     pub name: Ident,
     pub variants: Vec<LoweredEnumVariant>,
+    pub unknown: UnknownValues,
 }
 
 /// A single enum variant.
@@ -142,6 +387,20 @@ pub struct LoweredEnum {
 pub struct LoweredEnumVariant {
     pub name: Ident,
     pub value: u8,
+    /// Alternative name, generated as an associated constant.
+    pub alias: Option<Ident>,
+}
+
+/// How a generated enum represents values that have no variant.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum UnknownValues {
+    /// In an `Unknown(u8)` variant.
+    #[default]
+    Preserve,
+    /// Not at all: decoding them fails.
+    Error,
+    /// As the named variant.
+    MapTo(Ident),
 }
 
 // ── Decode Instructions ───────────────────────────────────────────────────
@@ -149,14 +408,34 @@ pub struct LoweredEnumVariant {
 /// A single decode operation (flat, no recursion).
 #[derive(Debug, Clone)]
 pub enum DecodeOp {
-    ReadField { name: Ident, bits: usize, rust_type: Ident },
+    /// A number, byte-swapped if `little_endian`; an `f32` or `f64`
+    /// `rust_type` is a float converted from its bits.
+    ReadField { name: Ident, bits: usize, rust_type: Ident, little_endian: bool },
     ReadEnum { name: Ident, bits: usize, enum_type: Ident },
-    ReadEpbField { name: Ident, bits: usize, rust_type: Ident },
+    ReadEpbField { name: Ident, bits: usize, rust_type: Ident, little_endian: bool },
     ReadEpbEnum { name: Ident, bits: usize, enum_type: Ident },
     ReadString { name: Ident, byte_len: usize },
     ReadEpbString { name: Ident, byte_len: usize },
+    ReadBytes { name: Ident, byte_len: usize },
+    ReadEpbBytes { name: Ident, byte_len: usize },
     SkipSpare { bits: usize },
-    ReadLengthByte,
+    ReadLengthByte { total_bytes: usize },
+    /// Code of a custom element, given by its plugin.
+    Custom { tag: String, bits: usize, code: TokenStream },
+}
+
+impl DecodeOp {
+    /// Number of bits the op reads, including the EPB valid bit.
+    pub fn bits(&self) -> usize {
+        match self {
+            DecodeOp::ReadField { bits, .. } | DecodeOp::ReadEnum { bits, .. } => *bits,
+            DecodeOp::ReadEpbField { bits, .. } | DecodeOp::ReadEpbEnum { bits, .. } => 1 + bits,
+            DecodeOp::ReadString { byte_len, .. } | DecodeOp::ReadBytes { byte_len, .. } => byte_len * 8,
+            DecodeOp::ReadEpbString { byte_len, .. } | DecodeOp::ReadEpbBytes { byte_len, .. } => 1 + byte_len * 8,
+            DecodeOp::SkipSpare { bits } | DecodeOp::Custom { bits, .. } => *bits,
+            DecodeOp::ReadLengthByte { .. } => 8,
+        }
+    }
 }
 
 // ── Encode Instructions ───────────────────────────────────────────────────
@@ -164,12 +443,18 @@ pub enum DecodeOp {
 /// A single encode operation (flat, no recursion).
 #[derive(Debug, Clone)]
 pub enum EncodeOp {
-    WriteField { name: Ident, bits: usize },
+    /// A number, byte-swapped if `little_endian`; a `float` is written
+    /// as its bits.
+    WriteField { name: Ident, bits: usize, little_endian: bool, float: bool },
     WriteEnum { name: Ident, bits: usize },
-    WriteEpbField { name: Ident, bits: usize },
+    WriteEpbField { name: Ident, bits: usize, little_endian: bool, float: bool },
     WriteEpbEnum { name: Ident, bits: usize },
     WriteString { name: Ident, byte_len: usize },
     WriteEpbString { name: Ident, byte_len: usize },
+    WriteBytes { name: Ident, byte_len: usize },
+    WriteEpbBytes { name: Ident, byte_len: usize },
     WriteSpare { bits: usize },
     WriteLengthByte { total_bytes: usize },
+    /// Code of a custom element, given by its plugin.
+    Custom { tag: String, bits: usize, code: TokenStream },
 }
@@ -1,79 +1,153 @@
-use proc_macro2::Ident;
-use quote::format_ident;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, ToTokens};
 
-use crate::generate::utils::{frn_to_fspec_position, rust_type_for_bits, to_pascal_case, to_snake_case};
+use crate::generate::utils::{is_float_type, rust_type_for_bits, to_pascal_case, to_snake_case, try_title_to_type_name};
 use super::ir::*;
 use super::lower_ir::*;
 
 /// Lowers the semantic IR into a flat, code-generation-oriented representation.
-pub fn lower(ir: &IR) -> LoweredIR {
+///
+/// # Errors
+///
+/// Fails if a name doesn't give an identifier, two item titles give the
+/// same type alias, a title gives the name of another generated type, or
+/// the definition can't be represented by the generated code.
+pub fn lower(ir: &IR) -> Result<LoweredIR, String> {
     let category = &ir.category;
+    category.check_names()?;
+    let items = category.items.iter().map(lower_item).collect::<Result<Vec<_>, _>>()?;
+    check_aliases(&items)?;
+    let mut record = lower_record(category)?;
+    record.is_hashable = items.iter().all(|item| item.is_hashable);
 
-    LoweredIR {
+    Ok(LoweredIR {
         category_id: category.id,
         module_name: format_ident!("cat{:03}", category.id),
-        record: lower_record(category),
-        items: category.items.iter().map(lower_item).collect(),
-    }
+        record,
+        items,
+    })
 }
 
-fn lower_record(category: &IRCategory) -> LoweredRecord {
+fn lower_record(category: &IRCategory) -> Result<LoweredRecord, String> {
     let entries = category.items.iter().map(|item| {
-        let (fspec_byte, fspec_bit) = frn_to_fspec_position(item.frn as usize);
-        RecordEntry {
+        if item.frn == 0 {
+            return Err(format!("Invalid FRN: item {:03} has FRN 0, but FRNs start at 1", item.id));
+        }
+        Ok(RecordEntry {
             field_name: format_ident!("item{:03}", item.id),
             type_name: format_ident!("Item{:03}", item.id),
-            fspec_byte,
-            fspec_bit,
-        }
-    }).collect();
+            frn: item.frn as usize,
+            feature: None,
+            is_mandatory: item.mandatory,
+        })
+    }).collect::<Result<_, _>>()?;
 
-    LoweredRecord {
+    Ok(LoweredRecord {
         name: format_ident!("Record"),
         entries,
-    }
+        is_hashable: false,
+    })
 }
 
-fn lower_item(item: &IRItem) -> LoweredItem {
+fn lower_item(item: &IRItem) -> Result<LoweredItem, String> {
     let name = format_ident!("Item{:03}", item.id);
-    let enums = collect_and_lower_enums(&item.layout);
-    let kind = lower_layout(&name, &item.layout);
+    let enums = collect_and_lower_enums(&item.layout)?;
+    let kind = lower_layout(&name, &item.layout)?;
+    let derived = lower_derived(&name, &item.layout)?;
+    let examples = item.examples.iter().map(|example| lower_example(item, example)).collect::<Result<_, _>>()?;
+    let alias = match item.title.as_deref() {
+        Some(title) => Some(try_title_to_type_name(title).ok_or_else(|| {
+            format!("Invalid name: item {:03}: title `{}` does not give a type name", item.id, title)
+        })?),
+        None => None,
+    };
+
+    let is_hashable = is_hashable(&kind);
 
-    LoweredItem { name, enums, kind }
+    Ok(LoweredItem {
+        id: item.id,
+        name,
+        title: item.title.clone(),
+        short_name: item.short.clone(),
+        alias,
+        is_reserved: item.reserved,
+        enums,
+        kind,
+        feature: None,
+        is_hashable,
+        derived,
+        examples,
+    })
 }
 
-fn lower_layout(parent_name: &Ident, layout: &IRLayout) -> LoweredItemKind {
-    match layout {
+/// Checks that the type aliases derived from item titles are distinct and
+/// don't shadow the record types or another item.
+fn check_aliases(items: &[LoweredItem]) -> Result<(), String> {
+    const RECORD_TYPES: [&str; 3] = ["Record", "RecordLazy", "DataBlock"];
+
+    for (i, item) in items.iter().enumerate() {
+        let Some(alias) = &item.alias else { continue };
+        let alias_name = alias.to_string();
+        if RECORD_TYPES.contains(&alias_name.as_str()) || items.iter().any(|other| other.name == alias_name) {
+            return Err(format!(
+                "Alias collision: the title of {} gives {}, which is already a type",
+                item.name, alias
+            ));
+        }
+        if let Some(other) = items[..i].iter().find(|other| other.alias.as_ref() == Some(alias)) {
+            return Err(format!("Alias collision: {} and {} both give {}", other.name, item.name, alias));
+        }
+    }
+    Ok(())
+}
+
+/// Returns `true` if no field of the item is a string or a float.
+fn is_hashable(kind: &LoweredItemKind) -> bool {
+    let parts_hashable = |parts: &[LoweredPart]| parts.iter().all(|p| fields_hashable(&p.fields));
+    match kind {
+        LoweredItemKind::Simple { fields, .. } | LoweredItemKind::Repetitive { fields, .. } => {
+            fields_hashable(fields)
+        }
+        LoweredItemKind::Extended { parts } => parts_hashable(parts),
+        LoweredItemKind::Compound { sub_items } => sub_items.iter().all(|sub| match &sub.kind {
+            LoweredSubItemKind::Simple { fields, .. } | LoweredSubItemKind::Repetitive { fields, .. } => {
+                fields_hashable(fields)
+            }
+            LoweredSubItemKind::Extended { parts } => parts_hashable(parts),
+        }),
+    }
+}
+
+fn fields_hashable(fields: &[FieldDescriptor]) -> bool {
+    fields.iter().all(|field| match &field.type_tokens {
+        FieldType::Primitive(ty) | FieldType::OptionalPrimitive(ty) => !is_float_type(ty),
+        FieldType::Enum(_) | FieldType::OptionalEnum(_) | FieldType::Bytes(_) | FieldType::OptionalBytes(_) => true,
+        FieldType::FixedString(_) | FieldType::OptionalFixedString(_) => false,
+    })
+}
+
+fn lower_layout(parent_name: &Ident, layout: &IRLayout) -> Result<LoweredItemKind, String> {
+    Ok(match layout {
         IRLayout::Fixed { bytes, elements } => {
             LoweredItemKind::Simple {
                 is_explicit: false,
                 byte_size: *bytes,
-                fields: lower_fields(elements),
-                decode_ops: lower_decode_ops(elements, false),
-                encode_ops: lower_encode_ops(elements, false, *bytes),
+                fields: lower_fields(elements)?,
+                decode_ops: lower_decode_ops(elements, false, *bytes)?,
+                encode_ops: lower_encode_ops(elements, false, *bytes)?,
             }
         }
         IRLayout::Explicit { bytes, elements } => {
             LoweredItemKind::Simple {
                 is_explicit: true,
                 byte_size: *bytes,
-                fields: lower_fields(elements),
-                decode_ops: lower_decode_ops(elements, true),
-                encode_ops: lower_encode_ops(elements, true, *bytes),
+                fields: lower_fields(elements)?,
+                decode_ops: lower_decode_ops(elements, true, *bytes)?,
+                encode_ops: lower_encode_ops(elements, true, *bytes)?,
             }
         }
         IRLayout::Extended { part_groups, .. } => {
-            let parts = part_groups.iter().map(|group| {
-                LoweredPart {
-                    index: group.index,
-                    struct_name: format_ident!("{}Part{}", parent_name, group.index),
-                    field_name: format_ident!("part{}", group.index),
-                    is_required: group.index == 0,
-                    fields: lower_fields(&group.elements),
-                    decode_ops: lower_element_ops_decode(&group.elements),
-                    encode_ops: lower_element_ops_encode(&group.elements),
-                }
-            }).collect();
+            let parts = lower_parts(parent_name, part_groups)?;
             LoweredItemKind::Extended { parts }
         }
         IRLayout::Repetitive { bytes: _, count, elements } => {
@@ -81,64 +155,53 @@ fn lower_layout(parent_name: &Ident, layout: &IRLayout) -> LoweredItemKind {
             LoweredItemKind::Repetitive {
                 element_type_name,
                 count: *count,
-                fields: lower_fields(elements),
-                decode_ops: lower_element_ops_decode(elements),
-                encode_ops: lower_element_ops_encode(elements),
+                storage: RepetitiveStorage::Vec,
+                fields: lower_fields(elements)?,
+                decode_ops: lower_element_ops_decode(elements)?,
+                encode_ops: lower_element_ops_encode(elements)?,
             }
         }
         IRLayout::Compound { sub_items } => {
             let lowered_subs = sub_items.iter().map(|sub| {
                 let sub_name = format_ident!("{}Sub{}", parent_name, sub.index);
-                let (fspec_byte, fspec_bit) = frn_to_fspec_position(sub.index);
-                let enums = collect_and_lower_enums(&sub.layout);
-                let kind = lower_sub_item_kind(&sub_name, &sub.layout);
-                LoweredSubItem {
+                let enums = collect_and_lower_enums(&sub.layout)?;
+                let kind = lower_sub_item_kind(&sub_name, &sub.layout)?;
+                Ok(LoweredSubItem {
                     index: sub.index,
                     struct_name: sub_name,
                     field_name: format_ident!("sub{}", sub.index),
-                    fspec_byte,
-                    fspec_bit,
+                    frn: sub.index + 1,
                     enums,
                     kind,
-                }
-            }).collect();
+                })
+            }).collect::<Result<_, String>>()?;
             LoweredItemKind::Compound { sub_items: lowered_subs }
         }
-    }
+    })
 }
 
-fn lower_sub_item_kind(parent_name: &Ident, layout: &IRLayout) -> LoweredSubItemKind {
-    match layout {
+fn lower_sub_item_kind(parent_name: &Ident, layout: &IRLayout) -> Result<LoweredSubItemKind, String> {
+    Ok(match layout {
         IRLayout::Fixed { bytes, elements } => {
             LoweredSubItemKind::Simple {
                 is_explicit: false,
                 byte_size: *bytes,
-                fields: lower_fields(elements),
-                decode_ops: lower_decode_ops(elements, false),
-                encode_ops: lower_encode_ops(elements, false, *bytes),
+                fields: lower_fields(elements)?,
+                decode_ops: lower_decode_ops(elements, false, *bytes)?,
+                encode_ops: lower_encode_ops(elements, false, *bytes)?,
             }
         }
         IRLayout::Explicit { bytes, elements } => {
             LoweredSubItemKind::Simple {
                 is_explicit: true,
                 byte_size: *bytes,
-                fields: lower_fields(elements),
-                decode_ops: lower_decode_ops(elements, true),
-                encode_ops: lower_encode_ops(elements, true, *bytes),
+                fields: lower_fields(elements)?,
+                decode_ops: lower_decode_ops(elements, true, *bytes)?,
+                encode_ops: lower_encode_ops(elements, true, *bytes)?,
             }
         }
         IRLayout::Extended { part_groups, .. } => {
-            let parts = part_groups.iter().map(|group| {
-                LoweredPart {
-                    index: group.index,
-                    struct_name: format_ident!("{}Part{}", parent_name, group.index),
-                    field_name: format_ident!("part{}", group.index),
-                    is_required: group.index == 0,
-                    fields: lower_fields(&group.elements),
-                    decode_ops: lower_element_ops_decode(&group.elements),
-                    encode_ops: lower_element_ops_encode(&group.elements),
-                }
-            }).collect();
+            let parts = lower_parts(parent_name, part_groups)?;
             LoweredSubItemKind::Extended { parts }
         }
         IRLayout::Repetitive { bytes: _, count, elements } => {
@@ -146,113 +209,553 @@ fn lower_sub_item_kind(parent_name: &Ident, layout: &IRLayout) -> LoweredSubItem
             LoweredSubItemKind::Repetitive {
                 element_type_name,
                 count: *count,
-                fields: lower_fields(elements),
-                decode_ops: lower_element_ops_decode(elements),
-                encode_ops: lower_element_ops_encode(elements),
+                storage: RepetitiveStorage::Vec,
+                fields: lower_fields(elements)?,
+                decode_ops: lower_element_ops_decode(elements)?,
+                encode_ops: lower_element_ops_encode(elements)?,
             }
         }
         IRLayout::Compound { .. } => {
-            panic!("Nested compounds not supported")
+            return Err(format!("{}: nested compounds not supported", parent_name));
         }
-    }
+    })
+}
+
+fn lower_parts(parent_name: &Ident, part_groups: &[IRPartGroup]) -> Result<Vec<LoweredPart>, String> {
+    part_groups.iter().map(|group| {
+        Ok(LoweredPart {
+            index: group.index,
+            struct_name: format_ident!("{}Part{}", parent_name, group.index),
+            field_name: format_ident!("part{}", group.index),
+            is_required: group.index == 0,
+            fields: lower_fields(&group.elements)?,
+            decode_ops: lower_element_ops_decode(&group.elements)?,
+            encode_ops: lower_element_ops_encode(&group.elements)?,
+        })
+    }).collect()
 }
 
 // ── Field Lowering ────────────────────────────────────────────────────────
 
-fn lower_fields(elements: &[IRElement]) -> Vec<FieldDescriptor> {
-    elements.iter().filter_map(lower_field).collect()
+/// Returns the Rust type of a numeric field: `f32` or `f64` for floats,
+/// the smallest unsigned integer holding its bits otherwise.
+fn field_rust_type(bits: usize, is_float: bool) -> Ident {
+    if is_float {
+        format_ident!("f{}", bits)
+    } else {
+        format_ident!("{}", rust_type_for_bits(bits))
+    }
 }
 
-fn lower_field(element: &IRElement) -> Option<FieldDescriptor> {
-    match element {
-        IRElement::Field { name, bits, is_string } => {
+fn lower_fields(elements: &[IRElement]) -> Result<Vec<FieldDescriptor>, String> {
+    let fields: Vec<_> = elements.iter().filter_map(|element| lower_field(element).transpose()).collect::<Result<_, _>>()?;
+    check_renamed_fields(&fields)?;
+    check_velocity_fields(&fields)?;
+    Ok(fields)
+}
+
+/// Fails unless the velocity components of a struct make one velocity:
+/// a ground speed and a heading, or east and north components of the same
+/// width, none of them optional.
+fn check_velocity_fields(fields: &[FieldDescriptor]) -> Result<(), String> {
+    let components: Vec<_> = fields
+        .iter()
+        .filter_map(|field| Some((field, field.typed.filter(|typed| typed.value_type.velocity_counterpart().is_some())?)))
+        .collect();
+    for (field, typed) in &components {
+        if !matches!(field.type_tokens, FieldType::Primitive(_)) {
+            return Err(format!("Velocity field {} can't be optional", field.name));
+        }
+        let counterpart = typed.value_type.velocity_counterpart().unwrap();
+        let others: Vec<_> = components.iter().filter(|(_, other)| other.value_type == counterpart).collect();
+        let [(_, other)] = others[..] else {
+            return Err(format!(
+                "Velocity field {} needs exactly one field of type {:?} in its struct",
+                field.name, counterpart
+            ));
+        };
+        if matches!(typed.value_type, ValueType::Vx | ValueType::Vy) && other.bits != typed.bits {
+            return Err(format!(
+                "Velocity field {} has {} bits but its counterpart has {}",
+                field.name, typed.bits, other.bits
+            ));
+        }
+    }
+    if components.len() > 2 {
+        return Err("A struct can have the fields of one velocity only".to_string());
+    }
+    Ok(())
+}
+
+/// Fails if the previous name of a renamed field is taken by another
+/// field or accessor of the same struct.
+fn check_renamed_fields(fields: &[FieldDescriptor]) -> Result<(), String> {
+    for (i, field) in fields.iter().enumerate() {
+        let Some(old) = &field.revision.renamed_from else { continue };
+        let taken = fields.iter().enumerate().any(|(j, other)| {
+            other.name == *old || (i != j && other.revision.renamed_from.as_ref() == Some(old))
+        });
+        if taken {
+            return Err(format!(
+                "Renamed field collision: {} was renamed from {}, which is already used",
+                field.name, old
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Lowers the element of a struct field, or returns `None` for elements
+/// that don't appear in the struct.
+fn lower_field(element: &IRElement) -> Result<Option<FieldDescriptor>, String> {
+    Ok(match element {
+        IRElement::Field { name, bits, is_string, revision, description, constraints, value_type, is_float, is_bytes, .. } => {
             let field_name = to_snake_case(name);
-            if *is_string {
+            if *is_string || *is_bytes {
                 let byte_len = bits / 8;
                 Some(FieldDescriptor {
                     name: field_name,
-                    type_tokens: FieldType::FixedString(byte_len),
+                    type_tokens: if *is_bytes { FieldType::Bytes(byte_len) } else { FieldType::FixedString(byte_len) },
+                    revision: lower_revision(revision),
+                    description: description.clone(),
+                    constraints: lower_constraints(constraints),
+                    typed: None,
                 })
             } else {
-                let rust_type = format_ident!("{}", rust_type_for_bits(*bits));
+                let rust_type = field_rust_type(*bits, *is_float);
                 Some(FieldDescriptor {
                     name: field_name,
                     type_tokens: FieldType::Primitive(rust_type),
+                    revision: lower_revision(revision),
+                    description: description.clone(),
+                    constraints: lower_constraints(constraints),
+                    typed: lower_value_type(name, *bits, *value_type)?,
                 })
             }
         }
         IRElement::EPB { content } => match content.as_ref() {
-            IRElement::Field { name, bits, is_string } => {
+            IRElement::Field { name, bits, is_string, revision, description, constraints, value_type, is_float, is_bytes, .. } => {
                 let field_name = to_snake_case(name);
-                if *is_string {
+                if *is_string || *is_bytes {
                     let byte_len = bits / 8;
                     Some(FieldDescriptor {
                         name: field_name,
-                        type_tokens: FieldType::OptionalFixedString(byte_len),
+                        type_tokens: if *is_bytes {
+                            FieldType::OptionalBytes(byte_len)
+                        } else {
+                            FieldType::OptionalFixedString(byte_len)
+                        },
+                        revision: lower_revision(revision),
+                        description: description.clone(),
+                        constraints: lower_constraints(constraints),
+                        typed: None,
                     })
                 } else {
-                    let rust_type = format_ident!("{}", rust_type_for_bits(*bits));
+                    let rust_type = field_rust_type(*bits, *is_float);
                     Some(FieldDescriptor {
                         name: field_name,
                         type_tokens: FieldType::OptionalPrimitive(rust_type),
+                        revision: lower_revision(revision),
+                        description: description.clone(),
+                        constraints: lower_constraints(constraints),
+                        typed: lower_value_type(name, *bits, *value_type)?,
                     })
                 }
             }
-            IRElement::Enum { name, .. } => {
+            IRElement::Enum { name, revision, description, .. } => {
                 let field_name = to_snake_case(name);
                 let enum_type = to_pascal_case(name);
                 Some(FieldDescriptor {
                     name: field_name,
                     type_tokens: FieldType::OptionalEnum(enum_type),
+                    revision: lower_revision(revision),
+                    description: description.clone(),
+                    constraints: FieldConstraints::default(),
+                    typed: None,
                 })
             }
-            _ => panic!("EPB can only contain Field or Enum"),
+            _ => return Err("EPB can only contain Field or Enum".to_string()),
         },
-        IRElement::Enum { name, .. } => {
+        IRElement::Enum { name, revision, description, .. } => {
             let field_name = to_snake_case(name);
             let enum_type = to_pascal_case(name);
             Some(FieldDescriptor {
                 name: field_name,
                 type_tokens: FieldType::Enum(enum_type),
+                revision: lower_revision(revision),
+                description: description.clone(),
+                constraints: FieldConstraints::default(),
+                typed: None,
             })
         }
-        IRElement::Spare { .. } => None,
+        IRElement::Spare { .. } | IRElement::Derived { .. } | IRElement::Custom { .. } => None,
+    })
+}
+
+/// Fails if a typed value doesn't fit the `u64` of its conversions, or an
+/// ICAO address isn't 24 bits wide.
+fn lower_value_type(name: &str, bits: usize, value_type: Option<IRValueType>) -> Result<Option<TypedValue>, String> {
+    let Some(value_type) = value_type else { return Ok(None) };
+    let value_type = match value_type {
+        IRValueType::FlightLevel => ValueType::FlightLevel,
+        IRValueType::Altitude => ValueType::Altitude,
+        IRValueType::GroundSpeed => ValueType::GroundSpeed,
+        IRValueType::Heading => ValueType::Heading,
+        IRValueType::Vx => ValueType::Vx,
+        IRValueType::Vy => ValueType::Vy,
+        IRValueType::Icao => ValueType::Icao,
+    };
+    if bits > 64 {
+        return Err(format!("Typed field {} has {} bits, at most 64 are supported", name, bits));
+    }
+    if value_type == ValueType::Icao && bits != 24 {
+        return Err(format!("ICAO address field {} has {} bits, 24 expected", name, bits));
+    }
+    Ok(Some(TypedValue { value_type, bits }))
+}
+
+fn lower_revision(revision: &IRRevision) -> FieldRevision {
+    FieldRevision {
+        deprecated_since: revision.deprecated_since.clone(),
+        renamed_from: revision.renamed_from.as_deref().map(to_snake_case),
+    }
+}
+
+fn lower_constraints(constraints: &IRConstraints) -> FieldConstraints {
+    FieldConstraints {
+        min: constraints.min,
+        max: constraints.max,
+        multiple_of: constraints.multiple_of,
+    }
+}
+
+// ── Derived Value Lowering ────────────────────────────────────────────────
+
+/// Types a derived value can have.
+const DERIVED_TYPES: [&str; 10] = ["f64", "f32", "u8", "u16", "u32", "u64", "i8", "i16", "i32", "i64"];
+
+fn is_derived(element: &IRElement) -> bool {
+    matches!(element, IRElement::Derived { .. })
+}
+
+/// Lowers the derived values of a layout, with the struct each belongs to.
+fn lower_derived(parent_name: &Ident, layout: &IRLayout) -> Result<Vec<DerivedField>, String> {
+    match layout {
+        IRLayout::Fixed { elements, .. } | IRLayout::Explicit { elements, .. } => {
+            lower_derived_fields(parent_name, elements)
+        }
+        IRLayout::Extended { part_groups, .. } => {
+            let mut derived = Vec::new();
+            for group in part_groups {
+                derived.extend(lower_derived_fields(&format_ident!("{}Part{}", parent_name, group.index), &group.elements)?);
+            }
+            Ok(derived)
+        }
+        IRLayout::Repetitive { elements, .. } => {
+            lower_derived_fields(&format_ident!("{}Element", parent_name), elements)
+        }
+        IRLayout::Compound { sub_items } => {
+            let mut derived = Vec::new();
+            for sub in sub_items {
+                derived.extend(lower_derived(&format_ident!("{}Sub{}", parent_name, sub.index), &sub.layout)?);
+            }
+            Ok(derived)
+        }
+    }
+}
+
+/// Fails if a derived value has an unsupported type, an expression that
+/// doesn't parse, or refers to a field that is not numeric, or if the geo
+/// roles of the values don't make up one position.
+fn lower_derived_fields(struct_name: &Ident, elements: &[IRElement]) -> Result<Vec<DerivedField>, String> {
+    let mut derived = Vec::new();
+    for element in elements {
+        let IRElement::Derived { name, rust_type, expr, description, geo } = element else {
+            continue;
+        };
+        if !DERIVED_TYPES.contains(&rust_type.as_str()) {
+            return Err(format!("Invalid type of derived field {}: {}", name, rust_type));
+        }
+        let expr = syn::parse_str::<syn::Expr>(expr)
+            .map_err(|e| format!("Invalid expression of derived field {}: {}", name, e))?;
+        let mut inputs = Vec::new();
+        collect_derived_inputs(name, &expr, elements, &mut inputs)?;
+        let expr = expr.to_token_stream();
+
+        let geo = match geo {
+            Some(role) => {
+                let role = GeoRole::parse(role)
+                    .ok_or_else(|| format!("Invalid geo role of derived field {}: {}", name, role))?;
+                if inputs.iter().any(|input| input.is_optional) {
+                    return Err(format!("Derived field {} has a geo role but depends on an optional field", name));
+                }
+                Some(role)
+            }
+            None => None,
+        };
+
+        derived.push(DerivedField {
+            struct_name: struct_name.clone(),
+            name: to_snake_case(name),
+            rust_type: format_ident!("{}", rust_type),
+            expr,
+            inputs,
+            description: description.clone(),
+            geo,
+        });
+    }
+
+    for field in &derived {
+        let Some(role) = field.geo else { continue };
+        let count = |role| derived.iter().filter(|other| other.geo == Some(role)).count();
+        if count(role) != 1 {
+            return Err(format!("Derived fields of {} have geo role {:?} more than once", struct_name, role));
+        }
+        if count(role.counterpart()) != 1 {
+            return Err(format!(
+                "Derived field {} has geo role {:?} but no derived field of {} has {:?}",
+                field.name, role, struct_name, role.counterpart()
+            ));
+        }
+    }
+    Ok(derived)
+}
+
+/// Collects the fields among `elements` that the expression refers to.
+///
+/// # Errors
+///
+/// Fails if the expression uses anything but numeric literals, numeric
+/// fields of `elements`, arithmetic, casts to numeric types and
+/// parentheses, since it is pasted into the generated code as is.
+fn collect_derived_inputs(
+    derived: &str,
+    expr: &syn::Expr,
+    elements: &[IRElement],
+    inputs: &mut Vec<DerivedInput>,
+) -> Result<(), String> {
+    use syn::{BinOp, Expr, Lit, Type, UnOp};
+
+    match expr {
+        Expr::Lit(lit) if matches!(lit.lit, Lit::Int(_) | Lit::Float(_)) => Ok(()),
+        Expr::Path(path) if path.qself.is_none() && path.path.get_ident().is_some() => {
+            let ident = path.path.get_ident().unwrap();
+            if !inputs.iter().any(|input| input.name == *ident) {
+                inputs.push(derived_input(derived, ident, elements)?);
+            }
+            Ok(())
+        }
+        Expr::Unary(unary) if matches!(unary.op, UnOp::Neg(_)) => {
+            collect_derived_inputs(derived, &unary.expr, elements, inputs)
+        }
+        Expr::Binary(binary)
+            if matches!(binary.op, BinOp::Add(_) | BinOp::Sub(_) | BinOp::Mul(_) | BinOp::Div(_) | BinOp::Rem(_)) =>
+        {
+            collect_derived_inputs(derived, &binary.left, elements, inputs)?;
+            collect_derived_inputs(derived, &binary.right, elements, inputs)
+        }
+        Expr::Cast(cast)
+            if matches!(&*cast.ty, Type::Path(ty) if ty.qself.is_none()
+                && ty.path.get_ident().is_some_and(|ident| DERIVED_TYPES.contains(&ident.to_string().as_str()))) =>
+        {
+            collect_derived_inputs(derived, &cast.expr, elements, inputs)
+        }
+        Expr::Paren(paren) => collect_derived_inputs(derived, &paren.expr, elements, inputs),
+        Expr::Group(group) => collect_derived_inputs(derived, &group.expr, elements, inputs),
+        expr => Err(format!(
+            "Derived field {} uses `{}`: only numeric literals, fields, arithmetic, casts and parentheses are allowed",
+            derived,
+            expr.to_token_stream()
+        )),
+    }
+}
+
+/// The field among `elements` named `ident`, an input of a derived field.
+fn derived_input(derived: &str, ident: &Ident, elements: &[IRElement]) -> Result<DerivedInput, String> {
+    let field = elements.iter().find_map(|element| {
+        let (element, is_optional) = match element {
+            IRElement::EPB { content } => (content.as_ref(), true),
+            element => (element, false),
+        };
+        match element {
+            IRElement::Field { name, .. } | IRElement::Enum { name, .. } if to_snake_case(name) == *ident => {
+                Some((element, is_optional))
+            }
+            _ => None,
+        }
+    });
+    match field {
+        Some((IRElement::Field { is_string: false, is_bytes: false, .. }, is_optional)) => {
+            Ok(DerivedInput { name: ident.clone(), field: ident.clone(), is_optional })
+        }
+        Some(_) => Err(format!("Derived field {} refers to {}, which is not a numeric field", derived, ident)),
+        None => Err(format!("Derived field {} refers to {}, which is not a field of its item", derived, ident)),
+    }
+}
+
+// ── Example Lowering ──────────────────────────────────────────────────────
+
+/// Resolves the field paths and values of a worked example.
+///
+/// # Errors
+///
+/// Fails if a path doesn't name a field of the item, or a value doesn't
+/// fit its field.
+fn lower_example(item: &IRItem, example: &IRExample) -> Result<LoweredExample, String> {
+    let checks = example.values.iter().map(|(path, value)| {
+        let mut steps = Vec::new();
+        let element = resolve_example_field(&item.layout, &mut path.split('.'), &mut steps)
+            .ok_or_else(|| format!("Example of item {:03} refers to `{}`, which is not a field", item.id, path))?;
+        let value = lower_expected_value(element, value)
+            .map_err(|e| format!("Example of item {:03} gives `{}` for {}: {}", item.id, value, path, e))?;
+        Ok(ExampleCheck { path: path.clone(), steps, value })
+    }).collect::<Result<_, String>>()?;
+
+    Ok(LoweredExample {
+        bytes: example.bytes.clone(),
+        description: example.description.clone(),
+        checks,
+    })
+}
+
+/// Follows a field path (`sac`, `part1.c`, `sub0.flags`, `2.azimuth`)
+/// through a layout, returning the field's element.
+fn resolve_example_field<'a>(
+    layout: &'a IRLayout,
+    segments: &mut std::str::Split<'_, char>,
+    steps: &mut Vec<ExampleStep>,
+) -> Option<&'a IRElement> {
+    let segment = segments.next()?;
+    let (elements, field) = match layout {
+        IRLayout::Fixed { elements, .. } | IRLayout::Explicit { elements, .. } => (elements, segment),
+        IRLayout::Extended { part_groups, .. } => {
+            let index: usize = segment.strip_prefix("part")?.parse().ok()?;
+            let position = part_groups.iter().position(|group| group.index == index)?;
+            steps.push(ExampleStep::Part(position));
+            (&part_groups[position].elements, segments.next()?)
+        }
+        IRLayout::Repetitive { count, elements, .. } => {
+            let repetition: usize = segment.parse().ok()?;
+            if repetition >= *count {
+                return None;
+            }
+            steps.push(ExampleStep::Element(repetition));
+            (elements, segments.next()?)
+        }
+        IRLayout::Compound { sub_items } => {
+            let index: usize = segment.strip_prefix("sub")?.parse().ok()?;
+            let position = sub_items.iter().position(|sub| sub.index == index)?;
+            steps.push(ExampleStep::SubItem(position));
+            return resolve_example_field(&sub_items[position].layout, segments, steps);
+        }
+    };
+
+    let name = to_snake_case(field);
+    let (position, element) = elements
+        .iter()
+        .filter_map(|element| Some((lower_field(element).ok().flatten()?, element)))
+        .enumerate()
+        .find(|(_, (descriptor, _))| descriptor.name == name)
+        .map(|(position, (_, element))| (position, element))?;
+    steps.push(ExampleStep::Field(position));
+    segments.next().is_none().then_some(element)
+}
+
+/// Parses the expected value of a field: a decimal or `0x` hex number, a
+/// decimal float, a string, a variant name or value, or `none` for an EPB
+/// field.
+fn lower_expected_value(element: &IRElement, value: &str) -> Result<ExpectedValue, String> {
+    let (element, is_optional) = match element {
+        IRElement::EPB { content } => (content.as_ref(), true),
+        element => (element, false),
+    };
+    if value == "none" {
+        return if is_optional { Ok(ExpectedValue::Absent) } else { Err("the field is not optional".to_string()) };
+    }
+
+    match element {
+        IRElement::Field { bits, is_string: true, .. } => {
+            if value.len() > bits / 8 {
+                return Err(format!("the field holds {} bytes", bits / 8));
+            }
+            Ok(ExpectedValue::Text(value.to_string()))
+        }
+        IRElement::Field { bits, is_bytes: true, .. } => {
+            let bytes = rasterix_core::hex::decode_loose(value).map_err(|_| "not hex bytes")?;
+            if bytes.len() != bits / 8 {
+                return Err(format!("the field holds {} bytes", bits / 8));
+            }
+            Ok(ExpectedValue::Bytes(bytes))
+        }
+        IRElement::Field { is_float: true, .. } => {
+            let number = value.parse::<f64>().ok().filter(|number| number.is_finite()).ok_or("not a finite float")?;
+            Ok(ExpectedValue::Float(number))
+        }
+        IRElement::Field { bits, .. } => {
+            let number = parse_example_number(value).ok_or("not a number")?;
+            if *bits < 128 && number >> bits != 0 {
+                return Err(format!("the field has {} bits", bits));
+            }
+            Ok(ExpectedValue::Number(number))
+        }
+        IRElement::Enum { values, .. } => {
+            let number = parse_example_number(value);
+            values
+                .iter()
+                .position(|(name, raw)| name == value || number == Some(*raw as u128))
+                .map(ExpectedValue::Variant)
+                .ok_or_else(|| "no variant has this name or value".to_string())
+        }
+        _ => unreachable!("examples resolve to fields"),
+    }
+}
+
+fn parse_example_number(value: &str) -> Option<u128> {
+    match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+        Some(hex) => u128::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
     }
 }
 
 // ── Decode Op Lowering ────────────────────────────────────────────────────
 
-fn lower_decode_ops(elements: &[IRElement], is_explicit: bool) -> Vec<DecodeOp> {
+fn lower_decode_ops(elements: &[IRElement], is_explicit: bool, byte_size: usize) -> Result<Vec<DecodeOp>, String> {
     let mut ops = Vec::new();
     if is_explicit {
-        ops.push(DecodeOp::ReadLengthByte);
+        ops.push(DecodeOp::ReadLengthByte { total_bytes: byte_size + 1 });
     }
-    ops.extend(lower_element_ops_decode(elements));
-    ops
+    ops.extend(lower_element_ops_decode(elements)?);
+    Ok(ops)
 }
 
-fn lower_element_ops_decode(elements: &[IRElement]) -> Vec<DecodeOp> {
-    elements.iter().map(lower_element_decode).collect()
+fn lower_element_ops_decode(elements: &[IRElement]) -> Result<Vec<DecodeOp>, String> {
+    elements.iter().filter(|element| !is_derived(element)).map(lower_element_decode).collect()
 }
 
-fn lower_element_decode(element: &IRElement) -> DecodeOp {
-    match element {
-        IRElement::Field { name, bits, is_string } => {
+fn lower_element_decode(element: &IRElement) -> Result<DecodeOp, String> {
+    Ok(match element {
+        IRElement::Field { name, bits, is_string, little_endian, is_float, is_bytes, .. } => {
             if *is_string {
                 DecodeOp::ReadString {
                     name: to_snake_case(name),
                     byte_len: bits / 8,
                 }
+            } else if *is_bytes {
+                DecodeOp::ReadBytes {
+                    name: to_snake_case(name),
+                    byte_len: bits / 8,
+                }
             } else {
                 DecodeOp::ReadField {
                     name: to_snake_case(name),
                     bits: *bits,
-                    rust_type: format_ident!("{}", rust_type_for_bits(*bits)),
+                    rust_type: field_rust_type(*bits, *is_float),
+                    little_endian: *little_endian,
                 }
             }
         }
         IRElement::EPB { content } 
-            => lower_epb_element_decode(content.as_ref()),
+            => lower_epb_element_decode(content.as_ref())?,
         IRElement::Enum { name, bits, .. } 
             => DecodeOp::ReadEnum {
                 name: to_snake_case(name),
@@ -261,22 +764,33 @@ fn lower_element_decode(element: &IRElement) -> DecodeOp {
             },
         IRElement::Spare { bits }
             => DecodeOp::SkipSpare { bits: *bits },
-    }
+        IRElement::Derived { .. } => unreachable!("derived values are not on the wire"),
+        IRElement::Custom { tag, code, .. } => {
+            let code = resolved_custom_code(tag, code)?;
+            DecodeOp::Custom { tag: tag.clone(), bits: code.bits, code: parse_custom_code(tag, &code.decode)? }
+        }
+    })
 }
 
-fn lower_epb_element_decode(element: &IRElement) -> DecodeOp {
-    match element { 
-        IRElement::Field { name, bits, is_string } => {
+fn lower_epb_element_decode(element: &IRElement) -> Result<DecodeOp, String> {
+    Ok(match element { 
+        IRElement::Field { name, bits, is_string, little_endian, is_float, is_bytes, .. } => {
             if *is_string {
                 DecodeOp::ReadEpbString {
                     name: to_snake_case(name),
                     byte_len: bits / 8,
                 }
+            } else if *is_bytes {
+                DecodeOp::ReadEpbBytes {
+                    name: to_snake_case(name),
+                    byte_len: bits / 8,
+                }
             } else {
                 DecodeOp::ReadEpbField {
                     name: to_snake_case(name),
                     bits: *bits,
-                    rust_type: format_ident!("{}", rust_type_for_bits(*bits)),
+                    rust_type: field_rust_type(*bits, *is_float),
+                    little_endian: *little_endian,
                 }
             }
         }
@@ -285,61 +799,94 @@ fn lower_epb_element_decode(element: &IRElement) -> DecodeOp {
             bits: *bits,
             enum_type: to_pascal_case(name),
         },
-        _ => panic!("EPB can only contain Field or Enum"),
-    }
+        _ => return Err("EPB can only contain Field or Enum".to_string()),
+    })
 }
 
 // ── Encode Op Lowering ────────────────────────────────────────────────────
 
-fn lower_encode_ops(elements: &[IRElement], is_explicit: bool, byte_size: usize) -> Vec<EncodeOp> {
+fn lower_encode_ops(elements: &[IRElement], is_explicit: bool, byte_size: usize) -> Result<Vec<EncodeOp>, String> {
     let mut ops = Vec::new();
     if is_explicit {
         ops.push(EncodeOp::WriteLengthByte { total_bytes: byte_size + 1 });
     }
-    ops.extend(lower_element_ops_encode(elements));
-    ops
+    ops.extend(lower_element_ops_encode(elements)?);
+    Ok(ops)
 }
 
-fn lower_element_ops_encode(elements: &[IRElement]) -> Vec<EncodeOp> {
-    elements.iter().map(lower_element_encode).collect()
+fn lower_element_ops_encode(elements: &[IRElement]) -> Result<Vec<EncodeOp>, String> {
+    elements.iter().filter(|element| !is_derived(element)).map(lower_element_encode).collect()
 }
 
-fn lower_element_encode(element: &IRElement) -> EncodeOp {
-    match element {
-        IRElement::Field { name, bits, is_string } => {
+fn lower_element_encode(element: &IRElement) -> Result<EncodeOp, String> {
+    Ok(match element {
+        IRElement::Field { name, bits, is_string, little_endian, is_float, is_bytes, .. } => {
             if *is_string {
                 EncodeOp::WriteString {
                     name: to_snake_case(name),
                     byte_len: bits / 8,
                 }
+            } else if *is_bytes {
+                EncodeOp::WriteBytes {
+                    name: to_snake_case(name),
+                    byte_len: bits / 8,
+                }
             } else {
                 EncodeOp::WriteField {
                     name: to_snake_case(name),
                     bits: *bits,
+                    little_endian: *little_endian,
+                    float: *is_float,
                 }
             }
         }
-        IRElement::EPB { content } => lower_epb_element_encode(content.as_ref()),
+        IRElement::EPB { content } => lower_epb_element_encode(content.as_ref())?,
         IRElement::Enum { name, bits, .. } => EncodeOp::WriteEnum {
             name: to_snake_case(name),
             bits: *bits,
         },
         IRElement::Spare { bits } => EncodeOp::WriteSpare { bits: *bits },
-    }
+        IRElement::Derived { .. } => unreachable!("derived values are not on the wire"),
+        IRElement::Custom { tag, code, .. } => {
+            let code = resolved_custom_code(tag, code)?;
+            EncodeOp::Custom { tag: tag.clone(), bits: code.bits, code: parse_custom_code(tag, &code.encode)? }
+        }
+    })
 }
 
-fn lower_epb_element_encode(element: &IRElement) -> EncodeOp {
-    match element {
-        IRElement::Field { name, bits, is_string } => {
+/// Returns the code of a custom element resolved by its plugin.
+///
+/// Fails if no plugin handled the element.
+fn resolved_custom_code<'a>(tag: &str, code: &'a Option<IRCustomCode>) -> Result<&'a IRCustomCode, String> {
+    code.as_ref().ok_or_else(|| format!("Custom element <{}> has no plugin", tag))
+}
+
+/// Parses the code a plugin gave for a custom element.
+///
+/// Fails if the code is not valid Rust tokens.
+fn parse_custom_code(tag: &str, code: &str) -> Result<TokenStream, String> {
+    code.parse().map_err(|e| format!("Invalid code for custom element <{}>: {}", tag, e))
+}
+
+fn lower_epb_element_encode(element: &IRElement) -> Result<EncodeOp, String> {
+    Ok(match element {
+        IRElement::Field { name, bits, is_string, little_endian, is_float, is_bytes, .. } => {
             if *is_string {
                 EncodeOp::WriteEpbString {
                     name: to_snake_case(name),
                     byte_len: bits / 8,
                 }
+            } else if *is_bytes {
+                EncodeOp::WriteEpbBytes {
+                    name: to_snake_case(name),
+                    byte_len: bits / 8,
+                }
             } else {
                 EncodeOp::WriteEpbField {
                     name: to_snake_case(name),
                     bits: *bits,
+                    little_endian: *little_endian,
+                    float: *is_float,
                 }
             }
         }
@@ -347,61 +894,91 @@ fn lower_epb_element_encode(element: &IRElement) -> EncodeOp {
             name: to_snake_case(name),
             bits: *bits,
         },
-        _ => panic!("EPB can only contain Field or Enum"),
-    }
+        _ => return Err("EPB can only contain Field or Enum".to_string()),
+    })
 }
 
 // ── Enum Collection ───────────────────────────────────────────────────────
 
-fn collect_and_lower_enums(layout: &IRLayout) -> Vec<LoweredEnum> {
+fn collect_and_lower_enums(layout: &IRLayout) -> Result<Vec<LoweredEnum>, String> {
     let mut enums = Vec::new();
     match layout {
         IRLayout::Fixed { elements, .. } | IRLayout::Explicit { elements, .. } => {
-            collect_enums_from_elements(elements, &mut enums);
+            collect_enums_from_elements(elements, &mut enums)?;
         }
         IRLayout::Extended { part_groups, .. } => {
             for group in part_groups {
-                collect_enums_from_elements(&group.elements, &mut enums);
+                collect_enums_from_elements(&group.elements, &mut enums)?;
             }
         }
         IRLayout::Repetitive { elements, .. } => {
-            collect_enums_from_elements(elements, &mut enums);
+            collect_enums_from_elements(elements, &mut enums)?;
         }
         IRLayout::Compound { sub_items } => {
             for sub_item in sub_items {
-                enums.extend(collect_and_lower_enums(&sub_item.layout));
+                enums.extend(collect_and_lower_enums(&sub_item.layout)?);
             }
         }
     }
-    enums
+    Ok(enums)
 }
 
-fn collect_enums_from_elements(elements: &[IRElement], enums: &mut Vec<LoweredEnum>) {
+fn collect_enums_from_elements(elements: &[IRElement], enums: &mut Vec<LoweredEnum>) -> Result<(), String> {
     for element in elements {
         match element {
-            IRElement::Enum { name, bits: _, values } => {
-                enums.push(lower_enum(name, values));
-            }
+            IRElement::Enum { .. } => enums.push(lower_enum(element)?),
             IRElement::EPB { content } => {
-                if let IRElement::Enum { name, bits: _, values } = content.as_ref() {
-                    enums.push(lower_enum(name, values));
+                if let IRElement::Enum { .. } = content.as_ref() {
+                    enums.push(lower_enum(content)?);
                 }
             }
             _ => {}
         }
     }
+    Ok(())
 }
 
-fn lower_enum(name: &str, values: &[(String, u8)]) -> LoweredEnum {
-    LoweredEnum {
+fn lower_enum(element: &IRElement) -> Result<LoweredEnum, String> {
+    let IRElement::Enum { name, values, aliases, unknown, .. } = element else {
+        unreachable!("lower_enum called on a non-enum element")
+    };
+    let lowered = LoweredEnum {
         name: to_pascal_case(name),
         variants: values.iter().map(|(vname, vval)| {
             LoweredEnumVariant {
                 name: to_pascal_case(vname),
                 value: *vval,
+                alias: aliases
+                    .iter()
+                    .find(|(variant, _)| variant == vname)
+                    .map(|(_, alias)| to_pascal_case(alias)),
             }
         }).collect(),
+        unknown: match unknown {
+            IRUnknownValues::Preserve => UnknownValues::Preserve,
+            IRUnknownValues::Error => UnknownValues::Error,
+            IRUnknownValues::MapTo(target) => UnknownValues::MapTo(to_pascal_case(target)),
+        },
+    };
+    check_variant_aliases(&lowered)?;
+    Ok(lowered)
+}
+
+/// Fails if a variant alias is already the name of a variant or of
+/// another alias of the enum.
+fn check_variant_aliases(lowered: &LoweredEnum) -> Result<(), String> {
+    let mut names: Vec<&Ident> = lowered.variants.iter().map(|v| &v.name).collect();
+    let unknown = format_ident!("Unknown");
+    if lowered.unknown == UnknownValues::Preserve {
+        names.push(&unknown);
+    }
+    for alias in lowered.variants.iter().filter_map(|v| v.alias.as_ref()) {
+        if names.contains(&alias) {
+            return Err(format!("Enum alias collision: {} is already a name of enum {}", alias, lowered.name));
+        }
+        names.push(alias);
     }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -415,25 +992,32 @@ mod tests {
                 id: 48,
                 items: vec![IRItem {
                     id: 10,
-                    frn: 0,
+                    frn: 1,
+                    title: None,
+                    short: None,
+                    reserved: false,
+                    mandatory: false,
+                    examples: vec![],
                     layout: IRLayout::Fixed {
                         bytes: 2,
                         elements: vec![
-                            IRElement::Field { name: "sac".to_string(), bits: 8 , is_string: false},
-                            IRElement::Field { name: "sic".to_string(), bits: 8, is_string: false},
+                            IRElement::Field { name: "sac".to_string(), bits: 8 , is_string: false, revision: IRRevision::default(), description: None, constraints: IRConstraints::default(), value_type: None, little_endian: false, is_float: false, is_bytes: false },
+                            IRElement::Field { name: "sic".to_string(), bits: 8, is_string: false, revision: IRRevision::default(), description: None, constraints: IRConstraints::default(), value_type: None, little_endian: false, is_float: false, is_bytes: false },
                         ],
                     },
                 }],
             },
         };
 
-        let lowered = lower(&ir);
+        let lowered = lower(&ir).unwrap();
         assert_eq!(lowered.category_id, 48);
         assert_eq!(lowered.module_name, format_ident!("cat048"));
         assert_eq!(lowered.items.len(), 1);
 
         let item = &lowered.items[0];
         assert_eq!(item.name, format_ident!("Item010"));
+        assert!(item.is_hashable);
+        assert!(lowered.record.is_hashable);
 
         match &item.kind {
             LoweredItemKind::Simple { is_explicit, fields, decode_ops, encode_ops, .. } => {
@@ -455,24 +1039,29 @@ mod tests {
                 id: 48,
                 items: vec![IRItem {
                     id: 20,
-                    frn: 1,
+                    frn: 2,
+                    title: None,
+                    short: None,
+                    reserved: false,
+                    mandatory: false,
+                    examples: vec![],
                     layout: IRLayout::Explicit {
                         bytes: 2,
                         elements: vec![
-                            IRElement::Field { name: "data".to_string(), bits: 16, is_string: false },
+                            IRElement::Field { name: "data".to_string(), bits: 16, is_string: false, revision: IRRevision::default(), description: None, constraints: IRConstraints::default(), value_type: None, little_endian: false, is_float: false, is_bytes: false },
                         ],
                     },
                 }],
             },
         };
 
-        let lowered = lower(&ir);
+        let lowered = lower(&ir).unwrap();
         let item = &lowered.items[0];
 
         match &item.kind {
             LoweredItemKind::Simple { is_explicit, decode_ops, encode_ops, .. } => {
                 assert!(is_explicit);
-                assert!(matches!(decode_ops[0], DecodeOp::ReadLengthByte));
+                assert!(matches!(decode_ops[0], DecodeOp::ReadLengthByte { total_bytes: 3 }));
                 assert!(matches!(encode_ops[0], EncodeOp::WriteLengthByte { total_bytes: 3 }));
             }
             _ => panic!("Expected Simple kind"),
@@ -486,11 +1075,16 @@ mod tests {
                 id: 48,
                 items: vec![IRItem {
                     id: 20,
-                    frn: 1,
+                    frn: 2,
+                    title: None,
+                    short: None,
+                    reserved: false,
+                    mandatory: false,
+                    examples: vec![],
                     layout: IRLayout::Fixed {
                         bytes: 1,
                         elements: vec![
-                            IRElement::Field { name: "data".to_string(), bits: 3, is_string: false },
+                            IRElement::Field { name: "data".to_string(), bits: 3, is_string: false, revision: IRRevision::default(), description: None, constraints: IRConstraints::default(), value_type: None, little_endian: false, is_float: false, is_bytes: false },
                             IRElement::Spare { bits: 5 },
                         ],
                     },
@@ -498,7 +1092,7 @@ mod tests {
             },
         };
 
-        let lowered = lower(&ir);
+        let lowered = lower(&ir).unwrap();
         let item = &lowered.items[0];
 
         match &item.kind {
@@ -520,7 +1114,12 @@ mod tests {
                 id: 48,
                 items: vec![IRItem {
                     id: 30,
-                    frn: 2,
+                    frn: 3,
+                    title: None,
+                    short: None,
+                    reserved: false,
+                    mandatory: false,
+                    examples: vec![],
                     layout: IRLayout::Fixed {
                         bytes: 2,
                         elements: vec![
@@ -529,6 +1128,13 @@ mod tests {
                                     name: "opt_val".to_string(),
                                     bits: 15,
                                     is_string: false,
+                                    revision: IRRevision::default(),
+                                    description: None,
+                                    constraints: IRConstraints::default(),
+                                    value_type: None,
+                                    little_endian: false,
+                                    is_float: false,
+                                    is_bytes: false,
                                 }),
                             },
                         ],
@@ -537,7 +1143,7 @@ mod tests {
             },
         };
 
-        let lowered = lower(&ir);
+        let lowered = lower(&ir).unwrap();
         let item = &lowered.items[0];
 
         match &item.kind {
@@ -557,7 +1163,12 @@ mod tests {
                 id: 48,
                 items: vec![IRItem {
                     id: 20,
-                    frn: 1,
+                    frn: 2,
+                    title: None,
+                    short: None,
+                    reserved: false,
+                    mandatory: false,
+                    examples: vec![],
                     layout: IRLayout::Fixed {
                         bytes: 1,
                         elements: vec![
@@ -568,6 +1179,10 @@ mod tests {
                                     ("PSR".to_string(), 1),
                                     ("SSR".to_string(), 2),
                                 ],
+                                aliases: vec![],
+                                unknown: IRUnknownValues::Preserve,
+                                revision: IRRevision::default(),
+                                description: None,
                             },
                             IRElement::Spare { bits: 5 },
                         ],
@@ -576,7 +1191,7 @@ mod tests {
             },
         };
 
-        let lowered = lower(&ir);
+        let lowered = lower(&ir).unwrap();
         let item = &lowered.items[0];
 
         assert_eq!(item.enums.len(), 1);
@@ -592,23 +1207,19 @@ mod tests {
             category: IRCategory {
                 id: 48,
                 items: vec![
-                    IRItem { id: 10, frn: 0, layout: IRLayout::Fixed { bytes: 2, elements: vec![] } },
-                    IRItem { id: 20, frn: 1, layout: IRLayout::Fixed { bytes: 1, elements: vec![] } },
-                    IRItem { id: 140, frn: 7, layout: IRLayout::Fixed { bytes: 2, elements: vec![] } },
+                    IRItem { id: 10, frn: 1, title: None, short: None, reserved: false, mandatory: false, layout: IRLayout::Fixed { bytes: 2, elements: vec![] }, examples: vec![] },
+                    IRItem { id: 20, frn: 2, title: None, short: None, reserved: false, mandatory: false, layout: IRLayout::Fixed { bytes: 1, elements: vec![] }, examples: vec![] },
+                    IRItem { id: 140, frn: 8, title: None, short: None, reserved: false, mandatory: false, layout: IRLayout::Fixed { bytes: 2, elements: vec![] }, examples: vec![] },
                 ],
             },
         };
 
-        let lowered = lower(&ir);
+        let lowered = lower(&ir).unwrap();
         let record = &lowered.record;
 
         assert_eq!(record.entries.len(), 3);
-        assert_eq!(record.entries[0].fspec_byte, 0);
-        assert_eq!(record.entries[0].fspec_bit, 0);
-        assert_eq!(record.entries[1].fspec_byte, 0);
-        assert_eq!(record.entries[1].fspec_bit, 1);
-        assert_eq!(record.entries[2].fspec_byte, 1);
-        assert_eq!(record.entries[2].fspec_bit, 0);
+        let frns: Vec<_> = record.entries.iter().map(|entry| entry.frn).collect();
+        assert_eq!(frns, [1, 2, 8]);
     }
 
     #[test]
@@ -618,21 +1229,26 @@ mod tests {
                 id: 48,
                 items: vec![IRItem {
                     id: 20,
-                    frn: 1,
+                    frn: 2,
+                    title: None,
+                    short: None,
+                    reserved: false,
+                    mandatory: false,
+                    examples: vec![],
                     layout: IRLayout::Extended {
                         bytes: 2,
                         part_groups: vec![
                             IRPartGroup {
                                 index: 0,
                                 elements: vec![
-                                    IRElement::Field { name: "a".to_string(), bits: 3, is_string: false },
-                                    IRElement::Field { name: "b".to_string(), bits: 4, is_string: false },
+                                    IRElement::Field { name: "a".to_string(), bits: 3, is_string: false, revision: IRRevision::default(), description: None, constraints: IRConstraints::default(), value_type: None, little_endian: false, is_float: false, is_bytes: false },
+                                    IRElement::Field { name: "b".to_string(), bits: 4, is_string: false, revision: IRRevision::default(), description: None, constraints: IRConstraints::default(), value_type: None, little_endian: false, is_float: false, is_bytes: false },
                                 ],
                             },
                             IRPartGroup {
                                 index: 1,
                                 elements: vec![
-                                    IRElement::Field { name: "c".to_string(), bits: 7, is_string: false },
+                                    IRElement::Field { name: "c".to_string(), bits: 7, is_string: false, revision: IRRevision::default(), description: None, constraints: IRConstraints::default(), value_type: None, little_endian: false, is_float: false, is_bytes: false },
                                 ],
                             },
                         ],
@@ -641,7 +1257,7 @@ mod tests {
             },
         };
 
-        let lowered = lower(&ir);
+        let lowered = lower(&ir).unwrap();
         let item = &lowered.items[0];
 
         match &item.kind {
@@ -665,7 +1281,12 @@ mod tests {
                 id: 48,
                 items: vec![IRItem {
                     id: 120,
-                    frn: 5,
+                    frn: 6,
+                    title: None,
+                    short: None,
+                    reserved: false,
+                    mandatory: false,
+                    examples: vec![],
                     layout: IRLayout::Compound {
                         sub_items: vec![
                             IRSubItem {
@@ -673,7 +1294,7 @@ mod tests {
                                 layout: IRLayout::Fixed {
                                     bytes: 2,
                                     elements: vec![
-                                        IRElement::Field { name: "x".to_string(), bits: 16, is_string: false },
+                                        IRElement::Field { name: "x".to_string(), bits: 16, is_string: false, revision: IRRevision::default(), description: None, constraints: IRConstraints::default(), value_type: None, little_endian: false, is_float: false, is_bytes: false },
                                     ],
                                 },
                             },
@@ -682,7 +1303,7 @@ mod tests {
                                 layout: IRLayout::Fixed {
                                     bytes: 1,
                                     elements: vec![
-                                        IRElement::Field { name: "y".to_string(), bits: 8, is_string: false },
+                                        IRElement::Field { name: "y".to_string(), bits: 8, is_string: false, revision: IRRevision::default(), description: None, constraints: IRConstraints::default(), value_type: None, little_endian: false, is_float: false, is_bytes: false },
                                     ],
                                 },
                             },
@@ -692,15 +1313,14 @@ mod tests {
             },
         };
 
-        let lowered = lower(&ir);
+        let lowered = lower(&ir).unwrap();
         let item = &lowered.items[0];
 
         match &item.kind {
             LoweredItemKind::Compound { sub_items } => {
                 assert_eq!(sub_items.len(), 2);
                 assert_eq!(sub_items[0].struct_name, format_ident!("Item120Sub0"));
-                assert_eq!(sub_items[0].fspec_byte, 0);
-                assert_eq!(sub_items[0].fspec_bit, 0);
+                assert_eq!(sub_items[0].frn, 1);
             }
             _ => panic!("Expected Compound kind"),
         }
@@ -713,20 +1333,27 @@ mod tests {
                 id: 48,
                 items: vec![IRItem {
                     id: 240,
-                    frn: 3,
+                    frn: 4,
+                    title: None,
+                    short: None,
+                    reserved: false,
+                    mandatory: false,
+                    examples: vec![],
                     layout: IRLayout::Fixed {
                         bytes: 6,
                         elements: vec![
-                            IRElement::Field { name: "aircraft_id".to_string(), bits: 48, is_string: true },
+                            IRElement::Field { name: "aircraft_id".to_string(), bits: 48, is_string: true, revision: IRRevision::default(), description: None, constraints: IRConstraints::default(), value_type: None, little_endian: false, is_float: false, is_bytes: false },
                         ],
                     },
                 }],
             },
         };
 
-        let lowered = lower(&ir);
+        let lowered = lower(&ir).unwrap();
         let item = &lowered.items[0];
         assert_eq!(item.name, format_ident!("Item240"));
+        assert!(!item.is_hashable);
+        assert!(!lowered.record.is_hashable);
 
         match &item.kind {
             LoweredItemKind::Simple { fields, decode_ops, encode_ops, .. } => {
@@ -746,7 +1373,12 @@ mod tests {
                 id: 48,
                 items: vec![IRItem {
                     id: 30,
-                    frn: 2,
+                    frn: 3,
+                    title: None,
+                    short: None,
+                    reserved: false,
+                    mandatory: false,
+                    examples: vec![],
                     layout: IRLayout::Fixed {
                         bytes: 7,
                         elements: vec![
@@ -755,6 +1387,13 @@ mod tests {
                                     name: "callsign".to_string(),
                                     bits: 48,
                                     is_string: true,
+                                    revision: IRRevision::default(),
+                                    description: None,
+                                    constraints: IRConstraints::default(),
+                                    value_type: None,
+                                    little_endian: false,
+                                    is_float: false,
+                                    is_bytes: false,
                                 }),
                             },
                         ],
@@ -763,7 +1402,7 @@ mod tests {
             },
         };
 
-        let lowered = lower(&ir);
+        let lowered = lower(&ir).unwrap();
         let item = &lowered.items[0];
 
         match &item.kind {
@@ -776,4 +1415,253 @@ mod tests {
             _ => panic!("Expected Simple kind"),
         }
     }
+
+    fn example_item(values: &[(&str, &str)]) -> IR {
+        let field = |name: &str, bits| IRElement::Field { name: name.to_string(), bits, is_string: false, revision: IRRevision::default(), description: None, constraints: IRConstraints::default(), value_type: None, little_endian: false, is_float: false, is_bytes: false };
+        IR {
+            category: IRCategory {
+                id: 48,
+                items: vec![IRItem {
+                    id: 20,
+                    frn: 1,
+                    title: None,
+                    short: None,
+                    reserved: false,
+                    mandatory: false,
+                    examples: vec![IRExample {
+                        bytes: vec![0x41, 0x02],
+                        description: None,
+                        values: values.iter().map(|(path, value)| (path.to_string(), value.to_string())).collect(),
+                    }],
+                    layout: IRLayout::Extended {
+                        bytes: 2,
+                        part_groups: vec![
+                            IRPartGroup {
+                                index: 0,
+                                elements: vec![
+                                    IRElement::Enum { name: "typ".to_string(), bits: 3, values: vec![("PSR".to_string(), 1), ("SSR".to_string(), 2)], aliases: vec![], unknown: IRUnknownValues::Preserve, revision: IRRevision::default(), description: None },
+                                    IRElement::Spare { bits: 1 },
+                                    field("b", 3),
+                                ],
+                            },
+                            IRPartGroup {
+                                index: 1,
+                                elements: vec![IRElement::EPB { content: Box::new(field("c", 6)) }],
+                            },
+                        ],
+                    },
+                }],
+            },
+        }
+    }
+
+    #[test]
+    fn test_lower_example_paths() {
+        let lowered = lower(&example_item(&[("part0.typ", "SSR"), ("part0.b", "0x1"), ("part1.c", "none")])).unwrap();
+        let example = &lowered.items[0].examples[0];
+
+        assert_eq!(example.bytes, [0x41, 0x02]);
+        let checks: Vec<_> = example.checks.iter().map(|check| (check.steps.clone(), check.value.clone())).collect();
+        assert_eq!(checks, [
+            (vec![ExampleStep::Part(0), ExampleStep::Field(0)], ExpectedValue::Variant(1)),
+            (vec![ExampleStep::Part(0), ExampleStep::Field(1)], ExpectedValue::Number(1)),
+            (vec![ExampleStep::Part(1), ExampleStep::Field(0)], ExpectedValue::Absent),
+        ]);
+    }
+
+    #[test]
+    fn test_lower_example_variant_by_value() {
+        let lowered = lower(&example_item(&[("part0.typ", "1")])).unwrap();
+        assert_eq!(lowered.items[0].examples[0].checks[0].value, ExpectedValue::Variant(0));
+    }
+
+    #[test]
+    fn test_example_path_must_name_a_field() {
+        let err = lower(&example_item(&[("part2.c", "1")])).unwrap_err();
+        assert!(err.contains("refers to `part2.c`, which is not a field"), "{}", err);
+    }
+
+    #[test]
+    fn test_example_value_must_fit_its_field() {
+        let err = lower(&example_item(&[("part0.b", "8")])).unwrap_err();
+        assert!(err.contains("the field has 3 bits"), "{}", err);
+    }
+
+    #[test]
+    fn test_example_required_field_has_a_value() {
+        let err = lower(&example_item(&[("part0.b", "none")])).unwrap_err();
+        assert!(err.contains("the field is not optional"), "{}", err);
+    }
+
+    fn derived_item(expr: &str) -> IR {
+        IR {
+            category: IRCategory {
+                id: 62,
+                items: vec![IRItem {
+                    id: 200,
+                    frn: 1,
+                    title: None,
+                    short: None,
+                    reserved: false,
+                    mandatory: false,
+                    examples: vec![],
+                    layout: IRLayout::Fixed {
+                        bytes: 3,
+                        elements: vec![
+                            IRElement::Field { name: "gsp".to_string(), bits: 16, is_string: false, revision: IRRevision::default(), description: None, constraints: IRConstraints::default(), value_type: None, little_endian: false, is_float: false, is_bytes: false },
+                            IRElement::Field { name: "tag".to_string(), bits: 8, is_string: true, revision: IRRevision::default(), description: None, constraints: IRConstraints::default(), value_type: None, little_endian: false, is_float: false, is_bytes: false },
+                            IRElement::Derived { name: "speed".to_string(), rust_type: "f64".to_string(), expr: expr.to_string(), description: None, geo: None },
+                        ],
+                    },
+                }],
+            },
+        }
+    }
+
+    #[test]
+    fn test_lower_derived_field() {
+        let lowered = lower(&derived_item("(gsp as f64 * 0.22 - -1.5) / 2.0 % 360.0")).unwrap();
+        let item = &lowered.items[0];
+
+        let [derived] = &item.derived[..] else { panic!("{:?}", item.derived) };
+        assert_eq!(derived.struct_name, format_ident!("Item200"));
+        assert_eq!(derived.name, format_ident!("speed"));
+        let inputs: Vec<_> = derived.inputs.iter().map(|input| input.field.to_string()).collect();
+        assert_eq!(inputs, ["gsp"]);
+        match &item.kind {
+            LoweredItemKind::Simple { fields, decode_ops, .. } => {
+                assert_eq!(fields.len(), 2);
+                assert_eq!(decode_ops.len(), 2);
+            }
+            _ => panic!("Expected Simple kind"),
+        }
+    }
+
+    #[test]
+    fn test_derived_field_rejects_string_input() {
+        let err = lower(&derived_item("gsp + tag")).unwrap_err();
+        assert!(err.contains("Derived field speed refers to tag, which is not a numeric field"), "{}", err);
+    }
+
+    #[test]
+    fn test_derived_field_rejects_method_calls() {
+        let err = lower(&derived_item("gsp.sqrt() * 2.0")).unwrap_err();
+        assert!(err.contains("Derived field speed uses `gsp . sqrt ()`: only numeric literals, fields, arithmetic, casts and parentheses are allowed"), "{}", err);
+    }
+
+    #[test]
+    fn test_derived_field_rejects_calls() {
+        let err = lower(&derived_item("gsp + std::process::exit(1)")).unwrap_err();
+        assert!(err.contains("Derived field speed uses `std :: process :: exit (1)`"), "{}", err);
+    }
+
+    #[test]
+    fn test_derived_field_rejects_blocks() {
+        let err = lower(&derived_item("{ gsp }")).unwrap_err();
+        assert!(err.contains("Derived field speed uses `{ gsp }`"), "{}", err);
+    }
+
+    #[test]
+    fn test_derived_field_rejects_unknown_names() {
+        let err = lower(&derived_item("gsp + other")).unwrap_err();
+        assert!(err.contains("Derived field speed refers to other, which is not a field of its item"), "{}", err);
+    }
+
+    fn geo_item(roles: &[&str]) -> IR {
+        let mut ir = derived_item("gsp");
+        let IRLayout::Fixed { elements, .. } = &mut ir.category.items[0].layout else { unreachable!() };
+        elements.retain(|element| !matches!(element, IRElement::Derived { .. }));
+        for (i, role) in roles.iter().enumerate() {
+            elements.push(IRElement::Derived {
+                name: format!("value{}", i),
+                rust_type: "f64".to_string(),
+                expr: "gsp / 256.0".to_string(),
+                description: None,
+                geo: Some(role.to_string()),
+            });
+        }
+        ir
+    }
+
+    #[test]
+    fn test_lower_derived_geo_roles() {
+        let lowered = lower(&geo_item(&["azimuth", "range"])).unwrap();
+        let roles: Vec<_> = lowered.items[0].derived.iter().map(|derived| derived.geo).collect();
+        assert_eq!(roles, [Some(GeoRole::Azimuth), Some(GeoRole::Range)]);
+    }
+
+    #[test]
+    fn test_derived_geo_role_needs_counterpart() {
+        let err = lower(&geo_item(&["east", "range", "azimuth"])).unwrap_err();
+        assert!(err.contains("Derived field value0 has geo role East but no derived field of Item200 has North"), "{}", err);
+    }
+
+    #[test]
+    fn test_derived_geo_role_must_be_known() {
+        let err = lower(&geo_item(&["altitude"])).unwrap_err();
+        assert!(err.contains("Invalid geo role of derived field value0: altitude"), "{}", err);
+    }
+
+    fn typed_item(fields: &[(usize, IRValueType)]) -> IR {
+        let mut ir = derived_item("gsp");
+        let IRLayout::Fixed { elements, .. } = &mut ir.category.items[0].layout else { unreachable!() };
+        elements.truncate(2);
+        for (i, (bits, value_type)) in fields.iter().enumerate() {
+            elements.insert(i, IRElement::Field {
+                name: format!("value{}", i),
+                bits: *bits,
+                is_string: false,
+                revision: IRRevision::default(),
+                description: None,
+                constraints: IRConstraints::default(),
+                value_type: Some(*value_type),
+                little_endian: false,
+                is_float: false,
+                is_bytes: false,
+            });
+        }
+        ir
+    }
+
+    #[test]
+    fn test_lower_typed_field() {
+        let lowered = lower(&typed_item(&[(16, IRValueType::FlightLevel)])).unwrap();
+        match &lowered.items[0].kind {
+            LoweredItemKind::Simple { fields, .. } => {
+                assert_eq!(fields[0].typed, Some(TypedValue { value_type: ValueType::FlightLevel, bits: 16 }));
+                assert_eq!(fields[1].typed, None);
+            }
+            _ => panic!("Expected Simple kind"),
+        }
+    }
+
+    #[test]
+    fn test_typed_field_fits_u64() {
+        let err = lower(&typed_item(&[(72, IRValueType::FlightLevel)])).unwrap_err();
+        assert!(err.contains("Typed field value0 has 72 bits, at most 64 are supported"), "{}", err);
+    }
+
+    #[test]
+    fn test_lower_velocity_fields() {
+        lower(&typed_item(&[(16, IRValueType::Heading), (16, IRValueType::GroundSpeed)])).unwrap();
+        lower(&typed_item(&[(10, IRValueType::Vx), (10, IRValueType::Vy)])).unwrap();
+    }
+
+    #[test]
+    fn test_velocity_field_needs_counterpart() {
+        let err = lower(&typed_item(&[(16, IRValueType::GroundSpeed), (16, IRValueType::Vy)])).unwrap_err();
+        assert!(err.contains("Velocity field value0 needs exactly one field of type Heading in its struct"), "{}", err);
+    }
+
+    #[test]
+    fn test_velocity_components_have_same_width() {
+        let err = lower(&typed_item(&[(16, IRValueType::Vx), (14, IRValueType::Vy)])).unwrap_err();
+        assert!(err.contains("Velocity field value0 has 16 bits but its counterpart has 14"), "{}", err);
+    }
+
+    #[test]
+    fn test_icao_field_has_24_bits() {
+        let err = lower(&typed_item(&[(16, IRValueType::Icao)])).unwrap_err();
+        assert!(err.contains("ICAO address field value0 has 16 bits, 24 expected"), "{}", err);
+    }
 }
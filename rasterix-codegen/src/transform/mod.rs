@@ -1,4 +1,17 @@
 pub mod ir;
 pub mod transformer;
 pub mod lower_ir;
-pub mod lowerer;
\ No newline at end of file
+pub mod lowerer;
+pub mod features;
+pub mod renames;
+pub mod storage;
+pub mod lint;
+pub mod plugins;
+
+pub use lower_ir::LoweredIR;
+pub use lowerer::lower;
+pub use features::apply_features;
+pub use renames::apply_renames;
+pub use storage::use_heapless;
+pub use lint::lint;
+pub use plugins::apply_plugins;
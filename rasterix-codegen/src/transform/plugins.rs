@@ -0,0 +1,247 @@
+//! Plugins for custom XML elements.
+//!
+//! Site-specific wire quirks, such as a checksum or padding inside an
+//! item, can be described with elements of their own (`<crc/>`,
+//! `<padding align="16"/>`) handled by an [`ElementPlugin`], instead of
+//! patching the crate.  Custom elements take up bits like spare bits and
+//! don't appear in the generated structs; the plugin gives their size and
+//! the code that reads and writes them.
+//!
+//! ```
+//! use quote::quote;
+//! use rasterix_codegen::builder::{Builder, RustBuilder};
+//! use rasterix_codegen::transform::plugins::{Attributes, ElementPlugin};
+//!
+//! /// A 16-bit checksum, written as `<crc/>`.
+//! struct Crc;
+//!
+//! impl ElementPlugin for Crc {
+//!     fn tag(&self) -> &str {
+//!         "crc"
+//!     }
+//!
+//!     fn bits(&self, _: &Attributes) -> Result<usize, String> {
+//!         Ok(16)
+//!     }
+//!
+//!     fn encode(&self, _: &Attributes, _: usize) -> proc_macro2::TokenStream {
+//!         quote! { writer.write_bits(0xffff, 16)?; }
+//!     }
+//! }
+//!
+//! let code = RustBuilder::new().element_plugin(Crc).build_from_str(r#"<category id="1">
+//!     <item id="10" frn="1">
+//!         <fixed bytes="3"><field name="a" bits="8"/><crc/></fixed>
+//!     </item>
+//! </category>"#).unwrap();
+//!
+//! assert!(code.contains("writer . write_bits (0xffff , 16)"));
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+pub use proc_macro2::TokenStream;
+use quote::quote;
+
+use super::ir::{IRCustomCode, IRElement, IRLayout, IR};
+
+/// Attributes of a custom element by name, e.g. `align` → `16`.
+pub type Attributes = BTreeMap<String, String>;
+
+/// Handler of a custom XML element.
+pub trait ElementPlugin: Send + Sync {
+    /// Tag of the element, e.g. `crc` for `<crc/>`.
+    fn tag(&self) -> &str;
+
+    /// Number of bits the element takes up, given its attributes.
+    ///
+    /// Returns an error for attributes the plugin doesn't accept.
+    fn bits(&self, attributes: &Attributes) -> Result<usize, String>;
+
+    /// Statements decoding the element from `reader`, a
+    /// `&mut BitReader<R>`, in a function returning `DecodeError`.
+    ///
+    /// By default, the bits are skipped.
+    fn decode(&self, attributes: &Attributes, bits: usize) -> TokenStream {
+        let _ = attributes;
+        quote! {
+            reader.read_bits(#bits)?;
+        }
+    }
+
+    /// Statements encoding the element to `writer`, a `&mut impl BitWrite`,
+    /// in a method of the item returning `DecodeError`.
+    ///
+    /// By default, the bits are written as zero.
+    fn encode(&self, attributes: &Attributes, bits: usize) -> TokenStream {
+        let _ = attributes;
+        quote! {
+            writer.write_bits(0, #bits)?;
+        }
+    }
+}
+
+/// The plugins registered with a build, by tag.
+#[derive(Clone, Default)]
+pub struct ElementPlugins {
+    plugins: Vec<Arc<dyn ElementPlugin>>,
+}
+
+impl ElementPlugins {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `plugin`, replacing any plugin of the same tag.
+    pub fn register(&mut self, plugin: impl ElementPlugin + 'static) {
+        self.plugins.retain(|other| other.tag() != plugin.tag());
+        self.plugins.push(Arc::new(plugin));
+    }
+
+    /// Returns the plugin handling `tag`.
+    pub fn get(&self, tag: &str) -> Option<&dyn ElementPlugin> {
+        self.plugins.iter().find(|plugin| plugin.tag() == tag).map(|plugin| plugin.as_ref())
+    }
+
+    /// Tags of the registered plugins.
+    pub fn tags(&self) -> Vec<&str> {
+        self.plugins.iter().map(|plugin| plugin.tag()).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+}
+
+impl fmt::Debug for ElementPlugins {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.tags()).finish()
+    }
+}
+
+/// Resolves the size and code of every custom element with its plugin.
+///
+/// Elements without a plugin are left unresolved, for
+/// [`lint`](super::lint::lint) to report.  Returns an error naming the
+/// first element whose plugin rejects its attributes.
+pub fn apply_plugins(ir: &mut IR, plugins: &ElementPlugins) -> Result<(), String> {
+    for item in &mut ir.category.items {
+        resolve_layout(&mut item.layout, plugins)
+            .map_err(|e| format!("item {:03}: {}", item.id, e))?;
+    }
+    Ok(())
+}
+
+fn resolve_layout(layout: &mut IRLayout, plugins: &ElementPlugins) -> Result<(), String> {
+    match layout {
+        IRLayout::Fixed { elements, .. }
+        | IRLayout::Explicit { elements, .. }
+        | IRLayout::Repetitive { elements, .. } => resolve_elements(elements, plugins),
+        IRLayout::Extended { part_groups, .. } => part_groups
+            .iter_mut()
+            .try_for_each(|group| resolve_elements(&mut group.elements, plugins)),
+        IRLayout::Compound { sub_items } => sub_items
+            .iter_mut()
+            .try_for_each(|sub_item| resolve_layout(&mut sub_item.layout, plugins)),
+    }
+}
+
+fn resolve_elements(elements: &mut [IRElement], plugins: &ElementPlugins) -> Result<(), String> {
+    for element in elements {
+        let IRElement::Custom { tag, attributes, code } = element else {
+            continue;
+        };
+        let Some(plugin) = plugins.get(tag) else {
+            continue;
+        };
+        let bits = plugin.bits(attributes).map_err(|e| format!("<{}>: {}", tag, e))?;
+        *code = Some(IRCustomCode {
+            bits,
+            decode: plugin.decode(attributes, bits).to_string(),
+            encode: plugin.encode(attributes, bits).to_string(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parser::parse_category_with_elements;
+    use crate::transform::lint::lint;
+    use crate::transform::transformer::to_unvalidated_ir;
+
+    /// Padding up to a multiple of `align` bits, written as
+    /// `<padding align="N" after="M"/>` after `M` bits of the item.
+    struct Padding;
+
+    impl ElementPlugin for Padding {
+        fn tag(&self) -> &str {
+            "padding"
+        }
+
+        fn bits(&self, attributes: &Attributes) -> Result<usize, String> {
+            let number = |name: &str| -> Result<usize, String> {
+                let value = attributes.get(name).ok_or_else(|| format!("missing `{}`", name))?;
+                value.parse().map_err(|_| format!("invalid `{}`: {}", name, value))
+            };
+            let (align, after) = (number("align")?, number("after")?);
+            Ok((align - after % align) % align)
+        }
+    }
+
+    fn ir(xml: &str, plugins: &ElementPlugins) -> Result<IR, String> {
+        let category = parse_category_with_elements(xml, &plugins.tags()).unwrap();
+        let mut ir = to_unvalidated_ir(category).unwrap();
+        apply_plugins(&mut ir, plugins)?;
+        Ok(ir)
+    }
+
+    fn padded(padding: &str) -> String {
+        format!(r#"<category id="1">
+            <item id="10" frn="1">
+                <fixed bytes="2"><field name="a" bits="12"/>{}</fixed>
+            </item>
+        </category>"#, padding)
+    }
+
+    #[test]
+    fn test_plugins_resolve_custom_elements() {
+        let mut plugins = ElementPlugins::new();
+        plugins.register(Padding);
+
+        let ir = ir(&padded(r#"<padding align="8" after="12"/>"#), &plugins).unwrap();
+
+        let IRLayout::Fixed { elements, .. } = &ir.category.items[0].layout else { panic!() };
+        let IRElement::Custom { tag, attributes, code: Some(code) } = &elements[1] else { panic!("{:?}", elements[1]) };
+        assert_eq!(tag, "padding");
+        assert_eq!(attributes["align"], "8");
+        assert_eq!(code.bits, 4);
+        assert_eq!(code.decode, "reader . read_bits (4usize) ? ;");
+        assert!(lint(&ir).is_empty());
+    }
+
+    #[test]
+    fn test_plugins_report_rejected_attributes() {
+        let mut plugins = ElementPlugins::new();
+        plugins.register(Padding);
+
+        let err = ir(&padded(r#"<padding align="8"/>"#), &plugins).unwrap_err();
+
+        assert_eq!(err, "item 010: <padding>: missing `after`");
+    }
+
+    #[test]
+    fn test_lint_reports_custom_elements_without_plugin() {
+        let ir = ir(&padded(r#"<custom tag="padding" align="8" after="12"/>"#), &ElementPlugins::new()).unwrap();
+
+        let lints: Vec<String> = lint(&ir).iter().map(ToString::to_string).collect();
+        assert!(
+            lints.contains(&"error[E009]: item 010: element <padding> has no plugin (unknown-element)".to_string()),
+            "{:?}",
+            lints
+        );
+    }
+}
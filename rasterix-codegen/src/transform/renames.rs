@@ -0,0 +1,282 @@
+use proc_macro2::Ident;
+
+use crate::parse::rename_model::{ItemRenames, RenameConfig};
+use super::lower_ir::*;
+
+/// Applies the symbol overrides of `config` for the lowered category.
+///
+/// Categories of the configuration other than this one are ignored, so a
+/// single file can cover every definition of a build.  Returns an error
+/// naming the first item, type, field or variant that the category doesn't
+/// have, or the first new name that is not a valid identifier.
+pub fn apply_renames(lowered: &mut LoweredIR, config: &RenameConfig) -> Result<(), String> {
+    let module = lowered.module_name.to_string();
+    let Some(items) = config.categories.get(&module) else {
+        return Ok(());
+    };
+
+    for (field_name, renames) in items {
+        let Some(entry) = lowered.record.entries.iter_mut().find(|entry| entry.field_name == field_name) else {
+            return Err(format!("{}: no item `{}`", module, field_name));
+        };
+        let Some(item) = lowered.items.iter_mut().find(|item| item.name == entry.type_name) else {
+            return Err(format!("{}: no item `{}`", module, field_name));
+        };
+
+        rename_item(item, renames).map_err(|e| format!("{}.{}: {}", module, field_name, e))?;
+        entry.type_name = item.name.clone();
+    }
+    Ok(())
+}
+
+fn rename_item(item: &mut LoweredItem, renames: &ItemRenames) -> Result<(), String> {
+    // Variants are keyed by the enum's original name, so rename them first.
+    for (enum_name, variants) in &renames.variants {
+        let mut enums: Vec<_> = item.enums.iter_mut().filter(|e| e.name == enum_name).collect();
+        if let LoweredItemKind::Compound { sub_items } = &mut item.kind {
+            enums.extend(sub_items.iter_mut().flat_map(|sub| &mut sub.enums).filter(|e| e.name == enum_name));
+        }
+        if enums.is_empty() {
+            return Err(format!("no enum `{}`", enum_name));
+        }
+        for (old, new) in variants {
+            let new = parse_ident(new)?;
+            for lowered_enum in &mut enums {
+                let Some(variant) = lowered_enum.variants.iter_mut().find(|v| v.name == old) else {
+                    return Err(format!("enum `{}` has no variant `{}`", enum_name, old));
+                };
+                variant.name = new.clone();
+                if let UnknownValues::MapTo(target) = &mut lowered_enum.unknown
+                    && target == old
+                {
+                    *target = new.clone();
+                }
+            }
+        }
+    }
+
+    for (old, new) in &renames.types {
+        rename_all(type_idents(item), "type", old, new)?;
+    }
+    for (old, new) in &renames.fields {
+        rename_all(field_idents(item), "field", old, new)?;
+    }
+    Ok(())
+}
+
+/// Renames every occurrence of `old` among `idents`, failing if there is
+/// none.
+fn rename_all(idents: Vec<&mut Ident>, what: &str, old: &str, new: &str) -> Result<(), String> {
+    let new = parse_ident(new)?;
+    let mut found = false;
+    for ident in idents.into_iter().filter(|ident| **ident == old) {
+        *ident = new.clone();
+        found = true;
+    }
+    if !found {
+        return Err(format!("no {} `{}`", what, old));
+    }
+    Ok(())
+}
+
+fn parse_ident(name: &str) -> Result<Ident, String> {
+    syn::parse_str::<Ident>(name).map_err(|_| format!("`{}` is not a valid identifier", name))
+}
+
+/// Every type name defined or referenced by the item.
+fn type_idents(item: &mut LoweredItem) -> Vec<&mut Ident> {
+    let mut idents = vec![&mut item.name];
+    idents.extend(item.enums.iter_mut().map(|e| &mut e.name));
+    idents.extend(item.derived.iter_mut().map(|derived| &mut derived.struct_name));
+    match &mut item.kind {
+        LoweredItemKind::Simple { fields, decode_ops, .. } => {
+            fields_types(fields, decode_ops, &mut idents);
+        }
+        LoweredItemKind::Extended { parts } => parts_types(parts, &mut idents),
+        LoweredItemKind::Repetitive { element_type_name, fields, decode_ops, .. } => {
+            idents.push(element_type_name);
+            fields_types(fields, decode_ops, &mut idents);
+        }
+        LoweredItemKind::Compound { sub_items } => {
+            for sub in sub_items {
+                idents.push(&mut sub.struct_name);
+                idents.extend(sub.enums.iter_mut().map(|e| &mut e.name));
+                match &mut sub.kind {
+                    LoweredSubItemKind::Simple { fields, decode_ops, .. } => {
+                        fields_types(fields, decode_ops, &mut idents);
+                    }
+                    LoweredSubItemKind::Extended { parts } => parts_types(parts, &mut idents),
+                    LoweredSubItemKind::Repetitive { element_type_name, fields, decode_ops, .. } => {
+                        idents.push(element_type_name);
+                        fields_types(fields, decode_ops, &mut idents);
+                    }
+                }
+            }
+        }
+    }
+    idents
+}
+
+fn parts_types<'a>(parts: &'a mut [LoweredPart], idents: &mut Vec<&'a mut Ident>) {
+    for part in parts {
+        idents.push(&mut part.struct_name);
+        fields_types(&mut part.fields, &mut part.decode_ops, idents);
+    }
+}
+
+fn fields_types<'a>(
+    fields: &'a mut [FieldDescriptor],
+    decode_ops: &'a mut [DecodeOp],
+    idents: &mut Vec<&'a mut Ident>,
+) {
+    for field in fields {
+        if let FieldType::Enum(name) | FieldType::OptionalEnum(name) = &mut field.type_tokens {
+            idents.push(name);
+        }
+    }
+    for op in decode_ops {
+        if let DecodeOp::ReadEnum { enum_type, .. } | DecodeOp::ReadEpbEnum { enum_type, .. } = op {
+            idents.push(enum_type);
+        }
+    }
+}
+
+/// Every struct field name of the item, with the ops reading and writing it
+/// and the derived values computed from it.
+fn field_idents(item: &mut LoweredItem) -> Vec<&mut Ident> {
+    let mut idents: Vec<&mut Ident> =
+        item.derived.iter_mut().flat_map(|derived| derived.inputs.iter_mut().map(|input| &mut input.field)).collect();
+    match &mut item.kind {
+        LoweredItemKind::Simple { fields, decode_ops, encode_ops, .. }
+        | LoweredItemKind::Repetitive { fields, decode_ops, encode_ops, .. } => {
+            fields_names(fields, decode_ops, encode_ops, &mut idents);
+        }
+        LoweredItemKind::Extended { parts } => parts_names(parts, &mut idents),
+        LoweredItemKind::Compound { sub_items } => {
+            for sub in sub_items {
+                idents.push(&mut sub.field_name);
+                match &mut sub.kind {
+                    LoweredSubItemKind::Simple { fields, decode_ops, encode_ops, .. }
+                    | LoweredSubItemKind::Repetitive { fields, decode_ops, encode_ops, .. } => {
+                        fields_names(fields, decode_ops, encode_ops, &mut idents);
+                    }
+                    LoweredSubItemKind::Extended { parts } => parts_names(parts, &mut idents),
+                }
+            }
+        }
+    }
+    idents
+}
+
+fn parts_names<'a>(parts: &'a mut [LoweredPart], idents: &mut Vec<&'a mut Ident>) {
+    for part in parts {
+        idents.push(&mut part.field_name);
+        fields_names(&mut part.fields, &mut part.decode_ops, &mut part.encode_ops, idents);
+    }
+}
+
+fn fields_names<'a>(
+    fields: &'a mut [FieldDescriptor],
+    decode_ops: &'a mut [DecodeOp],
+    encode_ops: &'a mut [EncodeOp],
+    idents: &mut Vec<&'a mut Ident>,
+) {
+    idents.extend(fields.iter_mut().map(|field| &mut field.name));
+    for op in decode_ops {
+        match op {
+            DecodeOp::ReadField { name, .. }
+            | DecodeOp::ReadEnum { name, .. }
+            | DecodeOp::ReadEpbField { name, .. }
+            | DecodeOp::ReadEpbEnum { name, .. }
+            | DecodeOp::ReadString { name, .. }
+            | DecodeOp::ReadEpbString { name, .. }
+            | DecodeOp::ReadBytes { name, .. }
+            | DecodeOp::ReadEpbBytes { name, .. } => idents.push(name),
+            DecodeOp::SkipSpare { .. } | DecodeOp::ReadLengthByte { .. } | DecodeOp::Custom { .. } => {}
+        }
+    }
+    for op in encode_ops {
+        match op {
+            EncodeOp::WriteField { name, .. }
+            | EncodeOp::WriteEnum { name, .. }
+            | EncodeOp::WriteEpbField { name, .. }
+            | EncodeOp::WriteEpbEnum { name, .. }
+            | EncodeOp::WriteString { name, .. }
+            | EncodeOp::WriteEpbString { name, .. }
+            | EncodeOp::WriteBytes { name, .. }
+            | EncodeOp::WriteEpbBytes { name, .. } => idents.push(name),
+            EncodeOp::WriteSpare { .. } | EncodeOp::WriteLengthByte { .. } | EncodeOp::Custom { .. } => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parser::{parse_category, parse_renames};
+    use crate::transform::{lower, transformer::to_ir};
+
+    fn lowered() -> LoweredIR {
+        lower(&to_ir(parse_category(r#"<category id="48">
+            <item id="10" frn="1">
+                <fixed bytes="2">
+                    <field name="sac" bits="8"/>
+                    <field name="sic" bits="8"/>
+                </fixed>
+            </item>
+            <item id="20" frn="2">
+                <fixed bytes="1">
+                    <enum name="target_type" bits="8">
+                        <value name="PSR" value="1"/>
+                        <value name="SSR" value="2"/>
+                    </enum>
+                </fixed>
+            </item>
+        </category>"#).unwrap()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn renames_types_fields_and_variants() {
+        let mut lowered = lowered();
+        let config = parse_renames(r#"
+            [cat048.item010.types]
+            Item010 = "DataSource"
+            [cat048.item010.fields]
+            sac = "area"
+
+            [cat048.item020.types]
+            TargetType = "DetectionKind"
+            [cat048.item020.variants.TargetType]
+            Psr = "Primary"
+
+            [cat062.item010.types]
+            Item010 = "Ignored"
+        "#).unwrap();
+        apply_renames(&mut lowered, &config).unwrap();
+
+        assert_eq!(lowered.items[0].name, "DataSource");
+        assert_eq!(lowered.record.entries[0].type_name, "DataSource");
+        let LoweredItemKind::Simple { fields, encode_ops, .. } = &lowered.items[0].kind else { unreachable!() };
+        assert_eq!(fields[0].name, "area");
+        assert!(matches!(&encode_ops[0], EncodeOp::WriteField { name, .. } if name == "area"));
+
+        let target_type = &lowered.items[1].enums[0];
+        assert_eq!(target_type.name, "DetectionKind");
+        assert_eq!(target_type.variants[0].name, "Primary");
+        let LoweredItemKind::Simple { fields, .. } = &lowered.items[1].kind else { unreachable!() };
+        assert!(matches!(&fields[0].type_tokens, FieldType::Enum(name) if name == "DetectionKind"));
+    }
+
+    #[test]
+    fn unknown_symbols_are_rejected() {
+        for (toml, error) in [
+            ("[cat048.item030.fields]\na = \"b\"", "cat048: no item `item030`"),
+            ("[cat048.item010.fields]\nsid = \"b\"", "cat048.item010: no field `sid`"),
+            ("[cat048.item020.variants.Kind]\nPsr = \"P\"", "cat048.item020: no enum `Kind`"),
+            ("[cat048.item010.fields]\nsac = \"not valid\"", "cat048.item010: `not valid` is not a valid identifier"),
+        ] {
+            let config = parse_renames(toml).unwrap();
+            assert_eq!(apply_renames(&mut lowered(), &config).unwrap_err(), error);
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use super::lower_ir::{LoweredIR, LoweredItemKind, LoweredSubItemKind, RepetitiveStorage};
+
+/// Stores the elements of every Repetitive item, including those of
+/// compound sub-items, in a `heapless::Vec`.
+///
+/// Repetitive items have a fixed repetition count, which becomes the
+/// capacity of the vector, so decoding them never allocates.  The
+/// generated code then requires the `heapless` feature of `rasterix-core`.
+pub fn use_heapless(lowered: &mut LoweredIR) {
+    for item in &mut lowered.items {
+        match &mut item.kind {
+            LoweredItemKind::Repetitive { storage, .. } => *storage = RepetitiveStorage::Heapless,
+            LoweredItemKind::Compound { sub_items } => {
+                for sub in sub_items {
+                    if let LoweredSubItemKind::Repetitive { storage, .. } = &mut sub.kind {
+                        *storage = RepetitiveStorage::Heapless;
+                    }
+                }
+            }
+            LoweredItemKind::Simple { .. } | LoweredItemKind::Extended { .. } => {}
+        }
+    }
+}
+
+/// Returns whether any Repetitive item of `lowered` uses `heapless::Vec`.
+pub fn uses_heapless(lowered: &LoweredIR) -> bool {
+    lowered.items.iter().any(|item| match &item.kind {
+        LoweredItemKind::Repetitive { storage, .. } => *storage == RepetitiveStorage::Heapless,
+        LoweredItemKind::Compound { sub_items } => sub_items.iter().any(|sub| {
+            matches!(sub.kind, LoweredSubItemKind::Repetitive { storage: RepetitiveStorage::Heapless, .. })
+        }),
+        LoweredItemKind::Simple { .. } | LoweredItemKind::Extended { .. } => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parser::parse_category;
+    use crate::transform::{lower, transformer::to_ir};
+
+    #[test]
+    fn test_use_heapless_marks_all_repetitive_items() {
+        let mut lowered = lower(&to_ir(parse_category(r#"<category id="48">
+            <item id="10" frn="1">
+                <repetitive bytes="1" counter="3"><field name="a" bits="8"/></repetitive>
+            </item>
+            <item id="20" frn="2">
+                <compound>
+                    <repetitive bytes="1" counter="2"><field name="b" bits="8"/></repetitive>
+                </compound>
+            </item>
+        </category>"#).unwrap()).unwrap()).unwrap();
+        assert!(!uses_heapless(&lowered));
+
+        use_heapless(&mut lowered);
+
+        assert!(uses_heapless(&lowered));
+        assert!(matches!(
+            lowered.items[0].kind,
+            LoweredItemKind::Repetitive { storage: RepetitiveStorage::Heapless, .. }
+        ));
+        let LoweredItemKind::Compound { sub_items } = &lowered.items[1].kind else { panic!() };
+        assert!(matches!(
+            sub_items[0].kind,
+            LoweredSubItemKind::Repetitive { storage: RepetitiveStorage::Heapless, .. }
+        ));
+    }
+}
@@ -7,50 +7,123 @@ use crate::transform::ir::*;
 /// the raw deserialized XML into a validated, normalized IR that is ready
 /// for code generation.
 /// 
-/// # Panics
+/// # Errors
 /// 
-/// Panics if validation fails (e.g., bit counts don't match byte declarations).
-pub fn to_ir(cat: Category) -> IR {
-    let ir_category = to_ir_category(cat);
+/// Fails if validation fails (e.g., bit counts don't match byte declarations,
+/// an item id has more than three digits, two items share an FRN, two
+/// names give the same identifier, or an item has no fields without being
+/// reserved).
+pub fn to_ir(cat: Category) -> Result<IR, String> {
+    let ir = to_unvalidated_ir(cat)?;
 
     // Validate all items
-    for item in &ir_category.items {
-        item.layout.validate();
-    }
-    
-    IR {
-        category: ir_category,
+    ir.category.validate_frns()?;
+    ir.category.validate_identifiers()?;
+    for item in &ir.category.items {
+        item.validate_reserved()?;
+        item.layout.validate()?;
     }
+
+    Ok(ir)
+}
+
+/// Transforms the XML model into the IR without validating it.
+///
+/// Used by [`lint`](crate::transform::lint::lint), which reports every
+/// problem of a definition instead of stopping at the first.
+///
+/// # Errors
+///
+/// Fails if the definition cannot be represented at all (e.g. an unknown
+/// field type, an enum value that is not a `u8`, or a name that doesn't
+/// give an identifier).
+pub fn to_unvalidated_ir(cat: Category) -> Result<IR, String> {
+    let category = to_ir_category(cat)?;
+    category.check_names()?;
+    Ok(IR { category })
 }
 
 /// Transforms a category from XML model to IR.
-fn to_ir_category(cat: Category) -> IRCategory {
-    IRCategory {
+fn to_ir_category(cat: Category) -> Result<IRCategory, String> {
+    Ok(IRCategory {
         id: cat.id,
-        items: cat.items.into_iter().map(to_ir_item).collect(),
-    }
+        items: cat.items.into_iter().map(to_ir_item).collect::<Result<_, _>>()?,
+    })
 }
 
 /// Transforms a single item from XML model to IR.
-fn to_ir_item(item: Item) -> IRItem {
-    IRItem {
+fn to_ir_item(item: Item) -> Result<IRItem, String> {
+    if item.id > 999 {
+        return Err(format!("Item id {} has more than three digits", item.id));
+    }
+    Ok(IRItem {
         id: item.id,
         frn: item.frn,
-        layout: to_ir_item_structure(item.data),
+        title: item.name,
+        short: item.short,
+        reserved: item.reserved,
+        mandatory: item.mandatory,
+        layout: to_ir_item_structure(item.data)?,
+        examples: item.examples
+            .into_iter()
+            .map(|example| to_ir_example(item.id, example))
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+/// Transforms a worked example of an item from XML model to IR.
+///
+/// Fails if the bytes of the example are not hex bytes.
+fn to_ir_example(item_id: u16, example: Example) -> Result<IRExample, String> {
+    let bytes = example.bytes
+        .split_whitespace()
+        .map(|token| {
+            let digits = token.strip_prefix("0x").or_else(|| token.strip_prefix("0X")).unwrap_or(token);
+            u8::from_str_radix(digits, 16)
+                .map_err(|_| format!("Example of item {:03} has an invalid byte `{}`", item_id, token))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(IRExample {
+        bytes,
+        description: example.description,
+        values: example.expects.into_iter().map(|expect| (expect.field, expect.value)).collect(),
+    })
+}
+
+/// Parses the counter of a repetitive item, for now an exact count.
+fn to_ir_count(counter: &str) -> Result<usize, String> {
+    counter.parse::<usize>().map_err(|_| format!("Invalid repetition counter `{}`: it must be a number", counter))
+}
+
+/// Transforms a repetitive item, whose count is exact: there is no REP byte
+/// for `min_rep`/`max_rep` to bound.
+fn to_ir_repetitive(rep: RepetitiveItem) -> Result<IRLayout, String> {
+    for (attribute, value) in [("min_rep", &rep.min_rep), ("max_rep", &rep.max_rep)] {
+        if value.is_some() {
+            return Err(format!(
+                "Unsupported attribute `{}` on repetitive item: counts are exact, with no REP byte to bound",
+                attribute
+            ));
+        }
     }
+    Ok(IRLayout::Repetitive {
+        bytes: rep.bytes,
+        count: to_ir_count(&rep.counter)?,
+        elements: to_ir_elements(rep.elements)?,
+    })
 }
 
 /// Transforms an item structure from XML model to IR layout.
-fn to_ir_item_structure(structure: ItemStructure) -> IRLayout {
-    match structure {
+fn to_ir_item_structure(structure: ItemStructure) -> Result<IRLayout, String> {
+    Ok(match structure {
         ItemStructure::Fixed(simple) => IRLayout::Fixed {
             bytes: simple.bytes,
-            elements: simple.elements.into_iter().map(to_ir_element).collect(),
+            elements: to_ir_elements(simple.elements)?,
         },
         
         ItemStructure::Explicit(simple) => IRLayout::Explicit {
             bytes: simple.bytes,
-            elements: simple.elements.into_iter().map(to_ir_element).collect(),
+            elements: to_ir_elements(simple.elements)?,
         },
         
         ItemStructure::Extended(ext) => {
@@ -58,56 +131,46 @@ fn to_ir_item_structure(structure: ItemStructure) -> IRLayout {
             let part_groups = ext.part_groups
                 .into_iter()
                 .map(|group| {
-                    IRPartGroup {
+                    Ok(IRPartGroup {
                         index: group.index,
-                        elements: group.elements.into_iter().map(to_ir_element).collect()
-                    }
+                        elements: to_ir_elements(group.elements)?
+                    })
                 })
-                .collect();
+                .collect::<Result<_, String>>()?;
             let bytes = ext.bytes;
             IRLayout::Extended { bytes, part_groups }
         }
         
-        ItemStructure::Repetitive(rep) => {
-            // Parse counter - for now only exact counts supported
-            let count = rep.counter.parse::<usize>()
-                .expect("Counter must be a valid number");
-            
-            IRLayout::Repetitive {
-                bytes: rep.bytes,
-                count,
-                elements: rep.elements.into_iter().map(to_ir_element).collect(),
-            }
-        }
+        ItemStructure::Repetitive(rep) => to_ir_repetitive(rep)?,
         
         ItemStructure::Compound(comp) => {
             let sub_items = comp.items
                 .into_iter()
                 .enumerate()
                 .map(|(index, item)| {
-                    IRSubItem {
+                    Ok(IRSubItem {
                         index,
-                        layout: to_ir_compoundable_item(item),
-                    }
+                        layout: to_ir_compoundable_item(item)?,
+                    })
                 })
-                .collect();
+                .collect::<Result<_, String>>()?;
             
             IRLayout::Compound { sub_items }
         }
-    }
+    })
 }
 
 /// Transforms a compoundable item (nested within a compound) to IR layout.
-fn to_ir_compoundable_item(item: CompoundableItem) -> IRLayout {
-    match item {
+fn to_ir_compoundable_item(item: CompoundableItem) -> Result<IRLayout, String> {
+    Ok(match item {
         CompoundableItem::Fixed(simple) => IRLayout::Fixed {
             bytes: simple.bytes,
-            elements: simple.elements.into_iter().map(to_ir_element).collect(),
+            elements: to_ir_elements(simple.elements)?,
         },
         
         CompoundableItem::Explicit(simple) => IRLayout::Explicit {
             bytes: simple.bytes,
-            elements: simple.elements.into_iter().map(to_ir_element).collect(),
+            elements: to_ir_elements(simple.elements)?,
         },
         
         CompoundableItem::Extended(ext) => {
@@ -115,57 +178,73 @@ fn to_ir_compoundable_item(item: CompoundableItem) -> IRLayout {
                 .into_iter()
                 .map(|group| {
                     
-                    IRPartGroup {
+                    Ok(IRPartGroup {
                         index: group.index,
-                        elements: group.elements.into_iter().map(to_ir_element).collect(),
-                    }
+                        elements: to_ir_elements(group.elements)?,
+                    })
                 })
-                .collect();
+                .collect::<Result<_, String>>()?;
             let bytes = ext.bytes;
             IRLayout::Extended { bytes, part_groups }
         }
         
-        CompoundableItem::Repetitive(rep) => {
-            let count = rep.counter.parse::<usize>()
-                .expect("Counter must be a valid number");
-            
-            IRLayout::Repetitive {
-                bytes: rep.bytes,
-                count,
-                elements: rep.elements.into_iter().map(to_ir_element).collect(),
-            }
-        }
-    }
+        CompoundableItem::Repetitive(rep) => to_ir_repetitive(rep)?,
+    })
+}
+/// Returns whether the field is a string, and the typed interpretation of
+/// a numeric one.
+fn check_field_type(field: &Field) -> Result<(bool, Option<IRValueType>), String> {
+    Ok(match field.field_type.as_str() {
+        "string" => (true, None),
+        "numeric" | "f32" | "f64" | "bytes" => (false, None),
+        "flight_level" => (false, Some(IRValueType::FlightLevel)),
+        "altitude" => (false, Some(IRValueType::Altitude)),
+        "ground_speed" => (false, Some(IRValueType::GroundSpeed)),
+        "heading" => (false, Some(IRValueType::Heading)),
+        "vx" => (false, Some(IRValueType::Vx)),
+        "vy" => (false, Some(IRValueType::Vy)),
+        "icao" => (false, Some(IRValueType::Icao)),
+        _ => return Err(format!("Invalid field type: {}", field.field_type)),
+    })
 }
-fn check_field_string_type(field: &Field) -> bool {
-    match field.field_type.as_str() {
-        "string" => true,
-        "numeric" => false,
-        _ => panic!("Invalid field type: {}", field.field_type),
+/// Transforms a list of elements from XML model to IR, replacing each
+/// alignment with the spare bits up to its boundary.
+///
+/// Fails if an alignment is 0 bits, or follows a custom element, whose
+/// size is only known once its plugin is applied.
+fn to_ir_elements(elements: Vec<Element>) -> Result<Vec<IRElement>, String> {
+    let mut ir_elements: Vec<IRElement> = Vec::new();
+    for element in elements {
+        let Element::Align(align) = element else {
+            ir_elements.push(to_ir_element(element)?);
+            continue;
+        };
+        if align.bits == 0 {
+            return Err("Invalid alignment: 0 bits".to_string());
+        }
+        if let Some(IRElement::Custom { tag, .. }) = ir_elements.iter().find(|e| matches!(e, IRElement::Custom { .. })) {
+            return Err(format!("Invalid alignment: it follows custom element <{}>, whose size is not known", tag));
+        }
+
+        let offset: usize = ir_elements.iter().map(IRElement::bit_size).sum();
+        let bits = (align.bits - offset % align.bits) % align.bits;
+        if bits > 0 {
+            ir_elements.push(IRElement::Spare { bits });
+        }
     }
+    Ok(ir_elements)
 }
+
 /// Transforms a single element from XML model to IR.
-fn to_ir_element(element: Element) -> IRElement {
-    match element {
-        Element::Field(field) => {
-            let is_string = check_field_string_type(&field);
-            IRElement::Field {
-                name: field.name,
-                bits: field.bits,
-                is_string: is_string,
-            }
-        },
+///
+/// Alignments are resolved by [`to_ir_elements`].
+fn to_ir_element(element: Element) -> Result<IRElement, String> {
+    Ok(match element {
+        Element::Field(field) => to_ir_field(field)?,
         Element::EPB(epb) => {
             let content = match epb.content {
-                EPBContent::Field(field) => {
-                    let is_string = check_field_string_type(&field);
-                    IRElement::Field {
-                        name: field.name,
-                        bits: field.bits,
-                        is_string: is_string,
-                    }
-                },
-                EPBContent::Enum(enum_def) => to_ir_enum(enum_def),
+                EPBContent::Field(field) => to_ir_field(field)?,
+                EPBContent::Enum(enum_def) => to_ir_enum(enum_def)?,
             };
             
             IRElement::EPB {
@@ -173,29 +252,188 @@ fn to_ir_element(element: Element) -> IRElement {
             }
         }
         
-        Element::Enum(enum_def) => to_ir_enum(enum_def),
+        Element::Enum(enum_def) => to_ir_enum(enum_def)?,
         
         Element::Spare(spare) => IRElement::Spare {
             bits: spare.bits,
         },
+
+        Element::Derived(derived) => IRElement::Derived {
+            name: derived.name,
+            rust_type: derived.rust_type,
+            expr: derived.expr,
+            description: derived.description,
+            geo: derived.geo,
+        },
+
+        Element::Align(_) => unreachable!("alignments are resolved with their preceding elements"),
+
+        Element::Custom(custom) => IRElement::Custom {
+            tag: custom.tag,
+            attributes: custom.attributes
+                .into_iter()
+                .map(|(name, value)| (name.trim_start_matches('@').to_string(), value))
+                .collect(),
+            code: None,
+        },
+    })
+}
+
+/// Transforms a field definition from XML model to IR.
+///
+/// Fails if the field has constraints it can't satisfy, or constraints
+/// on a field that is not a number.
+fn to_ir_field(field: Field) -> Result<IRElement, String> {
+    let (is_string, value_type) = check_field_type(&field)?;
+    let is_float = is_float(&field)?;
+    let is_bytes = is_bytes(&field)?;
+    let non_numeric = match (is_string, is_float, is_bytes) {
+        (true, _, _) => Some("a string"),
+        (_, true, _) => Some("a float"),
+        (_, _, true) => Some("a byte array"),
+        _ => None,
+    };
+    let constraints = to_ir_constraints(&field, non_numeric)?;
+    let little_endian = is_little_endian(&field, !is_string && !is_bytes)?;
+    Ok(IRElement::Field {
+        name: field.name,
+        bits: field.bits,
+        is_string,
+        revision: IRRevision {
+            deprecated_since: field.deprecated_since,
+            renamed_from: field.renamed_from,
+        },
+        description: field.description,
+        constraints,
+        value_type,
+        little_endian,
+        is_float,
+        is_bytes,
+    })
+}
+
+/// Returns whether the field is an IEEE 754 float.
+///
+/// Fails if a float field is not as wide as its type.
+fn is_float(field: &Field) -> Result<bool, String> {
+    let bits = match field.field_type.as_str() {
+        "f32" => 32,
+        "f64" => 64,
+        _ => return Ok(false),
+    };
+    if field.bits != bits {
+        return Err(format!(
+            "Invalid float field {}: type {} is {} bits, not {}",
+            field.name, field.field_type, bits, field.bits
+        ));
+    }
+    Ok(true)
+}
+
+/// Returns whether the field is an opaque array of bytes.
+///
+/// Fails if a byte field is not a whole number of bytes.
+fn is_bytes(field: &Field) -> Result<bool, String> {
+    if field.field_type != "bytes" {
+        return Ok(false);
+    }
+    if field.bits == 0 || !field.bits.is_multiple_of(8) {
+        return Err(format!("Invalid byte field {}: {} bits are not whole bytes", field.name, field.bits));
     }
+    Ok(true)
+}
+
+/// Returns whether the field is sent least significant byte first.
+///
+/// Fails if the byte order is unknown, or little-endian for a field that
+/// is not a number of whole bytes.
+fn is_little_endian(field: &Field, is_number: bool) -> Result<bool, String> {
+    match field.byte_order.as_deref() {
+        None | Some("big") => Ok(false),
+        Some("little") => {
+            if !(is_number && field.bits.is_multiple_of(8) && field.bits <= 64) {
+                return Err(format!(
+                    "Invalid byte order of field {}: little-endian fields are numbers of whole bytes",
+                    field.name
+                ));
+            }
+            Ok(true)
+        }
+        Some(other) => Err(format!("Invalid byte order of field {}: {}", field.name, other)),
+    }
+}
+
+/// Fails if the field has constraints it can't satisfy, or constraints
+/// while being `non_numeric`, e.g. "a string".
+fn to_ir_constraints(field: &Field, non_numeric: Option<&str>) -> Result<IRConstraints, String> {
+    let constraints = IRConstraints { min: field.min, max: field.max, multiple_of: field.multiple_of };
+    if constraints == IRConstraints::default() {
+        return Ok(constraints);
+    }
+    if let Some(kind) = non_numeric {
+        return Err(format!("Invalid constraints on field {}: it is {}", field.name, kind));
+    }
+    if constraints.multiple_of == Some(0) {
+        return Err(format!("Invalid constraints on field {}: multiple_of is 0", field.name));
+    }
+    if let (Some(min), Some(max)) = (constraints.min, constraints.max)
+        && min > max
+    {
+        return Err(format!("Invalid constraints on field {}: min {} is above max {}", field.name, min, max));
+    }
+    Ok(constraints)
 }
 
 /// Transforms an enum definition from XML model to IR.
-fn to_ir_enum(enum_def: Enum) -> IRElement {
-    let values = enum_def.values
+fn to_ir_enum(enum_def: Enum) -> Result<IRElement, String> {
+    let aliases = enum_def.values
+        .iter()
+        .filter_map(|v| Some((v.name.clone(), v.alias.clone()?)))
+        .collect();
+    let values: Vec<_> = enum_def.values
         .into_iter()
         .map(|v| {
             let value = v.value.parse::<u8>()
-                .expect("Enum value must be a valid u8");
-            (v.name, value)
+                .map_err(|_| format!("Invalid value of {} in enum {}: {} is not a u8", v.name, enum_def.name, v.value))?;
+            Ok((v.name, value))
         })
-        .collect();
+        .collect::<Result<_, String>>()?;
+    let unknown = to_ir_unknown(&enum_def.name, enum_def.unknown.as_deref(), &values)?;
     
-    IRElement::Enum {
+    Ok(IRElement::Enum {
         name: enum_def.name,
         bits: enum_def.bits,
         values,
+        aliases,
+        unknown,
+        revision: IRRevision {
+            deprecated_since: enum_def.deprecated_since,
+            renamed_from: enum_def.renamed_from,
+        },
+        description: enum_def.description,
+    })
+}
+
+/// Parses the `unknown` attribute of an enum.
+///
+/// Fails if it is not `preserve`, `error` or `map_to=NAME` with `NAME`
+/// one of the enum's values.
+fn to_ir_unknown(enum_name: &str, unknown: Option<&str>, values: &[(String, u8)]) -> Result<IRUnknownValues, String> {
+    match unknown {
+        None | Some("preserve") => Ok(IRUnknownValues::Preserve),
+        Some("error") => Ok(IRUnknownValues::Error),
+        Some(other) => match other.strip_prefix("map_to=") {
+            Some(target) => {
+                if !values.iter().any(|(name, _)| name == target) {
+                    return Err(format!(
+                        "Invalid unknown policy of enum {}: {} is not one of its values",
+                        enum_name, target
+                    ));
+                }
+                Ok(IRUnknownValues::MapTo(target.to_string()))
+            }
+            None => Err(format!("Invalid unknown policy of enum {}: {}", enum_name, other)),
+        },
     }
 }
 
@@ -204,7 +442,6 @@ mod tests {
     use super::*;
     
     #[test]
-    #[should_panic(expected = "Bit count mismatch")]
     fn test_validation_fails_on_mismatch() {
         // Create a simple item with mismatched bits
         let simple = SimpleItem {
@@ -213,16 +450,23 @@ mod tests {
                 Element::Field(Field {
                     name: "test".into(),
                     bits: 8, // Only 8 bits, but declared 2 bytes (16 bits)
-                    field_type: "numeric".into()
+                    field_type: "numeric".into(),
+                    deprecated_since: None,
+                    renamed_from: None,
+                    description: None,
+                    min: None,
+                    max: None,
+                    multiple_of: None,
+                    byte_order: None,
                 }),
             ],
         };
         
         let structure = ItemStructure::Fixed(simple);
-        let layout = to_ir_item_structure(structure);
+        let layout = to_ir_item_structure(structure).unwrap();
         
-        // This should panic
-        layout.validate();
+        let err = layout.validate().unwrap_err();
+        assert!(err.starts_with("Bit count mismatch"), "{}", err);
     }
     
     #[test]
@@ -233,20 +477,96 @@ mod tests {
                 Element::Field(Field {
                     name: "a".into(),
                     bits: 8,
-                    field_type: "numeric".into()
+                    field_type: "numeric".into(),
+                    deprecated_since: None,
+                    renamed_from: None,
+                    description: None,
+                    min: None,
+                    max: None,
+                    multiple_of: None,
+                    byte_order: None,
                 }),
                 Element::Field(Field {
                     name: "b".into(),
                     bits: 8,
-                    field_type: "string".into()
+                    field_type: "string".into(),
+                    deprecated_since: None,
+                    renamed_from: None,
+                    description: None,
+                    min: None,
+                    max: None,
+                    multiple_of: None,
+                    byte_order: None,
                 }),
             ],
         };
         
         let structure = ItemStructure::Fixed(simple);
-        let layout = to_ir_item_structure(structure);
+        let layout = to_ir_item_structure(structure).unwrap();
         
-        // Should not panic
-        layout.validate();
+        assert_eq!(layout.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_align_pads_to_boundary() {
+        let field = |bits| Element::Field(Field {
+            name: format!("f{}", bits),
+            bits,
+            field_type: "numeric".into(),
+            deprecated_since: None,
+            renamed_from: None,
+            description: None,
+            min: None,
+            max: None,
+            multiple_of: None,
+            byte_order: None,
+        });
+        let align = || Element::Align(Align { bits: 8 });
+
+        let elements = to_ir_elements(vec![field(3), align(), field(8), align(), field(6)]).unwrap();
+
+        // The second alignment is already on the boundary.
+        let bits: Vec<_> = elements.iter().map(|e| (e.is_visible(), e.bit_size())).collect();
+        assert_eq!(bits, [(true, 3), (false, 5), (true, 8), (true, 6)]);
+    }
+
+    #[test]
+    fn test_align_after_custom_element_fails() {
+        let crc = Element::Custom(Custom { tag: "crc".into(), attributes: Default::default() });
+
+        let err = to_ir_elements(vec![crc, Element::Align(Align { bits: 8 })]).unwrap_err();
+        assert!(err.starts_with("Invalid alignment: it follows custom element <crc>"), "{}", err);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_field_constraints_must_be_satisfiable() {
+        let err = to_ir_field(Field {
+            name: "gsp".into(),
+            bits: 8,
+            field_type: "numeric".into(),
+            deprecated_since: None,
+            renamed_from: None,
+            description: None,
+            min: Some(10),
+            max: Some(5),
+            multiple_of: None,
+            byte_order: None,
+        })
+        .unwrap_err();
+        assert_eq!(err, "Invalid constraints on field gsp: min 10 is above max 5");
+    }
+
+    #[test]
+    fn test_repetition_bounds_are_rejected() {
+        let rep = RepetitiveItem {
+            bytes: 1,
+            counter: "2".into(),
+            min_rep: None,
+            max_rep: Some("4".into()),
+            elements: Vec::new(),
+        };
+
+        let err = to_ir_item_structure(ItemStructure::Repetitive(rep)).unwrap_err();
+        assert_eq!(err, "Unsupported attribute `max_rep` on repetitive item: counts are exact, with no REP byte to bound");
+    }
+}
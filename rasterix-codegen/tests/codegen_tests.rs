@@ -3,22 +3,439 @@
 //! These tests verify that the code generator produces correct Rust code
 //! from the intermediate representation (IR).
 
-use rasterix_codegen::generate::generate;
-use rasterix_codegen::parse::parser::parse_category;
+use rasterix_codegen::generate::{
+    generate, generate_arrow, generate_docs, generate_ffi, generate_ksy, generate_lowered, generate_proto,
+    generate_wasm,
+};
+use rasterix_codegen::generate::backend::run_backend;
+use rasterix_codegen::generate::borrowed_gen::BorrowedBackend;
+use rasterix_codegen::parse::parser::{parse_category, parse_features, parse_renames};
+use rasterix_codegen::transform::{apply_features, apply_renames, lower};
 use rasterix_codegen::transform::transformer::to_ir;
 use test_utils::{
-    assert_code_contains, assert_code_not_contains, load_fixture,
+    assert_code_contains, assert_code_not_contains, assert_expected_output, compile_check,
+    compile_check_with_deps, compile_check_with_features, load_fixture,
 };
+use test_utils::synth::{synth_category, SynthConfig};
 
 /// Helper function to generate code from a fixture file.
 fn generate_from_fixture(category: &str, filename: &str) -> String {
     let xml = load_fixture(category, filename);
     let parsed = parse_category(&xml).expect("Failed to parse XML fixture");
-    let ir = to_ir(parsed);
-    let tokens = generate(&ir);
+    let ir = to_ir(parsed).unwrap();
+    let tokens = generate(&ir).unwrap();
     tokens.to_string()
 }
 
+// ============================================================================
+// Expected Output Tests
+// ============================================================================
+
+/// Fixtures whose complete generated code is checked against
+/// `testdata/expected/<name>.rs`.
+///
+/// After an intentional generator change, run the tests with
+/// `UPDATE_EXPECT=1` to rewrite the expected files, then review the diff.
+const EXPECTED_OUTPUT_FIXTURES: &[&str] = &[
+    "simple_fixed",
+    "multi_item_record",
+    "extended_multi_part",
+    "enum_basic",
+    "compound_simple",
+    "repetitive_basic",
+    "epb_field",
+    "explicit_item",
+    "spare_bits",
+];
+
+#[test]
+fn generated_code_matches_expected_outputs() {
+    for name in EXPECTED_OUTPUT_FIXTURES {
+        let code = generate_from_fixture("valid", &format!("{}.xml", name));
+        assert_expected_output(&code, name);
+    }
+}
+
+/// Fixtures whose generated code is type-checked with `cargo check`.
+const COMPILED_FIXTURES: &[&str] = &[
+    "simple_fixed",
+    "multi_item_record",
+    "extended_single_part",
+    "extended_multi_part",
+    "enum_basic",
+    "compound_simple",
+    "compound_complex",
+    "repetitive_basic",
+    "repetitive_with_epb",
+    "epb_field",
+    "epb_enum",
+    "explicit_item",
+    "spare_bits",
+    "mixed_all",
+    "same_name_different_items",
+    "same_name_different_parts",
+    "same_name_different_subitems",
+    "high_item_ids",
+    "named_items",
+    "field_revisions",
+    "enum_policies",
+    "field_descriptions",
+    "aligned_fields",
+    "derived_fields",
+    "field_constraints",
+    "geo_positions",
+    "altitude_fields",
+    "velocity_fields",
+    "icao_fields",
+    "non_ascii_names",
+    "reserved_items",
+    "little_endian_fields",
+    "float_fields",
+    "byte_fields",
+    "conformance_rules",
+];
+
+#[test]
+fn generated_code_compiles() {
+    let generated: Vec<(&str, String)> = COMPILED_FIXTURES
+        .iter()
+        .map(|name| (*name, generate_from_fixture("valid", &format!("{}.xml", name))))
+        .collect();
+    let modules: Vec<(&str, &str)> = generated.iter().map(|(n, c)| (*n, c.as_str())).collect();
+
+    if let Err(output) = compile_check(&modules) {
+        panic!("Generated code does not compile:\n{}", output);
+    }
+}
+
+#[test]
+fn generate_from_adjusted_lowered_ir() {
+    let xml = load_fixture("valid", "simple_fixed.xml");
+    let ir = to_ir(parse_category(&xml).unwrap()).unwrap();
+
+    let mut lowered = lower(&ir).unwrap();
+    assert_eq!(generate_lowered(&lowered).to_string(), generate(&ir).unwrap().to_string());
+
+    lowered.module_name = quote::format_ident!("renamed");
+    let code = generate_lowered(&lowered).to_string();
+    assert_code_contains(&code, &["pub mod renamed"]);
+}
+
+// ============================================================================
+// Feature-Gated Item Tests
+// ============================================================================
+
+#[test]
+fn feature_gated_items_compile_with_and_without_feature() {
+    let xml = load_fixture("valid", "multi_item_record.xml");
+    let mut lowered = lower(&to_ir(parse_category(&xml).unwrap()).unwrap()).unwrap();
+    let config = parse_features(r#"<features>
+        <feature name="extra_items">
+            <item id="20"/>
+            <item id="240"/>
+        </feature>
+    </features>"#).unwrap();
+    apply_features(&mut lowered, &config).unwrap();
+
+    let code = format!(
+        "{}\n{}",
+        generate_lowered(&lowered),
+        run_backend(BorrowedBackend::default(), &lowered)
+    );
+    assert_code_contains(&code, &[
+        "# [cfg (feature = \"extra_items\")] mod item240 { use super :: * ;",
+        "# [cfg (feature = \"extra_items\")] pub use item240 :: * ;",
+        "# [cfg (feature = \"extra_items\")] pub item240 : Option < Item240 >",
+        "item compiled out by a cargo feature",
+    ]);
+    assert_code_not_contains(&code, &["mod item010"]);
+
+    for enabled in [true, false] {
+        if let Err(output) = compile_check_with_features(&[("gated", &code)], &[("extra_items", enabled)]) {
+            panic!("Feature-gated code does not compile (enabled: {}):\n{}", enabled, output);
+        }
+    }
+}
+
+// ============================================================================
+// Symbol Rename Tests
+// ============================================================================
+
+#[test]
+fn renamed_symbols_compile() {
+    let xml = load_fixture("valid", "enum_basic.xml");
+    let mut lowered = lower(&to_ir(parse_category(&xml).unwrap()).unwrap()).unwrap();
+    let config = parse_renames(r#"
+        [cat001.item010.types]
+        Item010 = "TargetReport"
+        TargetType = "DetectionKind"
+
+        [cat001.item010.fields]
+        target_type = "detection"
+
+        [cat001.item010.variants.TargetType]
+        Psr = "Primary"
+    "#).unwrap();
+    apply_renames(&mut lowered, &config).unwrap();
+
+    let code = format!(
+        "{}\n{}",
+        generate_lowered(&lowered),
+        run_backend(BorrowedBackend::default(), &lowered)
+    );
+    assert_code_contains(&code, &[
+        "pub struct TargetReport",
+        "pub enum DetectionKind",
+        "Primary = 1",
+        "pub detection : DetectionKind",
+        "pub item010 : Option < TargetReport >",
+    ]);
+    assert_code_not_contains(&code, &["Item010", "TargetType", "Psr"]);
+
+    if let Err(output) = compile_check(&[("renamed", &code)]) {
+        panic!("Renamed code does not compile:\n{}", output);
+    }
+}
+
+// ============================================================================
+// C API Tests
+// ============================================================================
+
+#[test]
+fn ffi_shims_compile() {
+    let generated: Vec<(&str, String)> = COMPILED_FIXTURES
+        .iter()
+        .map(|name| {
+            let xml = load_fixture("valid", &format!("{}.xml", name));
+            let ir = to_ir(parse_category(&xml).unwrap()).unwrap();
+            (*name, format!("{}\n{}", generate(&ir).unwrap(), generate_ffi(&ir).unwrap().shims))
+        })
+        .collect();
+    let modules: Vec<(&str, &str)> = generated.iter().map(|(n, c)| (*n, c.as_str())).collect();
+
+    if let Err(output) = compile_check(&modules) {
+        panic!("Generated C API shims do not compile:\n{}", output);
+    }
+}
+
+#[test]
+fn ffi_headers_are_valid_c() {
+    let Ok(cc) = std::process::Command::new("cc").arg("--version").output() else {
+        eprintln!("no C compiler found, skipping header check");
+        return;
+    };
+    assert!(cc.status.success());
+
+    for name in COMPILED_FIXTURES {
+        let xml = load_fixture("valid", &format!("{}.xml", name));
+        let header = generate_ffi(&to_ir(parse_category(&xml).unwrap()).unwrap()).unwrap().header;
+        let path = test_utils::create_temp_file(&header, "h");
+
+        let output = std::process::Command::new("cc")
+            .args(["-std=c99", "-Wall", "-Werror", "-fsyntax-only", "-x", "c"])
+            .arg(&path)
+            .output()
+            .unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert!(
+            output.status.success(),
+            "Header for '{}' is not valid C:\n{}\n{}",
+            name,
+            String::from_utf8_lossy(&output.stderr),
+            header
+        );
+    }
+}
+
+// ============================================================================
+// WASM Tests
+// ============================================================================
+
+#[test]
+fn wasm_bindings_compile() {
+    let generated: Vec<(&str, String)> = COMPILED_FIXTURES
+        .iter()
+        .map(|name| {
+            let xml = load_fixture("valid", &format!("{}.xml", name));
+            let ir = to_ir(parse_category(&xml).unwrap()).unwrap();
+            (*name, format!("{}\n{}", generate(&ir).unwrap(), generate_wasm(&ir).unwrap()))
+        })
+        .collect();
+    let modules: Vec<(&str, &str)> = generated.iter().map(|(n, c)| (*n, c.as_str())).collect();
+
+    if let Err(output) = compile_check_with_deps(&modules, &["wasm-bindgen = \"0.2\""]) {
+        panic!("Generated wasm bindings do not compile:\n{}", output);
+    }
+}
+
+// ============================================================================
+// Protobuf Tests
+// ============================================================================
+
+#[test]
+fn proto_conversions_compile() {
+    let generated: Vec<(&str, String)> = COMPILED_FIXTURES
+        .iter()
+        .map(|name| {
+            let xml = load_fixture("valid", &format!("{}.xml", name));
+            let ir = to_ir(parse_category(&xml).unwrap()).unwrap();
+            (*name, format!("{}\n{}", generate(&ir).unwrap(), generate_proto(&ir).unwrap().conversions))
+        })
+        .collect();
+    let modules: Vec<(&str, &str)> = generated.iter().map(|(n, c)| (*n, c.as_str())).collect();
+
+    if let Err(output) = compile_check_with_deps(&modules, &["prost = \"0.14\""]) {
+        panic!("Generated protobuf conversions do not compile:\n{}", output);
+    }
+}
+
+#[test]
+fn proto_definitions_mirror_the_record() {
+    let xml = load_fixture("valid", "conformance_rules.xml");
+    let output = generate_proto(&to_ir(parse_category(&xml).unwrap()).unwrap()).unwrap();
+
+    for expected in [
+        "package asterix.cat048;\n",
+        "enum Typ {\n  TYP_UNSPECIFIED = 0;\n  TYP_PSR = 1;\n  TYP_SSR = 2;\n}\n",
+        "message Record {\n  Item010 item010 = 1;\n  Item020 item020 = 2;\n  Item030 item030 = 3;\n  Item240 item240 = 4;\n}\n",
+        "message Item020 {\n  Item020Part0 part0 = 1;\n  Item020Part1 part1 = 2;\n  Item020Part2 part2 = 3;\n  bytes extensions = 4;\n}\n",
+        "message Item020Part0 {\n  Typ typ = 1;\n  uint32 sim = 2;\n}\n",
+        "message Item030 {\n  repeated Item030Element items = 1;\n}\n",
+        "message Item240 {\n  string callsign = 1;\n}\n",
+    ] {
+        assert!(output.proto.contains(expected), "missing {:?} in:\n{}", expected, output.proto);
+    }
+
+    assert_code_contains(&output.conversions.to_string(), &[
+        "pub mod cat048_proto",
+        "# [derive (Clone , PartialEq , :: prost :: Message)] pub struct Record",
+        "# [prost (int32 , tag = \"1\")] pub typ : i32",
+        "typ : value :: raw_variant (message . typ) . map_err (| error | error . within (\"typ\")) ?",
+        "impl TryFrom < Item020 > for generated :: Item020",
+    ]);
+}
+
+// ============================================================================
+// Arrow Tests
+// ============================================================================
+
+#[test]
+fn arrow_conversions_compile() {
+    let generated: Vec<(&str, String)> = COMPILED_FIXTURES
+        .iter()
+        .map(|name| {
+            let xml = load_fixture("valid", &format!("{}.xml", name));
+            let ir = to_ir(parse_category(&xml).unwrap()).unwrap();
+            (*name, format!("{}\n{}", generate(&ir).unwrap(), generate_arrow(&ir).unwrap()))
+        })
+        .collect();
+    let modules: Vec<(&str, &str)> = generated.iter().map(|(n, c)| (*n, c.as_str())).collect();
+
+    if let Err(output) = compile_check_with_deps(&modules, &["arrow-array = \"54\"", "arrow-schema = \"54\""]) {
+        panic!("Generated Arrow conversions do not compile:\n{}", output);
+    }
+}
+
+// ============================================================================
+// Kaitai Struct Tests
+// ============================================================================
+
+/// Checks that every `.ksy` description is valid YAML with the expected
+/// top-level keys, using Python's `yaml` module when available.
+#[test]
+fn ksy_descriptions_are_valid_yaml() {
+    let check = "import sys, yaml\n\
+                 doc = yaml.safe_load(sys.stdin)\n\
+                 assert set(doc) >= {'meta', 'seq', 'types'}, sorted(doc)\n\
+                 assert 'record' in doc['types']\n";
+    let available = std::process::Command::new("python3")
+        .args(["-c", "import yaml"])
+        .output()
+        .is_ok_and(|o| o.status.success());
+    if !available {
+        eprintln!("python3 with yaml not found, skipping .ksy check");
+        return;
+    }
+
+    for name in COMPILED_FIXTURES {
+        let xml = load_fixture("valid", &format!("{}.xml", name));
+        let ksy = generate_ksy(&to_ir(parse_category(&xml).unwrap()).unwrap()).unwrap();
+
+        let mut child = std::process::Command::new("python3")
+            .args(["-c", check])
+            .stdin(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+        std::io::Write::write_all(&mut child.stdin.take().unwrap(), ksy.as_bytes()).unwrap();
+        let output = child.wait_with_output().unwrap();
+
+        assert!(
+            output.status.success(),
+            "Description for '{}' is not valid:\n{}\n{}",
+            name,
+            String::from_utf8_lossy(&output.stderr),
+            ksy
+        );
+    }
+}
+
+// ============================================================================
+// Documentation Tests
+// ============================================================================
+
+#[test]
+fn docs_list_every_item() {
+    for name in COMPILED_FIXTURES {
+        let xml = load_fixture("valid", &format!("{}.xml", name));
+        let ir = to_ir(parse_category(&xml).unwrap()).unwrap();
+        let lowered = lower(&ir).unwrap();
+        let docs = generate_docs(&ir).unwrap();
+
+        for item in &lowered.items {
+            let heading = format!(
+                "\n## I{:03}/{}\n",
+                lowered.category_id,
+                item.name.to_string().trim_start_matches("Item")
+            );
+            assert!(docs.contains(&heading), "'{}' has no section for {}:\n{}", name, item.name, docs);
+        }
+    }
+}
+
+// ============================================================================
+// Synthetic Definition Tests
+// ============================================================================
+
+/// Generates code for a synthetic definition, panicking with the seed on failure.
+fn generate_synth(seed: u64) -> String {
+    let xml = synth_category(&SynthConfig::with_seed(seed));
+    let parsed = parse_category(&xml)
+        .unwrap_or_else(|e| panic!("seed {}: failed to parse synthetic XML: {:?}\n{}", seed, e, xml));
+    generate(&to_ir(parsed).unwrap()).unwrap().to_string()
+}
+
+#[test]
+fn synthetic_definitions_generate() {
+    for seed in 0..200 {
+        let code = generate_synth(seed);
+        assert_code_contains(&code, &["pub struct Record"]);
+    }
+}
+
+#[test]
+fn synthetic_definitions_compile() {
+    let generated: Vec<(String, String)> = (0..20)
+        .map(|seed| (format!("synth_{}", seed), generate_synth(seed)))
+        .collect();
+    let modules: Vec<(&str, &str)> = generated.iter().map(|(n, c)| (n.as_str(), c.as_str())).collect();
+
+    if let Err(output) = compile_check(&modules) {
+        panic!("Synthetic definitions do not compile:\n{}", output);
+    }
+}
+
 // ============================================================================
 // Basic Code Generation Tests
 // ============================================================================
@@ -102,6 +519,51 @@ fn generate_enum_code() {
     ]);
 }
 
+#[test]
+fn generate_enum_aliases_and_unknown_values() {
+    let code = generate_from_fixture("valid", "enum_policies.xml");
+
+    assert_code_contains(&code, &[
+        "pub const None : Self = Self :: NoDetection ;",
+        "pub const Psr : Self = Self :: SinglePsr ;",
+        "pub enum Typ { NoDetection = 0u8 , SinglePsr = 1u8 , SingleSsr = 2u8 , Unknown (u8) , }",
+        "pub enum Sim { Actual = 0u8 , Simulated = 1u8 , }",
+        "DecodeError :: InvalidData (\"enum value has no variant\")",
+        "pub enum Rdp { Chain1 = 0u8 , Chain2 = 1u8 , Undefined = 7u8 , }",
+        ". unwrap_or (Self :: Undefined)",
+    ]);
+}
+
+#[test]
+fn enum_alias_of_another_variant_is_rejected() {
+    let xml = r#"<category id="1">
+        <item id="10" frn="1">
+            <fixed bytes="1">
+                <enum name="kind" bits="8">
+                    <value name="A" value="0"/>
+                    <value name="B" alias="A" value="1"/>
+                </enum>
+            </fixed>
+        </item>
+    </category>"#;
+    let err = lower(&to_ir(parse_category(xml).unwrap()).unwrap()).unwrap_err();
+    assert!(err.contains("Enum alias collision"), "{}", err);
+}
+
+#[test]
+fn generate_field_descriptions() {
+    let code = generate_from_fixture("valid", "field_descriptions.xml");
+
+    assert_code_contains(&code, &[
+        "# [doc = \"System Area Code\"] pub sac : u8",
+        "fn describe (field : & str) -> Option < & 'static str >",
+        "\"sic\" => Some (\"System Identification Code\")",
+        "\"part0.atp\" => Some (\"Address Type\")",
+        "\"sub0.wind_speed\" => Some (\"Wind Speed in knots\")",
+    ]);
+    assert!(!code.contains("\"part0.rc\""));
+}
+
 // ============================================================================
 // EPB (Extend Presence Bit) Code Generation
 // ============================================================================
@@ -153,6 +615,41 @@ fn generate_compound_complex_code() {
     assert_code_contains(&code, &["pub struct"]);
 }
 
+#[test]
+fn generate_compound_sub_item_enums_once() {
+    // Sub-item enums are part of the item's enums; emitting them again
+    // with the sub-items defined each enum twice.
+    let xml = r#"<category id="1">
+        <item id="100" frn="1">
+            <compound>
+                <fixed bytes="1">
+                    <enum name="mode" bits="2">
+                        <value name="OFF" value="0"/>
+                        <value name="ON" value="1"/>
+                    </enum>
+                    <spare bits="6"/>
+                </fixed>
+                <extended bytes="1">
+                    <part index="0">
+                        <enum name="kind" bits="7">
+                            <value name="PSR" value="1"/>
+                        </enum>
+                    </part>
+                </extended>
+            </compound>
+        </item>
+    </category>"#;
+    let code = generate(&to_ir(parse_category(xml).unwrap()).unwrap()).unwrap().to_string();
+
+    for name in ["Mode", "Kind"] {
+        let definitions = code.matches(&format!("pub enum {} ", name)).count();
+        assert_eq!(definitions, 1, "enum {} is defined {} times", name, definitions);
+    }
+    if let Err(output) = compile_check(&[("compound_enums", &code)]) {
+        panic!("Compound sub-item enums do not compile:\n{}", output);
+    }
+}
+
 // ============================================================================
 // Repetitive Item Code Generation
 // ============================================================================
@@ -164,6 +661,7 @@ fn generate_repetitive_code() {
     assert_code_contains(&code, &[
         "Item070Element",
         "pub items : Vec < Item070Element >",
+        "if self . items . len () != 5usize { return Err (DecodeError :: InvalidData (\"repetitive item does not have its number of repetitions\")) ; }",
     ]);
 }
 
@@ -203,6 +701,169 @@ fn spare_bits_not_in_struct() {
     assert_code_not_contains(&code, &["spare", "pub spare"]);
 }
 
+// ============================================================================
+// Item Names
+// ============================================================================
+
+#[test]
+fn item_titles_generate_aliases_and_constants() {
+    let code = generate_from_fixture("valid", "named_items.xml");
+
+    assert_code_contains(&code, &[
+        "pub type DataSourceIdentifier = Item010 ;",
+        "pub type Mode3ACodeInOctalRepresentation = Item070 ;",
+        "pub const TITLE : & 'static str = \"Data Source Identifier\" ;",
+        "pub const SHORT_NAME : & 'static str = \"DSI\" ;",
+        "pub const SHORT_NAME : & 'static str = \"TOD\" ;",
+    ]);
+}
+
+#[test]
+fn item_titles_giving_the_same_alias_are_rejected() {
+    let xml = r#"<category id="1">
+        <item id="10" frn="1" name="Track Number">
+            <fixed bytes="1"><field name="a" bits="8"/></fixed>
+        </item>
+        <item id="20" frn="2" name="Track number">
+            <fixed bytes="1"><field name="b" bits="8"/></fixed>
+        </item>
+    </category>"#;
+    let err = lower(&to_ir(parse_category(xml).unwrap()).unwrap()).unwrap_err();
+    assert!(err.contains("Alias collision"), "{}", err);
+}
+
+#[test]
+fn non_ascii_names_are_transliterated() {
+    let code = generate_from_fixture("valid", "non_ascii_names.xml");
+
+    assert_code_contains(&code, &[
+        "pub type QualiteDuSignal = Item010 ;",
+        "pub qualite : u8",
+        "pub portee_km_u00b2 : u8",
+        "pub etat : Etat",
+        "Arrete = 0",
+    ]);
+}
+
+#[test]
+fn reserved_items_generate_unit_structs() {
+    let code = generate_from_fixture("valid", "reserved_items.xml");
+
+    assert_code_contains(&code, &[
+        "pub struct Item015 ;",
+        "its 2 byte(s) of spare bits are skipped when decoding and written as zeros when encoding",
+    ]);
+}
+
+#[test]
+fn little_endian_fields_swap_bytes() {
+    let code = generate_from_fixture("valid", "little_endian_fields.xml");
+
+    assert_code_contains(&code, &[
+        "let sequence = reader . read_bits_le (16usize) ? as u16 ;",
+        "let offset = reader . read_bits_le (24usize) ? as u32 ;",
+        "writer . write_bits_le (self . offset as u64 , 24usize) ?",
+        "read_bits_le (32usize)",
+    ]);
+}
+
+#[test]
+fn float_fields_convert_their_bits() {
+    let code = generate_from_fixture("valid", "float_fields.xml");
+
+    assert_code_contains(&code, &[
+        "# [derive (Debug , Clone , PartialEq)] pub struct Item020 { pub temperature : f32 , pub pressure : f32 }",
+        "let temperature = f32 :: from_bits (reader . read_bits (32usize) ? as u32) ;",
+        "let pressure = f32 :: from_bits (reader . read_bits_le (32usize) ? as u32) ;",
+        "writer . write_bits (self . temperature . to_bits () as u64 , 32usize) ?",
+        "Some (f64 :: from_bits (reader . read_bits (64usize) ?))",
+        "writer . write_bits (value . to_bits () , 64usize) ?",
+        "assert_eq ! (item . pressure , 1013.0 , \"pressure\") ;",
+    ]);
+}
+
+#[test]
+fn byte_fields_are_arrays_or_vectors() {
+    let code = generate_from_fixture("valid", "byte_fields.xml");
+
+    assert_code_contains(&code, &[
+        "pub struct Item250Element { pub mb_data : [u8 ; 7usize] , pub bds1 : u8 , pub bds2 : u8 }",
+        "pub struct Item270 { pub payload : Vec < u8 > }",
+        "pub struct Item280 { pub signature : Option < [u8 ; 4usize] > }",
+        "let mut bytes = vec ! [0u8 ; 40usize] ; reader . read_bytes (& mut bytes) ? ; bytes",
+        "writer . write_bytes (& self . acas_ra) ?",
+        "writer . write_fixed_bytes (& self . payload , 40usize) ?",
+        "assert_eq ! (item . acas_ra , [48u8 , 0u8 , 20u8 , 0u8 , 0u8 , 0u8 , 1u8] , \"acas_ra\") ;",
+    ]);
+}
+
+#[test]
+fn conformance_checks_follow_the_definition() {
+    let code = generate_from_fixture("valid", "conformance_rules.xml");
+
+    assert_code_contains(&code, &[
+        "conformance :: report (issues , path , \"item010\" , conformance :: Rule :: MissingItem) ;",
+        "conformance :: audit_item (item , & format ! (\"{}item020.\" , path) , issues) ;",
+        "conformance :: check_bits (issues , path , \"sim\" , field as u64 , 4usize) ;",
+        "if let Typ :: Unknown (value) = field",
+        "if self . part2 . is_some () && self . part1 . is_none ()",
+        "if ! self . extensions . is_empty () && self . part2 . is_none ()",
+        "if self . items . len () != 2usize",
+        "if field . len () > 6usize",
+        "impl Conformance for Item240",
+    ]);
+    // Fields as wide as their type can't overflow.
+    assert!(!code.contains("\"sac\" , field as u64"));
+}
+
+#[test]
+fn records_convert_to_generic_values() {
+    let code = generate_from_fixture("valid", "conformance_rules.xml");
+
+    assert_code_contains(&code, &[
+        "pub fn to_value_map (& self) -> value :: ValueMap",
+        "pub fn from_value_map (map : & value :: ValueMap) -> Result < Self , value :: ValueError >",
+        "value :: check_keys (map , & [\"item010\" , \"item020\" , \"item030\" , \"item240\"]) ?",
+        "Typ :: Ssr => value :: Value :: Text (\"Ssr\" . to_string ())",
+        "Typ :: Unknown (raw) => value :: Value :: Number (* raw as u128)",
+        "value :: variant (input , & [(\"Psr\" , Typ :: Psr) , (\"Ssr\" , Typ :: Ssr)])",
+        "extensions : value :: field :: < Option < Vec < u8 >> > (map , \"extensions\") ? . unwrap_or_default ()",
+        "value :: Value :: List (self . items . iter () . map (ToValue :: to_value) . collect ())",
+    ]);
+}
+
+// ============================================================================
+// Field Revisions
+// ============================================================================
+
+#[test]
+fn field_revisions_generate_deprecations() {
+    let code = generate_from_fixture("valid", "field_revisions.xml");
+
+    assert_code_contains(&code, &[
+        "# ! [allow (deprecated)]",
+        "# [deprecated (note = \"deprecated since revision 1.27 of the definition\")] pub legacy_flag : u8",
+        "# [deprecated (note = \"deprecated since revision 1.29 of the definition\")] pub elevation : Option < u8 >",
+        "# [deprecated (note = \"renamed to `rotation_period`\")] pub fn antenna_rotation_speed (& self) -> & u16",
+        "pub fn mode (& self) -> & SystemMode",
+        "pub fn height_mut (& mut self) -> & mut Option < u8 >",
+    ]);
+}
+
+#[test]
+fn renamed_from_an_existing_field_is_rejected() {
+    let xml = r#"<category id="1">
+        <item id="10" frn="1">
+            <fixed bytes="2">
+                <field name="a" bits="8"/>
+                <field name="b" bits="8" renamed_from="a"/>
+            </fixed>
+        </item>
+    </category>"#;
+    let err = lower(&to_ir(parse_category(xml).unwrap()).unwrap()).unwrap_err();
+    assert!(err.contains("Renamed field collision"), "{}", err);
+}
+
 // ============================================================================
 // Record Generation
 // ============================================================================
@@ -224,8 +885,8 @@ fn generate_record_fspec_handling() {
 
     assert_code_contains(&code, &[
         "let fspec = Fspec :: read",
-        "fspec . is_set",
-        "fspec . set",
+        "fspec . is_frn_set",
+        "fspec . set_frn",
     ]);
 }
 
@@ -19,7 +19,18 @@ fn parse_simple_fixed_item() {
     assert_eq!(category.id, 1);
     assert_eq!(category.items.len(), 1);
     assert_eq!(category.items[0].id, 10);
-    assert_eq!(category.items[0].frn, 0);
+    assert_eq!(category.items[0].frn, 1);
+}
+
+#[test]
+fn parse_item_names() {
+    let xml = load_fixture("valid", "named_items.xml");
+    let category = parse_category(&xml).expect("Failed to parse XML");
+
+    assert_eq!(category.items[0].name.as_deref(), Some("Data Source Identifier"));
+    assert_eq!(category.items[0].short.as_deref(), Some("DSI"));
+    assert_eq!(category.items[2].name, None);
+    assert_eq!(category.items[2].short.as_deref(), Some("TOD"));
 }
 
 #[test]
@@ -294,6 +305,23 @@ fn parse_mixed_all() {
     assert!(result.is_ok());
 }
 
+#[test]
+fn parse_item_examples() {
+    let xml = load_fixture("valid", "item_examples.xml");
+    let category = parse_category(&xml).expect("Failed to parse XML");
+
+    assert!(matches!(category.items[0].data, ItemStructure::Fixed(_)));
+    let example = &category.items[0].examples[0];
+    assert_eq!(example.bytes, "0x19 0xC9");
+    assert_eq!(example.description.as_deref(), Some("SAC 25, SIC 201"));
+    assert_eq!(example.expects.len(), 2);
+    assert_eq!(example.expects[1].field, "sic");
+    assert_eq!(example.expects[1].value, "0xC9");
+
+    assert_eq!(category.items[1].examples.len(), 2);
+    assert_eq!(category.items[2].examples[0].description, None);
+}
+
 // ============================================================================
 // Invalid XML Tests
 // ============================================================================
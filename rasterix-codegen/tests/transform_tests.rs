@@ -13,7 +13,14 @@ use test_utils::load_fixture;
 fn build_ir_from_fixture(category: &str, filename: &str) -> IR {
     let xml = load_fixture(category, filename);
     let parsed = parse_category(&xml).expect("Failed to parse XML fixture");
-    to_ir(parsed)
+    to_ir(parsed).unwrap()
+}
+
+/// Helper function returning why a fixture file doesn't give IR.
+fn ir_error_from_fixture(category: &str, filename: &str) -> String {
+    let xml = load_fixture(category, filename);
+    let parsed = parse_category(&xml).expect("Failed to parse XML fixture");
+    to_ir(parsed).expect_err("Fixture should be rejected")
 }
 
 // ============================================================================
@@ -27,7 +34,15 @@ fn transform_simple_fixed_to_ir() {
     assert_eq!(ir.category.id, 1);
     assert_eq!(ir.category.items.len(), 1);
     assert_eq!(ir.category.items[0].id, 10);
-    assert_eq!(ir.category.items[0].frn, 0);
+    assert_eq!(ir.category.items[0].frn, 1);
+}
+
+#[test]
+fn transform_keeps_three_digit_item_ids() {
+    let ir = build_ir_from_fixture("valid", "high_item_ids.xml");
+
+    let ids: Vec<u16> = ir.category.items.iter().map(|i| i.id).collect();
+    assert_eq!(ids, [10, 500, 999]);
 }
 
 #[test]
@@ -38,7 +53,7 @@ fn transform_preserves_item_order() {
     assert!(ir.category.items.len() >= 2);
 
     // Items should be in order by their position in XML
-    let ids: Vec<u8> = ir.category.items.iter().map(|i| i.id).collect();
+    let ids: Vec<u16> = ir.category.items.iter().map(|i| i.id).collect();
     assert_eq!(ids[0], 10);
     assert_eq!(ids[1], 20);
 }
@@ -133,10 +148,17 @@ fn transform_field_element() {
     match &ir.category.items[0].layout {
         IRLayout::Fixed { elements, .. } => {
             match &elements[0] {
-                IRElement::Field { name, bits, is_string } => {
+                IRElement::Field { name, bits, is_string, revision, description, constraints, value_type, little_endian, is_float, is_bytes } => {
                     assert_eq!(name, "sac");
                     assert_eq!(*bits, 8);
-                    assert_eq!(*is_string, false);
+                    assert!(!*is_string);
+                    assert_eq!(*revision, IRRevision::default());
+                    assert_eq!(*description, None);
+                    assert_eq!(*constraints, IRConstraints::default());
+                    assert_eq!(*value_type, None);
+                    assert!(!*little_endian);
+                    assert!(!*is_float);
+                    assert!(!*is_bytes);
                 }
                 _ => panic!("Expected Field element"),
             }
@@ -152,7 +174,7 @@ fn transform_enum_element() {
     match &ir.category.items[0].layout {
         IRLayout::Fixed { elements, .. } => {
             match &elements[0] {
-                IRElement::Enum { name, bits, values } => {
+                IRElement::Enum { name, bits, values, .. } => {
                     assert_eq!(name, "target_type");
                     assert_eq!(*bits, 3);
                     assert!(!values.is_empty());
@@ -164,6 +186,72 @@ fn transform_enum_element() {
     }
 }
 
+#[test]
+fn transform_field_revisions() {
+    let ir = build_ir_from_fixture("valid", "field_revisions.xml");
+
+    let IRLayout::Fixed { elements, .. } = &ir.category.items[2].layout else {
+        panic!("Expected Fixed layout");
+    };
+    let revisions: Vec<_> = elements
+        .iter()
+        .filter_map(|element| match element {
+            IRElement::Field { revision, .. } | IRElement::Enum { revision, .. } => Some(revision),
+            IRElement::EPB { content } => match content.as_ref() {
+                IRElement::Field { revision, .. } => Some(revision),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(revisions[0].renamed_from.as_deref(), Some("mode"));
+    assert_eq!(revisions[0].deprecated_since, None);
+    assert_eq!(revisions[1].deprecated_since.as_deref(), Some("1.27"));
+    assert_eq!(revisions[2].renamed_from.as_deref(), Some("height"));
+    assert_eq!(revisions[2].deprecated_since.as_deref(), Some("1.29"));
+}
+
+#[test]
+fn transform_enum_aliases_and_unknown_values() {
+    let ir = build_ir_from_fixture("valid", "enum_policies.xml");
+
+    let IRLayout::Fixed { elements, .. } = &ir.category.items[0].layout else {
+        panic!("Expected Fixed layout");
+    };
+    let enums: Vec<_> = elements
+        .iter()
+        .map(|element| match element {
+            IRElement::Enum { aliases, unknown, .. } => (aliases, unknown),
+            _ => panic!("Expected Enum element"),
+        })
+        .collect();
+
+    assert_eq!(
+        *enums[0].0,
+        [
+            ("NO_DETECTION".to_string(), "None".to_string()),
+            ("SINGLE_PSR".to_string(), "Psr".to_string()),
+        ]
+    );
+    assert_eq!(*enums[0].1, IRUnknownValues::Preserve);
+    assert_eq!(*enums[1].1, IRUnknownValues::Error);
+    assert_eq!(*enums[2].1, IRUnknownValues::MapTo("UNDEFINED".to_string()));
+}
+
+#[test]
+fn transform_field_descriptions() {
+    let ir = build_ir_from_fixture("valid", "field_descriptions.xml");
+
+    let IRLayout::Fixed { elements, .. } = &ir.category.items[0].layout else {
+        panic!("Expected Fixed layout");
+    };
+    let IRElement::Field { description, .. } = &elements[0] else {
+        panic!("Expected Field element");
+    };
+    assert_eq!(description.as_deref(), Some("System Area Code"));
+}
+
 #[test]
 fn transform_epb_element() {
     let ir = build_ir_from_fixture("valid", "epb_field.xml");
@@ -190,20 +278,171 @@ fn transform_spare_element() {
     }
 }
 
+#[test]
+fn transform_item_examples() {
+    let ir = build_ir_from_fixture("valid", "item_examples.xml");
+
+    let example = &ir.category.items[0].examples[0];
+    assert_eq!(example.bytes, [0x19, 0xC9]);
+    assert_eq!(example.description.as_deref(), Some("SAC 25, SIC 201"));
+    assert_eq!(example.values, [("sac".to_string(), "25".to_string()), ("sic".to_string(), "0xC9".to_string())]);
+    assert_eq!(ir.category.items[1].examples[1].bytes, [0x31, 0x80]);
+}
+
 // ============================================================================
 // Validation Tests
 // ============================================================================
 
 #[test]
-#[should_panic(expected = "Bit count mismatch")]
 fn validation_rejects_bit_mismatch() {
-    let _ = build_ir_from_fixture("invalid", "bit_mismatch.xml");
+    let err = ir_error_from_fixture("invalid", "bit_mismatch.xml");
+    assert!(err.contains("Bit count mismatch"), "{}", err);
 }
 
 #[test]
-#[should_panic(expected = "Part group")]
 fn validation_rejects_extended_bit_mismatch() {
-    let _ = build_ir_from_fixture("invalid", "extended_bit_mismatch.xml");
+    let err = ir_error_from_fixture("invalid", "extended_bit_mismatch.xml");
+    assert!(err.contains("Part group"), "{}", err);
+}
+
+#[test]
+fn validation_rejects_duplicate_frn() {
+    let err = ir_error_from_fixture("invalid", "duplicate_frn.xml");
+    assert!(err.contains("FRN collision"), "{}", err);
+}
+
+#[test]
+fn validation_rejects_zero_frn() {
+    let err = ir_error_from_fixture("invalid", "zero_frn.xml");
+    assert!(err.contains("FRNs start at 1"), "{}", err);
+}
+
+#[test]
+fn validation_rejects_four_digit_item_id() {
+    let err = ir_error_from_fixture("invalid", "item_id_too_long.xml");
+    assert!(err.contains("more than three digits"), "{}", err);
+}
+
+#[test]
+fn validation_rejects_unknown_values_mapped_to_a_missing_value() {
+    let err = ir_error_from_fixture("invalid", "unknown_map_to_missing_value.xml");
+    assert!(err.contains("UNDEFINED is not one of its values"), "{}", err);
+}
+
+#[test]
+fn validation_rejects_duplicate_fields() {
+    let err = ir_error_from_fixture("invalid", "duplicate_field_same_scope.xml");
+    assert!(err.contains("Identifier collision: item 010: fields `value` and `value` both become `value`"), "{}", err);
+}
+
+#[test]
+fn validation_rejects_field_named_like_an_enum() {
+    let err = ir_error_from_fixture("invalid", "duplicate_enum_field.xml");
+    assert!(err.contains("Identifier collision: item 010: fields `data` and `data` both become `data`"), "{}", err);
+}
+
+#[test]
+fn validation_rejects_fields_colliding_after_case_conversion() {
+    let err = ir_error_from_fixture("invalid", "field_case_collision.xml");
+    assert!(err.contains("Identifier collision: item 070, part 1: fields `Mode3A` and `mode3a` both become `mode3a`"), "{}", err);
+}
+
+#[test]
+fn validation_rejects_variants_colliding_after_case_conversion() {
+    let err = ir_error_from_fixture("invalid", "variant_case_collision.xml");
+    assert!(err.contains("Identifier collision: item 020: variants `SSR` and `ssr` of enum `typ` both become `Ssr`"), "{}", err);
+}
+
+#[test]
+fn validation_rejects_enums_colliding_across_items() {
+    let err = ir_error_from_fixture("invalid", "enum_case_collision.xml");
+    assert!(err.contains("Identifier collision: enums `typ` of item 020 and `TYP` of item 030, sub-item 0 both become `Typ`"), "{}", err);
+}
+
+#[test]
+fn validation_rejects_spare_only_items_not_reserved() {
+    let err = ir_error_from_fixture("invalid", "spare_only_item.xml");
+    assert!(err.contains("Item 110 has no fields: mark it reserved=\"true\" if it only holds spare bits"), "{}", err);
+}
+
+#[test]
+fn validation_rejects_reserved_items_with_fields() {
+    let err = ir_error_from_fixture("invalid", "reserved_with_fields.xml");
+    assert!(err.contains("Reserved item 110 is not a fixed item of spare bits alone"), "{}", err);
+}
+
+#[test]
+fn reserved_items_are_kept() {
+    let ir = build_ir_from_fixture("valid", "reserved_items.xml");
+    let reserved: Vec<_> = ir.category.items.iter().map(|item| item.reserved).collect();
+    assert_eq!(reserved, [false, true, false]);
+}
+
+#[test]
+fn validation_rejects_little_endian_partial_bytes() {
+    let err = ir_error_from_fixture("invalid", "little_endian_bits.xml");
+    assert!(err.contains("Invalid byte order of field offset: little-endian fields are numbers of whole bytes"), "{}", err);
+}
+
+#[test]
+fn little_endian_fields_are_kept() {
+    let ir = build_ir_from_fixture("valid", "little_endian_fields.xml");
+    let IRLayout::Fixed { elements, .. } = &ir.category.items[1].layout else {
+        panic!("item 020 is fixed");
+    };
+    let little_endian: Vec<_> = elements
+        .iter()
+        .filter_map(|element| match element {
+            IRElement::Field { little_endian, .. } => Some(*little_endian),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(little_endian, [true, false, true]);
+}
+
+#[test]
+fn validation_rejects_floats_of_other_widths() {
+    let err = ir_error_from_fixture("invalid", "float_width.xml");
+    assert!(err.contains("Invalid float field temperature: type f32 is 32 bits, not 16"), "{}", err);
+}
+
+#[test]
+fn float_fields_are_kept() {
+    let ir = build_ir_from_fixture("valid", "float_fields.xml");
+    let IRLayout::Fixed { elements, .. } = &ir.category.items[1].layout else {
+        panic!("item 020 is fixed");
+    };
+    assert!(matches!(elements[0], IRElement::Field { bits: 32, is_float: true, little_endian: false, .. }));
+    assert!(matches!(elements[1], IRElement::Field { bits: 32, is_float: true, little_endian: true, .. }));
+}
+
+#[test]
+fn validation_rejects_byte_fields_of_partial_bytes() {
+    let err = ir_error_from_fixture("invalid", "byte_field_bits.xml");
+    assert!(err.contains("Invalid byte field mb_data: 12 bits are not whole bytes"), "{}", err);
+}
+
+#[test]
+fn byte_fields_are_kept() {
+    let ir = build_ir_from_fixture("valid", "byte_fields.xml");
+    let IRLayout::Fixed { elements, .. } = &ir.category.items[2].layout else {
+        panic!("item 260 is fixed");
+    };
+    assert!(matches!(elements[0], IRElement::Field { bits: 56, is_bytes: true, is_string: false, .. }));
+}
+
+#[test]
+fn non_ascii_names_are_listed() {
+    let ir = build_ir_from_fixture("valid", "non_ascii_names.xml");
+
+    assert_eq!(ir.category.non_ascii_names(), [
+        "item 010: title `Qualité du Signal`",
+        "item 010: field `qualité`",
+        "item 010: field `portée_km²`",
+        "item 020: field `état`",
+        "item 020: variant `Arrêté` of enum `état`",
+    ]);
+    assert!(build_ir_from_fixture("valid", "simple_fixed.xml").category.non_ascii_names().is_empty());
 }
 
 // ============================================================================
@@ -265,3 +504,50 @@ fn transform_handles_same_name_different_subitems() {
     let ir = build_ir_from_fixture("valid", "same_name_different_subitems.xml");
     assert!(!ir.category.items.is_empty());
 }
+
+// ============================================================================
+// JSON Tests
+// ============================================================================
+
+#[cfg(feature = "ir-json")]
+#[test]
+fn ir_json_roundtrip() {
+    use rasterix_codegen::generate::generate;
+
+    for fixture in ["mixed_all.xml", "enum_policies.xml", "field_revisions.xml"] {
+        let ir = build_ir_from_fixture("valid", fixture);
+
+        let loaded = IR::from_json(&ir.to_json()).unwrap();
+
+        assert_eq!(format!("{:?}", loaded), format!("{:?}", ir), "{}", fixture);
+        assert_eq!(generate(&loaded).unwrap().to_string(), generate(&ir).unwrap().to_string(), "{}", fixture);
+    }
+}
+
+#[cfg(feature = "ir-json")]
+#[test]
+fn ir_json_is_versioned() {
+    let json = build_ir_from_fixture("valid", "simple_fixed.xml").to_json();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["schema_version"], "1.0");
+    assert_eq!(value["ir"]["category"]["items"][0]["layout"]["fixed"]["bytes"], 2);
+
+    let newer = json.replace("\"schema_version\": \"1.0\"", "\"schema_version\": \"2.0\"");
+    assert_eq!(
+        IR::from_json(&newer).unwrap_err(),
+        "IR schema version 2.0 is not supported (expected 1.0 to 1.0)"
+    );
+}
+
+#[cfg(feature = "ir-json")]
+#[test]
+fn ir_json_rejects_invalid_ir() {
+    let json = build_ir_from_fixture("valid", "simple_fixed.xml").to_json();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let bits = value["ir"]["category"]["items"][0]["layout"]["fixed"]["elements"][0]["field"]["bits"].clone();
+    let edited = json.replacen(&format!("\"bits\": {}", bits), "\"bits\": 3", 1);
+
+    let err = IR::from_json(&edited).unwrap_err();
+
+    assert!(err.starts_with("invalid IR: error[E001]: item 010:"), "{}", err);
+}
@@ -0,0 +1,188 @@
+//! Altitudes with their units.
+//!
+//! ASTERIX carries pressure altitudes as flight levels in 1/4 FL (25 ft,
+//! e.g. I048/090 and I062/136) and geometric altitudes in 6.25 ft (e.g.
+//! I062/130 and I021/140), both in two's complement.  [`FlightLevel`] and
+//! [`GeometricAltitude`] convert those raw values and between feet, meters
+//! and flight levels.
+//!
+//! Fields defined with `type="flight_level"` or `type="altitude"` get a
+//! typed accessor and setter next to their raw value:
+//!
+//! ```ignore
+//! let level = record.item090.unwrap().flight_level(); // FlightLevel
+//! println!("{} = {:.0} m", level, level.meters());    // FL350 = 10668 m
+//! ```
+//!
+//! # Example
+//!
+//! ```
+//! use rasterix_core::altitude::FlightLevel;
+//!
+//! // -4 in 14 bits: FL -1.
+//! let level = FlightLevel::from_raw(0x3FFC, 14);
+//! assert_eq!(level.feet(), -100.0);
+//! assert_eq!(FlightLevel::from_feet(35_000.0).to_raw(14), 1400);
+//! ```
+
+use std::fmt;
+
+/// Meters in a foot.
+pub const METERS_PER_FOOT: f64 = 0.3048;
+
+/// Returns the value of the low `bits` bits of `raw` in two's complement.
+pub(crate) fn sign_extend(raw: u64, bits: usize) -> i64 {
+    if bits == 0 || bits >= 64 {
+        return raw as i64;
+    }
+    let shift = 64 - bits;
+    ((raw << shift) as i64) >> shift
+}
+
+/// Returns `value` in `bits` bits of two's complement, saturated to the
+/// representable range.
+pub(crate) fn to_twos_complement(value: f64, bits: usize) -> u64 {
+    if bits == 0 {
+        return 0;
+    }
+    let bits = bits.min(64);
+    let max = (i64::MAX >> (64 - bits)) as f64;
+    let raw = value.round().clamp(-max - 1.0, max) as i64 as u64;
+    if bits == 64 { raw } else { raw & ((1 << bits) - 1) }
+}
+
+/// A pressure altitude, in flight levels of 100 ft.
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+pub struct FlightLevel(f64);
+
+impl FlightLevel {
+    /// Flight levels in the least significant bit of a raw value.
+    pub const LSB: f64 = 0.25;
+
+    pub fn from_fl(fl: f64) -> Self {
+        Self(fl)
+    }
+
+    pub fn from_feet(feet: f64) -> Self {
+        Self(feet / 100.0)
+    }
+
+    pub fn from_meters(meters: f64) -> Self {
+        Self::from_feet(meters / METERS_PER_FOOT)
+    }
+
+    /// Decodes a raw value of `bits` bits in two's complement, in 1/4 FL.
+    pub fn from_raw(raw: u64, bits: usize) -> Self {
+        Self(sign_extend(raw, bits) as f64 * Self::LSB)
+    }
+
+    /// Encodes the level in `bits` bits of two's complement, in 1/4 FL,
+    /// saturating levels out of range.
+    pub fn to_raw(self, bits: usize) -> u64 {
+        to_twos_complement(self.0 / Self::LSB, bits)
+    }
+
+    pub fn fl(self) -> f64 {
+        self.0
+    }
+
+    pub fn feet(self) -> f64 {
+        self.0 * 100.0
+    }
+
+    pub fn meters(self) -> f64 {
+        self.feet() * METERS_PER_FOOT
+    }
+}
+
+impl fmt::Display for FlightLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.fract() == 0.0 {
+            write!(f, "FL{:03}", self.0)
+        } else {
+            write!(f, "FL{:06.2}", self.0)
+        }
+    }
+}
+
+/// A geometric altitude above mean sea level, in feet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+pub struct GeometricAltitude(f64);
+
+impl GeometricAltitude {
+    /// Feet in the least significant bit of a raw value.
+    pub const LSB: f64 = 6.25;
+
+    pub fn from_feet(feet: f64) -> Self {
+        Self(feet)
+    }
+
+    pub fn from_meters(meters: f64) -> Self {
+        Self(meters / METERS_PER_FOOT)
+    }
+
+    /// Decodes a raw value of `bits` bits in two's complement, in 6.25 ft.
+    pub fn from_raw(raw: u64, bits: usize) -> Self {
+        Self(sign_extend(raw, bits) as f64 * Self::LSB)
+    }
+
+    /// Encodes the altitude in `bits` bits of two's complement, in 6.25 ft,
+    /// saturating altitudes out of range.
+    pub fn to_raw(self, bits: usize) -> u64 {
+        to_twos_complement(self.0 / Self::LSB, bits)
+    }
+
+    pub fn feet(self) -> f64 {
+        self.0
+    }
+
+    pub fn meters(self) -> f64 {
+        self.0 * METERS_PER_FOOT
+    }
+
+    /// Returns the altitude in flight levels of 100 ft, as reported when
+    /// the pressure is standard.
+    pub fn fl(self) -> f64 {
+        self.0 / 100.0
+    }
+}
+
+impl fmt::Display for GeometricAltitude {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ft", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw_values_are_twos_complement() {
+        assert_eq!(FlightLevel::from_raw(1400, 14).fl(), 350.0);
+        assert_eq!(FlightLevel::from_raw(0x2000, 14).fl(), -2048.0);
+        assert_eq!(GeometricAltitude::from_raw(0xFFFF, 16).feet(), -6.25);
+        assert_eq!(GeometricAltitude::from_raw(4800, 16).feet(), 30_000.0);
+
+        assert_eq!(FlightLevel::from_fl(-1.0).to_raw(14), 0x3FFC);
+        assert_eq!(GeometricAltitude::from_feet(-6.25).to_raw(16), 0xFFFF);
+        // Out of range values saturate.
+        assert_eq!(FlightLevel::from_fl(5000.0).to_raw(14), 0x1FFF);
+        assert_eq!(FlightLevel::from_fl(-5000.0).to_raw(14), 0x2000);
+    }
+
+    #[test]
+    fn units_convert() {
+        let level = FlightLevel::from_meters(10_668.0);
+        assert!((level.fl() - 350.0).abs() < 1e-9);
+        assert!((FlightLevel::from_fl(100.0).meters() - 3048.0).abs() < 1e-9);
+        assert_eq!(GeometricAltitude::from_meters(3048.0).fl(), 100.0);
+    }
+
+    #[test]
+    fn display_with_units() {
+        assert_eq!(FlightLevel::from_fl(50.0).to_string(), "FL050");
+        assert_eq!(FlightLevel::from_fl(350.25).to_string(), "FL350.25");
+        assert_eq!(GeometricAltitude::from_feet(1512.5).to_string(), "1512.5 ft");
+    }
+}
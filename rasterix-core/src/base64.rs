@@ -0,0 +1,133 @@
+//! Standard base64 (RFC 4648) conversion for messages embedded in text
+//! formats such as JSON logs.
+
+use crate::DecodeError;
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes bytes as padded standard base64.
+///
+/// # Example
+///
+/// ```
+/// use rasterix_core::base64;
+///
+/// assert_eq!(base64::encode(&[0x30, 0x00, 0x04, 0x80]), "MAAEgA==");
+/// ```
+pub fn encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |acc, (i, &b)| acc | ((b as u32) << (16 - 8 * i)));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[((n >> (18 - 6 * i)) & 0x3F) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+
+    out
+}
+
+/// Decodes base64 text, ignoring whitespace.
+///
+/// Accepts both the standard (`+/`) and URL-safe (`-_`) alphabets, with or
+/// without `=` padding.
+///
+/// # Example
+///
+/// ```
+/// use rasterix_core::base64;
+///
+/// assert_eq!(base64::decode("MAAE gA==").unwrap(), vec![0x30, 0x00, 0x04, 0x80]);
+/// assert_eq!(base64::decode("MAAEgA").unwrap(), vec![0x30, 0x00, 0x04, 0x80]);
+/// ```
+pub fn decode(text: &str) -> Result<Vec<u8>, DecodeError> {
+    let mut out = Vec::with_capacity(text.len() / 4 * 3);
+    let mut acc = 0u32;
+    let mut bits = 0;
+    let mut padding = false;
+
+    for c in text.bytes().filter(|c| !c.is_ascii_whitespace()) {
+        if c == b'=' {
+            padding = true;
+            continue;
+        }
+        if padding {
+            return Err(DecodeError::InvalidData("base64 data after padding"));
+        }
+
+        acc = (acc << 6) | sextet(c)?;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+        }
+    }
+
+    if bits >= 6 {
+        return Err(DecodeError::InvalidData("truncated base64 data"));
+    }
+
+    Ok(out)
+}
+
+fn sextet(c: u8) -> Result<u32, DecodeError> {
+    let value = match c {
+        b'A'..=b'Z' => c - b'A',
+        b'a'..=b'z' => c - b'a' + 26,
+        b'0'..=b'9' => c - b'0' + 52,
+        b'+' | b'-' => 62,
+        b'/' | b'_' => 63,
+        _ => return Err(DecodeError::InvalidData("invalid base64 character")),
+    };
+    Ok(value as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rfc4648_vectors() {
+        let vectors = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+
+        for (plain, encoded) in vectors {
+            assert_eq!(encode(plain.as_bytes()), encoded);
+            assert_eq!(decode(encoded).unwrap(), plain.as_bytes());
+        }
+    }
+
+    #[test]
+    fn decode_without_padding_and_whitespace() {
+        assert_eq!(decode("Zm9v\nYmE").unwrap(), b"fooba");
+    }
+
+    #[test]
+    fn decode_url_safe() {
+        assert_eq!(decode("-_8=").unwrap(), vec![0xFB, 0xFF]);
+    }
+
+    #[test]
+    fn decode_binary_roundtrip() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        assert_eq!(decode(&encode(&bytes)).unwrap(), bytes);
+    }
+
+    #[test]
+    fn decode_rejects_invalid() {
+        assert!(decode("Zm9v!").is_err());
+        assert!(decode("Zg==Zg").is_err());
+        assert!(decode("Z").is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use std::io::{self, Read};
+use std::io::{self, Cursor, Read};
 
 /// Reads individual bits from a byte-oriented [`Read`] source.
 ///
@@ -6,9 +6,10 @@ use std::io::{self, Read};
 /// the underlying reader on demand, so the reader is never read ahead of what
 /// is needed.
 ///
-/// The struct also implements [`Read`] for byte-level access, but only when
-/// the internal bit buffer is empty (i.e. [`is_byte_aligned`](Self::is_byte_aligned)
-/// returns `true`).
+/// The struct also implements [`Read`] for byte-level access, which
+/// continues from the current bit position: when the reader is not
+/// [byte aligned](Self::is_byte_aligned), the bytes are shifted through the
+/// partial bit buffer.
 #[derive(Debug)]
 pub struct BitReader<R: Read> {
     reader: R,
@@ -52,6 +53,44 @@ impl<R: Read> BitReader<R> {
         Ok(value)
     }
 
+    /// Reads a field of `count` bits, a whole number of bytes, stored least
+    /// significant byte first, as some non-standard feeds do.
+    ///
+    /// The field may start at any bit position.
+    pub fn read_bits_le(&mut self, count: usize) -> io::Result<u64> {
+        Ok(swap_bytes(self.read_bits(count)?, count))
+    }
+
+    /// Discards `count` bits without decoding them.
+    ///
+    /// Whole bytes are skipped in one pass over the underlying reader, so
+    /// skipping a large item costs no more than reading its bytes.
+    ///
+    /// Returns an I/O error if the underlying reader runs out of data before
+    /// `count` bits have been consumed.
+    pub fn skip_bits(&mut self, count: usize) -> io::Result<()> {
+        let buffered = count.min(self.bits_left as usize);
+        self.bits_left -= buffered as u8;
+        let count = count - buffered;
+
+        let bytes = (count / 8) as u64;
+        let skipped = io::copy(&mut (&mut self.reader).take(bytes), &mut io::sink())?;
+        if skipped < bytes {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        self.read_bits(count % 8)?;
+        Ok(())
+    }
+
+    /// Fills `buf` with the next bytes of the stream, at any bit alignment.
+    ///
+    /// Used for opaque fields such as RE/SP data or video payloads.  Returns
+    /// an I/O error if the underlying reader runs out of data first.
+    pub fn read_bytes(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        self.read_exact(buf)
+    }
+
     /// Reads a fixed-length string field from the stream.
     ///
     /// Reads `byte_len` bytes, interprets them as ASCII/UTF-8, and trims
@@ -59,31 +98,91 @@ impl<R: Read> BitReader<R> {
     /// such as callsigns and target identifications.
     pub fn read_string(&mut self, byte_len: usize) -> io::Result<String> {
         let mut bytes = vec![0u8; byte_len];
-        for byte in bytes.iter_mut() {
-            *byte = self.read_bits(8)? as u8;
-        }
+        self.read_bytes(&mut bytes)?;
         let s = String::from_utf8_lossy(&bytes);
-        Ok(s.trim_end_matches(|c: char| c == ' ' || c == '\0').to_string())
+        Ok(s.trim_end_matches([' ', '\0']).to_string())
     }
 
     /// Returns true if the reader is at a byte boundary (no partial byte buffered).
     pub fn is_byte_aligned(&self) -> bool {
         self.bits_left == 0
     }
+
+    /// Unwraps the underlying reader, discarding any buffered bits.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+/// Reverses the order of the bytes of a value of `count` bits, a whole
+/// number of bytes.
+pub(crate) fn swap_bytes(value: u64, count: usize) -> u64 {
+    debug_assert!(count.is_multiple_of(8) && count <= 64, "byte-swapped fields are whole bytes");
+    match count {
+        0 => value,
+        _ => value.swap_bytes() >> (64 - count),
+    }
+}
+
+/// Borrowing reads for a reader over a byte slice.
+///
+/// These return data pointing into the slice instead of copying it, so
+/// borrowed decoding performs no allocation.  The reader must be at a byte
+/// boundary.
+impl<'a> BitReader<&'a [u8]> {
+    /// Reads `byte_len` bytes as a slice of the input.
+    pub fn read_slice(&mut self, byte_len: usize) -> io::Result<&'a [u8]> {
+        if self.bits_left != 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "borrowed field is not byte aligned"));
+        }
+        if byte_len > self.reader.len() {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let (bytes, rest) = self.reader.split_at(byte_len);
+        self.reader = rest;
+        Ok(bytes)
+    }
+
+    /// Reads a fixed-length string field as a slice of the input.
+    ///
+    /// Like [`read_string`](Self::read_string), trailing spaces and null
+    /// bytes are trimmed.  Fails if the bytes are not valid UTF-8.
+    pub fn read_str(&mut self, byte_len: usize) -> io::Result<&'a str> {
+        let bytes = self.read_slice(byte_len)?;
+        let s = std::str::from_utf8(bytes)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(s.trim_end_matches([' ', '\0']))
+    }
+}
+
+impl<T: AsRef<[u8]>> BitReader<Cursor<T>> {
+    /// Returns the number of bits consumed from the start of the cursor.
+    pub fn bit_position(&self) -> u64 {
+        self.reader.position() * 8 - self.bits_left as u64
+    }
 }
 
 /// Implement Read for BitReader to allow byte-level operations.
-/// Note: This only works correctly when the reader is at a byte boundary.
+///
+/// At a byte boundary, reads pass straight through to the underlying
+/// reader.  Otherwise the bytes are shifted through the partial bit buffer,
+/// so byte reads always continue exactly where the bit reads stopped.
 impl<R: Read> Read for BitReader<R> {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        // If we have partial bits buffered, we need to handle them
-        // For now, assert byte alignment for simplicity
-        debug_assert!(
-            self.bits_left == 0,
-            "BitReader::read called with {} bits still buffered",
-            self.bits_left
-        );
-        self.reader.read(buf)
+        let n = self.reader.read(buf)?;
+        if self.bits_left == 0 {
+            return Ok(n);
+        }
+
+        // Each byte returned is the buffered bits followed by the high bits
+        // of the next input byte, whose low bits stay buffered.
+        let shift = self.bits_left;
+        for byte in &mut buf[..n] {
+            let next = *byte;
+            *byte = (self.buffer << (8 - shift)) | (next >> shift);
+            self.buffer = next;
+        }
+        Ok(n)
     }
 }
 
@@ -121,6 +220,18 @@ mod tests {
         assert_eq!(reader.read_bits(8).unwrap(), 0xCD);
     }
 
+    #[test]
+    fn read_little_endian_fields() {
+        let data = vec![0x34, 0x12, 0x56, 0x34, 0x12, 0xF3, 0x41, 0x20];
+        let mut reader = BitReader::new(Cursor::new(data));
+
+        assert_eq!(reader.read_bits_le(16).unwrap(), 0x1234);
+        assert_eq!(reader.read_bits_le(24).unwrap(), 0x123456);
+        // Not byte aligned: bytes 0x34 0x12 after 4 bits
+        assert_eq!(reader.read_bits(4).unwrap(), 0xF);
+        assert_eq!(reader.read_bits_le(16).unwrap(), 0x1234);
+    }
+
     #[test]
     fn read_across_byte_boundary() {
         // Read 12 bits from 0xAB 0xCD = 0b10101011 0b11001101
@@ -195,6 +306,41 @@ mod tests {
         assert_eq!(buf, [0xCD, 0xEF]);
     }
 
+    #[test]
+    fn read_trait_off_byte_boundary() {
+        let data = vec![0xAB, 0xCD, 0xEF];
+        let mut reader = BitReader::new(Cursor::new(data));
+
+        assert_eq!(reader.read_bits(4).unwrap(), 0xA);
+
+        // Bytes continue from the bit position
+        let mut buf = [0u8; 2];
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, [0xBC, 0xDE]);
+        assert_eq!(reader.read_bits(4).unwrap(), 0xF);
+
+        // The last 4 bits cannot form a byte
+        assert!(reader.read_exact(&mut [0u8; 1]).is_err());
+    }
+
+    #[test]
+    fn read_bytes_at_any_alignment() {
+        let data = vec![0x12, 0x34, 0x56, 0x78];
+        let mut reader = BitReader::new(Cursor::new(data));
+
+        let mut buf = [0u8; 1];
+        reader.read_bytes(&mut buf).unwrap();
+        assert_eq!(buf, [0x12]);
+
+        assert_eq!(reader.read_bits(4).unwrap(), 0x3);
+        let mut buf = [0u8; 2];
+        reader.read_bytes(&mut buf).unwrap();
+        assert_eq!(buf, [0x45, 0x67]);
+
+        let err = reader.read_bytes(&mut [0u8; 1]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
     #[test]
     fn read_string_basic() {
         // "ABC" as bytes, followed by spaces
@@ -223,6 +369,59 @@ mod tests {
         assert_eq!(s, "AB");
     }
 
+    #[test]
+    fn skip_bits_across_bytes() {
+        let data = vec![0xFF, 0x00, 0x00, 0x0A, 0xBC];
+        let mut reader = BitReader::new(Cursor::new(data));
+
+        assert_eq!(reader.read_bits(4).unwrap(), 0xF);
+        reader.skip_bits(4 + 16 + 4).unwrap();
+        assert_eq!(reader.read_bits(8).unwrap(), 0xAB);
+    }
+
+    #[test]
+    fn bit_position_counts_consumed_bits() {
+        let data = vec![0xFF, 0x00, 0x12];
+        let mut reader = BitReader::new(Cursor::new(data));
+
+        reader.read_bits(3).unwrap();
+        assert_eq!(reader.bit_position(), 3);
+        reader.skip_bits(13).unwrap();
+        assert_eq!(reader.bit_position(), 16);
+    }
+
+    #[test]
+    fn skip_bits_insufficient_data() {
+        let data = vec![0xFF, 0x00];
+        let mut reader = BitReader::new(Cursor::new(data));
+
+        assert!(reader.skip_bits(24).is_err());
+    }
+
+    #[test]
+    fn read_str_borrows_from_slice() {
+        let data = b"BAW1  \0\x7F".as_slice();
+        let mut reader = BitReader::new(data);
+
+        let s = reader.read_str(7).unwrap();
+        assert_eq!(s, "BAW1");
+        assert_eq!(s.as_ptr(), data.as_ptr());
+        assert_eq!(reader.read_bits(8).unwrap(), 0x7F);
+    }
+
+    #[test]
+    fn read_slice_requires_alignment_and_data() {
+        let data = [0xAB, 0xCD];
+        let mut reader = BitReader::new(data.as_slice());
+
+        assert!(reader.read_slice(3).is_err());
+        reader.read_bits(4).unwrap();
+        assert_eq!(reader.read_slice(1).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        reader.read_bits(4).unwrap();
+        assert_eq!(reader.read_slice(1).unwrap(), &[0xCD]);
+        assert!(reader.into_inner().is_empty());
+    }
+
     #[test]
     fn read_insufficient_data() {
         let data = vec![0xAB];
@@ -0,0 +1,225 @@
+use std::io::{self, Write};
+
+use crate::bit_writer::BitWrite;
+
+/// Writes individual bits directly into a caller-provided byte slice.
+///
+/// Unlike a [`BitWriter`](crate::BitWriter) over a `Vec<u8>`, bits are
+/// stored in place without going through [`Write`] or allocating, which
+/// suits encoding into fixed transmit buffers.  Writing past the end of the
+/// slice fails with [`io::ErrorKind::WriteZero`] and leaves the slice
+/// unchanged beyond the bits already written.
+///
+/// # Example
+///
+/// ```
+/// use rasterix_core::BitSliceWriter;
+///
+/// let mut buf = [0xFFu8; 4];
+/// let mut writer = BitSliceWriter::new(&mut buf);
+/// writer.write_bits(0xABC, 12).unwrap();
+/// assert_eq!(writer.bits_written(), 12);
+/// assert_eq!(writer.bytes_written(), 2);
+/// assert_eq!(&buf[..2], &[0xAB, 0xC0]);
+/// ```
+#[derive(Debug)]
+pub struct BitSliceWriter<'a> {
+    buf: &'a mut [u8],
+    bits: usize,
+}
+
+impl<'a> BitSliceWriter<'a> {
+    /// Starts writing at the beginning of `buf`.
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Self { buf, bits: 0 }
+    }
+
+    /// Writes the lowest `count` bits of `value`, MSB-first.
+    ///
+    /// The unwritten bits of a partial byte are kept zero, so the bytes
+    /// written so far are always complete.
+    pub fn write_bits(&mut self, value: u64, count: usize) -> io::Result<()> {
+        if count > self.remaining_bits() {
+            return Err(io::Error::new(io::ErrorKind::WriteZero, "slice is full"));
+        }
+
+        let mut left = count;
+        while left > 0 {
+            let offset = self.bits % 8;
+            let n = left.min(8 - offset);
+            let chunk = ((value >> (left - n)) & ((1u64 << n) - 1)) as u8;
+            let byte = &mut self.buf[self.bits / 8];
+            if offset == 0 {
+                *byte = 0;
+            }
+            *byte |= chunk << (8 - offset - n);
+            self.bits += n;
+            left -= n;
+        }
+        Ok(())
+    }
+
+    /// Writes `bytes` at any bit alignment.  Fails with
+    /// [`io::ErrorKind::WriteZero`] if they do not fit.
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> io::Result<()> {
+        if bytes.len() * 8 > self.remaining_bits() {
+            return Err(io::Error::new(io::ErrorKind::WriteZero, "slice is full"));
+        }
+        self.write_all(bytes)
+    }
+
+    /// Writes a fixed-length string field of `byte_len` bytes, truncated or
+    /// padded with spaces.
+    pub fn write_string(&mut self, s: &str, byte_len: usize) -> io::Result<()> {
+        BitWrite::write_string(self, s, byte_len)
+    }
+
+    /// Skips to the next byte boundary.  The skipped bits are already zero.
+    pub fn flush(&mut self) {
+        self.bits = self.bytes_written() * 8;
+    }
+
+    /// Returns the number of bits written.
+    pub fn bits_written(&self) -> usize {
+        self.bits
+    }
+
+    /// Returns the number of bytes written to, including a partial byte.
+    pub fn bytes_written(&self) -> usize {
+        self.bits.div_ceil(8)
+    }
+
+    /// Returns true if the writer is at a byte boundary.
+    pub fn is_byte_aligned(&self) -> bool {
+        self.bits.is_multiple_of(8)
+    }
+
+    fn remaining_bits(&self) -> usize {
+        self.buf.len() * 8 - self.bits
+    }
+}
+
+impl BitWrite for BitSliceWriter<'_> {
+    fn write_bits(&mut self, value: u64, count: usize) -> io::Result<()> {
+        BitSliceWriter::write_bits(self, value, count)
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> io::Result<()> {
+        BitSliceWriter::write_bytes(self, bytes)
+    }
+
+    fn is_byte_aligned(&self) -> bool {
+        BitSliceWriter::is_byte_aligned(self)
+    }
+}
+
+/// Byte-level writes, continuing from the current bit position.
+impl Write for BitSliceWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.remaining_bits() / 8);
+        if self.is_byte_aligned() {
+            let start = self.bits / 8;
+            self.buf[start..start + len].copy_from_slice(&buf[..len]);
+            self.bits += len * 8;
+        } else {
+            for &byte in &buf[..len] {
+                self.write_bits(byte as u64, 8)?;
+            }
+        }
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        BitSliceWriter::flush(self);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bits_are_packed_msb_first_across_bytes() {
+        let mut buf = [0xFFu8; 3];
+        let mut writer = BitSliceWriter::new(&mut buf);
+
+        writer.write_bits(0b101, 3).unwrap();
+        writer.write_bits(0x1234, 16).unwrap();
+        assert_eq!(writer.bits_written(), 19);
+        writer.flush();
+
+        assert_eq!(writer.bytes_written(), 3);
+        assert_eq!(buf, [0b1010_0010, 0b0100_0110, 0b1000_0000]);
+    }
+
+    #[test]
+    fn matches_bit_writer_output() {
+        let mut expected = Vec::new();
+        let mut buf = [0u8; 6];
+        {
+            let mut bit_writer = crate::BitWriter::new(&mut expected);
+            let mut slice_writer = BitSliceWriter::new(&mut buf);
+            for (value, count) in [(1, 1), (0x3F, 6), (0xABCDE, 20), (0, 3), (0x1FF, 9)] {
+                bit_writer.write_bits(value, count).unwrap();
+                slice_writer.write_bits(value, count).unwrap();
+            }
+            bit_writer.flush().unwrap();
+        }
+        assert_eq!(&buf[..expected.len()], &expected[..]);
+    }
+
+    #[test]
+    fn writing_past_the_end_fails() {
+        let mut buf = [0u8; 1];
+        let mut writer = BitSliceWriter::new(&mut buf);
+
+        writer.write_bits(0x7F, 7).unwrap();
+        let err = writer.write_bits(0b11, 2).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+        assert_eq!(writer.bits_written(), 7);
+
+        writer.flush();
+        assert!(writer.write_all(&[1]).is_err());
+    }
+
+    #[test]
+    fn byte_writes_follow_bits() {
+        let mut buf = [0u8; 3];
+        let mut writer = BitSliceWriter::new(&mut buf);
+
+        writer.write_bits(0xA, 4).unwrap();
+        Write::flush(&mut writer).unwrap();
+        writer.write_all(&[0x12, 0x34]).unwrap();
+
+        assert_eq!(writer.bytes_written(), 3);
+        assert_eq!(buf, [0xA0, 0x12, 0x34]);
+    }
+
+    #[test]
+    fn byte_writes_off_byte_boundary() {
+        let mut buf = [0u8; 2];
+        let mut writer = BitSliceWriter::new(&mut buf);
+
+        writer.write_bits(0xA, 4).unwrap();
+        assert_eq!(writer.write(&[0xBC, 0xDE]).unwrap(), 1);
+        assert_eq!(writer.bits_written(), 12);
+
+        writer.flush();
+        assert_eq!(buf, [0xAB, 0xC0]);
+    }
+
+    #[test]
+    fn write_bytes_fails_without_partial_write() {
+        let mut buf = [0u8; 2];
+        let mut writer = BitSliceWriter::new(&mut buf);
+
+        writer.write_bits(0x1, 4).unwrap();
+        assert!(writer.write_bytes(&[0xFF, 0xFF]).is_err());
+        assert_eq!(writer.bits_written(), 4);
+        writer.write_bytes(&[0x23]).unwrap();
+
+        writer.flush();
+        assert_eq!(buf, [0x12, 0x30]);
+    }
+}
@@ -1,5 +1,56 @@
 use std::io::{self, Write};
 
+use crate::bit_reader::swap_bytes;
+
+/// Destination of bit-level writes.
+///
+/// [`Encode`](crate::Encode) implementations write through this trait, so a
+/// value can be encoded into any [`Write`] sink with a [`BitWriter`] or
+/// directly into a caller-provided slice with a
+/// [`BitSliceWriter`](crate::BitSliceWriter).  Byte-level writes through
+/// [`Write`] continue from the current bit position, and [`Write::flush`]
+/// pads a partial byte with zero bits.
+pub trait BitWrite: Write {
+    /// Writes the lowest `count` bits of `value`, MSB-first.
+    fn write_bits(&mut self, value: u64, count: usize) -> io::Result<()>;
+
+    /// Writes the lowest `count` bits of `value`, a whole number of bytes,
+    /// least significant byte first, as some non-standard feeds do.
+    fn write_bits_le(&mut self, value: u64, count: usize) -> io::Result<()> {
+        self.write_bits(swap_bytes(value, count), count)
+    }
+
+    /// Writes `bytes` at any bit alignment.
+    fn write_bytes(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.write_all(bytes)
+    }
+
+    /// Writes `bytes` as a fixed-length byte field of `byte_len` bytes,
+    /// truncated or padded with zeros.
+    fn write_fixed_bytes(&mut self, bytes: &[u8], byte_len: usize) -> io::Result<()> {
+        let len = bytes.len().min(byte_len);
+        self.write_bytes(&bytes[..len])?;
+        for _ in len..byte_len {
+            self.write_bits(0, 8)?;
+        }
+        Ok(())
+    }
+
+    /// Writes `s` as a fixed-length string field of `byte_len` bytes,
+    /// truncated or padded with spaces.
+    fn write_string(&mut self, s: &str, byte_len: usize) -> io::Result<()> {
+        let bytes = s.as_bytes();
+        for i in 0..byte_len {
+            let byte = if i < bytes.len() { bytes[i] } else { b' ' };
+            self.write_bits(byte as u64, 8)?;
+        }
+        Ok(())
+    }
+
+    /// Returns true if no partial byte is pending.
+    fn is_byte_aligned(&self) -> bool;
+}
+
 /// Writes individual bits to a byte-oriented [`Write`] sink.
 ///
 /// Bits are accumulated MSB-first into an internal byte buffer and flushed to
@@ -7,9 +58,10 @@ use std::io::{self, Write};
 /// [`flush`](Self::flush) after the last write to emit any remaining partial
 /// byte (padded with zero bits on the right).
 ///
-/// The struct also implements [`Write`] for byte-level access, but only when
-/// the internal bit buffer is empty (i.e. [`is_byte_aligned`](Self::is_byte_aligned)
-/// returns `true`).
+/// The struct also implements [`Write`] for byte-level access, which
+/// continues from the current bit position: when the writer is not
+/// [byte aligned](Self::is_byte_aligned), the bytes are shifted through the
+/// partial bit buffer.
 #[derive(Debug)]
 pub struct BitWriter<W: Write> {
     writer: W,
@@ -60,18 +112,21 @@ impl<W: Write> BitWriter<W> {
         Ok(())
     }
 
+    /// Writes `bytes` at any bit alignment.
+    ///
+    /// Used for opaque fields such as RE/SP data or video payloads; at a
+    /// byte boundary the bytes are passed to the underlying writer at once.
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.write_all(bytes)
+    }
+
     /// Writes a fixed-length string field to the stream.
     ///
     /// Writes exactly `byte_len` bytes: the bytes of `s` followed by space
     /// padding if `s` is shorter than `byte_len`. If `s` is longer, it is
     /// truncated. This is used for ASTERIX string fields such as callsigns.
     pub fn write_string(&mut self, s: &str, byte_len: usize) -> io::Result<()> {
-        let bytes = s.as_bytes();
-        for i in 0..byte_len {
-            let byte = if i < bytes.len() { bytes[i] } else { b' ' };
-            self.write_bits(byte as u64, 8)?;
-        }
-        Ok(())
+        BitWrite::write_string(self, s, byte_len)
     }
 
     /// Returns true if the writer is at a byte boundary (no partial byte buffered).
@@ -81,15 +136,18 @@ impl<W: Write> BitWriter<W> {
 }
 
 /// Implement Write for BitWriter to allow byte-level operations.
-/// Note: This only works correctly when the writer is at a byte boundary.
+///
+/// At a byte boundary, writes pass straight through to the underlying
+/// writer.  Otherwise each byte is written as 8 bits after the buffered ones.
 impl<W: Write> Write for BitWriter<W> {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        debug_assert!(
-            self.bits_filled == 0,
-            "BitWriter::write called with {} bits buffered",
-            self.bits_filled
-        );
-        self.writer.write(buf)
+        if self.bits_filled == 0 {
+            return self.writer.write(buf);
+        }
+        for &byte in buf {
+            self.write_bits(byte as u64, 8)?;
+        }
+        Ok(buf.len())
     }
 
     fn flush(&mut self) -> io::Result<()> {
@@ -99,6 +157,16 @@ impl<W: Write> Write for BitWriter<W> {
     }
 }
 
+impl<W: Write> BitWrite for BitWriter<W> {
+    fn write_bits(&mut self, value: u64, count: usize) -> io::Result<()> {
+        BitWriter::write_bits(self, value, count)
+    }
+
+    fn is_byte_aligned(&self) -> bool {
+        BitWriter::is_byte_aligned(self)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -245,6 +313,45 @@ mod tests {
         assert_eq!(buffer, vec![0xAB, 0xCD, 0xEF]);
     }
 
+    #[test]
+    fn write_trait_off_byte_boundary() {
+        let mut buffer = Vec::new();
+        let mut writer = BitWriter::new(&mut buffer);
+
+        writer.write_bits(0xA, 4).unwrap();
+
+        // Bytes continue from the bit position
+        writer.write_all(&[0xBC, 0xDE]).unwrap();
+        writer.write_bits(0xF, 4).unwrap();
+
+        assert_eq!(buffer, vec![0xAB, 0xCD, 0xEF]);
+    }
+
+    #[test]
+    fn write_bytes_at_any_alignment() {
+        let mut buffer = Vec::new();
+        let mut writer = BitWriter::new(&mut buffer);
+
+        writer.write_bytes(&[0x12]).unwrap();
+        writer.write_bits(0x3, 4).unwrap();
+        writer.write_bytes(&[0x45, 0x67]).unwrap();
+        writer.flush().unwrap();
+
+        assert_eq!(buffer, vec![0x12, 0x34, 0x56, 0x70]);
+    }
+
+    #[test]
+    fn write_fixed_bytes_truncated_or_padded() {
+        let mut buffer = Vec::new();
+        let mut writer = BitWriter::new(&mut buffer);
+
+        writer.write_fixed_bytes(&[0x12, 0x34, 0x56], 2).unwrap();
+        writer.write_fixed_bytes(&[0x78], 3).unwrap();
+        writer.flush().unwrap();
+
+        assert_eq!(buffer, vec![0x12, 0x34, 0x78, 0x00, 0x00]);
+    }
+
     #[test]
     fn write_multiple_sizes() {
         let mut buffer = Vec::new();
@@ -306,6 +413,19 @@ mod tests {
         assert_eq!(buffer, vec![0x41, 0x42, 0x43]);
     }
 
+    #[test]
+    fn write_little_endian_fields() {
+        let mut buffer = Vec::new();
+        let mut writer = BitWriter::new(&mut buffer);
+
+        writer.write_bits_le(0x1234, 16).unwrap();
+        writer.write_bits_le(0x123456, 24).unwrap();
+        writer.write_bits(0xF, 4).unwrap();
+        writer.write_bits_le(0x1234, 16).unwrap();
+        writer.write_bits(0, 4).unwrap();
+        assert_eq!(buffer, vec![0x34, 0x12, 0x56, 0x34, 0x12, 0xF3, 0x41, 0x20]);
+    }
+
     #[test]
     fn round_trip_string() {
         use crate::bit_reader::BitReader;
@@ -0,0 +1,161 @@
+//! Structural checks of records before they are encoded.
+//!
+//! Encoding writes whatever a record holds: a value wider than its field is
+//! cut to its bits, a part after an absent one breaks the FX chain, a
+//! mandatory item may be missing.  The generated [`Conformance`] impls find
+//! these without encoding, so records produced by other systems can be
+//! audited:
+//!
+//! ```ignore
+//! for issue in record.check_conformance() {
+//!     eprintln!("{}", issue); // item020.part2: present after an absent part
+//! }
+//! ```
+//!
+//! The field constraints checked by [`Validate`] are reported as issues
+//! too.
+
+use std::fmt;
+
+use crate::validate::{Constraint, Validate, ValidationError};
+
+/// A rule of the definition a record breaks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    /// A mandatory item is absent.
+    MissingItem,
+    /// A numeric value is wider than its field.
+    FieldOverflow { value: u64, bits: usize },
+    /// An enum holds a value without a variant.
+    UnknownEnumValue { value: u8 },
+    /// A string or byte array doesn't have the length of its field.
+    FieldLength { len: usize, byte_len: usize },
+    /// A value violates a constraint of its field.
+    Constraint { value: u64, constraint: Constraint },
+    /// An extended part, or the extensions, are present after an absent
+    /// part, so no FX bit announces them.
+    FxGap,
+    /// A repetitive item doesn't have its number of repetitions.
+    RepetitionCount { count: usize, expected: usize },
+}
+
+/// A value or item of a record breaking a rule of its definition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConformanceIssue {
+    /// Dotted path of the item, part or field, e.g. `item040.part0.gsp`.
+    pub path: String,
+    pub rule: Rule,
+}
+
+impl fmt::Display for ConformanceIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = &self.path;
+        match self.rule {
+            Rule::MissingItem => write!(f, "{}: mandatory item is absent", path),
+            Rule::FieldOverflow { value, bits } => write!(f, "{}: {} does not fit in {} bits", path, value, bits),
+            Rule::UnknownEnumValue { value } => write!(f, "{}: {} is not a variant of the enum", path, value),
+            Rule::FieldLength { len, byte_len } => write!(f, "{}: {} bytes, not {}", path, len, byte_len),
+            Rule::Constraint { value, constraint } => {
+                let error = ValidationError { field: path.clone(), value, constraint };
+                write!(f, "{}", error)
+            }
+            Rule::FxGap => write!(f, "{}: present after an absent part", path),
+            Rule::RepetitionCount { count, expected } => {
+                write!(f, "{}: {} repetitions instead of {}", path, count, expected)
+            }
+        }
+    }
+}
+
+impl From<ValidationError> for ConformanceIssue {
+    fn from(error: ValidationError) -> Self {
+        Self {
+            path: error.field,
+            rule: Rule::Constraint { value: error.value, constraint: error.constraint },
+        }
+    }
+}
+
+/// Checks of a generated item or record against the rules of its
+/// definition.
+///
+/// Every generated item and record implements `Conformance`; the checks of
+/// an item don't include the constraints of its fields, which the record
+/// gets from [`Validate`].
+pub trait Conformance {
+    /// Appends an issue for each broken rule to `issues`, naming values by
+    /// their path after `path`, e.g. `"item040."`.
+    fn audit(&self, path: &str, issues: &mut Vec<ConformanceIssue>) {
+        let _ = (path, issues);
+    }
+
+    /// Checks every rule, returning all the issues found.
+    fn check_conformance(&self) -> Vec<ConformanceIssue> {
+        let mut issues = Vec::new();
+        self.audit("", &mut issues);
+        issues
+    }
+}
+
+/// Appends the issues of `item`, including the constraints of its fields,
+/// naming them after `path`.  Called by generated code.
+pub fn audit_item<T: Conformance + Validate>(item: &T, path: &str, issues: &mut Vec<ConformanceIssue>) {
+    item.audit(path, issues);
+    let mut errors = Vec::new();
+    item.check(path, &mut errors);
+    issues.extend(errors.into_iter().map(ConformanceIssue::from));
+}
+
+/// Appends an issue to `issues` for `rule`, broken by `name` after `path`.
+/// Called by generated code.
+pub fn report(issues: &mut Vec<ConformanceIssue>, path: &str, name: &str, rule: Rule) {
+    issues.push(ConformanceIssue { path: format!("{}{}", path, name), rule });
+}
+
+/// Appends an issue to `issues` if `value`, the value of `field`, doesn't
+/// fit in `bits` bits.  Called by generated code.
+pub fn check_bits(issues: &mut Vec<ConformanceIssue>, path: &str, field: &str, value: u64, bits: usize) {
+    if bits < 64 && value >> bits != 0 {
+        report(issues, path, field, Rule::FieldOverflow { value, bits });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validate::check_field;
+
+    struct Item(u64);
+
+    impl Conformance for Item {
+        fn audit(&self, path: &str, issues: &mut Vec<ConformanceIssue>) {
+            check_bits(issues, path, "a", self.0, 4);
+        }
+    }
+
+    impl Validate for Item {
+        fn check(&self, path: &str, errors: &mut Vec<ValidationError>) {
+            check_field(errors, path, "a", self.0, &[Constraint::Max(10)]);
+        }
+    }
+
+    #[test]
+    fn test_audit_item_reports_rules_and_constraints() {
+        let mut issues = Vec::new();
+        audit_item(&Item(7), "item010.", &mut issues);
+        assert!(issues.is_empty());
+
+        audit_item(&Item(16), "item010.", &mut issues);
+        let messages: Vec<String> = issues.iter().map(ToString::to_string).collect();
+        assert_eq!(messages, ["item010.a: 16 does not fit in 4 bits", "item010.a: 16 is above the maximum 10"]);
+    }
+
+    #[test]
+    fn test_check_conformance_collects_issues() {
+        assert_eq!(Item(3).check_conformance(), []);
+        assert_eq!(
+            Item(32).check_conformance(),
+            [ConformanceIssue { path: "a".to_string(), rule: Rule::FieldOverflow { value: 32, bits: 4 } }]
+        );
+    }
+}
@@ -0,0 +1,254 @@
+use std::io::{self, Read};
+
+use crate::{BitReader, DecodeError, Fspec};
+
+/// Reusable scratch state for decoding many records in a row.
+///
+/// Generated `decode_into` implementations take a `DecodeContext` so that
+/// temporary buffers (string bytes, data block payloads, FSPECs) are allocated once
+/// and reused for every subsequent record instead of being freshly allocated
+/// per decode.
+///
+/// A context carries no decoding state between calls: it can be shared by
+/// records of different categories and reused after a decode error.
+///
+/// # Example
+///
+/// ```
+/// use rasterix_core::{BitReader, DecodeContext};
+/// use std::io::Cursor;
+///
+/// let mut ctx = DecodeContext::new();
+/// let mut callsign = String::new();
+///
+/// let mut reader = BitReader::new(Cursor::new(b"ABC  "));
+/// ctx.read_string_into(&mut reader, 5, &mut callsign).unwrap();
+/// assert_eq!(callsign, "ABC");
+/// ```
+#[derive(Debug, Default)]
+pub struct DecodeContext {
+    scratch: Vec<u8>,
+    buffer: Vec<u8>,
+    /// One FSPEC per nesting level: a record's, then a compound item's.
+    fspecs: Vec<Fspec>,
+}
+
+impl DecodeContext {
+    /// Creates a new context with empty scratch buffers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new context whose buffers can hold `capacity` bytes without
+    /// reallocating.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            scratch: Vec::with_capacity(capacity),
+            buffer: Vec::with_capacity(capacity),
+            fspecs: Vec::new(),
+        }
+    }
+
+    /// Reads `byte_len` bytes into the scratch buffer and returns them.
+    ///
+    /// The returned slice is only valid until the next call that uses the
+    /// scratch buffer.  Works regardless of the reader's bit alignment.
+    pub fn read_bytes<R: Read>(
+        &mut self,
+        reader: &mut BitReader<R>,
+        byte_len: usize,
+    ) -> io::Result<&[u8]> {
+        self.scratch.clear();
+        for _ in 0..byte_len {
+            self.scratch.push(reader.read_bits(8)? as u8);
+        }
+        Ok(&self.scratch)
+    }
+
+    /// Reads a fixed-length string field into `out`, reusing its allocation.
+    ///
+    /// Behaves like [`BitReader::read_string`]: the bytes are interpreted as
+    /// UTF-8 (lossily) and trailing spaces and null bytes are trimmed.
+    pub fn read_string_into<R: Read>(
+        &mut self,
+        reader: &mut BitReader<R>,
+        byte_len: usize,
+        out: &mut String,
+    ) -> io::Result<()> {
+        let bytes = self.read_bytes(reader, byte_len)?;
+        let s = String::from_utf8_lossy(bytes);
+        out.clear();
+        out.push_str(s.trim_end_matches([' ', '\0']));
+        Ok(())
+    }
+
+    /// Lends the context's block buffer to `f`, returning it afterwards.
+    ///
+    /// The buffer keeps its capacity from previous calls, which makes it
+    /// suitable for holding a data block payload while the records inside it
+    /// are decoded with the same context.  The buffer is returned to the
+    /// context even when `f` fails.
+    pub fn with_buffer<T, E>(
+        &mut self,
+        f: impl FnOnce(&mut Self, &mut Vec<u8>) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let mut buffer = std::mem::take(&mut self.buffer);
+        buffer.clear();
+        let result = f(self, &mut buffer);
+        self.buffer = buffer;
+        result
+    }
+
+    /// Reads an FSPEC from `reader` and lends it to `f`, along with the
+    /// reader, returning it afterwards.
+    ///
+    /// The FSPEC's bytes keep their allocation from previous calls at the
+    /// same nesting depth, so records and the compound items inside them
+    /// are decoded without allocating an FSPEC each.  The FSPEC is returned
+    /// to the context even when reading it or `f` fails.
+    pub fn with_fspec<R: Read, T>(
+        &mut self,
+        reader: &mut R,
+        f: impl FnOnce(&mut Self, &mut R, &Fspec) -> Result<T, DecodeError>,
+    ) -> Result<T, DecodeError> {
+        let mut fspec = self.fspecs.pop().unwrap_or_default();
+        let result = fspec.read_into(reader).and_then(|()| f(self, reader, &fspec));
+        self.fspecs.push(fspec);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn read_bytes_returns_requested_bytes() {
+        let data = vec![0x01, 0x02, 0x03];
+        let mut reader = BitReader::new(Cursor::new(data));
+        let mut ctx = DecodeContext::new();
+
+        assert_eq!(ctx.read_bytes(&mut reader, 2).unwrap(), &[0x01, 0x02]);
+        assert_eq!(ctx.read_bytes(&mut reader, 1).unwrap(), &[0x03]);
+    }
+
+    #[test]
+    fn read_bytes_unaligned() {
+        // 0xF0 0x0F read after a 4-bit offset yields 0x00
+        let data = vec![0xF0, 0x0F];
+        let mut reader = BitReader::new(Cursor::new(data));
+        let mut ctx = DecodeContext::new();
+
+        reader.read_bits(4).unwrap();
+        assert_eq!(ctx.read_bytes(&mut reader, 1).unwrap(), &[0x00]);
+    }
+
+    #[test]
+    fn read_string_into_trims_padding() {
+        let data = vec![0x41, 0x42, 0x00, 0x20];
+        let mut reader = BitReader::new(Cursor::new(data));
+        let mut ctx = DecodeContext::new();
+        let mut out = String::from("previous");
+
+        ctx.read_string_into(&mut reader, 4, &mut out).unwrap();
+        assert_eq!(out, "AB");
+    }
+
+    #[test]
+    fn read_string_into_reuses_allocation() {
+        let data = b"ABCDEFGHABCD    ".to_vec();
+        let mut reader = BitReader::new(Cursor::new(data));
+        let mut ctx = DecodeContext::new();
+        let mut out = String::new();
+
+        ctx.read_string_into(&mut reader, 8, &mut out).unwrap();
+        let ptr = out.as_ptr();
+        ctx.read_string_into(&mut reader, 8, &mut out).unwrap();
+
+        assert_eq!(out, "ABCD");
+        assert_eq!(out.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn with_buffer_keeps_capacity() {
+        let mut ctx = DecodeContext::new();
+
+        ctx.with_buffer(|_, buf| {
+            buf.extend_from_slice(&[0u8; 64]);
+            Ok::<_, ()>(())
+        })
+        .unwrap();
+
+        ctx.with_buffer(|_, buf| {
+            assert!(buf.is_empty());
+            assert!(buf.capacity() >= 64);
+            Ok::<_, ()>(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn with_fspec_reads_fspec() {
+        let mut reader = Cursor::new(vec![0x81, 0x40, 0xAA]);
+        let mut ctx = DecodeContext::new();
+
+        let next = ctx
+            .with_fspec(&mut reader, |_, reader, fspec| {
+                assert!(fspec.is_frn_set(1));
+                assert!(fspec.is_frn_set(9));
+                assert_eq!(fspec.as_bytes(), &[0x81, 0x40]);
+                let mut b = [0u8];
+                reader.read_exact(&mut b)?;
+                Ok(b[0])
+            })
+            .unwrap();
+        assert_eq!(next, 0xAA);
+    }
+
+    #[test]
+    fn with_fspec_reuses_fspecs_per_depth() {
+        let mut reader = Cursor::new(vec![0x81, 0x40, 0x80, 0x81, 0x40, 0x80]);
+        let mut ctx = DecodeContext::new();
+        let mut nested = |ctx: &mut DecodeContext| {
+            ctx.with_fspec(&mut reader, |ctx, reader, outer| {
+                let inner = ctx.with_fspec(reader, |_, _, inner| Ok(inner.as_bytes().as_ptr()))?;
+                Ok((outer.as_bytes().as_ptr(), inner))
+            })
+            .unwrap()
+        };
+
+        let first = nested(&mut ctx);
+        let second = nested(&mut ctx);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn with_fspec_returns_fspec_on_error() {
+        let mut ctx = DecodeContext::new();
+
+        let result: Result<(), _> = ctx.with_fspec(&mut Cursor::new(vec![0x80]), |_, _, _| {
+            Err(DecodeError::InvalidData("failed"))
+        });
+        assert!(result.is_err());
+        assert!(ctx.with_fspec(&mut Cursor::new(vec![0x01]), |_, _, _| Ok(())).is_err());
+        assert_eq!(ctx.fspecs.len(), 1);
+    }
+
+    #[test]
+    fn with_buffer_returns_buffer_on_error() {
+        let mut ctx = DecodeContext::new();
+
+        let result: Result<(), &str> = ctx.with_buffer(|_, buf| {
+            buf.extend_from_slice(&[0u8; 32]);
+            Err("failed")
+        });
+        assert!(result.is_err());
+
+        ctx.with_buffer(|_, buf| {
+            assert!(buf.capacity() >= 32);
+            Ok::<_, ()>(())
+        })
+        .unwrap();
+    }
+}
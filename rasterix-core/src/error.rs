@@ -9,6 +9,19 @@ use std::fmt;
 /// - [`InvalidData`](Self::InvalidData) -- represents a logical data-format
 ///   error such as an unexpected value, a missing field, or a constraint
 ///   violation.
+/// - [`LimitExceeded`](Self::LimitExceeded) -- a length read from the input
+///   or computed while encoding is larger than allowed, either by the format
+///   or by the [`DecodePolicy`](crate::DecodePolicy) in effect.
+/// - [`RecordOverrun`](Self::RecordOverrun) -- the record starting at byte
+///   `offset` of a data block runs past the block's `block_len` bytes.
+/// - [`RecordUnderrun`](Self::RecordUnderrun) -- the records of a data block
+///   end at byte `offset`, and the rest of its `block_len` bytes holds no
+///   record.
+///
+/// Record offsets count from the first byte of the data block, CAT included.
+///
+/// New variants may be added as decoding grows more checks, so matches on
+/// this type need a wildcard arm.
 ///
 /// # Example
 ///
@@ -23,9 +36,27 @@ use std::fmt;
 /// assert!(matches!(data_err, DecodeError::InvalidData(_)));
 /// ```
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum DecodeError {
     Io(std::io::Error),
     InvalidData(&'static str),
+    LimitExceeded { limit: &'static str, max: usize },
+    RecordOverrun { offset: usize, block_len: usize },
+    RecordUnderrun { offset: usize, block_len: usize },
+}
+
+impl DecodeError {
+    /// Places an error decoding the record at byte `offset` of a data block
+    /// of `block_len` bytes: running out of input means the record overruns
+    /// the block.  Called by generated code.
+    pub fn in_record(self, offset: usize, block_len: usize) -> Self {
+        match self {
+            DecodeError::Io(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                DecodeError::RecordOverrun { offset, block_len }
+            }
+            other => other,
+        }
+    }
 }
 
 impl From<std::io::Error> for DecodeError {
@@ -39,6 +70,13 @@ impl fmt::Display for DecodeError {
         match self {
             DecodeError::Io(e) => write!(f, "IO error: {}", e),
             DecodeError::InvalidData(msg) => write!(f, "Invalid data: {}", msg),
+            DecodeError::LimitExceeded { limit, max } => write!(f, "{} exceeds {}", limit, max),
+            DecodeError::RecordOverrun { offset, block_len } => {
+                write!(f, "record at byte {} overruns the data block of {} bytes", offset, block_len)
+            }
+            DecodeError::RecordUnderrun { offset, block_len } => {
+                write!(f, "records end at byte {} of the data block of {} bytes", offset, block_len)
+            }
         }
     }
 }
@@ -1,5 +1,7 @@
 use std::io::{self, Read, Write};
 
+use crate::DecodeError;
+
 /// ASTERIX Field Specification (FSPEC) bitmap.
 ///
 /// An FSPEC is a variable-length sequence of bytes where each byte's LSB (the
@@ -8,18 +10,24 @@ use std::io::{self, Read, Write};
 ///
 /// This struct manages the FX bits automatically: when you [`set`](Self::set) a
 /// bit in a later byte, all preceding FX bits are enabled so the FSPEC
-/// serialises correctly.
+/// serialises correctly, and [`unset`](Self::unset) drops the bytes left
+/// without items.  FSPECs compare equal when they flag the same items,
+/// whatever their length and FX bits.
 ///
 /// ## Bit numbering
 ///
-/// Within each FSPEC byte the bits are numbered 0 (MSB) through 7 (LSB):
+/// Items are best addressed by their field reference number (FRN), counted
+/// from 1 as in the specifications, with [`set_frn`](Self::set_frn) and
+/// [`is_frn_set`](Self::is_frn_set), which skip the FX bits.  The `(byte,
+/// bit)` methods number the bits within each FSPEC byte 0 (MSB) through 7
+/// (LSB):
 ///
 /// ```text
 /// Bit:  0   1   2   3   4   5   6   7
 ///       ^                           ^
 ///       MSB (first data item)       FX (extension indicator)
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Eq)]
 pub struct Fspec {
     bytes: Vec<u8>,
 }
@@ -35,13 +43,27 @@ impl Fspec {
     /// Reads an FSPEC from a reader.
     ///
     /// Bytes are consumed until one with FX = 0 (no extension) is encountered.
-    pub fn read<R: Read>(reader: &mut R) -> io::Result<Self> {
-        let mut bytes = Vec::new();
+    /// An FSPEC longer than the [policy](crate::policy::max_fspec_len)
+    /// allows fails with [`DecodeError::LimitExceeded`].
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, DecodeError> {
+        let mut fspec = Self { bytes: Vec::new() };
+        fspec.read_into(reader)?;
+        Ok(fspec)
+    }
+
+    /// Reads an FSPEC like [`read`](Self::read), over the bytes of `self`,
+    /// reusing their allocation.
+    pub(crate) fn read_into<R: Read>(&mut self, reader: &mut R) -> Result<(), DecodeError> {
+        let max = crate::policy::max_fspec_len();
+        self.bytes.clear();
 
         loop {
+            if self.bytes.len() == max {
+                return Err(DecodeError::LimitExceeded { limit: "FSPEC length", max });
+            }
             let mut b = [0u8];
             reader.read_exact(&mut b)?;
-            bytes.push(b[0]);
+            self.bytes.push(b[0]);
 
             // FX bit (LSB)
             if b[0] & 0x01 == 0 {
@@ -49,7 +71,35 @@ impl Fspec {
             }
         }
 
-        Ok(Self { bytes })
+        Ok(())
+    }
+
+    /// Reads an FSPEC and checks that it flags exactly the items of
+    /// `profile`, without allocating.
+    ///
+    /// Bit `n` of `profile` stands for FSPEC position `n`, that is bit
+    /// `n % 7` of byte `n / 7`.  The FSPEC must have the minimal length for
+    /// the profile, as written by [`set`](Self::set).
+    pub fn read_profile<R: Read>(reader: &mut R, profile: u64) -> Result<(), DecodeError> {
+        let len = (64 - profile.leading_zeros() as usize).div_ceil(7).max(1);
+        for byte in 0..len {
+            let mut expected = 0u8;
+            for bit in 0..7 {
+                if profile & (1 << (byte * 7 + bit)) != 0 {
+                    expected |= 1 << (7 - bit);
+                }
+            }
+            if byte + 1 < len {
+                expected |= 0x01;
+            }
+
+            let mut b = [0u8];
+            reader.read_exact(&mut b)?;
+            if b[0] != expected {
+                return Err(DecodeError::InvalidData("FSPEC does not match profile"));
+            }
+        }
+        Ok(())
     }
 
     /// Writes all FSPEC bytes (including FX bits) to a writer.
@@ -69,6 +119,8 @@ impl Fspec {
 
     /// Sets a bit in the FSPEC at the given byte and bit position.
     /// Also sets FX bits (bit 0) on all preceding bytes to indicate continuation.
+    ///
+    /// Setting a bit that is already set changes nothing.
     pub fn set(&mut self, byte: usize, bit: u8) {
         // Expand bytes vector if needed
         while self.bytes.len() <= byte {
@@ -81,6 +133,106 @@ impl Fspec {
             self.bytes[i] |= 0x01; // Set FX bit (LSB)
         }
     }
+
+    /// Flags the item with field reference number `frn`, counted from 1:
+    /// FRNs 1 to 7 are the item bits of the first byte, 8 to 14 of the
+    /// second, and so on.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `frn` is 0.
+    pub fn set_frn(&mut self, frn: usize) {
+        let (byte, bit) = frn_position(frn);
+        self.set(byte, bit);
+    }
+
+    /// Returns `true` if the item with field reference number `frn` is
+    /// flagged, see [`set_frn`](Self::set_frn).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `frn` is 0.
+    pub fn is_frn_set(&self, frn: usize) -> bool {
+        let (byte, bit) = frn_position(frn);
+        self.is_set(byte, bit)
+    }
+
+    /// Clears the item with field reference number `frn`, then
+    /// [normalizes](Self::normalize) the FSPEC.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `frn` is 0.
+    pub fn unset_frn(&mut self, frn: usize) {
+        let (byte, bit) = frn_position(frn);
+        self.unset(byte, bit);
+    }
+
+    /// Clears a bit in the FSPEC at the given byte and bit position, then
+    /// [normalizes](Self::normalize) the FSPEC.
+    pub fn unset(&mut self, byte: usize, bit: u8) {
+        if let Some(b) = self.bytes.get_mut(byte) {
+            *b &= !(1 << (7 - bit));
+        }
+        self.normalize();
+    }
+
+    /// Makes the FSPEC canonical: drops the trailing bytes flagging no item,
+    /// keeping at least one byte, and sets the FX bit of every byte but the
+    /// last, whose FX bit is cleared.
+    pub fn normalize(&mut self) {
+        let len = self.bytes.iter().rposition(|b| b & 0xFE != 0).map_or(1, |i| i + 1);
+        self.bytes.resize(len, 0);
+        for b in &mut self.bytes[..len - 1] {
+            *b |= 0x01;
+        }
+        self.bytes[len - 1] &= 0xFE;
+    }
+
+    /// Returns `true` if the FSPEC is as [`normalize`](Self::normalize)
+    /// leaves it, the only form [`read_profile`](Self::read_profile)
+    /// accepts.
+    pub fn is_canonical(&self) -> bool {
+        let mut canonical = self.clone();
+        canonical.normalize();
+        canonical.bytes == self.bytes
+    }
+
+    /// Returns `true` if both FSPECs flag the same items, ignoring FX bits
+    /// and trailing bytes without items.
+    pub fn same_items(&self, other: &Fspec) -> bool {
+        let len = self.bytes.len().max(other.bytes.len());
+        (0..len).all(|i| {
+            let items = |fspec: &Fspec| fspec.bytes.get(i).map_or(0, |b| b & 0xFE);
+            items(self) == items(other)
+        })
+    }
+
+    /// Returns the FSPEC bytes, FX bits included, as [`write`](Self::write)
+    /// writes them.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+/// Returns the byte and bit of an FRN, skipping the FX bit of each byte.
+fn frn_position(frn: usize) -> (usize, u8) {
+    assert!(frn >= 1, "FRNs start at 1");
+    ((frn - 1) / 7, ((frn - 1) % 7) as u8)
+}
+
+/// FSPECs are equal when they flag the same items, see
+/// [`same_items`](Fspec::same_items).
+impl PartialEq for Fspec {
+    fn eq(&self, other: &Self) -> bool {
+        self.same_items(other)
+    }
+}
+
+impl Default for Fspec {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[cfg(test)]
@@ -88,6 +240,21 @@ mod tests {
     use super::*;
     use std::io::Cursor;
 
+    #[test]
+    fn read_profile_matches_minimal_fspec() {
+        // Positions 0 and 8: byte 0 MSB and byte 1 bit 1.
+        let profile = 1 | 1 << 8;
+        assert!(Fspec::read_profile(&mut Cursor::new([0x81, 0x40]), profile).is_ok());
+        assert!(Fspec::read_profile(&mut Cursor::new([0x00]), 0).is_ok());
+
+        assert!(Fspec::read_profile(&mut Cursor::new([0x80]), profile).is_err());
+        assert!(Fspec::read_profile(&mut Cursor::new([0x81, 0x41, 0x00]), profile).is_err());
+        assert!(matches!(
+            Fspec::read_profile(&mut Cursor::new([0x81]), profile),
+            Err(DecodeError::Io(_))
+        ));
+    }
+
     #[test]
     fn new_creates_single_byte_fspec() {
         let fspec = Fspec::new();
@@ -116,27 +283,13 @@ mod tests {
     }
 
     #[test]
-    fn set_first_item_bit() {
+    fn set_last_index_is_fx_bit() {
         let mut fspec = Fspec::new();
 
-        // Set bit 7 (MSB) of byte 0 - corresponds to FRN 0
+        // `set` counts bit indices from the MSB: index 0 is FRN 1 (bit 7,
+        // 0x80) and index 7 is the FX bit (bit 0, the LSB, 0x01).
         fspec.set(0, 7);
 
-        // Check the bit is set (bit position 7 from MSB = 0x80 >> 7 = 0x80)
-        // Actually is_set(0, 7) checks bit (7-7)=0 from right, which is 0x01
-        // Wait, let me re-read the is_set implementation:
-        // (b & (1 << (7 - bit))) != 0
-        // For bit=7: (b & (1 << 0)) = b & 0x01
-        // For bit=0: (b & (1 << 7)) = b & 0x80
-        // So bit=0 is MSB, bit=7 is LSB (FX bit)
-
-        // set(0, 7) sets: bytes[0] |= 1 << (7-7) = 1 << 0 = 0x01
-        // But that's the FX bit! Let me check the code again...
-
-        // Actually the set function does: bytes[byte] |= 1 << (7 - bit)
-        // So set(0, 0) would set bit 7 (MSB) = 0x80
-        // And set(0, 7) would set bit 0 (LSB/FX) = 0x01
-
         assert!(fspec.is_set(0, 7));
         assert_eq!(fspec.bytes[0], 0x01);
     }
@@ -145,7 +298,7 @@ mod tests {
     fn set_msb_item() {
         let mut fspec = Fspec::new();
 
-        // Set bit 0 (MSB) of byte 0 - this is the first data item bit
+        // Index 0 is the MSB of byte 0 (0x80): the bit of FRN 1
         fspec.set(0, 0);
 
         assert!(fspec.is_set(0, 0));
@@ -193,6 +346,74 @@ mod tests {
         assert_eq!(fspec.bytes[2], 0x80); // Item bit set
     }
 
+    #[test]
+    fn frns_skip_fx_bits() {
+        let mut fspec = Fspec::new();
+        fspec.set_frn(1);
+        fspec.set_frn(7);
+        fspec.set_frn(8);
+        fspec.set_frn(16);
+        assert_eq!(fspec.as_bytes(), [0x83, 0x81, 0x40]);
+
+        for frn in 1..=21 {
+            assert_eq!(fspec.is_frn_set(frn), [1, 7, 8, 16].contains(&frn), "FRN {}", frn);
+        }
+
+        fspec.unset_frn(16);
+        assert_eq!(fspec.as_bytes(), [0x83, 0x80]);
+    }
+
+    #[test]
+    #[should_panic(expected = "FRNs start at 1")]
+    fn frn_zero_is_rejected() {
+        Fspec::new().is_frn_set(0);
+    }
+
+    #[test]
+    fn unset_drops_bytes_without_items() {
+        let mut fspec = Fspec::new();
+        fspec.set(0, 0);
+        fspec.set(2, 3);
+
+        fspec.unset(2, 3);
+        assert_eq!(fspec.as_bytes(), [0x80]);
+
+        // Unsetting a bit that isn't set, even beyond the end, changes nothing.
+        fspec.unset(0, 1);
+        fspec.unset(4, 0);
+        assert_eq!(fspec.as_bytes(), [0x80]);
+
+        fspec.unset(0, 0);
+        assert_eq!(fspec.as_bytes(), [0x00]);
+    }
+
+    #[test]
+    fn normalize_fixes_fx_chain() {
+        // A stale FX bit on the last byte, and trailing bytes without items.
+        let mut fspec = Fspec { bytes: vec![0x40, 0x21, 0x01, 0x00] };
+        assert!(!fspec.is_canonical());
+
+        fspec.normalize();
+        assert_eq!(fspec.as_bytes(), [0x41, 0x20]);
+        assert!(fspec.is_canonical());
+        assert!(Fspec::read_profile(&mut Cursor::new(fspec.as_bytes()), 1 << 1 | 1 << 9).is_ok());
+    }
+
+    #[test]
+    fn equality_ignores_fx_and_trailing_bytes() {
+        let mut built = Fspec::new();
+        built.set(0, 0);
+        built.set(1, 1);
+
+        let read = Fspec::read(&mut Cursor::new([0x81, 0x41, 0x00])).unwrap();
+        assert!(!read.is_canonical());
+        assert_eq!(built, read);
+
+        built.set(1, 2);
+        assert_ne!(built, read);
+        assert_eq!(Fspec::new(), Fspec::read(&mut Cursor::new([0x01, 0x00])).unwrap());
+    }
+
     #[test]
     fn read_single_byte_fspec() {
         // Single byte with no FX (FX=0 means no more bytes)
@@ -232,6 +453,18 @@ mod tests {
         assert!(fspec.is_set(2, 0)); // Item in third byte
     }
 
+    #[test]
+    fn read_fspec_longer_than_limit_fails() {
+        let mut data = vec![0x01; crate::policy::DEFAULT_MAX_FSPEC_LEN];
+        data.push(0x80);
+
+        let result = Fspec::read(&mut Cursor::new(&data));
+        assert!(matches!(result, Err(DecodeError::LimitExceeded { limit: "FSPEC length", .. })));
+
+        // One byte shorter is accepted
+        assert!(Fspec::read(&mut Cursor::new(&data[1..])).is_ok());
+    }
+
     #[test]
     fn write_single_byte_fspec() {
         let mut fspec = Fspec::new();
@@ -0,0 +1,257 @@
+//! Conversion of sensor-local positions to WGS-84.
+//!
+//! Radars report positions relative to themselves: slant range and azimuth
+//! (I048/040) or cartesian coordinates on the local tangent plane
+//! (I048/042).  Displaying or fusing them needs geodetic coordinates, which
+//! depend on where the radar stands.  [`RadarSite`] converts both kinds of
+//! position on the WGS-84 ellipsoid, taking the target's height into
+//! account for slant ranges.
+//!
+//! Generated structs implement [`PolarPosition`] or [`CartesianPosition`]
+//! when the definition tags derived values with their role:
+//!
+//! ```xml
+//! <field name="rho" bits="16"/>
+//! <field name="theta" bits="16"/>
+//! <derived name="range_nm" expr="rho / 256.0" geo="range"/>
+//! <derived name="azimuth_deg" expr="theta * 360.0 / 65536.0" geo="azimuth"/>
+//! ```
+//!
+//! ```ignore
+//! let site = RadarSite::new(45.63, 8.72, 250.0);
+//! let position = record.item040.unwrap().to_wgs84(&site, Some(10_668.0));
+//! ```
+//!
+//! # Example
+//!
+//! ```
+//! use rasterix_core::geo::RadarSite;
+//!
+//! let site = RadarSite::new(45.0, 9.0, 0.0);
+//! // 60 NM due north, at the height of the site.
+//! let target = site.polar_to_wgs84(60.0 * 1852.0, 0.0, None);
+//! assert!((target.latitude - 46.0).abs() < 0.01);
+//! assert!((target.longitude - 9.0).abs() < 1e-9);
+//! ```
+
+/// Meters in a nautical mile.
+pub const METERS_PER_NM: f64 = 1852.0;
+
+/// Semi-major axis of the WGS-84 ellipsoid, in meters.
+const A: f64 = 6_378_137.0;
+/// Flattening of the WGS-84 ellipsoid.
+const F: f64 = 1.0 / 298.257_223_563;
+/// Square of the first eccentricity.
+const E2: f64 = F * (2.0 - F);
+
+/// A geodetic position on the WGS-84 ellipsoid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Wgs84 {
+    /// Latitude, in degrees north.
+    pub latitude: f64,
+    /// Longitude, in degrees east.
+    pub longitude: f64,
+    /// Height above the ellipsoid, in meters.
+    pub height: f64,
+}
+
+impl Wgs84 {
+    pub fn new(latitude: f64, longitude: f64, height: f64) -> Self {
+        Self { latitude, longitude, height }
+    }
+
+    /// Returns the earth-centered, earth-fixed coordinates, in meters.
+    pub fn to_ecef(&self) -> [f64; 3] {
+        let (lat, lon) = (self.latitude.to_radians(), self.longitude.to_radians());
+        let n = A / (1.0 - E2 * lat.sin().powi(2)).sqrt();
+        [
+            (n + self.height) * lat.cos() * lon.cos(),
+            (n + self.height) * lat.cos() * lon.sin(),
+            (n * (1.0 - E2) + self.height) * lat.sin(),
+        ]
+    }
+
+    /// Returns the position of earth-centered, earth-fixed coordinates, in
+    /// meters.
+    pub fn from_ecef([x, y, z]: [f64; 3]) -> Self {
+        let p = x.hypot(y);
+        let longitude = y.atan2(x);
+        // Bowring's iteration converges to well under a millimeter in a few
+        // steps for positions near the surface.
+        let mut latitude = z.atan2(p * (1.0 - E2));
+        let mut height = 0.0;
+        for _ in 0..5 {
+            let n = A / (1.0 - E2 * latitude.sin().powi(2)).sqrt();
+            height = if latitude.cos().abs() > 1e-12 { p / latitude.cos() - n } else { z.abs() - n * (1.0 - E2) };
+            latitude = z.atan2(p * (1.0 - E2 * n / (n + height)));
+        }
+        Self { latitude: latitude.to_degrees(), longitude: longitude.to_degrees(), height }
+    }
+}
+
+/// Location of a sensor, the origin of the positions it reports.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RadarSite {
+    pub position: Wgs84,
+}
+
+impl RadarSite {
+    /// Creates a site at `latitude` and `longitude`, in degrees, with the
+    /// antenna `height` meters above the ellipsoid.
+    pub fn new(latitude: f64, longitude: f64, height: f64) -> Self {
+        Self { position: Wgs84::new(latitude, longitude, height) }
+    }
+
+    /// Converts a position on the local east-north-up frame of the site, in
+    /// meters, to WGS-84.
+    pub fn local_to_wgs84(&self, east: f64, north: f64, up: f64) -> Wgs84 {
+        let (lat, lon) = (self.position.latitude.to_radians(), self.position.longitude.to_radians());
+        let [x, y, z] = self.position.to_ecef();
+        Wgs84::from_ecef([
+            x - lon.sin() * east - lat.sin() * lon.cos() * north + lat.cos() * lon.cos() * up,
+            y + lon.cos() * east - lat.sin() * lon.sin() * north + lat.cos() * lon.sin() * up,
+            z + lat.cos() * north + lat.sin() * up,
+        ])
+    }
+
+    /// Converts a slant range, in meters, and azimuth, in degrees clockwise
+    /// from north, to WGS-84.
+    ///
+    /// The elevation of the target follows from its `height` above the
+    /// ellipsoid, e.g. from its flight level; without it the target is
+    /// taken to be at the height of the site.
+    pub fn polar_to_wgs84(&self, range: f64, azimuth: f64, height: Option<f64>) -> Wgs84 {
+        if range <= 0.0 {
+            return self.position;
+        }
+        let height = height.unwrap_or(self.position.height);
+        let azimuth = azimuth.to_radians();
+
+        // The elevation comes from the triangle of the earth center, the site
+        // and the target on a sphere of the local radius of curvature.  The
+        // ellipsoid differs by meters at long ranges, so the height on the
+        // sphere is corrected until the target is at `height`.
+        let radius = self.local_radius();
+        let site = radius + self.position.height;
+        let mut sphere_height = height;
+        let mut position = self.position;
+        for _ in 0..3 {
+            let target = radius + sphere_height;
+            let sin_elevation =
+                ((target * target - site * site - range * range) / (2.0 * site * range)).clamp(-1.0, 1.0);
+            let ground = range * sin_elevation.asin().cos();
+            position = self.local_to_wgs84(ground * azimuth.sin(), ground * azimuth.cos(), range * sin_elevation);
+            sphere_height += height - position.height;
+        }
+        position
+    }
+
+    /// Converts cartesian coordinates on the tangent plane of the site, in
+    /// meters east and north, to WGS-84 at the surface of the ellipsoid.
+    pub fn cartesian_to_wgs84(&self, east: f64, north: f64) -> Wgs84 {
+        // Down to the ellipsoid, along the local vertical of the target.
+        let on_plane = self.local_to_wgs84(east, north, 0.0);
+        Wgs84 { height: 0.0, ..on_plane }
+    }
+
+    /// Gaussian mean radius of curvature at the site, in meters.
+    fn local_radius(&self) -> f64 {
+        let sin2 = self.position.latitude.to_radians().sin().powi(2);
+        let w = 1.0 - E2 * sin2;
+        A * (1.0 - E2).sqrt() / w
+    }
+}
+
+/// A position given as slant range and azimuth from a sensor.
+pub trait PolarPosition {
+    /// Slant range, in NM.
+    fn range_nm(&self) -> f64;
+
+    /// Azimuth, in degrees clockwise from north.
+    fn azimuth_deg(&self) -> f64;
+
+    /// Converts the position to WGS-84, for a target `height` meters above
+    /// the ellipsoid if known (see [`RadarSite::polar_to_wgs84`]).
+    fn to_wgs84(&self, site: &RadarSite, height: Option<f64>) -> Wgs84 {
+        site.polar_to_wgs84(self.range_nm() * METERS_PER_NM, self.azimuth_deg(), height)
+    }
+}
+
+/// A position given as cartesian coordinates relative to a sensor.
+pub trait CartesianPosition {
+    /// Distance east of the sensor, in NM.
+    fn east_nm(&self) -> f64;
+
+    /// Distance north of the sensor, in NM.
+    fn north_nm(&self) -> f64;
+
+    /// Converts the position to WGS-84 (see [`RadarSite::cartesian_to_wgs84`]).
+    fn to_wgs84(&self, site: &RadarSite) -> Wgs84 {
+        site.cartesian_to_wgs84(self.east_nm() * METERS_PER_NM, self.north_nm() * METERS_PER_NM)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64, tolerance: f64) -> bool {
+        (a - b).abs() < tolerance
+    }
+
+    #[test]
+    fn ecef_round_trips() {
+        for position in [Wgs84::new(45.63, 8.72, 250.0), Wgs84::new(-33.9, 151.2, 11_000.0), Wgs84::new(89.9, -20.0, 0.0)] {
+            let back = Wgs84::from_ecef(position.to_ecef());
+            assert!(close(back.latitude, position.latitude, 1e-9), "{:?}", back);
+            assert!(close(back.longitude, position.longitude, 1e-9), "{:?}", back);
+            assert!(close(back.height, position.height, 1e-3), "{:?}", back);
+        }
+    }
+
+    #[test]
+    fn polar_positions_keep_azimuth_and_height() {
+        let site = RadarSite::new(45.0, 9.0, 300.0);
+        assert_eq!(site.polar_to_wgs84(0.0, 123.0, None), site.position);
+
+        // Due east, on the parallel of the site at first.
+        let east = site.polar_to_wgs84(50_000.0, 90.0, Some(10_000.0));
+        assert!(east.longitude > 9.6 && east.longitude < 9.7, "{:?}", east);
+        assert!(close(east.height, 10_000.0, 0.01), "{:?}", east);
+
+        // The same slant range is a shorter ground distance to a high
+        // target.
+        let low = site.polar_to_wgs84(100_000.0, 180.0, Some(300.0));
+        let high = site.polar_to_wgs84(100_000.0, 180.0, Some(12_000.0));
+        assert!(high.latitude > low.latitude);
+        assert!(close(low.height, 300.0, 0.01), "{:?}", low);
+        assert!(close(high.height, 12_000.0, 0.01), "{:?}", high);
+    }
+
+    #[test]
+    fn cartesian_positions_are_on_the_ellipsoid() {
+        let site = RadarSite::new(0.0, 0.0, 50.0);
+        let target = site.cartesian_to_wgs84(0.0, 60.0 * METERS_PER_NM);
+        assert!(close(target.latitude, 1.0, 0.01), "{:?}", target);
+        assert!(close(target.longitude, 0.0, 1e-9));
+        assert_eq!(target.height, 0.0);
+    }
+
+    #[test]
+    fn traits_convert_from_nautical_miles() {
+        struct Plot;
+
+        impl PolarPosition for Plot {
+            fn range_nm(&self) -> f64 {
+                10.0
+            }
+
+            fn azimuth_deg(&self) -> f64 {
+                45.0
+            }
+        }
+
+        let site = RadarSite::new(50.0, 5.0, 0.0);
+        assert_eq!(Plot.to_wgs84(&site, None), site.polar_to_wgs84(18_520.0, 45.0, None));
+    }
+}
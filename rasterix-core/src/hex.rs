@@ -0,0 +1,111 @@
+//! Hex text conversion for pasted or logged ASTERIX messages.
+
+use crate::DecodeError;
+
+/// Decodes hex text, tolerating the usual formatting around the digits.
+///
+/// Whitespace and `:`, `,`, `-`, `;` separators are ignored, as are `0x` /
+/// `0X` / `\x` prefixes on each group, so all of these decode to the same
+/// bytes:
+///
+/// ```text
+/// 30 00 06 80 01 02
+/// 300006800102
+/// 0x30, 0x00, 0x06, 0x80, 0x01, 0x02
+/// 30:00:06:80:01:02
+/// ```
+///
+/// Returns an error on any other character or on an odd number of digits.
+///
+/// # Example
+///
+/// ```
+/// use rasterix_core::hex::decode_loose;
+///
+/// assert_eq!(decode_loose("3015 00 0f").unwrap(), vec![0x30, 0x15, 0x00, 0x0f]);
+/// assert_eq!(decode_loose("0x30,0x15").unwrap(), vec![0x30, 0x15]);
+/// ```
+pub fn decode_loose(text: &str) -> Result<Vec<u8>, DecodeError> {
+    let mut digits = Vec::with_capacity(text.len());
+
+    // `\x` escapes are always prefixes, even without a separator in front.
+    let text = text.replace("\\x", " ");
+
+    for group in text.split(|c: char| c.is_whitespace() || matches!(c, ':' | ',' | '-' | ';')) {
+        let group = ["0x", "0X"]
+            .iter()
+            .find_map(|prefix| group.strip_prefix(prefix))
+            .unwrap_or(group);
+
+        for c in group.chars() {
+            let digit = c
+                .to_digit(16)
+                .ok_or(DecodeError::InvalidData("invalid hex digit"))?;
+            digits.push(digit as u8);
+        }
+    }
+
+    if digits.len() % 2 != 0 {
+        return Err(DecodeError::InvalidData("odd number of hex digits"));
+    }
+
+    Ok(digits.chunks(2).map(|pair| (pair[0] << 4) | pair[1]).collect())
+}
+
+/// Encodes bytes as lowercase hex, with a space between bytes.
+///
+/// The output is accepted by [`decode_loose`].
+pub fn encode_spaced(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_and_spaced() {
+        let expected = vec![0x30, 0x00, 0x06, 0x80, 0x01, 0x02];
+        assert_eq!(decode_loose("300006800102").unwrap(), expected);
+        assert_eq!(decode_loose("30 00 06 80 01 02").unwrap(), expected);
+        assert_eq!(decode_loose("3000 0680\n0102\n").unwrap(), expected);
+    }
+
+    #[test]
+    fn prefixes_and_separators() {
+        let expected = vec![0xAB, 0xCD];
+        assert_eq!(decode_loose("0xAB 0xCD").unwrap(), expected);
+        assert_eq!(decode_loose("0xab,0XCD").unwrap(), expected);
+        assert_eq!(decode_loose("\\xab\\xcd").unwrap(), expected);
+        assert_eq!(decode_loose("ab:cd").unwrap(), expected);
+        assert_eq!(decode_loose("ab-cd;").unwrap(), expected);
+    }
+
+    #[test]
+    fn empty_input() {
+        assert!(decode_loose("").unwrap().is_empty());
+        assert!(decode_loose("  \n\t ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn invalid_digit() {
+        assert!(decode_loose("30 0g").is_err());
+    }
+
+    #[test]
+    fn odd_digit_count() {
+        assert!(decode_loose("30 0").is_err());
+    }
+
+    #[test]
+    fn encode_roundtrip() {
+        let bytes = vec![0x00, 0x7f, 0xff];
+        let text = encode_spaced(&bytes);
+        assert_eq!(text, "00 7f ff");
+        assert_eq!(decode_loose(&text).unwrap(), bytes);
+    }
+}
@@ -0,0 +1,219 @@
+//! Minimal JSON output for decoded messages.
+//!
+//! Generated code can implement [`ToJson`] so decoded records can be handed
+//! to JavaScript or written as JSON lines without pulling a serialization
+//! framework into the runtime.  Only writing is supported.
+
+/// Types that can write themselves as JSON.
+pub trait ToJson {
+    /// Appends the JSON representation of `self` to `out`.
+    fn write_json(&self, out: &mut String);
+
+    /// Returns the JSON representation of `self`.
+    fn to_json(&self) -> String {
+        let mut out = String::new();
+        self.write_json(&mut out);
+        out
+    }
+}
+
+macro_rules! impl_to_json_number {
+    ($($ty:ty),*) => {
+        $(
+            impl ToJson for $ty {
+                fn write_json(&self, out: &mut String) {
+                    out.push_str(&self.to_string());
+                }
+            }
+        )*
+    };
+}
+
+impl_to_json_number!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+macro_rules! impl_to_json_float {
+    ($($ty:ty),*) => {
+        $(
+            /// Non-finite values, which JSON cannot represent, are written as `null`.
+            impl ToJson for $ty {
+                fn write_json(&self, out: &mut String) {
+                    if self.is_finite() {
+                        out.push_str(&self.to_string());
+                    } else {
+                        out.push_str("null");
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_to_json_float!(f32, f64);
+
+impl ToJson for bool {
+    fn write_json(&self, out: &mut String) {
+        out.push_str(if *self { "true" } else { "false" });
+    }
+}
+
+impl ToJson for str {
+    fn write_json(&self, out: &mut String) {
+        write_string(out, self);
+    }
+}
+
+impl ToJson for String {
+    fn write_json(&self, out: &mut String) {
+        write_string(out, self);
+    }
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+    fn write_json(&self, out: &mut String) {
+        match self {
+            Some(value) => value.write_json(out),
+            None => out.push_str("null"),
+        }
+    }
+}
+
+impl<T: ToJson> ToJson for [T] {
+    fn write_json(&self, out: &mut String) {
+        out.push('[');
+        for (i, value) in self.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            value.write_json(out);
+        }
+        out.push(']');
+    }
+}
+
+impl<T: ToJson, const N: usize> ToJson for [T; N] {
+    fn write_json(&self, out: &mut String) {
+        self.as_slice().write_json(out);
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn write_json(&self, out: &mut String) {
+        self.as_slice().write_json(out);
+    }
+}
+
+impl<T: ToJson + ?Sized> ToJson for &T {
+    fn write_json(&self, out: &mut String) {
+        (**self).write_json(out);
+    }
+}
+
+/// Appends `value` as a quoted, escaped JSON string.
+pub fn write_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Writes a JSON object field by field.
+///
+/// # Example
+///
+/// ```
+/// use rasterix_core::json::JsonObject;
+///
+/// let mut out = String::new();
+/// let mut obj = JsonObject::new(&mut out);
+/// obj.field("sac", &1u8);
+/// obj.field("name", "BAW123");
+/// obj.finish();
+///
+/// assert_eq!(out, r#"{"sac":1,"name":"BAW123"}"#);
+/// ```
+pub struct JsonObject<'a> {
+    out: &'a mut String,
+    empty: bool,
+}
+
+impl<'a> JsonObject<'a> {
+    /// Starts an object.
+    pub fn new(out: &'a mut String) -> Self {
+        out.push('{');
+        Self { out, empty: true }
+    }
+
+    /// Writes a field.
+    pub fn field<T: ToJson + ?Sized>(&mut self, name: &str, value: &T) -> &mut Self {
+        if !self.empty {
+            self.out.push(',');
+        }
+        self.empty = false;
+        write_string(self.out, name);
+        self.out.push(':');
+        value.write_json(self.out);
+        self
+    }
+
+    /// Closes the object.
+    pub fn finish(self) {
+        self.out.push('}');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn primitives() {
+        assert_eq!(42u16.to_json(), "42");
+        assert_eq!(true.to_json(), "true");
+        assert_eq!(Some(7u8).to_json(), "7");
+        assert_eq!(None::<u8>.to_json(), "null");
+        assert_eq!(vec![1u8, 2, 3].to_json(), "[1,2,3]");
+        assert_eq!([0xABu8, 0].to_json(), "[171,0]");
+        assert_eq!(1.5f64.to_json(), "1.5");
+        assert_eq!(f64::NAN.to_json(), "null");
+    }
+
+    #[test]
+    fn string_escaping() {
+        assert_eq!("a\"b\\c\n\u{1}".to_json(), r#""a\"b\\c\n\u0001""#);
+    }
+
+    #[test]
+    fn nested_objects() {
+        struct Inner(u8);
+        impl ToJson for Inner {
+            fn write_json(&self, out: &mut String) {
+                let mut obj = JsonObject::new(out);
+                obj.field("v", &self.0);
+                obj.finish();
+            }
+        }
+
+        let mut out = String::new();
+        let mut obj = JsonObject::new(&mut out);
+        obj.field("items", &vec![Inner(1), Inner(2)]).field("none", &None::<Inner>);
+        obj.finish();
+
+        assert_eq!(out, r#"{"items":[{"v":1},{"v":2}],"none":null}"#);
+    }
+
+    #[test]
+    fn empty_object() {
+        let mut out = String::new();
+        JsonObject::new(&mut out).finish();
+        assert_eq!(out, "{}");
+    }
+}
@@ -4,7 +4,11 @@
 //!
 //! This crate provides the foundational types used by code generated from
 //! ASTERIX XML category definitions. It has **zero external dependencies** and
-//! relies only on the Rust standard library.
+//! relies only on the Rust standard library, unless the optional `tracing`
+//! feature is enabled (see [`trace`]), the `heapless` feature, which
+//! re-exports the [`heapless`](https://docs.rs/heapless) crate for generated
+//! repetitive items stored inline, or the `zstd` feature, for compressed
+//! [`recording`]s.
 //!
 //! ## Key components
 //!
@@ -12,14 +16,39 @@
 //! |------|---------|
 //! | [`BitReader`] | Read individual bits from any [`std::io::Read`] source |
 //! | [`BitWriter`] | Write individual bits to any [`std::io::Write`] sink |
+//! | [`BitSliceWriter`] | Write individual bits directly into a byte slice |
 //! | [`Fspec`] | ASTERIX Field Specification bitmap (variable-length) |
 //! | [`MemoryBuffer`] | Convenience in-memory buffer implementing both `Read` and `Write` |
+//! | [`DecodeContext`] | Reusable scratch buffers for allocation-free repeated decoding |
+//! | [`RecordStream`] | Iterator over the records of one category in a stream of data blocks |
+//! | [`StreamCheckpoint`] | Saved state of a [`RecordStream`], to resume it after a restart |
+//! | [`StreamMetrics`] | Receives the decoding events of a [`RecordStream`] for monitoring |
+//! | [`ResyncScanner`] | Finds the next plausible data block header in corrupted input |
+//! | [`DecodePolicy`] | Accepts, repairs or rejects recoverable anomalies at decode time |
+//! | [`Timestamped`] | A value paired with its receive time, taken from a [`Clock`] |
+//! | [`DecodeFromRead`] / [`EncodeToWrite`] | Byte-level decoding and encoding of records and compound items |
+//! | [`DataItem`] | Item number and FSPEC position of a generated item type |
+//! | [`Validate`] | Checks the field constraints of a generated item or record |
+//! | [`Conformance`] | Checks a generated record against the rules of its definition before encoding |
+//! | [`ToValue`] / [`FromValue`] | Converts generated items to and from generic [`Value`]s |
 //! | [`DecodeError`] | Unified error type for encoding/decoding failures |
 //!
+//! The [`hex`] and [`base64`] modules convert messages pasted or logged as
+//! text back into bytes; [`json`] writes decoded values as JSON.  The
+//! [`recording`] module stores data blocks with their receive times and,
+//! with the `manifest` feature, checks them against SHA-256 digests,
+//! [`geo`] converts sensor-local positions to WGS-84, [`altitude`]
+//! converts flight levels and altitudes between units, [`velocity`]
+//! converts polar and cartesian ground velocities and [`icao`] formats
+//! aircraft addresses.
+//!
 //! ## Traits
 //!
 //! Generated ASTERIX data structures implement the [`Encode`] and [`Decode`]
-//! traits, which operate on [`BitWriter`] / [`BitReader`] respectively.
+//! traits, which operate on a [`BitWrite`] implementation ([`BitWriter`] or
+//! [`BitSliceWriter`]) / [`BitReader`] respectively.
+//! Records, data blocks and most items additionally implement [`DecodeInto`],
+//! which overwrites an existing value in place and reuses its allocations.
 //!
 //! ## Example
 //!
@@ -38,24 +67,70 @@
 //! assert_eq!(reader.read_bits(12).unwrap(), 0xABC);
 //! ```
 
+pub mod altitude;
+pub mod base64;
 pub mod bit_reader;
+pub mod bit_slice_writer;
 pub mod bit_writer;
 pub mod buffer;
+pub mod conformance;
+pub mod context;
 pub mod error;
 pub mod fspec;
+pub mod geo;
+pub mod hex;
+pub mod icao;
+pub mod json;
+pub mod metrics;
+pub mod policy;
+pub mod recording;
+pub mod resync;
+pub mod stream;
+pub mod time;
+pub mod trace;
+pub mod validate;
+pub mod value;
+pub mod velocity;
 
 pub use bit_reader::BitReader;
-pub use bit_writer::BitWriter;
+pub use bit_slice_writer::BitSliceWriter;
+pub use bit_writer::{BitWrite, BitWriter};
 pub use buffer::MemoryBuffer;
+pub use conformance::{Conformance, ConformanceIssue};
+pub use context::DecodeContext;
 pub use error::DecodeError;
 pub use fspec::Fspec;
+pub use json::ToJson;
+pub use metrics::{FeedCounters, StreamMetrics};
+pub use policy::DecodePolicy;
+pub use resync::{Resync, ResyncScanner};
+pub use stream::{
+    CategoryRecord, Header, RecordStream, StreamCheckpoint, StreamConfig, TailPadding, TimestampedRecords,
+};
+pub use time::{Clock, MonotonicClock, SystemClock, Timestamped};
+pub use validate::{Validate, ValidationError};
+pub use value::{FromValue, ToValue, Value, ValueError, ValueMap};
+
+#[cfg(feature = "heapless")]
+pub use heapless;
 
 /// Trait for encoding ASTERIX data structures into a bit stream.
 ///
-/// Implementors serialize their fields into the provided [`BitWriter`],
-/// returning a [`DecodeError`] on failure.
+/// Implementors serialize their fields into the provided [`BitWrite`]
+/// writer, returning a [`DecodeError`] on failure.
 pub trait Encode {
-    fn encode<W: std::io::Write>(&self, writer: &mut BitWriter<W>) -> Result<(), DecodeError>;
+    fn encode<W: BitWrite>(&self, writer: &mut W) -> Result<(), DecodeError>;
+
+    /// Encodes `self` at the start of `buf` and returns the number of bytes
+    /// written, padding a final partial byte with zero bits.
+    ///
+    /// Fails with [`DecodeError::Io`] if `buf` is too small.
+    fn encode_to_slice(&self, buf: &mut [u8]) -> Result<usize, DecodeError> {
+        let mut writer = BitSliceWriter::new(buf);
+        self.encode(&mut writer)?;
+        writer.flush();
+        Ok(writer.bytes_written())
+    }
 }
 
 /// Trait for decoding ASTERIX data structures from a bit stream.
@@ -66,6 +141,87 @@ pub trait Decode: Sized {
     fn decode<R: std::io::Read>(reader: &mut BitReader<R>) -> Result<Self, DecodeError>;
 }
 
+/// Trait for decoding ASTERIX data structures into an existing value.
+///
+/// Unlike [`Decode::decode`], which builds a fresh value, `decode_into`
+/// overwrites `self` in place: `Vec`s of repetitive items and `String` fields
+/// keep their capacity, and temporary buffers are borrowed from the
+/// [`DecodeContext`].  Decoding a stream of records into the same value
+/// therefore stops allocating once the buffers have grown large enough.
+///
+/// If decoding fails, `self` is left in an unspecified (but valid) state.
+pub trait DecodeInto: Decode {
+    fn decode_into<R: std::io::Read>(
+        &mut self,
+        reader: &mut BitReader<R>,
+        ctx: &mut DecodeContext,
+    ) -> Result<(), DecodeError>;
+}
+
+/// Trait for decoding ASTERIX data structures that start on a byte boundary
+/// from a byte reader.
+///
+/// Implemented by generated records and compound items, which read their
+/// FSPEC byte by byte before their fields.  Unlike [`Decode`], it doesn't
+/// require a [`BitReader`], so generic utilities can decode both alike:
+///
+/// ```ignore
+/// fn decode_all<T: DecodeFromRead>(bytes: &[u8], count: usize) -> Result<Vec<T>, DecodeError> {
+///     let mut reader = bytes;
+///     (0..count).map(|_| T::decode_from(&mut reader)).collect()
+/// }
+/// ```
+pub trait DecodeFromRead: Sized {
+    fn decode_from<R: std::io::Read>(reader: &mut R) -> Result<Self, DecodeError>;
+}
+
+/// Trait for encoding ASTERIX data structures that end on a byte boundary
+/// to a byte writer.
+///
+/// The counterpart of [`DecodeFromRead`], implemented by generated records
+/// and compound items.
+pub trait EncodeToWrite {
+    fn encode_to<W: std::io::Write>(&self, writer: &mut W) -> Result<(), DecodeError>;
+
+    /// Encodes `self` into a new buffer.
+    fn to_bytes(&self) -> Result<Vec<u8>, DecodeError> {
+        let mut buf = Vec::new();
+        self.encode_to(&mut buf)?;
+        Ok(buf)
+    }
+}
+
+/// Position of a generated data item in the record of its category.
+///
+/// Implemented by every generated item type, so generic code can find an
+/// item in an [`Fspec`] without parsing type names:
+///
+/// ```ignore
+/// fn is_present<I: DataItem>(fspec: &Fspec) -> bool {
+///     fspec.is_frn_set(I::FRN)
+/// }
+/// ```
+pub trait DataItem {
+    /// Item number, e.g. `10` for I048/010.
+    const ITEM_ID: u16;
+    /// Field reference number of the item, counted from 1 as in the
+    /// specifications.
+    const FRN: usize;
+    /// FSPEC byte holding the item's presence bit: `(FRN - 1) / 7`.
+    const FSPEC_BYTE: usize;
+    /// Presence bit within that byte, numbered from 0 (MSB) as in
+    /// [`Fspec::is_set`]: `(FRN - 1) % 7`.
+    const FSPEC_BIT: u8;
+
+    /// Returns the description of `field` given by the definition, if any.
+    ///
+    /// Fields of parts and sub-items are named by their dotted path, e.g.
+    /// `"part0.sac"`, and the fields of repetitive elements by their name.
+    fn describe(field: &str) -> Option<&'static str> {
+        let _ = field;
+        None
+    }
+}
 
 #[cfg(test)]
 mod tests {}
\ No newline at end of file
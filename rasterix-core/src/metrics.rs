@@ -0,0 +1,125 @@
+//! Feed health metrics for the streaming decoder.
+//!
+//! A [`RecordStream`](crate::RecordStream) reports what it reads to a
+//! [`StreamMetrics`] implementation: data blocks and their sizes, decoded
+//! records, errors and bytes skipped while resynchronizing.  Services
+//! embedding the stream decoder can forward these events to their
+//! monitoring system; [`FeedCounters`] keeps simple in-memory totals.
+
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use crate::{DecodeError, Fspec, Header};
+
+/// Receiver of stream decoding events.
+///
+/// Every method does nothing by default, so implementations only override
+/// the events they track.  `()` is the no-op implementation used when no
+/// metrics are attached.
+pub trait StreamMetrics {
+    /// A data block of any category was read.
+    fn block_read(&mut self, _header: &Header) {}
+
+    /// A record of `category` was decoded from `encoded`, its bytes on the
+    /// wire.  Use [`Fspec::read`] on `encoded` to find the items present.
+    fn record_decoded(&mut self, _category: u8, _encoded: &[u8]) {}
+
+    /// Decoding failed.  `category` is `None` for errors outside a record,
+    /// such as malformed headers or I/O errors.
+    fn decode_error(&mut self, _category: Option<u8>, _error: &DecodeError) {}
+
+    /// `count` bytes were discarded while resynchronizing.
+    fn bytes_skipped(&mut self, _count: u64) {}
+}
+
+impl StreamMetrics for () {}
+
+impl<M: StreamMetrics + ?Sized> StreamMetrics for &mut M {
+    fn block_read(&mut self, header: &Header) {
+        (**self).block_read(header)
+    }
+
+    fn record_decoded(&mut self, category: u8, encoded: &[u8]) {
+        (**self).record_decoded(category, encoded)
+    }
+
+    fn decode_error(&mut self, category: Option<u8>, error: &DecodeError) {
+        (**self).decode_error(category, error)
+    }
+
+    fn bytes_skipped(&mut self, count: u64) {
+        (**self).bytes_skipped(count)
+    }
+}
+
+/// Returns the FSPEC positions (`byte * 7 + bit`) flagged in an encoded
+/// record, or an empty list if its FSPEC is truncated.
+pub fn present_items(encoded: &[u8]) -> Vec<usize> {
+    let Ok(fspec) = Fspec::read(&mut Cursor::new(encoded)) else {
+        return Vec::new();
+    };
+    let bytes = encoded.iter().position(|b| b & 0x01 == 0).map_or(encoded.len(), |i| i + 1);
+    (0..bytes)
+        .flat_map(|byte| (0..7u8).map(move |bit| (byte, bit)))
+        .filter(|&(byte, bit)| fspec.is_set(byte, bit))
+        .map(|(byte, bit)| byte * 7 + bit as usize)
+        .collect()
+}
+
+/// In-memory totals of stream events, per category.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeedCounters {
+    /// Data blocks read, per category.
+    pub blocks: HashMap<u8, u64>,
+    /// Bytes of data blocks read, per category.
+    pub bytes: HashMap<u8, u64>,
+    /// Records decoded, per category.
+    pub records: HashMap<u8, u64>,
+    /// Decoding errors, per category; `None` for errors outside records.
+    pub errors: HashMap<Option<u8>, u64>,
+    /// Records in which each item was present, per category and FSPEC
+    /// position.
+    pub item_presence: HashMap<(u8, usize), u64>,
+    /// Bytes discarded while resynchronizing.
+    pub skipped_bytes: u64,
+}
+
+impl FeedCounters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl StreamMetrics for FeedCounters {
+    fn block_read(&mut self, header: &Header) {
+        *self.blocks.entry(header.category).or_default() += 1;
+        *self.bytes.entry(header.category).or_default() += header.length as u64;
+    }
+
+    fn record_decoded(&mut self, category: u8, encoded: &[u8]) {
+        *self.records.entry(category).or_default() += 1;
+        for position in present_items(encoded) {
+            *self.item_presence.entry((category, position)).or_default() += 1;
+        }
+    }
+
+    fn decode_error(&mut self, category: Option<u8>, _error: &DecodeError) {
+        *self.errors.entry(category).or_default() += 1;
+    }
+
+    fn bytes_skipped(&mut self, count: u64) {
+        self.skipped_bytes += count;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn present_items_across_fspec_bytes() {
+        assert_eq!(present_items(&[0x81, 0x40, 0xFF]), vec![0, 8]);
+        assert_eq!(present_items(&[0x00]), Vec::<usize>::new());
+        assert_eq!(present_items(&[0x01]), Vec::<usize>::new());
+    }
+}
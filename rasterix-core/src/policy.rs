@@ -0,0 +1,341 @@
+//! Runtime handling of recoverable decode anomalies.
+//!
+//! Some malformed input can still be decoded: an enum field holding a value
+//! with no variant, spare bits that are not zero, or an explicit-length item
+//! whose length byte disagrees with its definition.  Generated code reports
+//! each of these to the [`DecodePolicy`] installed on the current thread with
+//! [`with_policy`], which decides whether to accept, repair or reject it.
+//! The policy also bounds the lengths read from the input, so hostile data
+//! cannot make the decoder buffer more than expected.  Repetition counts and
+//! explicit item sizes are constants of the definition, not read from the
+//! input, so they aren't bounded.
+//!
+//! Without a policy, every anomaly is accepted: unknown enum values decode to
+//! the `Unknown` variant, spare bits are ignored and items are decoded as
+//! defined regardless of their length byte.  Zero bytes padding a data block
+//! after its last record are rejected.  Data blocks may use the full 16-bit
+//! length and FSPECs up to [`DEFAULT_MAX_FSPEC_LEN`] bytes.
+//!
+//! # Example
+//!
+//! ```
+//! use rasterix_core::policy::{self, DecodePolicy};
+//! use rasterix_core::DecodeError;
+//!
+//! /// Rejects records with non-zero spare bits.
+//! struct Strict;
+//!
+//! impl DecodePolicy for Strict {
+//!     fn on_nonzero_spare(&self, _bits: usize, _value: u64) -> Result<(), DecodeError> {
+//!         Err(DecodeError::InvalidData("non-zero spare bits"))
+//!     }
+//! }
+//!
+//! let result = policy::with_policy(Strict, || policy::reserved_bits(2, 0b01));
+//! assert!(result.is_err());
+//! assert!(policy::reserved_bits(2, 0b01).is_ok());
+//! ```
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::DecodeError;
+
+/// Longest FSPEC accepted without a policy, in bytes.  Standard categories
+/// need far fewer: 7 bytes cover 49 items.
+pub const DEFAULT_MAX_FSPEC_LEN: usize = 16;
+
+/// Decides how anomalies found while decoding are handled.
+///
+/// Each method is called only when its anomaly occurs and returns an error
+/// to reject the data, which fails decoding of the enclosing record.  The
+/// defaults accept everything.  Policies that log or count anomalies need
+/// interior mutability, since they are shared by reference.
+pub trait DecodePolicy {
+    /// An enum field of type `enum_name` holds `value`, which has no
+    /// variant.  Returns the value to decode instead: `value` itself keeps
+    /// it as `Unknown(value)`, the value of a variant repairs it.
+    fn on_unknown_enum(&self, enum_name: &'static str, value: u8) -> Result<u8, DecodeError> {
+        let _ = enum_name;
+        Ok(value)
+    }
+
+    /// `bits` spare bits hold `value`, which is not zero.  Spare bits are
+    /// not kept, so accepting them drops the value.
+    fn on_nonzero_spare(&self, bits: usize, value: u64) -> Result<(), DecodeError> {
+        let _ = (bits, value);
+        Ok(())
+    }
+
+    /// The length byte of an explicit-length item says `declared` bytes
+    /// where the definition has `expected`.  If accepted, the item is
+    /// decoded as defined.
+    fn on_length_mismatch(&self, declared: usize, expected: usize) -> Result<(), DecodeError> {
+        let _ = (declared, expected);
+        Ok(())
+    }
+
+    /// The bytes of a data block of `block_len` bytes from byte `offset` on
+    /// are all zero, so they hold no record: they pad the block, or are left
+    /// over by a record decoded too short.  If accepted, they are skipped and
+    /// the block ends there.  The default rejects them with
+    /// [`DecodeError::RecordUnderrun`].
+    fn on_tail_padding(&self, offset: usize, block_len: usize) -> Result<(), DecodeError> {
+        Err(DecodeError::RecordUnderrun { offset, block_len })
+    }
+
+    /// Longest data block decoded, in bytes including its header.  Longer
+    /// blocks fail with [`DecodeError::LimitExceeded`] before their payload
+    /// is read.
+    fn max_block_len(&self) -> usize {
+        u16::MAX as usize
+    }
+
+    /// Longest FSPEC read, in bytes.  Longer FSPECs fail with
+    /// [`DecodeError::LimitExceeded`].
+    fn max_fspec_len(&self) -> usize {
+        DEFAULT_MAX_FSPEC_LEN
+    }
+}
+
+impl<P: DecodePolicy + ?Sized> DecodePolicy for Rc<P> {
+    fn on_unknown_enum(&self, enum_name: &'static str, value: u8) -> Result<u8, DecodeError> {
+        (**self).on_unknown_enum(enum_name, value)
+    }
+
+    fn on_nonzero_spare(&self, bits: usize, value: u64) -> Result<(), DecodeError> {
+        (**self).on_nonzero_spare(bits, value)
+    }
+
+    fn on_length_mismatch(&self, declared: usize, expected: usize) -> Result<(), DecodeError> {
+        (**self).on_length_mismatch(declared, expected)
+    }
+
+    fn on_tail_padding(&self, offset: usize, block_len: usize) -> Result<(), DecodeError> {
+        (**self).on_tail_padding(offset, block_len)
+    }
+
+    fn max_block_len(&self) -> usize {
+        (**self).max_block_len()
+    }
+
+    fn max_fspec_len(&self) -> usize {
+        (**self).max_fspec_len()
+    }
+}
+
+impl<P: DecodePolicy + ?Sized> DecodePolicy for Arc<P> {
+    fn on_unknown_enum(&self, enum_name: &'static str, value: u8) -> Result<u8, DecodeError> {
+        (**self).on_unknown_enum(enum_name, value)
+    }
+
+    fn on_nonzero_spare(&self, bits: usize, value: u64) -> Result<(), DecodeError> {
+        (**self).on_nonzero_spare(bits, value)
+    }
+
+    fn on_length_mismatch(&self, declared: usize, expected: usize) -> Result<(), DecodeError> {
+        (**self).on_length_mismatch(declared, expected)
+    }
+
+    fn on_tail_padding(&self, offset: usize, block_len: usize) -> Result<(), DecodeError> {
+        (**self).on_tail_padding(offset, block_len)
+    }
+
+    fn max_block_len(&self) -> usize {
+        (**self).max_block_len()
+    }
+
+    fn max_fspec_len(&self) -> usize {
+        (**self).max_fspec_len()
+    }
+}
+
+thread_local! {
+    static POLICY: RefCell<Option<Rc<dyn DecodePolicy>>> = const { RefCell::new(None) };
+}
+
+/// Restores the previous policy when dropped, including on panic.
+struct Restore(Option<Rc<dyn DecodePolicy>>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        POLICY.with(|policy| *policy.borrow_mut() = previous);
+    }
+}
+
+/// Runs `f` with `policy` handling the anomalies decoded on this thread.
+///
+/// Calls nest: the previous policy is restored when `f` returns.  To keep
+/// state from the policy after the call, pass an [`Rc`] and keep a clone.
+pub fn with_policy<P: DecodePolicy + 'static, T>(policy: P, f: impl FnOnce() -> T) -> T {
+    let policy: Rc<dyn DecodePolicy> = Rc::new(policy);
+    let _restore = Restore(POLICY.with(|current| current.replace(Some(policy))));
+    f()
+}
+
+fn current() -> Option<Rc<dyn DecodePolicy>> {
+    POLICY.with(|policy| policy.borrow().clone())
+}
+
+/// Consults the policy about an enum value with no variant and returns the
+/// value to decode.  Called by generated code.
+pub fn unknown_enum(enum_name: &'static str, value: u8) -> Result<u8, DecodeError> {
+    match current() {
+        Some(policy) => policy.on_unknown_enum(enum_name, value),
+        None => Ok(value),
+    }
+}
+
+/// Checks decoded spare bits, consulting the policy if they are not zero.
+/// Called by generated code.
+#[inline]
+pub fn reserved_bits(bits: usize, value: u64) -> Result<(), DecodeError> {
+    if value == 0 {
+        return Ok(());
+    }
+    match current() {
+        Some(policy) => policy.on_nonzero_spare(bits, value),
+        None => Ok(()),
+    }
+}
+
+/// Checks the length byte of an explicit-length item, consulting the policy
+/// if it differs from the definition.  Called by generated code.
+#[inline]
+pub fn length(declared: usize, expected: usize) -> Result<(), DecodeError> {
+    if declared == expected {
+        return Ok(());
+    }
+    match current() {
+        Some(policy) => policy.on_length_mismatch(declared, expected),
+        None => Ok(()),
+    }
+}
+
+/// Checks the length of a data block, in bytes including its header,
+/// against the policy's maximum.  Called by generated code.
+#[inline]
+pub fn block_len(length: usize) -> Result<(), DecodeError> {
+    let max = POLICY.with(|policy| policy.borrow().as_ref().map_or(u16::MAX as usize, |p| p.max_block_len()));
+    if length > max {
+        return Err(DecodeError::LimitExceeded { limit: "data block length", max });
+    }
+    Ok(())
+}
+
+/// Checks the `rest` of a data block of `block_len` bytes, from byte
+/// `offset`, before a record is decoded from it, and returns whether a
+/// record follows.  Zero bytes hold no record: they are left over by a
+/// record decoded too short, or pad the block, and the policy decides
+/// whether to accept them.  Called by generated code.
+#[inline]
+pub fn block_rest(rest: &[u8], offset: usize, block_len: usize) -> Result<bool, DecodeError> {
+    // The first byte of a record is its FSPEC, which is not zero.
+    if !rest.iter().all(|&byte| byte == 0) {
+        return Ok(true);
+    }
+    match current() {
+        Some(policy) => policy.on_tail_padding(offset, block_len).map(|()| false),
+        None => Err(DecodeError::RecordUnderrun { offset, block_len }),
+    }
+}
+
+/// Returns the longest FSPEC, in bytes, allowed by the policy.
+#[inline]
+pub fn max_fspec_len() -> usize {
+    POLICY.with(|policy| policy.borrow().as_ref().map_or(DEFAULT_MAX_FSPEC_LEN, |p| p.max_fspec_len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[derive(Default)]
+    struct Counting {
+        anomalies: Cell<usize>,
+    }
+
+    impl DecodePolicy for Counting {
+        fn on_unknown_enum(&self, _: &'static str, _: u8) -> Result<u8, DecodeError> {
+            self.anomalies.set(self.anomalies.get() + 1);
+            Ok(0)
+        }
+
+        fn on_length_mismatch(&self, _: usize, _: usize) -> Result<(), DecodeError> {
+            Err(DecodeError::InvalidData("length mismatch"))
+        }
+    }
+
+    #[test]
+    fn policy_applies_only_within_scope() {
+        let counting = Rc::new(Counting::default());
+
+        with_policy(counting.clone(), || {
+            assert_eq!(unknown_enum("Kind", 9).unwrap(), 0);
+            assert!(length(4, 3).is_err());
+            assert!(length(3, 3).is_ok());
+            assert!(reserved_bits(3, 5).is_ok());
+        });
+
+        assert_eq!(counting.anomalies.get(), 1);
+        assert_eq!(unknown_enum("Kind", 9).unwrap(), 9);
+        assert!(length(4, 3).is_ok());
+    }
+
+    #[test]
+    fn nested_policies_restore_the_outer_one() {
+        struct Reject;
+        impl DecodePolicy for Reject {
+            fn on_nonzero_spare(&self, _: usize, _: u64) -> Result<(), DecodeError> {
+                Err(DecodeError::InvalidData("spare"))
+            }
+        }
+
+        with_policy(Reject, || {
+            with_policy(Counting::default(), || assert!(reserved_bits(1, 1).is_ok()));
+            assert!(reserved_bits(1, 1).is_err());
+        });
+        assert!(reserved_bits(1, 1).is_ok());
+    }
+
+    #[test]
+    fn limits_default_and_apply_within_scope() {
+        struct Small;
+        impl DecodePolicy for Small {
+            fn max_block_len(&self) -> usize {
+                100
+            }
+            fn max_fspec_len(&self) -> usize {
+                2
+            }
+        }
+
+        assert!(block_len(u16::MAX as usize).is_ok());
+        assert_eq!(max_fspec_len(), DEFAULT_MAX_FSPEC_LEN);
+        with_policy(Small, || {
+            assert!(block_len(100).is_ok());
+            assert!(matches!(block_len(101), Err(DecodeError::LimitExceeded { max: 100, .. })));
+            assert_eq!(max_fspec_len(), 2);
+        });
+    }
+
+    #[test]
+    fn tail_padding_is_left_to_the_policy() {
+        struct Padded;
+        impl DecodePolicy for Padded {
+            fn on_tail_padding(&self, _: usize, _: usize) -> Result<(), DecodeError> {
+                Ok(())
+            }
+        }
+
+        assert!(block_rest(&[0, 1], 4, 6).unwrap());
+        assert!(matches!(block_rest(&[0, 0], 4, 6), Err(DecodeError::RecordUnderrun { offset: 4, block_len: 6 })));
+        with_policy(Padded, || {
+            assert!(block_rest(&[0, 1], 4, 6).unwrap());
+            assert!(!block_rest(&[0, 0], 4, 6).unwrap());
+        });
+    }
+}
@@ -0,0 +1,806 @@
+//! Timestamped recordings of data blocks.
+//!
+//! A recording keeps every data block together with the time it was
+//! received, so traffic can be replayed at its original pace or lined up
+//! with other sources.  The format is a 4-byte magic, `RXR1`, followed by
+//! one entry per block:
+//!
+//! ```text
+//! [receive time: u64 big-endian, nanoseconds since the Unix epoch][data block]
+//! ```
+//!
+//! Blocks are stored whole, header included, and delimited by their LEN
+//! field.
+//!
+//! With the `zstd` feature, recordings can be written compressed with
+//! `RecordingWriter::compressed`, which matters at busy sites where raw
+//! captures reach tens of gigabytes a day.  `RecordingReader::decompressing`
+//! reads recordings whether they were compressed or not.
+//!
+//! [`MergedRecordings`] interleaves several recordings, e.g. separate
+//! captures of each sensor, into one in receive time order, and [`slice()`]
+//! cuts one down to an interval and a set of categories.
+//!
+//! With the `manifest` feature, archives can be checked for corruption
+//! before replay with a sidecar manifest, holding the length and SHA-256
+//! digest of every entry: written by `ManifestWriter` and checked by
+//! `verify_manifest`.  A manifest is text, a first line `RXM1` followed by
+//! one line per entry:
+//!
+//! ```text
+//! <block length> <SHA-256 of the entry, receive time included, in hex>
+//! ```
+//!
+//! # Example
+//!
+//! ```
+//! use std::time::{Duration, SystemTime};
+//! use rasterix_core::recording::{RecordingReader, RecordingWriter};
+//!
+//! let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+//! let mut writer = RecordingWriter::new(Vec::new()).unwrap();
+//! writer.write_block(time, &[0x30, 0x00, 0x06, 0x80, 0x01, 0x02]).unwrap();
+//! let bytes = writer.into_inner();
+//!
+//! let mut reader = RecordingReader::new(&bytes[..]).unwrap();
+//! let block = reader.next().unwrap().unwrap();
+//! assert_eq!(block.recv_time, time);
+//! assert_eq!(block.data, [0x30, 0x00, 0x06, 0x80, 0x01, 0x02]);
+//! assert!(reader.next().is_none());
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+#[cfg(any(feature = "zstd", feature = "manifest"))]
+use std::fmt;
+use std::io::{self, Read, Write};
+#[cfg(feature = "manifest")]
+use std::io::BufRead;
+#[cfg(feature = "zstd")]
+use std::io::{BufReader, Chain, Cursor};
+use std::time::{Duration, SystemTime};
+
+#[cfg(feature = "manifest")]
+use sha2::{Digest, Sha256};
+
+use crate::{Header, Timestamped};
+
+/// First bytes of every recording.
+pub const MAGIC: [u8; 4] = *b"RXR1";
+
+/// First line of every manifest.
+#[cfg(feature = "manifest")]
+pub const MANIFEST_MAGIC: &str = "RXM1";
+
+/// First bytes of every zstd frame.
+#[cfg(feature = "zstd")]
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Writes data blocks to a recording.
+#[derive(Debug)]
+pub struct RecordingWriter<W> {
+    inner: W,
+}
+
+impl<W: Write> RecordingWriter<W> {
+    /// Starts a recording by writing its magic to `inner`.
+    pub fn new(mut inner: W) -> io::Result<Self> {
+        inner.write_all(&MAGIC)?;
+        Ok(Self { inner })
+    }
+
+    /// Appends a data block received at `recv_time`.
+    ///
+    /// Fails with [`io::ErrorKind::InvalidInput`] if the LEN field of
+    /// `block` doesn't match its length, since the block could not be read
+    /// back, or if `recv_time` is before the Unix epoch.
+    pub fn write_block(&mut self, recv_time: SystemTime, block: &[u8]) -> io::Result<()> {
+        let [cat, hi, lo, ..] = *block else {
+            return Err(invalid_input("data block shorter than its header"));
+        };
+        if Header::from_bytes([cat, hi, lo]).length as usize != block.len() {
+            return Err(invalid_input("data block length does not match its LEN field"));
+        }
+        self.inner.write_all(&nanos(recv_time)?.to_be_bytes())?;
+        self.inner.write_all(block)
+    }
+
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Consumes the recording writer, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+#[cfg(feature = "zstd")]
+impl<W: Write> RecordingWriter<zstd::Encoder<'static, W>> {
+    /// Starts a recording compressed with zstd at `level`, from 1 to 22, or
+    /// 0 for the zstd default.
+    ///
+    /// The recording must be ended with [`finish`](Self::finish), or the
+    /// end of the compressed stream is lost.
+    pub fn compressed(inner: W, level: i32) -> io::Result<Self> {
+        Self::new(zstd::Encoder::new(inner, level)?)
+    }
+
+    /// Ends the compressed stream, returning the underlying writer.
+    pub fn finish(self) -> io::Result<W> {
+        self.inner.finish()
+    }
+}
+
+/// Input of a recording read with [`RecordingReader::decompressing`].
+#[cfg(feature = "zstd")]
+pub enum Decompressed<R> {
+    /// Recording stored as is.
+    Plain(Chain<Cursor<[u8; 4]>, R>),
+    /// Recording compressed with zstd.
+    Zstd(zstd::Decoder<'static, BufReader<Chain<Cursor<[u8; 4]>, R>>>),
+}
+
+#[cfg(feature = "zstd")]
+impl<R> fmt::Debug for Decompressed<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Decompressed::Plain(_) => f.write_str("Plain"),
+            Decompressed::Zstd(_) => f.write_str("Zstd"),
+        }
+    }
+}
+
+#[cfg(feature = "zstd")]
+impl<R: Read> Read for Decompressed<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Decompressed::Plain(inner) => inner.read(buf),
+            Decompressed::Zstd(inner) => inner.read(buf),
+        }
+    }
+}
+
+/// Reads the data blocks of a recording, in the order they were written.
+///
+/// Iteration ends at the end of the input.  An entry cut short, or whose
+/// LEN field is smaller than the block header, yields an error and ends
+/// iteration, since the following entries can't be located.
+#[derive(Debug)]
+pub struct RecordingReader<R> {
+    inner: R,
+    done: bool,
+}
+
+impl<R: Read> RecordingReader<R> {
+    /// Reads the magic of a recording from `inner`.
+    ///
+    /// Fails with [`io::ErrorKind::InvalidData`] if `inner` is not a
+    /// recording.
+    pub fn new(mut inner: R) -> io::Result<Self> {
+        let mut magic = [0u8; 4];
+        inner.read_exact(&mut magic).map_err(|_| invalid_data("not a rasterix recording"))?;
+        if magic != MAGIC {
+            return Err(invalid_data("not a rasterix recording"));
+        }
+        Ok(Self { inner, done: false })
+    }
+
+    /// Reads a recording from `inner`, decompressing it if it was written
+    /// with [`RecordingWriter::compressed`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::SystemTime;
+    /// use rasterix_core::recording::{RecordingReader, RecordingWriter};
+    ///
+    /// let block = [0x30, 0x00, 0x03];
+    /// let mut plain = RecordingWriter::new(Vec::new()).unwrap();
+    /// let mut compressed = RecordingWriter::compressed(Vec::new(), 0).unwrap();
+    /// plain.write_block(SystemTime::UNIX_EPOCH, &block).unwrap();
+    /// compressed.write_block(SystemTime::UNIX_EPOCH, &block).unwrap();
+    ///
+    /// for bytes in [plain.into_inner(), compressed.finish().unwrap()] {
+    ///     let mut reader = RecordingReader::decompressing(&bytes[..]).unwrap();
+    ///     assert_eq!(reader.next().unwrap().unwrap().data, block);
+    /// }
+    /// ```
+    #[cfg(feature = "zstd")]
+    pub fn decompressing(mut inner: R) -> io::Result<RecordingReader<Decompressed<R>>> {
+        let mut magic = [0u8; 4];
+        inner.read_exact(&mut magic).map_err(|_| invalid_data("not a rasterix recording"))?;
+        let input = Cursor::new(magic).chain(inner);
+        let input = if magic == ZSTD_MAGIC {
+            Decompressed::Zstd(zstd::Decoder::new(input)?)
+        } else {
+            Decompressed::Plain(input)
+        };
+        RecordingReader::new(input)
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Consumes the recording reader, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn read_entry(&mut self) -> io::Result<Option<Timestamped<Vec<u8>>>> {
+        let mut time = [0u8; 8];
+        let read = read_full(&mut self.inner, &mut time)?;
+        if read == 0 {
+            return Ok(None);
+        }
+        if read < time.len() {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "recording entry cut short"));
+        }
+
+        let mut header = [0u8; Header::SIZE];
+        self.inner.read_exact(&mut header)?;
+        let length = Header::from_bytes(header).length as usize;
+        if length < Header::SIZE {
+            return Err(invalid_data("data block length too small"));
+        }
+        let mut block = header.to_vec();
+        block.resize(length, 0);
+        self.inner.read_exact(&mut block[Header::SIZE..])?;
+
+        let recv_time = SystemTime::UNIX_EPOCH + Duration::from_nanos(u64::from_be_bytes(time));
+        Ok(Some(Timestamped::new(recv_time, block)))
+    }
+}
+
+impl<R: Read> Iterator for RecordingReader<R> {
+    type Item = io::Result<Timestamped<Vec<u8>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let entry = self.read_entry().transpose();
+        if !matches!(entry, Some(Ok(_))) {
+            self.done = true;
+        }
+        entry
+    }
+}
+
+/// A recording being merged, with the entries read ahead of the others.
+#[derive(Debug)]
+struct MergeSource<I> {
+    entries: I,
+    /// Entries read but not yet yielded, keyed by time then read order.
+    buffer: BinaryHeap<Reverse<(SystemTime, u64, Vec<u8>)>>,
+    /// Latest receive time read so far.
+    latest: Option<SystemTime>,
+    read: u64,
+    done: bool,
+}
+
+/// Merges several recordings into one, in receive time order.
+///
+/// Yields each block with the index of the recording it comes from.  Blocks
+/// received at the same time keep the order of their recordings.
+///
+/// Captures taken on different machines, or with jittery timestamps, may go
+/// back in time a little.  With a tolerance set by
+/// [`with_tolerance`](Self::with_tolerance), each recording is read ahead
+/// until its timestamps pass the next block by that much, so blocks that
+/// arrive up to the tolerance late are still yielded in order.  Later blocks
+/// are yielded as they come.
+///
+/// Errors of a recording are yielded as soon as they are read; the other
+/// recordings are merged on.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, SystemTime};
+/// use rasterix_core::recording::MergedRecordings;
+/// use rasterix_core::Timestamped;
+///
+/// let at = |secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+/// let north = vec![Ok(Timestamped::new(at(1), vec![1])), Ok(Timestamped::new(at(4), vec![4]))];
+/// let south = vec![Ok(Timestamped::new(at(2), vec![2])), Ok(Timestamped::new(at(3), vec![3]))];
+///
+/// let merged: Vec<_> = MergedRecordings::new([north.into_iter(), south.into_iter()])
+///     .map(|entry| entry.unwrap())
+///     .map(|(source, block)| (source, block.data[0]))
+///     .collect();
+/// assert_eq!(merged, [(0, 1), (1, 2), (1, 3), (0, 4)]);
+/// ```
+#[derive(Debug)]
+pub struct MergedRecordings<I> {
+    sources: Vec<MergeSource<I>>,
+    tolerance: Duration,
+}
+
+impl<I: Iterator<Item = io::Result<Timestamped<Vec<u8>>>>> MergedRecordings<I> {
+    /// Merges the entries of `recordings`, such as [`RecordingReader`]s.
+    pub fn new(recordings: impl IntoIterator<Item = I>) -> Self {
+        let sources = recordings
+            .into_iter()
+            .map(|entries| MergeSource { entries, buffer: BinaryHeap::new(), latest: None, read: 0, done: false })
+            .collect();
+        Self { sources, tolerance: Duration::ZERO }
+    }
+
+    /// Yields blocks in order even if their recording went back up to
+    /// `tolerance` in time before them.
+    pub fn with_tolerance(mut self, tolerance: Duration) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+}
+
+impl<I: Iterator<Item = io::Result<Timestamped<Vec<u8>>>>> Iterator for MergedRecordings<I> {
+    type Item = io::Result<(usize, Timestamped<Vec<u8>>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for source in &mut self.sources {
+            // Read ahead until no later entry can be due before the earliest
+            // one buffered.
+            while !source.done {
+                if let (Some(Reverse((head, ..))), Some(latest)) = (source.buffer.peek(), source.latest)
+                    && latest >= *head + self.tolerance
+                {
+                    break;
+                }
+                match source.entries.next() {
+                    Some(Ok(entry)) => {
+                        source.latest = source.latest.max(Some(entry.recv_time));
+                        source.buffer.push(Reverse((entry.recv_time, source.read, entry.data)));
+                        source.read += 1;
+                    }
+                    Some(Err(e)) => return Some(Err(e)),
+                    None => source.done = true,
+                }
+            }
+        }
+
+        let (index, _) = self
+            .sources
+            .iter()
+            .enumerate()
+            .filter_map(|(index, source)| source.buffer.peek().map(|Reverse((time, ..))| (index, *time)))
+            .min_by_key(|&(index, time)| (time, index))?;
+        let Reverse((recv_time, _, data)) = self.sources[index].buffer.pop()?;
+        Some(Ok((index, Timestamped::new(recv_time, data))))
+    }
+}
+
+/// Writes the manifest of a recording, one entry at a time.
+///
+/// Entries are added with the same receive times and blocks as written to
+/// the recording.
+#[cfg(feature = "manifest")]
+#[derive(Debug)]
+pub struct ManifestWriter<W> {
+    inner: W,
+}
+
+#[cfg(feature = "manifest")]
+impl<W: Write> ManifestWriter<W> {
+    /// Starts a manifest by writing its first line to `inner`.
+    pub fn new(mut inner: W) -> io::Result<Self> {
+        writeln!(inner, "{}", MANIFEST_MAGIC)?;
+        Ok(Self { inner })
+    }
+
+    /// Adds the entry of a data block received at `recv_time`.
+    ///
+    /// Fails with [`io::ErrorKind::InvalidInput`] if `recv_time` is before
+    /// the Unix epoch.
+    pub fn add_block(&mut self, recv_time: SystemTime, block: &[u8]) -> io::Result<()> {
+        let digest = entry_digest(recv_time, block)?;
+        writeln!(self.inner, "{} {}", block.len(), to_hex(&digest))
+    }
+
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    /// Consumes the manifest writer, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+/// Result of checking a recording against its manifest.
+#[cfg(feature = "manifest")]
+#[derive(Debug, Default)]
+pub struct ManifestReport {
+    /// Entries matching the manifest.
+    pub verified: usize,
+    /// Indices of the entries whose length or digest differ.
+    pub corrupt: Vec<usize>,
+    /// Manifest entries past the last entry read from the recording.
+    pub missing: usize,
+    /// Recording entries past the end of the manifest.
+    pub unlisted: usize,
+    /// Error that stopped reading the recording, with the index of the
+    /// entry being read.
+    pub read_error: Option<(usize, io::Error)>,
+}
+
+#[cfg(feature = "manifest")]
+impl ManifestReport {
+    /// Returns whether the recording matches its manifest exactly.
+    pub fn is_intact(&self) -> bool {
+        self.corrupt.is_empty() && self.missing == 0 && self.unlisted == 0 && self.read_error.is_none()
+    }
+}
+
+#[cfg(feature = "manifest")]
+impl fmt::Display for ManifestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some((index, error)) = &self.read_error {
+            writeln!(f, "entry {}: {}", index, error)?;
+        }
+        for index in &self.corrupt {
+            writeln!(f, "entry {}: does not match the manifest", index)?;
+        }
+        write!(
+            f,
+            "{} entry(ies) verified, {} corrupt, {} missing, {} not in the manifest",
+            self.verified,
+            self.corrupt.len(),
+            self.missing,
+            self.unlisted
+        )
+    }
+}
+
+/// Checks every entry of `recording` against `manifest`, as written by
+/// [`ManifestWriter`].
+///
+/// Fails only if the manifest can't be read; problems with the recording
+/// are listed in the report.
+///
+/// # Example
+///
+/// ```
+/// use std::time::SystemTime;
+/// use rasterix_core::recording::{verify_manifest, ManifestWriter, RecordingReader, RecordingWriter};
+///
+/// let block = [0x30, 0x00, 0x03];
+/// let mut recording = RecordingWriter::new(Vec::new()).unwrap();
+/// let mut manifest = ManifestWriter::new(Vec::new()).unwrap();
+/// recording.write_block(SystemTime::UNIX_EPOCH, &block).unwrap();
+/// manifest.add_block(SystemTime::UNIX_EPOCH, &block).unwrap();
+///
+/// let mut bytes = recording.into_inner();
+/// let manifest = manifest.into_inner();
+/// let report = verify_manifest(RecordingReader::new(&bytes[..]).unwrap(), &manifest[..]).unwrap();
+/// assert!(report.is_intact());
+///
+/// bytes[12] ^= 0x01;
+/// let report = verify_manifest(RecordingReader::new(&bytes[..]).unwrap(), &manifest[..]).unwrap();
+/// assert_eq!(report.corrupt, [0]);
+/// ```
+#[cfg(feature = "manifest")]
+pub fn verify_manifest<R: Read, M: BufRead>(recording: RecordingReader<R>, manifest: M) -> io::Result<ManifestReport> {
+    let mut lines = manifest.lines();
+    if lines.next().transpose()?.as_deref() != Some(MANIFEST_MAGIC) {
+        return Err(invalid_data("not a rasterix recording manifest"));
+    }
+    let mut expected = Vec::new();
+    for line in lines {
+        let line = line?;
+        let entry = line
+            .split_once(' ')
+            .and_then(|(length, digest)| Some((length.parse::<usize>().ok()?, from_hex(digest)?)));
+        expected.push(entry.ok_or_else(|| invalid_data("malformed manifest entry"))?);
+    }
+
+    let mut report = ManifestReport::default();
+    let mut read = 0;
+    for (index, entry) in recording.enumerate() {
+        let block = match entry {
+            Ok(block) => block,
+            Err(e) => {
+                report.read_error = Some((index, e));
+                break;
+            }
+        };
+        read += 1;
+        match expected.get(index) {
+            Some(&(length, digest)) => {
+                if length == block.data.len() && entry_digest(block.recv_time, &block.data)? == digest {
+                    report.verified += 1;
+                } else {
+                    report.corrupt.push(index);
+                }
+            }
+            None => report.unlisted += 1,
+        }
+    }
+    report.missing = expected.len().saturating_sub(read);
+    Ok(report)
+}
+
+/// Keeps the blocks of `input` received from `from` until before `to` whose
+/// category is one of `categories`, or of any category if `None`.
+///
+/// `input` yields recording entries, as [`RecordingReader`] does.  Errors
+/// are kept, and entries are read to the end of `input` since a recording
+/// need not be in time order.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, SystemTime};
+/// use rasterix_core::recording;
+/// use rasterix_core::Timestamped;
+///
+/// let at = |secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+/// let entries = vec![
+///     Ok(Timestamped::new(at(1), vec![48, 0, 3])),
+///     Ok(Timestamped::new(at(2), vec![62, 0, 3])),
+///     Ok(Timestamped::new(at(3), vec![48, 0, 3])),
+/// ];
+///
+/// let sliced: Vec<_> = recording::slice(entries, at(2), at(4), Some(&[48])).collect();
+/// assert_eq!(sliced.len(), 1);
+/// assert_eq!(sliced[0].as_ref().unwrap().recv_time, at(3));
+/// ```
+pub fn slice<'a, I>(
+    input: I,
+    from: SystemTime,
+    to: SystemTime,
+    categories: Option<&'a [u8]>,
+) -> impl Iterator<Item = io::Result<Timestamped<Vec<u8>>>> + 'a
+where
+    I: IntoIterator<Item = io::Result<Timestamped<Vec<u8>>>>,
+    I::IntoIter: 'a,
+{
+    input.into_iter().filter(move |entry| match entry {
+        Ok(block) => {
+            (from..to).contains(&block.recv_time)
+                && categories.is_none_or(|categories| block.data.first().is_some_and(|cat| categories.contains(cat)))
+        }
+        Err(_) => true,
+    })
+}
+
+/// Returns `recv_time` as stored: nanoseconds since the Unix epoch.
+fn nanos(recv_time: SystemTime) -> io::Result<u64> {
+    Ok(recv_time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_err(|_| invalid_input("receive time before the Unix epoch"))?
+        .as_nanos() as u64)
+}
+
+/// Returns the SHA-256 digest of a recording entry, as stored.
+#[cfg(feature = "manifest")]
+fn entry_digest(recv_time: SystemTime, block: &[u8]) -> io::Result<[u8; 32]> {
+    let mut hasher = Sha256::new();
+    hasher.update(nanos(recv_time)?.to_be_bytes());
+    hasher.update(block);
+    Ok(hasher.finalize().into())
+}
+
+#[cfg(feature = "manifest")]
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(feature = "manifest")]
+fn from_hex(text: &str) -> Option<[u8; 32]> {
+    if text.len() != 64 || !text.is_ascii() {
+        return None;
+    }
+    let mut bytes = [0u8; 32];
+    for (byte, pair) in bytes.iter_mut().zip(text.as_bytes().chunks(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()?;
+    }
+    Some(bytes)
+}
+
+/// Reads until `buf` is full or the input ends, returning the bytes read.
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut read = 0;
+    while read < buf.len() {
+        match reader.read(&mut buf[read..]) {
+            Ok(0) => break,
+            Ok(n) => read += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(read)
+}
+
+fn invalid_input(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+fn invalid_data(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLOCK: [u8; 6] = [0x30, 0x00, 0x06, 0x80, 0x01, 0x02];
+
+    fn at(nanos: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_nanos(nanos)
+    }
+
+    #[test]
+    fn blocks_keep_their_receive_times() {
+        let mut writer = RecordingWriter::new(Vec::new()).unwrap();
+        writer.write_block(at(1_500), &BLOCK).unwrap();
+        writer.write_block(at(1_700_000_000_123_456_789), &[0x01, 0x00, 0x03]).unwrap();
+        let bytes = writer.into_inner();
+
+        assert_eq!(&bytes[..12], b"RXR1\0\0\0\0\0\0\x05\xdc");
+        let blocks: Vec<_> = RecordingReader::new(&bytes[..]).unwrap().collect::<io::Result<_>>().unwrap();
+        assert_eq!(
+            blocks,
+            [Timestamped::new(at(1_500), BLOCK.to_vec()), Timestamped::new(at(1_700_000_000_123_456_789), vec![0x01, 0x00, 0x03])]
+        );
+    }
+
+    #[test]
+    fn blocks_must_match_their_length() {
+        let mut writer = RecordingWriter::new(Vec::new()).unwrap();
+
+        let err = writer.write_block(at(0), &BLOCK[..5]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(writer.write_block(at(0), &[0x30]).is_err());
+        assert_eq!(writer.get_ref().len(), MAGIC.len());
+    }
+
+    #[test]
+    fn truncated_entries_end_the_recording() {
+        let mut writer = RecordingWriter::new(Vec::new()).unwrap();
+        writer.write_block(at(0), &BLOCK).unwrap();
+        writer.write_block(at(0), &BLOCK).unwrap();
+        let bytes = writer.into_inner();
+
+        let mut reader = RecordingReader::new(&bytes[..bytes.len() - 1]).unwrap();
+        assert!(reader.next().unwrap().is_ok());
+        assert_eq!(reader.next().unwrap().unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn merge_tolerates_timestamps_going_back() {
+        let entries = |times: &[u64]| -> Vec<io::Result<Timestamped<Vec<u8>>>> {
+            times.iter().map(|&t| Ok(Timestamped::new(at(t), vec![t as u8]))).collect()
+        };
+        let merge = |tolerance| {
+            MergedRecordings::new([entries(&[10, 30, 25, 40]).into_iter(), entries(&[20, 28]).into_iter()])
+                .with_tolerance(Duration::from_nanos(tolerance))
+                .map(|entry| entry.unwrap().1.data[0])
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(merge(0), [10, 20, 28, 30, 25, 40]);
+        assert_eq!(merge(5), [10, 20, 25, 28, 30, 40]);
+    }
+
+    #[test]
+    fn merge_yields_errors_and_goes_on() {
+        let mut writer = RecordingWriter::new(Vec::new()).unwrap();
+        writer.write_block(at(1), &BLOCK).unwrap();
+        writer.write_block(at(3), &BLOCK).unwrap();
+        let bytes = writer.into_inner();
+
+        let readers = [
+            RecordingReader::new(&bytes[..]).unwrap(),
+            RecordingReader::new(&bytes[..bytes.len() - 1]).unwrap(),
+        ];
+        let results: Vec<_> = MergedRecordings::new(readers).collect();
+
+        // The second recording fails when its second block is read.
+        assert_eq!(results.len(), 4);
+        assert!(results[2].is_err());
+        let sources: Vec<_> = results.iter().filter_map(|r| r.as_ref().ok()).map(|(source, _)| *source).collect();
+        assert_eq!(sources, [0, 1, 0]);
+    }
+
+    #[test]
+    fn slice_keeps_interval_and_categories() {
+        let mut writer = RecordingWriter::new(Vec::new()).unwrap();
+        for (time, cat) in [(10, 48), (20, 48), (20, 62), (30, 1), (40, 48)] {
+            writer.write_block(at(time), &[cat, 0x00, 0x03]).unwrap();
+        }
+        let bytes = writer.into_inner();
+        let sliced = |categories| -> Vec<(u64, u8)> {
+            slice(RecordingReader::new(&bytes[..]).unwrap(), at(20), at(40), categories)
+                .map(|entry| entry.unwrap())
+                .map(|block| (block.recv_time.duration_since(SystemTime::UNIX_EPOCH).unwrap().as_nanos() as u64, block.data[0]))
+                .collect()
+        };
+
+        assert_eq!(sliced(None), [(20, 48), (20, 62), (30, 1)]);
+        assert_eq!(sliced(Some(&[48, 1])), [(20, 48), (30, 1)]);
+    }
+
+    #[cfg(feature = "manifest")]
+    #[test]
+    fn manifest_entries_hold_the_digest_of_the_entry() {
+        let mut manifest = ManifestWriter::new(Vec::new()).unwrap();
+        manifest.add_block(at(1), &BLOCK).unwrap();
+
+        assert_eq!(
+            String::from_utf8(manifest.into_inner()).unwrap(),
+            "RXM1\n6 134b8c1e8e136f6f09c74633d362200313fcda4bf592a8f4ffc8b936b10923ac\n"
+        );
+    }
+
+    #[cfg(feature = "manifest")]
+    #[test]
+    fn manifest_finds_corrupt_and_missing_entries() {
+        let mut recording = RecordingWriter::new(Vec::new()).unwrap();
+        let mut manifest = ManifestWriter::new(Vec::new()).unwrap();
+        for time in [1, 2, 3] {
+            recording.write_block(at(time), &BLOCK).unwrap();
+            manifest.add_block(at(time), &BLOCK).unwrap();
+        }
+        let bytes = recording.into_inner();
+        let manifest = manifest.into_inner();
+        let verify = |bytes: &[u8]| verify_manifest(RecordingReader::new(bytes).unwrap(), &manifest[..]).unwrap();
+
+        assert!(verify(&bytes).is_intact());
+        assert_eq!(verify(&bytes).verified, 3);
+
+        // A changed receive time is caught as well as a changed block.
+        let mut changed = bytes.clone();
+        changed[11] ^= 0x01;
+        changed[bytes.len() - 1] ^= 0x01;
+        let report = verify(&changed);
+        assert_eq!((report.verified, report.corrupt.clone()), (1, vec![0, 2]));
+
+        let report = verify(&bytes[..bytes.len() - 1]);
+        assert_eq!(report.verified, 2);
+        assert_eq!(report.read_error.as_ref().map(|(index, _)| *index), Some(2));
+        assert_eq!(report.missing, 1);
+        assert_eq!(report.to_string().lines().last(), Some("2 entry(ies) verified, 0 corrupt, 1 missing, 0 not in the manifest"));
+
+        assert!(verify_manifest(RecordingReader::new(&bytes[..]).unwrap(), &b"RXM1\n6 zz\n"[..]).is_err());
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn compressed_recording_round_trips() {
+        let mut writer = RecordingWriter::compressed(Vec::new(), 3).unwrap();
+        for time in 0..1000 {
+            writer.write_block(at(time), &BLOCK).unwrap();
+        }
+        let bytes = writer.finish().unwrap();
+        assert!(bytes.len() < 1000 * (8 + BLOCK.len()) / 10);
+
+        let blocks: Vec<_> = RecordingReader::decompressing(&bytes[..]).unwrap().collect::<io::Result<_>>().unwrap();
+        assert_eq!(blocks.len(), 1000);
+        assert_eq!(blocks[999], Timestamped::new(at(999), BLOCK.to_vec()));
+
+        // A compressed stream cut short fails like a plain recording, at
+        // its start or at the entry cut short.
+        let cut = RecordingReader::decompressing(&bytes[..bytes.len() / 2]);
+        assert!(cut.map_or(true, |reader| reader.last().unwrap().is_err()));
+        assert!(RecordingReader::decompressing(&b"RXR2"[..]).is_err());
+    }
+
+    #[test]
+    fn other_input_is_rejected() {
+        let err = RecordingReader::new(&BLOCK[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(RecordingReader::new(&b"RX"[..]).is_err());
+    }
+}
@@ -0,0 +1,271 @@
+use std::io::{self, Read};
+
+use crate::stream::Header;
+
+/// Result of a successful resynchronization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Resync {
+    /// Number of bytes discarded before the header that was found.
+    pub skipped: usize,
+    /// The plausible data block header found after the skipped bytes.
+    pub header: Header,
+}
+
+/// Searches corrupted input for the next plausible data block header.
+///
+/// ASTERIX has no sync word, so after a decode error the only way back into
+/// a stream is to look for three bytes that *could* be a `CAT`+`LEN` header.
+/// A candidate is plausible when:
+///
+/// - its category is one of the expected categories;
+/// - its length lies between [`min_length`](Self::with_min_length) and
+///   [`max_length`](Self::with_max_length);
+/// - with [cross-checking](Self::with_cross_check) enabled (the default), the
+///   block is followed either by the end of the input or by another
+///   plausible header.
+///
+/// # Example
+///
+/// ```
+/// use rasterix_core::ResyncScanner;
+///
+/// let scanner = ResyncScanner::new([48]);
+/// // Two garbage bytes, then a 4-byte CAT048 block.
+/// let data = [0xDE, 0xAD, 48, 0x00, 0x04, 0x80];
+///
+/// let resync = scanner.find(&data).unwrap();
+/// assert_eq!(resync.skipped, 2);
+/// assert_eq!(resync.header.category, 48);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ResyncScanner {
+    categories: Vec<u8>,
+    min_length: u16,
+    max_length: u16,
+    cross_check: bool,
+}
+
+impl ResyncScanner {
+    /// Default minimum block length: a header plus a one-byte FSPEC.
+    pub const DEFAULT_MIN_LENGTH: u16 = Header::SIZE as u16 + 1;
+
+    /// Creates a scanner accepting blocks of the given categories.
+    pub fn new(categories: impl IntoIterator<Item = u8>) -> Self {
+        Self {
+            categories: categories.into_iter().collect(),
+            min_length: Self::DEFAULT_MIN_LENGTH,
+            max_length: u16::MAX,
+            cross_check: true,
+        }
+    }
+
+    /// Sets the smallest block length considered plausible.
+    ///
+    /// Values below the header size are raised to it.
+    pub fn with_min_length(mut self, min_length: u16) -> Self {
+        self.min_length = min_length.max(Header::SIZE as u16);
+        self
+    }
+
+    /// Sets the largest block length considered plausible.
+    ///
+    /// Real feeds rarely send blocks near the 65535-byte limit, so a tighter
+    /// bound rejects many false candidates.
+    pub fn with_max_length(mut self, max_length: u16) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    /// Enables or disables checking the header that follows a candidate.
+    pub fn with_cross_check(mut self, cross_check: bool) -> Self {
+        self.cross_check = cross_check;
+        self
+    }
+
+    /// Returns `true` if `header` passes the category and length checks.
+    pub fn is_plausible(&self, header: &Header) -> bool {
+        self.categories.contains(&header.category)
+            && header.length >= self.min_length
+            && header.length <= self.max_length
+    }
+
+    /// Searches `data` for the first plausible header.
+    ///
+    /// The end of `data` is treated as the end of the input.  Returns `None`
+    /// if no plausible header is found.
+    pub fn find(&self, data: &[u8]) -> Option<Resync> {
+        let mut buf = data.to_vec();
+        self.scan(&mut io::empty(), &mut buf).ok().flatten()
+    }
+
+    /// Searches `buf`, then `reader`, for the first plausible header.
+    ///
+    /// `buf` holds bytes already read from the input; more are read from
+    /// `reader` as needed.  On success, the skipped bytes are removed from
+    /// `buf`, which then starts with the header found (and may already
+    /// contain part of the following data).  On `Ok(None)` the input ended
+    /// without a plausible header and `buf` holds every byte scanned.
+    pub fn scan<R: Read>(
+        &self,
+        reader: &mut R,
+        buf: &mut Vec<u8>,
+    ) -> io::Result<Option<Resync>> {
+        let mut offset = 0;
+
+        loop {
+            if !fill(reader, buf, offset + Header::SIZE)? {
+                return Ok(None);
+            }
+
+            let header = header_at(buf, offset);
+            if self.is_plausible(&header) && self.confirm(reader, buf, offset, &header)? {
+                buf.drain(..offset);
+                return Ok(Some(Resync { skipped: offset, header }));
+            }
+
+            offset += 1;
+        }
+    }
+
+    /// Cross-checks a candidate against the header that follows it.
+    fn confirm<R: Read>(
+        &self,
+        reader: &mut R,
+        buf: &mut Vec<u8>,
+        offset: usize,
+        header: &Header,
+    ) -> io::Result<bool> {
+        if !self.cross_check {
+            return Ok(true);
+        }
+
+        let end = offset + header.length as usize;
+        if fill(reader, buf, end + Header::SIZE)? {
+            Ok(self.is_plausible(&header_at(buf, end)))
+        } else {
+            // The input ended: only a block ending exactly at the end of the
+            // input is acceptable.
+            Ok(buf.len() == end)
+        }
+    }
+}
+
+/// Reads from `reader` until `buf` holds at least `len` bytes.
+///
+/// Returns `false` if the input ends first.
+fn fill<R: Read>(reader: &mut R, buf: &mut Vec<u8>, len: usize) -> io::Result<bool> {
+    if buf.len() >= len {
+        return Ok(true);
+    }
+    let missing = (len - buf.len()) as u64;
+    reader.take(missing).read_to_end(buf)?;
+    Ok(buf.len() >= len)
+}
+
+fn header_at(buf: &[u8], offset: usize) -> Header {
+    Header::from_bytes([buf[offset], buf[offset + 1], buf[offset + 2]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn block(category: u8, records: &[u8]) -> Vec<u8> {
+        let len = (Header::SIZE + records.len()) as u16;
+        let mut bytes = vec![category];
+        bytes.extend_from_slice(&len.to_be_bytes());
+        bytes.extend_from_slice(records);
+        bytes
+    }
+
+    #[test]
+    fn finds_block_at_start() {
+        let scanner = ResyncScanner::new([48]);
+        let data = block(48, &[0x80, 0x01]);
+
+        let resync = scanner.find(&data).unwrap();
+        assert_eq!(resync.skipped, 0);
+        assert_eq!(resync.header, Header { category: 48, length: 5 });
+    }
+
+    #[test]
+    fn skips_garbage() {
+        let scanner = ResyncScanner::new([48]);
+        let mut data = vec![0x00, 0xFF, 0x12];
+        data.extend(block(48, &[0x80]));
+
+        assert_eq!(scanner.find(&data).unwrap().skipped, 3);
+    }
+
+    #[test]
+    fn rejects_unknown_category() {
+        let scanner = ResyncScanner::new([48]);
+        assert!(scanner.find(&block(34, &[0x80])).is_none());
+    }
+
+    #[test]
+    fn rejects_lengths_out_of_range() {
+        let scanner = ResyncScanner::new([48]).with_max_length(8);
+
+        assert!(!scanner.is_plausible(&Header { category: 48, length: 3 }));
+        assert!(scanner.is_plausible(&Header { category: 48, length: 4 }));
+        assert!(scanner.is_plausible(&Header { category: 48, length: 8 }));
+        assert!(!scanner.is_plausible(&Header { category: 48, length: 9 }));
+    }
+
+    #[test]
+    fn cross_check_rejects_false_header() {
+        // Garbage containing a byte pattern that looks like a CAT048 header
+        // but isn't followed by another header.
+        let scanner = ResyncScanner::new([48]);
+        let mut data = vec![48, 0x00, 0x04, 0xAA, 0xBB];
+        data.extend(block(48, &[0x80]));
+        data.extend(block(48, &[0x80]));
+
+        assert_eq!(scanner.find(&data).unwrap().skipped, 5);
+    }
+
+    #[test]
+    fn cross_check_disabled_accepts_first_candidate() {
+        let scanner = ResyncScanner::new([48]).with_cross_check(false);
+        let mut data = vec![48, 0x00, 0x04, 0xAA, 0xBB];
+        data.extend(block(48, &[0x80]));
+
+        assert_eq!(scanner.find(&data).unwrap().skipped, 0);
+    }
+
+    #[test]
+    fn cross_check_rejects_block_past_end_of_input() {
+        let scanner = ResyncScanner::new([48]);
+        let mut data = block(48, &[0x80, 0x01]);
+        data.pop();
+
+        assert!(scanner.find(&data).is_none());
+    }
+
+    #[test]
+    fn scan_reads_from_reader_and_drains_skipped_bytes() {
+        let scanner = ResyncScanner::new([48]);
+        let mut data = vec![0x01, 0x02];
+        data.extend(block(48, &[0x80]));
+        data.extend(block(48, &[0x40]));
+
+        let mut buf = vec![0xEE];
+        let mut reader = Cursor::new(data);
+        let resync = scanner.scan(&mut reader, &mut buf).unwrap().unwrap();
+
+        assert_eq!(resync.skipped, 3);
+        assert_eq!(&buf[..4], &block(48, &[0x80])[..]);
+    }
+
+    #[test]
+    fn scan_returns_none_at_end_of_input() {
+        let scanner = ResyncScanner::new([48]);
+        let mut buf = Vec::new();
+        let mut reader = Cursor::new(vec![1, 2, 3, 4, 5]);
+
+        assert!(scanner.scan(&mut reader, &mut buf).unwrap().is_none());
+        assert_eq!(buf.len(), 5);
+    }
+}
@@ -0,0 +1,1122 @@
+use std::collections::HashSet;
+use std::io::{self, Cursor, Read};
+use std::marker::PhantomData;
+
+use crate::metrics::StreamMetrics;
+use crate::time::{Clock, Timestamped};
+use crate::{policy, trace};
+use crate::{BitReader, Decode, DecodeError, ResyncScanner};
+
+/// Header of an ASTERIX data block.
+///
+/// Wire format: `[CAT: 1 byte][LEN: 2 bytes big-endian]`, where `LEN` counts
+/// the whole block including the header itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Header {
+    pub category: u8,
+    pub length: u16,
+}
+
+impl Header {
+    /// Size of the header on the wire, in bytes.
+    pub const SIZE: usize = 3;
+
+    /// Builds a header from its three wire bytes.
+    pub fn from_bytes(bytes: [u8; 3]) -> Self {
+        Self {
+            category: bytes[0],
+            length: u16::from_be_bytes([bytes[1], bytes[2]]),
+        }
+    }
+
+    /// Returns the number of record bytes following the header.
+    ///
+    /// Returns `0` for malformed headers whose length is smaller than the
+    /// header itself.
+    pub fn payload_len(&self) -> usize {
+        (self.length as usize).saturating_sub(Self::SIZE)
+    }
+}
+
+/// Handling of zero bytes after the last record of a data block.
+///
+/// Some senders pad data blocks with trailing zero bytes.  A zero byte can't
+/// start a record, since a record starts with its FSPEC, so a block whose
+/// remaining bytes are all zero has no more records.  A tail holding any
+/// non-zero byte is always decoded as records, so malformed padding still
+/// fails to decode whatever the mode.
+///
+/// Generated `DataBlock` decoders leave padding to the thread's
+/// [`DecodePolicy`](crate::policy::DecodePolicy) alone; a stream consults
+/// it too, and its mode can accept padding the policy rejects.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TailPadding {
+    /// Leaves the padding to the policy, whose error is yielded.  Without a
+    /// policy, the error is [`DecodeError::RecordUnderrun`].  The padding is
+    /// discarded either way.
+    #[default]
+    Reject,
+    /// Discards the padding silently.
+    IgnoreZeros,
+    /// Discards the padding and keeps a copy, available from
+    /// [`RecordStream::padding`] until the next padded block.
+    Capture,
+}
+
+/// Limits checked by a [`RecordStream`] on each data block header, before
+/// any of the block's records is decoded.
+///
+/// A block breaking a limit yields an error and is skipped, so reading
+/// resumes with the next block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamConfig {
+    /// Categories expected in the stream.  Blocks of other categories yield
+    /// an error instead of being skipped silently.  `None` allows any.
+    pub allowed_categories: Option<HashSet<u8>>,
+    /// Longest data block accepted, in bytes including its header.
+    pub max_block_len: usize,
+    /// Most records decoded from a single data block.  Further records
+    /// yield an error and the rest of the block is discarded.
+    pub max_records_per_block: usize,
+}
+
+impl StreamConfig {
+    /// Allows only the data blocks of `categories`.
+    pub fn allow_categories(mut self, categories: impl IntoIterator<Item = u8>) -> Self {
+        self.allowed_categories = Some(categories.into_iter().collect());
+        self
+    }
+
+    /// Checks a block header against the limits.
+    fn check(&self, header: &Header) -> Result<(), DecodeError> {
+        if let Some(allowed) = &self.allowed_categories
+            && !allowed.contains(&header.category)
+        {
+            return Err(DecodeError::InvalidData("data block of a category not allowed"));
+        }
+        if header.length as usize > self.max_block_len {
+            return Err(DecodeError::LimitExceeded { limit: "data block length", max: self.max_block_len });
+        }
+        Ok(())
+    }
+}
+
+impl Default for StreamConfig {
+    /// Allows every category, block length and record count.
+    fn default() -> Self {
+        Self {
+            allowed_categories: None,
+            max_block_len: u16::MAX as usize,
+            max_records_per_block: usize::MAX,
+        }
+    }
+}
+
+/// A record type that belongs to a single ASTERIX category.
+///
+/// Implemented by every generated `Record` so that [`RecordStream`] knows
+/// which data blocks to decode and which to skip.
+pub trait CategoryRecord: Decode {
+    /// The ASTERIX category identifier of this record type.
+    const CATEGORY: u8;
+}
+
+/// Iterator over the records of one category in a stream of data blocks.
+///
+/// `RecordStream` reads data blocks from any [`Read`] source, decodes every
+/// record of category [`T::CATEGORY`](CategoryRecord::CATEGORY) and yields it
+/// together with the header of the block it came from.  Blocks of other
+/// categories are skipped.
+///
+/// Errors don't end the stream where recovery is possible:
+///
+/// - a record that fails to decode yields an error and the rest of its block
+///   is discarded, since record boundaries inside it are lost;
+/// - a header with an impossible length (smaller than the header itself)
+///   yields an error, is discarded, and reading resumes right after it.
+///
+/// With a [`ResyncScanner`] attached (see [`with_resync`](Self::with_resync)),
+/// any header the scanner finds implausible yields an error and the stream
+/// skips forward to the next plausible header instead; the number of bytes
+/// skipped so far is available from [`skipped_bytes`](Self::skipped_bytes).
+///
+/// A record running past the end of its block yields a
+/// [`DecodeError::RecordOverrun`] error.  Zero bytes after the last record
+/// are handled as configured with [`with_tail_padding`](Self::with_tail_padding),
+/// by default as the thread's decode policy says, which without a policy is
+/// yielding a [`DecodeError::RecordUnderrun`] error.
+///
+/// I/O errors (including a block truncated by the end of the input) yield a
+/// final error, after which the iterator is exhausted.
+///
+/// Blocks can be rejected from their header alone, by category or length,
+/// with a [`StreamConfig`] (see [`with_config`](Self::with_config)).
+///
+/// Blocks, records, errors and skipped bytes are reported to the
+/// [`StreamMetrics`] attached with [`with_metrics`](Self::with_metrics).
+///
+/// # Example
+///
+/// ```ignore
+/// use rasterix::rcore::RecordStream;
+///
+/// for result in RecordStream::<cat048::Record, _>::new(tcp_stream) {
+///     let (header, record) = result?;
+///     println!("{} bytes: {:?}", header.length, record);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct RecordStream<T, R, M = ()> {
+    reader: R,
+    metrics: M,
+    scanner: Option<ResyncScanner>,
+    config: StreamConfig,
+    tail_padding: TailPadding,
+    /// Trailing zero bytes of the last padded block, when captured.
+    padding: Vec<u8>,
+    /// Bytes read ahead of the current block while resynchronizing.
+    pending: Vec<u8>,
+    skipped: u64,
+    blocks: u64,
+    /// Input offset of the next data block header.
+    offset: u64,
+    /// Header and payload of the block currently being decoded.
+    header: Option<Header>,
+    payload: Vec<u8>,
+    position: usize,
+    /// Records decoded from the current block.
+    block_records: usize,
+    done: bool,
+    _record: PhantomData<fn() -> T>,
+}
+
+impl<T: CategoryRecord, R: Read> RecordStream<T, R> {
+    /// Creates a stream reading data blocks from `reader`.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            metrics: (),
+            offset: 0,
+            scanner: None,
+            config: StreamConfig::default(),
+            tail_padding: TailPadding::default(),
+            padding: Vec::new(),
+            pending: Vec::new(),
+            skipped: 0,
+            blocks: 0,
+            header: None,
+            payload: Vec::new(),
+            position: 0,
+            block_records: 0,
+            done: false,
+            _record: PhantomData,
+        }
+    }
+}
+
+impl<T: CategoryRecord, R: Read> RecordStream<T, R> {
+    /// Resumes the stream saved in `checkpoint`, reading the rest of the
+    /// input from `reader`.
+    ///
+    /// `reader` must continue where the checkpointed stream's reader
+    /// stopped, i.e. at [`StreamCheckpoint::input_position`] of the same
+    /// input.  The resync scanner, configuration, tail padding mode and
+    /// metrics are not part of the checkpoint and must be attached again.
+    ///
+    /// Fails if the checkpoint was taken from a stream of another category.
+    pub fn resume(reader: R, checkpoint: StreamCheckpoint) -> Result<Self, DecodeError> {
+        if checkpoint.category != T::CATEGORY {
+            return Err(DecodeError::InvalidData("checkpoint of a stream of another category"));
+        }
+        Ok(Self {
+            reader,
+            metrics: (),
+            offset: checkpoint.offset,
+            scanner: None,
+            config: StreamConfig::default(),
+            tail_padding: TailPadding::default(),
+            padding: Vec::new(),
+            pending: checkpoint.pending,
+            skipped: checkpoint.skipped,
+            blocks: checkpoint.blocks,
+            header: checkpoint.header,
+            payload: checkpoint.payload,
+            position: checkpoint.position,
+            block_records: checkpoint.block_records,
+            done: checkpoint.done,
+            _record: PhantomData,
+        })
+    }
+}
+
+impl<T: CategoryRecord, R: Read, M: StreamMetrics> RecordStream<T, R, M> {
+    /// Reports stream events to `metrics`, replacing any attached before.
+    pub fn with_metrics<N: StreamMetrics>(self, metrics: N) -> RecordStream<T, R, N> {
+        RecordStream {
+            reader: self.reader,
+            metrics,
+            scanner: self.scanner,
+            config: self.config,
+            tail_padding: self.tail_padding,
+            padding: self.padding,
+            pending: self.pending,
+            skipped: self.skipped,
+            blocks: self.blocks,
+            offset: self.offset,
+            header: self.header,
+            payload: self.payload,
+            position: self.position,
+            block_records: self.block_records,
+            done: self.done,
+            _record: PhantomData,
+        }
+    }
+
+    /// Returns the attached metrics.
+    pub fn metrics(&self) -> &M {
+        &self.metrics
+    }
+
+    /// Resynchronizes with `scanner` after implausible headers.
+    pub fn with_resync(mut self, scanner: ResyncScanner) -> Self {
+        self.scanner = Some(scanner);
+        self
+    }
+
+    /// Checks every block header against the limits of `config`.
+    pub fn with_config(mut self, config: StreamConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Handles zero bytes after the last record of a block as `mode` says.
+    pub fn with_tail_padding(mut self, mode: TailPadding) -> Self {
+        self.tail_padding = mode;
+        self
+    }
+
+    /// Returns the padding of the last padded block, if captured with
+    /// [`TailPadding::Capture`].
+    pub fn padding(&self) -> &[u8] {
+        &self.padding
+    }
+
+    /// Returns the total number of bytes discarded while resynchronizing.
+    pub fn skipped_bytes(&self) -> u64 {
+        self.skipped
+    }
+
+    /// Returns the number of data blocks of category `T` read so far.
+    pub fn blocks_read(&self) -> u64 {
+        self.blocks
+    }
+
+    /// Stamps every record with the time its data block was read.
+    ///
+    /// All records of a block share the same timestamp, taken from `clock`
+    /// as soon as the whole block has been received.
+    pub fn timestamped<C: Clock>(self, clock: C) -> TimestampedRecords<T, R, C, M> {
+        TimestampedRecords {
+            inner: self,
+            clock,
+            block_time: None,
+        }
+    }
+
+    /// Saves the decoding state, so the stream can be [resumed](RecordStream::resume)
+    /// after a restart without losing block alignment.
+    ///
+    /// The checkpoint holds the current block, with the position and count
+    /// of the records decoded from it, and any bytes read ahead while
+    /// resynchronizing; the input itself must be reopened at
+    /// [`StreamCheckpoint::input_position`].
+    pub fn checkpoint(&self) -> StreamCheckpoint {
+        // The whole block is kept, so that errors are placed and records
+        // counted from its start after resuming.
+        let (payload, position, block_records) = match self.header {
+            Some(_) => (self.payload.clone(), self.position, self.block_records),
+            None => (Vec::new(), 0, 0),
+        };
+        StreamCheckpoint {
+            category: T::CATEGORY,
+            offset: self.offset,
+            skipped: self.skipped,
+            blocks: self.blocks,
+            header: self.header,
+            payload,
+            position,
+            block_records,
+            pending: self.pending.clone(),
+            done: self.done,
+        }
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Consumes the stream and returns the underlying reader.
+    ///
+    /// Any bytes of a partially decoded block, or read ahead while
+    /// resynchronizing, are lost.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Reads the next block header, or `None` at a clean end of input.
+    fn read_header(&mut self) -> io::Result<Option<Header>> {
+        let mut bytes = [0u8; Header::SIZE];
+        let mut filled = 0;
+
+        while filled < Header::SIZE {
+            match self.read_input(&mut bytes[filled..]) {
+                Ok(0) if filled == 0 => return Ok(None),
+                Ok(0) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "truncated data block header",
+                    ));
+                }
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(Some(Header::from_bytes(bytes)))
+    }
+
+    /// Reads from the pending bytes first, then from the reader.
+    fn read_input(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pending.is_empty() {
+            return self.reader.read(buf);
+        }
+        let n = buf.len().min(self.pending.len());
+        buf[..n].copy_from_slice(&self.pending[..n]);
+        self.pending.drain(..n);
+        Ok(n)
+    }
+
+    /// Fills `buf` completely from the pending bytes and the reader.
+    fn read_exact_input(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        let n = self.read_input(buf)?;
+        if n < buf.len() {
+            self.reader.read_exact(&mut buf[n..])?;
+        }
+        Ok(())
+    }
+
+    /// Discards `len` bytes from the pending bytes and the reader.
+    fn skip_input(&mut self, mut len: usize) -> io::Result<()> {
+        let mut buf = [0u8; 256];
+        while len > 0 {
+            let n = len.min(buf.len());
+            self.read_exact_input(&mut buf[..n])?;
+            len -= n;
+        }
+        Ok(())
+    }
+
+    /// Skips forward to the next header the scanner finds plausible.
+    fn resync(&mut self, header: Header) -> io::Result<()> {
+        let Some(scanner) = &self.scanner else {
+            return Ok(());
+        };
+
+        // Restart the search one byte into the rejected header.
+        let length = header.length.to_be_bytes();
+        let mut buf = vec![length[0], length[1]];
+        buf.append(&mut self.pending);
+
+        match scanner.scan(&mut self.reader, &mut buf)? {
+            Some(resync) => {
+                let skipped = 1 + resync.skipped as u64;
+                self.skipped += skipped;
+                self.offset += skipped;
+                self.metrics.bytes_skipped(skipped);
+                self.pending = buf;
+            }
+            None => {
+                let skipped = 1 + buf.len() as u64;
+                self.skipped += skipped;
+                self.offset += skipped;
+                self.metrics.bytes_skipped(skipped);
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the next block of category `T` into the payload buffer.
+    fn next_block(&mut self) -> Option<Result<(), DecodeError>> {
+        loop {
+            let header = match self.read_header() {
+                Ok(Some(header)) => header,
+                Ok(None) => return None,
+                Err(e) => return Some(Err(e.into())),
+            };
+
+            if let Some(scanner) = &self.scanner {
+                if !scanner.is_plausible(&header) {
+                    let error = DecodeError::InvalidData("implausible data block header");
+                    trace::header_error(self.offset, &error);
+                    if let Err(e) = self.resync(header) {
+                        return Some(Err(e.into()));
+                    }
+                    return Some(Err(error));
+                }
+            } else if (header.length as usize) < Header::SIZE {
+                let error = DecodeError::InvalidData("data block length too small");
+                trace::header_error(self.offset, &error);
+                self.offset += Header::SIZE as u64;
+                return Some(Err(error));
+            }
+
+            if let Err(error) = self.config.check(&header) {
+                trace::header_error(self.offset, &error);
+                let result = self.skip_input(header.payload_len());
+                self.offset += header.length as u64;
+                if let Err(e) = result {
+                    return Some(Err(e.into()));
+                }
+                return Some(Err(error));
+            }
+
+            let mut payload = std::mem::take(&mut self.payload);
+            payload.resize(header.payload_len(), 0);
+            let result = self.read_exact_input(&mut payload);
+            self.payload = payload;
+            if let Err(e) = result {
+                return Some(Err(e.into()));
+            }
+            self.metrics.block_read(&header);
+            self.offset += header.length as u64;
+
+            if header.category == T::CATEGORY {
+                self.blocks += 1;
+                self.header = Some(header);
+                self.position = 0;
+                self.block_records = 0;
+                return Some(Ok(()));
+            }
+        }
+    }
+}
+
+impl<T: CategoryRecord, R: Read, M: StreamMetrics> Iterator for RecordStream<T, R, M> {
+    type Item = Result<(Header, T), DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.done {
+                return None;
+            }
+
+            if let Some(header) = self.header {
+                if self.position < self.payload.len() {
+                    let rest = &self.payload[self.position..];
+                    let block_offset = Header::SIZE + self.position;
+                    let mut cursor = Cursor::new(rest);
+                    let result = match policy::block_rest(rest, block_offset, header.length as usize) {
+                        Ok(true) if self.block_records == self.config.max_records_per_block => {
+                            Err(DecodeError::LimitExceeded {
+                                limit: "records per data block",
+                                max: self.config.max_records_per_block,
+                            })
+                        }
+                        Ok(true) => T::decode(&mut BitReader::new(&mut cursor))
+                            .map_err(|e| e.in_record(block_offset, header.length as usize)),
+                        Err(e) if self.tail_padding == TailPadding::Reject => Err(e),
+                        // Padding accepted by the policy, or by the stream's mode
+                        Ok(false) | Err(_) => {
+                            if self.tail_padding == TailPadding::Capture {
+                                self.padding.clear();
+                                self.padding.extend_from_slice(rest);
+                            }
+                            self.header = None;
+                            continue;
+                        }
+                    };
+                    return Some(match result {
+                        Ok(record) => {
+                            let end = self.position + cursor.position() as usize;
+                            self.metrics.record_decoded(T::CATEGORY, &self.payload[self.position..end]);
+                            self.position = end;
+                            self.block_records += 1;
+                            Ok((header, record))
+                        }
+                        Err(e) => {
+                            let block_start = self.offset - header.length as u64;
+                            let offset = block_start + (Header::SIZE + self.position) as u64;
+                            trace::record_error(T::CATEGORY, offset, &e);
+                            self.metrics.decode_error(Some(T::CATEGORY), &e);
+                            self.header = None;
+                            Err(e)
+                        }
+                    });
+                }
+                self.header = None;
+            }
+
+            match self.next_block() {
+                Some(Ok(())) => {}
+                Some(Err(e)) => {
+                    self.metrics.decode_error(None, &e);
+                    // Only a bad or rejected header can be recovered from; I/O errors end
+                    // the stream.
+                    self.done = matches!(e, DecodeError::Io(_));
+                    return Some(Err(e));
+                }
+                None => {
+                    self.done = true;
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+/// Saved state of a [`RecordStream`], taken with
+/// [`checkpoint`](RecordStream::checkpoint).
+///
+/// A checkpoint converts to and from bytes, to be stored by long-running
+/// services and restored with [`RecordStream::resume`]:
+///
+/// ```
+/// use rasterix_core::{RecordStream, StreamCheckpoint};
+/// # use rasterix_core::{BitReader, CategoryRecord, Decode, DecodeError};
+/// # struct Record;
+/// # impl Decode for Record {
+/// #     fn decode<R: std::io::Read>(reader: &mut BitReader<R>) -> Result<Self, DecodeError> {
+/// #         reader.read_bits(8)?;
+/// #         Ok(Record)
+/// #     }
+/// # }
+/// # impl CategoryRecord for Record { const CATEGORY: u8 = 48; }
+///
+/// // A CAT048 block of two one-byte records.
+/// let input = [48, 0x00, 0x05, 0x01, 0x02];
+/// let mut records = RecordStream::<Record, _>::new(&input[..]);
+/// records.next();
+/// let saved = records.checkpoint().to_bytes();
+///
+/// // After a restart, the rest of the block is still decoded.
+/// let checkpoint = StreamCheckpoint::from_bytes(&saved).unwrap();
+/// let position = checkpoint.input_position() as usize;
+/// let records = RecordStream::<Record, _>::resume(&input[position..], checkpoint).unwrap();
+/// assert_eq!(records.count(), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamCheckpoint {
+    category: u8,
+    offset: u64,
+    skipped: u64,
+    blocks: u64,
+    header: Option<Header>,
+    /// Payload of the current block.
+    payload: Vec<u8>,
+    /// Position in `payload` of the next record.
+    position: usize,
+    /// Records decoded from the current block.
+    block_records: usize,
+    pending: Vec<u8>,
+    done: bool,
+}
+
+impl StreamCheckpoint {
+    const MAGIC: &'static [u8; 4] = b"RXCK";
+    const VERSION: u8 = 2;
+
+    /// Returns the number of input bytes the stream had read: the offset
+    /// at which to reopen the input when resuming.
+    pub fn input_position(&self) -> u64 {
+        self.offset + self.pending.len() as u64
+    }
+
+    /// Returns the number of data blocks of the category read before the
+    /// checkpoint.
+    pub fn blocks_read(&self) -> u64 {
+        self.blocks
+    }
+
+    /// Serializes the checkpoint into a versioned binary form.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(64 + self.payload.len() + self.pending.len());
+        bytes.extend_from_slice(Self::MAGIC);
+        bytes.push(Self::VERSION);
+        bytes.push(self.category);
+        bytes.push(self.done as u8);
+        let (position, block_records) = (self.position as u64, self.block_records as u64);
+        for value in [self.offset, self.skipped, self.blocks, position, block_records] {
+            bytes.extend_from_slice(&value.to_be_bytes());
+        }
+        match self.header {
+            Some(header) => {
+                bytes.push(1);
+                bytes.push(header.category);
+                bytes.extend_from_slice(&header.length.to_be_bytes());
+            }
+            None => bytes.extend_from_slice(&[0; 4]),
+        }
+        for buf in [&self.payload, &self.pending] {
+            bytes.extend_from_slice(&(buf.len() as u32).to_be_bytes());
+            bytes.extend_from_slice(buf);
+        }
+        bytes
+    }
+
+    /// Reads a checkpoint serialized with [`to_bytes`](Self::to_bytes).
+    ///
+    /// Fails on bytes that aren't a checkpoint of a supported version.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let invalid = || DecodeError::InvalidData("invalid stream checkpoint");
+        let mut rest = bytes;
+        let mut take = |n: usize| -> Result<&[u8], DecodeError> {
+            let (head, tail) = rest.split_at_checked(n).ok_or_else(invalid)?;
+            rest = tail;
+            Ok(head)
+        };
+        let u64_at = |bytes: &[u8]| u64::from_be_bytes(bytes.try_into().unwrap());
+
+        if take(4)? != Self::MAGIC {
+            return Err(invalid());
+        }
+        if take(1)?[0] != Self::VERSION {
+            return Err(DecodeError::InvalidData("unsupported stream checkpoint version"));
+        }
+        let [category, done] = take(2)?.try_into().unwrap();
+        let offset = u64_at(take(8)?);
+        let skipped = u64_at(take(8)?);
+        let blocks = u64_at(take(8)?);
+        let position = usize::try_from(u64_at(take(8)?)).map_err(|_| invalid())?;
+        let block_records = usize::try_from(u64_at(take(8)?)).map_err(|_| invalid())?;
+        let header = match take(4)? {
+            [0, ..] => None,
+            [1, category, length @ ..] => Some(Header::from_bytes([*category, length[0], length[1]])),
+            _ => return Err(invalid()),
+        };
+        let mut buf = || -> Result<Vec<u8>, DecodeError> {
+            let len = u32::from_be_bytes(take(4)?.try_into().unwrap());
+            Ok(take(len as usize)?.to_vec())
+        };
+        let payload = buf()?;
+        let pending = buf()?;
+        if !rest.is_empty() || done > 1 || position > payload.len() {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            category,
+            offset,
+            skipped,
+            blocks,
+            header,
+            payload,
+            position,
+            block_records,
+            pending,
+            done: done == 1,
+        })
+    }
+}
+
+/// Iterator over timestamped records, created by
+/// [`RecordStream::timestamped`].
+#[derive(Debug)]
+pub struct TimestampedRecords<T, R, C, M = ()> {
+    inner: RecordStream<T, R, M>,
+    clock: C,
+    block_time: Option<std::time::SystemTime>,
+}
+
+impl<T, R, C, M> TimestampedRecords<T, R, C, M> {
+    /// Returns a reference to the wrapped stream.
+    pub fn get_ref(&self) -> &RecordStream<T, R, M> {
+        &self.inner
+    }
+
+    /// Consumes the adapter and returns the wrapped stream.
+    pub fn into_inner(self) -> RecordStream<T, R, M> {
+        self.inner
+    }
+}
+
+impl<T: CategoryRecord, R: Read, C: Clock, M: StreamMetrics> Iterator for TimestampedRecords<T, R, C, M> {
+    type Item = Result<Timestamped<(Header, T)>, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let blocks = self.inner.blocks;
+        let item = self.inner.next()?;
+
+        let recv_time = match self.block_time {
+            Some(time) if self.inner.blocks == blocks => time,
+            _ => *self.block_time.insert(self.clock.now()),
+        };
+
+        Some(item.map(|data| Timestamped::new(recv_time, data)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test record: a single byte, with `0xFF` rejected as invalid.
+    #[derive(Debug, PartialEq)]
+    struct Byte(u8);
+
+    impl Decode for Byte {
+        fn decode<R: Read>(reader: &mut BitReader<R>) -> Result<Self, DecodeError> {
+            match reader.read_bits(8)? as u8 {
+                0xFF => Err(DecodeError::InvalidData("bad record")),
+                value => Ok(Byte(value)),
+            }
+        }
+    }
+
+    impl CategoryRecord for Byte {
+        const CATEGORY: u8 = 48;
+    }
+
+    fn block(category: u8, records: &[u8]) -> Vec<u8> {
+        let len = (Header::SIZE + records.len()) as u16;
+        let mut bytes = vec![category];
+        bytes.extend_from_slice(&len.to_be_bytes());
+        bytes.extend_from_slice(records);
+        bytes
+    }
+
+    fn stream(data: Vec<u8>) -> RecordStream<Byte, Cursor<Vec<u8>>> {
+        RecordStream::new(Cursor::new(data))
+    }
+
+    #[test]
+    fn header_from_bytes() {
+        let header = Header::from_bytes([48, 0x01, 0x02]);
+        assert_eq!(header.category, 48);
+        assert_eq!(header.length, 0x0102);
+        assert_eq!(header.payload_len(), 0x0102 - 3);
+    }
+
+    #[test]
+    fn empty_input_yields_nothing() {
+        assert!(stream(Vec::new()).next().is_none());
+    }
+
+    #[test]
+    fn yields_all_records_of_all_blocks() {
+        let mut data = block(48, &[1, 2]);
+        data.extend(block(48, &[3]));
+
+        let records: Vec<_> = stream(data).map(|r| r.unwrap()).collect();
+        let values: Vec<_> = records.iter().map(|(_, r)| r.0).collect();
+
+        assert_eq!(values, vec![1, 2, 3]);
+        assert_eq!(records[0].0, Header { category: 48, length: 5 });
+        assert_eq!(records[2].0, Header { category: 48, length: 4 });
+    }
+
+    #[test]
+    fn skips_other_categories() {
+        let mut data = block(1, &[9, 9]);
+        data.extend(block(48, &[7]));
+
+        let values: Vec<_> = stream(data).map(|r| r.unwrap().1 .0).collect();
+        assert_eq!(values, vec![7]);
+    }
+
+    #[test]
+    fn empty_block_yields_nothing() {
+        let mut data = block(48, &[]);
+        data.extend(block(48, &[5]));
+
+        let values: Vec<_> = stream(data).map(|r| r.unwrap().1 .0).collect();
+        assert_eq!(values, vec![5]);
+    }
+
+    #[test]
+    fn record_error_discards_rest_of_block() {
+        let mut data = block(48, &[1, 0xFF, 2]);
+        data.extend(block(48, &[3]));
+
+        let results: Vec<_> = stream(data).collect();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap().1, Byte(1));
+        assert!(matches!(results[1], Err(DecodeError::InvalidData(_))));
+        assert_eq!(results[2].as_ref().unwrap().1, Byte(3));
+    }
+
+    #[test]
+    fn tail_padding_is_rejected_by_default() {
+        let mut data = block(48, &[1, 0, 0]);
+        data.extend(block(48, &[2]));
+
+        let results: Vec<_> = stream(data).collect();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap().1, Byte(1));
+        assert!(matches!(results[1], Err(DecodeError::RecordUnderrun { offset: 4, block_len: 6 })));
+        assert_eq!(results[2].as_ref().unwrap().1, Byte(2));
+    }
+
+    #[test]
+    fn tail_padding_can_be_ignored_or_captured() {
+        let mut data = block(48, &[1, 0, 0]);
+        data.extend(block(48, &[2]));
+
+        let values: Vec<_> = stream(data.clone())
+            .with_tail_padding(TailPadding::IgnoreZeros)
+            .map(|r| r.unwrap().1 .0)
+            .collect();
+        assert_eq!(values, vec![1, 2]);
+
+        let mut records = stream(data).with_tail_padding(TailPadding::Capture);
+        assert_eq!(records.next().unwrap().unwrap().1, Byte(1));
+        assert!(records.padding().is_empty());
+        assert_eq!(records.next().unwrap().unwrap().1, Byte(2));
+        assert_eq!(records.padding(), [0, 0]);
+        assert!(records.next().is_none());
+    }
+
+    #[test]
+    fn tail_padding_is_left_to_the_policy() {
+        use crate::policy::{with_policy, DecodePolicy};
+
+        struct Padded;
+        impl DecodePolicy for Padded {
+            fn on_tail_padding(&self, _: usize, _: usize) -> Result<(), DecodeError> {
+                Ok(())
+            }
+        }
+
+        let mut data = block(48, &[1, 0, 0]);
+        data.extend(block(48, &[2]));
+
+        let values: Vec<_> = with_policy(Padded, || stream(data).map(|r| r.unwrap().1 .0).collect());
+        assert_eq!(values, vec![1, 2]);
+    }
+
+    #[test]
+    fn config_rejects_blocks_by_header() {
+        let mut data = block(62, &[9]);
+        data.extend(block(48, &[1, 2, 3, 4]));
+        data.extend(block(48, &[5]));
+
+        let config = StreamConfig { max_block_len: 6, ..StreamConfig::default() }.allow_categories([48]);
+        let results: Vec<_> = stream(data).with_config(config).collect();
+
+        assert_eq!(results.len(), 3);
+        assert!(matches!(results[0], Err(DecodeError::InvalidData(_))));
+        assert!(matches!(results[1], Err(DecodeError::LimitExceeded { limit: "data block length", max: 6 })));
+        assert_eq!(results[2].as_ref().unwrap().1, Byte(5));
+    }
+
+    #[test]
+    fn config_limits_records_per_block() {
+        let mut data = block(48, &[1, 2, 3]);
+        data.extend(block(48, &[4]));
+
+        let config = StreamConfig { max_records_per_block: 2, ..StreamConfig::default() };
+        let results: Vec<_> = stream(data).with_config(config).collect();
+
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[1].as_ref().unwrap().1, Byte(2));
+        assert!(matches!(results[2], Err(DecodeError::LimitExceeded { limit: "records per data block", max: 2 })));
+        assert_eq!(results[3].as_ref().unwrap().1, Byte(4));
+    }
+
+    #[test]
+    fn resynchronizes_after_bad_header() {
+        let mut data = vec![48, 0x00, 0x01];
+        data.extend(block(48, &[4]));
+
+        let mut records = stream(data);
+        assert!(matches!(records.next(), Some(Err(DecodeError::InvalidData(_)))));
+        assert_eq!(records.next().unwrap().unwrap().1, Byte(4));
+        assert!(records.next().is_none());
+    }
+
+    #[test]
+    fn resync_skips_garbage_between_blocks() {
+        let mut data = block(48, &[1]);
+        data.extend([0xDE, 0xAD, 0xBE]);
+        data.extend(block(48, &[2]));
+        data.extend(block(48, &[3]));
+
+        let mut records = stream(data).with_resync(ResyncScanner::new([48]));
+        assert_eq!(records.next().unwrap().unwrap().1, Byte(1));
+        assert!(matches!(records.next(), Some(Err(DecodeError::InvalidData(_)))));
+        assert_eq!(records.next().unwrap().unwrap().1, Byte(2));
+        assert_eq!(records.next().unwrap().unwrap().1, Byte(3));
+        assert!(records.next().is_none());
+        assert_eq!(records.skipped_bytes(), 3);
+    }
+
+    #[test]
+    fn resync_counts_trailing_garbage() {
+        let mut data = block(48, &[1]);
+        data.extend([0xDE, 0xAD, 0xBE, 0xEF]);
+
+        let mut records = stream(data).with_resync(ResyncScanner::new([48]));
+        assert_eq!(records.next().unwrap().unwrap().1, Byte(1));
+        assert!(records.next().unwrap().is_err());
+        assert!(records.next().is_none());
+        assert_eq!(records.skipped_bytes(), 4);
+    }
+
+    #[test]
+    fn metrics_count_blocks_records_and_errors() {
+        use crate::FeedCounters;
+
+        let mut data = block(1, &[9, 9]);
+        data.extend(block(48, &[0x80, 0xFF, 2]));
+        data.extend([0xDE, 0xAD]);
+        data.extend(block(48, &[0x01]));
+
+        let mut counters = FeedCounters::new();
+        let results: Vec<_> = stream(data)
+            .with_resync(ResyncScanner::new([1, 48]))
+            .with_metrics(&mut counters)
+            .collect();
+
+        assert_eq!(results.len(), 4);
+        assert_eq!(counters.blocks, [(1, 1), (48, 2)].into());
+        assert_eq!(counters.bytes, [(1, 5), (48, 10)].into());
+        assert_eq!(counters.records, [(48, 2)].into());
+        assert_eq!(counters.errors, [(Some(48), 1), (None, 1)].into());
+        assert_eq!(counters.item_presence, [((48, 0), 1)].into());
+        assert_eq!(counters.skipped_bytes, 2);
+    }
+
+    #[test]
+    fn timestamped_records_share_block_time() {
+        use std::time::{Duration, SystemTime};
+
+        let mut data = block(48, &[1, 2]);
+        data.extend(block(48, &[3]));
+
+        let mut seconds = 0;
+        let clock = move || {
+            seconds += 1;
+            SystemTime::UNIX_EPOCH + Duration::from_secs(seconds)
+        };
+
+        let stamped: Vec<_> = stream(data)
+            .timestamped(clock)
+            .map(|r| r.unwrap())
+            .map(|t| (t.recv_time, t.data.1 .0))
+            .collect();
+
+        let at = |s| SystemTime::UNIX_EPOCH + Duration::from_secs(s);
+        assert_eq!(stamped, vec![(at(1), 1), (at(1), 2), (at(2), 3)]);
+    }
+
+    /// Checkpoints `records` and resumes it on the rest of `data`, through
+    /// the serialized form.
+    fn restart(records: RecordStream<Byte, Cursor<Vec<u8>>>, data: &[u8]) -> RecordStream<Byte, Cursor<Vec<u8>>> {
+        let checkpoint = StreamCheckpoint::from_bytes(&records.checkpoint().to_bytes()).unwrap();
+        let rest = data[checkpoint.input_position() as usize..].to_vec();
+        RecordStream::resume(Cursor::new(rest), checkpoint).unwrap()
+    }
+
+    #[test]
+    fn resumes_from_checkpoint_mid_block() {
+        let mut data = block(48, &[1, 2, 3]);
+        data.extend(block(48, &[4]));
+
+        let mut records = stream(data.clone());
+        assert_eq!(records.next().unwrap().unwrap().1, Byte(1));
+
+        let records = restart(records, &data);
+        assert_eq!(records.blocks_read(), 1);
+        let records: Vec<_> = records.map(|r| r.unwrap()).collect();
+        let values: Vec<_> = records.iter().map(|(_, r)| r.0).collect();
+        assert_eq!(values, [2, 3, 4]);
+        assert_eq!(records[0].0, Header { category: 48, length: 6 });
+    }
+
+    #[test]
+    fn resumes_mid_block_with_offsets_and_counts_from_block_start() {
+        let mut data = block(48, &[1, 2, 0, 0]);
+        data.extend(block(48, &[3, 4, 5]));
+
+        let mut records = stream(data.clone());
+        assert_eq!(records.next().unwrap().unwrap().1, Byte(1));
+
+        // The padding after record 2 is at byte 5 of the first block.
+        let mut records = restart(records, &data);
+        assert_eq!(records.next().unwrap().unwrap().1, Byte(2));
+        assert!(matches!(records.next(), Some(Err(DecodeError::RecordUnderrun { offset: 5, block_len: 7 }))));
+
+        // The record decoded before the checkpoint counts towards the limit.
+        let data = block(48, &[1, 2, 3]);
+        let config = StreamConfig { max_records_per_block: 2, ..StreamConfig::default() };
+        let mut records = stream(data.clone()).with_config(config.clone());
+        assert_eq!(records.next().unwrap().unwrap().1, Byte(1));
+
+        let results: Vec<_> = restart(records, &data).with_config(config).collect();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].as_ref().unwrap().1, Byte(2));
+        assert!(matches!(results[1], Err(DecodeError::LimitExceeded { limit: "records per data block", max: 2 })));
+    }
+
+    #[test]
+    fn resumes_from_checkpoint_while_resynchronizing() {
+        let mut data = block(48, &[1]);
+        data.extend([0xDE, 0xAD, 0xBE]);
+        data.extend(block(48, &[2]));
+        data.extend(block(48, &[3]));
+
+        let mut records = stream(data.clone()).with_resync(ResyncScanner::new([48]));
+        assert_eq!(records.next().unwrap().unwrap().1, Byte(1));
+        assert!(records.next().unwrap().is_err());
+
+        // The block found by the scanner was read ahead, and is kept.
+        let records = restart(records, &data).with_resync(ResyncScanner::new([48]));
+        assert_eq!(records.skipped_bytes(), 3);
+        let values: Vec<_> = records.map(|r| r.unwrap().1 .0).collect();
+        assert_eq!(values, [2, 3]);
+    }
+
+    #[test]
+    fn checkpoint_rejects_invalid_bytes_and_other_categories() {
+        let checkpoint = stream(block(48, &[1])).checkpoint();
+        let bytes = checkpoint.to_bytes();
+
+        assert!(StreamCheckpoint::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(StreamCheckpoint::from_bytes(b"not a checkpoint").is_err());
+
+        struct Other;
+        impl Decode for Other {
+            fn decode<R: Read>(_: &mut BitReader<R>) -> Result<Self, DecodeError> {
+                Ok(Other)
+            }
+        }
+        impl CategoryRecord for Other {
+            const CATEGORY: u8 = 62;
+        }
+        assert!(RecordStream::<Other, _>::resume(io::empty(), checkpoint).is_err());
+    }
+
+    #[test]
+    fn truncated_block_ends_stream() {
+        let mut data = block(48, &[1, 2, 3]);
+        data.truncate(4);
+
+        let mut records = stream(data);
+        assert!(matches!(records.next(), Some(Err(DecodeError::Io(_)))));
+        assert!(records.next().is_none());
+    }
+
+    #[test]
+    fn truncated_header_ends_stream() {
+        let mut data = block(48, &[1]);
+        data.extend([48, 0x00]);
+
+        let mut records = stream(data);
+        assert_eq!(records.next().unwrap().unwrap().1, Byte(1));
+        assert!(matches!(records.next(), Some(Err(DecodeError::Io(_)))));
+        assert!(records.next().is_none());
+    }
+}
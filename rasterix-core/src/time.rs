@@ -0,0 +1,140 @@
+use std::time::{Instant, SystemTime};
+
+/// Source of receive timestamps.
+///
+/// Implemented by [`SystemClock`], [`MonotonicClock`] and any
+/// `FnMut() -> SystemTime` closure, so tests and replays can inject their own
+/// notion of time.
+pub trait Clock {
+    /// Returns the current time.
+    fn now(&mut self) -> SystemTime;
+}
+
+/// Wall clock time, as reported by [`SystemTime::now`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&mut self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Wall clock time that never goes backwards.
+///
+/// Reads the system time once, at creation, and advances it with a monotonic
+/// [`Instant`] afterwards, so timestamps are unaffected by NTP steps or manual
+/// clock changes while the clock is in use.
+#[derive(Debug, Clone, Copy)]
+pub struct MonotonicClock {
+    origin: SystemTime,
+    start: Instant,
+}
+
+impl MonotonicClock {
+    /// Creates a clock anchored at the current system time.
+    pub fn new() -> Self {
+        Self::anchored_at(SystemTime::now())
+    }
+
+    /// Creates a clock that reports `origin` now and advances from there.
+    pub fn anchored_at(origin: SystemTime) -> Self {
+        Self {
+            origin,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for MonotonicClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for MonotonicClock {
+    fn now(&mut self) -> SystemTime {
+        self.origin + self.start.elapsed()
+    }
+}
+
+impl<F: FnMut() -> SystemTime> Clock for F {
+    fn now(&mut self) -> SystemTime {
+        self()
+    }
+}
+
+/// A value paired with the time it was received.
+///
+/// ASTERIX items carry time of day only for some categories, and never the
+/// arrival time at the receiver; downstream fusion usually needs the latter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Timestamped<T> {
+    pub recv_time: SystemTime,
+    pub data: T,
+}
+
+impl<T> Timestamped<T> {
+    /// Pairs `data` with `recv_time`.
+    pub fn new(recv_time: SystemTime, data: T) -> Self {
+        Self { recv_time, data }
+    }
+
+    /// Stamps `data` with the current time of `clock`.
+    pub fn now<C: Clock + ?Sized>(clock: &mut C, data: T) -> Self {
+        Self::new(clock.now(), data)
+    }
+
+    /// Transforms the data, keeping the timestamp.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Timestamped<U> {
+        Timestamped {
+            recv_time: self.recv_time,
+            data: f(self.data),
+        }
+    }
+
+    /// Discards the timestamp.
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn closure_clock() {
+        let mut t = SystemTime::UNIX_EPOCH;
+        let mut clock = move || {
+            t += Duration::from_secs(1);
+            t
+        };
+
+        assert_eq!(clock.now(), SystemTime::UNIX_EPOCH + Duration::from_secs(1));
+        assert_eq!(clock.now(), SystemTime::UNIX_EPOCH + Duration::from_secs(2));
+    }
+
+    #[test]
+    fn monotonic_clock_starts_at_origin() {
+        let origin = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
+        let mut clock = MonotonicClock::anchored_at(origin);
+
+        let first = clock.now();
+        let second = clock.now();
+
+        assert!(first >= origin);
+        assert!(second >= first);
+        assert!(first < origin + Duration::from_secs(60));
+    }
+
+    #[test]
+    fn timestamped_now_and_map() {
+        let mut clock = || SystemTime::UNIX_EPOCH;
+        let stamped = Timestamped::now(&mut clock, 21u8).map(|v| v as u16 * 2);
+
+        assert_eq!(stamped.recv_time, SystemTime::UNIX_EPOCH);
+        assert_eq!(stamped.into_inner(), 42u16);
+    }
+}
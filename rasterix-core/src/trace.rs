@@ -0,0 +1,165 @@
+//! Optional [`tracing`](https://docs.rs/tracing) instrumentation of the
+//! decode paths.
+//!
+//! With the `tracing` feature enabled, generated code and the stream decoder
+//! report wire-level failures as events, with the category, the item and the
+//! byte offset at which the failing record starts:
+//!
+//! | Event | Level | Fields |
+//! |-------|-------|--------|
+//! | An item failed to decode | `DEBUG` | `category`, `item`, `error` |
+//! | A record failed to decode | `WARN` | `category`, `offset`, `error` |
+//! | A data block header was rejected | `WARN` | `offset`, `error` |
+//!
+//! Generated data block decoding runs inside a `decode_block` span with the
+//! block's `category` and `length`.  Offsets are relative to the start of
+//! the data block for generated code, and to the start of the input for a
+//! [`RecordStream`](crate::RecordStream).
+//!
+//! Without the feature these functions are empty and compile away.
+
+use crate::DecodeError;
+
+/// Reports that `item` of a `category` record failed to decode.
+#[inline(always)]
+pub fn item_error(category: u8, item: &'static str, error: &DecodeError) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(category, item, %error, "failed to decode item");
+    #[cfg(not(feature = "tracing"))]
+    let _ = (category, item, error);
+}
+
+/// Reports that the `category` record starting at byte `offset` failed to
+/// decode.
+#[inline(always)]
+pub fn record_error(category: u8, offset: u64, error: &DecodeError) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(category, offset, %error, "failed to decode record");
+    #[cfg(not(feature = "tracing"))]
+    let _ = (category, offset, error);
+}
+
+/// Reports that the data block header at byte `offset` was rejected.
+#[inline(always)]
+pub fn header_error(offset: u64, error: &DecodeError) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(offset, %error, "rejected data block header");
+    #[cfg(not(feature = "tracing"))]
+    let _ = (offset, error);
+}
+
+/// Guard of the span covering the decoding of a data block.
+///
+/// The span is exited when the guard is dropped.
+#[must_use = "the span is exited when the guard is dropped"]
+pub struct BlockSpan {
+    #[cfg(feature = "tracing")]
+    _span: tracing::span::EnteredSpan,
+}
+
+/// Enters the span covering the decoding of a data block.
+#[inline(always)]
+pub fn block_span(category: u8, length: u16) -> BlockSpan {
+    #[cfg(feature = "tracing")]
+    return BlockSpan {
+        _span: tracing::debug_span!("decode_block", category, length).entered(),
+    };
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = (category, length);
+        BlockSpan {}
+    }
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    /// Subscriber that records the fields of every event as text.
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    struct Fields(String);
+
+    impl Visit for Fields {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0.push_str(&format!("{}={:?} ", field.name(), value));
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, _: &Attributes<'_>) -> Id {
+            Id::from_u64(1)
+        }
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+        fn event(&self, event: &Event<'_>) {
+            let mut fields = Fields(String::new());
+            event.record(&mut fields);
+            self.0.lock().unwrap().push(fields.0.trim_end().to_string());
+        }
+        fn enter(&self, _: &Id) {}
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn failures_are_reported_with_their_location() {
+        let recorder = Recorder::default();
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let _span = block_span(48, 10);
+            item_error(48, "Item020", &DecodeError::InvalidData("bad"));
+            record_error(48, 7, &DecodeError::InvalidData("bad"));
+        });
+
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            [
+                "message=failed to decode item category=48 item=\"Item020\" error=Invalid data: bad",
+                "message=failed to decode record category=48 offset=7 error=Invalid data: bad",
+            ]
+        );
+    }
+
+    #[test]
+    fn stream_reports_input_offsets() {
+        use crate::{BitReader, CategoryRecord, Decode, RecordStream, ResyncScanner};
+        use std::io::{Cursor, Read};
+
+        /// A single byte, with `0xFF` rejected.
+        struct Byte;
+
+        impl Decode for Byte {
+            fn decode<R: Read>(reader: &mut BitReader<R>) -> Result<Self, DecodeError> {
+                match reader.read_bits(8)? {
+                    0xFF => Err(DecodeError::InvalidData("bad")),
+                    _ => Ok(Byte),
+                }
+            }
+        }
+
+        impl CategoryRecord for Byte {
+            const CATEGORY: u8 = 48;
+        }
+
+        // Garbage at 4, then a block at 6 whose second record is bad.
+        let data = vec![48, 0, 4, 1, 0xEE, 0xEE, 48, 0, 5, 2, 0xFF];
+        let recorder = Recorder::default();
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let stream = RecordStream::<Byte, _>::new(Cursor::new(data))
+                .with_resync(ResyncScanner::new([48]));
+            assert_eq!(stream.count(), 4);
+        });
+
+        let events = recorder.0.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(events[0].contains("offset=4"), "{}", events[0]);
+        assert!(events[1].contains("offset=10"), "{}", events[1]);
+    }
+}
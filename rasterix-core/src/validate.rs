@@ -0,0 +1,125 @@
+//! Semantic checks of decoded values.
+//!
+//! Decoding only checks the structure of a message.  Fields defined with
+//! `min`, `max` or `multiple_of` constraints are checked by the generated
+//! [`Validate`] impls, so applications can reject implausible values:
+//!
+//! ```ignore
+//! let record = Record::decode(&mut reader)?;
+//! if let Err(errors) = record.validate() {
+//!     for error in errors {
+//!         eprintln!("{}", error); // item040.gsp: 1200 is above the maximum 1000
+//!     }
+//! }
+//! ```
+
+use std::fmt;
+
+/// A constraint on the raw value of a field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Constraint {
+    /// The value is at least this.
+    Min(u64),
+    /// The value is at most this.
+    Max(u64),
+    /// The value is a multiple of this.
+    MultipleOf(u64),
+}
+
+impl Constraint {
+    /// Returns whether `value` satisfies the constraint.
+    pub fn accepts(&self, value: u64) -> bool {
+        match *self {
+            Constraint::Min(min) => value >= min,
+            Constraint::Max(max) => value <= max,
+            Constraint::MultipleOf(n) => value.is_multiple_of(n),
+        }
+    }
+}
+
+/// A field whose value violates a constraint of its definition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// Dotted path of the field, e.g. `item040.part0.gsp`.
+    pub field: String,
+    /// Raw value of the field.
+    pub value: u64,
+    /// The violated constraint.
+    pub constraint: Constraint,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.constraint {
+            Constraint::Min(min) => write!(f, "{}: {} is below the minimum {}", self.field, self.value, min),
+            Constraint::Max(max) => write!(f, "{}: {} is above the maximum {}", self.field, self.value, max),
+            Constraint::MultipleOf(n) => write!(f, "{}: {} is not a multiple of {}", self.field, self.value, n),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Checks of the field constraints of a generated item or record.
+///
+/// Every generated item and record implements `Validate`; types without
+/// constrained fields accept any value.
+pub trait Validate {
+    /// Appends an error for each violated constraint to `errors`, naming
+    /// fields by their path after `path`, e.g. `"item040."`.
+    fn check(&self, path: &str, errors: &mut Vec<ValidationError>) {
+        let _ = (path, errors);
+    }
+
+    /// Checks every constrained field, returning all the violations.
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+        self.check("", &mut errors);
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
+/// Appends an error to `errors` for each of `constraints` that `value`, the
+/// value of `field`, violates.  Called by generated code.
+pub fn check_field(errors: &mut Vec<ValidationError>, path: &str, field: &str, value: u64, constraints: &[Constraint]) {
+    for constraint in constraints {
+        if !constraint.accepts(value) {
+            errors.push(ValidationError { field: format!("{}{}", path, field), value, constraint: *constraint });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_field_reports_each_violated_constraint() {
+        let mut errors = Vec::new();
+        let constraints = [Constraint::Min(10), Constraint::Max(100), Constraint::MultipleOf(4)];
+
+        check_field(&mut errors, "item040.", "gsp", 40, &constraints);
+        assert!(errors.is_empty());
+
+        check_field(&mut errors, "item040.", "gsp", 6, &constraints);
+        let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(messages, ["item040.gsp: 6 is below the minimum 10", "item040.gsp: 6 is not a multiple of 4"]);
+    }
+
+    #[test]
+    fn test_validate_collects_errors() {
+        struct Speed(u64);
+
+        impl Validate for Speed {
+            fn check(&self, path: &str, errors: &mut Vec<ValidationError>) {
+                check_field(errors, path, "speed", self.0, &[Constraint::Max(100)]);
+            }
+        }
+
+        assert_eq!(Speed(100).validate(), Ok(()));
+        assert_eq!(
+            Speed(101).validate(),
+            Err(vec![ValidationError { field: "speed".to_string(), value: 101, constraint: Constraint::Max(100) }])
+        );
+    }
+}
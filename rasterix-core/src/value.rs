@@ -0,0 +1,381 @@
+//! Generic values of records, for tools that don't know their types.
+//!
+//! Generated records convert to and from a [`ValueMap`] of their items, and
+//! items to and from [`Value`]s, so field mappings, scripting engines and
+//! other config-driven pipelines can edit records without matching on the
+//! types of a category:
+//!
+//! ```ignore
+//! let mut map = record.to_value_map();
+//! if let Some(Value::Map(item)) = map.get_mut("item010") {
+//!     item.insert("sac".to_string(), Value::Number(25));
+//! }
+//! let record = Record::from_value_map(&map)?;
+//! ```
+//!
+//! Structs are maps keyed by field name, repetitive items lists of their
+//! elements, enums the name of their variant, or the raw value of an
+//! unknown one, and absent values `Null`.  Converting back accepts the raw
+//! value of a variant too, and a missing key for an absent value.
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// A value of a record, without its type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// An absent EPB value, extended part, sub-item or item.
+    Null,
+    /// A numeric field, or an enum value without a variant.
+    Number(u128),
+    /// A float field.
+    Float(f64),
+    /// A string field, or the name of an enum variant.
+    Text(String),
+    /// A byte array field, or the unknown extensions of an extended item.
+    Bytes(Vec<u8>),
+    /// The elements of a repetitive item.
+    List(Vec<Value>),
+    /// The fields of a struct, the parts of an extended item or the
+    /// sub-items of a compound item.
+    Map(ValueMap),
+}
+
+/// Values keyed by the name of their field, part, sub-item or item.
+pub type ValueMap = HashMap<String, Value>;
+
+/// What is wrong with a value converted back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValueErrorKind {
+    /// The value is missing or `Null`, but isn't optional.
+    Missing,
+    /// The value is of another kind, e.g. text for a number.
+    WrongKind { expected: &'static str },
+    /// The number doesn't fit in the type of its field.
+    OutOfRange { value: u128 },
+    /// The enum has no such variant.
+    UnknownVariant,
+    /// The map has a key naming no field.
+    UnknownKey,
+    /// The bytes or elements are too many or too few.
+    WrongLength { len: usize, expected: usize },
+}
+
+/// A value that doesn't convert back to its type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueError {
+    /// Dotted path of the value, e.g. `item040.part0.gsp`.
+    pub path: String,
+    pub kind: ValueErrorKind,
+}
+
+impl ValueError {
+    pub fn new(kind: ValueErrorKind) -> Self {
+        Self { path: String::new(), kind }
+    }
+
+    /// Returns the error with its path put under `name`.
+    pub fn within(mut self, name: &str) -> Self {
+        self.path = if self.path.is_empty() { name.to_string() } else { format!("{}.{}", name, self.path) };
+        self
+    }
+}
+
+impl fmt::Display for ValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.path.is_empty() {
+            write!(f, "{}: ", self.path)?;
+        }
+        match &self.kind {
+            ValueErrorKind::Missing => write!(f, "missing value"),
+            ValueErrorKind::WrongKind { expected } => write!(f, "expected {}", expected),
+            ValueErrorKind::OutOfRange { value } => write!(f, "{} is out of range", value),
+            ValueErrorKind::UnknownVariant => write!(f, "unknown variant"),
+            ValueErrorKind::UnknownKey => write!(f, "unknown key"),
+            ValueErrorKind::WrongLength { len, expected } => write!(f, "{} values instead of {}", len, expected),
+        }
+    }
+}
+
+impl std::error::Error for ValueError {}
+
+/// Conversion of a generated type, or of a field, into a [`Value`].
+pub trait ToValue {
+    fn to_value(&self) -> Value;
+}
+
+/// Conversion of a [`Value`] back into a generated type, or a field.
+pub trait FromValue: Sized {
+    fn from_value(value: &Value) -> Result<Self, ValueError>;
+}
+
+fn wrong_kind(value: &Value, expected: &'static str) -> ValueError {
+    match value {
+        Value::Null => ValueError::new(ValueErrorKind::Missing),
+        _ => ValueError::new(ValueErrorKind::WrongKind { expected }),
+    }
+}
+
+macro_rules! impl_value_number {
+    ($($ty:ty),*) => {
+        $(
+            impl ToValue for $ty {
+                fn to_value(&self) -> Value {
+                    Value::Number(*self as u128)
+                }
+            }
+
+            impl FromValue for $ty {
+                fn from_value(value: &Value) -> Result<Self, ValueError> {
+                    match *value {
+                        Value::Number(number) => <$ty>::try_from(number)
+                            .map_err(|_| ValueError::new(ValueErrorKind::OutOfRange { value: number })),
+                        _ => Err(wrong_kind(value, "a number")),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_value_number!(u8, u16, u32, u64, u128);
+
+macro_rules! impl_value_float {
+    ($($ty:ty),*) => {
+        $(
+            /// Numbers convert back to floats too.
+            impl ToValue for $ty {
+                fn to_value(&self) -> Value {
+                    Value::Float(*self as f64)
+                }
+            }
+
+            impl FromValue for $ty {
+                fn from_value(value: &Value) -> Result<Self, ValueError> {
+                    match *value {
+                        Value::Float(number) => Ok(number as $ty),
+                        Value::Number(number) => Ok(number as $ty),
+                        _ => Err(wrong_kind(value, "a float")),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_value_float!(f32, f64);
+
+impl ToValue for String {
+    fn to_value(&self) -> Value {
+        Value::Text(self.clone())
+    }
+}
+
+impl FromValue for String {
+    fn from_value(value: &Value) -> Result<Self, ValueError> {
+        match value {
+            Value::Text(text) => Ok(text.clone()),
+            _ => Err(wrong_kind(value, "text")),
+        }
+    }
+}
+
+impl ToValue for Vec<u8> {
+    fn to_value(&self) -> Value {
+        Value::Bytes(self.clone())
+    }
+}
+
+impl FromValue for Vec<u8> {
+    fn from_value(value: &Value) -> Result<Self, ValueError> {
+        match value {
+            Value::Bytes(bytes) => Ok(bytes.clone()),
+            _ => Err(wrong_kind(value, "bytes")),
+        }
+    }
+}
+
+impl<const N: usize> ToValue for [u8; N] {
+    fn to_value(&self) -> Value {
+        Value::Bytes(self.to_vec())
+    }
+}
+
+impl<const N: usize> FromValue for [u8; N] {
+    fn from_value(value: &Value) -> Result<Self, ValueError> {
+        match value {
+            Value::Bytes(bytes) => bytes
+                .as_slice()
+                .try_into()
+                .map_err(|_| ValueError::new(ValueErrorKind::WrongLength { len: bytes.len(), expected: N })),
+            _ => Err(wrong_kind(value, "bytes")),
+        }
+    }
+}
+
+impl<T: ToValue> ToValue for Option<T> {
+    fn to_value(&self) -> Value {
+        match self {
+            Some(value) => value.to_value(),
+            None => Value::Null,
+        }
+    }
+}
+
+impl<T: FromValue> FromValue for Option<T> {
+    fn from_value(value: &Value) -> Result<Self, ValueError> {
+        match value {
+            Value::Null => Ok(None),
+            value => T::from_value(value).map(Some),
+        }
+    }
+}
+
+/// Returns the fields of a struct `value`.  Called by generated code.
+pub fn map(value: &Value) -> Result<&ValueMap, ValueError> {
+    match value {
+        Value::Map(map) => Ok(map),
+        _ => Err(wrong_kind(value, "a map")),
+    }
+}
+
+/// Returns the elements of a repetitive `value`.  Called by generated code.
+pub fn list(value: &Value) -> Result<&[Value], ValueError> {
+    match value {
+        Value::List(list) => Ok(list),
+        _ => Err(wrong_kind(value, "a list")),
+    }
+}
+
+/// Converts the value of `name` in `map`, a missing one as `Null`.  Called
+/// by generated code.
+pub fn field<T: FromValue>(map: &ValueMap, name: &str) -> Result<T, ValueError> {
+    T::from_value(map.get(name).unwrap_or(&Value::Null)).map_err(|error| error.within(name))
+}
+
+/// Fails if `map` has a key other than `names`, so misspelled keys are not
+/// dropped silently.  Called by generated code.
+pub fn check_keys(map: &ValueMap, names: &[&str]) -> Result<(), ValueError> {
+    match map.keys().filter(|key| !names.contains(&key.as_str())).min() {
+        Some(key) => Err(ValueError::new(ValueErrorKind::UnknownKey).within(key)),
+        None => Ok(()),
+    }
+}
+
+/// Converts an enum `value`, the name of one of `variants` or a raw value.
+/// Called by generated code.
+pub fn variant<T: Copy + TryFrom<u8>>(value: &Value, variants: &[(&str, T)]) -> Result<T, ValueError> {
+    match value {
+        Value::Text(text) => variants
+            .iter()
+            .find(|(name, _)| name == text)
+            .map(|&(_, variant)| variant)
+            .ok_or(ValueError::new(ValueErrorKind::UnknownVariant)),
+        &Value::Number(number) => {
+            let raw = u8::try_from(number).map_err(|_| ValueError::new(ValueErrorKind::OutOfRange { value: number }))?;
+            T::try_from(raw).map_err(|_| ValueError::new(ValueErrorKind::UnknownVariant))
+        }
+        _ => Err(wrong_kind(value, "a variant")),
+    }
+}
+
+/// Converts a number of a wider type, e.g. of a protobuf message, to the
+/// type of its field.  Called by generated code.
+pub fn narrow<T: TryFrom<u128>>(number: impl Into<u128>) -> Result<T, ValueError> {
+    let number = number.into();
+    T::try_from(number).map_err(|_| ValueError::new(ValueErrorKind::OutOfRange { value: number }))
+}
+
+/// Converts the raw value of an enum, e.g. of a protobuf message, to its
+/// variant.  Called by generated code.
+pub fn raw_variant<T: TryFrom<u8>>(raw: i32) -> Result<T, ValueError> {
+    u8::try_from(raw)
+        .ok()
+        .and_then(|raw| T::try_from(raw).ok())
+        .ok_or(ValueError::new(ValueErrorKind::UnknownVariant))
+}
+
+/// Converts `bytes` to an array of their field.  Called by generated code.
+pub fn byte_array<const N: usize>(bytes: &[u8]) -> Result<[u8; N], ValueError> {
+    bytes.try_into().map_err(|_| ValueError::new(ValueErrorKind::WrongLength { len: bytes.len(), expected: N }))
+}
+
+/// Returns the value of a field that isn't optional, e.g. the first part of
+/// an extended item in a protobuf message.  Called by generated code.
+pub fn required<T>(value: Option<T>) -> Result<T, ValueError> {
+    value.ok_or(ValueError::new(ValueErrorKind::Missing))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fields_convert_back() {
+        let mut map = ValueMap::new();
+        map.insert("a".to_string(), 300u16.to_value());
+        map.insert("b".to_string(), [1u8, 2].to_value());
+
+        assert_eq!(field::<u16>(&map, "a"), Ok(300));
+        assert_eq!(field::<[u8; 2]>(&map, "b"), Ok([1, 2]));
+        assert_eq!(field::<Option<u8>>(&map, "c"), Ok(None));
+        assert_eq!(field::<f64>(&map, "a"), Ok(300.0));
+
+        let error = field::<u8>(&map, "a").unwrap_err().within("item010");
+        assert_eq!(error.to_string(), "item010.a: 300 is out of range");
+        assert_eq!(field::<u8>(&map, "c").unwrap_err().to_string(), "c: missing value");
+        assert_eq!(field::<[u8; 3]>(&map, "b").unwrap_err().to_string(), "b: 2 values instead of 3");
+        assert_eq!(field::<String>(&map, "a").unwrap_err().to_string(), "a: expected text");
+    }
+
+    #[test]
+    fn test_message_values_convert_to_fields() {
+        assert_eq!(narrow::<u8>(200u32), Ok(200));
+        assert_eq!(narrow::<u8>(300u32).unwrap_err().kind, ValueErrorKind::OutOfRange { value: 300 });
+        assert_eq!(raw_variant::<u8>(7), Ok(7));
+        assert_eq!(raw_variant::<u8>(-1).unwrap_err().kind, ValueErrorKind::UnknownVariant);
+        assert_eq!(byte_array::<2>(&[1, 2]), Ok([1, 2]));
+        assert_eq!(byte_array::<2>(&[1]).unwrap_err().kind, ValueErrorKind::WrongLength { len: 1, expected: 2 });
+        assert_eq!(required::<u8>(None).unwrap_err().kind, ValueErrorKind::Missing);
+    }
+
+    #[test]
+    fn test_check_keys_names_an_unknown_key() {
+        let mut map = ValueMap::new();
+        map.insert("sac".to_string(), Value::Number(1));
+        assert_eq!(check_keys(&map, &["sac", "sic"]), Ok(()));
+
+        map.insert("sik".to_string(), Value::Number(2));
+        assert_eq!(check_keys(&map, &["sac", "sic"]).unwrap_err().to_string(), "sik: unknown key");
+    }
+
+    #[test]
+    fn test_variants_convert_from_names_and_raw_values() {
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        enum Mode {
+            A,
+            B,
+        }
+        impl TryFrom<u8> for Mode {
+            type Error = ();
+
+            fn try_from(value: u8) -> Result<Self, ()> {
+                match value {
+                    1 => Ok(Mode::A),
+                    2 => Ok(Mode::B),
+                    _ => Err(()),
+                }
+            }
+        }
+        let variants = [("A", Mode::A), ("B", Mode::B)];
+
+        assert_eq!(variant(&Value::Text("B".to_string()), &variants), Ok(Mode::B));
+        assert_eq!(variant(&Value::Number(1), &variants), Ok(Mode::A));
+        assert_eq!(variant(&Value::Number(3), &variants).unwrap_err().kind, ValueErrorKind::UnknownVariant);
+        assert_eq!(
+            variant(&Value::Number(256), &variants).unwrap_err().kind,
+            ValueErrorKind::OutOfRange { value: 256 }
+        );
+        assert_eq!(variant(&Value::Text("C".to_string()), &variants).unwrap_err().kind, ValueErrorKind::UnknownVariant);
+    }
+}
@@ -0,0 +1,163 @@
+//! Ground velocities, polar or cartesian.
+//!
+//! ASTERIX reports velocities either as a ground speed and a heading, in
+//! 2^-14 NM/s (about 0.22 kt) and 360/2^16 degrees (e.g. I048/200), or as
+//! their east and north components in 0.25 m/s, in two's complement (e.g.
+//! I062/185 and I021/157).  [`Velocity`] converts those raw values and
+//! between both representations.
+//!
+//! Fields defined with `type="ground_speed"` and `type="heading"`, or with
+//! `type="vx"` and `type="vy"`, give their struct a `velocity()` accessor and
+//! a `set_velocity()` setter:
+//!
+//! ```ignore
+//! let velocity = record.item200.unwrap().velocity();
+//! println!("{:.0} kt", velocity.knots());
+//! ```
+//!
+//! # Example
+//!
+//! ```
+//! use rasterix_core::velocity::Velocity;
+//!
+//! // 0.1 NM/s to the east.
+//! let velocity = Velocity::from_polar_raw(1638, 16384, 16);
+//! assert!((velocity.knots() - 360.0).abs() < 0.1);
+//! assert_eq!(velocity.to_cartesian_raw(16), (741, 0));
+//! ```
+
+use crate::altitude::{sign_extend, to_twos_complement};
+use crate::geo::METERS_PER_NM;
+
+/// Meters per second in a knot.
+pub const MPS_PER_KNOT: f64 = METERS_PER_NM / 3600.0;
+
+/// A velocity over the ground, in meters per second east and north.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Velocity {
+    east: f64,
+    north: f64,
+}
+
+impl Velocity {
+    /// NM/s in the least significant bit of a raw ground speed.
+    pub const GROUND_SPEED_LSB: f64 = 1.0 / 16384.0;
+    /// m/s in the least significant bit of a raw cartesian component.
+    pub const CARTESIAN_LSB: f64 = 0.25;
+
+    pub fn from_cartesian(east: f64, north: f64) -> Self {
+        Self { east, north }
+    }
+
+    /// Returns the velocity of a speed in m/s along a heading in degrees
+    /// clockwise from north.
+    pub fn from_polar(speed: f64, heading_deg: f64) -> Self {
+        let heading = heading_deg.to_radians();
+        Self { east: speed * heading.sin(), north: speed * heading.cos() }
+    }
+
+    pub fn from_knots(speed_kt: f64, heading_deg: f64) -> Self {
+        Self::from_polar(speed_kt * MPS_PER_KNOT, heading_deg)
+    }
+
+    /// Decodes a raw ground speed in 2^-14 NM/s and a raw heading of
+    /// `heading_bits` bits.
+    pub fn from_polar_raw(speed: u64, heading: u64, heading_bits: usize) -> Self {
+        let speed = speed as f64 * Self::GROUND_SPEED_LSB * METERS_PER_NM;
+        Self::from_polar(speed, heading as f64 * heading_lsb(heading_bits))
+    }
+
+    /// Encodes the ground speed in `speed_bits` bits, saturating speeds out
+    /// of range, and the heading in `heading_bits` bits.
+    pub fn to_polar_raw(self, speed_bits: usize, heading_bits: usize) -> (u64, u64) {
+        let max = if speed_bits >= 64 { u64::MAX } else { (1 << speed_bits) - 1 };
+        let speed = (self.speed() / METERS_PER_NM / Self::GROUND_SPEED_LSB).round().min(max as f64) as u64;
+        let steps = 1u128 << heading_bits.min(64);
+        let heading = (self.heading() / heading_lsb(heading_bits)).round() as u128 % steps;
+        (speed, heading as u64)
+    }
+
+    /// Decodes raw east and north components of `bits` bits in two's
+    /// complement, in 0.25 m/s.
+    pub fn from_cartesian_raw(vx: u64, vy: u64, bits: usize) -> Self {
+        Self {
+            east: sign_extend(vx, bits) as f64 * Self::CARTESIAN_LSB,
+            north: sign_extend(vy, bits) as f64 * Self::CARTESIAN_LSB,
+        }
+    }
+
+    /// Encodes the east and north components in `bits` bits of two's
+    /// complement, in 0.25 m/s, saturating components out of range.
+    pub fn to_cartesian_raw(self, bits: usize) -> (u64, u64) {
+        (
+            to_twos_complement(self.east / Self::CARTESIAN_LSB, bits),
+            to_twos_complement(self.north / Self::CARTESIAN_LSB, bits),
+        )
+    }
+
+    /// Returns the east component in m/s.
+    pub fn east(self) -> f64 {
+        self.east
+    }
+
+    /// Returns the north component in m/s.
+    pub fn north(self) -> f64 {
+        self.north
+    }
+
+    /// Returns the ground speed in m/s.
+    pub fn speed(self) -> f64 {
+        self.east.hypot(self.north)
+    }
+
+    pub fn knots(self) -> f64 {
+        self.speed() / MPS_PER_KNOT
+    }
+
+    /// Returns the heading in degrees clockwise from north, in `[0, 360)`.
+    pub fn heading(self) -> f64 {
+        let heading = self.east.atan2(self.north).to_degrees();
+        if heading < 0.0 { heading + 360.0 } else { heading }
+    }
+}
+
+/// Degrees in the least significant bit of a heading of `bits` bits.
+fn heading_lsb(bits: usize) -> f64 {
+    360.0 / 2f64.powi(bits as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn polar_and_cartesian_agree() {
+        let velocity = Velocity::from_knots(400.0, 225.0);
+        assert!((velocity.east() - velocity.north()).abs() < 1e-9);
+        assert!(velocity.east() < 0.0);
+        assert!((velocity.knots() - 400.0).abs() < 1e-9);
+        assert!((velocity.heading() - 225.0).abs() < 1e-9);
+
+        let velocity = Velocity::from_cartesian(0.0, 100.0);
+        assert_eq!((velocity.speed(), velocity.heading()), (100.0, 0.0));
+    }
+
+    #[test]
+    fn raw_values_roundtrip() {
+        let velocity = Velocity::from_polar_raw(2000, 0xC000, 16);
+        assert_eq!(velocity.to_polar_raw(16, 16), (2000, 0xC000));
+        assert!((velocity.heading() - 270.0).abs() < 1e-9);
+
+        let velocity = Velocity::from_cartesian_raw(0xFFFC, 400, 16);
+        assert_eq!((velocity.east(), velocity.north()), (-1.0, 100.0));
+        assert_eq!(velocity.to_cartesian_raw(16), (0xFFFC, 400));
+    }
+
+    #[test]
+    fn raw_values_saturate() {
+        let velocity = Velocity::from_polar(10_000.0, 359.999);
+        // The heading wraps to north, the speed saturates.
+        assert_eq!(velocity.to_polar_raw(16, 16), (0xFFFF, 0));
+        assert_eq!(Velocity::from_cartesian(-10_000.0, 10_000.0).to_cartesian_raw(16), (0x8000, 0x7FFF));
+    }
+}
@@ -0,0 +1,351 @@
+//! Interpretation of the CAT021 quality indicators.
+//!
+//! ADS-B reports carry their quality as small integers, packed in I021/090
+//! across the parts of an extended item: NUCr or NACv, NUCp or NIC, NIC for
+//! barometric altitude, SIL and its supplement, NACp, SDA, GVA and PIC.  The
+//! types of this module turn those integers into the bounds they categorize,
+//! in meters, meters per second or probabilities, independently of the names
+//! the generated CAT021 types give the fields:
+//!
+//! ```
+//! use rasterix::cat021_support::{Nacp, QualityIndicators};
+//!
+//! assert_eq!(Nacp(9).accuracy(), Some(30.0));
+//!
+//! // NACv 2 and NUCp 7, then SIL 3 and NACp 10.
+//! let quality = QualityIndicators::decode(&[0x4F, 0x74]).unwrap();
+//! assert_eq!(quality.nacp, Some(Nacp(10)));
+//! assert_eq!(quality.sil.unwrap().probability(), Some(1e-7));
+//! assert_eq!(quality.nacp.unwrap().to_string(), "NACp 10 (EPU < 10 m)");
+//! ```
+//!
+//! Values an indicator leaves unknown or reserved have no bound.
+
+use std::fmt;
+
+/// Meters in a nautical mile.
+const NM: f64 = 1852.0;
+
+/// Navigation Uncertainty Category for position (version 0 of ADS-B):
+/// bound on the horizontal protection limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Nucp(pub u8);
+
+impl Nucp {
+    /// Returns the horizontal protection limit in meters.
+    pub fn protection_limit(self) -> Option<f64> {
+        match self.0 {
+            1 => Some(20.0 * NM),
+            2 => Some(10.0 * NM),
+            3 => Some(2.0 * NM),
+            4 => Some(NM),
+            5 => Some(926.0),
+            6 => Some(370.4),
+            7 => Some(185.2),
+            8 => Some(25.0),
+            9 => Some(7.5),
+            _ => None,
+        }
+    }
+}
+
+/// Navigation Accuracy Category for position: bound on the estimated
+/// position uncertainty (EPU) at 95 %.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Nacp(pub u8);
+
+impl Nacp {
+    /// Returns the estimated position uncertainty in meters.
+    pub fn accuracy(self) -> Option<f64> {
+        match self.0 {
+            1 => Some(10.0 * NM),
+            2 => Some(4.0 * NM),
+            3 => Some(2.0 * NM),
+            4 => Some(NM),
+            5 => Some(926.0),
+            6 => Some(555.6),
+            7 => Some(185.2),
+            8 => Some(92.6),
+            9 => Some(30.0),
+            10 => Some(10.0),
+            11 => Some(3.0),
+            _ => None,
+        }
+    }
+}
+
+/// Navigation Accuracy Category for velocity, or Navigation Uncertainty
+/// Category for velocity (NUCr) in version 0, which shares its table:
+/// bound on the horizontal velocity error at 95 %.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Nacv(pub u8);
+
+impl Nacv {
+    /// Returns the horizontal velocity error in m/s.
+    pub fn accuracy(self) -> Option<f64> {
+        match self.0 {
+            1 => Some(10.0),
+            2 => Some(3.0),
+            3 => Some(1.0),
+            4 => Some(0.3),
+            _ => None,
+        }
+    }
+}
+
+/// Position Integrity Category: bound on the containment radius of the
+/// position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Pic(pub u8);
+
+impl Pic {
+    /// Returns the containment radius in meters.
+    pub fn containment_radius(self) -> Option<f64> {
+        match self.0 {
+            1 => Some(20.0 * NM),
+            2 => Some(10.0 * NM),
+            3 => Some(8.0 * NM),
+            4 => Some(4.0 * NM),
+            5 => Some(2.0 * NM),
+            6 => Some(NM),
+            7 => Some(1111.2),
+            8 => Some(926.0),
+            9 => Some(555.6),
+            10 => Some(370.4),
+            11 => Some(185.2),
+            12 => Some(74.08),
+            13 => Some(25.0),
+            14 => Some(7.5),
+            _ => None,
+        }
+    }
+}
+
+/// Source Integrity Level: bound on the probability of the position
+/// exceeding its containment radius unnoticed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Sil {
+    pub level: u8,
+    /// The SIL supplement: the probability is per sample rather than per
+    /// flight hour.
+    pub per_sample: bool,
+}
+
+impl Sil {
+    pub fn probability(self) -> Option<f64> {
+        integrity_probability(self.level)
+    }
+}
+
+/// System Design Assurance: bound on the probability per flight hour of a
+/// failure corrupting the transmitted data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Sda(pub u8);
+
+impl Sda {
+    pub fn probability(self) -> Option<f64> {
+        integrity_probability(self.0)
+    }
+}
+
+/// Geometric Vertical Accuracy: bound on the error of the geometric altitude
+/// at 95 %.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Gva(pub u8);
+
+impl Gva {
+    /// Returns the vertical accuracy in meters.
+    pub fn accuracy(self) -> Option<f64> {
+        match self.0 {
+            1 => Some(150.0),
+            2 => Some(45.0),
+            _ => None,
+        }
+    }
+}
+
+/// Probabilities of the SIL and SDA levels.
+fn integrity_probability(level: u8) -> Option<f64> {
+    match level {
+        1 => Some(1e-3),
+        2 => Some(1e-5),
+        3 => Some(1e-7),
+        _ => None,
+    }
+}
+
+/// Writes `name value (label < bound unit)`, or `(unknown)` without a bound.
+fn write_bound(f: &mut fmt::Formatter<'_>, name: &str, value: u8, label: &str, bound: Option<f64>, unit: &str) -> fmt::Result {
+    match bound {
+        Some(bound) if bound >= NM && unit == "m" => write!(f, "{} {} ({} < {} NM)", name, value, label, bound / NM),
+        Some(bound) => write!(f, "{} {} ({} < {} {})", name, value, label, bound, unit),
+        None => write!(f, "{} {} (unknown)", name, value),
+    }
+}
+
+impl fmt::Display for Nucp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_bound(f, "NUCp", self.0, "HPL", self.protection_limit(), "m")
+    }
+}
+
+impl fmt::Display for Nacp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_bound(f, "NACp", self.0, "EPU", self.accuracy(), "m")
+    }
+}
+
+impl fmt::Display for Nacv {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_bound(f, "NACv", self.0, "error", self.accuracy(), "m/s")
+    }
+}
+
+impl fmt::Display for Pic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_bound(f, "PIC", self.0, "Rc", self.containment_radius(), "m")
+    }
+}
+
+impl fmt::Display for Sil {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let unit = if self.per_sample { "per sample" } else { "per hour" };
+        write_bound(f, "SIL", self.level, "p", self.probability(), unit)
+    }
+}
+
+impl fmt::Display for Sda {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_bound(f, "SDA", self.0, "p", self.probability(), "per hour")
+    }
+}
+
+impl fmt::Display for Gva {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_bound(f, "GVA", self.0, "error", self.accuracy(), "m")
+    }
+}
+
+/// The quality indicators of I021/090, as far as the item extends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QualityIndicators {
+    /// NACv, or NUCr in version 0.
+    pub nacv: Nacv,
+    /// NIC, or NUCp in version 0; see [`QualityIndicators::nucp`].
+    pub nic: u8,
+    /// NIC for barometric altitude: the altitude is cross-checked.
+    pub nic_baro: Option<bool>,
+    pub sil: Option<Sil>,
+    pub nacp: Option<Nacp>,
+    pub sda: Option<Sda>,
+    pub gva: Option<Gva>,
+    pub pic: Option<Pic>,
+}
+
+impl QualityIndicators {
+    /// Decodes the octets of I021/090, extensions included.
+    ///
+    /// Returns `None` if the item is empty or an FX bit announces an octet
+    /// missing from `octets`.
+    pub fn decode(octets: &[u8]) -> Option<Self> {
+        let first = *octets.first()?;
+        let mut quality = Self {
+            nacv: Nacv(first >> 5),
+            nic: (first >> 1) & 0x0F,
+            nic_baro: None,
+            sil: None,
+            nacp: None,
+            sda: None,
+            gva: None,
+            pic: None,
+        };
+        let mut extends = first & 1 == 1;
+        // Parts 2 to 4, each announced by the FX bit of the one before.
+        for part in 2..=4 {
+            if !extends {
+                break;
+            }
+            let octet = *octets.get(part - 1)?;
+            match part {
+                2 => {
+                    quality.nic_baro = Some(octet & 0x80 != 0);
+                    quality.sil = Some(Sil { level: (octet >> 5) & 0x03, per_sample: false });
+                    quality.nacp = Some(Nacp((octet >> 1) & 0x0F));
+                }
+                3 => {
+                    if let Some(sil) = &mut quality.sil {
+                        sil.per_sample = octet & 0x20 != 0;
+                    }
+                    quality.sda = Some(Sda((octet >> 3) & 0x03));
+                    quality.gva = Some(Gva((octet >> 1) & 0x03));
+                }
+                _ => quality.pic = Some(Pic(octet >> 4)),
+            }
+            extends = octet & 1 == 1;
+        }
+        Some(quality)
+    }
+
+    /// Returns the NIC field as the NUCp of version 0 reports.
+    pub fn nucp(&self) -> Nucp {
+        Nucp(self.nic)
+    }
+
+    /// Returns the best available bound on the horizontal position error in
+    /// meters: the NACp, or the protection limit of the NUCp without one.
+    pub fn horizontal_accuracy(&self) -> Option<f64> {
+        match self.nacp {
+            Some(nacp) => nacp.accuracy(),
+            None => self.nucp().protection_limit(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tables_bound_errors() {
+        assert_eq!(Nucp(0).protection_limit(), None);
+        assert_eq!(Nucp(4).protection_limit(), Some(1852.0));
+        assert_eq!(Nacp(8).accuracy(), Some(92.6));
+        assert_eq!(Nacp(12).accuracy(), None);
+        assert_eq!(Nacv(4).accuracy(), Some(0.3));
+        assert_eq!(Pic(13).containment_radius(), Some(25.0));
+        assert_eq!(Gva(3).accuracy(), None);
+        assert_eq!(Sda(2).probability(), Some(1e-5));
+
+        assert_eq!(Nucp(2).to_string(), "NUCp 2 (HPL < 10 NM)");
+        assert_eq!(Pic(0).to_string(), "PIC 0 (unknown)");
+        assert_eq!(Sil { level: 3, per_sample: true }.to_string(), "SIL 3 (p < 0.0000001 per sample)");
+    }
+
+    #[test]
+    fn decodes_all_parts() {
+        // NACv 1, NIC 8; NIC baro, SIL 3, NACp 9; SIL per sample, SDA 2,
+        // GVA 1; PIC 12.
+        let quality = QualityIndicators::decode(&[0x31, 0xF3, 0x33, 0xC0]).unwrap();
+        assert_eq!(quality.nacv, Nacv(1));
+        assert_eq!(quality.nic, 8);
+        assert_eq!(quality.nic_baro, Some(true));
+        assert_eq!(quality.sil, Some(Sil { level: 3, per_sample: true }));
+        assert_eq!(quality.nacp, Some(Nacp(9)));
+        assert_eq!(quality.sda, Some(Sda(2)));
+        assert_eq!(quality.gva, Some(Gva(1)));
+        assert_eq!(quality.pic, Some(Pic(12)));
+        assert_eq!(quality.horizontal_accuracy(), Some(30.0));
+    }
+
+    #[test]
+    fn decodes_first_part_only() {
+        // NUCp 7 without extension: the accuracy falls back to it.
+        let quality = QualityIndicators::decode(&[0x0E]).unwrap();
+        assert_eq!((quality.nacp, quality.pic), (None, None));
+        assert_eq!(quality.horizontal_accuracy(), Some(185.2));
+
+        // An FX bit announcing a missing octet.
+        assert_eq!(QualityIndicators::decode(&[0x0F]), None);
+        assert_eq!(QualityIndicators::decode(&[]), None);
+    }
+}
@@ -0,0 +1,345 @@
+//! Several simultaneous inputs merged into one stream.
+//!
+//! Fusion of several sensors reads each of them from its own input: a
+//! multicast group per radar, a TCP feed, a file.  [`Channels`] reads every
+//! input added to it on its own thread, stamps each decoded record with its
+//! receive time and the [`ChannelId`] of its input, and yields the records
+//! of all inputs in receive time order.
+//!
+//! Records received at nearly the same time on different inputs may reach
+//! the merging thread out of order.  They are held for a short reorder
+//! window, set with [`with_reorder_window`](Channels::with_reorder_window),
+//! before being yielded.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use std::net::Ipv4Addr;
+//! use rasterix::channels::Channels;
+//!
+//! let mut channels = Channels::<cat048::Record>::new();
+//! let north = channels.add_udp_group(Ipv4Addr::new(239, 1, 1, 1), 8600)?;
+//! let south = channels.add_udp_group(Ipv4Addr::new(239, 1, 1, 2), 8600)?;
+//!
+//! for (channel, result) in channels {
+//!     let record = result?;
+//!     println!("{} at {:?}: {:?}", channel, record.recv_time, record.data.1);
+//! }
+//! ```
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::net::{Ipv4Addr, TcpStream, ToSocketAddrs, UdpSocket};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use rasterix_core::{CategoryRecord, Clock, DecodeError, Header, MonotonicClock, RecordStream, Timestamped};
+
+/// Reorder window used unless set otherwise.
+pub const DEFAULT_REORDER_WINDOW: Duration = Duration::from_millis(50);
+
+/// Identifies an input of [`Channels`], in the order inputs were added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ChannelId(pub usize);
+
+impl fmt::Display for ChannelId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "channel {}", self.0)
+    }
+}
+
+/// A record decoded from an input, stamped with its receive time.
+pub type ChannelRecord<T> = Timestamped<(Header, T)>;
+
+/// A record waiting in the reorder window.
+struct Pending<T> {
+    recv_time: SystemTime,
+    /// Arrival order, keeping records received at the same time in order.
+    seq: u64,
+    channel: ChannelId,
+    record: ChannelRecord<T>,
+}
+
+impl<T> Pending<T> {
+    fn key(&self) -> (SystemTime, u64) {
+        (self.recv_time, self.seq)
+    }
+}
+
+impl<T> PartialEq for Pending<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl<T> Eq for Pending<T> {}
+
+impl<T> PartialOrd for Pending<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Pending<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+type Message<T> = (ChannelId, Result<ChannelRecord<T>, DecodeError>);
+
+/// Reads several inputs at once and merges their records in receive time
+/// order.
+///
+/// Inputs are added before iterating.  Every input is read by its own
+/// thread until it ends; the iterator ends once all inputs have ended and
+/// every record has been yielded.  Errors are yielded as soon as they are
+/// received, tagged with their channel.  Inputs still blocked on a read when
+/// `Channels` is dropped stop at their next record.
+pub struct Channels<T> {
+    sender: Option<Sender<Message<T>>>,
+    receiver: Receiver<Message<T>>,
+    clock: MonotonicClock,
+    window: Duration,
+    pending: BinaryHeap<Reverse<Pending<T>>>,
+    seq: u64,
+    channels: usize,
+}
+
+impl<T: CategoryRecord + Send + 'static> Channels<T> {
+    /// Creates a manager without inputs.
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            sender: Some(sender),
+            receiver,
+            clock: MonotonicClock::new(),
+            window: DEFAULT_REORDER_WINDOW,
+            pending: BinaryHeap::new(),
+            seq: 0,
+            channels: 0,
+        }
+    }
+
+    /// Holds each record for `window` after its receive time, so records
+    /// received just before it on other inputs are yielded first.
+    pub fn with_reorder_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Returns the number of inputs added.
+    pub fn len(&self) -> usize {
+        self.channels
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.channels == 0
+    }
+
+    /// Reads data blocks from `reader`, such as a TCP stream or a file.
+    pub fn add_reader<R: Read + Send + 'static>(&mut self, reader: R) -> ChannelId {
+        let stream = RecordStream::<T, R>::new(reader);
+        self.spawn(move |channel, sender, clock| {
+            for result in stream.timestamped(clock) {
+                if sender.send((channel, result)).is_err() {
+                    return;
+                }
+            }
+        })
+    }
+
+    /// Reads data blocks from the file at `path`.
+    pub fn add_file<P: AsRef<Path>>(&mut self, path: P) -> io::Result<ChannelId> {
+        Ok(self.add_reader(BufReader::new(File::open(path)?)))
+    }
+
+    /// Connects to `addr` and reads data blocks from the connection.
+    pub fn add_tcp<A: ToSocketAddrs>(&mut self, addr: A) -> io::Result<ChannelId> {
+        Ok(self.add_reader(TcpStream::connect(addr)?))
+    }
+
+    /// Reads data blocks from the datagrams received on `socket`, each
+    /// holding whole data blocks.
+    ///
+    /// The input ends when receiving fails.
+    pub fn add_udp(&mut self, socket: UdpSocket) -> ChannelId {
+        self.spawn(move |channel, sender, mut clock| {
+            let mut buf = vec![0u8; u16::MAX as usize];
+            loop {
+                let len = match socket.recv(&mut buf) {
+                    Ok(len) => len,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => {
+                        let _ = sender.send((channel, Err(e.into())));
+                        return;
+                    }
+                };
+                let recv_time = clock.now();
+                for result in RecordStream::<T, _>::new(&buf[..len]) {
+                    let message = (channel, result.map(|record| Timestamped::new(recv_time, record)));
+                    if sender.send(message).is_err() {
+                        return;
+                    }
+                }
+            }
+        })
+    }
+
+    /// Joins the multicast `group` on all interfaces and reads data blocks
+    /// from the datagrams sent to it on `port`.
+    pub fn add_udp_group(&mut self, group: Ipv4Addr, port: u16) -> io::Result<ChannelId> {
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, port))?;
+        socket.join_multicast_v4(&group, &Ipv4Addr::UNSPECIFIED)?;
+        Ok(self.add_udp(socket))
+    }
+
+    /// Runs `read` on a new thread as the next channel.
+    fn spawn<F>(&mut self, read: F) -> ChannelId
+    where
+        F: FnOnce(ChannelId, Sender<Message<T>>, MonotonicClock) + Send + 'static,
+    {
+        let channel = ChannelId(self.channels);
+        self.channels += 1;
+        let sender = self.sender.clone().expect("inputs are added before iterating");
+        let clock = self.clock;
+        thread::spawn(move || read(channel, sender, clock));
+        channel
+    }
+}
+
+impl<T: CategoryRecord + Send + 'static> Default for Channels<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for Channels<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Channels")
+            .field("channels", &self.channels)
+            .field("window", &self.window)
+            .field("pending", &self.pending.len())
+            .finish()
+    }
+}
+
+impl<T> Iterator for Channels<T> {
+    type Item = (ChannelId, Result<ChannelRecord<T>, DecodeError>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Only the input threads may keep the queue open from now on.
+        self.sender = None;
+
+        loop {
+            let now = self.clock.now();
+            let wait = match self.pending.peek() {
+                Some(Reverse(next)) => {
+                    let release = next.recv_time + self.window;
+                    match release.duration_since(now) {
+                        Ok(wait) if !wait.is_zero() => Some(wait),
+                        _ => return self.pending.pop().map(|Reverse(next)| (next.channel, Ok(next.record))),
+                    }
+                }
+                None => None,
+            };
+
+            let message = match wait {
+                Some(wait) => self.receiver.recv_timeout(wait),
+                None => self.receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+
+            match message {
+                Ok((channel, Ok(record))) => {
+                    self.seq += 1;
+                    self.pending.push(Reverse(Pending {
+                        recv_time: record.recv_time,
+                        seq: self.seq,
+                        channel,
+                        record,
+                    }));
+                }
+                Ok((channel, Err(e))) => return Some((channel, Err(e))),
+                Err(RecvTimeoutError::Timeout) => {}
+                // Every input has ended: nothing can arrive before the
+                // records still held.
+                Err(RecvTimeoutError::Disconnected) => {
+                    return self.pending.pop().map(|Reverse(next)| (next.channel, Ok(next.record)));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rasterix_core::{BitReader, Decode};
+    use std::io::Cursor;
+
+    #[derive(Debug, PartialEq)]
+    struct Byte(u8);
+
+    impl Decode for Byte {
+        fn decode<R: Read>(reader: &mut BitReader<R>) -> Result<Self, DecodeError> {
+            Ok(Byte(reader.read_bits(8)? as u8))
+        }
+    }
+
+    impl CategoryRecord for Byte {
+        const CATEGORY: u8 = 48;
+    }
+
+    fn block(records: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![48, 0, (3 + records.len()) as u8];
+        bytes.extend_from_slice(records);
+        bytes
+    }
+
+    #[test]
+    fn merges_readers_in_receive_time_order() {
+        let mut channels = Channels::<Byte>::new().with_reorder_window(Duration::ZERO);
+        let first = channels.add_reader(Cursor::new(block(&[1, 2])));
+        let second = channels.add_reader(Cursor::new([block(&[3]), block(&[4, 5])].concat()));
+        assert_eq!(channels.len(), 2);
+
+        let records: Vec<_> = channels.map(|(channel, result)| (channel, result.unwrap())).collect();
+
+        assert_eq!(records.len(), 5);
+        assert!(records.windows(2).all(|pair| pair[0].1.recv_time <= pair[1].1.recv_time));
+        let values = |id| -> Vec<u8> {
+            records.iter().filter(|(channel, _)| *channel == id).map(|(_, r)| r.data.1 .0).collect()
+        };
+        assert_eq!(values(first), [1, 2]);
+        assert_eq!(values(second), [3, 4, 5]);
+    }
+
+    #[test]
+    fn reads_udp_datagrams() {
+        let receiver = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let addr = receiver.local_addr().unwrap();
+        let mut channels = Channels::<Byte>::new();
+        let channel = channels.add_udp(receiver);
+
+        let sender = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        sender.send_to(&[block(&[7]), block(&[8])].concat(), addr).unwrap();
+
+        let values: Vec<_> = channels.take(2).map(|(id, result)| (id, result.unwrap().data.1 .0)).collect();
+        assert_eq!(values, [(channel, 7), (channel, 8)]);
+    }
+
+    #[test]
+    fn yields_errors_with_their_channel() {
+        let mut channels = Channels::<Byte>::new();
+        channels.add_reader(Cursor::new(block(&[1])));
+        let truncated = channels.add_reader(Cursor::new(vec![48, 0, 9, 1]));
+
+        let errors: Vec<_> = channels.filter_map(|(channel, result)| result.err().map(|_| channel)).collect();
+        assert_eq!(errors, [truncated]);
+    }
+}
@@ -0,0 +1,179 @@
+//! Duplicate record detection for redundant distribution paths.
+//!
+//! Surveillance data is often distributed over several networks or
+//! multicast groups at once, so the same record reaches the receiver more
+//! than once.  [`Deduplicator`] remembers the hashes of recent records and
+//! reports the ones seen again.
+//!
+//! Generated records carry no field metadata, so the caller extracts the
+//! [`RecordKey`] itself, e.g. from I048/010 (SAC/SIC), I048/161 (track
+//! number) and I048/140 (time of day).
+
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Identity of a record: its data source, track and time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RecordKey {
+    /// System Area Code of the data source.
+    pub sac: u8,
+    /// System Identification Code of the data source.
+    pub sic: u8,
+    pub track_number: u16,
+    /// Raw time of day, in 1/128 s.
+    pub time_of_day: u32,
+}
+
+impl RecordKey {
+    pub fn new(sac: u8, sic: u8, track_number: u16, time_of_day: u32) -> Self {
+        Self { sac, sic, track_number, time_of_day }
+    }
+}
+
+/// Detects records already seen among the most recent ones.
+///
+/// Holds the hashes of the last `capacity` distinct records in a ring
+/// buffer; when it is full, the oldest hash is forgotten.  The capacity
+/// should cover the records received during the largest delay between
+/// distribution paths.
+///
+/// # Example
+///
+/// ```
+/// use rasterix::dedup::{Deduplicator, RecordKey};
+///
+/// let mut dedup = Deduplicator::new(1024);
+/// let key = RecordKey::new(1, 2, 42, 128);
+///
+/// assert!(!dedup.is_duplicate_key(&key));
+/// assert!(dedup.is_duplicate_key(&key));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Deduplicator {
+    /// Recent hashes, oldest at `next` once the buffer is full.
+    ring: Vec<u64>,
+    next: usize,
+    capacity: usize,
+    /// Number of occurrences of each hash in `ring`.
+    counts: HashMap<u64, usize>,
+}
+
+impl Deduplicator {
+    /// Creates a deduplicator remembering the last `capacity` records.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "deduplicator capacity must be positive");
+        Self {
+            ring: Vec::with_capacity(capacity),
+            next: 0,
+            capacity,
+            counts: HashMap::with_capacity(capacity),
+        }
+    }
+
+    /// Returns `true` if a record with the same key and contents was seen
+    /// recently; otherwise remembers it and returns `false`.
+    ///
+    /// Hashing the contents as well as the key tells apart distinct records
+    /// that share a key, such as plots from two scans within one time tick.
+    pub fn is_duplicate<T: Hash + ?Sized>(&mut self, key: &RecordKey, record: &T) -> bool {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        record.hash(&mut hasher);
+        self.check(hasher.finish())
+    }
+
+    /// Like [`is_duplicate`](Self::is_duplicate), comparing keys only.
+    ///
+    /// Use this for records that don't implement `Hash`, e.g. those with
+    /// string fields.
+    pub fn is_duplicate_key(&mut self, key: &RecordKey) -> bool {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        self.check(hasher.finish())
+    }
+
+    /// Returns the number of records remembered.
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    /// Returns `true` if no record has been remembered yet.
+    pub fn is_empty(&self) -> bool {
+        self.ring.is_empty()
+    }
+
+    /// Forgets every record.
+    pub fn clear(&mut self) {
+        self.ring.clear();
+        self.next = 0;
+        self.counts.clear();
+    }
+
+    fn check(&mut self, hash: u64) -> bool {
+        if self.counts.contains_key(&hash) {
+            return true;
+        }
+
+        if self.ring.len() < self.capacity {
+            self.ring.push(hash);
+        } else {
+            let evicted = std::mem::replace(&mut self.ring[self.next], hash);
+            if let Some(count) = self.counts.get_mut(&evicted) {
+                *count -= 1;
+                if *count == 0 {
+                    self.counts.remove(&evicted);
+                }
+            }
+        }
+        self.next = (self.next + 1) % self.capacity;
+        *self.counts.entry(hash).or_insert(0) += 1;
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duplicates_within_window() {
+        let mut dedup = Deduplicator::new(4);
+        let key = RecordKey::new(1, 2, 42, 128);
+
+        assert!(!dedup.is_duplicate(&key, &[1u8, 2, 3]));
+        assert!(dedup.is_duplicate(&key, &[1u8, 2, 3]));
+        assert!(!dedup.is_duplicate(&key, &[1u8, 2, 4]));
+        assert!(!dedup.is_duplicate(&RecordKey::new(1, 3, 42, 128), &[1u8, 2, 3]));
+        assert_eq!(dedup.len(), 3);
+    }
+
+    #[test]
+    fn oldest_records_are_forgotten() {
+        let mut dedup = Deduplicator::new(2);
+        let key = |track| RecordKey::new(1, 2, track, 0);
+
+        assert!(!dedup.is_duplicate_key(&key(1)));
+        assert!(!dedup.is_duplicate_key(&key(2)));
+        assert!(!dedup.is_duplicate_key(&key(3)));
+        assert_eq!(dedup.len(), 2);
+
+        assert!(!dedup.is_duplicate_key(&key(1)));
+        assert!(dedup.is_duplicate_key(&key(3)));
+    }
+
+    #[test]
+    fn clear_forgets_everything() {
+        let mut dedup = Deduplicator::new(2);
+        let key = RecordKey::new(1, 2, 3, 4);
+        dedup.is_duplicate_key(&key);
+
+        dedup.clear();
+        assert!(dedup.is_empty());
+        assert!(!dedup.is_duplicate_key(&key));
+    }
+}
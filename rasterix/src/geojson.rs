@@ -0,0 +1,205 @@
+//! GeoJSON export of target positions.
+//!
+//! Builds GeoJSON `Feature`s and `FeatureCollection`s from WGS-84 positions,
+//! so decoded traffic can be dropped onto a map in debugging tools such as
+//! geojson.io or QGIS.  Tracks from a [`TrackStore`](crate::tracks::TrackStore)
+//! convert directly; other records are converted by building a [`Feature`]
+//! from their position item and adding properties.
+//!
+//! Only available with the `geojson` feature.
+
+use rasterix_core::json::{JsonObject, ToJson};
+
+use crate::tracks::{Position, TrackState};
+
+/// A GeoJSON `Point` feature with properties.
+///
+/// # Example
+///
+/// ```
+/// use rasterix::geojson::Feature;
+/// use rasterix::rcore::ToJson;
+///
+/// let feature = Feature::point(45.5, 9.25).property("track_number", &42u16);
+/// assert_eq!(
+///     feature.to_json(),
+///     r#"{"type":"Feature","geometry":{"type":"Point","coordinates":[9.25,45.5]},"properties":{"track_number":42}}"#
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Feature {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Property names and their values, already written as JSON.
+    properties: Vec<(String, String)>,
+}
+
+impl Feature {
+    /// Creates a point feature at a WGS-84 position, in degrees.
+    pub fn point(latitude: f64, longitude: f64) -> Self {
+        Self {
+            latitude,
+            longitude,
+            properties: Vec::new(),
+        }
+    }
+
+    /// Creates a point feature from a position, or returns `None` if it is
+    /// not WGS-84: polar and Cartesian positions need the sensor location to
+    /// be placed on a map.
+    pub fn from_position(position: &Position) -> Option<Self> {
+        match *position {
+            Position::Wgs84 { latitude, longitude } => Some(Self::point(latitude, longitude)),
+            Position::Polar { .. } | Position::Cartesian { .. } => None,
+        }
+    }
+
+    /// Creates a feature for a track with a WGS-84 position, with its
+    /// identity, altitude, callsign and time of day as properties.
+    pub fn from_track(track: &TrackState) -> Option<Self> {
+        let feature = Self::from_position(track.position.as_ref()?)?
+            .property("sac", &track.id.sac)
+            .property("sic", &track.id.sic)
+            .property("track_number", &track.id.track_number);
+        Some(
+            feature
+                .property("altitude", &track.altitude)
+                .property("callsign", &track.callsign)
+                .property("time_of_day", &track.time_of_day),
+        )
+    }
+
+    /// Adds a property, replacing any with the same name.
+    pub fn property<T: ToJson + ?Sized>(mut self, name: &str, value: &T) -> Self {
+        let value = value.to_json();
+        match self.properties.iter_mut().find(|(n, _)| n == name) {
+            Some((_, existing)) => *existing = value,
+            None => self.properties.push((name.to_string(), value)),
+        }
+        self
+    }
+}
+
+/// Writes a value that is already JSON.
+struct RawJson<'a>(&'a str);
+
+impl ToJson for RawJson<'_> {
+    fn write_json(&self, out: &mut String) {
+        out.push_str(self.0);
+    }
+}
+
+struct Point(f64, f64);
+
+impl ToJson for Point {
+    fn write_json(&self, out: &mut String) {
+        let mut obj = JsonObject::new(out);
+        // GeoJSON coordinates are longitude first.
+        obj.field("type", "Point").field("coordinates", &[self.1, self.0][..]);
+        obj.finish();
+    }
+}
+
+struct Properties<'a>(&'a [(String, String)]);
+
+impl ToJson for Properties<'_> {
+    fn write_json(&self, out: &mut String) {
+        let mut obj = JsonObject::new(out);
+        for (name, value) in self.0 {
+            obj.field(name, &RawJson(value));
+        }
+        obj.finish();
+    }
+}
+
+impl ToJson for Feature {
+    fn write_json(&self, out: &mut String) {
+        let mut obj = JsonObject::new(out);
+        obj.field("type", "Feature")
+            .field("geometry", &Point(self.latitude, self.longitude))
+            .field("properties", &Properties(&self.properties));
+        obj.finish();
+    }
+}
+
+/// A GeoJSON `FeatureCollection`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeatureCollection {
+    pub features: Vec<Feature>,
+}
+
+impl FeatureCollection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Collects the features of every track with a WGS-84 position.
+    pub fn from_tracks<'a>(tracks: impl IntoIterator<Item = &'a TrackState>) -> Self {
+        Self {
+            features: tracks.into_iter().filter_map(Feature::from_track).collect(),
+        }
+    }
+}
+
+impl FromIterator<Feature> for FeatureCollection {
+    fn from_iter<I: IntoIterator<Item = Feature>>(iter: I) -> Self {
+        Self {
+            features: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl ToJson for FeatureCollection {
+    fn write_json(&self, out: &mut String) {
+        let mut obj = JsonObject::new(out);
+        obj.field("type", "FeatureCollection").field("features", &self.features);
+        obj.finish();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tracks::{TrackId, TrackStore, TrackUpdate};
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn properties_are_replaced_by_name() {
+        let feature = Feature::point(1.0, 2.0)
+            .property("a", &1u8)
+            .property("b", "x")
+            .property("a", &2u8);
+
+        assert_eq!(
+            feature.to_json(),
+            r#"{"type":"Feature","geometry":{"type":"Point","coordinates":[2,1]},"properties":{"a":2,"b":"x"}}"#
+        );
+    }
+
+    #[test]
+    fn collection_from_tracks_skips_non_wgs84_positions() {
+        let mut store = TrackStore::new(Duration::from_secs(10));
+        let now = SystemTime::UNIX_EPOCH;
+        store.update(TrackId::new(1, 2, 3), TrackUpdate {
+            position: Some(Position::Wgs84 { latitude: 45.5, longitude: 9.25 }),
+            callsign: Some("AZA123".to_string()),
+            ..Default::default()
+        }, now);
+        store.update(TrackId::new(1, 2, 4), TrackUpdate {
+            position: Some(Position::Polar { range: 10.0, azimuth: 90.0 }),
+            ..Default::default()
+        }, now);
+
+        let collection = FeatureCollection::from_tracks(store.iter());
+
+        assert_eq!(
+            collection.to_json(),
+            concat!(
+                r#"{"type":"FeatureCollection","features":[{"type":"Feature","#,
+                r#""geometry":{"type":"Point","coordinates":[9.25,45.5]},"#,
+                r#""properties":{"sac":1,"sic":2,"track_number":3,"altitude":null,"#,
+                r#""callsign":"AZA123","time_of_day":null}}]}"#
+            )
+        );
+    }
+}
@@ -6,7 +6,17 @@
 //! ## Crate Structure
 //!
 //! - [`rcore`] - Core runtime types (BitReader, BitWriter, Encode, Decode, Fspec)
-//! - [`codegen`] - Code generation from XML definitions
+//! - `codegen` - Code generation from XML definitions (requires the `codegen`
+//!   feature)
+//! - [`rotation`] - Antenna rotation tracking from CAT034-style service messages
+//! - [`dedup`] - Duplicate record detection for redundant distribution paths
+//! - [`pacing`] - Rate limiting of encoded output, e.g. when replaying recordings
+//! - [`tracks`] - Latest-state track store assembled from target reports
+//! - [`cat021_support`] - Interpretation of the CAT021 (ADS-B) quality indicators
+//! - [`channels`] - Several simultaneous inputs merged in receive time order
+//! - `geojson` - GeoJSON export of positions (requires the `geojson` feature)
+//! - `metrics` - Prometheus exporter for stream metrics (requires the
+//!   `prometheus` feature)
 //!
 //! ## Usage
 //!
@@ -16,12 +26,20 @@
 //! use rasterix::rcore::{BitReader, BitWriter, Decode, Encode};
 //! ```
 //!
-//! For code generation, use the `codegen` module:
+//! For code generation, enable the `codegen` feature, usually only for the
+//! build script, and use the `codegen` module:
+//!
+//! ```toml
+//! [build-dependencies]
+//! rasterix = { version = "0.1", features = ["codegen"] }
+//! ```
 //!
 //! ```ignore
 //! use rasterix::codegen::builder::RustBuilder;
 //! ```
 
+#![cfg_attr(docsrs, feature(doc_cfg))]
+
 /// Re-export of rasterix-core as `rcore`.
 ///
 /// Contains runtime types for ASTERIX message encoding/decoding:
@@ -29,6 +47,8 @@
 /// - [`BitWriter`](rcore::BitWriter) - Bit-level writing to byte streams
 /// - [`Decode`](rcore::Decode) - Trait for decoding ASTERIX structures
 /// - [`Encode`](rcore::Encode) - Trait for encoding ASTERIX structures
+/// - [`DecodeInto`](rcore::DecodeInto) - Trait for decoding into an existing value
+/// - [`DecodeContext`](rcore::DecodeContext) - Reusable buffers for `decode_into`
 /// - [`Fspec`](rcore::Fspec) - ASTERIX Field Specification handling
 /// - [`DecodeError`](rcore::DecodeError) - Error type for decode operations
 pub mod rcore {
@@ -42,9 +62,24 @@ pub mod rcore {
 /// - [`parse`](codegen::parse) - XML parsing
 /// - [`transform`](codegen::transform) - IR transformation
 /// - [`generate`](codegen::generate) - Rust code generation
+#[cfg(feature = "codegen")]
+#[cfg_attr(docsrs, doc(cfg(feature = "codegen")))]
 pub mod codegen {
     pub use rasterix_codegen::*;
 }
 
+pub mod cat021_support;
+pub mod channels;
+pub mod dedup;
+#[cfg(feature = "geojson")]
+#[cfg_attr(docsrs, doc(cfg(feature = "geojson")))]
+pub mod geojson;
+#[cfg(feature = "prometheus")]
+#[cfg_attr(docsrs, doc(cfg(feature = "prometheus")))]
+pub mod metrics;
+pub mod pacing;
+pub mod rotation;
+pub mod tracks;
+
 // Re-export commonly used types at the crate root for convenience
 pub use rcore::{BitReader, BitWriter, Decode, DecodeError, Encode, Fspec};
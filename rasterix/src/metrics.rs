@@ -0,0 +1,145 @@
+//! Prometheus exporter for stream metrics.
+//!
+//! [`PrometheusMetrics`] implements [`StreamMetrics`] by counting the events
+//! of a [`RecordStream`](rasterix_core::RecordStream) in Prometheus counters,
+//! registered on a [`Registry`] the service already exposes:
+//!
+//! | Metric | Labels |
+//! |--------|--------|
+//! | `asterix_blocks_total` | `category` |
+//! | `asterix_bytes_total` | `category` |
+//! | `asterix_records_total` | `category` |
+//! | `asterix_decode_errors_total` | `category` (`none` outside records) |
+//! | `asterix_item_presence_total` | `category`, `frn` |
+//! | `asterix_skipped_bytes_total` | |
+//!
+//! `frn` is the field reference number of the item, counted from 1 as in
+//! the XML definitions.
+//!
+//! Only available with the `prometheus` feature.
+
+use prometheus::{IntCounter, IntCounterVec, Opts, Registry};
+use rasterix_core::metrics::present_items;
+use rasterix_core::{DecodeError, Header, StreamMetrics};
+
+/// Stream metrics counted in Prometheus counters.
+///
+/// Counters are shared handles, so clones of a `PrometheusMetrics` attached
+/// to several streams add up into the same metrics.
+///
+/// # Example
+///
+/// ```
+/// use prometheus::Registry;
+/// use rasterix::metrics::PrometheusMetrics;
+/// use rasterix::rcore::{Header, StreamMetrics};
+///
+/// let registry = Registry::new();
+/// let mut metrics = PrometheusMetrics::new(&registry).unwrap();
+/// metrics.block_read(&Header { category: 48, length: 10 });
+///
+/// let families = registry.gather();
+/// assert!(families.iter().any(|f| f.name() == "asterix_blocks_total"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct PrometheusMetrics {
+    blocks: IntCounterVec,
+    bytes: IntCounterVec,
+    records: IntCounterVec,
+    errors: IntCounterVec,
+    item_presence: IntCounterVec,
+    skipped_bytes: IntCounter,
+}
+
+impl PrometheusMetrics {
+    /// Creates the counters and registers them on `registry`.
+    ///
+    /// Fails if metrics with the same names are already registered.
+    pub fn new(registry: &Registry) -> prometheus::Result<Self> {
+        let metrics = Self {
+            blocks: counter_vec("asterix_blocks_total", "Data blocks read.", &["category"])?,
+            bytes: counter_vec("asterix_bytes_total", "Bytes of data blocks read.", &["category"])?,
+            records: counter_vec("asterix_records_total", "Records decoded.", &["category"])?,
+            errors: counter_vec("asterix_decode_errors_total", "Decoding errors.", &["category"])?,
+            item_presence: counter_vec(
+                "asterix_item_presence_total",
+                "Decoded records in which an item was present.",
+                &["category", "frn"],
+            )?,
+            skipped_bytes: IntCounter::new(
+                "asterix_skipped_bytes_total",
+                "Bytes discarded while resynchronizing.",
+            )?,
+        };
+
+        registry.register(Box::new(metrics.blocks.clone()))?;
+        registry.register(Box::new(metrics.bytes.clone()))?;
+        registry.register(Box::new(metrics.records.clone()))?;
+        registry.register(Box::new(metrics.errors.clone()))?;
+        registry.register(Box::new(metrics.item_presence.clone()))?;
+        registry.register(Box::new(metrics.skipped_bytes.clone()))?;
+        Ok(metrics)
+    }
+}
+
+fn counter_vec(name: &str, help: &str, labels: &[&str]) -> prometheus::Result<IntCounterVec> {
+    IntCounterVec::new(Opts::new(name, help), labels)
+}
+
+impl StreamMetrics for PrometheusMetrics {
+    fn block_read(&mut self, header: &Header) {
+        let category = header.category.to_string();
+        self.blocks.with_label_values(&[&category]).inc();
+        self.bytes.with_label_values(&[&category]).inc_by(header.length as u64);
+    }
+
+    fn record_decoded(&mut self, category: u8, encoded: &[u8]) {
+        let category = category.to_string();
+        self.records.with_label_values(&[&category]).inc();
+        for position in present_items(encoded) {
+            self.item_presence
+                .with_label_values(&[category.as_str(), &(position + 1).to_string()])
+                .inc();
+        }
+    }
+
+    fn decode_error(&mut self, category: Option<u8>, _error: &DecodeError) {
+        let category = category.map_or_else(|| "none".to_string(), |c| c.to_string());
+        self.errors.with_label_values(&[&category]).inc();
+    }
+
+    fn bytes_skipped(&mut self, count: u64) {
+        self.skipped_bytes.inc_by(count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_update_labelled_counters() {
+        let registry = Registry::new();
+        let mut metrics = PrometheusMetrics::new(&registry).unwrap();
+
+        metrics.block_read(&Header { category: 48, length: 10 });
+        metrics.record_decoded(48, &[0x81, 0x40, 0x00]);
+        metrics.record_decoded(48, &[0x80, 0x00]);
+        metrics.decode_error(None, &DecodeError::InvalidData("bad header"));
+        metrics.bytes_skipped(3);
+
+        assert_eq!(metrics.bytes.with_label_values(&["48"]).get(), 10);
+        assert_eq!(metrics.records.with_label_values(&["48"]).get(), 2);
+        assert_eq!(metrics.item_presence.with_label_values(&["48", "1"]).get(), 2);
+        assert_eq!(metrics.item_presence.with_label_values(&["48", "9"]).get(), 1);
+        assert_eq!(metrics.errors.with_label_values(&["none"]).get(), 1);
+        assert_eq!(metrics.skipped_bytes.get(), 3);
+    }
+
+    #[test]
+    fn registering_twice_fails() {
+        let registry = Registry::new();
+        PrometheusMetrics::new(&registry).unwrap();
+        assert!(PrometheusMetrics::new(&registry).is_err());
+    }
+}
@@ -0,0 +1,190 @@
+//! Pacing of encoded output.
+//!
+//! Replaying a recording at full speed floods receivers sized for live
+//! traffic.  A [`Pacer`] limits the records and/or bytes sent per second,
+//! allowing short bursts, and [`PacedWriter`] applies it to a [`Write`]
+//! sink by sleeping before each data block that would exceed the rate.
+//!
+//! # Example
+//!
+//! ```
+//! use std::time::{Duration, Instant};
+//! use rasterix::pacing::Pacer;
+//!
+//! // 100 records per second, in bursts of at most 10.
+//! let mut pacer = Pacer::new().records_per_second(100.0, 10);
+//! let start = Instant::now();
+//!
+//! assert_eq!(pacer.reserve_at(start, 10, 480), Duration::ZERO);
+//! // The burst is spent: the next 5 records wait for 50 ms of refill.
+//! assert_eq!(pacer.reserve_at(start, 5, 240), Duration::from_millis(50));
+//! ```
+
+use std::io::{self, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Token bucket allowing `rate` units per second in bursts of `burst`.
+#[derive(Debug, Clone)]
+struct Bucket {
+    rate: f64,
+    burst: f64,
+    /// Units available at `updated`; negative while sends are owed.
+    tokens: f64,
+    updated: Option<Instant>,
+}
+
+impl Bucket {
+    fn new(rate: f64, burst: u32) -> Self {
+        assert!(rate > 0.0 && rate.is_finite(), "rate must be positive, got {}", rate);
+        let burst = burst.max(1) as f64;
+        Self { rate, burst, tokens: burst, updated: None }
+    }
+
+    /// Takes `units` at `now`, returning how long to wait before they
+    /// are available.
+    fn take(&mut self, now: Instant, units: usize) -> Duration {
+        if let Some(updated) = self.updated {
+            let elapsed = now.saturating_duration_since(updated).as_secs_f64();
+            self.tokens = (self.tokens + elapsed * self.rate).min(self.burst);
+        }
+        self.updated = Some(now);
+
+        self.tokens -= units as f64;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+}
+
+/// Limits the rate of data blocks by records and/or bytes per second.
+///
+/// Each limit is a token bucket: up to `burst` records (or bytes) go out
+/// at once, then sending is held to the rate.  A block larger than the
+/// burst is still sent, after waiting for as long as it takes at the rate.
+/// Without limits, blocks are never delayed.
+#[derive(Debug, Clone, Default)]
+pub struct Pacer {
+    records: Option<Bucket>,
+    bytes: Option<Bucket>,
+}
+
+impl Pacer {
+    /// Creates a pacer without limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits the output to `rate` records per second, in bursts of at
+    /// most `burst` records.
+    ///
+    /// Panics if `rate` isn't positive.
+    pub fn records_per_second(mut self, rate: f64, burst: u32) -> Self {
+        self.records = Some(Bucket::new(rate, burst));
+        self
+    }
+
+    /// Limits the output to `rate` bytes per second, in bursts of at most
+    /// `burst` bytes.
+    ///
+    /// Panics if `rate` isn't positive.
+    pub fn bytes_per_second(mut self, rate: f64, burst: u32) -> Self {
+        self.bytes = Some(Bucket::new(rate, burst));
+        self
+    }
+
+    /// Reserves the sending of a block of `records` records and `bytes`
+    /// bytes at `now`, returning how long to wait before sending it.
+    pub fn reserve_at(&mut self, now: Instant, records: usize, bytes: usize) -> Duration {
+        let records = self.records.as_mut().map_or(Duration::ZERO, |bucket| bucket.take(now, records));
+        let bytes = self.bytes.as_mut().map_or(Duration::ZERO, |bucket| bucket.take(now, bytes));
+        records.max(bytes)
+    }
+
+    /// Waits until a block of `records` records and `bytes` bytes may be
+    /// sent.
+    pub fn wait(&mut self, records: usize, bytes: usize) {
+        let delay = self.reserve_at(Instant::now(), records, bytes);
+        if !delay.is_zero() {
+            thread::sleep(delay);
+        }
+    }
+}
+
+/// Writes data blocks to `inner`, paced by a [`Pacer`].
+#[derive(Debug)]
+pub struct PacedWriter<W> {
+    inner: W,
+    pacer: Pacer,
+}
+
+impl<W: Write> PacedWriter<W> {
+    pub fn new(inner: W, pacer: Pacer) -> Self {
+        Self { inner, pacer }
+    }
+
+    /// Writes `block`, holding `records` records, once the pacer allows it.
+    pub fn write_block(&mut self, block: &[u8], records: usize) -> io::Result<()> {
+        self.pacer.wait(records, block.len());
+        self.inner.write_all(block)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Consumes the writer and returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unlimited_pacer_never_waits() {
+        let mut pacer = Pacer::new();
+        assert_eq!(pacer.reserve_at(Instant::now(), 1000, 1_000_000), Duration::ZERO);
+    }
+
+    #[test]
+    fn bucket_refills_at_rate_up_to_burst() {
+        let start = Instant::now();
+        let mut pacer = Pacer::new().bytes_per_second(1000.0, 100);
+
+        assert_eq!(pacer.reserve_at(start, 0, 100), Duration::ZERO);
+        assert_eq!(pacer.reserve_at(start, 0, 50), Duration::from_millis(50));
+
+        // A long pause refills only up to the burst.
+        let later = start + Duration::from_secs(10);
+        assert_eq!(pacer.reserve_at(later, 0, 100), Duration::ZERO);
+        assert_eq!(pacer.reserve_at(later, 0, 1), Duration::from_millis(1));
+    }
+
+    #[test]
+    fn block_waits_for_the_slower_limit() {
+        let start = Instant::now();
+        let mut pacer = Pacer::new().records_per_second(10.0, 1).bytes_per_second(1000.0, 1000);
+
+        assert_eq!(pacer.reserve_at(start, 1, 100), Duration::ZERO);
+        // 2 records are owed at 10/s; the bytes are within the burst.
+        assert_eq!(pacer.reserve_at(start, 2, 100), Duration::from_millis(200));
+    }
+
+    #[test]
+    fn paced_writer_writes_blocks() {
+        let mut writer = PacedWriter::new(Vec::new(), Pacer::new().records_per_second(1000.0, 10));
+        writer.write_block(&[48, 0x00, 0x04, 0x80], 1).unwrap();
+        writer.write_block(&[48, 0x00, 0x04, 0x00], 1).unwrap();
+        assert_eq!(writer.into_inner(), [48, 0x00, 0x04, 0x80, 48, 0x00, 0x04, 0x00]);
+    }
+}
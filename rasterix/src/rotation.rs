@@ -0,0 +1,255 @@
+//! Antenna rotation tracking from CAT034-style service messages.
+//!
+//! Radar service messages report north marker crossings and sector crossings
+//! with a time of day, but leave it to the receiver to work out the antenna
+//! period and where the antenna points between messages.  [`RotationTracker`]
+//! does that bookkeeping independently of the generated CAT034 types: feed it
+//! the decoded times and sector numbers and query the current estimate.
+
+/// Number of seconds in a day, used to unwrap time-of-day rollovers.
+pub const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// LSB of an ASTERIX time of day (I034/030, I048/140, ...), in seconds.
+pub const TIME_OF_DAY_LSB: f64 = 1.0 / 128.0;
+
+/// LSB of a sector number (I034/020), in degrees.
+pub const SECTOR_LSB: f64 = 360.0 / 256.0;
+
+/// Converts a raw 24-bit time of day to seconds since midnight.
+pub fn time_of_day_seconds(raw: u32) -> f64 {
+    raw as f64 * TIME_OF_DAY_LSB
+}
+
+/// Converts a raw 8-bit sector number to an azimuth in degrees.
+pub fn sector_to_azimuth(sector: u8) -> f64 {
+    sector as f64 * SECTOR_LSB
+}
+
+/// Converts an azimuth in degrees to the sector that contains it.
+pub fn azimuth_to_sector(azimuth: f64) -> u8 {
+    (azimuth.rem_euclid(360.0) / SECTOR_LSB) as u8
+}
+
+/// Tracks antenna rotation from north marker and sector crossing messages.
+///
+/// Every message is a reference point `(time, azimuth)`, with north markers
+/// at azimuth 0.  The rotation period is estimated from consecutive reference
+/// points and smoothed exponentially; rotations missed entirely (e.g. lost
+/// north markers) are detected against the current estimate and accounted
+/// for.
+///
+/// # Example
+///
+/// ```
+/// use rasterix::rotation::RotationTracker;
+///
+/// let mut tracker = RotationTracker::new();
+/// tracker.north_marker(100.0);
+/// tracker.north_marker(104.0);
+///
+/// assert_eq!(tracker.period(), Some(4.0));
+/// assert_eq!(tracker.azimuth_at(105.0), Some(90.0));
+/// ```
+#[derive(Debug, Clone)]
+pub struct RotationTracker {
+    smoothing: f64,
+    period: Option<f64>,
+    /// Last reference point: (time of day in seconds, azimuth in degrees).
+    last: Option<(f64, f64)>,
+    rotations: u64,
+}
+
+impl RotationTracker {
+    /// Default weight of a new period sample in the smoothed estimate.
+    pub const DEFAULT_SMOOTHING: f64 = 0.25;
+
+    /// Creates a tracker with no rotation history.
+    pub fn new() -> Self {
+        Self {
+            smoothing: Self::DEFAULT_SMOOTHING,
+            period: None,
+            last: None,
+            rotations: 0,
+        }
+    }
+
+    /// Sets the weight (0, 1] of a new period sample in the estimate.
+    ///
+    /// `1.0` uses the latest sample only; smaller values react more slowly
+    /// to jitter in message times.
+    pub fn with_smoothing(mut self, smoothing: f64) -> Self {
+        self.smoothing = smoothing.clamp(f64::EPSILON, 1.0);
+        self
+    }
+
+    /// Records a north marker message received at `time_of_day` seconds.
+    pub fn north_marker(&mut self, time_of_day: f64) {
+        self.update(time_of_day, 0.0);
+    }
+
+    /// Records a sector crossing message for `sector` at `time_of_day`.
+    pub fn sector_crossing(&mut self, time_of_day: f64, sector: u8) {
+        self.update(time_of_day, sector_to_azimuth(sector));
+    }
+
+    /// Returns the estimated rotation period in seconds.
+    pub fn period(&self) -> Option<f64> {
+        self.period
+    }
+
+    /// Returns the estimated rotation speed in degrees per second.
+    pub fn degrees_per_second(&self) -> Option<f64> {
+        self.period.map(|p| 360.0 / p)
+    }
+
+    /// Returns the number of complete rotations observed so far.
+    pub fn rotations(&self) -> u64 {
+        self.rotations
+    }
+
+    /// Estimates the antenna azimuth, in degrees, at `time_of_day`.
+    ///
+    /// Returns `None` until a period has been estimated.
+    pub fn azimuth_at(&self, time_of_day: f64) -> Option<f64> {
+        let (time, azimuth) = self.last?;
+        let period = self.period?;
+        let elapsed = elapsed(time, time_of_day);
+        Some((azimuth + elapsed * 360.0 / period).rem_euclid(360.0))
+    }
+
+    /// Estimates the time of day at which the antenna next reaches `azimuth`
+    /// after the last message.
+    pub fn time_at_azimuth(&self, azimuth: f64) -> Option<f64> {
+        let (time, last_azimuth) = self.last?;
+        let period = self.period?;
+        let delta = (azimuth - last_azimuth).rem_euclid(360.0);
+        Some((time + delta / 360.0 * period).rem_euclid(SECONDS_PER_DAY))
+    }
+
+    fn update(&mut self, time: f64, azimuth: f64) {
+        if let Some((last_time, last_azimuth)) = self.last {
+            let dt = elapsed(last_time, time);
+            let mut turns = (azimuth - last_azimuth).rem_euclid(360.0) / 360.0;
+            if turns == 0.0 {
+                // Same azimuth again: at least one full rotation.
+                turns = 1.0;
+            }
+
+            // Account for whole rotations without any message.
+            if let Some(period) = self.period {
+                let missed = ((dt / period) - turns).round().max(0.0);
+                turns += missed;
+            }
+
+            if dt > 0.0 {
+                let sample = dt / turns;
+                self.period = Some(match self.period {
+                    Some(period) => period + self.smoothing * (sample - period),
+                    None => sample,
+                });
+            }
+
+            let start = last_azimuth / 360.0;
+            self.rotations += (start + turns).floor() as u64;
+        }
+        self.last = Some((time, azimuth));
+    }
+}
+
+impl Default for RotationTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Seconds from `from` to `to`, unwrapping a midnight rollover.
+fn elapsed(from: f64, to: f64) -> f64 {
+    let dt = to - from;
+    if dt < -SECONDS_PER_DAY / 2.0 {
+        dt + SECONDS_PER_DAY
+    } else {
+        dt
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(time_of_day_seconds(128), 1.0);
+        assert_eq!(sector_to_azimuth(64), 90.0);
+        assert_eq!(azimuth_to_sector(90.0), 64);
+        assert_eq!(azimuth_to_sector(-90.0), 192);
+    }
+
+    #[test]
+    fn period_from_north_markers() {
+        let mut tracker = RotationTracker::new();
+        assert_eq!(tracker.period(), None);
+
+        tracker.north_marker(10.0);
+        tracker.north_marker(14.0);
+
+        assert_eq!(tracker.period(), Some(4.0));
+        assert_eq!(tracker.degrees_per_second(), Some(90.0));
+        assert_eq!(tracker.rotations(), 1);
+    }
+
+    #[test]
+    fn period_from_sector_crossings() {
+        let mut tracker = RotationTracker::new();
+        tracker.sector_crossing(0.0, 0);
+        tracker.sector_crossing(1.0, 64);
+
+        assert!(close(tracker.period().unwrap(), 4.0));
+    }
+
+    #[test]
+    fn missed_north_marker() {
+        let mut tracker = RotationTracker::new().with_smoothing(1.0);
+        tracker.north_marker(0.0);
+        tracker.north_marker(4.0);
+        tracker.north_marker(12.0);
+
+        assert!(close(tracker.period().unwrap(), 4.0));
+        assert_eq!(tracker.rotations(), 3);
+    }
+
+    #[test]
+    fn smoothing_weights_new_samples() {
+        let mut tracker = RotationTracker::new().with_smoothing(0.5);
+        tracker.north_marker(0.0);
+        tracker.north_marker(4.0);
+        tracker.north_marker(8.2);
+
+        assert!(close(tracker.period().unwrap(), 4.1));
+    }
+
+    #[test]
+    fn midnight_rollover() {
+        let mut tracker = RotationTracker::new();
+        tracker.north_marker(SECONDS_PER_DAY - 2.0);
+        tracker.north_marker(2.0);
+
+        assert!(close(tracker.period().unwrap(), 4.0));
+        assert!(close(tracker.azimuth_at(3.0).unwrap(), 90.0));
+    }
+
+    #[test]
+    fn azimuth_and_time_estimates() {
+        let mut tracker = RotationTracker::new();
+        tracker.north_marker(0.0);
+        tracker.north_marker(4.0);
+        tracker.sector_crossing(5.0, 64);
+
+        assert!(close(tracker.azimuth_at(6.0).unwrap(), 180.0));
+        assert!(close(tracker.time_at_azimuth(270.0).unwrap(), 7.0));
+        assert!(close(tracker.time_at_azimuth(0.0).unwrap(), 8.0));
+    }
+}
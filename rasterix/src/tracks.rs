@@ -0,0 +1,252 @@
+//! Latest-state track store assembled from CAT048/CAT062-style reports.
+//!
+//! Target reports carry a position, altitude or callsign only when they
+//! change or when the sensor has them, so an application needs to merge
+//! successive reports of a track into its current state and drop tracks
+//! that stopped reporting.  [`TrackStore`] does that bookkeeping
+//! independently of the generated types: extract a [`TrackUpdate`] from
+//! each decoded record and feed it to the store.
+
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
+
+/// Identity of a track: the data source and its track number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TrackId {
+    /// System Area Code of the data source.
+    pub sac: u8,
+    /// System Identification Code of the data source.
+    pub sic: u8,
+    pub track_number: u16,
+}
+
+impl TrackId {
+    pub fn new(sac: u8, sic: u8, track_number: u16) -> Self {
+        Self { sac, sic, track_number }
+    }
+}
+
+/// Reported position of a target.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Position {
+    /// Slant range in NM and azimuth in degrees from the sensor (I048/040).
+    Polar { range: f64, azimuth: f64 },
+    /// Cartesian coordinates in NM relative to the sensor or system
+    /// reference point (I048/042, I062/100).
+    Cartesian { x: f64, y: f64 },
+    /// WGS-84 latitude and longitude in degrees (I062/105).
+    Wgs84 { latitude: f64, longitude: f64 },
+}
+
+/// The data of one target report; absent values leave the track unchanged.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrackUpdate {
+    /// Time of day of the report, in seconds since midnight.
+    pub time_of_day: Option<f64>,
+    pub position: Option<Position>,
+    /// Flight level or geometric altitude, in feet.
+    pub altitude: Option<f64>,
+    pub callsign: Option<String>,
+}
+
+/// Latest known state of a track.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackState {
+    pub id: TrackId,
+    /// Time of day of the latest report that had one.
+    pub time_of_day: Option<f64>,
+    pub position: Option<Position>,
+    pub altitude: Option<f64>,
+    pub callsign: Option<String>,
+    /// Receive time of the first report.
+    pub first_seen: SystemTime,
+    /// Receive time of the latest report.
+    pub last_seen: SystemTime,
+    /// Number of reports merged into the state.
+    pub updates: u64,
+}
+
+impl TrackState {
+    fn new(id: TrackId, recv_time: SystemTime) -> Self {
+        Self {
+            id,
+            time_of_day: None,
+            position: None,
+            altitude: None,
+            callsign: None,
+            first_seen: recv_time,
+            last_seen: recv_time,
+            updates: 0,
+        }
+    }
+
+    fn merge(&mut self, update: TrackUpdate, recv_time: SystemTime) {
+        if update.time_of_day.is_some() {
+            self.time_of_day = update.time_of_day;
+        }
+        if update.position.is_some() {
+            self.position = update.position;
+        }
+        if update.altitude.is_some() {
+            self.altitude = update.altitude;
+        }
+        if update.callsign.is_some() {
+            self.callsign = update.callsign;
+        }
+        self.last_seen = self.last_seen.max(recv_time);
+        self.updates += 1;
+    }
+}
+
+/// Per-track latest state, with expiry of tracks that stopped reporting.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, SystemTime};
+/// use rasterix::tracks::{Position, TrackId, TrackStore, TrackUpdate};
+///
+/// let mut store = TrackStore::new(Duration::from_secs(20));
+/// let id = TrackId::new(1, 2, 42);
+/// let t0 = SystemTime::UNIX_EPOCH;
+///
+/// store.update(id, TrackUpdate { altitude: Some(35_000.0), ..Default::default() }, t0);
+/// store.update(id, TrackUpdate {
+///     position: Some(Position::Polar { range: 40.0, azimuth: 90.0 }),
+///     ..Default::default()
+/// }, t0 + Duration::from_secs(4));
+///
+/// let track = store.get(&id).unwrap();
+/// assert_eq!(track.altitude, Some(35_000.0));
+/// assert_eq!(track.updates, 2);
+///
+/// store.expire(t0 + Duration::from_secs(30));
+/// assert!(store.is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct TrackStore {
+    timeout: Duration,
+    tracks: HashMap<TrackId, TrackState>,
+}
+
+impl TrackStore {
+    /// Creates an empty store dropping tracks not updated for `timeout`.
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            tracks: HashMap::new(),
+        }
+    }
+
+    /// Merges a report received at `recv_time` into its track, creating the
+    /// track if needed, and returns the updated state.
+    pub fn update(&mut self, id: TrackId, update: TrackUpdate, recv_time: SystemTime) -> &TrackState {
+        let track = self
+            .tracks
+            .entry(id)
+            .or_insert_with(|| TrackState::new(id, recv_time));
+        track.merge(update, recv_time);
+        track
+    }
+
+    /// Removes the tracks not updated within the timeout before `now` and
+    /// returns them.
+    pub fn expire(&mut self, now: SystemTime) -> Vec<TrackState> {
+        let timeout = self.timeout;
+        let is_stale = |track: &TrackState| {
+            now.duration_since(track.last_seen).is_ok_and(|age| age > timeout)
+        };
+
+        let stale: Vec<TrackId> = self
+            .tracks
+            .values()
+            .filter(|track| is_stale(track))
+            .map(|track| track.id)
+            .collect();
+        stale.iter().filter_map(|id| self.tracks.remove(id)).collect()
+    }
+
+    /// Removes a track, e.g. on a track termination report.
+    pub fn remove(&mut self, id: &TrackId) -> Option<TrackState> {
+        self.tracks.remove(id)
+    }
+
+    /// Returns the state of a track.
+    pub fn get(&self, id: &TrackId) -> Option<&TrackState> {
+        self.tracks.get(id)
+    }
+
+    /// Iterates over every track, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &TrackState> {
+        self.tracks.values()
+    }
+
+    /// Returns the number of tracks.
+    pub fn len(&self) -> usize {
+        self.tracks.len()
+    }
+
+    /// Returns `true` if the store holds no track.
+    pub fn is_empty(&self) -> bool {
+        self.tracks.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn updates_merge_present_values() {
+        let mut store = TrackStore::new(Duration::from_secs(10));
+        let id = TrackId::new(1, 2, 7);
+
+        store.update(id, TrackUpdate {
+            time_of_day: Some(100.0),
+            callsign: Some("AZA123".to_string()),
+            altitude: Some(10_000.0),
+            ..Default::default()
+        }, at(1));
+        let track = store.update(id, TrackUpdate {
+            time_of_day: Some(104.0),
+            altitude: Some(11_000.0),
+            ..Default::default()
+        }, at(5));
+
+        assert_eq!(track.time_of_day, Some(104.0));
+        assert_eq!(track.altitude, Some(11_000.0));
+        assert_eq!(track.callsign.as_deref(), Some("AZA123"));
+        assert_eq!(track.first_seen, at(1));
+        assert_eq!(track.last_seen, at(5));
+        assert_eq!(track.updates, 2);
+    }
+
+    #[test]
+    fn tracks_are_separate_per_source() {
+        let mut store = TrackStore::new(Duration::from_secs(10));
+        store.update(TrackId::new(1, 2, 7), TrackUpdate::default(), at(0));
+        store.update(TrackId::new(1, 3, 7), TrackUpdate::default(), at(0));
+
+        assert_eq!(store.len(), 2);
+        assert!(store.remove(&TrackId::new(1, 3, 7)).is_some());
+        assert_eq!(store.iter().map(|t| t.id).collect::<Vec<_>>(), vec![TrackId::new(1, 2, 7)]);
+    }
+
+    #[test]
+    fn stale_tracks_expire() {
+        let mut store = TrackStore::new(Duration::from_secs(10));
+        store.update(TrackId::new(1, 2, 1), TrackUpdate::default(), at(0));
+        store.update(TrackId::new(1, 2, 2), TrackUpdate::default(), at(8));
+
+        assert!(store.expire(at(10)).is_empty());
+
+        let expired = store.expire(at(12));
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].id, TrackId::new(1, 2, 1));
+        assert_eq!(store.len(), 1);
+    }
+}
@@ -3,9 +3,12 @@
 //! These tests verify that the high-level Builder API correctly
 //! orchestrates the parsing, transformation, and code generation pipeline.
 
-use rasterix_codegen::builder::{Builder, RustBuilder};
+use rasterix_codegen::builder::{BuildObserver, BuildStage, Builder, RustBuilder};
+use rasterix_codegen::generate::{docs_gen::DocsBackend, ffi_gen::FfiBackend};
+use rasterix_codegen::transform::plugins::{Attributes, ElementPlugin, TokenStream};
 use std::fs;
-use test_utils::{cleanup_temp_files, create_temp_file, load_fixture};
+use std::path::Path;
+use test_utils::{cleanup_temp_files, create_temp_file, fixture_path, load_fixture};
 
 // ============================================================================
 // Basic Builder Tests
@@ -51,6 +54,19 @@ fn builder_generates_item_structs() {
     assert!(code.contains("Item020"));
 }
 
+#[test]
+fn builder_builds_from_str() {
+    let path = fixture_path("valid", "multi_item_record.xml");
+    let xml = load_fixture("valid", "multi_item_record.xml");
+
+    let builder = RustBuilder::new();
+    let code = builder.build_from_str(&xml).unwrap();
+
+    assert_eq!(code, builder.build(path.to_str().unwrap()).unwrap());
+    assert_eq!(code, builder.build_tokens(&xml).unwrap().to_string());
+    assert!(code.contains("pub struct Item240"));
+}
+
 // ============================================================================
 // Build File Tests
 // ============================================================================
@@ -126,7 +142,9 @@ fn builder_fails_on_missing_file() {
     let builder = RustBuilder::new();
     let result = builder.build("nonexistent_file.xml");
 
-    assert!(result.is_err());
+    let err = result.unwrap_err();
+    assert_eq!(err.stage, BuildStage::Io);
+    assert_eq!(err.file.as_deref(), Some(std::path::Path::new("nonexistent_file.xml")));
 }
 
 #[test]
@@ -141,6 +159,118 @@ fn builder_fails_on_invalid_xml() {
     assert!(result.is_err());
 }
 
+#[test]
+fn builder_from_str_fails_on_invalid_xml() {
+    let result = RustBuilder::new().build_from_str("<invalid xml");
+
+    let err = result.unwrap_err();
+    assert_eq!(err.stage, BuildStage::Parse);
+    assert_eq!(err.file, None);
+}
+
+#[test]
+fn builder_reports_invalid_definitions_with_their_file() {
+    let path = fixture_path("invalid", "duplicate_frn.xml");
+
+    let err = RustBuilder::new().build(path.to_str().unwrap()).unwrap_err();
+
+    assert_eq!(err.stage, BuildStage::Transform);
+    assert_eq!(
+        err.to_string(),
+        format!("{}: Invalid definition: error[E003]: item 020: FRN 1 is also used by item 010 (frn-collision)", path.display())
+    );
+    assert!(std::error::Error::source(&err).is_some());
+}
+
+#[test]
+fn builder_reports_definitions_that_cant_be_lowered() {
+    let err = RustBuilder::new().build_from_str(r#"<category id="1">
+        <item id="10" frn="1">
+            <fixed bytes="1">
+                <field name="a" bits="8"/>
+                <derived name="b" expr="a.sqrt()"/>
+            </fixed>
+        </item>
+    </category>"#).unwrap_err();
+
+    assert_eq!(err.stage, BuildStage::Transform);
+    assert_eq!(
+        err.to_string(),
+        "Invalid definition: Derived field b uses `a . sqrt ()`: only numeric literals, fields, arithmetic, casts and parentheses are allowed"
+    );
+}
+
+// ============================================================================
+// Feature Configuration Tests
+// ============================================================================
+
+#[test]
+fn builder_gates_items_from_features_file() {
+    let xml_path = create_temp_file(&load_fixture("valid", "multi_item_record.xml"), "xml");
+    let features_path = create_temp_file(
+        r#"<features><feature name="cat_extra"><item id="240"/></feature></features>"#,
+        "xml",
+    );
+
+    let builder = RustBuilder::new().features_file(&features_path);
+    let result = builder.build(xml_path.to_str().unwrap());
+
+    cleanup_temp_files();
+
+    let code = result.unwrap();
+    assert!(code.contains("# [cfg (feature = \"cat_extra\")] mod item240"));
+    assert!(!code.contains("mod item010"));
+}
+
+#[test]
+fn backend_builder_writes_the_files_of_the_backend() {
+    fs::create_dir_all("target/test_backend").unwrap();
+    fs::write("target/test_backend/cat048.xml", load_fixture("valid", "simple_fixed.xml")).unwrap();
+
+    let builder = RustBuilder::new().backend::<FfiBackend>();
+    let result = builder.build_file("target/test_backend/cat048.xml", "target/test_backend/out");
+    let docs = RustBuilder::new().backend::<DocsBackend>().build_file("target/test_backend/cat048.xml", "target/test_backend/out");
+
+    let paths = result.unwrap();
+    assert_eq!(paths, [
+        Path::new("target/test_backend/out/cat048.rs"),
+        Path::new("target/test_backend/out/cat048.h"),
+    ]);
+    let code = fs::read_to_string(&paths[0]).unwrap();
+    assert!(code.contains("pub mod cat001"));
+    assert!(code.contains("pub mod cat001_ffi"));
+    assert!(fs::read_to_string(&paths[1]).unwrap().contains("#ifndef RASTERIX_CAT001_H"));
+    assert_eq!(docs.unwrap(), [Path::new("target/test_backend/out/cat048.md")]);
+
+    fs::remove_dir_all("target/test_backend").ok();
+}
+
+#[test]
+fn backend_builder_applies_the_builder_options() {
+    let xml_path = create_temp_file(&load_fixture("valid", "multi_item_record.xml"), "xml");
+    let features_path = create_temp_file(
+        r#"<features><feature name="cat_extra"><item id="240"/></feature></features>"#,
+        "xml",
+    );
+
+    let builder = RustBuilder::new().features_file(&features_path).backend::<FfiBackend>();
+    let result = builder.build(xml_path.to_str().unwrap());
+
+    cleanup_temp_files();
+
+    let code = result.unwrap();
+    assert!(code.contains("# [cfg (feature = \"cat_extra\")] mod item240"));
+    assert!(code.contains("# [cfg (feature = \"cat_extra\")] # [unsafe (no_mangle)] pub unsafe extern \"C\" fn cat048_has_item240"));
+}
+
+#[test]
+fn builder_fails_on_missing_features_file() {
+    let builder = RustBuilder::new().features_file("nonexistent_features.xml");
+    let result = builder.build(fixture_path("valid", "simple_fixed.xml").to_str().unwrap());
+
+    assert!(result.unwrap_err().to_string().contains("nonexistent_features.xml"));
+}
+
 // ============================================================================
 // Complex Fixture Tests
 // ============================================================================
@@ -230,3 +360,150 @@ fn builder_default_trait() {
 
     assert!(result.is_ok());
 }
+
+// ============================================================================
+// Verify Tests
+// ============================================================================
+
+#[test]
+fn builder_verify_reports_changed_bits() {
+    let temp_path = create_temp_file(&load_fixture("valid", "spare_bits.xml"), "xml");
+    let builder = RustBuilder::new();
+
+    let clean = builder.verify(temp_path.to_str().unwrap(), &[0x01, 0x00, 0x06, 0x80, 0x2A, 0x00]);
+    let report = builder.verify(temp_path.to_str().unwrap(), &[0x01, 0x00, 0x06, 0x80, 0x2A, 0x01]);
+
+    cleanup_temp_files();
+
+    assert!(clean.unwrap().is_lossless());
+    let report = report.unwrap();
+    assert!(!report.is_lossless());
+    assert!(report.to_string().contains("byte 5 bit 0: expected 1, got 0"));
+}
+
+// ============================================================================
+// Post-Processing Tests
+// ============================================================================
+
+#[test]
+fn builder_post_processes_in_order() {
+    let builder = RustBuilder::new()
+        .with_post_process(|code| format!("pub mod wrapped {{ {} }}", code).parse().unwrap())
+        .with_post_process(|code| format!("{} pub const AFTER: u8 = 0;", code).parse().unwrap());
+
+    let code = builder.build_from_str(&load_fixture("valid", "simple_fixed.xml")).unwrap();
+
+    assert!(code.starts_with("pub mod wrapped {"), "{}", code);
+    assert!(code.contains("pub mod cat001"), "{}", code);
+    assert!(code.ends_with("} pub const AFTER : u8 = 0 ;"), "{}", code);
+}
+
+// ============================================================================
+// Element Plugin Tests
+// ============================================================================
+
+/// A checksum written as `<crc bits="N"/>`, encoded as all ones.
+struct Crc;
+
+impl ElementPlugin for Crc {
+    fn tag(&self) -> &str {
+        "crc"
+    }
+
+    fn bits(&self, attributes: &Attributes) -> Result<usize, String> {
+        attributes.get("bits").ok_or("missing `bits`")?.parse().map_err(|e| format!("invalid `bits`: {}", e))
+    }
+
+    fn encode(&self, _: &Attributes, bits: usize) -> TokenStream {
+        format!("writer.write_bits(u64::MAX >> (64 - {0}), {0})?;", bits).parse().unwrap()
+    }
+}
+
+#[test]
+fn builder_generates_custom_element_code() {
+    let builder = RustBuilder::new().element_plugin(Crc);
+
+    let code = builder.build_from_str(r#"<category id="1">
+        <item id="10" frn="1">
+            <fixed bytes="3"><field name="a" bits="8"/><crc bits="16"/></fixed>
+        </item>
+    </category>"#).unwrap();
+
+    assert!(code.contains("pub struct Item010 { pub a : u8 }"), "{}", code);
+    assert!(code.contains("reader . read_bits (16usize) ? ;"), "{}", code);
+    assert!(code.contains("writer . write_bits (u64 :: MAX >> (64 - 16) , 16) ?;"), "{}", code);
+}
+
+#[test]
+fn builder_fails_on_rejected_custom_element() {
+    let builder = RustBuilder::new().element_plugin(Crc);
+
+    let err = builder.build_from_str(r#"<category id="1">
+        <item id="10" frn="1">
+            <fixed bytes="1"><crc/></fixed>
+        </item>
+    </category>"#).unwrap_err();
+
+    assert_eq!(err.stage, BuildStage::Transform);
+    assert_eq!(err.to_string(), "Invalid custom element: item 010: <crc>: missing `bits`");
+}
+
+// ============================================================================
+// Observer Tests
+// ============================================================================
+
+/// Records the events of a build as lines.
+#[derive(Clone, Default)]
+struct Recorder(std::sync::Arc<std::sync::Mutex<Vec<String>>>);
+
+impl BuildObserver for Recorder {
+    fn on_stage_start(&self, stage: BuildStage, _: Option<&std::path::Path>) {
+        self.0.lock().unwrap().push(format!("start {}", stage));
+    }
+
+    fn on_stage_finish(&self, stage: BuildStage, _: Option<&std::path::Path>, _: std::time::Duration) {
+        self.0.lock().unwrap().push(format!("finish {}", stage));
+    }
+
+    fn on_warning(&self, file: Option<&std::path::Path>, message: &str) {
+        self.0.lock().unwrap().push(format!("warning {:?}: {}", file.is_some(), message));
+    }
+}
+
+#[test]
+fn builder_reports_stages_and_warnings() {
+    let recorder = Recorder::default();
+    let builder = RustBuilder::new().observer(recorder.clone());
+
+    builder.build_tokens(r#"<category id="1">
+        <item id="20" frn="2"><fixed bytes="1"><field name="b" bits="8"/></fixed></item>
+        <item id="10" frn="1"><fixed bytes="1"><field name="a" bits="8"/></fixed></item>
+    </category>"#).unwrap();
+
+    assert_eq!(*recorder.0.lock().unwrap(), [
+        "start parse",
+        "finish parse",
+        "start transform",
+        "warning false: warning[W003]: item 010: FRN 1 is listed after FRN 2 of item 020 (unordered-items)",
+        "finish transform",
+        "start generate",
+        "finish generate",
+    ]);
+}
+
+#[test]
+fn builder_reports_failed_stages_by_error_only() {
+    let recorder = Recorder::default();
+    let path = fixture_path("invalid", "bit_mismatch.xml");
+
+    let result = RustBuilder::new().observer(recorder.clone()).build(path.to_str().unwrap());
+
+    assert_eq!(result.unwrap_err().stage, BuildStage::Transform);
+    assert_eq!(*recorder.0.lock().unwrap(), [
+        "start io",
+        "finish io",
+        "start parse",
+        "finish parse",
+        "start transform",
+    ]);
+}
@@ -0,0 +1,56 @@
+//! Tests of the structure of the facade.
+//!
+//! The crate root re-exports the most used runtime types, `rcore` all of
+//! `rasterix-core`, and `codegen`, with the `codegen` feature, all of
+//! `rasterix-codegen`.  These tests fail to compile if a re-export goes
+//! missing or stops pointing at the underlying crate.
+
+use std::any::TypeId;
+
+#[test]
+fn root_reexports_runtime_types() {
+    assert_eq!(TypeId::of::<rasterix::BitReader<&[u8]>>(), TypeId::of::<rasterix_core::BitReader<&[u8]>>());
+    assert_eq!(TypeId::of::<rasterix::BitWriter<Vec<u8>>>(), TypeId::of::<rasterix_core::BitWriter<Vec<u8>>>());
+    assert_eq!(TypeId::of::<rasterix::DecodeError>(), TypeId::of::<rasterix_core::DecodeError>());
+    assert_eq!(TypeId::of::<rasterix::Fspec>(), TypeId::of::<rasterix_core::Fspec>());
+}
+
+#[test]
+fn rcore_reexports_all_of_core() {
+    assert_eq!(
+        TypeId::of::<rasterix::rcore::recording::RecordingReader<&[u8]>>(),
+        TypeId::of::<rasterix_core::recording::RecordingReader<&[u8]>>()
+    );
+    assert_eq!(TypeId::of::<rasterix::rcore::Header>(), TypeId::of::<rasterix_core::Header>());
+}
+
+#[cfg(feature = "codegen")]
+#[test]
+fn codegen_reexports_all_of_codegen() {
+    assert_eq!(
+        TypeId::of::<rasterix::codegen::builder::RustBuilder>(),
+        TypeId::of::<rasterix_codegen::builder::RustBuilder>()
+    );
+    assert_eq!(
+        TypeId::of::<rasterix::codegen::dynamic::DynamicDecoder>(),
+        TypeId::of::<rasterix_codegen::dynamic::DynamicDecoder>()
+    );
+}
+
+/// Runtime-only users must not build the code generator, neither as a
+/// dependency nor for a build script.
+#[test]
+fn runtime_only_build_has_no_codegen_dependencies() {
+    let output = std::process::Command::new(std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string()))
+        .args(["tree", "--offline", "-p", "rasterix", "--no-default-features", "-e", "normal,build"])
+        .args(["--prefix", "none", "--format", "{p}"])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .expect("cargo tree runs");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let tree = String::from_utf8_lossy(&output.stdout);
+    for name in ["syn ", "quote ", "rasterix-codegen "] {
+        assert!(!tree.lines().any(|line| line.starts_with(name)), "runtime build depends on {}:\n{}", name, tree);
+    }
+}
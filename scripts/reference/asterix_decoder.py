@@ -0,0 +1,49 @@
+#!/usr/bin/env python3
+"""Reference decoder adapter for the `asterix_decoder` Python package.
+
+Reads hex-encoded data blocks from stdin, one per line, and prints every
+decoded field as `<record> <item> <field>=<value>`, the format expected by
+`test_utils::reference`.
+
+Usage:
+
+    pip install asterix_decoder
+    RASTERIX_REFERENCE_DECODER="python3 scripts/reference/asterix_decoder.py" \
+        cargo test -p fixture-tests differential
+"""
+
+import sys
+
+import asterix
+
+
+def fields(item):
+    """Yields (name, value) for every leaf field of a decoded item."""
+    for name, field in item.items():
+        if isinstance(field, dict) and "val" in field:
+            yield name, field["val"]
+        elif isinstance(field, dict):
+            yield from fields(field)
+        elif isinstance(field, list):
+            for entry in field:
+                if isinstance(entry, dict):
+                    yield from fields(entry)
+
+
+def main():
+    record = 0
+    for line in sys.stdin:
+        data = bytes.fromhex(line.strip())
+        if not data:
+            continue
+        for decoded in asterix.parse(data):
+            for key, item in decoded.items():
+                if not key.startswith("I") or not isinstance(item, dict):
+                    continue
+                for name, value in fields(item):
+                    print(f"{record} {key[1:]} {name}={value}")
+            record += 1
+
+
+if __name__ == "__main__":
+    main()
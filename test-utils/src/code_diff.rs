@@ -0,0 +1,227 @@
+//! Structural comparison of generated Rust code.
+//!
+//! Generated code is parsed with `syn` and compared as an AST, so formatting
+//! never matters.  On mismatch the report names the first item that differs
+//! (e.g. `mod cat048 > impl Decode for Item010 > fn decode`) and shows a
+//! unified diff of both sides pretty-printed with `prettyplease`.
+
+use similar::TextDiff;
+use syn::{Item, Type};
+
+/// Formats Rust source with `prettyplease`.
+///
+/// Returns `None` if the source doesn't parse as a Rust file.
+pub fn pretty_print(code: &str) -> Option<String> {
+    syn::parse_file(code).ok().map(|file| prettyplease::unparse(&file))
+}
+
+/// Compares two Rust sources structurally.
+///
+/// Returns `None` if they parse to the same AST, or a report describing the
+/// first differing item followed by a unified diff.  Sources that don't
+/// parse are compared as whitespace-normalized text instead.
+pub fn code_diff(generated: &str, expected: &str) -> Option<String> {
+    let (generated_file, expected_file) = match (canonical_file(generated), canonical_file(expected)) {
+        (Ok(g), Ok(e)) => (g, e),
+        (g, e) => {
+            if crate::normalize_whitespace(generated) == crate::normalize_whitespace(expected) {
+                return None;
+            }
+            let mut report = String::from("code could not be parsed, compared as text\n");
+            if let Err(err) = g {
+                report.push_str(&format!("generated: {}\n", err));
+            }
+            if let Err(err) = e {
+                report.push_str(&format!("expected: {}\n", err));
+            }
+            report.push_str(&unified_diff(expected, generated));
+            return Some(report);
+        }
+    };
+
+    if generated_file == expected_file {
+        return None;
+    }
+
+    let location = first_difference(&expected_file.items, &generated_file.items)
+        .unwrap_or_else(|| "file attributes".to_string());
+
+    Some(format!(
+        "first difference in: {}\n{}",
+        location,
+        unified_diff(
+            &prettyplease::unparse(&expected_file),
+            &prettyplease::unparse(&generated_file),
+        )
+    ))
+}
+
+/// Asserts that two Rust sources are structurally equal.
+///
+/// # Panics
+///
+/// Panics with the report from [`code_diff`] if they differ.
+pub fn assert_code_eq(generated: &str, expected: &str, fixture_name: &str) {
+    if let Some(report) = code_diff(generated, expected) {
+        panic!(
+            "Generated code for '{}' doesn't match expected output.\n{}",
+            fixture_name, report
+        );
+    }
+}
+
+/// Parses `code` in the form it takes after a pretty-print round trip.
+///
+/// `quote!` emits doc comments as raw-string `#[doc]` attributes while a
+/// pretty-printed file reads them back as `///` comments; the round trip
+/// makes both spell their literals the same way.
+fn canonical_file(code: &str) -> syn::Result<syn::File> {
+    let file = syn::parse_file(code)?;
+    syn::parse_file(&prettyplease::unparse(&file))
+}
+
+fn unified_diff(expected: &str, generated: &str) -> String {
+    TextDiff::from_lines(expected, generated)
+        .unified_diff()
+        .context_radius(3)
+        .header("expected", "generated")
+        .to_string()
+}
+
+/// Returns a path to the first item that differs between two item lists.
+fn first_difference(expected: &[Item], generated: &[Item]) -> Option<String> {
+    for (i, exp) in expected.iter().enumerate() {
+        let Some(generated_item) = generated.get(i) else {
+            return Some(format!("{} (missing from generated code)", describe_item(exp)));
+        };
+        if exp == generated_item {
+            continue;
+        }
+
+        let name = describe_item(exp);
+        if name != describe_item(generated_item) {
+            return Some(format!("{} (generated has {})", name, describe_item(generated_item)));
+        }
+
+        let inner = match (exp, generated_item) {
+            (Item::Mod(e), Item::Mod(g)) => match (&e.content, &g.content) {
+                (Some((_, e)), Some((_, g))) => first_difference(e, g),
+                _ => None,
+            },
+            (Item::Impl(e), Item::Impl(g)) => first_impl_difference(&e.items, &g.items),
+            _ => None,
+        };
+
+        return Some(match inner {
+            Some(inner) => format!("{} > {}", name, inner),
+            None => name,
+        });
+    }
+
+    generated
+        .get(expected.len())
+        .map(|extra| format!("{} (unexpected in generated code)", describe_item(extra)))
+}
+
+fn first_impl_difference(expected: &[syn::ImplItem], generated: &[syn::ImplItem]) -> Option<String> {
+    let describe = |item: &syn::ImplItem| match item {
+        syn::ImplItem::Fn(f) => format!("fn {}", f.sig.ident),
+        syn::ImplItem::Const(c) => format!("const {}", c.ident),
+        syn::ImplItem::Type(t) => format!("type {}", t.ident),
+        _ => "item".to_string(),
+    };
+
+    for (i, exp) in expected.iter().enumerate() {
+        match generated.get(i) {
+            Some(generated_item) if generated_item == exp => continue,
+            Some(_) => return Some(describe(exp)),
+            None => return Some(format!("{} (missing from generated code)", describe(exp))),
+        }
+    }
+    generated
+        .get(expected.len())
+        .map(|extra| format!("{} (unexpected in generated code)", describe(extra)))
+}
+
+/// Returns a human-readable name for an item.
+fn describe_item(item: &Item) -> String {
+    match item {
+        Item::Struct(s) => format!("struct {}", s.ident),
+        Item::Enum(e) => format!("enum {}", e.ident),
+        Item::Fn(f) => format!("fn {}", f.sig.ident),
+        Item::Mod(m) => format!("mod {}", m.ident),
+        Item::Const(c) => format!("const {}", c.ident),
+        Item::Static(s) => format!("static {}", s.ident),
+        Item::Trait(t) => format!("trait {}", t.ident),
+        Item::Type(t) => format!("type {}", t.ident),
+        Item::Use(_) => "use".to_string(),
+        Item::Impl(i) => {
+            let self_ty = type_name(&i.self_ty);
+            match &i.trait_ {
+                Some((_, path, _)) => format!("impl {} for {}", path_name(path), self_ty),
+                None => format!("impl {}", self_ty),
+            }
+        }
+        _ => "item".to_string(),
+    }
+}
+
+fn path_name(path: &syn::Path) -> String {
+    path.segments
+        .iter()
+        .map(|s| s.ident.to_string())
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+fn type_name(ty: &Type) -> String {
+    match ty {
+        Type::Path(p) => path_name(&p.path),
+        _ => "_".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formatting_is_ignored() {
+        let a = "pub struct Foo { pub bar : u8 }";
+        let b = "pub struct Foo {\n    pub bar: u8,\n}\n";
+        assert!(code_diff(a, b).is_none());
+    }
+
+    #[test]
+    fn reports_differing_fn_in_impl_in_mod() {
+        let expected = "mod cat { struct A; impl Decode for A { fn decode() { a(); } } }";
+        let generated = "mod cat { struct A; impl Decode for A { fn decode() { b(); } } }";
+
+        let report = code_diff(generated, expected).unwrap();
+        assert!(report.contains("first difference in: mod cat > impl Decode for A > fn decode"));
+        assert!(report.contains("-            a();"));
+        assert!(report.contains("+            b();"));
+    }
+
+    #[test]
+    fn reports_missing_and_extra_items() {
+        let report = code_diff("struct A;", "struct A; struct B;").unwrap();
+        assert!(report.contains("struct B (missing from generated code)"));
+
+        let report = code_diff("struct A; enum C {}", "struct A;").unwrap();
+        assert!(report.contains("enum C (unexpected in generated code)"));
+    }
+
+    #[test]
+    fn unparsable_code_falls_back_to_text() {
+        assert!(code_diff("fn (", "fn  (").is_none());
+        let report = code_diff("fn (", "fn )").unwrap();
+        assert!(report.contains("could not be parsed"));
+    }
+
+    #[test]
+    #[should_panic(expected = "first difference in: struct A")]
+    fn assert_code_eq_panics_on_mismatch() {
+        assert_code_eq("struct A(u8);", "struct A(u16);", "fixture");
+    }
+}
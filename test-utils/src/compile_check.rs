@@ -0,0 +1,200 @@
+//! Compile-checking generated code.
+//!
+//! String assertions can't tell whether generated code actually builds.
+//! [`compile_check`] writes the code into a throwaway crate and runs
+//! `cargo check` on it, so type errors, name collisions and bad attributes
+//! in generated output fail the test suite.
+//!
+//! The throwaway crate depends on a minimal `rasterix` shim that re-exports
+//! `rasterix-core` as `rcore`, which is all generated code needs; this keeps
+//! checks fast since the code generator itself is never built.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Type-checks generated modules as one crate.
+///
+/// Each `(name, code)` pair becomes `pub mod <name>;` in the crate root, so
+/// the code must be a valid module file (inner attributes are allowed).
+///
+/// Returns the compiler output if checking fails.
+pub fn compile_check(modules: &[(&str, &str)]) -> Result<(), String> {
+    compile_check_with_deps(modules, &[])
+}
+
+/// Like [`compile_check`], with extra dependencies for the crate.
+///
+/// Each entry is a line of the `[dependencies]` table, e.g.
+/// `wasm-bindgen = "0.2"`.  Dependencies must be available offline.
+pub fn compile_check_with_deps(modules: &[(&str, &str)], dependencies: &[&str]) -> Result<(), String> {
+    run_check(modules, dependencies, &[])
+}
+
+/// Like [`compile_check`], declaring cargo features for the crate.
+///
+/// Each `(name, enabled)` pair declares the feature `name`, enabled by
+/// default when `enabled` is `true`.
+pub fn compile_check_with_features(modules: &[(&str, &str)], features: &[(&str, bool)]) -> Result<(), String> {
+    run_check(modules, &[], features)
+}
+
+fn run_check(
+    modules: &[(&str, &str)],
+    dependencies: &[&str],
+    features: &[(&str, bool)],
+) -> Result<(), String> {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let root = compile_check_dir();
+    let dir = root.join(format!(
+        "check_{}_{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    let shim = root.join("rasterix-shim");
+
+    write_shim(&shim).map_err(|e| format!("Failed to write shim crate: {}", e))?;
+    write_crate(&dir, &shim, modules, dependencies, features).map_err(|e| format!("Failed to write check crate: {}", e))?;
+
+    let output = Command::new(std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string()))
+        .arg("check")
+        .arg("--quiet")
+        .arg("--offline")
+        .arg("--manifest-path")
+        .arg(dir.join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(root.join("target"))
+        .env_remove("RUSTFLAGS")
+        .output()
+        .map_err(|e| format!("Failed to run cargo: {}", e))?;
+
+    let result = if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).into_owned())
+    };
+
+    fs::remove_dir_all(&dir).ok();
+    result
+}
+
+/// Asserts that generated code compiles as a module named `fixture_name`.
+///
+/// # Panics
+///
+/// Panics with the compiler output if checking fails.
+pub fn assert_compiles(generated: &str, fixture_name: &str) {
+    if let Err(output) = compile_check(&[(fixture_name, generated)]) {
+        panic!("Generated code for '{}' does not compile:\n{}", fixture_name, output);
+    }
+}
+
+fn compile_check_dir() -> PathBuf {
+    crate::workspace_root().join("target").join("compile_check")
+}
+
+fn write_shim(shim: &Path) -> std::io::Result<()> {
+    let core = crate::workspace_root().join("rasterix-core");
+    fs::create_dir_all(shim.join("src"))?;
+    write_if_changed(
+        &shim.join("Cargo.toml"),
+        &format!(
+            "[package]\n\
+             name = \"rasterix\"\n\
+             version = \"0.0.0\"\n\
+             edition = \"2024\"\n\
+             publish = false\n\n\
+             [dependencies]\n\
+             rasterix-core = {{ path = {:?} }}\n\n\
+             [workspace]\n",
+            core.display().to_string()
+        ),
+    )?;
+    write_if_changed(&shim.join("src").join("lib.rs"), "pub use rasterix_core as rcore;\n")
+}
+
+fn write_crate(
+    dir: &Path,
+    shim: &Path,
+    modules: &[(&str, &str)],
+    dependencies: &[&str],
+    features: &[(&str, bool)],
+) -> std::io::Result<()> {
+    fs::create_dir_all(dir.join("src"))?;
+    let extra: String = dependencies.iter().map(|d| format!("{}\n", d)).collect();
+    let mut feature_table = String::new();
+    if !features.is_empty() {
+        let default: Vec<_> = features
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| format!("{:?}", name))
+            .collect();
+        feature_table.push_str(&format!("\n[features]\ndefault = [{}]\n", default.join(", ")));
+        for (name, _) in features {
+            feature_table.push_str(&format!("{} = []\n", name));
+        }
+    }
+    fs::write(
+        dir.join("Cargo.toml"),
+        format!(
+            "[package]\n\
+             name = \"compile-check\"\n\
+             version = \"0.0.0\"\n\
+             edition = \"2024\"\n\
+             publish = false\n\n\
+             [dependencies]\n\
+             rasterix = {{ path = {:?} }}\n\
+             {}{}\n\
+             [workspace]\n",
+            shim.display().to_string(),
+            extra,
+            feature_table
+        ),
+    )?;
+
+    let mut lib = String::new();
+    for (name, code) in modules {
+        lib.push_str(&format!("pub mod {};\n", name));
+        // Pretty-printed code gives compiler errors useful line numbers.
+        let code = crate::pretty_print(code).unwrap_or_else(|| code.to_string());
+        fs::write(dir.join("src").join(format!("{}.rs", name)), code)?;
+    }
+    fs::write(dir.join("src").join("lib.rs"), lib)
+}
+
+/// Avoids touching files cargo tracks, so the shim is not rebuilt every run.
+fn write_if_changed(path: &Path, content: &str) -> std::io::Result<()> {
+    if fs::read_to_string(path).is_ok_and(|existing| existing == content) {
+        return Ok(());
+    }
+    fs::write(path, content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_code_compiles() {
+        let code = "use rasterix::rcore::{Fspec, DecodeError};\n\
+                    pub fn fspec() -> Fspec { Fspec::new() }\n";
+        assert!(compile_check(&[("valid", code)]).is_ok());
+    }
+
+    #[test]
+    fn type_errors_are_reported() {
+        let code = "pub fn broken() -> u8 { \"not a number\" }\n";
+        let output = compile_check(&[("broken", code)]).unwrap_err();
+        assert!(output.contains("mismatched types"), "{}", output);
+    }
+
+    #[test]
+    fn declared_features_are_enabled_by_default() {
+        let code = "#[cfg(feature = \"on\")]\ncompile_error!(\"feature on\");\n";
+        let output = compile_check_with_features(&[("gated", code)], &[("on", true)]).unwrap_err();
+        assert!(output.contains("feature on"), "{}", output);
+        assert!(compile_check_with_features(&[("gated", code)], &[("on", false)]).is_ok());
+    }
+}
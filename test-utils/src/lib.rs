@@ -4,7 +4,19 @@
 //! and other test utilities shared across multiple crates.
 
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+pub mod code_diff;
+pub mod compile_check;
+pub mod recordings;
+pub mod reference;
+pub mod roundtrip;
+pub mod synth;
+
+pub use code_diff::{assert_code_eq, code_diff, pretty_print};
+pub use compile_check::{
+    assert_compiles, compile_check, compile_check_with_deps, compile_check_with_features,
+};
 
 /// Returns the path to the workspace-level testdata directory.
 ///
@@ -35,8 +47,8 @@ pub fn testdata_dir() -> PathBuf {
 ///
 /// # Arguments
 ///
-/// * `category` - "valid" or "invalid"
-/// * `filename` - Name of the XML file (e.g., "simple_fixed.xml")
+/// * `category` - "valid", "invalid", "messages", "recordings" or "expected"
+/// * `filename` - Name of the fixture file (e.g., "simple_fixed.xml")
 pub fn fixture_path(category: &str, filename: &str) -> PathBuf {
     testdata_dir().join(category).join(filename)
 }
@@ -57,6 +69,51 @@ pub fn load_fixture(category: &str, filename: &str) -> String {
         .unwrap_or_else(|e| panic!("Failed to read fixture {}: {}", path.display(), e))
 }
 
+/// Loads an encoded ASTERIX message from `testdata/messages/`.
+///
+/// The file format is chosen by extension:
+///
+/// - `.hex` - hex text, decoded with [`rasterix_core::hex::decode_loose`];
+/// - `.b64` - base64 text, decoded with [`rasterix_core::base64::decode`];
+/// - anything else - raw bytes.
+///
+/// In text fixtures, lines starting with `#` are comments.
+///
+/// # Panics
+///
+/// Panics if the file cannot be read or decoded.
+pub fn load_message_fixture(filename: &str) -> Vec<u8> {
+    load_message_file(&fixture_path("messages", filename))
+}
+
+/// Loads an encoded message file, choosing the format by extension as
+/// described for [`load_message_fixture`].
+///
+/// # Panics
+///
+/// Panics if the file cannot be read or decoded.
+pub fn load_message_file(path: &Path) -> Vec<u8> {
+    let read_text = || {
+        fs::read_to_string(path)
+            .unwrap_or_else(|e| panic!("Failed to read fixture {}: {}", path.display(), e))
+            .lines()
+            .filter(|line| !line.trim_start().starts_with('#'))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let decoded = match path.extension().and_then(|s| s.to_str()) {
+        Some("hex") => rasterix_core::hex::decode_loose(&read_text()),
+        Some("b64") => rasterix_core::base64::decode(&read_text()),
+        _ => {
+            return fs::read(path)
+                .unwrap_or_else(|e| panic!("Failed to read fixture {}: {}", path.display(), e));
+        }
+    };
+
+    decoded.unwrap_or_else(|e| panic!("Failed to decode fixture {}: {}", path.display(), e))
+}
+
 /// Normalizes whitespace in code for comparison.
 ///
 /// This is useful for comparing generated code where formatting may differ
@@ -106,9 +163,79 @@ pub fn assert_code_not_contains(generated: &str, forbidden_fragments: &[&str]) {
     }
 }
 
+/// Environment variable that switches expected-output assertions to
+/// rewriting the expected files instead of failing.
+pub const UPDATE_EXPECT_VAR: &str = "UPDATE_EXPECT";
+
+/// Returns `true` when running with `UPDATE_EXPECT=1`.
+pub fn update_expect() -> bool {
+    std::env::var(UPDATE_EXPECT_VAR).is_ok_and(|v| v == "1")
+}
+
+/// Returns the path of the expected generated code for a fixture.
+///
+/// Expected outputs live in `testdata/expected/<fixture_name>.rs`.
+pub fn expected_output_path(fixture_name: &str) -> PathBuf {
+    fixture_path("expected", &format!("{}.rs", fixture_name))
+}
+
+/// Loads the expected generated code for a fixture.
+///
+/// # Panics
+///
+/// Panics if the file cannot be read, unless running with `UPDATE_EXPECT=1`,
+/// in which case a missing file loads as empty so it gets created.
+pub fn load_expected_output(fixture_name: &str) -> String {
+    let path = expected_output_path(fixture_name);
+    match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(_) if update_expect() => String::new(),
+        Err(e) => panic!(
+            "Failed to read expected output {}: {}\n\
+             Run with {}=1 to create it.",
+            path.display(),
+            e,
+            UPDATE_EXPECT_VAR
+        ),
+    }
+}
+
+/// Writes `generated`, pretty-printed, as the new expected output for a
+/// fixture.
+///
+/// Prints a one-line summary to stderr so that an update run reports every
+/// file it touched.
+fn update_expected_output(generated: &str, fixture_name: &str) {
+    let path = expected_output_path(fixture_name);
+    let existed = path.exists();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).expect("Failed to create expected output dir");
+    }
+
+    let mut content = pretty_print(generated).unwrap_or_else(|| generated.to_string());
+    if !content.ends_with('\n') {
+        content.push('\n');
+    }
+    fs::write(&path, content)
+        .unwrap_or_else(|e| panic!("Failed to write {}: {}", path.display(), e));
+
+    eprintln!(
+        "{}: {} {}",
+        UPDATE_EXPECT_VAR,
+        if existed { "updated" } else { "created" },
+        path.display()
+    );
+}
+
 /// Asserts that two code strings are equal after normalizing whitespace.
 ///
 /// This handles differences from quote! formatting vs stored expected output.
+/// Prefer [`assert_code_eq`], which compares the parsed syntax trees and
+/// reports where the first difference is.
+///
+/// When running with `UPDATE_EXPECT=1`, a mismatch rewrites
+/// `testdata/expected/<fixture_name>.rs` with the generated code instead of
+/// failing.
 ///
 /// # Arguments
 ///
@@ -123,6 +250,11 @@ pub fn assert_normalized_eq(generated: &str, expected: &str, fixture_name: &str)
     let gen_normalized = normalize_whitespace(generated);
     let exp_normalized = normalize_whitespace(expected);
 
+    if gen_normalized != exp_normalized && update_expect() {
+        update_expected_output(generated, fixture_name);
+        return;
+    }
+
     assert_eq!(
         gen_normalized,
         exp_normalized,
@@ -135,6 +267,124 @@ pub fn assert_normalized_eq(generated: &str, expected: &str, fixture_name: &str)
     );
 }
 
+/// A named bit range within an encoded message, used to label diffs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldSpan {
+    /// Field name shown in the diff (e.g. `"I048/010.sac"`).
+    pub name: String,
+    /// Offset of the first bit from the start of the message, MSB first.
+    pub bit_offset: usize,
+    /// Width of the field in bits.
+    pub bits: usize,
+}
+
+impl FieldSpan {
+    pub fn new(name: impl Into<String>, bit_offset: usize, bits: usize) -> Self {
+        Self {
+            name: name.into(),
+            bit_offset,
+            bits,
+        }
+    }
+
+    fn contains(&self, bit: usize) -> bool {
+        bit >= self.bit_offset && bit < self.bit_offset + self.bits
+    }
+}
+
+/// Returns the offset of the first differing bit, MSB first.
+///
+/// If one slice is a prefix of the other, the first bit past the shorter one
+/// is reported.  Returns `None` if the slices are equal.
+pub fn first_differing_bit(expected: &[u8], actual: &[u8]) -> Option<usize> {
+    for (i, (e, a)) in expected.iter().zip(actual).enumerate() {
+        let diff = e ^ a;
+        if diff != 0 {
+            return Some(i * 8 + diff.leading_zeros() as usize);
+        }
+    }
+    (expected.len() != actual.len()).then(|| expected.len().min(actual.len()) * 8)
+}
+
+/// Renders a side-by-side hex + binary diff of two byte slices.
+///
+/// Each row shows one byte offset; differing rows are marked with `!` and
+/// the first differing bit is highlighted with a `^` under the binary
+/// columns.  If `fields` is non-empty, the field containing that bit is
+/// named.
+pub fn format_bitdiff(expected: &[u8], actual: &[u8], fields: &[FieldSpan]) -> String {
+    use std::fmt::Write;
+
+    let first = first_differing_bit(expected, actual);
+    let mut out = String::new();
+
+    writeln!(out, "expected {} bytes, actual {} bytes", expected.len(), actual.len()).unwrap();
+    if let Some(bit) = first {
+        write!(out, "first difference at byte {}, bit {} (bit offset {})", bit / 8, bit % 8, bit)
+            .unwrap();
+        if let Some(field) = fields.iter().find(|f| f.contains(bit)) {
+            write!(out, " in field `{}`", field.name).unwrap();
+        }
+        out.push('\n');
+    }
+
+    writeln!(out, "  offset | exp | act | expected  | actual").unwrap();
+    let rows = expected.len().max(actual.len());
+    for i in 0..rows {
+        let e = expected.get(i);
+        let a = actual.get(i);
+        let hex = |b: Option<&u8>| b.map_or("--".to_string(), |b| format!("{:02x}", b));
+        let bin = |b: Option<&u8>| b.map_or("--------".to_string(), |b| format!("{:08b}", b));
+        let marker = if e == a { ' ' } else { '!' };
+
+        writeln!(out, "{} {:6} | {}  | {}  | {}  | {}", marker, i, hex(e), hex(a), bin(e), bin(a))
+            .unwrap();
+
+        if first.is_some_and(|bit| bit / 8 == i) {
+            let col = first.unwrap() % 8;
+            let pad = " ".repeat(col);
+            writeln!(out, "         |     |     | {pad}^{:w$}| {pad}^", "", w = 9 - col).unwrap();
+        }
+    }
+
+    out
+}
+
+/// Asserts that two encoded messages are equal, printing a bit-level diff
+/// on mismatch.
+///
+/// # Panics
+///
+/// Panics with the output of [`format_bitdiff`] if the bytes differ.
+pub fn assert_bytes_eq_bitdiff(expected: &[u8], actual: &[u8]) {
+    assert_bytes_eq_bitdiff_with_fields(expected, actual, &[]);
+}
+
+/// Like [`assert_bytes_eq_bitdiff`], naming the field at the first
+/// differing bit using `fields`.
+pub fn assert_bytes_eq_bitdiff_with_fields(expected: &[u8], actual: &[u8], fields: &[FieldSpan]) {
+    if expected != actual {
+        panic!("encoded bytes differ\n{}", format_bitdiff(expected, actual, fields));
+    }
+}
+
+/// Asserts that the code generated for a fixture matches its expected output
+/// in `testdata/expected/`.
+///
+/// The comparison is structural (see [`assert_code_eq`]).  When running with
+/// `UPDATE_EXPECT=1`, a mismatch rewrites the expected file with the
+/// pretty-printed generated code instead of failing.
+pub fn assert_expected_output(generated: &str, fixture_name: &str) {
+    let expected = load_expected_output(fixture_name);
+
+    if update_expect() && code_diff(generated, &expected).is_some() {
+        update_expected_output(generated, fixture_name);
+        return;
+    }
+
+    assert_code_eq(generated, &expected, fixture_name);
+}
+
 /// Returns the workspace root directory.
 fn workspace_root() -> PathBuf {
     let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -210,6 +460,51 @@ mod tests {
         assert_eq!(normalize_whitespace(input), expected);
     }
 
+    #[test]
+    fn test_load_message_fixture_hex_and_base64_agree() {
+        let hex = load_message_fixture("cat048_two_records.hex");
+        let b64 = load_message_fixture("cat048_two_records.b64");
+        assert_eq!(hex, b64);
+        assert_eq!(&hex[..3], &[0x30, 0x00, 0x0e]);
+        assert_eq!(hex.len(), 14);
+    }
+
+    #[test]
+    fn test_first_differing_bit() {
+        assert_eq!(first_differing_bit(&[0xAB], &[0xAB]), None);
+        assert_eq!(first_differing_bit(&[0x80], &[0x00]), Some(0));
+        assert_eq!(first_differing_bit(&[0xFF, 0x01], &[0xFF, 0x00]), Some(15));
+        assert_eq!(first_differing_bit(&[0xFF], &[0xFF, 0x00]), Some(8));
+    }
+
+    #[test]
+    fn test_format_bitdiff_highlights_field() {
+        let fields = [FieldSpan::new("sac", 0, 8), FieldSpan::new("sic", 8, 8)];
+        let diff = format_bitdiff(&[0x01, 0x02], &[0x01, 0x06], &fields);
+
+        assert!(diff.contains("first difference at byte 1, bit 5 (bit offset 13) in field `sic`"));
+        assert!(diff.contains("!      1 | 02  | 06  | 00000010  | 00000110"));
+        assert!(diff.contains("| 00000010  | 00000110\n         |     |     |      ^    |      ^"));
+    }
+
+    #[test]
+    fn test_format_bitdiff_length_mismatch() {
+        let diff = format_bitdiff(&[0x01], &[0x01, 0x02], &[]);
+        assert!(diff.contains("expected 1 bytes, actual 2 bytes"));
+        assert!(diff.contains("!      1 | --  | 02  | --------  | 00000010"));
+    }
+
+    #[test]
+    fn test_assert_bytes_eq_bitdiff_pass() {
+        assert_bytes_eq_bitdiff(&[1, 2, 3], &[1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "first difference at byte 0, bit 7")]
+    fn test_assert_bytes_eq_bitdiff_fail() {
+        assert_bytes_eq_bitdiff(&[0x00], &[0x01]);
+    }
+
     #[test]
     fn test_assert_code_contains_pass() {
         let code = "pub struct Foo { pub bar: u8 }";
@@ -0,0 +1,111 @@
+//! Recorded sample data for replay tests.
+//!
+//! Each recording in `testdata/recordings/` is a pair of files sharing a
+//! name:
+//!
+//! - `<name>.bin`, `<name>.hex` or `<name>.b64` - concatenated data blocks,
+//!   in any format [`crate::load_message_file`] understands;
+//! - `<name>.expect` - the definition the data decodes with and selected
+//!   field values, in the format of [`crate::reference`]:
+//!
+//! ```text
+//! # definition: multi_item_record
+//! 0 010 sac=1
+//! 0 010 sic=2
+//! ```
+//!
+//! `definition` names the generated module the replay test decodes with.
+
+use std::fs;
+use std::path::PathBuf;
+
+use crate::reference::{compare_labelled, parse_reference_output, FieldValues};
+
+/// Extensions tried, in order, for a recording's data file.
+const DATA_EXTENSIONS: &[&str] = &["bin", "hex", "b64"];
+
+/// A recording with its expected field values.
+#[derive(Debug, Clone)]
+pub struct Recording {
+    /// File name without extension.
+    pub name: String,
+    /// Generated module the data decodes with.
+    pub definition: String,
+    /// Concatenated data blocks.
+    pub data: Vec<u8>,
+    /// Expected field values per record; fields not listed are not checked.
+    pub expected: Vec<FieldValues>,
+}
+
+impl Recording {
+    /// Compares decoded field values with the expected ones.
+    ///
+    /// Only expected fields are checked.  Returns one line per mismatch.
+    pub fn mismatches(&self, decoded: &[FieldValues]) -> Vec<String> {
+        compare_labelled(&self.expected, decoded, ("expected", "decoded"))
+    }
+}
+
+/// Returns the path to the recordings directory.
+pub fn recordings_dir() -> PathBuf {
+    crate::testdata_dir().join("recordings")
+}
+
+/// Loads every recording in `testdata/recordings/`, sorted by name.
+///
+/// # Panics
+///
+/// Panics if an `.expect` file has no data file, no `definition` line or
+/// malformed field lines.
+pub fn load_recordings() -> Vec<Recording> {
+    let Ok(entries) = fs::read_dir(recordings_dir()) else {
+        return Vec::new();
+    };
+
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()? != "expect" {
+                return None;
+            }
+            Some(path.file_stem()?.to_string_lossy().into_owned())
+        })
+        .collect();
+    names.sort();
+
+    names.iter().map(|name| load_recording(name)).collect()
+}
+
+/// Loads a single recording by name.
+///
+/// # Panics
+///
+/// See [`load_recordings`].
+pub fn load_recording(name: &str) -> Recording {
+    let dir = recordings_dir();
+    let expect_path = dir.join(format!("{}.expect", name));
+    let expect = fs::read_to_string(&expect_path)
+        .unwrap_or_else(|e| panic!("Failed to read {}: {}", expect_path.display(), e));
+
+    let definition = expect
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("# definition:"))
+        .map(|d| d.trim().to_string())
+        .unwrap_or_else(|| panic!("{} has no '# definition:' line", expect_path.display()));
+
+    let expected = parse_reference_output(&expect)
+        .unwrap_or_else(|e| panic!("{}: {}", expect_path.display(), e));
+
+    let data_path = DATA_EXTENSIONS
+        .iter()
+        .map(|ext| dir.join(format!("{}.{}", name, ext)))
+        .find(|path| path.exists())
+        .unwrap_or_else(|| panic!("No data file for recording '{}'", name));
+
+    Recording {
+        name: name.to_string(),
+        definition,
+        data: crate::load_message_file(&data_path),
+        expected,
+    }
+}
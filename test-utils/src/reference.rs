@@ -0,0 +1,259 @@
+//! Differential testing against an external reference decoder.
+//!
+//! Generated decoders are only as good as the definitions they are built
+//! from.  This harness runs the same blocks through an established decoder
+//! (a Python `asterix` library, `tshark`, ...) and compares field values.
+//!
+//! The reference decoder is any command named by [`REFERENCE_DECODER_VAR`].
+//! It reads hex-encoded data blocks from stdin, one block per line, and
+//! prints one line per decoded field:
+//!
+//! ```text
+//! <record> <item> <field>=<value>
+//! ```
+//!
+//! where `<record>` is the record's index across all input blocks, `<item>`
+//! the item id without category (e.g. `010`) and `<field>` the field name.
+//! Names are compared case-insensitively.  `scripts/reference/` contains an
+//! adapter for the Python `asterix_decoder` package.
+//!
+//! Differential tests are skipped when the variable is not set, so the
+//! reference decoder is never a build requirement.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use rasterix_core::hex;
+
+/// Environment variable holding the reference decoder command line.
+pub const REFERENCE_DECODER_VAR: &str = "RASTERIX_REFERENCE_DECODER";
+
+/// Field values of one record, keyed by `<item>.<field>` in lowercase.
+pub type FieldValues = BTreeMap<String, String>;
+
+/// Builds a `<item>.<field>` key as used in [`FieldValues`].
+pub fn field_key(item: &str, field: &str) -> String {
+    format!("{}.{}", item, field).to_lowercase()
+}
+
+/// An external decoder invoked as a subprocess.
+#[derive(Debug, Clone)]
+pub struct ReferenceDecoder {
+    program: String,
+    args: Vec<String>,
+}
+
+impl ReferenceDecoder {
+    /// Creates a decoder from a whitespace-separated command line.
+    ///
+    /// Returns `None` if the command line is empty.
+    pub fn new(command: &str) -> Option<Self> {
+        let mut parts = command.split_whitespace().map(str::to_string);
+        let program = parts.next()?;
+        Some(Self {
+            program,
+            args: parts.collect(),
+        })
+    }
+
+    /// Returns the decoder configured in [`REFERENCE_DECODER_VAR`], if any.
+    pub fn from_env() -> Option<Self> {
+        std::env::var(REFERENCE_DECODER_VAR).ok().and_then(|c| Self::new(&c))
+    }
+
+    /// Decodes `blocks` and returns the field values of every record.
+    pub fn decode(&self, blocks: &[&[u8]]) -> Result<Vec<FieldValues>, String> {
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to run reference decoder '{}': {}", self.program, e))?;
+
+        let input: String = blocks
+            .iter()
+            .map(|block| hex::encode_spaced(block) + "\n")
+            .collect();
+        let written = child.stdin.take().expect("stdin is piped").write_all(input.as_bytes());
+        // A decoder that exits without reading everything is judged by its
+        // exit status below, not by the broken pipe.
+        if let Err(e) = written
+            && e.kind() != std::io::ErrorKind::BrokenPipe
+        {
+            return Err(format!("Failed to write to reference decoder: {}", e));
+        }
+
+        let output = child
+            .wait_with_output()
+            .map_err(|e| format!("Failed to read reference decoder output: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "Reference decoder failed ({}):\n{}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        parse_reference_output(&String::from_utf8_lossy(&output.stdout))
+    }
+}
+
+/// Parses reference decoder output into per-record field values.
+///
+/// Empty lines and lines starting with `#` are ignored.
+pub fn parse_reference_output(output: &str) -> Result<Vec<FieldValues>, String> {
+    let mut records: Vec<FieldValues> = Vec::new();
+
+    for (n, line) in output.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let malformed = || format!("Malformed reference output on line {}: '{}'", n + 1, line);
+        let mut parts = line.splitn(3, ' ');
+        let (Some(record), Some(item), Some(assignment)) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(malformed());
+        };
+        let record: usize = record.parse().map_err(|_| malformed())?;
+        let (field, value) = assignment.split_once('=').ok_or_else(malformed)?;
+
+        if records.len() <= record {
+            records.resize_with(record + 1, FieldValues::new);
+        }
+        records[record].insert(field_key(item, field.trim()), value.trim().to_string());
+    }
+
+    Ok(records)
+}
+
+/// Compares decoded records against reference records.
+///
+/// Only fields present in `ours` are checked, since reference decoders
+/// usually report more (descriptions, derived values) than a definition
+/// covers.  Returns one line per mismatch.
+pub fn compare_records(ours: &[FieldValues], reference: &[FieldValues]) -> Vec<String> {
+    compare_labelled(ours, reference, ("ours", "reference"))
+}
+
+/// Checks every field of `checked` against `other`, naming the two sides
+/// with `labels` in the report.
+pub(crate) fn compare_labelled(
+    checked: &[FieldValues],
+    other: &[FieldValues],
+    labels: (&str, &str),
+) -> Vec<String> {
+    let (checked_label, other_label) = labels;
+    let mut mismatches = Vec::new();
+
+    if checked.len() != other.len() {
+        mismatches.push(format!(
+            "record count: {} {}, {} {}",
+            checked_label,
+            checked.len(),
+            other_label,
+            other.len()
+        ));
+    }
+
+    for (i, (checked, other)) in checked.iter().zip(other).enumerate() {
+        for (key, value) in checked {
+            match other.get(key) {
+                Some(found) if found == value => {}
+                Some(found) => mismatches.push(format!(
+                    "record {} {}: {} {}, {} {}",
+                    i, key, checked_label, value, other_label, found
+                )),
+                None => mismatches.push(format!("record {} {}: missing from {}", i, key, other_label)),
+            }
+        }
+    }
+
+    mismatches
+}
+
+/// Asserts that `ours` matches what the reference decoder reports for `blocks`.
+///
+/// Does nothing (and says so on stderr) if no reference decoder is
+/// configured.
+///
+/// # Panics
+///
+/// Panics if the reference decoder fails or any field value differs.
+pub fn assert_matches_reference(blocks: &[&[u8]], ours: &[FieldValues]) {
+    let Some(decoder) = ReferenceDecoder::from_env() else {
+        eprintln!("{} not set, skipping differential check", REFERENCE_DECODER_VAR);
+        return;
+    };
+
+    let reference = decoder.decode(blocks).unwrap_or_else(|e| panic!("{}", e));
+    let mismatches = compare_records(ours, &reference);
+    if !mismatches.is_empty() {
+        panic!(
+            "Decoded values differ from reference decoder:\n  {}",
+            mismatches.join("\n  ")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(pairs: &[(&str, &str, &str)]) -> FieldValues {
+        pairs
+            .iter()
+            .map(|(item, field, value)| (field_key(item, field), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parse_output() {
+        let output = "0 010 SAC=1\n0 010 SIC=2\n\n1 240 TId = BAW123\n";
+        let records = parse_reference_output(output).unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0], values(&[("010", "sac", "1"), ("010", "sic", "2")]));
+        assert_eq!(records[1], values(&[("240", "tid", "BAW123")]));
+    }
+
+    #[test]
+    fn parse_rejects_malformed_lines() {
+        assert!(parse_reference_output("0 010\n").is_err());
+        assert!(parse_reference_output("x 010 sac=1\n").is_err());
+        assert!(parse_reference_output("0 010 sac\n").is_err());
+    }
+
+    #[test]
+    fn compare_ignores_extra_reference_fields() {
+        let ours = vec![values(&[("010", "sac", "1")])];
+        let reference = vec![values(&[("010", "sac", "1"), ("010", "sic", "2")])];
+        assert!(compare_records(&ours, &reference).is_empty());
+    }
+
+    #[test]
+    fn compare_reports_mismatches() {
+        let ours = vec![values(&[("010", "sac", "1"), ("020", "typ", "3")])];
+        let reference = vec![values(&[("010", "sac", "7")]), FieldValues::new()];
+
+        let mismatches = compare_records(&ours, &reference);
+        assert_eq!(
+            mismatches,
+            vec![
+                "record count: ours 1, reference 2",
+                "record 0 010.sac: ours 1, reference 7",
+                "record 0 020.typ: missing from reference",
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_through_subprocess() {
+        // `echo` ignores stdin and prints one record in the expected format.
+        let decoder = ReferenceDecoder::new("echo 0 010 sac=1").unwrap();
+        let records = decoder.decode(&[&[0x30, 0x00, 0x03]]).unwrap();
+        assert_eq!(records, vec![values(&[("010", "sac", "1")])]);
+    }
+}
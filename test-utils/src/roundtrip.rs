@@ -6,10 +6,13 @@
 //! The round-trip tests verify that:
 //! 1. decode(encode(value)) == value (struct round-trip)
 //! 2. encode(decode(bytes)) == bytes (bytes round-trip)
+//!
+//! The `*_struct` and `*_bytes` helpers take bit-level types, implementing
+//! [`Encode`] and [`Decode`] over a [`BitReader`]/[`BitWriter`].  The
+//! `*_record` helpers take byte-level types, records and compound items,
+//! through [`EncodeToWrite`] and [`DecodeFromRead`].
 
-#![allow(dead_code)]
-
-use rasterix_core::{BitReader, BitWriter, Decode, Encode};
+use rasterix_core::{BitReader, BitWriter, Decode, DecodeFromRead, Encode, EncodeToWrite};
 use std::fmt::Debug;
 use std::io::Cursor;
 
@@ -30,9 +33,7 @@ where
 
     // Decode
     let mut reader = BitReader::new(Cursor::new(&buffer));
-    let decoded = T::decode(&mut reader).expect("Decode failed");
-
-    decoded
+    T::decode(&mut reader).expect("Decode failed")
 }
 
 /// Performs a struct round-trip test and asserts equality.
@@ -75,12 +76,70 @@ where
     T: Encode + Decode,
 {
     let reencoded = roundtrip_bytes::<T>(bytes);
+    crate::assert_bytes_eq_bitdiff(bytes, &reencoded);
+}
+
+/// Performs a struct round-trip test of a record or compound item: encode
+/// it to bytes, then decode them back.
+///
+/// Returns the encoded bytes and the decoded value.
+///
+/// # Panics
+///
+/// Panics if encoding or decoding fails, or if decoding leaves bytes
+/// unread.
+pub fn roundtrip_record<T>(original: &T) -> (Vec<u8>, T)
+where
+    T: EncodeToWrite + DecodeFromRead,
+{
+    let bytes = original.to_bytes().expect("Encode failed");
+    let mut rest = bytes.as_slice();
+    let decoded = T::decode_from(&mut rest).expect("Decode failed");
+    assert!(rest.is_empty(), "Decode left {} of {} byte(s) unread", rest.len(), bytes.len());
+    (bytes, decoded)
+}
+
+/// Performs a struct round-trip test of a record or compound item and
+/// asserts equality.
+pub fn assert_record_roundtrip<T>(original: &T)
+where
+    T: EncodeToWrite + DecodeFromRead + Debug + PartialEq,
+{
+    let (_, decoded) = roundtrip_record(original);
     assert_eq!(
-        bytes, &reencoded[..],
-        "Bytes round-trip failed: original bytes != re-encoded bytes"
+        original, &decoded,
+        "Round-trip failed: original != decoded"
     );
 }
 
+/// Performs a bytes round-trip test of a record or compound item: decode
+/// bytes, then encode back.
+///
+/// Returns the re-encoded bytes for comparison.
+///
+/// # Panics
+///
+/// Panics if decoding fails or leaves bytes unread, or if encoding fails.
+pub fn roundtrip_record_bytes<T>(bytes: &[u8]) -> Vec<u8>
+where
+    T: EncodeToWrite + DecodeFromRead,
+{
+    let mut rest = bytes;
+    let value = T::decode_from(&mut rest).expect("Decode failed");
+    assert!(rest.is_empty(), "Decode left {} of {} byte(s) unread", rest.len(), bytes.len());
+    value.to_bytes().expect("Encode failed")
+}
+
+/// Performs a bytes round-trip test of a record or compound item and
+/// asserts equality.
+pub fn assert_record_bytes_roundtrip<T>(bytes: &[u8])
+where
+    T: EncodeToWrite + DecodeFromRead,
+{
+    let reencoded = roundtrip_record_bytes::<T>(bytes);
+    crate::assert_bytes_eq_bitdiff(bytes, &reencoded);
+}
+
 /// Test helper for generating random-ish test values.
 pub mod generators {
     /// Generates test u8 values covering edge cases.
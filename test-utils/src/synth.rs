@@ -0,0 +1,315 @@
+//! Synthetic category XML for stress-testing the code generator.
+//!
+//! The hand-written fixtures in `testdata/valid/` cover one feature each.
+//! [`synth_category`] builds random but valid category definitions instead,
+//! mixing layouts, widths, enums, EPBs and spare bits, so the parser,
+//! transformer and generators see combinations no fixture spells out.
+//!
+//! Generation is driven by a seeded PRNG: the same [`SynthConfig`] always
+//! produces the same XML, so a failing seed can be reproduced and turned into
+//! a regular fixture.
+
+use std::fmt::Write;
+
+/// Item layouts [`synth_category`] can generate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SynthLayout {
+    Fixed,
+    Explicit,
+    Extended,
+    Repetitive,
+    Compound,
+}
+
+impl SynthLayout {
+    /// All layouts.
+    pub const ALL: &'static [SynthLayout] = &[
+        SynthLayout::Fixed,
+        SynthLayout::Explicit,
+        SynthLayout::Extended,
+        SynthLayout::Repetitive,
+        SynthLayout::Compound,
+    ];
+}
+
+/// Parameters for [`synth_category`].
+#[derive(Debug, Clone)]
+pub struct SynthConfig {
+    /// Seed for the PRNG.
+    pub seed: u64,
+    /// Category number of the generated definition.
+    pub category: u8,
+    /// Number of top-level items (at most 255).
+    pub items: usize,
+    /// Layouts to choose from; must not be empty.
+    pub layouts: Vec<SynthLayout>,
+    /// Maximum byte size of fixed, explicit and repetitive layouts.
+    pub max_bytes: usize,
+    /// Maximum number of parts of an extended layout.
+    pub max_parts: usize,
+    /// Maximum width of a single field, at most 64.
+    pub max_field_bits: usize,
+    /// Percentage of elements generated as enums.
+    pub enum_percent: u32,
+    /// Percentage of elements wrapped in an EPB.
+    pub epb_percent: u32,
+    /// Percentage of elements generated as spare bits.
+    pub spare_percent: u32,
+}
+
+impl Default for SynthConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            category: 1,
+            items: 8,
+            layouts: SynthLayout::ALL.to_vec(),
+            max_bytes: 4,
+            max_parts: 3,
+            max_field_bits: 32,
+            enum_percent: 20,
+            epb_percent: 15,
+            spare_percent: 10,
+        }
+    }
+}
+
+impl SynthConfig {
+    /// Default configuration with the given seed.
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            seed,
+            ..Self::default()
+        }
+    }
+}
+
+/// Builds a valid category definition from `config`.
+///
+/// Items get consecutive FRNs starting at 0.  Names are unique across the
+/// whole category, so every definition passes the transformer's validation.
+///
+/// # Panics
+///
+/// Panics if `config.layouts` is empty or `config.items` exceeds 255.
+///
+/// # Example
+///
+/// ```
+/// use test_utils::synth::{synth_category, SynthConfig};
+///
+/// let xml = synth_category(&SynthConfig::with_seed(42));
+/// assert!(xml.contains("<category id=\"1\">"));
+/// assert_eq!(xml, synth_category(&SynthConfig::with_seed(42)));
+/// ```
+pub fn synth_category(config: &SynthConfig) -> String {
+    assert!(!config.layouts.is_empty(), "SynthConfig::layouts must not be empty");
+    assert!(config.items <= 255, "SynthConfig::items must be at most 255");
+
+    let mut synth = Synth {
+        rng: Rng::new(config.seed),
+        config,
+        names: 0,
+        out: String::new(),
+    };
+
+    synth.out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    writeln!(synth.out, "<category id=\"{}\">", config.category).unwrap();
+    for id in 1..=config.items {
+        writeln!(synth.out, "    <item id=\"{}\" frn=\"{}\">", id, id).unwrap();
+        let layout = *synth.rng.choose(&config.layouts);
+        synth.layout(layout, 2);
+        synth.out.push_str("    </item>\n");
+    }
+    synth.out.push_str("</category>\n");
+
+    synth.out
+}
+
+struct Synth<'a> {
+    rng: Rng,
+    config: &'a SynthConfig,
+    /// Counter for names unique within the category; enum types are
+    /// generated at category level, so per-item uniqueness isn't enough.
+    names: usize,
+    out: String,
+}
+
+impl Synth<'_> {
+    fn layout(&mut self, layout: SynthLayout, depth: usize) {
+        let indent = "    ".repeat(depth);
+        match layout {
+            SynthLayout::Fixed | SynthLayout::Explicit => {
+                let tag = if layout == SynthLayout::Fixed { "fixed" } else { "explicit" };
+                let bytes = self.rng.range(1, self.config.max_bytes.max(1));
+                writeln!(self.out, "{}<{} bytes=\"{}\">", indent, tag, bytes).unwrap();
+                self.elements(bytes * 8, depth + 1);
+                writeln!(self.out, "{}</{}>", indent, tag).unwrap();
+            }
+            SynthLayout::Extended => {
+                let parts = self.rng.range(1, self.config.max_parts.max(1));
+                writeln!(self.out, "{}<extended bytes=\"{}\">", indent, parts).unwrap();
+                for index in 0..parts {
+                    writeln!(self.out, "{}    <part index=\"{}\">", indent, index).unwrap();
+                    self.elements(7, depth + 2);
+                    writeln!(self.out, "{}    </part>", indent).unwrap();
+                }
+                writeln!(self.out, "{}</extended>", indent).unwrap();
+            }
+            SynthLayout::Repetitive => {
+                let bytes = self.rng.range(1, self.config.max_bytes.max(1));
+                let counter = self.rng.range(1, 4);
+                writeln!(
+                    self.out,
+                    "{}<repetitive bytes=\"{}\" counter=\"{}\">",
+                    indent, bytes, counter
+                )
+                .unwrap();
+                self.elements(bytes * 8, depth + 1);
+                writeln!(self.out, "{}</repetitive>", indent).unwrap();
+            }
+            SynthLayout::Compound => {
+                let sub_layouts: Vec<SynthLayout> = self
+                    .config
+                    .layouts
+                    .iter()
+                    .copied()
+                    .filter(|l| *l != SynthLayout::Compound)
+                    .collect();
+                let sub_layouts = if sub_layouts.is_empty() {
+                    vec![SynthLayout::Fixed]
+                } else {
+                    sub_layouts
+                };
+
+                writeln!(self.out, "{}<compound>", indent).unwrap();
+                for _ in 0..self.rng.range(1, 4) {
+                    let sub = *self.rng.choose(&sub_layouts);
+                    self.layout(sub, depth + 1);
+                }
+                writeln!(self.out, "{}</compound>", indent).unwrap();
+            }
+        }
+    }
+
+    /// Emits elements filling exactly `bits` bits, at least one of them a
+    /// value, since structs of spare bits alone must be reserved items.
+    fn elements(&mut self, bits: usize, depth: usize) {
+        let indent = "    ".repeat(depth);
+        let mut remaining = bits;
+        let mut has_value = false;
+
+        while remaining > 0 {
+            let width = self.rng.range(1, remaining.min(self.config.max_field_bits.clamp(1, 64)));
+            let last = width == remaining;
+
+            let spare = (has_value || !last) && self.rng.percent(self.config.spare_percent);
+            if spare {
+                writeln!(self.out, "{}<spare bits=\"{}\"/>", indent, width).unwrap();
+            } else if width >= 2 && self.rng.percent(self.config.epb_percent) {
+                writeln!(self.out, "{}<epb>", indent).unwrap();
+                self.value(width - 1, depth + 1);
+                writeln!(self.out, "{}</epb>", indent).unwrap();
+            } else {
+                self.value(width, depth);
+            }
+            has_value |= !spare;
+
+            remaining -= width;
+        }
+    }
+
+    /// Emits a field or an enum of `bits` bits.
+    fn value(&mut self, bits: usize, depth: usize) {
+        let indent = "    ".repeat(depth);
+        let name = self.next_name();
+
+        if bits <= 8 && self.rng.percent(self.config.enum_percent) {
+            writeln!(self.out, "{}<enum name=\"{}\" bits=\"{}\">", indent, name, bits).unwrap();
+            let max_values = 1usize << bits.min(3);
+            for value in 0..self.rng.range(1, max_values) {
+                writeln!(
+                    self.out,
+                    "{}    <value name=\"V{}\" value=\"{}\"/>",
+                    indent, value, value
+                )
+                .unwrap();
+            }
+            writeln!(self.out, "{}</enum>", indent).unwrap();
+        } else {
+            writeln!(self.out, "{}<field name=\"{}\" bits=\"{}\"/>", indent, name, bits).unwrap();
+        }
+    }
+
+    fn next_name(&mut self) -> String {
+        self.names += 1;
+        format!("f{}", self.names)
+    }
+}
+
+/// SplitMix64, good enough for picking shapes and cheap to seed.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a value in `min..=max`.
+    fn range(&mut self, min: usize, max: usize) -> usize {
+        min + (self.next_u64() % (max - min + 1) as u64) as usize
+    }
+
+    fn percent(&mut self, percent: u32) -> bool {
+        self.next_u64() % 100 < percent as u64
+    }
+
+    fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0, items.len() - 1)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_output() {
+        let config = SynthConfig::with_seed(7);
+        assert_eq!(synth_category(&config), synth_category(&config));
+        assert_ne!(synth_category(&config), synth_category(&SynthConfig::with_seed(8)));
+    }
+
+    #[test]
+    fn item_count_and_frns() {
+        let config = SynthConfig {
+            items: 5,
+            ..SynthConfig::with_seed(1)
+        };
+        let xml = synth_category(&config);
+
+        assert_eq!(xml.matches("<item ").count(), 5);
+        assert!(xml.contains("frn=\"1\""));
+        assert!(xml.contains("frn=\"5\""));
+    }
+
+    #[test]
+    fn restricted_layouts() {
+        let config = SynthConfig {
+            layouts: vec![SynthLayout::Extended],
+            ..SynthConfig::with_seed(3)
+        };
+        let xml = synth_category(&config);
+
+        assert_eq!(xml.matches("<extended ").count(), config.items);
+        assert!(!xml.contains("<fixed"));
+    }
+}
@@ -0,0 +1,636 @@
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(deprecated)]
+use rasterix::rcore::{
+    BitReader, BitWrite, BitWriter, CategoryRecord, DataItem, DecodeContext, DecodeError,
+    Fspec, Decode, DecodeFromRead, DecodeInto, Encode, EncodeToWrite, Validate,
+    ValidationError, Conformance, ConformanceIssue, FromValue, ToValue, altitude,
+    conformance, geo, icao, policy, trace, validate, value, velocity,
+};
+use std::io::{Read, Write};
+pub mod cat001 {
+    use super::*;
+    /// ASTERIX Category record.
+    ///
+    /// Contains optional data items, each controlled by a bit in the FSPEC.
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct Record {
+        pub item100: Option<Item100>,
+    }
+    impl Decode for Record {
+        fn decode<R: std::io::Read>(
+            reader: &mut BitReader<R>,
+        ) -> Result<Self, DecodeError> {
+            let fspec = Fspec::read(reader)?;
+            Ok(Self {
+                item100: if fspec.is_frn_set(1usize) {
+                    Some(
+                        Item100::decode(reader)
+                            .inspect_err(|e| trace::item_error(
+                                <Self as CategoryRecord>::CATEGORY,
+                                "Item100",
+                                e,
+                            ))?,
+                    )
+                } else {
+                    None
+                },
+            })
+        }
+    }
+    impl DecodeFromRead for Record {
+        fn decode_from<R: std::io::Read>(reader: &mut R) -> Result<Self, DecodeError> {
+            Self::decode(&mut BitReader::new(reader))
+        }
+    }
+    impl DecodeInto for Record {
+        fn decode_into<R: std::io::Read>(
+            &mut self,
+            reader: &mut BitReader<R>,
+            ctx: &mut DecodeContext,
+        ) -> Result<(), DecodeError> {
+            ctx.with_fspec(
+                reader,
+                |ctx, reader, fspec| {
+                    if fspec.is_frn_set(1usize) {
+                        let result = match self.item100.as_mut() {
+                            Some(value) => value.decode_into(reader, ctx),
+                            None => {
+                                Item100::decode(reader)
+                                    .map(|value| self.item100 = Some(value))
+                            }
+                        };
+                        result
+                            .inspect_err(|e| trace::item_error(
+                                <Self as CategoryRecord>::CATEGORY,
+                                "Item100",
+                                e,
+                            ))?;
+                    } else {
+                        self.item100 = None;
+                    };
+                    Ok(())
+                },
+            )
+        }
+    }
+    /// Item sets for [`super::Record::decode_exact`]: one bit per item, combined with `|`.
+    pub mod profile {
+        pub const ITEM100: u64 = 1u64;
+        /// Every item a profile can contain.
+        pub const ALL: u64 = ITEM100;
+    }
+    impl Record {
+        /// Decodes a record that contains exactly the items of `PROFILE`,
+        /// a combination of the [`profile`] constants.
+        ///
+        /// The item set is fixed at compile time, so no FSPEC is kept and
+        /// nothing is allocated beyond the items themselves.  Fails if the
+        /// record's FSPEC differs from the profile's.
+        pub fn decode_exact<const PROFILE: u64, R: std::io::Read>(
+            reader: &mut BitReader<R>,
+        ) -> Result<Self, DecodeError> {
+            const {
+                assert!(
+                    PROFILE & ! profile::ALL == 0,
+                    "profile contains an item the record doesn't have"
+                )
+            };
+            Fspec::read_profile(reader, PROFILE)?;
+            Ok(Self {
+                item100: if PROFILE & profile::ITEM100 != 0 {
+                    Some(
+                        Item100::decode(reader)
+                            .inspect_err(|e| trace::item_error(
+                                <Self as CategoryRecord>::CATEGORY,
+                                "Item100",
+                                e,
+                            ))?,
+                    )
+                } else {
+                    None
+                },
+            })
+        }
+    }
+    impl Encode for Record {
+        fn encode<W: BitWrite>(&self, writer: &mut W) -> Result<(), DecodeError> {
+            let mut fspec = Fspec::new();
+            if self.item100.is_some() {
+                fspec.set_frn(1usize);
+            }
+            fspec.write(writer)?;
+            if let Some(ref item) = self.item100 {
+                item.encode(writer)?;
+            }
+            Ok(())
+        }
+    }
+    impl EncodeToWrite for Record {
+        fn encode_to<W: std::io::Write>(
+            &self,
+            writer: &mut W,
+        ) -> Result<(), DecodeError> {
+            let mut writer = BitWriter::new(writer);
+            self.encode(&mut writer)?;
+            writer.flush()?;
+            Ok(())
+        }
+    }
+    impl Validate for Record {
+        fn check(&self, path: &str, errors: &mut Vec<ValidationError>) {
+            if let Some(ref item) = self.item100 {
+                item.check(&format!("{}item100.", path), errors);
+            }
+        }
+    }
+    impl Conformance for Record {
+        fn audit(&self, path: &str, issues: &mut Vec<ConformanceIssue>) {
+            if let Some(ref item) = self.item100 {
+                conformance::audit_item(item, &format!("{}item100.", path), issues);
+            }
+        }
+    }
+    impl Record {
+        /// Encoded length of the item at each FSPEC position, from
+        /// `encoded_len_hint`.  `None` for variable-length items,
+        /// compiled-out items and unused positions.
+        pub const ITEM_LENGTHS: [Option<usize>; 1usize] = [Item100::encoded_len_hint()];
+        /// Reads past the item at FSPEC position `position`
+        /// (`byte * 7 + bit`, one less than its FRN) without decoding it.
+        pub fn skip_item<R: std::io::Read>(
+            position: usize,
+            reader: &mut BitReader<R>,
+        ) -> Result<(), DecodeError> {
+            match position {
+                0usize => Item100::skip(reader),
+                _ => Err(DecodeError::InvalidData("no item at FSPEC position")),
+            }
+        }
+    }
+    /// Lazily decoded view of a record.
+    ///
+    /// [`parse`](Self::parse) only locates the present items; each
+    /// accessor decodes its item on demand.
+    #[derive(Debug, Clone, Copy)]
+    pub struct RecordLazy<'a> {
+        bytes: &'a [u8],
+        /// Bit offset in `bytes` of each present item, in record order.
+        offsets: [Option<usize>; 1usize],
+        encoded_len: usize,
+    }
+    impl<'a> RecordLazy<'a> {
+        /// Parses the FSPEC and item offsets of the record at the start
+        /// of `bytes`.
+        pub fn parse(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+            let mut reader = BitReader::new(std::io::Cursor::new(bytes));
+            let fspec = Fspec::read(&mut reader)?;
+            let mut offsets = [None; 1usize];
+            if fspec.is_frn_set(1usize) {
+                offsets[0usize] = Some(reader.bit_position() as usize);
+                Item100::skip(&mut reader)?;
+            }
+            Ok(Self {
+                bytes,
+                offsets,
+                encoded_len: (reader.bit_position() as usize).div_ceil(8),
+            })
+        }
+        /// Length of the record in bytes, so that the next record of a
+        /// data block starts at `bytes[encoded_len()..]`.
+        pub fn encoded_len(&self) -> usize {
+            self.encoded_len
+        }
+        /// Decodes every present item.
+        pub fn decode(&self) -> Result<Record, DecodeError> {
+            Ok(Record { item100: self.item100()? })
+        }
+        /// Decodes `Item100`, or returns `None` if it is absent.
+        pub fn item100(&self) -> Result<Option<Item100>, DecodeError> {
+            match self.offsets[0usize] {
+                Some(offset) => Ok(Some(Item100::decode(&mut self.reader_at(offset)?)?)),
+                None => Ok(None),
+            }
+        }
+        fn reader_at(&self, offset: usize) -> Result<BitReader<&'a [u8]>, DecodeError> {
+            let mut reader = BitReader::new(&self.bytes[offset / 8..]);
+            reader.skip_bits(offset % 8)?;
+            Ok(reader)
+        }
+    }
+    impl Record {
+        /// Returns the items present as generic values, keyed by their
+        /// field names, e.g. `item010`.
+        pub fn to_value_map(&self) -> value::ValueMap {
+            let mut map = value::ValueMap::new();
+            if let Some(item) = &self.item100 {
+                map.insert("item100".to_string(), item.to_value());
+            }
+            map
+        }
+        /// Builds a record from generic values keyed by item, as
+        /// returned by [`Self::to_value_map`].  Missing items are
+        /// absent.
+        pub fn from_value_map(map: &value::ValueMap) -> Result<Self, value::ValueError> {
+            value::check_keys(map, &["item100"])?;
+            Ok(Self {
+                item100: value::field(map, "item100")?,
+            })
+        }
+    }
+    /// ASTERIX Data Block — a container of records for this category.
+    ///
+    /// Wire format:
+    /// ```text
+    /// [CAT: 1 byte][LEN: 2 bytes (big-endian)][Record 0][Record 1]...
+    /// ```
+    ///
+    /// `LEN` is the total byte length of the entire data block, including
+    /// the CAT and LEN fields themselves (minimum value is 3).
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct DataBlock {
+        pub records: Vec<Record>,
+    }
+    impl DataBlock {
+        /// The ASTERIX category identifier for this data block.
+        pub const CATEGORY: u8 = 1u8;
+        /// Creates a new, empty data block.
+        pub fn new() -> Self {
+            Self { records: Vec::new() }
+        }
+        /// Creates a data block containing the given records.
+        pub fn with_records(records: Vec<Record>) -> Self {
+            Self { records }
+        }
+    }
+    impl CategoryRecord for Record {
+        const CATEGORY: u8 = 1u8;
+    }
+    impl Default for DataBlock {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+    impl Encode for DataBlock {
+        fn encode<W: BitWrite>(&self, writer: &mut W) -> Result<(), DecodeError> {
+            let mut record_buf = Vec::new();
+            {
+                let mut record_writer = BitWriter::new(&mut record_buf);
+                for record in &self.records {
+                    record.encode(&mut record_writer)?;
+                }
+                record_writer.flush()?;
+            }
+            let total_len = u16::try_from(3 + record_buf.len())
+                .map_err(|_| DecodeError::LimitExceeded {
+                    limit: "data block length",
+                    max: u16::MAX as usize,
+                })?;
+            writer.write_bits(1u8 as u64, 8)?;
+            writer.write_bits(total_len as u64, 16)?;
+            for &byte in &record_buf {
+                writer.write_bits(byte as u64, 8)?;
+            }
+            Ok(())
+        }
+    }
+    impl Decode for DataBlock {
+        fn decode<R: std::io::Read>(
+            reader: &mut BitReader<R>,
+        ) -> Result<Self, DecodeError> {
+            let cat = reader.read_bits(8)? as u8;
+            if cat != 1u8 {
+                return Err(DecodeError::InvalidData("category mismatch"));
+            }
+            let len = reader.read_bits(16)? as u16;
+            if len < 3 {
+                return Err(DecodeError::InvalidData("data block length too small"));
+            }
+            policy::block_len(len as usize)?;
+            let _span = trace::block_span(1u8, len);
+            let payload_len = (len - 3) as usize;
+            let mut payload = vec![0u8; payload_len];
+            for byte in payload.iter_mut() {
+                *byte = reader.read_bits(8)? as u8;
+            }
+            let mut records = Vec::new();
+            let mut cursor = std::io::Cursor::new(payload);
+            let total = payload_len as u64;
+            while cursor.position() < total {
+                let position = cursor.position() as usize;
+                let offset = 3 + position;
+                let record = policy::block_rest(
+                        &cursor.get_ref()[position..],
+                        offset,
+                        len as usize,
+                    )
+                    .and_then(|record_follows| {
+                        let mut record_reader = BitReader::new(&mut cursor);
+                        record_follows
+                            .then(|| Record::decode(&mut record_reader))
+                            .transpose()
+                            .map_err(|e| e.in_record(offset, len as usize))
+                    })
+                    .inspect_err(|e| trace::record_error(1u8, offset as u64, e))?;
+                let Some(record) = record else { break };
+                records.push(record);
+            }
+            Ok(Self { records })
+        }
+    }
+    impl DecodeInto for DataBlock {
+        fn decode_into<R: std::io::Read>(
+            &mut self,
+            reader: &mut BitReader<R>,
+            ctx: &mut DecodeContext,
+        ) -> Result<(), DecodeError> {
+            let cat = reader.read_bits(8)? as u8;
+            if cat != 1u8 {
+                return Err(DecodeError::InvalidData("category mismatch"));
+            }
+            let len = reader.read_bits(16)? as u16;
+            if len < 3 {
+                return Err(DecodeError::InvalidData("data block length too small"));
+            }
+            policy::block_len(len as usize)?;
+            let _span = trace::block_span(1u8, len);
+            let payload_len = (len - 3) as usize;
+            ctx.with_buffer(|ctx, payload| {
+                for _ in 0..payload_len {
+                    payload.push(reader.read_bits(8)? as u8);
+                }
+                let mut cursor = std::io::Cursor::new(payload.as_slice());
+                let total = payload_len as u64;
+                let mut count = 0;
+                while cursor.position() < total {
+                    let position = cursor.position() as usize;
+                    let offset = 3 + position;
+                    let result = policy::block_rest(
+                            &cursor.get_ref()[position..],
+                            offset,
+                            len as usize,
+                        )
+                        .and_then(|record_follows| {
+                            if !record_follows {
+                                return Ok(false);
+                            }
+                            let mut record_reader = BitReader::new(&mut cursor);
+                            match self.records.get_mut(count) {
+                                Some(record) => record.decode_into(&mut record_reader, ctx),
+                                None => {
+                                    Record::decode(&mut record_reader)
+                                        .map(|record| self.records.push(record))
+                                }
+                            }
+                                .map(|()| true)
+                                .map_err(|e| e.in_record(offset, len as usize))
+                        });
+                    if !result
+                        .inspect_err(|e| trace::record_error(1u8, offset as u64, e))?
+                    {
+                        break;
+                    }
+                    count += 1;
+                }
+                self.records.truncate(count);
+                Ok(())
+            })
+        }
+    }
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct Item100Sub0 {
+        pub flags: u8,
+    }
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct Item100Sub1 {
+        pub data: u16,
+    }
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct Item100 {
+        pub sub0: Option<Item100Sub0>,
+        pub sub1: Option<Item100Sub1>,
+    }
+    impl DataItem for Item100 {
+        const ITEM_ID: u16 = 100u16;
+        const FRN: usize = 1usize;
+        const FSPEC_BYTE: usize = 0usize;
+        const FSPEC_BIT: u8 = 0u8;
+    }
+    impl Validate for Item100 {}
+    impl Conformance for Item100Sub0 {}
+    impl Conformance for Item100Sub1 {}
+    impl Conformance for Item100 {
+        fn audit(&self, path: &str, issues: &mut Vec<ConformanceIssue>) {
+            if let Some(sub) = &self.sub0 {
+                sub.audit(&format!("{}sub0.", path), issues);
+            }
+            if let Some(sub) = &self.sub1 {
+                sub.audit(&format!("{}sub1.", path), issues);
+            }
+        }
+    }
+    impl ToValue for Item100Sub0 {
+        fn to_value(&self) -> value::Value {
+            let mut map = value::ValueMap::new();
+            map.insert("flags".to_string(), self.flags.to_value());
+            value::Value::Map(map)
+        }
+    }
+    impl FromValue for Item100Sub0 {
+        fn from_value(input: &value::Value) -> Result<Self, value::ValueError> {
+            let map = value::map(input)?;
+            value::check_keys(map, &["flags"])?;
+            Ok(Self {
+                flags: value::field(map, "flags")?,
+            })
+        }
+    }
+    impl ToValue for Item100Sub1 {
+        fn to_value(&self) -> value::Value {
+            let mut map = value::ValueMap::new();
+            map.insert("data".to_string(), self.data.to_value());
+            value::Value::Map(map)
+        }
+    }
+    impl FromValue for Item100Sub1 {
+        fn from_value(input: &value::Value) -> Result<Self, value::ValueError> {
+            let map = value::map(input)?;
+            value::check_keys(map, &["data"])?;
+            Ok(Self {
+                data: value::field(map, "data")?,
+            })
+        }
+    }
+    impl ToValue for Item100 {
+        fn to_value(&self) -> value::Value {
+            let mut map = value::ValueMap::new();
+            map.insert("sub0".to_string(), self.sub0.to_value());
+            map.insert("sub1".to_string(), self.sub1.to_value());
+            value::Value::Map(map)
+        }
+    }
+    impl FromValue for Item100 {
+        fn from_value(input: &value::Value) -> Result<Self, value::ValueError> {
+            let map = value::map(input)?;
+            value::check_keys(map, &["sub0", "sub1"])?;
+            Ok(Self {
+                sub0: value::field(map, "sub0")?,
+                sub1: value::field(map, "sub1")?,
+            })
+        }
+    }
+    impl Decode for Item100Sub0 {
+        fn decode<R: std::io::Read>(
+            reader: &mut BitReader<R>,
+        ) -> Result<Self, DecodeError> {
+            let flags = reader.read_bits(8usize)? as u8;
+            Ok(Self { flags })
+        }
+    }
+    impl DecodeInto for Item100Sub0 {
+        fn decode_into<R: std::io::Read>(
+            &mut self,
+            reader: &mut BitReader<R>,
+            _ctx: &mut DecodeContext,
+        ) -> Result<(), DecodeError> {
+            self.flags = reader.read_bits(8usize)? as u8;
+            Ok(())
+        }
+    }
+    impl Decode for Item100Sub1 {
+        fn decode<R: std::io::Read>(
+            reader: &mut BitReader<R>,
+        ) -> Result<Self, DecodeError> {
+            let data = reader.read_bits(16usize)? as u16;
+            Ok(Self { data })
+        }
+    }
+    impl DecodeInto for Item100Sub1 {
+        fn decode_into<R: std::io::Read>(
+            &mut self,
+            reader: &mut BitReader<R>,
+            _ctx: &mut DecodeContext,
+        ) -> Result<(), DecodeError> {
+            self.data = reader.read_bits(16usize)? as u16;
+            Ok(())
+        }
+    }
+    impl Item100 {
+        pub fn decode<R: std::io::Read>(reader: &mut R) -> Result<Self, DecodeError> {
+            let fspec = Fspec::read(reader)?;
+            let mut reader = BitReader::new(reader);
+            let sub0 = if fspec.is_frn_set(1usize) {
+                Some(Item100Sub0::decode(&mut reader)?)
+            } else {
+                None
+            };
+            let sub1 = if fspec.is_frn_set(2usize) {
+                Some(Item100Sub1::decode(&mut reader)?)
+            } else {
+                None
+            };
+            Ok(Self { sub0, sub1 })
+        }
+        pub fn decode_into<R: std::io::Read>(
+            &mut self,
+            reader: &mut R,
+            ctx: &mut DecodeContext,
+        ) -> Result<(), DecodeError> {
+            ctx.with_fspec(
+                reader,
+                |ctx, reader, fspec| {
+                    let mut reader = BitReader::new(reader);
+                    if fspec.is_frn_set(1usize) {
+                        match self.sub0.as_mut() {
+                            Some(value) => value.decode_into(&mut reader, ctx)?,
+                            None => self.sub0 = Some(Item100Sub0::decode(&mut reader)?),
+                        }
+                    } else {
+                        self.sub0 = None;
+                    };
+                    if fspec.is_frn_set(2usize) {
+                        match self.sub1.as_mut() {
+                            Some(value) => value.decode_into(&mut reader, ctx)?,
+                            None => self.sub1 = Some(Item100Sub1::decode(&mut reader)?),
+                        }
+                    } else {
+                        self.sub1 = None;
+                    };
+                    Ok(())
+                },
+            )
+        }
+    }
+    impl DecodeFromRead for Item100 {
+        fn decode_from<R: std::io::Read>(reader: &mut R) -> Result<Self, DecodeError> {
+            Self::decode(reader)
+        }
+    }
+    impl Item100 {
+        /// Length in bytes of the shortest encoding the decoder accepts.
+        pub const fn min_encoded_len() -> usize {
+            1usize
+        }
+        /// Length in bytes of every encoding of the item, or `None` if
+        /// it depends on the value.
+        pub const fn encoded_len_hint() -> Option<usize> {
+            None
+        }
+        /// Reads past the item without decoding its fields.
+        pub fn skip<R: std::io::Read>(reader: &mut R) -> Result<(), DecodeError> {
+            let fspec = Fspec::read(reader)?;
+            let mut reader = BitReader::new(reader);
+            if fspec.is_frn_set(1usize) {
+                reader.skip_bits(8usize)?;
+            }
+            if fspec.is_frn_set(2usize) {
+                reader.skip_bits(16usize)?;
+            }
+            Ok(())
+        }
+    }
+    impl Encode for Item100Sub0 {
+        fn encode<W: BitWrite>(&self, writer: &mut W) -> Result<(), DecodeError> {
+            writer.write_bits(self.flags as u64, 8usize)?;
+            Ok(())
+        }
+    }
+    impl Encode for Item100Sub1 {
+        fn encode<W: BitWrite>(&self, writer: &mut W) -> Result<(), DecodeError> {
+            writer.write_bits(self.data as u64, 16usize)?;
+            Ok(())
+        }
+    }
+    impl Item100 {
+        pub fn encode<W: std::io::Write>(
+            &self,
+            writer: &mut W,
+        ) -> Result<(), DecodeError> {
+            let mut fspec = Fspec::new();
+            if self.sub0.is_some() {
+                fspec.set_frn(1usize);
+            }
+            if self.sub1.is_some() {
+                fspec.set_frn(2usize);
+            }
+            fspec.write(writer)?;
+            let mut writer = BitWriter::new(writer);
+            if let Some(ref sub_data) = self.sub0 {
+                sub_data.encode(&mut writer)?;
+            }
+            if let Some(ref sub_data) = self.sub1 {
+                sub_data.encode(&mut writer)?;
+            }
+            writer.flush()?;
+            Ok(())
+        }
+    }
+    impl EncodeToWrite for Item100 {
+        fn encode_to<W: std::io::Write>(
+            &self,
+            writer: &mut W,
+        ) -> Result<(), DecodeError> {
+            self.encode(writer)
+        }
+    }
+}
@@ -0,0 +1,504 @@
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(deprecated)]
+use rasterix::rcore::{
+    BitReader, BitWrite, BitWriter, CategoryRecord, DataItem, DecodeContext, DecodeError,
+    Fspec, Decode, DecodeFromRead, DecodeInto, Encode, EncodeToWrite, Validate,
+    ValidationError, Conformance, ConformanceIssue, FromValue, ToValue, altitude,
+    conformance, geo, icao, policy, trace, validate, value, velocity,
+};
+use std::io::{Read, Write};
+pub mod cat001 {
+    use super::*;
+    /// ASTERIX Category record.
+    ///
+    /// Contains optional data items, each controlled by a bit in the FSPEC.
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct Record {
+        pub item010: Option<Item010>,
+    }
+    impl Decode for Record {
+        fn decode<R: std::io::Read>(
+            reader: &mut BitReader<R>,
+        ) -> Result<Self, DecodeError> {
+            let fspec = Fspec::read(reader)?;
+            Ok(Self {
+                item010: if fspec.is_frn_set(1usize) {
+                    Some(
+                        Item010::decode(reader)
+                            .inspect_err(|e| trace::item_error(
+                                <Self as CategoryRecord>::CATEGORY,
+                                "Item010",
+                                e,
+                            ))?,
+                    )
+                } else {
+                    None
+                },
+            })
+        }
+    }
+    impl DecodeFromRead for Record {
+        fn decode_from<R: std::io::Read>(reader: &mut R) -> Result<Self, DecodeError> {
+            Self::decode(&mut BitReader::new(reader))
+        }
+    }
+    impl DecodeInto for Record {
+        fn decode_into<R: std::io::Read>(
+            &mut self,
+            reader: &mut BitReader<R>,
+            ctx: &mut DecodeContext,
+        ) -> Result<(), DecodeError> {
+            ctx.with_fspec(
+                reader,
+                |ctx, reader, fspec| {
+                    if fspec.is_frn_set(1usize) {
+                        let result = match self.item010.as_mut() {
+                            Some(value) => value.decode_into(reader, ctx),
+                            None => {
+                                Item010::decode(reader)
+                                    .map(|value| self.item010 = Some(value))
+                            }
+                        };
+                        result
+                            .inspect_err(|e| trace::item_error(
+                                <Self as CategoryRecord>::CATEGORY,
+                                "Item010",
+                                e,
+                            ))?;
+                    } else {
+                        self.item010 = None;
+                    };
+                    Ok(())
+                },
+            )
+        }
+    }
+    /// Item sets for [`super::Record::decode_exact`]: one bit per item, combined with `|`.
+    pub mod profile {
+        pub const ITEM010: u64 = 1u64;
+        /// Every item a profile can contain.
+        pub const ALL: u64 = ITEM010;
+    }
+    impl Record {
+        /// Decodes a record that contains exactly the items of `PROFILE`,
+        /// a combination of the [`profile`] constants.
+        ///
+        /// The item set is fixed at compile time, so no FSPEC is kept and
+        /// nothing is allocated beyond the items themselves.  Fails if the
+        /// record's FSPEC differs from the profile's.
+        pub fn decode_exact<const PROFILE: u64, R: std::io::Read>(
+            reader: &mut BitReader<R>,
+        ) -> Result<Self, DecodeError> {
+            const {
+                assert!(
+                    PROFILE & ! profile::ALL == 0,
+                    "profile contains an item the record doesn't have"
+                )
+            };
+            Fspec::read_profile(reader, PROFILE)?;
+            Ok(Self {
+                item010: if PROFILE & profile::ITEM010 != 0 {
+                    Some(
+                        Item010::decode(reader)
+                            .inspect_err(|e| trace::item_error(
+                                <Self as CategoryRecord>::CATEGORY,
+                                "Item010",
+                                e,
+                            ))?,
+                    )
+                } else {
+                    None
+                },
+            })
+        }
+    }
+    impl Encode for Record {
+        fn encode<W: BitWrite>(&self, writer: &mut W) -> Result<(), DecodeError> {
+            let mut fspec = Fspec::new();
+            if self.item010.is_some() {
+                fspec.set_frn(1usize);
+            }
+            fspec.write(writer)?;
+            if let Some(ref item) = self.item010 {
+                item.encode(writer)?;
+            }
+            Ok(())
+        }
+    }
+    impl EncodeToWrite for Record {
+        fn encode_to<W: std::io::Write>(
+            &self,
+            writer: &mut W,
+        ) -> Result<(), DecodeError> {
+            let mut writer = BitWriter::new(writer);
+            self.encode(&mut writer)?;
+            writer.flush()?;
+            Ok(())
+        }
+    }
+    impl Validate for Record {
+        fn check(&self, path: &str, errors: &mut Vec<ValidationError>) {
+            if let Some(ref item) = self.item010 {
+                item.check(&format!("{}item010.", path), errors);
+            }
+        }
+    }
+    impl Conformance for Record {
+        fn audit(&self, path: &str, issues: &mut Vec<ConformanceIssue>) {
+            if let Some(ref item) = self.item010 {
+                conformance::audit_item(item, &format!("{}item010.", path), issues);
+            }
+        }
+    }
+    impl Record {
+        /// Encoded length of the item at each FSPEC position, from
+        /// `encoded_len_hint`.  `None` for variable-length items,
+        /// compiled-out items and unused positions.
+        pub const ITEM_LENGTHS: [Option<usize>; 1usize] = [Item010::encoded_len_hint()];
+        /// Reads past the item at FSPEC position `position`
+        /// (`byte * 7 + bit`, one less than its FRN) without decoding it.
+        pub fn skip_item<R: std::io::Read>(
+            position: usize,
+            reader: &mut BitReader<R>,
+        ) -> Result<(), DecodeError> {
+            match position {
+                0usize => Item010::skip(reader),
+                _ => Err(DecodeError::InvalidData("no item at FSPEC position")),
+            }
+        }
+    }
+    /// Lazily decoded view of a record.
+    ///
+    /// [`parse`](Self::parse) only locates the present items; each
+    /// accessor decodes its item on demand.
+    #[derive(Debug, Clone, Copy)]
+    pub struct RecordLazy<'a> {
+        bytes: &'a [u8],
+        /// Bit offset in `bytes` of each present item, in record order.
+        offsets: [Option<usize>; 1usize],
+        encoded_len: usize,
+    }
+    impl<'a> RecordLazy<'a> {
+        /// Parses the FSPEC and item offsets of the record at the start
+        /// of `bytes`.
+        pub fn parse(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+            let mut reader = BitReader::new(std::io::Cursor::new(bytes));
+            let fspec = Fspec::read(&mut reader)?;
+            let mut offsets = [None; 1usize];
+            if fspec.is_frn_set(1usize) {
+                offsets[0usize] = Some(reader.bit_position() as usize);
+                Item010::skip(&mut reader)?;
+            }
+            Ok(Self {
+                bytes,
+                offsets,
+                encoded_len: (reader.bit_position() as usize).div_ceil(8),
+            })
+        }
+        /// Length of the record in bytes, so that the next record of a
+        /// data block starts at `bytes[encoded_len()..]`.
+        pub fn encoded_len(&self) -> usize {
+            self.encoded_len
+        }
+        /// Decodes every present item.
+        pub fn decode(&self) -> Result<Record, DecodeError> {
+            Ok(Record { item010: self.item010()? })
+        }
+        /// Decodes `Item010`, or returns `None` if it is absent.
+        pub fn item010(&self) -> Result<Option<Item010>, DecodeError> {
+            match self.offsets[0usize] {
+                Some(offset) => Ok(Some(Item010::decode(&mut self.reader_at(offset)?)?)),
+                None => Ok(None),
+            }
+        }
+        fn reader_at(&self, offset: usize) -> Result<BitReader<&'a [u8]>, DecodeError> {
+            let mut reader = BitReader::new(&self.bytes[offset / 8..]);
+            reader.skip_bits(offset % 8)?;
+            Ok(reader)
+        }
+    }
+    impl Record {
+        /// Returns the items present as generic values, keyed by their
+        /// field names, e.g. `item010`.
+        pub fn to_value_map(&self) -> value::ValueMap {
+            let mut map = value::ValueMap::new();
+            if let Some(item) = &self.item010 {
+                map.insert("item010".to_string(), item.to_value());
+            }
+            map
+        }
+        /// Builds a record from generic values keyed by item, as
+        /// returned by [`Self::to_value_map`].  Missing items are
+        /// absent.
+        pub fn from_value_map(map: &value::ValueMap) -> Result<Self, value::ValueError> {
+            value::check_keys(map, &["item010"])?;
+            Ok(Self {
+                item010: value::field(map, "item010")?,
+            })
+        }
+    }
+    /// ASTERIX Data Block — a container of records for this category.
+    ///
+    /// Wire format:
+    /// ```text
+    /// [CAT: 1 byte][LEN: 2 bytes (big-endian)][Record 0][Record 1]...
+    /// ```
+    ///
+    /// `LEN` is the total byte length of the entire data block, including
+    /// the CAT and LEN fields themselves (minimum value is 3).
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct DataBlock {
+        pub records: Vec<Record>,
+    }
+    impl DataBlock {
+        /// The ASTERIX category identifier for this data block.
+        pub const CATEGORY: u8 = 1u8;
+        /// Creates a new, empty data block.
+        pub fn new() -> Self {
+            Self { records: Vec::new() }
+        }
+        /// Creates a data block containing the given records.
+        pub fn with_records(records: Vec<Record>) -> Self {
+            Self { records }
+        }
+    }
+    impl CategoryRecord for Record {
+        const CATEGORY: u8 = 1u8;
+    }
+    impl Default for DataBlock {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+    impl Encode for DataBlock {
+        fn encode<W: BitWrite>(&self, writer: &mut W) -> Result<(), DecodeError> {
+            let mut record_buf = Vec::new();
+            {
+                let mut record_writer = BitWriter::new(&mut record_buf);
+                for record in &self.records {
+                    record.encode(&mut record_writer)?;
+                }
+                record_writer.flush()?;
+            }
+            let total_len = u16::try_from(3 + record_buf.len())
+                .map_err(|_| DecodeError::LimitExceeded {
+                    limit: "data block length",
+                    max: u16::MAX as usize,
+                })?;
+            writer.write_bits(1u8 as u64, 8)?;
+            writer.write_bits(total_len as u64, 16)?;
+            for &byte in &record_buf {
+                writer.write_bits(byte as u64, 8)?;
+            }
+            Ok(())
+        }
+    }
+    impl Decode for DataBlock {
+        fn decode<R: std::io::Read>(
+            reader: &mut BitReader<R>,
+        ) -> Result<Self, DecodeError> {
+            let cat = reader.read_bits(8)? as u8;
+            if cat != 1u8 {
+                return Err(DecodeError::InvalidData("category mismatch"));
+            }
+            let len = reader.read_bits(16)? as u16;
+            if len < 3 {
+                return Err(DecodeError::InvalidData("data block length too small"));
+            }
+            policy::block_len(len as usize)?;
+            let _span = trace::block_span(1u8, len);
+            let payload_len = (len - 3) as usize;
+            let mut payload = vec![0u8; payload_len];
+            for byte in payload.iter_mut() {
+                *byte = reader.read_bits(8)? as u8;
+            }
+            let mut records = Vec::new();
+            let mut cursor = std::io::Cursor::new(payload);
+            let total = payload_len as u64;
+            while cursor.position() < total {
+                let position = cursor.position() as usize;
+                let offset = 3 + position;
+                let record = policy::block_rest(
+                        &cursor.get_ref()[position..],
+                        offset,
+                        len as usize,
+                    )
+                    .and_then(|record_follows| {
+                        let mut record_reader = BitReader::new(&mut cursor);
+                        record_follows
+                            .then(|| Record::decode(&mut record_reader))
+                            .transpose()
+                            .map_err(|e| e.in_record(offset, len as usize))
+                    })
+                    .inspect_err(|e| trace::record_error(1u8, offset as u64, e))?;
+                let Some(record) = record else { break };
+                records.push(record);
+            }
+            Ok(Self { records })
+        }
+    }
+    impl DecodeInto for DataBlock {
+        fn decode_into<R: std::io::Read>(
+            &mut self,
+            reader: &mut BitReader<R>,
+            ctx: &mut DecodeContext,
+        ) -> Result<(), DecodeError> {
+            let cat = reader.read_bits(8)? as u8;
+            if cat != 1u8 {
+                return Err(DecodeError::InvalidData("category mismatch"));
+            }
+            let len = reader.read_bits(16)? as u16;
+            if len < 3 {
+                return Err(DecodeError::InvalidData("data block length too small"));
+            }
+            policy::block_len(len as usize)?;
+            let _span = trace::block_span(1u8, len);
+            let payload_len = (len - 3) as usize;
+            ctx.with_buffer(|ctx, payload| {
+                for _ in 0..payload_len {
+                    payload.push(reader.read_bits(8)? as u8);
+                }
+                let mut cursor = std::io::Cursor::new(payload.as_slice());
+                let total = payload_len as u64;
+                let mut count = 0;
+                while cursor.position() < total {
+                    let position = cursor.position() as usize;
+                    let offset = 3 + position;
+                    let result = policy::block_rest(
+                            &cursor.get_ref()[position..],
+                            offset,
+                            len as usize,
+                        )
+                        .and_then(|record_follows| {
+                            if !record_follows {
+                                return Ok(false);
+                            }
+                            let mut record_reader = BitReader::new(&mut cursor);
+                            match self.records.get_mut(count) {
+                                Some(record) => record.decode_into(&mut record_reader, ctx),
+                                None => {
+                                    Record::decode(&mut record_reader)
+                                        .map(|record| self.records.push(record))
+                                }
+                            }
+                                .map(|()| true)
+                                .map_err(|e| e.in_record(offset, len as usize))
+                        });
+                    if !result
+                        .inspect_err(|e| trace::record_error(1u8, offset as u64, e))?
+                    {
+                        break;
+                    }
+                    count += 1;
+                }
+                self.records.truncate(count);
+                Ok(())
+            })
+        }
+    }
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct Item010 {
+        pub optional_value: Option<u16>,
+    }
+    impl DataItem for Item010 {
+        const ITEM_ID: u16 = 10u16;
+        const FRN: usize = 1usize;
+        const FSPEC_BYTE: usize = 0usize;
+        const FSPEC_BIT: u8 = 0u8;
+    }
+    impl Validate for Item010 {}
+    impl Conformance for Item010 {
+        fn audit(&self, path: &str, issues: &mut Vec<ConformanceIssue>) {
+            if let Some(field) = self.optional_value {
+                conformance::check_bits(
+                    issues,
+                    path,
+                    "optional_value",
+                    field as u64,
+                    15usize,
+                );
+            }
+        }
+    }
+    impl ToValue for Item010 {
+        fn to_value(&self) -> value::Value {
+            let mut map = value::ValueMap::new();
+            map.insert("optional_value".to_string(), self.optional_value.to_value());
+            value::Value::Map(map)
+        }
+    }
+    impl FromValue for Item010 {
+        fn from_value(input: &value::Value) -> Result<Self, value::ValueError> {
+            let map = value::map(input)?;
+            value::check_keys(map, &["optional_value"])?;
+            Ok(Self {
+                optional_value: value::field(map, "optional_value")?,
+            })
+        }
+    }
+    impl Decode for Item010 {
+        fn decode<R: std::io::Read>(
+            reader: &mut BitReader<R>,
+        ) -> Result<Self, DecodeError> {
+            let optional_value = {
+                let valid = reader.read_bits(1)? != 0;
+                if valid {
+                    Some(reader.read_bits(15usize)? as u16)
+                } else {
+                    reader.read_bits(15usize)?;
+                    None
+                }
+            };
+            Ok(Self { optional_value })
+        }
+    }
+    impl DecodeInto for Item010 {
+        fn decode_into<R: std::io::Read>(
+            &mut self,
+            reader: &mut BitReader<R>,
+            _ctx: &mut DecodeContext,
+        ) -> Result<(), DecodeError> {
+            self.optional_value = {
+                let valid = reader.read_bits(1)? != 0;
+                if valid {
+                    Some(reader.read_bits(15usize)? as u16)
+                } else {
+                    reader.read_bits(15usize)?;
+                    None
+                }
+            };
+            Ok(())
+        }
+    }
+    impl Item010 {
+        /// Length in bytes of the shortest encoding the decoder accepts.
+        pub const fn min_encoded_len() -> usize {
+            2usize
+        }
+        /// Length in bytes of every encoding of the item, or `None` if
+        /// it depends on the value.
+        pub const fn encoded_len_hint() -> Option<usize> {
+            Some(2usize)
+        }
+        /// Reads past the item without decoding its fields.
+        pub fn skip<R: std::io::Read>(
+            reader: &mut BitReader<R>,
+        ) -> Result<(), DecodeError> {
+            reader.skip_bits(16usize)?;
+            Ok(())
+        }
+    }
+    impl Encode for Item010 {
+        fn encode<W: BitWrite>(&self, writer: &mut W) -> Result<(), DecodeError> {
+            if let Some(value) = self.optional_value {
+                writer.write_bits(1, 1)?;
+                writer.write_bits(value as u64, 15usize)?;
+            } else {
+                writer.write_bits(0, 1)?;
+                writer.write_bits(0, 15usize)?;
+            }
+            Ok(())
+        }
+    }
+}
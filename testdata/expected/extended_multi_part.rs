@@ -0,0 +1,767 @@
+#![allow(unused_imports)]
+#![allow(dead_code)]
+#![allow(deprecated)]
+use rasterix::rcore::{
+    BitReader, BitWrite, BitWriter, CategoryRecord, DataItem, DecodeContext, DecodeError,
+    Fspec, Decode, DecodeFromRead, DecodeInto, Encode, EncodeToWrite, Validate,
+    ValidationError, Conformance, ConformanceIssue, FromValue, ToValue, altitude,
+    conformance, geo, icao, policy, trace, validate, value, velocity,
+};
+use std::io::{Read, Write};
+pub mod cat048 {
+    use super::*;
+    /// ASTERIX Category record.
+    ///
+    /// Contains optional data items, each controlled by a bit in the FSPEC.
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct Record {
+        pub item020: Option<Item020>,
+    }
+    impl Decode for Record {
+        fn decode<R: std::io::Read>(
+            reader: &mut BitReader<R>,
+        ) -> Result<Self, DecodeError> {
+            let fspec = Fspec::read(reader)?;
+            Ok(Self {
+                item020: if fspec.is_frn_set(1usize) {
+                    Some(
+                        Item020::decode(reader)
+                            .inspect_err(|e| trace::item_error(
+                                <Self as CategoryRecord>::CATEGORY,
+                                "Item020",
+                                e,
+                            ))?,
+                    )
+                } else {
+                    None
+                },
+            })
+        }
+    }
+    impl DecodeFromRead for Record {
+        fn decode_from<R: std::io::Read>(reader: &mut R) -> Result<Self, DecodeError> {
+            Self::decode(&mut BitReader::new(reader))
+        }
+    }
+    impl DecodeInto for Record {
+        fn decode_into<R: std::io::Read>(
+            &mut self,
+            reader: &mut BitReader<R>,
+            ctx: &mut DecodeContext,
+        ) -> Result<(), DecodeError> {
+            ctx.with_fspec(
+                reader,
+                |ctx, reader, fspec| {
+                    if fspec.is_frn_set(1usize) {
+                        let result = match self.item020.as_mut() {
+                            Some(value) => value.decode_into(reader, ctx),
+                            None => {
+                                Item020::decode(reader)
+                                    .map(|value| self.item020 = Some(value))
+                            }
+                        };
+                        result
+                            .inspect_err(|e| trace::item_error(
+                                <Self as CategoryRecord>::CATEGORY,
+                                "Item020",
+                                e,
+                            ))?;
+                    } else {
+                        self.item020 = None;
+                    };
+                    Ok(())
+                },
+            )
+        }
+    }
+    /// Item sets for [`super::Record::decode_exact`]: one bit per item, combined with `|`.
+    pub mod profile {
+        pub const ITEM020: u64 = 1u64;
+        /// Every item a profile can contain.
+        pub const ALL: u64 = ITEM020;
+    }
+    impl Record {
+        /// Decodes a record that contains exactly the items of `PROFILE`,
+        /// a combination of the [`profile`] constants.
+        ///
+        /// The item set is fixed at compile time, so no FSPEC is kept and
+        /// nothing is allocated beyond the items themselves.  Fails if the
+        /// record's FSPEC differs from the profile's.
+        pub fn decode_exact<const PROFILE: u64, R: std::io::Read>(
+            reader: &mut BitReader<R>,
+        ) -> Result<Self, DecodeError> {
+            const {
+                assert!(
+                    PROFILE & ! profile::ALL == 0,
+                    "profile contains an item the record doesn't have"
+                )
+            };
+            Fspec::read_profile(reader, PROFILE)?;
+            Ok(Self {
+                item020: if PROFILE & profile::ITEM020 != 0 {
+                    Some(
+                        Item020::decode(reader)
+                            .inspect_err(|e| trace::item_error(
+                                <Self as CategoryRecord>::CATEGORY,
+                                "Item020",
+                                e,
+                            ))?,
+                    )
+                } else {
+                    None
+                },
+            })
+        }
+    }
+    impl Encode for Record {
+        fn encode<W: BitWrite>(&self, writer: &mut W) -> Result<(), DecodeError> {
+            let mut fspec = Fspec::new();
+            if self.item020.is_some() {
+                fspec.set_frn(1usize);
+            }
+            fspec.write(writer)?;
+            if let Some(ref item) = self.item020 {
+                item.encode(writer)?;
+            }
+            Ok(())
+        }
+    }
+    impl EncodeToWrite for Record {
+        fn encode_to<W: std::io::Write>(
+            &self,
+            writer: &mut W,
+        ) -> Result<(), DecodeError> {
+            let mut writer = BitWriter::new(writer);
+            self.encode(&mut writer)?;
+            writer.flush()?;
+            Ok(())
+        }
+    }
+    impl Validate for Record {
+        fn check(&self, path: &str, errors: &mut Vec<ValidationError>) {
+            if let Some(ref item) = self.item020 {
+                item.check(&format!("{}item020.", path), errors);
+            }
+        }
+    }
+    impl Conformance for Record {
+        fn audit(&self, path: &str, issues: &mut Vec<ConformanceIssue>) {
+            if let Some(ref item) = self.item020 {
+                conformance::audit_item(item, &format!("{}item020.", path), issues);
+            }
+        }
+    }
+    impl Record {
+        /// Encoded length of the item at each FSPEC position, from
+        /// `encoded_len_hint`.  `None` for variable-length items,
+        /// compiled-out items and unused positions.
+        pub const ITEM_LENGTHS: [Option<usize>; 1usize] = [Item020::encoded_len_hint()];
+        /// Reads past the item at FSPEC position `position`
+        /// (`byte * 7 + bit`, one less than its FRN) without decoding it.
+        pub fn skip_item<R: std::io::Read>(
+            position: usize,
+            reader: &mut BitReader<R>,
+        ) -> Result<(), DecodeError> {
+            match position {
+                0usize => Item020::skip(reader),
+                _ => Err(DecodeError::InvalidData("no item at FSPEC position")),
+            }
+        }
+    }
+    /// Lazily decoded view of a record.
+    ///
+    /// [`parse`](Self::parse) only locates the present items; each
+    /// accessor decodes its item on demand.
+    #[derive(Debug, Clone, Copy)]
+    pub struct RecordLazy<'a> {
+        bytes: &'a [u8],
+        /// Bit offset in `bytes` of each present item, in record order.
+        offsets: [Option<usize>; 1usize],
+        encoded_len: usize,
+    }
+    impl<'a> RecordLazy<'a> {
+        /// Parses the FSPEC and item offsets of the record at the start
+        /// of `bytes`.
+        pub fn parse(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+            let mut reader = BitReader::new(std::io::Cursor::new(bytes));
+            let fspec = Fspec::read(&mut reader)?;
+            let mut offsets = [None; 1usize];
+            if fspec.is_frn_set(1usize) {
+                offsets[0usize] = Some(reader.bit_position() as usize);
+                Item020::skip(&mut reader)?;
+            }
+            Ok(Self {
+                bytes,
+                offsets,
+                encoded_len: (reader.bit_position() as usize).div_ceil(8),
+            })
+        }
+        /// Length of the record in bytes, so that the next record of a
+        /// data block starts at `bytes[encoded_len()..]`.
+        pub fn encoded_len(&self) -> usize {
+            self.encoded_len
+        }
+        /// Decodes every present item.
+        pub fn decode(&self) -> Result<Record, DecodeError> {
+            Ok(Record { item020: self.item020()? })
+        }
+        /// Decodes `Item020`, or returns `None` if it is absent.
+        pub fn item020(&self) -> Result<Option<Item020>, DecodeError> {
+            match self.offsets[0usize] {
+                Some(offset) => Ok(Some(Item020::decode(&mut self.reader_at(offset)?)?)),
+                None => Ok(None),
+            }
+        }
+        fn reader_at(&self, offset: usize) -> Result<BitReader<&'a [u8]>, DecodeError> {
+            let mut reader = BitReader::new(&self.bytes[offset / 8..]);
+            reader.skip_bits(offset % 8)?;
+            Ok(reader)
+        }
+    }
+    impl Record {
+        /// Returns the items present as generic values, keyed by their
+        /// field names, e.g. `item010`.
+        pub fn to_value_map(&self) -> value::ValueMap {
+            let mut map = value::ValueMap::new();
+            if let Some(item) = &self.item020 {
+                map.insert("item020".to_string(), item.to_value());
+            }
+            map
+        }
+        /// Builds a record from generic values keyed by item, as
+        /// returned by [`Self::to_value_map`].  Missing items are
+        /// absent.
+        pub fn from_value_map(map: &value::ValueMap) -> Result<Self, value::ValueError> {
+            value::check_keys(map, &["item020"])?;
+            Ok(Self {
+                item020: value::field(map, "item020")?,
+            })
+        }
+    }
+    /// ASTERIX Data Block — a container of records for this category.
+    ///
+    /// Wire format:
+    /// ```text
+    /// [CAT: 1 byte][LEN: 2 bytes (big-endian)][Record 0][Record 1]...
+    /// ```
+    ///
+    /// `LEN` is the total byte length of the entire data block, including
+    /// the CAT and LEN fields themselves (minimum value is 3).
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct DataBlock {
+        pub records: Vec<Record>,
+    }
+    impl DataBlock {
+        /// The ASTERIX category identifier for this data block.
+        pub const CATEGORY: u8 = 48u8;
+        /// Creates a new, empty data block.
+        pub fn new() -> Self {
+            Self { records: Vec::new() }
+        }
+        /// Creates a data block containing the given records.
+        pub fn with_records(records: Vec<Record>) -> Self {
+            Self { records }
+        }
+    }
+    impl CategoryRecord for Record {
+        const CATEGORY: u8 = 48u8;
+    }
+    impl Default for DataBlock {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+    impl Encode for DataBlock {
+        fn encode<W: BitWrite>(&self, writer: &mut W) -> Result<(), DecodeError> {
+            let mut record_buf = Vec::new();
+            {
+                let mut record_writer = BitWriter::new(&mut record_buf);
+                for record in &self.records {
+                    record.encode(&mut record_writer)?;
+                }
+                record_writer.flush()?;
+            }
+            let total_len = u16::try_from(3 + record_buf.len())
+                .map_err(|_| DecodeError::LimitExceeded {
+                    limit: "data block length",
+                    max: u16::MAX as usize,
+                })?;
+            writer.write_bits(48u8 as u64, 8)?;
+            writer.write_bits(total_len as u64, 16)?;
+            for &byte in &record_buf {
+                writer.write_bits(byte as u64, 8)?;
+            }
+            Ok(())
+        }
+    }
+    impl Decode for DataBlock {
+        fn decode<R: std::io::Read>(
+            reader: &mut BitReader<R>,
+        ) -> Result<Self, DecodeError> {
+            let cat = reader.read_bits(8)? as u8;
+            if cat != 48u8 {
+                return Err(DecodeError::InvalidData("category mismatch"));
+            }
+            let len = reader.read_bits(16)? as u16;
+            if len < 3 {
+                return Err(DecodeError::InvalidData("data block length too small"));
+            }
+            policy::block_len(len as usize)?;
+            let _span = trace::block_span(48u8, len);
+            let payload_len = (len - 3) as usize;
+            let mut payload = vec![0u8; payload_len];
+            for byte in payload.iter_mut() {
+                *byte = reader.read_bits(8)? as u8;
+            }
+            let mut records = Vec::new();
+            let mut cursor = std::io::Cursor::new(payload);
+            let total = payload_len as u64;
+            while cursor.position() < total {
+                let position = cursor.position() as usize;
+                let offset = 3 + position;
+                let record = policy::block_rest(
+                        &cursor.get_ref()[position..],
+                        offset,
+                        len as usize,
+                    )
+                    .and_then(|record_follows| {
+                        let mut record_reader = BitReader::new(&mut cursor);
+                        record_follows
+                            .then(|| Record::decode(&mut record_reader))
+                            .transpose()
+                            .map_err(|e| e.in_record(offset, len as usize))
+                    })
+                    .inspect_err(|e| trace::record_error(48u8, offset as u64, e))?;
+                let Some(record) = record else { break };
+                records.push(record);
+            }
+            Ok(Self { records })
+        }
+    }
+    impl DecodeInto for DataBlock {
+        fn decode_into<R: std::io::Read>(
+            &mut self,
+            reader: &mut BitReader<R>,
+            ctx: &mut DecodeContext,
+        ) -> Result<(), DecodeError> {
+            let cat = reader.read_bits(8)? as u8;
+            if cat != 48u8 {
+                return Err(DecodeError::InvalidData("category mismatch"));
+            }
+            let len = reader.read_bits(16)? as u16;
+            if len < 3 {
+                return Err(DecodeError::InvalidData("data block length too small"));
+            }
+            policy::block_len(len as usize)?;
+            let _span = trace::block_span(48u8, len);
+            let payload_len = (len - 3) as usize;
+            ctx.with_buffer(|ctx, payload| {
+                for _ in 0..payload_len {
+                    payload.push(reader.read_bits(8)? as u8);
+                }
+                let mut cursor = std::io::Cursor::new(payload.as_slice());
+                let total = payload_len as u64;
+                let mut count = 0;
+                while cursor.position() < total {
+                    let position = cursor.position() as usize;
+                    let offset = 3 + position;
+                    let result = policy::block_rest(
+                            &cursor.get_ref()[position..],
+                            offset,
+                            len as usize,
+                        )
+                        .and_then(|record_follows| {
+                            if !record_follows {
+                                return Ok(false);
+                            }
+                            let mut record_reader = BitReader::new(&mut cursor);
+                            match self.records.get_mut(count) {
+                                Some(record) => record.decode_into(&mut record_reader, ctx),
+                                None => {
+                                    Record::decode(&mut record_reader)
+                                        .map(|record| self.records.push(record))
+                                }
+                            }
+                                .map(|()| true)
+                                .map_err(|e| e.in_record(offset, len as usize))
+                        });
+                    if !result
+                        .inspect_err(|e| trace::record_error(48u8, offset as u64, e))?
+                    {
+                        break;
+                    }
+                    count += 1;
+                }
+                self.records.truncate(count);
+                Ok(())
+            })
+        }
+    }
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct Item020Part0 {
+        pub a: u8,
+        pub b: u8,
+    }
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct Item020Part1 {
+        pub c: u8,
+    }
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct Item020Part2 {
+        pub d: u8,
+    }
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct Item020 {
+        pub part0: Item020Part0,
+        pub part1: Option<Item020Part1>,
+        pub part2: Option<Item020Part2>,
+        /// Extension octets received after the last defined part, FX
+        /// bits included, so that they are encoded back unchanged.
+        pub extensions: Vec<u8>,
+    }
+    impl DataItem for Item020 {
+        const ITEM_ID: u16 = 20u16;
+        const FRN: usize = 1usize;
+        const FSPEC_BYTE: usize = 0usize;
+        const FSPEC_BIT: u8 = 0u8;
+    }
+    impl Validate for Item020 {}
+    impl Conformance for Item020Part0 {
+        fn audit(&self, path: &str, issues: &mut Vec<ConformanceIssue>) {
+            {
+                let field = self.a;
+                conformance::check_bits(issues, path, "a", field as u64, 3usize);
+            };
+            {
+                let field = self.b;
+                conformance::check_bits(issues, path, "b", field as u64, 4usize);
+            };
+        }
+    }
+    impl Conformance for Item020Part1 {
+        fn audit(&self, path: &str, issues: &mut Vec<ConformanceIssue>) {
+            {
+                let field = self.c;
+                conformance::check_bits(issues, path, "c", field as u64, 5usize);
+            };
+        }
+    }
+    impl Conformance for Item020Part2 {
+        fn audit(&self, path: &str, issues: &mut Vec<ConformanceIssue>) {
+            {
+                let field = self.d;
+                conformance::check_bits(issues, path, "d", field as u64, 5usize);
+            };
+        }
+    }
+    impl Conformance for Item020 {
+        fn audit(&self, path: &str, issues: &mut Vec<ConformanceIssue>) {
+            if self.part2.is_some() && self.part1.is_none() {
+                conformance::report(issues, path, "part2", conformance::Rule::FxGap);
+            }
+            if !self.extensions.is_empty() && self.part2.is_none() {
+                conformance::report(
+                    issues,
+                    path,
+                    "extensions",
+                    conformance::Rule::FxGap,
+                );
+            }
+            self.part0.audit(&format!("{}part0.", path), issues);
+            if let Some(part) = &self.part1 {
+                part.audit(&format!("{}part1.", path), issues);
+            }
+            if let Some(part) = &self.part2 {
+                part.audit(&format!("{}part2.", path), issues);
+            }
+        }
+    }
+    impl ToValue for Item020Part0 {
+        fn to_value(&self) -> value::Value {
+            let mut map = value::ValueMap::new();
+            map.insert("a".to_string(), self.a.to_value());
+            map.insert("b".to_string(), self.b.to_value());
+            value::Value::Map(map)
+        }
+    }
+    impl FromValue for Item020Part0 {
+        fn from_value(input: &value::Value) -> Result<Self, value::ValueError> {
+            let map = value::map(input)?;
+            value::check_keys(map, &["a", "b"])?;
+            Ok(Self {
+                a: value::field(map, "a")?,
+                b: value::field(map, "b")?,
+            })
+        }
+    }
+    impl ToValue for Item020Part1 {
+        fn to_value(&self) -> value::Value {
+            let mut map = value::ValueMap::new();
+            map.insert("c".to_string(), self.c.to_value());
+            value::Value::Map(map)
+        }
+    }
+    impl FromValue for Item020Part1 {
+        fn from_value(input: &value::Value) -> Result<Self, value::ValueError> {
+            let map = value::map(input)?;
+            value::check_keys(map, &["c"])?;
+            Ok(Self { c: value::field(map, "c")? })
+        }
+    }
+    impl ToValue for Item020Part2 {
+        fn to_value(&self) -> value::Value {
+            let mut map = value::ValueMap::new();
+            map.insert("d".to_string(), self.d.to_value());
+            value::Value::Map(map)
+        }
+    }
+    impl FromValue for Item020Part2 {
+        fn from_value(input: &value::Value) -> Result<Self, value::ValueError> {
+            let map = value::map(input)?;
+            value::check_keys(map, &["d"])?;
+            Ok(Self { d: value::field(map, "d")? })
+        }
+    }
+    impl ToValue for Item020 {
+        fn to_value(&self) -> value::Value {
+            let mut map = value::ValueMap::new();
+            map.insert("part0".to_string(), self.part0.to_value());
+            map.insert("part1".to_string(), self.part1.to_value());
+            map.insert("part2".to_string(), self.part2.to_value());
+            if !self.extensions.is_empty() {
+                map.insert("extensions".to_string(), self.extensions.to_value());
+            }
+            value::Value::Map(map)
+        }
+    }
+    impl FromValue for Item020 {
+        fn from_value(input: &value::Value) -> Result<Self, value::ValueError> {
+            let map = value::map(input)?;
+            value::check_keys(map, &["part0", "part1", "part2", "extensions"])?;
+            Ok(Self {
+                part0: value::field(map, "part0")?,
+                part1: value::field(map, "part1")?,
+                part2: value::field(map, "part2")?,
+                extensions: value::field::<Option<Vec<u8>>>(map, "extensions")?
+                    .unwrap_or_default(),
+            })
+        }
+    }
+    impl Item020Part0 {
+        pub fn decode<R: std::io::Read>(
+            reader: &mut BitReader<R>,
+        ) -> Result<Self, DecodeError> {
+            let a = reader.read_bits(3usize)? as u8;
+            let b = reader.read_bits(4usize)? as u8;
+            Ok(Self { a, b })
+        }
+        pub fn decode_into<R: std::io::Read>(
+            &mut self,
+            reader: &mut BitReader<R>,
+            _ctx: &mut DecodeContext,
+        ) -> Result<(), DecodeError> {
+            self.a = reader.read_bits(3usize)? as u8;
+            self.b = reader.read_bits(4usize)? as u8;
+            Ok(())
+        }
+    }
+    impl Item020Part1 {
+        pub fn decode<R: std::io::Read>(
+            reader: &mut BitReader<R>,
+        ) -> Result<Self, DecodeError> {
+            let c = reader.read_bits(5usize)? as u8;
+            policy::reserved_bits(2usize, reader.read_bits(2usize)?)?;
+            Ok(Self { c })
+        }
+        pub fn decode_into<R: std::io::Read>(
+            &mut self,
+            reader: &mut BitReader<R>,
+            _ctx: &mut DecodeContext,
+        ) -> Result<(), DecodeError> {
+            self.c = reader.read_bits(5usize)? as u8;
+            policy::reserved_bits(2usize, reader.read_bits(2usize)?)?;
+            Ok(())
+        }
+    }
+    impl Item020Part2 {
+        pub fn decode<R: std::io::Read>(
+            reader: &mut BitReader<R>,
+        ) -> Result<Self, DecodeError> {
+            let d = reader.read_bits(5usize)? as u8;
+            policy::reserved_bits(2usize, reader.read_bits(2usize)?)?;
+            Ok(Self { d })
+        }
+        pub fn decode_into<R: std::io::Read>(
+            &mut self,
+            reader: &mut BitReader<R>,
+            _ctx: &mut DecodeContext,
+        ) -> Result<(), DecodeError> {
+            self.d = reader.read_bits(5usize)? as u8;
+            policy::reserved_bits(2usize, reader.read_bits(2usize)?)?;
+            Ok(())
+        }
+    }
+    impl Decode for Item020 {
+        fn decode<R: std::io::Read>(
+            reader: &mut BitReader<R>,
+        ) -> Result<Self, DecodeError> {
+            let part0 = Item020Part0::decode(reader)?;
+            let mut fx = reader.read_bits(1)? != 0;
+            let part1 = if fx {
+                let part = Item020Part1::decode(reader)?;
+                fx = reader.read_bits(1)? != 0;
+                Some(part)
+            } else {
+                None
+            };
+            let part2 = if fx {
+                let part = Item020Part2::decode(reader)?;
+                fx = reader.read_bits(1)? != 0;
+                Some(part)
+            } else {
+                None
+            };
+            let mut extensions = Vec::new();
+            while fx {
+                let octet = reader.read_bits(8)? as u8;
+                extensions.push(octet);
+                fx = octet & 1 != 0;
+            }
+            Ok(Self {
+                part0,
+                part1,
+                part2,
+                extensions,
+            })
+        }
+    }
+    impl DecodeInto for Item020 {
+        fn decode_into<R: std::io::Read>(
+            &mut self,
+            reader: &mut BitReader<R>,
+            ctx: &mut DecodeContext,
+        ) -> Result<(), DecodeError> {
+            self.part0.decode_into(reader, ctx)?;
+            let mut fx = reader.read_bits(1)? != 0;
+            if fx {
+                match self.part1.as_mut() {
+                    Some(value) => value.decode_into(reader, ctx)?,
+                    None => self.part1 = Some(Item020Part1::decode(reader)?),
+                }
+                fx = reader.read_bits(1)? != 0;
+            } else {
+                self.part1 = None;
+            };
+            if fx {
+                match self.part2.as_mut() {
+                    Some(value) => value.decode_into(reader, ctx)?,
+                    None => self.part2 = Some(Item020Part2::decode(reader)?),
+                }
+                fx = reader.read_bits(1)? != 0;
+            } else {
+                self.part2 = None;
+            };
+            self.extensions.clear();
+            while fx {
+                let octet = reader.read_bits(8)? as u8;
+                self.extensions.push(octet);
+                fx = octet & 1 != 0;
+            }
+            Ok(())
+        }
+    }
+    impl Item020 {
+        /// Length in bytes of the shortest encoding the decoder accepts.
+        pub const fn min_encoded_len() -> usize {
+            1usize
+        }
+        /// Length in bytes of every encoding of the item, or `None` if
+        /// it depends on the value.
+        pub const fn encoded_len_hint() -> Option<usize> {
+            None
+        }
+        /// Reads past the item without decoding its fields.
+        pub fn skip<R: std::io::Read>(
+            reader: &mut BitReader<R>,
+        ) -> Result<(), DecodeError> {
+            reader.skip_bits(7usize)?;
+            let mut fx = reader.read_bits(1)? != 0;
+            if fx {
+                reader.skip_bits(7usize)?;
+                fx = reader.read_bits(1)? != 0;
+            }
+            if fx {
+                reader.skip_bits(7usize)?;
+                fx = reader.read_bits(1)? != 0;
+            }
+            while fx {
+                fx = reader.read_bits(8)? & 1 != 0;
+            }
+            Ok(())
+        }
+    }
+    impl Item020Part0 {
+        pub fn encode<W: BitWrite>(&self, writer: &mut W) -> Result<(), DecodeError> {
+            writer.write_bits(self.a as u64, 3usize)?;
+            writer.write_bits(self.b as u64, 4usize)?;
+            Ok(())
+        }
+    }
+    impl Item020Part1 {
+        pub fn encode<W: BitWrite>(&self, writer: &mut W) -> Result<(), DecodeError> {
+            writer.write_bits(self.c as u64, 5usize)?;
+            writer.write_bits(0, 2usize)?;
+            Ok(())
+        }
+    }
+    impl Item020Part2 {
+        pub fn encode<W: BitWrite>(&self, writer: &mut W) -> Result<(), DecodeError> {
+            writer.write_bits(self.d as u64, 5usize)?;
+            writer.write_bits(0, 2usize)?;
+            Ok(())
+        }
+    }
+    impl Encode for Item020 {
+        fn encode<W: BitWrite>(&self, writer: &mut W) -> Result<(), DecodeError> {
+            if self.part2.is_some() && self.part1.is_none() {
+                return Err(
+                    DecodeError::InvalidData(
+                        "extended item has a part after an absent one",
+                    ),
+                );
+            }
+            if !self.extensions.is_empty() && self.part2.is_none() {
+                return Err(
+                    DecodeError::InvalidData(
+                        "extended item has extensions after an absent part",
+                    ),
+                );
+            }
+            let fx_broken = self
+                .extensions
+                .split_last()
+                .is_some_and(|(last, octets)| {
+                    last & 1 != 0 || octets.iter().any(|octet| octet & 1 == 0)
+                });
+            if fx_broken {
+                return Err(
+                    DecodeError::InvalidData(
+                        "extended item extensions must have FX set on every octet but the last",
+                    ),
+                );
+            }
+            self.part0.encode(writer)?;
+            writer.write_bits(self.part1.is_some() as u64, 1)?;
+            if let Some(ref part_data) = self.part1 {
+                part_data.encode(writer)?;
+                writer.write_bits(self.part2.is_some() as u64, 1)?;
+            }
+            if let Some(ref part_data) = self.part2 {
+                part_data.encode(writer)?;
+                writer.write_bits(!self.extensions.is_empty() as u64, 1)?;
+                writer.write_bytes(&self.extensions)?;
+            }
+            Ok(())
+        }
+    }
+}